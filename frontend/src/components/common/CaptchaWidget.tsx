@@ -0,0 +1,119 @@
+import { useEffect, useRef, useState } from 'react';
+import { getPublicConfig } from '../../services/configApi';
+
+type CaptchaProvider = 'recaptcha' | 'hcaptcha';
+
+interface CaptchaRenderOptions {
+  sitekey: string;
+  theme?: 'dark' | 'light';
+  callback: (token: string) => void;
+  'expired-callback': () => void;
+}
+
+/** reCAPTCHA・hCaptchaのどちらも同じ形のAPIを持つ */
+interface CaptchaApi {
+  render: (container: HTMLElement, options: CaptchaRenderOptions) => number | string;
+}
+
+declare global {
+  interface Window {
+    grecaptcha?: CaptchaApi & { ready?: (callback: () => void) => void };
+    hcaptcha?: CaptchaApi;
+  }
+}
+
+const SCRIPT_URLS: Record<CaptchaProvider, string> = {
+  recaptcha: 'https://www.google.com/recaptcha/api.js?render=explicit',
+  hcaptcha: 'https://js.hcaptcha.com/1/api.js?render=explicit',
+};
+
+const getApi = (provider: CaptchaProvider): CaptchaApi | undefined =>
+  provider === 'hcaptcha' ? window.hcaptcha : window.grecaptcha;
+
+const loadScript = (provider: CaptchaProvider): Promise<void> =>
+  new Promise((resolve, reject) => {
+    if (getApi(provider)?.render) {
+      resolve();
+      return;
+    }
+    const script = document.createElement('script');
+    script.src = SCRIPT_URLS[provider];
+    script.async = true;
+    script.onload = () => resolve();
+    script.onerror = () => reject(new Error('CAPTCHA script failed to load'));
+    document.head.appendChild(script);
+  });
+
+interface CaptchaWidgetProps {
+  /** トークンが発行・失効したときに呼ばれる（失効時はnull） */
+  onChange: (token: string | null) => void;
+  /** CAPTCHAが有効かどうかが分かったときに呼ばれる */
+  onAvailabilityChange?: (enabled: boolean) => void;
+}
+
+/**
+ * 登録・お問い合わせ用のCAPTCHA
+ * サーバーでCAPTCHAが無効な場合は何も表示しない
+ */
+export default function CaptchaWidget({ onChange, onAvailabilityChange }: CaptchaWidgetProps) {
+  const containerRef = useRef<HTMLDivElement>(null);
+  const onChangeRef = useRef(onChange);
+  const onAvailabilityChangeRef = useRef(onAvailabilityChange);
+  const [loadError, setLoadError] = useState(false);
+
+  useEffect(() => {
+    onChangeRef.current = onChange;
+    onAvailabilityChangeRef.current = onAvailabilityChange;
+  }, [onChange, onAvailabilityChange]);
+
+  useEffect(() => {
+    let cancelled = false;
+
+    const setup = async () => {
+      const config = await getPublicConfig().catch(() => null);
+      const provider = config?.captchaProvider;
+      const siteKey = config?.captchaSiteKey;
+      onAvailabilityChangeRef.current?.(!!provider && !!siteKey);
+      if (!provider || !siteKey || cancelled) return;
+
+      try {
+        await loadScript(provider);
+      } catch {
+        if (!cancelled) setLoadError(true);
+        return;
+      }
+
+      const render = () => {
+        const api = getApi(provider);
+        if (cancelled || !api?.render || !containerRef.current) return;
+        containerRef.current.innerHTML = '';
+        api.render(containerRef.current, {
+          sitekey: siteKey,
+          theme: 'dark',
+          callback: (token) => onChangeRef.current(token),
+          'expired-callback': () => onChangeRef.current(null),
+        });
+      };
+      // reCAPTCHAはスクリプトの読み込み後も初期化が終わるまで待つ必要がある
+      if (provider === 'recaptcha' && window.grecaptcha?.ready) {
+        window.grecaptcha.ready(render);
+      } else {
+        render();
+      }
+    };
+    setup();
+
+    return () => {
+      cancelled = true;
+    };
+  }, []);
+
+  if (loadError) {
+    return (
+      <div style={{ color: '#ff6b6b', fontSize: '13px', margin: '8px 0' }}>
+        CAPTCHAを読み込めませんでした。ページを再読み込みしてください。
+      </div>
+    );
+  }
+  return <div ref={containerRef} style={{ margin: '12px 0' }} />;
+}
@@ -7,6 +7,30 @@ import { useWindowEventListener } from '../../hooks';
 
 import '../../styles/daily-reward.css';
 
+/** 1週分のタイル数 */
+const DAYS_PER_ROW = 7;
+
+/** タイルに表示する報酬 */
+const rewardLabel = (day: DailyRewardDay): string => {
+  switch (day.rewardType) {
+    case 'coins':
+      return `${day.amount} コイン`;
+    case 'item':
+      return `${day.itemName ?? 'アイテム'} ×${day.amount}`;
+    default:
+      return `${day.boostedExp} EXP`;
+  }
+};
+
+/** 1週ごとに分割 */
+const chunkDays = (days: DailyRewardDay[]): DailyRewardDay[][] => {
+  const rows: DailyRewardDay[][] = [];
+  for (let i = 0; i < days.length; i += DAYS_PER_ROW) {
+    rows.push(days.slice(i, i + DAYS_PER_ROW));
+  }
+  return rows;
+};
+
 interface DailyRewardModalProps {
   isOpen: boolean;
   onClose: () => void;
@@ -33,6 +57,8 @@ export default function DailyRewardModal({ isOpen, onClose, autoClaimOnOpen = fa
         // Show reward toast
         const rewardText = [];
         if (data.expEarned > 0) rewardText.push(`${data.expEarned} EXP`);
+        if (data.coinsEarned > 0) rewardText.push(`${data.coinsEarned} コイン`);
+        if (data.itemQuantity > 0) rewardText.push(`${data.itemCode} ×${data.itemQuantity}`);
         showToast(`🎁 Day ${data.rewardDay} 報酬獲得！ ${rewardText.join(' + ')}`, 'success');
         
         // Invalidate queries to refresh data
@@ -116,7 +142,7 @@ export default function DailyRewardModal({ isOpen, onClose, autoClaimOnOpen = fa
         <>
           <span className="tile-check">✓</span>
           <span className="tile-reward">
-            {day.exp} EXP
+            {rewardLabel(day)}
           </span>
         </>
       );
@@ -124,8 +150,8 @@ export default function DailyRewardModal({ isOpen, onClose, autoClaimOnOpen = fa
       // Show big reward preview
       content = (
         <>
-          <span className="tile-icon">{day.day === 14 ? '🎁🎁' : '🎁'}</span>
-          <span className="tile-reward">{day.exp} EXP</span>
+          <span className="tile-icon">{day.day === rewardData?.cycleLength ? '🎁🎁' : '🎁'}</span>
+          <span className="tile-reward">{rewardLabel(day)}</span>
         </>
       );
     } else if (isToday) {
@@ -134,7 +160,7 @@ export default function DailyRewardModal({ isOpen, onClose, autoClaimOnOpen = fa
         <>
           <span className="tile-icon">⭐</span>
           <span className="tile-reward">
-            {day.exp} EXP
+            {rewardLabel(day)}
           </span>
         </>
       );
@@ -160,7 +186,7 @@ export default function DailyRewardModal({ isOpen, onClose, autoClaimOnOpen = fa
           <h2>🎁 デイリーリワード</h2>
           {rewardData ? (
             <p className="reward-progress">
-              Day {rewardData.currentDay}/14
+              Day {rewardData.currentDay}/{rewardData.cycleLength}
             </p>
           ) : null}
         </div>
@@ -170,18 +196,13 @@ export default function DailyRewardModal({ isOpen, onClose, autoClaimOnOpen = fa
         ) : rewardData ? (
           <>
             <div className="reward-grid">
-              {/* Week 1 */}
-              <div className="reward-week">
-                {rewardData.days.slice(0, 7).map((day) => 
-                  renderTile(day, rewardData.currentDay, rewardData.todayClaimed)
-                )}
-              </div>
-              {/* Week 2 */}
-              <div className="reward-week">
-                {rewardData.days.slice(7, 14).map((day) => 
-                  renderTile(day, rewardData.currentDay, rewardData.todayClaimed)
-                )}
-              </div>
+              {chunkDays(rewardData.days).map((week, index) => (
+                <div key={index} className="reward-week">
+                  {week.map((day) =>
+                    renderTile(day, rewardData.currentDay, rewardData.todayClaimed)
+                  )}
+                </div>
+              ))}
             </div>
 
             <div className="reward-footer">
@@ -6,7 +6,7 @@ import BottomNav from './BottomNav';
 import MobileHeader from './MobileHeader';
 import Footer from './Footer';
 import { useUIStore } from '../../stores/uiStore';
-import { useSwipe } from '../../hooks';
+import { usePetEvents, useSwipe } from '../../hooks';
 
 // 遅延読み込み（初期バンドルサイズ削減）
 const Sidebar = lazy(() => import('./Sidebar'));
@@ -25,7 +25,8 @@ function SidebarSkeleton() {
 }
 
 export default function Layout() {
-  const { toasts, removeToast, activeModal, openMobileMenu, closeMobileMenu } = useUIStore();
+  const { toasts, removeToast, showToast, activeModal, openMobileMenu, closeMobileMenu } =
+    useUIStore();
   const queryClient = useQueryClient();
 
   const swipeHandlers = useSwipe({
@@ -56,6 +57,24 @@ export default function Layout() {
     recordLogin();
   }, [queryClient]);
 
+  // パートナーのイベント（記録の保存直後にWebSocketで届く）
+  usePetEvents((event) => {
+    switch (event.type) {
+      case 'pet.levelUp':
+        showToast(`パートナーがLv.${event.payload.level}になりました！`, 'success');
+        break;
+      case 'pet.matured':
+        showToast('パートナーが覚醒しました！', 'success');
+        break;
+      case 'pet.unlocked':
+        showToast(`新しいパートナー「${event.payload.petTypeName}」が解放されました！`, 'success');
+        break;
+    }
+    queryClient.invalidateQueries({ queryKey: ['pet'] });
+    queryClient.invalidateQueries({ queryKey: ['barn'] });
+    queryClient.invalidateQueries({ queryKey: ['petStatus'] });
+  });
+
   return (
     <div className="app-layout" {...swipeHandlers}>
       {/* サイドバー（PC） */}
@@ -1,25 +1,85 @@
 import { useState, useEffect } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
-import { getUserInfo, updateDisplayName, updatePassword, deleteAccount, logout } from '../../services/authApi';
+import {
+  getUserInfo,
+  updateDisplayName,
+  updateLoginId,
+  uploadProfileImage,
+  updatePassword,
+  updateEmail,
+  resendEmailVerification,
+  getTwoFactorStatus,
+  setupTwoFactor,
+  verifyTwoFactor,
+  regenerateBackupCodes,
+  disableTwoFactor,
+  type TwoFactorSetup,
+  getApiKeys,
+  createApiKey,
+  revokeApiKey,
+  type ApiKeyScope,
+  getLoginActivity,
+  type LoginActivity,
+  deleteAccount,
+  getAccountDeletionStatus,
+  cancelAccountDeletion,
+  deactivateAccount,
+  requestAccountExport,
+  getAccountExport,
+  downloadAccountExport,
+  logout,
+} from '../../services/authApi';
 import { useUIStore } from '../../stores/uiStore';
 import { useAuthStore } from '../../stores/authStore';
 import { useWindowEventListener } from '../../hooks';
 
-type TabType = 'display-name' | 'password' | 'delete-account';
+type TabType =
+  | 'display-name'
+  | 'password'
+  | 'two-factor'
+  | 'email'
+  | 'api-keys'
+  | 'security'
+  | 'delete-account';
+
+const API_KEY_SCOPE_OPTIONS: { value: ApiKeyScope; label: string }[] = [
+  { value: 'read', label: '読み取り' },
+  { value: 'workout:write', label: 'トレーニング記録の登録・編集' },
+];
+
+const LOGIN_FAILURE_LABELS: Record<NonNullable<LoginActivity['failureReason']>, string> = {
+  invalid_credentials: 'パスワード誤り',
+  two_factor_required: '認証コード未入力',
+  two_factor_failed: '認証コード誤り',
+  account_deactivated: '無効化中のログイン',
+};
 
 export default function UserSettingsModal() {
   const navigate = useNavigate();
   const queryClient = useQueryClient();
   const { activeModal, closeModal, showToast } = useUIStore();
-  const { clearUser } = useAuthStore();
+  const { clearUser, fetchUser } = useAuthStore();
   const isOpen = activeModal === 'user-settings';
 
   const [activeTab, setActiveTab] = useState<TabType>('display-name');
   const [newDisplayName, setNewDisplayName] = useState('');
+  const [newLoginId, setNewLoginId] = useState('');
+  const [loginIdPassword, setLoginIdPassword] = useState('');
   const [currentPassword, setCurrentPassword] = useState('');
   const [newPassword, setNewPassword] = useState('');
   const [confirmNewPassword, setConfirmNewPassword] = useState('');
+  const [newEmail, setNewEmail] = useState('');
+  const [twoFactorSetup, setTwoFactorSetup] = useState<TwoFactorSetup | null>(null);
+  const [twoFactorCode, setTwoFactorCode] = useState('');
+  const [twoFactorPassword, setTwoFactorPassword] = useState('');
+  const [backupCodes, setBackupCodes] = useState<string[]>([]);
+  const [deletePassword, setDeletePassword] = useState('');
+  const [deactivatePassword, setDeactivatePassword] = useState('');
+  const [accountExportId, setAccountExportId] = useState<number | null>(null);
+  const [apiKeyName, setApiKeyName] = useState('');
+  const [apiKeyScopes, setApiKeyScopes] = useState<ApiKeyScope[]>(['read', 'workout:write']);
+  const [createdApiKey, setCreatedApiKey] = useState<string | null>(null);
 
   // ユーザー情報取得
   const { data: userInfo } = useQuery({
@@ -28,12 +88,56 @@ export default function UserSettingsModal() {
     enabled: isOpen,
   });
 
+  // 退会リクエストの状態
+  const { data: deletionStatus } = useQuery({
+    queryKey: ['accountDeletionStatus'],
+    queryFn: getAccountDeletionStatus,
+    enabled: isOpen,
+  });
+
+  // データのエクスポート（作成が終わるまで状況を確認する）
+  const { data: accountExport } = useQuery({
+    queryKey: ['accountExport', accountExportId],
+    queryFn: () => getAccountExport(accountExportId!),
+    enabled: isOpen && accountExportId !== null,
+    refetchInterval: (query) => {
+      const status = query.state.data?.status;
+      return status === 'COMPLETED' || status === 'FAILED' ? false : 3000;
+    },
+  });
+
+  // 二段階認証の設定状況（パスワードでログインするユーザーのみ）
+  const { data: twoFactorStatus } = useQuery({
+    queryKey: ['twoFactorStatus'],
+    queryFn: getTwoFactorStatus,
+    enabled: isOpen && !!userInfo && !userInfo.isOAuthUser,
+  });
+
+  // APIキー一覧
+  const { data: apiKeys } = useQuery({
+    queryKey: ['apiKeys'],
+    queryFn: getApiKeys,
+    enabled: isOpen && activeTab === 'api-keys',
+  });
+
+  // ログイン履歴
+  const { data: loginActivity } = useQuery({
+    queryKey: ['loginActivity'],
+    queryFn: () => getLoginActivity(),
+    enabled: isOpen && activeTab === 'security',
+  });
+
+  // メールアドレスがないパスワードユーザーはパスワードで本人確認する
+  const requiresDeletePassword = !!userInfo && !userInfo.email && !userInfo.isOAuthUser;
+
 
 
   // モーダルが開いた時に初期化
   useEffect(() => {
     if (isOpen && userInfo) {
       setNewDisplayName(userInfo.displayName || '');
+      setNewLoginId(userInfo.loginId);
+      setCreatedApiKey(null);
       // OAuthユーザーの場合は削除タブを初期表示
       if (userInfo.isOAuthUser) {
         setActiveTab('delete-account');
@@ -67,6 +171,37 @@ export default function UserSettingsModal() {
     },
   });
 
+  // ログインID変更（30日に1回まで）
+  const updateLoginIdMutation = useMutation({
+    mutationFn: () =>
+      updateLoginId({
+        loginId: newLoginId.trim(),
+        currentPassword: userInfo?.isOAuthUser ? undefined : loginIdPassword,
+      }),
+    onSuccess: () => {
+      showToast('ユーザーIDを変更しました', 'success');
+      setLoginIdPassword('');
+      queryClient.invalidateQueries({ queryKey: ['userInfo'] });
+      fetchUser();
+    },
+    onError: (error: Error) => {
+      showToast(error.message || 'ユーザーIDの変更に失敗しました', 'error');
+    },
+  });
+
+  // プロフィール画像のアップロード
+  const uploadProfileImageMutation = useMutation({
+    mutationFn: uploadProfileImage,
+    onSuccess: () => {
+      showToast('プロフィール画像を変更しました', 'success');
+      queryClient.invalidateQueries({ queryKey: ['userInfo'] });
+      fetchUser();
+    },
+    onError: (error: Error) => {
+      showToast(error.message || 'プロフィール画像の変更に失敗しました', 'error');
+    },
+  });
+
   // パスワード更新
   const updatePasswordMutation = useMutation({
     mutationFn: () =>
@@ -87,18 +222,183 @@ export default function UserSettingsModal() {
     },
   });
 
-  // アカウント削除
-  const deleteAccountMutation = useMutation({
-    mutationFn: deleteAccount,
+  // 二段階認証の設定開始
+  const setupTwoFactorMutation = useMutation({
+    mutationFn: setupTwoFactor,
+    onSuccess: (setup) => {
+      setTwoFactorSetup(setup);
+      setTwoFactorCode('');
+      setBackupCodes([]);
+    },
+    onError: (error: Error) => {
+      showToast(error.message || '二段階認証の設定に失敗しました', 'error');
+    },
+  });
+
+  // 認証アプリのコードを確認して有効化
+  const verifyTwoFactorMutation = useMutation({
+    mutationFn: () => verifyTwoFactor(twoFactorCode.trim()),
+    onSuccess: (result) => {
+      showToast('二段階認証を有効にしました', 'success');
+      setTwoFactorSetup(null);
+      setTwoFactorCode('');
+      setBackupCodes(result.backupCodes);
+      queryClient.invalidateQueries({ queryKey: ['twoFactorStatus'] });
+    },
+    onError: (error: Error) => {
+      showToast(error.message || '認証コードが正しくありません', 'error');
+    },
+  });
+
+  // バックアップコードの再発行
+  const regenerateBackupCodesMutation = useMutation({
+    mutationFn: () => regenerateBackupCodes(twoFactorCode.trim()),
+    onSuccess: (result) => {
+      showToast('バックアップコードを再発行しました', 'success');
+      setTwoFactorCode('');
+      setBackupCodes(result.backupCodes);
+      queryClient.invalidateQueries({ queryKey: ['twoFactorStatus'] });
+    },
+    onError: (error: Error) => {
+      showToast(error.message || 'バックアップコードの再発行に失敗しました', 'error');
+    },
+  });
+
+  // 二段階認証の無効化
+  const disableTwoFactorMutation = useMutation({
+    mutationFn: () => disableTwoFactor(twoFactorPassword, twoFactorCode.trim()),
+    onSuccess: () => {
+      showToast('二段階認証を無効にしました', 'success');
+      setTwoFactorPassword('');
+      setTwoFactorCode('');
+      setBackupCodes([]);
+      queryClient.invalidateQueries({ queryKey: ['twoFactorStatus'] });
+    },
+    onError: (error: Error) => {
+      showToast(error.message || '二段階認証の無効化に失敗しました', 'error');
+    },
+  });
+
+  // メールアドレス変更（確認メールのリンクを開くと反映される）
+  const updateEmailMutation = useMutation({
+    mutationFn: () => updateEmail({ email: newEmail.trim() }),
+    onSuccess: () => {
+      showToast('確認メールを送信しました。メール内のリンクを開くと変更が完了します', 'success');
+      setNewEmail('');
+    },
+    onError: (error: Error) => {
+      showToast(error.message || '確認メールの送信に失敗しました', 'error');
+    },
+  });
+
+  // 確認メールの再送信
+  const resendVerificationMutation = useMutation({
+    mutationFn: resendEmailVerification,
+    onSuccess: () => {
+      showToast('確認メールを再送信しました', 'success');
+    },
+    onError: (error: Error) => {
+      showToast(error.message || '確認メールの送信に失敗しました', 'error');
+    },
+  });
+
+  // APIキーの発行（キーは発行時にのみ表示する）
+  const createApiKeyMutation = useMutation({
+    mutationFn: () => createApiKey(apiKeyName.trim(), apiKeyScopes),
+    onSuccess: (result) => {
+      showToast('APIキーを発行しました', 'success');
+      setApiKeyName('');
+      setCreatedApiKey(result.key);
+      queryClient.invalidateQueries({ queryKey: ['apiKeys'] });
+    },
+    onError: (error: Error) => {
+      showToast(error.message || 'APIキーの発行に失敗しました', 'error');
+    },
+  });
+
+  // APIキーの無効化
+  const revokeApiKeyMutation = useMutation({
+    mutationFn: revokeApiKey,
     onSuccess: () => {
-      showToast('アカウントを削除しました', 'success');
-      window.location.href = '/login';
+      showToast('APIキーを削除しました', 'success');
+      queryClient.invalidateQueries({ queryKey: ['apiKeys'] });
+    },
+    onError: (error: Error) => {
+      showToast(error.message || 'APIキーの削除に失敗しました', 'error');
+    },
+  });
+
+  // アカウント削除（退会リクエスト）
+  const deleteAccountMutation = useMutation({
+    mutationFn: () => deleteAccount(requiresDeletePassword ? deletePassword : undefined),
+    onSuccess: (result) => {
+      if (result.emailSent) {
+        showToast('確認メールを送信しました。メール内のリンクを開くと削除が確定します', 'success');
+      } else {
+        showToast('アカウントの削除を予約しました（30日後に削除されます）', 'success');
+      }
+      setDeletePassword('');
+      queryClient.invalidateQueries({ queryKey: ['accountDeletionStatus'] });
     },
     onError: (error: Error) => {
       showToast(error.message || 'アカウントの削除に失敗しました', 'error');
     },
   });
 
+  // アカウントの無効化（すべての端末からログアウトされる）
+  const deactivateAccountMutation = useMutation({
+    mutationFn: () => deactivateAccount(userInfo?.isOAuthUser ? undefined : deactivatePassword),
+    onSuccess: () => {
+      setDeactivatePassword('');
+      showToast('アカウントを無効化しました（30日後に削除されます）', 'success');
+      clearUser();
+      closeModal();
+      navigate('/login');
+    },
+    onError: (error: Error) => {
+      showToast(error.message || 'アカウントの無効化に失敗しました', 'error');
+    },
+  });
+
+  // データのエクスポートの作成
+  const requestExportMutation = useMutation({
+    mutationFn: requestAccountExport,
+    onSuccess: (result) => {
+      setAccountExportId(result.id);
+      queryClient.setQueryData(['accountExport', result.id], result);
+    },
+    onError: (error: Error) => {
+      showToast(error.message || 'エクスポートを開始できませんでした', 'error');
+    },
+  });
+
+  const handleDownloadExport = async () => {
+    if (!accountExport) return;
+    try {
+      const blob = await downloadAccountExport(accountExport.id);
+      const url = URL.createObjectURL(blob);
+      const link = document.createElement('a');
+      link.href = url;
+      link.download = `fithub-export-${accountExport.requestedAt.slice(0, 10).replace(/-/g, '')}.zip`;
+      link.click();
+      URL.revokeObjectURL(url);
+    } catch {
+      showToast('ダウンロードに失敗しました', 'error');
+    }
+  };
+
+  // 退会リクエストの取り消し
+  const cancelDeletionMutation = useMutation({
+    mutationFn: cancelAccountDeletion,
+    onSuccess: () => {
+      showToast('アカウントの削除を取り消しました', 'success');
+      queryClient.invalidateQueries({ queryKey: ['accountDeletionStatus'] });
+    },
+    onError: (error: Error) => {
+      showToast(error.message || '削除の取り消しに失敗しました', 'error');
+    },
+  });
+
   const handleUpdateDisplayName = () => {
     if (!newDisplayName.trim()) {
       showToast('ユーザー名を入力してください', 'error');
@@ -107,6 +407,30 @@ export default function UserSettingsModal() {
     updateNameMutation.mutate();
   };
 
+  const handleUpdateLoginId = () => {
+    const loginId = newLoginId.trim();
+    if (loginId.length < 4 || loginId.length > 20) {
+      showToast('ユーザーIDは4〜20文字で入力してください', 'error');
+      return;
+    }
+    if (!/^[A-Za-z0-9_.-]+$/.test(loginId)) {
+      showToast('ユーザーIDは半角英数字と _ - . のみ使用できます', 'error');
+      return;
+    }
+    if (loginId === userInfo?.loginId) {
+      showToast('現在のユーザーIDと同じです', 'error');
+      return;
+    }
+    if (!userInfo?.isOAuthUser && !loginIdPassword) {
+      showToast('パスワードを入力してください', 'error');
+      return;
+    }
+    if (!confirm(`ユーザーIDを「${loginId}」に変更しますか？\n次に変更できるのは30日後です。`)) {
+      return;
+    }
+    updateLoginIdMutation.mutate();
+  };
+
   const handleUpdatePassword = () => {
     if (!currentPassword || !newPassword || !confirmNewPassword) {
       showToast('すべてのフィールドを入力してください', 'error');
@@ -116,23 +440,59 @@ export default function UserSettingsModal() {
       showToast('新しいパスワードが一致しません', 'error');
       return;
     }
-    if (newPassword.length < 6 || !/^[A-Za-z0-9]+$/.test(newPassword)) {
-      showToast('パスワードは半角英数字6文字以上で入力してください', 'error');
+    if (newPassword.length < 8) {
+      showToast('パスワードは8文字以上で入力してください', 'error');
       return;
     }
     updatePasswordMutation.mutate();
   };
 
+  const handleTwoFactorCodeAction = (action: () => void) => {
+    if (!twoFactorCode.trim()) {
+      showToast('認証コードを入力してください', 'error');
+      return;
+    }
+    action();
+  };
+
+  const handleDisableTwoFactor = () => {
+    if (!twoFactorPassword) {
+      showToast('パスワードを入力してください', 'error');
+      return;
+    }
+    handleTwoFactorCodeAction(() => disableTwoFactorMutation.mutate());
+  };
+
+  const handleUpdateEmail = () => {
+    if (!newEmail.trim()) {
+      showToast('メールアドレスを入力してください', 'error');
+      return;
+    }
+    updateEmailMutation.mutate();
+  };
+
   const handleDeleteAccount = () => {
-    if (!confirm('本当にアカウントを削除しますか？\nこの操作は取り消せません。')) {
+    if (requiresDeletePassword && !deletePassword) {
+      showToast('パスワードを入力してください', 'error');
       return;
     }
-    if (!confirm('最終確認：すべてのデータが削除されます。本当によろしいですか？')) {
+    if (!confirm('本当にアカウントを削除しますか？\n30日後にすべてのデータが削除されます。')) {
       return;
     }
     deleteAccountMutation.mutate();
   };
 
+  const handleDeactivateAccount = () => {
+    if (!userInfo?.isOAuthUser && !deactivatePassword) {
+      showToast('パスワードを入力してください', 'error');
+      return;
+    }
+    if (!confirm('アカウントを無効化しますか？\n30日以内にログインしない場合、すべてのデータが削除されます。')) {
+      return;
+    }
+    deactivateAccountMutation.mutate();
+  };
+
   const handleLogout = async () => {
     try {
       await logout();
@@ -150,6 +510,71 @@ export default function UserSettingsModal() {
 
   const isOAuthUser = userInfo?.isOAuthUser ?? false;
 
+  const inputStyle = {
+    width: '100%',
+    padding: '14px 16px',
+    background: '#1a1a1a',
+    border: '1px solid var(--border)',
+    borderRadius: '10px',
+    color: 'var(--text)',
+    fontSize: '15px',
+    boxSizing: 'border-box',
+  } as const;
+
+  // ログインID変更（OAuthユーザーは自動生成のIDを変更できるようアカウント削除タブにも表示する）
+  const loginIdSection = (
+    <div style={{ marginBottom: '24px', paddingBottom: '20px', borderBottom: '1px solid var(--border)' }}>
+      <label
+        style={{
+          display: 'block',
+          fontSize: '13px',
+          fontWeight: 600,
+          color: 'var(--text)',
+          marginBottom: '8px',
+        }}
+      >
+        ユーザーID
+      </label>
+      <input
+        type="text"
+        value={newLoginId}
+        onChange={(e) => setNewLoginId(e.target.value)}
+        placeholder="4〜20文字（半角英数字と _ - .）"
+        maxLength={20}
+        style={{ ...inputStyle, marginBottom: '12px' }}
+      />
+      {!isOAuthUser ? (
+        <input
+          type="password"
+          value={loginIdPassword}
+          onChange={(e) => setLoginIdPassword(e.target.value)}
+          placeholder="現在のパスワード"
+          style={{ ...inputStyle, marginBottom: '12px' }}
+        />
+      ) : null}
+      <p style={{ margin: '0 0 12px', fontSize: '12px', color: 'var(--muted)' }}>
+        ユーザーIDは30日に1回まで変更できます
+      </p>
+      <button
+        onClick={handleUpdateLoginId}
+        disabled={updateLoginIdMutation.isPending}
+        style={{
+          width: '100%',
+          padding: '14px',
+          background: 'transparent',
+          color: 'var(--gold)',
+          border: '1px solid var(--gold)',
+          borderRadius: '10px',
+          fontSize: '15px',
+          fontWeight: 700,
+          cursor: 'pointer',
+        }}
+      >
+        ユーザーIDを変更
+      </button>
+    </div>
+  );
+
   return (
     <div
       className="user-settings-modal-overlay active"
@@ -206,7 +631,7 @@ export default function UserSettingsModal() {
         </div>
 
         {/* Body */}
-        <div style={{ padding: '20px' }}>
+        <div style={{ padding: '20px', maxHeight: 'calc(90vh - 60px)', overflowY: 'auto' }}>
           {/* Tabs */}
           <div
             style={{
@@ -252,9 +677,80 @@ export default function UserSettingsModal() {
                 >
                   パスワード変更
                 </button>
+                <button
+                  onClick={() => setActiveTab('two-factor')}
+                  style={{
+                    flex: 1,
+                    padding: '12px 8px',
+                    background: 'transparent',
+                    border: 'none',
+                    color: activeTab === 'two-factor' ? 'var(--gold)' : 'var(--muted)',
+                    fontSize: '13px',
+                    fontWeight: 500,
+                    cursor: 'pointer',
+                    borderBottom: activeTab === 'two-factor' ? '2px solid var(--gold)' : '2px solid transparent',
+                    marginBottom: '-1px',
+                  }}
+                >
+                  二段階認証
+                </button>
               </>
             ) : null}
 
+            <button
+              onClick={() => setActiveTab('email')}
+              style={{
+                flex: 1,
+                padding: '12px 8px',
+                background: 'transparent',
+                border: 'none',
+                color: activeTab === 'email' ? 'var(--gold)' : 'var(--muted)',
+                fontSize: '13px',
+                fontWeight: 500,
+                cursor: 'pointer',
+                borderBottom: activeTab === 'email' ? '2px solid var(--gold)' : '2px solid transparent',
+                marginBottom: '-1px',
+              }}
+            >
+              メール
+            </button>
+
+            <button
+              onClick={() => setActiveTab('api-keys')}
+              style={{
+                flex: 1,
+                padding: '12px 8px',
+                background: 'transparent',
+                border: 'none',
+                color: activeTab === 'api-keys' ? 'var(--gold)' : 'var(--muted)',
+                fontSize: '13px',
+                fontWeight: 500,
+                cursor: 'pointer',
+                borderBottom: activeTab === 'api-keys' ? '2px solid var(--gold)' : '2px solid transparent',
+                marginBottom: '-1px',
+              }}
+            >
+              APIキー
+            </button>
+
+            <button
+              onClick={() => setActiveTab('security')}
+              style={{
+                flex: 1,
+                padding: '12px 8px',
+                background: 'transparent',
+                border: 'none',
+                color: activeTab === 'security' ? 'var(--gold)' : 'var(--muted)',
+                fontSize: '13px',
+                fontWeight: 500,
+                cursor: 'pointer',
+                borderBottom: activeTab === 'security' ? '2px solid var(--gold)' : '2px solid transparent',
+                marginBottom: '-1px',
+              }}
+            >
+              ログイン履歴
+            </button>
+
             <button
               onClick={() => setActiveTab('delete-account')}
               style={{
@@ -277,6 +773,36 @@ export default function UserSettingsModal() {
           {/* Tab Content: Display Name */}
           {activeTab === 'display-name' && !isOAuthUser ? (
             <div>
+              {loginIdSection}
+              <div style={{ marginBottom: '20px' }}>
+                <label
+                  style={{
+                    display: 'block',
+                    fontSize: '13px',
+                    fontWeight: 600,
+                    color: 'var(--text)',
+                    marginBottom: '8px',
+                  }}
+                >
+                  プロフィール画像
+                </label>
+                <input
+                  type="file"
+                  accept="image/jpeg,image/png,image/gif,image/webp"
+                  disabled={uploadProfileImageMutation.isPending}
+                  onChange={(e) => {
+                    const file = e.target.files?.[0];
+                    e.target.value = '';
+                    if (!file) return;
+                    if (file.size > 5 * 1024 * 1024) {
+                      showToast('画像サイズは5MB以下にしてください', 'error');
+                      return;
+                    }
+                    uploadProfileImageMutation.mutate(file);
+                  }}
+                  style={{ fontSize: '13px', color: 'var(--muted)' }}
+                />
+              </div>
               <div style={{ marginBottom: '20px' }}>
                 <label
                   style={{
@@ -327,6 +853,535 @@ export default function UserSettingsModal() {
             </div>
           ) : null}
 
+          {/* Tab Content: Two-factor */}
+          {activeTab === 'two-factor' && !isOAuthUser ? (
+            <div>
+              {backupCodes.length > 0 ? (
+                <div
+                  style={{
+                    marginBottom: '20px',
+                    padding: '12px 16px',
+                    background: '#1a1a1a',
+                    border: '1px solid var(--border-gold)',
+                    borderRadius: '10px',
+                    fontSize: '13px',
+                    color: 'var(--text)',
+                  }}
+                >
+                  <div style={{ marginBottom: '8px', fontWeight: 600 }}>
+                    バックアップコード（この画面でしか表示されません）
+                  </div>
+                  <div
+                    style={{
+                      display: 'grid',
+                      gridTemplateColumns: '1fr 1fr',
+                      gap: '4px',
+                      fontFamily: 'monospace',
+                      fontSize: '14px',
+                    }}
+                  >
+                    {backupCodes.map((code) => (
+                      <span key={code}>{code}</span>
+                    ))}
+                  </div>
+                  <div style={{ marginTop: '8px', color: 'var(--muted)', fontSize: '12px' }}>
+                    認証アプリを使えなくなったときに、各コードを1回だけ使えます。安全な場所に保管してください。
+                  </div>
+                </div>
+              ) : null}
+
+              {twoFactorStatus?.enabled ? (
+                <div>
+                  <div style={{ marginBottom: '16px', fontSize: '13px', color: 'var(--muted)', lineHeight: 1.6 }}>
+                    二段階認証は有効です。残りのバックアップコード: {twoFactorStatus.backupCodesRemaining}個
+                  </div>
+                  <div style={{ marginBottom: '16px' }}>
+                    <label
+                      style={{
+                        display: 'block',
+                        fontSize: '13px',
+                        fontWeight: 600,
+                        color: 'var(--text)',
+                        marginBottom: '8px',
+                      }}
+                    >
+                      認証コード
+                    </label>
+                    <input
+                      type="text"
+                      inputMode="numeric"
+                      autoComplete="one-time-code"
+                      value={twoFactorCode}
+                      onChange={(e) => setTwoFactorCode(e.target.value)}
+                      placeholder="認証アプリのコード、またはバックアップコード"
+                      style={{
+                        width: '100%',
+                        padding: '14px 16px',
+                        background: '#1a1a1a',
+                        border: '1px solid var(--border)',
+                        borderRadius: '10px',
+                        color: 'var(--text)',
+                        fontSize: '15px',
+                        boxSizing: 'border-box',
+                      }}
+                    />
+                  </div>
+                  <button
+                    onClick={() => handleTwoFactorCodeAction(() => regenerateBackupCodesMutation.mutate())}
+                    disabled={regenerateBackupCodesMutation.isPending}
+                    style={{
+                      width: '100%',
+                      padding: '12px',
+                      marginBottom: '12px',
+                      background: 'transparent',
+                      color: 'var(--gold)',
+                      border: '1px solid var(--border-gold)',
+                      borderRadius: '10px',
+                      fontSize: '14px',
+                      fontWeight: 600,
+                      cursor: 'pointer',
+                    }}
+                  >
+                    バックアップコードを再発行
+                  </button>
+                  <div style={{ marginBottom: '16px' }}>
+                    <label
+                      style={{
+                        display: 'block',
+                        fontSize: '13px',
+                        fontWeight: 600,
+                        color: 'var(--text)',
+                        marginBottom: '8px',
+                      }}
+                    >
+                      パスワード（無効にする場合）
+                    </label>
+                    <input
+                      type="password"
+                      value={twoFactorPassword}
+                      onChange={(e) => setTwoFactorPassword(e.target.value)}
+                      placeholder="現在のパスワード"
+                      style={{
+                        width: '100%',
+                        padding: '14px 16px',
+                        background: '#1a1a1a',
+                        border: '1px solid var(--border)',
+                        borderRadius: '10px',
+                        color: 'var(--text)',
+                        fontSize: '15px',
+                        boxSizing: 'border-box',
+                      }}
+                    />
+                  </div>
+                  <button
+                    onClick={handleDisableTwoFactor}
+                    disabled={disableTwoFactorMutation.isPending}
+                    style={{
+                      width: '100%',
+                      padding: '12px',
+                      marginBottom: '12px',
+                      background: 'transparent',
+                      color: 'var(--gold)',
+                      border: '1px solid var(--border-gold)',
+                      borderRadius: '10px',
+                      fontSize: '14px',
+                      fontWeight: 600,
+                      cursor: 'pointer',
+                    }}
+                  >
+                    二段階認証を無効にする
+                  </button>
+                </div>
+              ) : twoFactorSetup ? (
+                <div>
+                  <div style={{ marginBottom: '16px', fontSize: '13px', color: 'var(--muted)', lineHeight: 1.6 }}>
+                    認証アプリ（Google Authenticatorなど）に次のキーを登録し、表示された6桁のコードを入力してください。
+                  </div>
+                  <div
+                    style={{
+                      marginBottom: '8px',
+                      padding: '12px 16px',
+                      background: '#1a1a1a',
+                      border: '1px solid var(--border)',
+                      borderRadius: '10px',
+                      fontFamily: 'monospace',
+                      fontSize: '14px',
+                      color: 'var(--text)',
+                      wordBreak: 'break-all',
+                    }}
+                  >
+                    {twoFactorSetup.secret}
+                  </div>
+                  <a
+                    href={twoFactorSetup.otpauthUri}
+                    style={{ display: 'block', marginBottom: '16px', fontSize: '13px', color: 'var(--gold)' }}
+                  >
+                    この端末の認証アプリで開く
+                  </a>
+                  <div style={{ marginBottom: '20px' }}>
+                    <label
+                      style={{
+                        display: 'block',
+                        fontSize: '13px',
+                        fontWeight: 600,
+                        color: 'var(--text)',
+                        marginBottom: '8px',
+                      }}
+                    >
+                      認証コード
+                    </label>
+                    <input
+                      type="text"
+                      inputMode="numeric"
+                      autoComplete="one-time-code"
+                      maxLength={6}
+                      value={twoFactorCode}
+                      onChange={(e) => setTwoFactorCode(e.target.value)}
+                      placeholder="6桁のコード"
+                      style={{
+                        width: '100%',
+                        padding: '14px 16px',
+                        background: '#1a1a1a',
+                        border: '1px solid var(--border)',
+                        borderRadius: '10px',
+                        color: 'var(--text)',
+                        fontSize: '15px',
+                        boxSizing: 'border-box',
+                      }}
+                    />
+                  </div>
+                  <button
+                    onClick={() => handleTwoFactorCodeAction(() => verifyTwoFactorMutation.mutate())}
+                    disabled={verifyTwoFactorMutation.isPending}
+                    style={{
+                      width: '100%',
+                      padding: '14px',
+                      background: 'linear-gradient(135deg, var(--gold) 0%, var(--gold-light) 100%)',
+                      color: 'var(--bg)',
+                      border: 'none',
+                      borderRadius: '10px',
+                      fontSize: '15px',
+                      fontWeight: 700,
+                      cursor: 'pointer',
+                    }}
+                  >
+                    有効にする
+                  </button>
+                </div>
+              ) : (
+                <div>
+                  <div style={{ marginBottom: '16px', fontSize: '13px', color: 'var(--muted)', lineHeight: 1.6 }}>
+                    ログイン時にパスワードに加えて認証アプリのコードを求めるようにします。
+                  </div>
+                  <button
+                    onClick={() => setupTwoFactorMutation.mutate()}
+                    disabled={setupTwoFactorMutation.isPending}
+                    style={{
+                      width: '100%',
+                      padding: '14px',
+                      background: 'linear-gradient(135deg, var(--gold) 0%, var(--gold-light) 100%)',
+                      color: 'var(--bg)',
+                      border: 'none',
+                      borderRadius: '10px',
+                      fontSize: '15px',
+                      fontWeight: 700,
+                      cursor: 'pointer',
+                    }}
+                  >
+                    設定を開始
+                  </button>
+                </div>
+              )}
+            </div>
+          ) : null}
+
+          {/* Tab Content: Email */}
+          {activeTab === 'email' ? (
+            <div>
+              <div
+                style={{
+                  marginBottom: '20px',
+                  padding: '12px 16px',
+                  background: '#1a1a1a',
+                  border: '1px solid var(--border)',
+                  borderRadius: '10px',
+                  fontSize: '13px',
+                  color: 'var(--text)',
+                }}
+              >
+                <div style={{ marginBottom: '4px' }}>
+                  {userInfo?.email || 'メールアドレスは未登録です'}
+                </div>
+                {userInfo?.email ? (
+                  <div
+                    style={{
+                      color: userInfo.emailVerified ? 'var(--gold)' : 'var(--muted)',
+                      fontSize: '12px',
+                    }}
+                  >
+                    {userInfo.emailVerified ? '確認済み' : '未確認（お問い合わせへの返信には確認が必要です）'}
+                  </div>
+                ) : null}
+              </div>
+              {userInfo?.email && !userInfo.emailVerified ? (
+                <button
+                  onClick={() => resendVerificationMutation.mutate()}
+                  disabled={resendVerificationMutation.isPending}
+                  style={{
+                    width: '100%',
+                    padding: '12px',
+                    marginBottom: '20px',
+                    background: 'transparent',
+                    color: 'var(--gold)',
+                    border: '1px solid var(--border-gold)',
+                    borderRadius: '10px',
+                    fontSize: '14px',
+                    fontWeight: 600,
+                    cursor: 'pointer',
+                  }}
+                >
+                  確認メールを再送信
+                </button>
+              ) : null}
+              <div style={{ marginBottom: '20px' }}>
+                <label
+                  style={{
+                    display: 'block',
+                    fontSize: '13px',
+                    fontWeight: 600,
+                    color: 'var(--text)',
+                    marginBottom: '8px',
+                  }}
+                >
+                  新しいメールアドレス
+                </label>
+                <input
+                  type="email"
+                  value={newEmail}
+                  onChange={(e) => setNewEmail(e.target.value)}
+                  placeholder="example@example.com"
+                  maxLength={200}
+                  style={{
+                    width: '100%',
+                    padding: '14px 16px',
+                    background: '#1a1a1a',
+                    border: '1px solid var(--border)',
+                    borderRadius: '10px',
+                    color: 'var(--text)',
+                    fontSize: '15px',
+                    boxSizing: 'border-box',
+                  }}
+                />
+              </div>
+              <button
+                onClick={handleUpdateEmail}
+                disabled={updateEmailMutation.isPending}
+                style={{
+                  width: '100%',
+                  padding: '14px',
+                  background: 'linear-gradient(135deg, var(--gold) 0%, var(--gold-light) 100%)',
+                  color: 'var(--bg)',
+                  border: 'none',
+                  borderRadius: '10px',
+                  fontSize: '15px',
+                  fontWeight: 700,
+                  cursor: 'pointer',
+                }}
+              >
+                確認メールを送信
+              </button>
+            </div>
+          ) : null}
+
+          {/* Tab Content: API Keys */}
+          {activeTab === 'api-keys' ? (
+            <div>
+              <p style={{ margin: '0 0 16px', fontSize: '13px', color: 'var(--muted)', lineHeight: 1.6 }}>
+                スクリプトからトレーニング記録を登録するためのキーです。X-Api-Keyヘッダーに設定して利用します。
+              </p>
+              {createdApiKey ? (
+                <div
+                  style={{
+                    marginBottom: '20px',
+                    padding: '12px 16px',
+                    background: '#1a1a1a',
+                    border: '1px solid var(--border-gold)',
+                    borderRadius: '10px',
+                    fontSize: '13px',
+                    color: 'var(--text)',
+                  }}
+                >
+                  <div style={{ marginBottom: '8px', color: 'var(--gold)' }}>
+                    このキーは今しか表示されません。安全な場所に保存してください。
+                  </div>
+                  <code style={{ wordBreak: 'break-all' }}>{createdApiKey}</code>
+                </div>
+              ) : null}
+              {(apiKeys ?? []).map((apiKey) => (
+                <div
+                  key={apiKey.id}
+                  style={{
+                    display: 'flex',
+                    alignItems: 'center',
+                    gap: '12px',
+                    marginBottom: '8px',
+                    padding: '12px 16px',
+                    background: '#1a1a1a',
+                    border: '1px solid var(--border)',
+                    borderRadius: '10px',
+                    fontSize: '13px',
+                    color: 'var(--text)',
+                  }}
+                >
+                  <div style={{ flex: 1, minWidth: 0 }}>
+                    <div style={{ fontWeight: 600 }}>{apiKey.name}</div>
+                    <div style={{ color: 'var(--muted)', fontSize: '12px' }}>
+                      {apiKey.keyPrefix}… /{' '}
+                      {apiKey.scopes
+                        .map((scope) => API_KEY_SCOPE_OPTIONS.find((o) => o.value === scope)?.label ?? scope)
+                        .join('・')}
+                      {' / '}
+                      {apiKey.lastUsedAt ? `最終利用 ${apiKey.lastUsedAt.slice(0, 10)}` : '未使用'}
+                    </div>
+                  </div>
+                  <button
+                    onClick={() => {
+                      if (window.confirm(`APIキー「${apiKey.name}」を削除しますか？`)) {
+                        revokeApiKeyMutation.mutate(apiKey.id);
+                      }
+                    }}
+                    disabled={revokeApiKeyMutation.isPending}
+                    style={{
+                      padding: '6px 12px',
+                      background: 'transparent',
+                      color: 'var(--muted)',
+                      border: '1px solid var(--border)',
+                      borderRadius: '8px',
+                      fontSize: '12px',
+                      cursor: 'pointer',
+                    }}
+                  >
+                    削除
+                  </button>
+                </div>
+              ))}
+              <div style={{ margin: '20px 0' }}>
+                <label
+                  style={{
+                    display: 'block',
+                    fontSize: '13px',
+                    fontWeight: 600,
+                    color: 'var(--text)',
+                    marginBottom: '8px',
+                  }}
+                >
+                  新しいキーの名前
+                </label>
+                <input
+                  type="text"
+                  value={apiKeyName}
+                  onChange={(e) => setApiKeyName(e.target.value)}
+                  placeholder="例: 記録アップロード用スクリプト"
+                  maxLength={50}
+                  style={{
+                    width: '100%',
+                    padding: '14px 16px',
+                    marginBottom: '12px',
+                    background: '#1a1a1a',
+                    border: '1px solid var(--border)',
+                    borderRadius: '10px',
+                    color: 'var(--text)',
+                    fontSize: '15px',
+                    boxSizing: 'border-box',
+                  }}
+                />
+                {API_KEY_SCOPE_OPTIONS.map((option) => (
+                  <label
+                    key={option.value}
+                    style={{ display: 'flex', alignItems: 'center', gap: '8px', fontSize: '13px', color: 'var(--text)' }}
+                  >
+                    <input
+                      type="checkbox"
+                      checked={apiKeyScopes.includes(option.value)}
+                      onChange={(e) =>
+                        setApiKeyScopes((scopes) =>
+                          e.target.checked
+                            ? [...scopes, option.value]
+                            : scopes.filter((scope) => scope !== option.value)
+                        )
+                      }
+                    />
+                    {option.label}
+                  </label>
+                ))}
+              </div>
+              <button
+                onClick={() => {
+                  if (!apiKeyName.trim()) {
+                    showToast('キーの名前を入力してください', 'error');
+                    return;
+                  }
+                  if (apiKeyScopes.length === 0) {
+                    showToast('権限を1つ以上選択してください', 'error');
+                    return;
+                  }
+                  createApiKeyMutation.mutate();
+                }}
+                disabled={createApiKeyMutation.isPending}
+                style={{
+                  width: '100%',
+                  padding: '14px',
+                  background: 'linear-gradient(135deg, var(--gold) 0%, var(--gold-light) 100%)',
+                  color: 'var(--bg)',
+                  border: 'none',
+                  borderRadius: '10px',
+                  fontSize: '15px',
+                  fontWeight: 700,
+                  cursor: 'pointer',
+                }}
+              >
+                APIキーを発行
+              </button>
+            </div>
+          ) : null}
+
+          {/* Tab Content: Login Activity */}
+          {activeTab === 'security' ? (
+            <div>
+              <p style={{ margin: '0 0 16px', fontSize: '13px', color: 'var(--muted)', lineHeight: 1.6 }}>
+                最近のログインと失敗した試行です。心当たりのないアクセスがあればパスワードを変更してください。
+              </p>
+              {loginActivity && loginActivity.length === 0 ? (
+                <div style={{ fontSize: '13px', color: 'var(--muted)' }}>ログイン履歴はありません</div>
+              ) : null}
+              {(loginActivity ?? []).map((entry) => (
+                <div
+                  key={entry.id}
+                  style={{
+                    marginBottom: '8px',
+                    padding: '12px 16px',
+                    background: '#1a1a1a',
+                    border: `1px solid ${entry.success ? 'var(--border)' : '#ff6b6b'}`,
+                    borderRadius: '10px',
+                    fontSize: '13px',
+                    color: 'var(--text)',
+                  }}
+                >
+                  <div style={{ fontWeight: 600 }}>
+                    {entry.success
+                      ? 'ログイン成功'
+                      : `ログイン失敗（${entry.failureReason ? LOGIN_FAILURE_LABELS[entry.failureReason] : '不明'}）`}
+                  </div>
+                  <div style={{ color: 'var(--muted)', fontSize: '12px' }}>
+                    {entry.createdAt.replace('T', ' ')} / {entry.device} / {entry.ipAddress ?? 'IP不明'}
+                    {entry.provider !== 'LOCAL' ? ` / ${entry.provider}` : ''}
+                    {entry.client === 'token' ? ' / アプリ' : ''}
+                  </div>
+                </div>
+              ))}
+            </div>
+          ) : null}
+
           {/* Tab Content: Password */}
           {activeTab === 'password' && !isOAuthUser ? (
             <div>
@@ -375,7 +1430,7 @@ export default function UserSettingsModal() {
                   type="password"
                   value={newPassword}
                   onChange={(e) => setNewPassword(e.target.value)}
-                  placeholder="半角英数字6文字以上"
+                  placeholder="8文字以上（英大小文字・数字・記号のうち2種類以上）"
                   style={{
                     width: '100%',
                     padding: '14px 16px',
@@ -442,39 +1497,216 @@ export default function UserSettingsModal() {
           {/* Tab Content: Delete Account */}
           {activeTab === 'delete-account' ? (
             <div>
+              {isOAuthUser ? loginIdSection : null}
               <div
                 style={{
                   padding: '16px',
-                  background: 'rgba(220, 38, 38, 0.1)',
-                  border: '1px solid rgba(220, 38, 38, 0.3)',
+                  border: '1px solid var(--border)',
                   borderRadius: '10px',
                   marginBottom: '20px',
                 }}
               >
-                <p style={{ margin: '0 0 8px', fontWeight: 600, color: '#f87171' }}>
-                  ⚠️ この操作は取り消せません
-                </p>
-                <p style={{ margin: 0, fontSize: '14px', color: 'var(--muted)' }}>
-                  アカウントを削除すると、すべてのトレーニング記録やカスタム種目が完全に削除されます。
+                <p style={{ margin: '0 0 8px', fontWeight: 600 }}>データのエクスポート</p>
+                <p style={{ margin: '0 0 12px', fontSize: '14px', color: 'var(--muted)' }}>
+                  プロフィール・トレーニング記録・パートナーなどのデータをZIPファイルにまとめます。
+                  作成したファイルは7日間ダウンロードできます。
                 </p>
+                {accountExport?.status === 'COMPLETED' ? (
+                  <button
+                    onClick={handleDownloadExport}
+                    style={{
+                      width: '100%',
+                      padding: '12px',
+                      background: 'linear-gradient(135deg, var(--gold) 0%, var(--gold-light) 100%)',
+                      color: 'var(--bg)',
+                      border: 'none',
+                      borderRadius: '10px',
+                      fontSize: '15px',
+                      fontWeight: 700,
+                      cursor: 'pointer',
+                    }}
+                  >
+                    ZIPファイルをダウンロード
+                  </button>
+                ) : (
+                  <>
+                    {accountExport?.status === 'FAILED' ? (
+                      <p style={{ margin: '0 0 12px', fontSize: '14px', color: '#f87171' }}>
+                        {accountExport.errorMessage ?? 'エクスポートの作成に失敗しました'}
+                      </p>
+                    ) : null}
+                    <button
+                      onClick={() => requestExportMutation.mutate()}
+                      disabled={
+                        requestExportMutation.isPending ||
+                        accountExport?.status === 'PENDING' ||
+                        accountExport?.status === 'PROCESSING'
+                      }
+                      style={{
+                        width: '100%',
+                        padding: '12px',
+                        background: 'transparent',
+                        color: 'var(--text)',
+                        border: '1px solid var(--border)',
+                        borderRadius: '10px',
+                        fontSize: '15px',
+                        fontWeight: 700,
+                        cursor: 'pointer',
+                      }}
+                    >
+                      {accountExport?.status === 'PENDING' || accountExport?.status === 'PROCESSING'
+                        ? '作成中...'
+                        : 'エクスポートを作成'}
+                    </button>
+                  </>
+                )}
               </div>
-              <button
-                onClick={handleDeleteAccount}
-                disabled={deleteAccountMutation.isPending}
-                style={{
-                  width: '100%',
-                  padding: '14px',
-                  background: 'transparent',
-                  color: '#dc2626',
-                  border: '1px solid #dc2626',
-                  borderRadius: '10px',
-                  fontSize: '15px',
-                  fontWeight: 700,
-                  cursor: 'pointer',
-                }}
-              >
-                アカウントを削除する
-              </button>
+              {deletionStatus && deletionStatus.status !== 'NONE' ? (
+                <div>
+                  <div
+                    style={{
+                      padding: '16px',
+                      background: 'rgba(220, 38, 38, 0.1)',
+                      border: '1px solid rgba(220, 38, 38, 0.3)',
+                      borderRadius: '10px',
+                      marginBottom: '20px',
+                    }}
+                  >
+                    <p style={{ margin: '0 0 8px', fontWeight: 600, color: '#f87171' }}>
+                      {deletionStatus.status === 'SCHEDULED'
+                        ? 'アカウントの削除が予約されています'
+                        : '確認メールを送信済みです'}
+                    </p>
+                    <p style={{ margin: 0, fontSize: '14px', color: 'var(--muted)' }}>
+                      {deletionStatus.status === 'SCHEDULED'
+                        ? `${deletionStatus.scheduledDeletionAt?.slice(0, 10) ?? ''} にすべてのデータが削除されます。`
+                        : 'メール内のリンクを開くと削除が確定します。'}
+                    </p>
+                  </div>
+                  <button
+                    onClick={() => cancelDeletionMutation.mutate()}
+                    disabled={cancelDeletionMutation.isPending}
+                    style={{
+                      width: '100%',
+                      padding: '14px',
+                      background: 'transparent',
+                      color: 'var(--text)',
+                      border: '1px solid var(--border)',
+                      borderRadius: '10px',
+                      fontSize: '15px',
+                      fontWeight: 700,
+                      cursor: 'pointer',
+                    }}
+                  >
+                    削除を取り消す
+                  </button>
+                </div>
+              ) : (
+                <div>
+                  <div
+                    style={{
+                      padding: '16px',
+                      background: 'rgba(220, 38, 38, 0.1)',
+                      border: '1px solid rgba(220, 38, 38, 0.3)',
+                      borderRadius: '10px',
+                      marginBottom: '20px',
+                    }}
+                  >
+                    <p style={{ margin: '0 0 8px', fontWeight: 600, color: '#f87171' }}>
+                      ⚠️ 30日後にすべてのデータが削除されます
+                    </p>
+                    <p style={{ margin: 0, fontSize: '14px', color: 'var(--muted)' }}>
+                      {requiresDeletePassword
+                        ? 'パスワードを確認すると削除が予約され、30日後にすべてのトレーニング記録やカスタム種目が完全に削除されます。'
+                        : '登録メールアドレスに確認メールを送信します。メール内のリンクを開くと削除が予約され、30日後にすべてのトレーニング記録やカスタム種目が完全に削除されます。'}
+                    </p>
+                  </div>
+                  {requiresDeletePassword ? (
+                    <input
+                      type="password"
+                      value={deletePassword}
+                      onChange={(e) => setDeletePassword(e.target.value)}
+                      placeholder="現在のパスワード"
+                      style={{
+                        width: '100%',
+                        padding: '14px 16px',
+                        marginBottom: '16px',
+                        background: '#1a1a1a',
+                        border: '1px solid var(--border)',
+                        borderRadius: '10px',
+                        color: 'var(--text)',
+                        fontSize: '15px',
+                        boxSizing: 'border-box',
+                      }}
+                    />
+                  ) : null}
+                  <button
+                    onClick={handleDeleteAccount}
+                    disabled={deleteAccountMutation.isPending}
+                    style={{
+                      width: '100%',
+                      padding: '14px',
+                      background: 'transparent',
+                      color: '#dc2626',
+                      border: '1px solid #dc2626',
+                      borderRadius: '10px',
+                      fontSize: '15px',
+                      fontWeight: 700,
+                      cursor: 'pointer',
+                    }}
+                  >
+                    アカウントを削除する
+                  </button>
+                  <div
+                    style={{
+                      marginTop: '24px',
+                      paddingTop: '20px',
+                      borderTop: '1px solid var(--border)',
+                    }}
+                  >
+                    <p style={{ margin: '0 0 8px', fontWeight: 600 }}>アカウントの無効化</p>
+                    <p style={{ margin: '0 0 12px', fontSize: '14px', color: 'var(--muted)' }}>
+                      すぐにログインできなくなり、30日後にすべてのデータが削除されます。30日以内にログインすると、アカウントを再開できます。
+                    </p>
+                    {!isOAuthUser ? (
+                      <input
+                        type="password"
+                        value={deactivatePassword}
+                        onChange={(e) => setDeactivatePassword(e.target.value)}
+                        placeholder="現在のパスワード"
+                        style={{
+                          width: '100%',
+                          padding: '14px 16px',
+                          marginBottom: '16px',
+                          background: '#1a1a1a',
+                          border: '1px solid var(--border)',
+                          borderRadius: '10px',
+                          color: 'var(--text)',
+                          fontSize: '15px',
+                          boxSizing: 'border-box',
+                        }}
+                      />
+                    ) : null}
+                    <button
+                      onClick={handleDeactivateAccount}
+                      disabled={deactivateAccountMutation.isPending}
+                      style={{
+                        width: '100%',
+                        padding: '14px',
+                        background: 'transparent',
+                        color: '#dc2626',
+                        border: '1px solid #dc2626',
+                        borderRadius: '10px',
+                        fontSize: '15px',
+                        fontWeight: 700,
+                        cursor: 'pointer',
+                      }}
+                    >
+                      アカウントを無効化する
+                    </button>
+                  </div>
+                </div>
+              )}
             </div>
           ) : null}
 
@@ -4,5 +4,7 @@ export { useDebouncedValue } from './useDebouncedValue';
 export { useWindowEventListener } from './useWindowEventListener';
 export { useScrollDirection } from './useScrollDirection';
 export { useSwipe } from './useSwipe';
+export { usePetEvents } from './usePetEvents';
+export type { PetEvent } from './usePetEvents';
 
 
@@ -0,0 +1,55 @@
+import { useEffect, useRef } from 'react';
+
+export type PetEvent =
+  | { type: 'pet.levelUp'; payload: { petId: number; level: number } }
+  | { type: 'pet.matured'; payload: { petId: number; level: number } }
+  | { type: 'pet.unlocked'; payload: { petTypeName: string } };
+
+// 切断時の再接続までの待ち時間
+const RECONNECT_DELAY_MS = 5000;
+
+/**
+ * パートナーのイベント（レベルアップ・覚醒・種類の解放）をWebSocketで受け取る
+ */
+export function usePetEvents(handler: (event: PetEvent) => void, options?: { enabled?: boolean }) {
+  const { enabled = true } = options ?? {};
+  const handlerRef = useRef(handler);
+
+  useEffect(() => {
+    handlerRef.current = handler;
+  }, [handler]);
+
+  useEffect(() => {
+    if (!enabled || typeof window === 'undefined') return;
+
+    let socket: WebSocket | null = null;
+    let reconnectTimer: number | undefined;
+    let closed = false;
+
+    const connect = () => {
+      const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+      socket = new WebSocket(`${protocol}//${window.location.host}/ws/pet`);
+
+      socket.onmessage = (message) => {
+        try {
+          handlerRef.current(JSON.parse(message.data) as PetEvent);
+        } catch (error) {
+          console.error('Failed to parse pet event:', error);
+        }
+      };
+
+      socket.onclose = () => {
+        if (closed) return;
+        reconnectTimer = window.setTimeout(connect, RECONNECT_DELAY_MS);
+      };
+    };
+
+    connect();
+
+    return () => {
+      closed = true;
+      window.clearTimeout(reconnectTimer);
+      socket?.close();
+    };
+  }, [enabled]);
+}
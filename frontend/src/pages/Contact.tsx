@@ -2,6 +2,7 @@ import { useMemo, useRef, useState } from 'react';
 import { useNavigate } from 'react-router-dom';
 import { useUIStore } from '../stores/uiStore';
 import contactApi, { type ContactRequest, ContactApiError } from '../services/contactApi';
+import CaptchaWidget from '../components/common/CaptchaWidget';
 import '../styles/contact.css';
 
 const MAX_IMAGE_SIZE = 2 * 1024 * 1024; // 2MB
@@ -133,6 +134,10 @@ export default function Contact() {
   const [form, setForm] = useState<FormState>(initialState);
   const [images, setImages] = useState<ImagePreview[]>([]);
   const [isSubmitting, setIsSubmitting] = useState(false);
+  const [captchaEnabled, setCaptchaEnabled] = useState(false);
+  const [captchaToken, setCaptchaToken] = useState<string | null>(null);
+  // トークンは1回しか使えないため、送信のたびにウィジェットを作り直す
+  const [captchaKey, setCaptchaKey] = useState(0);
   const [isProcessingImages, setIsProcessingImages] = useState(false);
   const [isDragging, setIsDragging] = useState(false);
   const [fieldErrors, setFieldErrors] = useState<FieldErrors>({});
@@ -287,6 +292,7 @@ export default function Contact() {
       userAgent: navigator.userAgent,
       screenWidth: window.screen?.width,
       screenHeight: window.screen?.height,
+      captchaToken: captchaToken ?? undefined,
     };
 
     const imageFiles = images.map((img) => img.file);
@@ -309,6 +315,8 @@ export default function Contact() {
       }
     } finally {
       setIsSubmitting(false);
+      setCaptchaToken(null);
+      setCaptchaKey((key) => key + 1);
     }
   };
 
@@ -474,6 +482,12 @@ export default function Contact() {
             </div>
           )}
 
+          <CaptchaWidget
+            key={captchaKey}
+            onChange={setCaptchaToken}
+            onAvailabilityChange={setCaptchaEnabled}
+          />
+
           <div className="contact-actions">
             <button
               type="button"
@@ -486,7 +500,9 @@ export default function Contact() {
             <button
               type="submit"
               className="contact-submit"
-              disabled={!isValid || isSubmitting || isProcessingImages}
+              disabled={
+                !isValid || isSubmitting || isProcessingImages || (captchaEnabled && !captchaToken)
+              }
             >
               {isSubmitting ? '送信中...' : '送信する'}
             </button>
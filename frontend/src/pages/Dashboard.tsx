@@ -159,6 +159,7 @@ export default function Dashboard() {
 
   const heatmapData = heatmapResponse?.heatmapData || {};
   const volumeData = heatmapResponse?.volumeData || {};
+  const heatmapThresholds = heatmapResponse?.thresholds;
 
   // Calculate weekly workouts and changes
   const weeklyWorkouts = userStats?.weeklyWorkouts ?? 0;
@@ -460,11 +461,11 @@ export default function Dashboard() {
 
         <div className="heatmap-legend">
           <span className="legend-label">Less</span>
-          <div className="legend-cell level-0" />
-          <div className="legend-cell level-1" />
-          <div className="legend-cell level-2" />
-          <div className="legend-cell level-3" />
-          <div className="legend-cell level-4" />
+          <div className="legend-cell level-0" title="0kg" />
+          <div className="legend-cell level-1" title={heatmapThresholds ? `〜${heatmapThresholds[0].toLocaleString()}kg` : undefined} />
+          <div className="legend-cell level-2" title={heatmapThresholds ? `${heatmapThresholds[0].toLocaleString()}〜${heatmapThresholds[1].toLocaleString()}kg` : undefined} />
+          <div className="legend-cell level-3" title={heatmapThresholds ? `${heatmapThresholds[1].toLocaleString()}〜${heatmapThresholds[2].toLocaleString()}kg` : undefined} />
+          <div className="legend-cell level-4" title={heatmapThresholds ? `${heatmapThresholds[2].toLocaleString()}kg〜` : undefined} />
           <span className="legend-label">More</span>
         </div>
       </section>
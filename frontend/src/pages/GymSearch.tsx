@@ -14,6 +14,7 @@ export default function GymSearch() {
   const [searchInput, setSearchInput] = useState('');
   const [selectedAreas, setSelectedAreas] = useState<string[]>([]);
   const [selectedTags, setSelectedTags] = useState<string[]>([]);
+  const [selectedGroups, setSelectedGroups] = useState<string[]>([]);
   const [maxPrice, setMaxPrice] = useState<number>(15000);
   const [viewMode, setViewMode] = useState<'list' | 'map'>('list');
 
@@ -30,7 +31,10 @@ export default function GymSearch() {
     queryFn: getGymTags,
     staleTime: 5 * 60 * 1000, // 5分間キャッシュ
   });
-  const tags = Array.isArray(tagsData) ? tagsData.map((t) => t.name) : [];
+  const tagGroups = Array.isArray(tagsData) ? tagsData : [];
+  const groupNames = tagGroups
+    .map((g) => g.group)
+    .filter((g): g is string => g !== null);
 
   // Gym search (infinite scroll)
   const {
@@ -58,8 +62,9 @@ export default function GymSearch() {
       areas: selectedAreas.length > 0 ? selectedAreas : undefined,
       maxFee: debouncedMaxPrice,
       tags: selectedTags.length > 0 ? selectedTags : undefined,
+      groups: selectedGroups.length > 0 ? selectedGroups : undefined,
     });
-  }, [debouncedSearchInput, selectedAreas, debouncedMaxPrice, selectedTags]);
+  }, [debouncedSearchInput, selectedAreas, debouncedMaxPrice, selectedTags, selectedGroups]);
 
   // 無限スクロール用カスタムフック
   const loadMoreRef = useInfiniteScroll({
@@ -91,11 +96,21 @@ export default function GymSearch() {
     }
   };
 
+  // Toggle tag group selection
+  const toggleGroup = (group: string) => {
+    if (selectedGroups.includes(group)) {
+      setSelectedGroups(selectedGroups.filter((g) => g !== group));
+    } else {
+      setSelectedGroups([...selectedGroups, group]);
+    }
+  };
+
   // Reset all filters
   const handleReset = () => {
     setSearchInput('');
     setSelectedAreas([]);
     setSelectedTags([]);
+    setSelectedGroups([]);
     setMaxPrice(15000);
     setFilter({});
   };
@@ -209,6 +224,30 @@ export default function GymSearch() {
           </div>
         </div>
 
+        {/* Tag group filter */}
+        {groupNames.length > 0 && (
+          <>
+            <div className="label">カテゴリ</div>
+            <div className="chips" id="tagGroupChips">
+              <button
+                className={`chip${selectedGroups.length === 0 ? ' active' : ''}`}
+                onClick={() => setSelectedGroups([])}
+              >
+                すべて
+              </button>
+              {groupNames.map((group) => (
+                <button
+                  key={group}
+                  className={`chip${selectedGroups.includes(group) ? ' active' : ''}`}
+                  onClick={() => toggleGroup(group)}
+                >
+                  {group}
+                </button>
+              ))}
+            </div>
+          </>
+        )}
+
         {/* Tag filter */}
         <div className="label">設備・施設</div>
         <div className="chips" id="tagChips">
@@ -218,16 +257,25 @@ export default function GymSearch() {
           >
             すべて
           </button>
-          {tags.map((tag) => (
-            <button
-              key={tag}
-              className={`chip${selectedTags.includes(tag) ? ' active' : ''}`}
-              onClick={() => toggleTag(tag)}
-            >
-              {tag}
-            </button>
-          ))}
         </div>
+        {tagGroups.map((group) => (
+          <div key={group.group ?? '__ungrouped'}>
+            {groupNames.length > 0 && (
+              <div className="label">{group.group ?? 'その他'}</div>
+            )}
+            <div className="chips">
+              {group.tags.map((tag) => (
+                <button
+                  key={tag.id}
+                  className={`chip${selectedTags.includes(tag.name) ? ' active' : ''}`}
+                  onClick={() => toggleTag(tag.name)}
+                >
+                  {tag.name}
+                </button>
+              ))}
+            </div>
+          </div>
+        ))}
 
         <div id="resultCount" className="result-count">
           {totalCount}件のジムが見つかりました
@@ -1,6 +1,7 @@
 import { useState } from 'react';
-import { Link, useNavigate } from 'react-router-dom';
-import { login } from '../services/authApi';
+import { Link, useNavigate, useSearchParams } from 'react-router-dom';
+import axios from 'axios';
+import { login, loginTwoFactor, reactivateAccount } from '../services/authApi';
 import { useAuthStore } from '../stores/authStore';
 
 // Inline styles matching the original login.html
@@ -184,7 +185,22 @@ export default function Login() {
   const [username, setUsername] = useState('');
   const [password, setPassword] = useState('');
   const [showPassword, setShowPassword] = useState(false);
-  const [error, setError] = useState('');
+  const [searchParams] = useSearchParams();
+  // ソーシャルログインで二段階認証が必要な場合はコードだけを入力する（クエリで通知される）
+  const [socialTwoFactor, setSocialTwoFactor] = useState(
+    searchParams.get('twoFactorRequired') === '1'
+  );
+  // 二段階認証が有効なアカウントではコードの入力欄を表示する
+  const [twoFactorRequired, setTwoFactorRequired] = useState(socialTwoFactor);
+  const [totpCode, setTotpCode] = useState('');
+  // ソーシャルログインのコールバックが拒否された場合（別のタブで開始した・時間切れなど）
+  const [error, setError] = useState(
+    searchParams.get('error') === 'oauth_state'
+      ? 'ログインの有効期限が切れました。もう一度お試しください'
+      : ''
+  );
+  // 無効化されたアカウントでログインした場合は再開を確認する（ソーシャルログインはクエリで通知される）
+  const [deactivated, setDeactivated] = useState(searchParams.get('accountDeactivated') === '1');
   const [isLoading, setIsLoading] = useState(false);
   const [focusedInput, setFocusedInput] = useState<string | null>(null);
   const [hoveredBtn, setHoveredBtn] = useState<string | null>(null);
@@ -195,11 +211,45 @@ export default function Login() {
     setIsLoading(true);
 
     try {
-      await login(username, password);
+      if (socialTwoFactor) {
+        await loginTwoFactor(totpCode.trim());
+      } else {
+        await login(username, password, twoFactorRequired ? totpCode.trim() : undefined);
+      }
+      await fetchUser();
+      navigate('/dashboard');
+    } catch (err) {
+      const data = axios.isAxiosError(err) ? err.response?.data : undefined;
+      if (socialTwoFactor && data?.redirect) {
+        // 時間切れなどで保留中のソーシャルログインが無い場合は通常のログインに戻す
+        setSocialTwoFactor(false);
+        setTwoFactorRequired(false);
+        setError(data.error);
+      } else if (data?.twoFactorRequired) {
+        setTwoFactorRequired(true);
+        setError(data.error);
+      } else if (data?.accountDeactivated) {
+        setDeactivated(true);
+      } else if (axios.isAxiosError(err) && err.response?.status === 429) {
+        setError(data?.message || '試行回数が多すぎます。しばらく待ってから再度お試しください');
+      } else {
+        setError('ログインIDまたはパスワードが正しくありません');
+      }
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  const handleReactivate = async () => {
+    setError('');
+    setIsLoading(true);
+    try {
+      await reactivateAccount();
       await fetchUser();
       navigate('/dashboard');
     } catch {
-      setError('ログインIDまたはパスワードが正しくありません');
+      setDeactivated(false);
+      setError('アカウントを再開できませんでした。もう一度ログインしてください');
     } finally {
       setIsLoading(false);
     }
@@ -248,58 +298,100 @@ export default function Login() {
 
             {/* Login Form */}
             <form onSubmit={handleSubmit} noValidate>
-              {/* Username */}
-              <div style={styles.formGroup}>
-                <label htmlFor="username" style={styles.label}>ユーザーID</label>
-                <input
-                  type="text"
-                  id="username"
-                  name="username"
-                  pattern="^[a-zA-Z0-9]{6,}$"
-                  minLength={6}
-                  required
-                  autoComplete="username"
-                  placeholder="英数字6文字以上"
-                  value={username}
-                  onChange={(e) => setUsername(e.target.value)}
-                  onFocus={() => setFocusedInput('username')}
-                  onBlur={() => setFocusedInput(null)}
-                  style={getInputStyle('username')}
-                />
-                <div style={styles.infoMessage}>※ 英数字6文字以上で入力してください</div>
-              </div>
-
-              {/* Password */}
-              <div style={styles.formGroup}>
-                <label htmlFor="password" style={styles.label}>パスワード</label>
-                <div style={styles.passwordField}>
+              {socialTwoFactor ? null : (
+                <>
+                {/* Username */}
+                <div style={styles.formGroup}>
+                  <label htmlFor="username" style={styles.label}>ユーザーID</label>
                   <input
-                    type={showPassword ? 'text' : 'password'}
-                    id="password"
-                    name="password"
+                    type="text"
+                    id="username"
+                    name="username"
+                    pattern="^[a-zA-Z0-9]{6,}$"
                     minLength={6}
                     required
-                    autoComplete="current-password"
+                    autoComplete="username"
                     placeholder="英数字6文字以上"
-                    value={password}
-                    onChange={(e) => setPassword(e.target.value)}
-                    onFocus={() => setFocusedInput('password')}
+                    value={username}
+                    onChange={(e) => setUsername(e.target.value)}
+                    onFocus={() => setFocusedInput('username')}
+                    onBlur={() => setFocusedInput(null)}
+                    style={getInputStyle('username')}
+                  />
+                  <div style={styles.infoMessage}>※ 英数字6文字以上で入力してください</div>
+                </div>
+
+                {/* Password */}
+                <div style={styles.formGroup}>
+                  <label htmlFor="password" style={styles.label}>パスワード</label>
+                  <div style={styles.passwordField}>
+                    <input
+                      type={showPassword ? 'text' : 'password'}
+                      id="password"
+                      name="password"
+                      minLength={6}
+                      required
+                      autoComplete="current-password"
+                      placeholder="英数字6文字以上"
+                      value={password}
+                      onChange={(e) => setPassword(e.target.value)}
+                      onFocus={() => setFocusedInput('password')}
+                      onBlur={() => setFocusedInput(null)}
+                      style={getInputStyle('password')}
+                    />
+                    <button
+                      type="button"
+                      style={styles.toggleVisibility}
+                      onClick={() => setShowPassword(!showPassword)}
+                    >
+                      👁
+                    </button>
+                  </div>
+                  <div style={styles.infoMessage}>※ 英数字6文字以上で入力してください</div>
+                </div>
+                </>
+              )}
+
+              {/* Two-factor Code */}
+              {twoFactorRequired ? (
+                <div style={styles.formGroup}>
+                  <label htmlFor="totpCode" style={styles.label}>認証コード</label>
+                  <input
+                    type="text"
+                    id="totpCode"
+                    name="totpCode"
+                    inputMode="numeric"
+                    autoComplete="one-time-code"
+                    placeholder="6桁のコード"
+                    value={totpCode}
+                    onChange={(e) => setTotpCode(e.target.value)}
+                    onFocus={() => setFocusedInput('totpCode')}
                     onBlur={() => setFocusedInput(null)}
-                    style={getInputStyle('password')}
+                    style={getInputStyle('totpCode')}
                   />
+                  <div style={styles.infoMessage}>
+                    ※ 認証アプリに表示されるコード、またはバックアップコードを入力してください
+                  </div>
+                </div>
+              ) : null}
+
+              {/* Error Message */}
+              {error ? <div style={styles.errorMessage}>{error}</div> : null}
+
+              {/* Deactivated Account */}
+              {deactivated ? (
+                <div style={{ ...styles.infoMessage, marginBottom: '12px' }}>
+                  このアカウントは無効化されています。再開すると削除の予約が取り消されます。
                   <button
                     type="button"
-                    style={styles.toggleVisibility}
-                    onClick={() => setShowPassword(!showPassword)}
+                    disabled={isLoading}
+                    onClick={handleReactivate}
+                    style={{ ...styles.btnLogin, marginTop: '8px' }}
                   >
-                    👁
+                    アカウントを再開する
                   </button>
                 </div>
-                <div style={styles.infoMessage}>※ 英数字6文字以上で入力してください</div>
-              </div>
-
-              {/* Error Message */}
-              {error ? <div style={styles.errorMessage}>{error}</div> : null}
+              ) : null}
 
               {/* Login Button */}
               <button
@@ -322,6 +414,17 @@ export default function Login() {
 
             {/* Social Buttons */}
             <div style={styles.socialButtons}>
+              <a
+                href="/oauth2/authorization/line"
+                style={getSocialBtnStyle('line')}
+                onMouseEnter={() => setHoveredBtn('line')}
+                onMouseLeave={() => setHoveredBtn(null)}
+              >
+                <svg width="20" height="20" viewBox="0 0 24 24" fill="#06C755">
+                  <path d="M12 2C6.48 2 2 5.64 2 10.13c0 4.02 3.57 7.39 8.39 8.03.33.07.77.22.89.5.1.25.07.65.03.9l-.14.86c-.04.25-.2 1 .87.54 1.08-.45 5.8-3.42 7.91-5.85C21.4 13.5 22 11.9 22 10.13 22 5.64 17.52 2 12 2z" />
+                </svg>
+                LINEでログイン
+              </a>
               <a
                 href="/oauth2/authorization/google"
                 style={getSocialBtnStyle('google')}
@@ -351,6 +454,17 @@ export default function Login() {
                 </svg>
                 GitHubでログイン
               </a>
+              <a
+                href="/oauth2/authorization/apple"
+                style={getSocialBtnStyle('apple')}
+                onMouseEnter={() => setHoveredBtn('apple')}
+                onMouseLeave={() => setHoveredBtn(null)}
+              >
+                <svg width="20" height="20" viewBox="0 0 16 16" fill="currentColor">
+                  <path d="M11.18.008c.03.04.19 1.03-.55 2.02-.74.98-1.86.96-1.86.96s-.15-.87.55-1.92C10.02.02 11.15-.03 11.18.008zm2.49 11.09c-.31.7-.47 1.01-.87 1.63-.56.86-1.35 1.93-2.33 1.94-.87.01-1.09-.57-2.27-.56-1.18 0-1.43.57-2.3.56-.98-.01-1.73-.98-2.29-1.84C2.06 10.42 1.9 7.58 2.87 6.07c.69-1.07 1.78-1.7 2.8-1.7 1.04 0 1.7.57 2.56.57.84 0 1.35-.57 2.55-.57.91 0 1.87.5 2.56 1.35-2.25 1.23-1.88 4.44.33 5.38z" />
+                </svg>
+                Appleでログイン
+              </a>
             </div>
 
             {/* Register Link */}
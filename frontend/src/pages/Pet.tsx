@@ -11,6 +11,7 @@ const STAGE_VISUALS: Record<number, { emoji: string; bgColor: string }> = {
   1: { emoji: '🥚', bgColor: 'linear-gradient(135deg, #1a1a2e 0%, #16213e 100%)' },
   2: { emoji: '🐣', bgColor: 'linear-gradient(135deg, #1a1a2e 0%, #0f3460 100%)' },
   3: { emoji: '🐤', bgColor: 'linear-gradient(135deg, #1a1a2e 0%, #533483 100%)' },
+  4: { emoji: '🦅', bgColor: 'linear-gradient(135deg, #1a1a2e 0%, #7b2cbf 100%)' },
 };
 
 // 種類に応じたデフォルト絵文字
@@ -215,6 +216,19 @@ function ActivePetDetail({
   const [newName, setNewName] = useState('');
   const { petAnimation } = useUIStore();
 
+  const { data: evolution } = useQuery({
+    queryKey: ['pet', pet.id, 'evolution', pet.totalExp],
+    queryFn: () => petApi.getEvolutionPreview(pet.id),
+  });
+
+  const evolveMutation = useMutation({
+    mutationFn: () => petApi.evolvePet(pet.id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['barn'] });
+      queryClient.invalidateQueries({ queryKey: ['pet'] });
+    },
+  });
+
   const updateMutation = useMutation({
     mutationFn: (name: string) => petApi.updatePetById(pet.id, { name }),
     onSuccess: () => {
@@ -256,7 +270,13 @@ function ActivePetDetail({
         <div className="left-column" style={{ display: 'flex', flexDirection: 'column', gap: '24px' }}>
           <section className="pet-visual-section settings-card" style={{ ...getBackgroundStyle(pet), border: 'none' }}>
             <div className={`pet-sprite ${shouldAnimate ? moodEffect.animation : ''}`}>
-              {pet.imageUrl ? (
+              {pet.imageLayers.length > 1 ? (
+                <div className="pet-image-layers">
+                  {pet.imageLayers.map((src, i) => (
+                    <img key={src} src={src} alt={i === 0 ? pet.name : ''} className="pet-image" />
+                  ))}
+                </div>
+              ) : pet.imageUrl ? (
                 <img src={pet.imageUrl} alt={pet.name} className="pet-image" />
               ) : (
                 <span className="pet-emoji">{getPetEmoji()}</span>
@@ -380,11 +400,45 @@ function ActivePetDetail({
                   <span className="stage-emoji">{STAGE_VISUALS[3].emoji}</span>
                 )}
                 <span className="stage-name">覚醒</span>
-                <span className="stage-level">Lv.31+</span>
+                <span className="stage-level">Lv.31-50</span>
+              </div>
+              <div className="evolution-arrow">→</div>
+              <div className={`evolution-stage stage-4 ${pet.stage >= 4 ? 'active' : ''} ${pet.stage === 4 ? 'current' : ''}`}>
+                {pet.branch?.imagePath ? (
+                  <img src={pet.branch.imagePath} alt={pet.branch.name} className="stage-image" />
+                ) : (
+                  <span className="stage-emoji">{STAGE_VISUALS[4].emoji}</span>
+                )}
+                <span className="stage-name">{pet.branch?.name ?? '進化'}</span>
+                <span className="stage-level">Lv.51+</span>
               </div>
             </div>
+            {pet.canEvolve && (
+              <button
+                className="btn btn-primary evolution-evolve-button"
+                onClick={() => evolveMutation.mutate()}
+                disabled={evolveMutation.isPending}
+              >
+                {evolveMutation.isPending ? '進化中...' : '進化させる'}
+              </button>
+            )}
+            {evolution?.nextStage && (
+              <div className="evolution-next">
+                <span className="evolution-next-label">
+                  {evolution.nextStage.stageName}まであと {evolution.nextStage.remainingExp.toLocaleString()} EXP
+                  （Lv.{evolution.nextStage.requiredLevel}）
+                </span>
+                <div className="exp-bar">
+                  <div
+                    className="exp-bar-fill"
+                    style={{ width: `${Math.round(evolution.stageProgress * 100)}%` }}
+                  />
+                </div>
+              </div>
+            )}
             <p className="evolution-hint">
               トレーニングを続けてレベルを上げると、パートナーが進化します！
+              最後の進化は、いちばん鍛えた部位によって姿が変わります。
             </p>
           </section>
 
@@ -453,12 +507,27 @@ function BarnView({ barnData }: { barnData: BarnResponse }) {
     },
   });
 
+  const backgroundMutation = useMutation({
+    mutationFn: petApi.setBarnBackground,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['barn'] });
+    },
+  });
+
   const handleCreatePet = (petTypeId: number) => {
     setCreatingTypeId(petTypeId);
     createMutation.mutate({ petTypeId });
   };
 
-  const { activePet, ownedPets, unlockedTypes, lockedTypes } = barnData;
+  const { activePet, ownedPets, unlockedTypes, lockedTypes, selectedBackground, backgrounds } =
+    barnData;
+  const barnSceneStyle: React.CSSProperties | undefined = selectedBackground.imagePath
+    ? {
+        backgroundImage: `url(${selectedBackground.imagePath})`,
+        backgroundSize: 'cover',
+        backgroundPosition: 'center',
+      }
+    : undefined;
 
   return (
     <div className="settings-page">
@@ -480,7 +549,7 @@ function BarnView({ barnData }: { barnData: BarnResponse }) {
       )}
 
       {/* パートナー小屋カード */}
-      <section className="settings-card barn-card">
+      <section className="settings-card barn-card" style={barnSceneStyle}>
         {/* タブナビゲーション */}
         <div className="barn-tabs">
           <button 
@@ -562,6 +631,31 @@ function BarnView({ barnData }: { barnData: BarnResponse }) {
         </div>
       </section>
 
+      {/* 背景シーン（ジムチェックインで解放） */}
+      <section className="settings-card barn-card">
+        <h2 className="barn-section-title">小屋の背景</h2>
+        <div className="barn-tabs">
+          {backgrounds.map(bg => (
+            <button
+              key={bg.code}
+              className={`barn-tab ${bg.code === selectedBackground.code ? 'active' : ''}`}
+              onClick={() => backgroundMutation.mutate(bg.code)}
+              disabled={!bg.unlocked || backgroundMutation.isPending}
+              title={bg.unlocked ? bg.description ?? undefined : bg.unlockProgress}
+            >
+              {bg.unlocked ? bg.name : `🔒 ${bg.name}`}
+            </button>
+          ))}
+        </div>
+        {backgrounds
+          .filter(bg => !bg.unlocked)
+          .map(bg => (
+            <p key={bg.code} className="barn-empty-hint">
+              {bg.name}: {bg.unlockProgress}
+            </p>
+          ))}
+      </section>
+
       {createMutation.isError && (
         <div className="pet-create-error">
           <p>パートナーの入手に失敗しました。もう一度お試しください。</p>
@@ -177,6 +177,7 @@ export default function Profile() {
   const [error, setError] = useState('');
   const [isLoading, setIsLoading] = useState(false);
   const [isCheckingStatus, setIsCheckingStatus] = useState(true);
+  const [expiresAt, setExpiresAt] = useState<Date | null>(null);
   const [focusedInput, setFocusedInput] = useState<string | null>(null);
   const [hoveredBtn, setHoveredBtn] = useState<string | null>(null);
 
@@ -187,7 +188,18 @@ export default function Profile() {
         const status = await checkRegistrationStatus();
         if (!status.hasPendingRegistration) {
           // No pending registration, redirect to register
+          if (status.expired) {
+            window.alert('登録の有効期限が切れました。お手数ですが最初からやり直してください。');
+          }
           navigate('/register', { replace: true });
+          return;
+        }
+        if (status.expiresAt) {
+          setExpiresAt(new Date(status.expiresAt));
+        }
+        if (status.suggestedDisplayName) {
+          // OAuth registration: prefill the name given by the provider
+          setDisplayName(status.suggestedDisplayName.slice(0, 20));
         }
       } catch {
         // Error checking status, redirect to register
@@ -263,8 +275,12 @@ export default function Profile() {
       window.onbeforeunload = null;
       navigate('/dashboard');
     } catch (err: unknown) {
-      const error = err as { response?: { data?: { error?: string } } };
-      if (error.response?.data?.error) {
+      const error = err as { response?: { data?: { error?: string; code?: string } } };
+      if (error.response?.data?.code === 'REGISTRATION_EXPIRED') {
+        window.alert(error.response.data.error);
+        window.onbeforeunload = null;
+        navigate('/register', { replace: true });
+      } else if (error.response?.data?.error) {
         setError(error.response.data.error);
       } else {
         setError('プロフィールの保存に失敗しました');
@@ -347,6 +363,12 @@ export default function Profile() {
       <div style={styles.card}>
         <p style={styles.title}>プロフィールを仕上げましょう</p>
         <p style={styles.subtitle}>ダッシュボードで表示されるユーザー名や基本情報を設定します。</p>
+        {expiresAt && (
+          <p style={styles.helper}>
+            {expiresAt.toLocaleTimeString('ja-JP', { hour: '2-digit', minute: '2-digit' })}
+            までに登録を完了してください。
+          </p>
+        )}
 
         <form onSubmit={handleSubmit} noValidate>
           {/* Display Name */}
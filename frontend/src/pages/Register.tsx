@@ -1,6 +1,7 @@
 import { useState } from 'react';
 import { Link, useNavigate } from 'react-router-dom';
 import { register } from '../services/authApi';
+import CaptchaWidget from '../components/common/CaptchaWidget';
 
 // Inline styles matching the original register.html
 const styles = {
@@ -132,6 +133,10 @@ export default function Register() {
   const [isLoading, setIsLoading] = useState(false);
   const [focusedInput, setFocusedInput] = useState<string | null>(null);
   const [hoveredBtn, setHoveredBtn] = useState<string | null>(null);
+  const [captchaEnabled, setCaptchaEnabled] = useState(false);
+  const [captchaToken, setCaptchaToken] = useState<string | null>(null);
+  // トークンは1回しか使えないため、登録に失敗したらウィジェットを作り直す
+  const [captchaKey, setCaptchaKey] = useState(0);
 
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
@@ -143,15 +148,20 @@ export default function Register() {
       return;
     }
 
-    if (password.length < 6) {
-      setError('パスワードは6文字以上で入力してください');
+    if (password.length < 8) {
+      setError('パスワードは8文字以上で入力してください');
+      return;
+    }
+
+    if (captchaEnabled && !captchaToken) {
+      setError('「私はロボットではありません」にチェックしてください');
       return;
     }
 
     setIsLoading(true);
 
     try {
-      await register(loginId, password, confirmPassword);
+      await register(loginId, password, confirmPassword, captchaToken ?? undefined);
       // セッションに一時保存されたので/profileに遷移（まだユーザーは作成されていない）
       navigate('/profile');
     } catch (err: unknown) {
@@ -163,6 +173,8 @@ export default function Register() {
       } else {
         setError('登録に失敗しました。別のログインIDをお試しください');
       }
+      setCaptchaToken(null);
+      setCaptchaKey((key) => key + 1);
     } finally {
       setIsLoading(false);
     }
@@ -225,9 +237,9 @@ export default function Register() {
                 id="password"
                 name="password"
                 type={showPassword ? 'text' : 'password'}
-                minLength={6}
+                minLength={8}
                 required
-                placeholder="英数字6文字以上"
+                placeholder="8文字以上"
                 value={password}
                 onChange={(e) => setPassword(e.target.value)}
                 onFocus={() => setFocusedInput('password')}
@@ -244,7 +256,7 @@ export default function Register() {
                 {showPassword ? <EyeOffIcon /> : <EyeIcon />}
               </button>
             </div>
-            <div style={styles.helper}>英数字6文字以上。大文字小文字を区別します。</div>
+            <div style={styles.helper}>8文字以上で、英小文字・英大文字・数字・記号のうち2種類以上を組み合わせてください。</div>
           </div>
 
           {/* Confirm Password */}
@@ -255,7 +267,7 @@ export default function Register() {
                 id="confirmPassword"
                 name="confirmPassword"
                 type={showConfirmPassword ? 'text' : 'password'}
-                minLength={6}
+                minLength={8}
                 required
                 placeholder="もう一度入力してください"
                 value={confirmPassword}
@@ -279,6 +291,12 @@ export default function Register() {
           {/* Error Message */}
           {error ? <div style={styles.errorMessage}>{error}</div> : null}
 
+          <CaptchaWidget
+            key={captchaKey}
+            onChange={setCaptchaToken}
+            onAvailabilityChange={setCaptchaEnabled}
+          />
+
           {/* Submit Button */}
           <button
             type="submit"
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { useNavigate } from 'react-router-dom';
+import { useNavigate, useSearchParams } from 'react-router-dom';
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import {
     DndContext,
@@ -19,7 +19,12 @@ import {
     verticalListSortingStrategy,
 } from '@dnd-kit/sortable';
 import { CSS } from '@dnd-kit/utilities';
-import streakApi from '../services/streakApi';
+import streakApi, {
+    type Locale,
+    type NotificationPreferences,
+    type PrivacySettings,
+} from '../services/streakApi';
+import type { HeatmapMode, WeightUnit } from '../types';
 import { useUIStore } from '../stores/uiStore';
 import { navItems, isDeveloper, isSpecialAdmin, type NavItem } from '../config/navItems';
 import { useAuthStore } from '../stores/authStore';
@@ -86,9 +91,15 @@ function SortableNavItem({
     );
 }
 
+// 選択できるタイムゾーン（ブラウザが対応するIANA名）
+const TIMEZONE_OPTIONS: string[] = Intl.supportedValuesOf('timeZone');
+// この端末のタイムゾーン
+const DEVICE_TIMEZONE = Intl.DateTimeFormat().resolvedOptions().timeZone;
+
 export default function Settings() {
     const queryClient = useQueryClient();
     const navigate = useNavigate();
+    const [searchParams, setSearchParams] = useSearchParams();
     const { user } = useAuthStore();
     const {
         showToast,
@@ -104,6 +115,9 @@ export default function Settings() {
     } = useUIStore();
 
     const [graceDays, setGraceDays] = useState(1);
+    const [heatmapMode, setHeatmapMode] = useState<HeatmapMode>('ADAPTIVE');
+    const [weightUnit, setWeightUnit] = useState<WeightUnit>('kg');
+    const [timezone, setTimezone] = useState('Asia/Tokyo');
     const [localNavOrder, setLocalNavOrder] = useState<string[]>([]);
 
     // dnd-kit センサー設定（PC + モバイル対応）
@@ -124,6 +138,21 @@ export default function Settings() {
         })
     );
 
+    // メールの確認リンクから戻ってきた場合は結果を表示する
+    useEffect(() => {
+        const result = searchParams.get('emailVerification');
+        if (!result) return;
+        if (result === 'verified') {
+            showToast('メールアドレスの確認が完了しました', 'success');
+            queryClient.invalidateQueries({ queryKey: ['userInfo'] });
+        } else if (result === 'taken') {
+            showToast('このメールアドレスは既に使用されています', 'error');
+        } else {
+            showToast('確認リンクが無効か、有効期限が切れています', 'error');
+        }
+        setSearchParams({}, { replace: true });
+    }, [searchParams, setSearchParams, showToast, queryClient]);
+
     // 1. ゲーム設定（ストリーク）の取得
     const { data: settingsData } = useQuery({
         queryKey: ['userSettings'],
@@ -134,24 +163,95 @@ export default function Settings() {
     useEffect(() => {
         if (settingsData) {
             setGraceDays(settingsData.graceDaysAllowed);
+            setHeatmapMode(settingsData.heatmapMode ?? 'ADAPTIVE');
+            setWeightUnit(settingsData.weightUnit ?? 'kg');
+            setTimezone(settingsData.timezone ?? 'Asia/Tokyo');
         }
     }, [settingsData]);
 
     // ストリーク設定更新Mutation
     const updateSettingsMutation = useMutation({
-        mutationFn: (days: number) => streakApi.updateSettings(days),
+        mutationFn: (vars: {
+            days: number;
+            heatmapMode: HeatmapMode;
+            weightUnit: WeightUnit;
+            timezone: string;
+        }) => streakApi.updateSettings(vars.days, vars.heatmapMode, vars.weightUnit, vars.timezone),
         onSuccess: (_, variables) => {
             showToast('トレーニング設定を保存しました', 'success');
             queryClient.invalidateQueries({ queryKey: ['userSettings'] });
-            // ダッシュボードの表示更新のためストリーク情報も再取得
+            // ダッシュボードの表示更新のためストリーク情報・ヒートマップも再取得
             queryClient.invalidateQueries({ queryKey: ['streaks'] });
-            setGraceDays(variables);
+            queryClient.invalidateQueries({ queryKey: ['heatmap'] });
+            // 「今日」の境界が変わるためデイリーリワードも再取得
+            queryClient.invalidateQueries({ queryKey: ['dailyRewards'] });
+            setGraceDays(variables.days);
+            setHeatmapMode(variables.heatmapMode);
+            setWeightUnit(variables.weightUnit);
+            setTimezone(variables.timezone);
+        },
+        onError: () => {
+            showToast('設定の保存に失敗しました', 'error');
+        },
+    });
+
+    // プライバシー設定（公開プロフィール）
+    const { data: privacySettings } = useQuery({
+        queryKey: ['privacySettings'],
+        queryFn: streakApi.getPrivacySettings,
+    });
+
+    const updatePrivacyMutation = useMutation({
+        mutationFn: streakApi.updatePrivacySettings,
+        onSuccess: (data) => {
+            queryClient.setQueryData(['privacySettings'], data);
+        },
+        onError: () => {
+            showToast('プライバシー設定の保存に失敗しました', 'error');
+        },
+    });
+
+    const togglePrivacy = (key: keyof PrivacySettings) => {
+        if (!privacySettings) return;
+        updatePrivacyMutation.mutate({ ...privacySettings, [key]: !privacySettings[key] });
+    };
+
+    const privacyItems: { key: keyof PrivacySettings; label: string }[] = [
+        { key: 'showLevel', label: 'レベル' },
+        { key: 'showStreak', label: 'トレーニングストリーク' },
+        { key: 'showPet', label: 'パートナー' },
+        { key: 'showTotalVolume', label: '累計ボリューム' },
+    ];
+
+    // 表示言語・通知設定
+    const { data: preferences } = useQuery({
+        queryKey: ['preferences'],
+        queryFn: streakApi.getPreferences,
+    });
+
+    const updatePreferencesMutation = useMutation({
+        mutationFn: streakApi.updatePreferences,
+        onSuccess: (data) => {
+            queryClient.setQueryData(['preferences'], data);
         },
         onError: () => {
             showToast('設定の保存に失敗しました', 'error');
         },
     });
 
+    const toggleNotification = (key: keyof NotificationPreferences) => {
+        if (!preferences) return;
+        updatePreferencesMutation.mutate({
+            notifications: { ...preferences.notifications, [key]: !preferences.notifications[key] },
+        });
+    };
+
+    const notificationItems: { key: keyof NotificationPreferences; label: string }[] = [
+        { key: 'workoutComments', label: 'トレーニング記録へのコメント' },
+        { key: 'streakReminder', label: 'ストリークのリマインダー' },
+        { key: 'weeklySummary', label: '週間のまとめ' },
+    ];
+
     // 2. ナビゲーション設定の初期化
     // navItemsの全項目を、navOrderの順序でソートしてローカルステートにセット
     useEffect(() => {
@@ -241,15 +341,112 @@ export default function Settings() {
                             </select>
                         </div>
 
+                        <div className="settings-form-group">
+                            <label className="settings-label">ヒートマップの色の基準</label>
+                            <p className="settings-description">
+                                ダッシュボードのヒートマップで色の濃さを決めるボリュームの基準を設定します。
+                            </p>
+                            <select
+                                className="settings-select"
+                                value={heatmapMode}
+                                onChange={(e) => setHeatmapMode(e.target.value as HeatmapMode)}
+                            >
+                                <option value="ADAPTIVE">自分の記録に合わせる（おすすめ）</option>
+                                <option value="FIXED">固定（1,000 / 2,500 / 5,000kg）</option>
+                            </select>
+                        </div>
+
+                        <div className="settings-form-group">
+                            <label className="settings-label">重量の単位</label>
+                            <p className="settings-description">
+                                トレーニング履歴のエクスポートや、APIで単位を指定せずに記録を保存するときの重量の単位です。
+                            </p>
+                            <select
+                                className="settings-select"
+                                value={weightUnit}
+                                onChange={(e) => setWeightUnit(e.target.value as WeightUnit)}
+                            >
+                                <option value="kg">kg</option>
+                                <option value="lb">lb（ポンド）</option>
+                            </select>
+                        </div>
+
+                        <div className="settings-form-group">
+                            <label className="settings-label">タイムゾーン</label>
+                            <p className="settings-description">
+                                記録日・ストリーク・デイリーリワードの「今日」を判定するタイムゾーンです（この端末: {DEVICE_TIMEZONE}）。
+                            </p>
+                            <select
+                                className="settings-select"
+                                value={timezone}
+                                onChange={(e) => setTimezone(e.target.value)}
+                            >
+                                {(TIMEZONE_OPTIONS.includes(timezone)
+                                    ? TIMEZONE_OPTIONS
+                                    : [timezone, ...TIMEZONE_OPTIONS]
+                                ).map((tz) => (
+                                    <option key={tz} value={tz}>{tz}</option>
+                                ))}
+                            </select>
+                        </div>
+
                         <button
                             className="settings-save-btn"
-                            onClick={() => updateSettingsMutation.mutate(graceDays)}
-                            disabled={updateSettingsMutation.isPending || (settingsData && settingsData.graceDaysAllowed === graceDays)}
+                            onClick={() => updateSettingsMutation.mutate({ days: graceDays, heatmapMode, weightUnit, timezone })}
+                            disabled={
+                                updateSettingsMutation.isPending ||
+                                (settingsData &&
+                                    settingsData.graceDaysAllowed === graceDays &&
+                                    settingsData.heatmapMode === heatmapMode &&
+                                    settingsData.weightUnit === weightUnit &&
+                                    settingsData.timezone === timezone)
+                            }
                         >
                             {updateSettingsMutation.isPending ? '保存中...' : '設定を保存'}
                         </button>
                     </div>
 
+                    {/* カード: 表示言語・通知 */}
+                    <div className="settings-card">
+                        <div className="settings-card-header">
+                            <span className="settings-card-icon">🔔</span>
+                            <h2 className="settings-card-title">言語・通知</h2>
+                        </div>
+
+                        <div className="settings-form-group">
+                            <label className="settings-label">表示言語</label>
+                            <select
+                                className="settings-select"
+                                value={preferences?.locale ?? 'ja'}
+                                disabled={!preferences || updatePreferencesMutation.isPending}
+                                onChange={(e) =>
+                                    updatePreferencesMutation.mutate({ locale: e.target.value as Locale })
+                                }
+                            >
+                                <option value="ja">日本語</option>
+                                <option value="en">English</option>
+                            </select>
+                        </div>
+
+                        {notificationItems.map(item => (
+                            <div className="settings-form-group" key={item.key}>
+                                <label className="settings-toggle-row">
+                                    <span className="settings-toggle-label">{item.label}</span>
+                                    <label className="nav-visibility-toggle">
+                                        <input
+                                            type="checkbox"
+                                            className="nav-visibility-chk"
+                                            checked={preferences?.notifications[item.key] ?? false}
+                                            disabled={!preferences || updatePreferencesMutation.isPending}
+                                            onChange={() => toggleNotification(item.key)}
+                                        />
+                                        <span className="nav-visibility-slider"></span>
+                                    </label>
+                                </label>
+                            </div>
+                        ))}
+                    </div>
+
                     {/* カード 2: パフォーマンス設定 */}
                     <div className="settings-card">
                         <div className="settings-card-header">
@@ -294,6 +491,76 @@ export default function Settings() {
                         </div>
                     </div>
 
+                    {/* カード: 公開プロフィール */}
+                    <div className="settings-card">
+                        <div className="settings-card-header">
+                            <span className="settings-card-icon">🔗</span>
+                            <h2 className="settings-card-title">公開プロフィール</h2>
+                        </div>
+
+                        <div className="settings-form-group">
+                            <label className="settings-toggle-row">
+                                <span className="settings-toggle-label">プロフィールを公開</span>
+                                <label className="nav-visibility-toggle">
+                                    <input
+                                        type="checkbox"
+                                        className="nav-visibility-chk"
+                                        checked={privacySettings?.profilePublic ?? false}
+                                        disabled={!privacySettings || updatePrivacyMutation.isPending}
+                                        onChange={() => togglePrivacy('profilePublic')}
+                                    />
+                                    <span className="nav-visibility-slider"></span>
+                                </label>
+                            </label>
+                            <p className="settings-description">
+                                ONにすると、ログインIDを知っている人が選んだ項目を閲覧できます
+                                {user?.loginId && (
+                                    <>
+                                        <br />
+                                        <code>/api/users/{user.loginId}/profile</code>
+                                    </>
+                                )}
+                            </p>
+                        </div>
+
+                        {privacySettings?.profilePublic && privacyItems.map(item => (
+                            <div className="settings-form-group" key={item.key}>
+                                <label className="settings-toggle-row">
+                                    <span className="settings-toggle-label">{item.label}を公開</span>
+                                    <label className="nav-visibility-toggle">
+                                        <input
+                                            type="checkbox"
+                                            className="nav-visibility-chk"
+                                            checked={privacySettings[item.key]}
+                                            disabled={updatePrivacyMutation.isPending}
+                                            onChange={() => togglePrivacy(item.key)}
+                                        />
+                                        <span className="nav-visibility-slider"></span>
+                                    </label>
+                                </label>
+                            </div>
+                        ))}
+
+                        <div className="settings-form-group">
+                            <label className="settings-toggle-row">
+                                <span className="settings-toggle-label">ランキングに参加</span>
+                                <label className="nav-visibility-toggle">
+                                    <input
+                                        type="checkbox"
+                                        className="nav-visibility-chk"
+                                        checked={privacySettings?.showOnLeaderboard ?? false}
+                                        disabled={!privacySettings || updatePrivacyMutation.isPending}
+                                        onChange={() => togglePrivacy('showOnLeaderboard')}
+                                    />
+                                    <span className="nav-visibility-slider"></span>
+                                </label>
+                            </label>
+                            <p className="settings-description">
+                                ONにすると、週間EXP・週間ボリューム・最長ストリークのランキングに表示名が載ります
+                            </p>
+                        </div>
+                    </div>
+
                     {/* カード 3: お問い合わせ */}
                     <div className="settings-card">
                         <div className="settings-card-header">
@@ -0,0 +1,43 @@
+import api from './api';
+
+export type AchievementConditionType =
+  | 'TOTAL_WORKOUTS'
+  | 'SESSION_VOLUME'
+  | 'TRAINING_STREAK'
+  | 'LOGIN_STREAK'
+  | 'PET_LEVEL';
+
+// 新たに達成した実績（記録の保存・ログインボーナスのレスポンスに含まれる）
+export interface NewAchievement {
+  code: string;
+  name: string;
+  description: string | null;
+  iconPath: string | null;
+}
+
+// 実績と進捗（currentは目標値で頭打ち）
+export interface Achievement extends NewAchievement {
+  conditionType: AchievementConditionType;
+  achieved: boolean;
+  achievedAt: string | null;
+  current: number;
+  target: number;
+}
+
+export interface AchievementsResponse {
+  achievedCount: number;
+  totalCount: number;
+  achievements: Achievement[];
+}
+
+const achievementApi = {
+  /**
+   * 実績の一覧（進捗を含む）
+   */
+  getAchievements: async (): Promise<AchievementsResponse> => {
+    const response = await api.get<AchievementsResponse>('/api/achievements');
+    return response.data;
+  },
+};
+
+export default achievementApi;
@@ -1,5 +1,16 @@
 import api from './api';
-import type { AdminUser, UpdateLevelResponse } from '../types/admin';
+import type {
+  AdminDailyReward,
+  AdminDailyRewardRequest,
+  AdminUser,
+  ExpConfig,
+  LevelCurve,
+  LevelCurveMigrationStrategy,
+  MigrateLevelCurveResponse,
+  PetExpConfig,
+  UpdateLevelResponse,
+  UpdatePetExpConfigResponse,
+} from '../types/admin';
 
 /**
  * 管理者用ユーザー一覧を取得
@@ -21,3 +32,95 @@ export const updateUserLevel = async (
   const response = await api.put(`/api/admin/users/${userId}/level`, { level });
   return response.data;
 };
+
+/**
+ * レベル曲線の一覧を取得
+ */
+export const getLevelCurves = async (): Promise<LevelCurve[]> => {
+  const response = await api.get('/api/admin/level-curves');
+  return response.data;
+};
+
+/**
+ * 新しいレベル曲線を作成して有効化
+ * 既存ユーザーはmigrateLevelCurveを実行するまで旧曲線の値のまま
+ */
+export const createLevelCurve = async (
+  quadratic: number,
+  linear: number,
+  constant: number
+): Promise<LevelCurve> => {
+  const response = await api.post('/api/admin/level-curves', { quadratic, linear, constant });
+  return response.data;
+};
+
+/**
+ * 旧曲線のユーザーを現在の曲線へ移行
+ * @param strategy KEEP_EXP: 累計EXPを維持 / KEEP_LEVEL: レベルと進行度を維持
+ */
+export const migrateLevelCurve = async (
+  strategy: LevelCurveMigrationStrategy
+): Promise<MigrateLevelCurveResponse> => {
+  const response = await api.post('/api/admin/level-curves/migrate', { strategy });
+  return response.data;
+};
+
+/**
+ * 現在のEXP設定を取得
+ */
+export const getExpConfig = async (): Promise<ExpConfig> => {
+  const response = await api.get('/api/admin/exp-config');
+  return response.data;
+};
+
+/**
+ * EXP設定を更新（全インスタンスに即時反映）
+ */
+export const updateExpConfig = async (config: ExpConfig): Promise<ExpConfig> => {
+  const response = await api.put('/api/admin/exp-config', config);
+  return response.data;
+};
+
+/**
+ * 現在のペットの成長曲線を取得
+ */
+export const getPetExpConfig = async (): Promise<PetExpConfig> => {
+  const response = await api.get('/api/admin/pet-exp-config');
+  return response.data;
+};
+
+/**
+ * ペットの成長曲線を更新（保存済みのペットのレベルも再計算し、全インスタンスに即時反映）
+ */
+export const updatePetExpConfig = async (
+  config: PetExpConfig
+): Promise<UpdatePetExpConfigResponse> => {
+  const response = await api.put('/api/admin/pet-exp-config', config);
+  return response.data;
+};
+
+/**
+ * デイリーリワードの報酬一覧を取得
+ */
+export const getDailyRewardConfig = async (): Promise<AdminDailyReward[]> => {
+  const response = await api.get('/api/admin/daily-rewards');
+  return response.data;
+};
+
+/**
+ * デイリーリワードの報酬を登録・更新
+ * @param day 既存の日か最終日の翌日
+ */
+export const upsertDailyRewardConfig = async (
+  day: number,
+  reward: AdminDailyRewardRequest
+): Promise<void> => {
+  await api.put(`/api/admin/daily-rewards/${day}`, reward);
+};
+
+/**
+ * デイリーリワードの最終日を削除
+ */
+export const deleteDailyRewardConfig = async (day: number): Promise<void> => {
+  await api.delete(`/api/admin/daily-rewards/${day}`);
+};
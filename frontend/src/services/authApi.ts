@@ -1,5 +1,12 @@
 import api, { getCookie } from './api';
-import type { User, UpdateDisplayNameRequest, UpdatePasswordRequest } from '../types';
+import type {
+  User,
+  UpdateDisplayNameRequest,
+  UpdateLoginIdRequest,
+  UpdateBodyWeightRequest,
+  UpdatePasswordRequest,
+  UpdateEmailRequest,
+} from '../types';
 
 // ユーザー情報取得
 export const getUserInfo = async (): Promise<User> => {
@@ -16,18 +23,230 @@ export const updateDisplayName = async (data: UpdateDisplayNameRequest): Promise
   await api.put('/api/user/display-name', data);
 };
 
+// ログインID変更（30日に1回まで）
+export const updateLoginId = async (data: UpdateLoginIdRequest): Promise<void> => {
+  await api.put('/api/user/login-id', data);
+};
+
+// プロフィール画像のアップロード（サーバーで正方形に切り抜き、サイズ別に保存される）
+export interface ProfileImageUpload {
+  profileImageUrl: string;
+  variants: { size: number; url: string }[];
+}
+
+export const uploadProfileImage = async (file: File): Promise<ProfileImageUpload> => {
+  const formData = new FormData();
+  formData.append('image', file);
+  const response = await api.post('/api/user/profile-image', formData, {
+    headers: { 'Content-Type': 'multipart/form-data' },
+  });
+  return response.data;
+};
+
+// 体重更新（自重種目のEXPは体重 + 追加重量で計算される）
+export const updateBodyWeight = async (data: UpdateBodyWeightRequest): Promise<void> => {
+  await api.put('/api/user/body-weight', data);
+};
+
 // パスワード更新
 export const updatePassword = async (data: UpdatePasswordRequest): Promise<void> => {
   await api.put('/api/user/password', data);
 };
 
-// アカウント削除
-export const deleteAccount = async (): Promise<void> => {
-  await api.delete('/api/user/account');
+// メールアドレス変更（確認メールのリンクを開くまでは変更前のメールアドレスのまま）
+export const updateEmail = async (data: UpdateEmailRequest): Promise<void> => {
+  await api.put('/api/user/email', data);
+};
+
+// 確認メールの再送信
+export const resendEmailVerification = async (): Promise<void> => {
+  await api.post('/api/user/email/resend');
+};
+
+// 二段階認証
+export interface TwoFactorStatus {
+  enabled: boolean;
+  backupCodesRemaining: number;
+}
+
+export interface TwoFactorSetup {
+  secret: string;
+  otpauthUri: string;
+}
+
+export interface TwoFactorBackupCodes {
+  enabled: boolean;
+  backupCodes: string[];
+}
+
+// 二段階認証の設定状況
+export const getTwoFactorStatus = async (): Promise<TwoFactorStatus> => {
+  const response = await api.get('/api/auth/2fa');
+  return response.data;
+};
+
+// 二段階認証の設定開始（認証アプリに登録するシークレットを発行）
+export const setupTwoFactor = async (): Promise<TwoFactorSetup> => {
+  const response = await api.post('/api/auth/2fa/setup');
+  return response.data;
+};
+
+// 認証アプリのコードを確認して二段階認証を有効化（バックアップコードが返る）
+export const verifyTwoFactor = async (code: string): Promise<TwoFactorBackupCodes> => {
+  const response = await api.post('/api/auth/2fa/verify', { code });
+  return response.data;
+};
+
+// バックアップコードの再発行
+export const regenerateBackupCodes = async (code: string): Promise<TwoFactorBackupCodes> => {
+  const response = await api.post('/api/auth/2fa/backup-codes', { code });
+  return response.data;
+};
+
+// 二段階認証の無効化
+export const disableTwoFactor = async (password: string, code: string): Promise<void> => {
+  await api.post('/api/auth/2fa/disable', { password, code });
+};
+
+// APIキー（スクリプトからのアクセス用）
+export type ApiKeyScope = 'read' | 'workout:write';
+
+export interface ApiKey {
+  id: number;
+  name: string;
+  keyPrefix: string;
+  scopes: ApiKeyScope[];
+  lastUsedAt: string | null;
+  createdAt: string;
+}
+
+export interface CreatedApiKey extends ApiKey {
+  /** 作成時にのみ返るAPIキー */
+  key: string;
+}
+
+// APIキーの一覧
+export const getApiKeys = async (): Promise<ApiKey[]> => {
+  const response = await api.get('/api/user/api-keys');
+  return response.data;
+};
+
+// APIキーの発行
+export const createApiKey = async (name: string, scopes: ApiKeyScope[]): Promise<CreatedApiKey> => {
+  const response = await api.post('/api/user/api-keys', { name, scopes });
+  return response.data;
+};
+
+// APIキーの無効化
+export const revokeApiKey = async (id: number): Promise<void> => {
+  await api.delete(`/api/user/api-keys/${id}`);
+};
+
+// ログイン履歴
+export interface LoginActivity {
+  id: number;
+  success: boolean;
+  /** LOCAL（パスワード）またはOAuthプロバイダー */
+  provider: string;
+  /** web（ブラウザ）/ token（アプリ） */
+  client: 'web' | 'token';
+  failureReason:
+    | 'invalid_credentials'
+    | 'two_factor_required'
+    | 'two_factor_failed'
+    | 'account_deactivated'
+    | null;
+  ipAddress: string | null;
+  userAgent: string | null;
+  device: string;
+  createdAt: string;
+}
+
+// 直近のログイン履歴（失敗した試行を含む）
+export const getLoginActivity = async (limit = 50): Promise<LoginActivity[]> => {
+  const response = await api.get('/api/user/security/activity', { params: { limit } });
+  return response.data;
+};
+
+// 退会リクエストの状態
+export interface AccountDeletionStatus {
+  status: 'NONE' | 'AWAITING_CONFIRMATION' | 'SCHEDULED';
+  requestedAt?: string | null;
+  tokenExpiresAt?: string | null;
+  scheduledDeletionAt?: string | null;
+}
+
+export interface AccountDeletionResult {
+  status: 'AWAITING_CONFIRMATION' | 'SCHEDULED';
+  emailSent: boolean;
+}
+
+// アカウント削除（退会リクエスト）
+// メールアドレス登録済みの場合は確認メールが送信され、リンクを開くと30日後の削除が確定する
+export const deleteAccount = async (password?: string): Promise<AccountDeletionResult> => {
+  const response = await api.delete('/api/user/account', {
+    data: password ? { password } : undefined,
+  });
+  return response.data;
+};
+
+// 退会リクエストの状態取得
+export const getAccountDeletionStatus = async (): Promise<AccountDeletionStatus> => {
+  const response = await api.get('/api/user/account/deletion');
+  return response.data;
+};
+
+// 退会リクエストの取り消し
+export const cancelAccountDeletion = async (): Promise<void> => {
+  await api.post('/api/user/account/deletion/cancel');
+};
+
+// アカウントの無効化（ログインできなくなり、30日後に削除される）
+export const deactivateAccount = async (password?: string): Promise<void> => {
+  await api.post('/api/user/account/deactivate', password ? { password } : {});
+};
+
+// 無効化したアカウントの再開（無効化中のログインに成功した直後のみ）
+export const reactivateAccount = async (): Promise<void> => {
+  await api.post('/api/user/account/reactivate');
+};
+
+// アカウントデータのエクスポート
+export interface AccountExport {
+  id: number;
+  status: 'PENDING' | 'PROCESSING' | 'COMPLETED' | 'FAILED';
+  requestedAt: string;
+  completedAt: string | null;
+  expiresAt: string | null;
+  fileSize: number | null;
+  downloadUrl: string | null;
+  errorMessage: string | null;
+}
+
+// エクスポートの作成を開始（作成中のものがあればそれを返す）
+export const requestAccountExport = async (): Promise<AccountExport> => {
+  const response = await api.get('/api/user/export');
+  return response.data;
 };
 
-// ログイン
-export const login = async (username: string, password: string): Promise<void> => {
+// エクスポートの作成状況
+export const getAccountExport = async (id: number): Promise<AccountExport> => {
+  const response = await api.get(`/api/user/export/${id}`);
+  return response.data;
+};
+
+// 作成したZIPファイルのダウンロード
+export const downloadAccountExport = async (id: number): Promise<Blob> => {
+  const response = await api.get(`/api/user/export/${id}/download`, { responseType: 'blob' });
+  return response.data;
+};
+
+// ログイン（二段階認証が有効な場合は認証アプリのコードまたはバックアップコードも送る）
+export const login = async (
+  username: string,
+  password: string,
+  totpCode?: string
+): Promise<void> => {
   // CSRFトークンを取得するために、まずCSRFエンドポイントを呼び出す
   // (/loginへのGETはSpring SecurityのログインページとしてThymeleafを期待するため使用しない)
   await api.get('/api/csrf');
@@ -38,6 +257,7 @@ export const login = async (username: string, password: string): Promise<void> =
   const params = new URLSearchParams();
   params.append('username', username);
   params.append('password', password);
+  if (totpCode) params.append('totpCode', totpCode);
   
   await api.post('/login', params, {
     headers: {
@@ -47,6 +267,21 @@ export const login = async (username: string, password: string): Promise<void> =
   });
 };
 
+// ソーシャルログインの二段階認証（認証アプリのコードまたはバックアップコード）
+export const loginTwoFactor = async (totpCode: string): Promise<void> => {
+  const csrfToken = getCookie('XSRF-TOKEN');
+
+  const params = new URLSearchParams();
+  params.append('totpCode', totpCode);
+
+  await api.post('/login/two-factor', params, {
+    headers: {
+      'Content-Type': 'application/x-www-form-urlencoded',
+      ...(csrfToken && { 'X-XSRF-TOKEN': csrfToken }),
+    },
+  });
+};
+
 // ログアウト
 export const logout = async (): Promise<void> => {
   await api.post('/logout');
@@ -61,7 +296,8 @@ export const cancelRegistration = async (): Promise<void> => {
 export const register = async (
   loginId: string,
   password: string,
-  confirmPassword: string
+  confirmPassword: string,
+  captchaToken?: string
 ): Promise<void> => {
   // CSRFトークンを取得
   await api.get('/api/csrf');
@@ -73,6 +309,7 @@ export const register = async (
   params.append('loginId', loginId);
   params.append('password', password);
   params.append('confirmPassword', confirmPassword);
+  if (captchaToken) params.append('captchaToken', captchaToken);
   
   await api.post('/register', params, {
     headers: {
@@ -104,7 +341,19 @@ export const saveProfile = async (
 };
 
 // 登録状態チェック
-export const checkRegistrationStatus = async (): Promise<{ hasPendingRegistration: boolean }> => {
+export interface RegistrationStatus {
+  hasPendingRegistration: boolean;
+  registrationType?: 'LOCAL' | 'OAUTH';
+  provider?: string;
+  suggestedDisplayName?: string;
+  /** 登録の有効期限（ISO 8601） */
+  expiresAt?: string;
+  expiresInSeconds?: number;
+  /** 有効期限切れの登録が破棄された */
+  expired: boolean;
+}
+
+export const checkRegistrationStatus = async (): Promise<RegistrationStatus> => {
   const response = await api.get('/api/auth/registration-status');
   return response.data;
 };
@@ -0,0 +1,53 @@
+import api from './api';
+import type { CoinBalance } from './petApi';
+
+export type ChallengeType = 'training_days' | 'total_volume' | 'total_sets' | 'muscle_days';
+
+// ウィークリーチャレンジ（progressは目標値で頭打ち）
+export interface Challenge {
+  id: number;
+  challengeType: ChallengeType;
+  title: string;
+  targetMuscle: string | null;
+  targetValue: number;
+  progress: number;
+  expReward: number;
+  coinReward: number;
+  completed: boolean;
+  claimed: boolean;
+}
+
+export interface ChallengesResponse {
+  weekStart: string;
+  weekEnd: string;
+  challenges: Challenge[];
+}
+
+export interface ClaimChallengeResponse {
+  challengeId: number;
+  expEarned: number;
+  coinsEarned: number;
+  totalExp: number;
+  level: number;
+  coins: CoinBalance;
+}
+
+const challengeApi = {
+  /**
+   * 今週のチャレンジ一覧
+   */
+  getChallenges: async (): Promise<ChallengesResponse> => {
+    const response = await api.get<ChallengesResponse>('/api/challenges');
+    return response.data;
+  },
+
+  /**
+   * 達成したチャレンジの報酬を受け取る
+   */
+  claim: async (challengeId: number): Promise<ClaimChallengeResponse> => {
+    const response = await api.post<ClaimChallengeResponse>(`/api/challenges/${challengeId}/claim`);
+    return response.data;
+  },
+};
+
+export default challengeApi;
@@ -2,6 +2,9 @@ import api from './api';
 
 export interface PublicConfigResponse {
   googleMapsApiKey?: string;
+  /** CAPTCHAを使う場合のみ */
+  captchaProvider?: 'recaptcha' | 'hcaptcha';
+  captchaSiteKey?: string;
 }
 
 export const getPublicConfig = async (): Promise<PublicConfigResponse> => {
@@ -12,6 +12,8 @@ export type ContactRequest = {
   userAgent: string;
   screenWidth?: number;
   screenHeight?: number;
+  /** CAPTCHAのトークン（CAPTCHA有効時のみ） */
+  captchaToken?: string;
 };
 
 export type ContactErrorResponse = {
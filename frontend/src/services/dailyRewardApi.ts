@@ -1,16 +1,28 @@
 import api from './api';
 
+export type DailyRewardType = 'exp' | 'coins' | 'item';
+
 export interface DailyRewardDay {
   day: number;
   claimed: boolean;
   claimedDate: string | null;
+  rewardType: DailyRewardType;
+  // EXP・コインの量、またはアイテムの個数
+  amount: number;
+  itemCode: string | null;
+  itemName: string | null;
+  // 基本EXP（EXP以外の報酬の日は0）
   exp: number;
+  // 倍率適用後のEXP（受取済みの日は実際に獲得したEXP）
+  boostedExp: number;
   isBigReward: boolean;
 }
 
 export interface DailyRewardsResponse {
   currentDay: number;
   todayClaimed: boolean;
+  cycleLength: number;
+  multiplier: number;
   days: DailyRewardDay[];
 }
 
@@ -18,13 +30,17 @@ export interface ClaimRewardResponse {
   success: boolean;
   alreadyClaimed: boolean;
   rewardDay: number;
+  rewardType: DailyRewardType | null;
   expEarned: number;
+  coinsEarned: number;
+  itemCode: string | null;
+  itemQuantity: number;
   totalExp: number;
 }
 
 const dailyRewardApi = {
   /**
-   * Get the 14-day reward status
+   * Get the reward status for the current cycle
    */
   getRewards: async (): Promise<DailyRewardsResponse> => {
     const response = await api.get('/api/daily-rewards');
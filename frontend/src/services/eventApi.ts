@@ -0,0 +1,27 @@
+import api from './api';
+
+// 開催中の期間限定イベント
+export interface SeasonalEvent {
+  code: string;
+  name: string;
+  description: string | null;
+  bannerImagePath: string | null;
+  startDate: string;
+  endDate: string;
+  // 記録の保存・デイリーリワードのEXPにかかる倍率
+  expMultiplier: number;
+  // 期間中に解放されるペットの名前
+  specialPetName: string | null;
+}
+
+const eventApi = {
+  /**
+   * 開催中のイベントを取得（なければnull）
+   */
+  getCurrentEvent: async (): Promise<SeasonalEvent | null> => {
+    const response = await api.get<{ event: SeasonalEvent | null }>('/api/events/current');
+    return response.data.event;
+  },
+};
+
+export default eventApi;
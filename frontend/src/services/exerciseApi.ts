@@ -1,5 +1,5 @@
 import api from './api';
-import type { Exercise, ExerciseFilter, PagedResponse } from '../types';
+import type { Exercise, ExerciseDetail, ExerciseFilter, PagedResponse } from '../types';
 
 // バックエンドから返される実際の形式
 interface ExerciseApiResponse {
@@ -72,6 +72,12 @@ export const getExercisesPaged = async (
   };
 };
 
+// 種目詳細（解説を含む）を取得
+export const getExerciseDetail = async (id: number): Promise<ExerciseDetail> => {
+  const response = await api.get<ExerciseDetail>(`/api/exercises/${id}`);
+  return response.data;
+};
+
 // ターゲット筋肉一覧を取得
 export const getTargetMuscles = async (): Promise<string[]> => {
   const response = await api.get<string[]>('/api/exercises/target-muscles');
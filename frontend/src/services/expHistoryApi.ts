@@ -0,0 +1,52 @@
+import api from './api';
+
+export type ExpSource =
+  | 'workout'
+  | 'workout_edit'
+  | 'workout_delete'
+  | 'workout_undo'
+  | 'goal'
+  | 'login_bonus'
+  | 'daily_reward'
+  | 'challenge'
+  | 'streak_recovery'
+  | 'admin'
+  | 'level_curve';
+
+// EXPの増減（amountは減った場合マイナス）
+export interface ExpTransaction {
+  id: number;
+  amount: number;
+  balanceAfter: number;
+  source: ExpSource;
+  referenceId: number | null;
+  createdAt: string;
+}
+
+export interface ExpHistoryResponse {
+  content: ExpTransaction[];
+  page: number;
+  size: number;
+  totalElements: number;
+  totalPages: number;
+  hasNext: boolean;
+  hasPrevious: boolean;
+}
+
+const expHistoryApi = {
+  /**
+   * EXPの増減履歴を取得（新しい順、ページ番号は0始まり）
+   */
+  getExpHistory: async (
+    page = 0,
+    size = 20,
+    source?: ExpSource
+  ): Promise<ExpHistoryResponse> => {
+    const response = await api.get<ExpHistoryResponse>('/api/user/exp-history', {
+      params: { page, size, source },
+    });
+    return response.data;
+  },
+};
+
+export default expHistoryApi;
@@ -46,6 +46,9 @@ export const searchGymsPaged = async (
   if (filter?.tags && filter.tags.length > 0) {
     params.tags = filter.tags.join(',');
   }
+  if (filter?.groups && filter.groups.length > 0) {
+    params.groups = filter.groups.join(',');
+  }
   
   const response = await api.get<GymApiResponse>('/api/gyms/search/paged', { params });
   const data = response.data;
@@ -84,9 +87,16 @@ export interface GymTag {
   id: number;
   name: string;
   displayOrder: number;
+  group: string | null;
+}
+
+// グループ別のタグ（未分類のタグはgroup: null）
+export interface GymTagGroup {
+  group: string | null;
+  tags: GymTag[];
 }
 
-export const getGymTags = async (): Promise<GymTag[]> => {
-  const response = await api.get<GymTag[]>('/api/gyms/tags');
+export const getGymTags = async (): Promise<GymTagGroup[]> => {
+  const response = await api.get<GymTagGroup[]>('/api/gyms/tags');
   return response.data;
 };
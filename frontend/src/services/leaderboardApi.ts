@@ -0,0 +1,45 @@
+import api from './api';
+
+export type LeaderboardType = 'weekly_exp' | 'weekly_volume' | 'longest_streak';
+
+export interface LeaderboardEntry {
+  rank: number;
+  displayName: string | null;
+  profileImageUrl: string | null;
+  score: number;
+  isMe: boolean;
+}
+
+// ランキング（ジョブが定期的に集計した結果）
+export interface LeaderboardResponse {
+  boardType: LeaderboardType;
+  periodStart: string | null;
+  refreshedAt: string | null;
+  entries: LeaderboardEntry[];
+  // 自分の順位（参加していない・スコアがない場合はnull）
+  me: { rank: number; score: number } | null;
+  page: number;
+  size: number;
+  totalElements: number;
+  totalPages: number;
+  hasNext: boolean;
+  hasPrevious: boolean;
+}
+
+const leaderboardApi = {
+  /**
+   * ランキングを取得（ページ番号は0始まり）
+   */
+  getLeaderboard: async (
+    boardType: LeaderboardType,
+    page = 0,
+    size = 20
+  ): Promise<LeaderboardResponse> => {
+    const response = await api.get<LeaderboardResponse>(`/api/leaderboards/${boardType}`, {
+      params: { page, size },
+    });
+    return response.data;
+  },
+};
+
+export default leaderboardApi;
@@ -14,6 +14,17 @@ export interface PetType {
   unlockLevel: number | null;
   unlockPetCode: string | null;
   isStarter: boolean | null;
+  branches: PetBranch[];
+}
+
+// 覚醒後の進化先（dominantMuscleはその部位を最も鍛えた場合、nullは偏りがない場合）
+export interface PetBranch {
+  id: number;
+  code: string;
+  name: string;
+  description: string | null;
+  dominantMuscle: string | null;
+  imagePath: string | null;
 }
 
 // ペット情報の型定義
@@ -32,10 +43,104 @@ export interface PetData {
   moodScore: number;
   moodLabel: string;
   imageUrl: string | null;
+  accessories: EquippedAccessory[];
+  imageLayers: string[]; // 基本画像とアクセサリー画像（奥から順に重ねる）
   isActive: boolean;
+  branch: PetBranch | null;
+  canEvolve: boolean;
   createdAt: string | null;
 }
 
+// 装着中のアクセサリー
+export interface EquippedAccessory {
+  code: string;
+  name: string;
+  slot: string;
+  imagePath: string | null;
+}
+
+// アクセサリー（解放状況・装着状況）
+export interface PetAccessory {
+  code: string;
+  name: string;
+  description: string | null;
+  slot: string;
+  imagePath: string | null;
+  unlocked: boolean;
+  unlockType: string;
+  unlockProgress: string;
+  equipped: boolean;
+  equippedPetId: number | null;
+}
+
+export interface PetAccessoriesResponse {
+  pet: PetData;
+  accessories: PetAccessory[];
+}
+
+// パートナーの節目（達成済みは達成日時順、未達成は後ろ）
+export interface PetMilestone {
+  code: string;
+  name: string;
+  description: string;
+  bonusExp: number;
+  achieved: boolean;
+  achievedAt: string | null;
+  current: number;
+  target: number;
+}
+
+export interface PetMilestonesResponse {
+  petId: number;
+  milestones: PetMilestone[];
+}
+
+export interface PetQuest {
+  id: number;
+  questType: 'train_muscle' | 'log_sets' | 'log_exercises';
+  title: string;
+  targetMuscle: string | null;
+  targetValue: number;
+  progress: number;
+  expReward: number;
+  completed: boolean;
+  claimed: boolean;
+}
+
+export interface PetQuestsResponse {
+  date: string;
+  quests: PetQuest[];
+}
+
+export interface ClaimPetQuestResponse {
+  questId: number;
+  expEarned: number;
+  petId: number;
+  petLevel: number;
+  levelUp: boolean;
+}
+
+// 贈り物（ペット種類かアイテムのどちらか）
+export interface PetGift {
+  id: number;
+  senderName: string;
+  petTypeId: number | null;
+  petTypeName: string | null;
+  itemCode: string | null;
+  itemName: string | null;
+  quantity: number;
+  message: string | null;
+  createdAt: string;
+}
+
+export interface SendGiftRequest {
+  loginId: string;
+  petTypeId?: number;
+  itemCode?: string;
+  quantity?: number;
+  message?: string;
+}
+
 // ペット状態レスポンス
 export interface PetStatusResponse {
   hasPet: boolean;
@@ -56,12 +161,101 @@ export interface LockedPetType {
   unlockProgress: string;
 }
 
+// 小屋の背景シーン（ジムチェックインで解放）
+export interface BarnBackground {
+  code: string;
+  name: string;
+  description: string | null;
+  imagePath: string | null;
+  unlocked: boolean;
+  unlockType: string;
+  unlockProgress: string;
+}
+
 // 小屋レスポンス
 export interface BarnResponse {
   activePet: PetData | null;
   ownedPets: PetData[];
   unlockedTypes: PetType[];
   lockedTypes: LockedPetType[];
+  selectedBackground: BarnBackground;
+  backgrounds: BarnBackground[];
+}
+
+// 次ステージの情報
+export interface NextStage {
+  stage: number;
+  stageName: string;
+  imageUrl: string | null;
+  requiredLevel: number;
+  requiredExp: number;
+  remainingExp: number;
+  remainingLevels: number;
+}
+
+// 進化プレビュー
+export interface EvolutionPreview {
+  petId: number;
+  stage: number;
+  stageName: string;
+  imageUrl: string | null;
+  backgroundImage: string | null;
+  level: number;
+  totalExp: number;
+  stageProgress: number;
+  isFinalStage: boolean;
+  nextStage: NextStage | null;
+}
+
+// 進化の結果
+export interface EvolveResponse {
+  pet: PetData;
+  branch: PetBranch;
+  dominantMuscle: string | null;
+}
+
+// コイン残高（ログインボーナス・チャレンジ・レベルアップで獲得）
+export interface CoinBalance {
+  balance: number;
+  earned: number;
+  spent: number;
+}
+
+export type CoinSource =
+  | 'login_bonus'
+  | 'challenge'
+  | 'level_up'
+  | 'shop'
+  | 'pet_care'
+  | 'streak_recovery';
+
+// コインの増減（amountは使用した場合マイナス）
+export interface CoinTransaction {
+  id: number;
+  amount: number;
+  balanceAfter: number;
+  source: CoinSource;
+  referenceId: number | null;
+  createdAt: string;
+}
+
+export interface CoinTransactionsResponse {
+  content: CoinTransaction[];
+  page: number;
+  size: number;
+  totalElements: number;
+  totalPages: number;
+  hasNext: boolean;
+  hasPrevious: boolean;
+}
+
+// お世話（ごはん・あそぶ）の結果
+export interface PetCareResponse {
+  pet: PetData;
+  action: 'feed' | 'play';
+  moodBoost: number;
+  remainingToday: number;
+  coins: CoinBalance;
 }
 
 export interface CreatePetRequest {
@@ -98,6 +292,32 @@ const petApi = {
     return response.data;
   },
 
+  /**
+   * 小屋の背景を変更（null でデフォルトに戻す）
+   */
+  setBarnBackground: async (
+    code: string | null
+  ): Promise<Pick<BarnResponse, 'selectedBackground' | 'backgrounds'>> => {
+    const response = await api.put('/api/pet/barn/background', { code });
+    return response.data;
+  },
+
+  /**
+   * 次ステージへの進化プレビューを取得
+   */
+  getEvolutionPreview: async (petId: number): Promise<EvolutionPreview> => {
+    const response = await api.get<EvolutionPreview>(`/api/pet/${petId}/evolution-preview`);
+    return response.data;
+  },
+
+  /**
+   * 最も鍛えた筋肉グループに応じた進化先へ進化させる
+   */
+  evolvePet: async (petId: number): Promise<EvolveResponse> => {
+    const response = await api.post<EvolveResponse>(`/api/pet/${petId}/evolve`);
+    return response.data;
+  },
+
   /**
    * ペットを作成（種類を選択して作成）
    */
@@ -130,6 +350,123 @@ const petApi = {
     return response.data;
   },
 
+  /**
+   * ごはんをあげる（コイン、またはitemCodeのごはんアイテムを消費してムードを上げる）
+   */
+  feedPet: async (petId: number, itemCode?: string): Promise<PetCareResponse> => {
+    const response = await api.post<PetCareResponse>(`/api/pet/${petId}/feed`, null, {
+      params: itemCode ? { item: itemCode } : undefined,
+    });
+    return response.data;
+  },
+
+  /**
+   * 一緒にあそぶ（コイン、またはitemCodeのおもちゃアイテムを消費してムードを上げる）
+   */
+  playWithPet: async (petId: number, itemCode?: string): Promise<PetCareResponse> => {
+    const response = await api.post<PetCareResponse>(`/api/pet/${petId}/play`, null, {
+      params: itemCode ? { item: itemCode } : undefined,
+    });
+    return response.data;
+  },
+
+  /**
+   * 節目（マイルストーン）の一覧を取得
+   */
+  getPetMilestones: async (petId: number): Promise<PetMilestonesResponse> => {
+    const response = await api.get<PetMilestonesResponse>(`/api/pet/${petId}/milestones`);
+    return response.data;
+  },
+
+  /**
+   * 今日のデイリークエストを取得
+   */
+  getPetQuests: async (): Promise<PetQuestsResponse> => {
+    const response = await api.get<PetQuestsResponse>('/api/pet/quests');
+    return response.data;
+  },
+
+  /**
+   * 達成したデイリークエストの報酬を受け取る
+   */
+  claimPetQuest: async (questId: number): Promise<ClaimPetQuestResponse> => {
+    const response = await api.post<ClaimPetQuestResponse>(`/api/pet/quests/${questId}/claim`);
+    return response.data;
+  },
+
+  /**
+   * アクセサリー一覧と装着状況を取得
+   */
+  getPetAccessories: async (petId: number): Promise<PetAccessoriesResponse> => {
+    const response = await api.get<PetAccessoriesResponse>(`/api/pet/${petId}/accessories`);
+    return response.data;
+  },
+
+  /**
+   * 装着するアクセサリーを指定（指定しなかったものは外れる）
+   */
+  updatePetAccessories: async (
+    petId: number,
+    accessoryCodes: string[]
+  ): Promise<PetAccessoriesResponse> => {
+    const response = await api.put<PetAccessoriesResponse>(`/api/pet/${petId}/accessories`, {
+      accessoryCodes,
+    });
+    return response.data;
+  },
+
+  /**
+   * 解放済みのペット種類、または所持アイテムを贈る
+   */
+  sendGift: async (data: SendGiftRequest): Promise<{ success: boolean; giftId: number }> => {
+    const response = await api.post<{ success: boolean; giftId: number }>('/api/pet/gift', data);
+    return response.data;
+  },
+
+  /**
+   * 自分宛ての未回答の贈り物一覧を取得
+   */
+  getGifts: async (): Promise<PetGift[]> => {
+    const response = await api.get<PetGift[]>('/api/pet/gifts');
+    return response.data;
+  },
+
+  /**
+   * 贈り物を受け取る
+   */
+  acceptGift: async (giftId: number): Promise<{ success: boolean; gift: PetGift }> => {
+    const response = await api.post<{ success: boolean; gift: PetGift }>(
+      `/api/pet/gifts/${giftId}/accept`
+    );
+    return response.data;
+  },
+
+  /**
+   * 贈り物を辞退する（アイテムは送り主に戻る）
+   */
+  declineGift: async (giftId: number): Promise<{ success: boolean }> => {
+    const response = await api.post<{ success: boolean }>(`/api/pet/gifts/${giftId}/decline`);
+    return response.data;
+  },
+
+  /**
+   * コイン残高を取得
+   */
+  getCoins: async (): Promise<CoinBalance> => {
+    const response = await api.get<CoinBalance>('/api/coins');
+    return response.data;
+  },
+
+  /**
+   * コインの増減履歴を取得（新しい順、ページ番号は0始まり）
+   */
+  getCoinTransactions: async (page = 0, size = 20): Promise<CoinTransactionsResponse> => {
+    const response = await api.get<CoinTransactionsResponse>('/api/coins/transactions', {
+      params: { page, size },
+    });
+    return response.data;
+  },
+
   /**
    * アクティブペットを小屋に戻す（削除ではない）
    */
@@ -137,6 +474,17 @@ const petApi = {
     const response = await api.delete<PetStatusResponse>('/api/pet');
     return response.data;
   },
+
+  /**
+   * ペットとお別れする（最後のパートナーは不可、履歴は残る）
+   */
+  releasePet: async (petId: number): Promise<{ success: boolean; releasedPetId: number }> => {
+    const response = await api.delete<{ success: boolean; releasedPetId: number }>(
+      `/api/pet/${petId}`,
+      { params: { confirm: true } }
+    );
+    return response.data;
+  },
 };
 
 export default petApi;
@@ -0,0 +1,76 @@
+import api from './api';
+
+// 1か月分の合計（ウォームアップを除く）
+export interface MonthSummary {
+  workouts: number;
+  trainingDays: number;
+  totalSets: number;
+  totalReps: number;
+  // 総ボリューム（kg）
+  totalVolume: number;
+  expEarned: number;
+}
+
+export interface TopExercise {
+  exerciseId: number;
+  isCustom: boolean;
+  exerciseName: string;
+  sets: number;
+  volume: number;
+}
+
+// 月内に更新した最高重量の自己ベスト
+export interface MonthlyPersonalRecord {
+  exerciseId: number;
+  isCustom: boolean;
+  exerciseName: string;
+  recordId: number;
+  achievedOn: string;
+  weight: number;
+  previousBest: number;
+}
+
+export interface MonthlyStreakSummary {
+  // 月内で最も長く連続してトレーニングした日数
+  longestTrainingRun: number;
+  loginDays: number;
+  currentTrainingStreak: number;
+  bestTrainingStreak: number;
+}
+
+export interface MonthComparison {
+  month: string;
+  summary: MonthSummary;
+  totalVolumeDiff: number;
+  trainingDaysDiff: number;
+  totalSetsDiff: number;
+  expEarnedDiff: number;
+  // 総ボリュームの増減率（前月が0の場合はnull）
+  totalVolumeChangeRate: number | null;
+}
+
+export interface MonthlyReport {
+  month: string;
+  startDate: string;
+  endDate: string;
+  summary: MonthSummary;
+  topExercises: TopExercise[];
+  personalRecords: MonthlyPersonalRecord[];
+  streak: MonthlyStreakSummary;
+  previousMonth: MonthComparison;
+}
+
+const reportApi = {
+  /**
+   * 月間レポートを取得
+   * @param month YYYY-MM（省略時は今月）
+   */
+  getMonthlyReport: async (month?: string): Promise<MonthlyReport> => {
+    const response = await api.get<MonthlyReport>('/api/reports/monthly', {
+      params: month ? { month } : undefined,
+    });
+    return response.data;
+  },
+};
+
+export default reportApi;
@@ -0,0 +1,60 @@
+import api from './api';
+import type { CoinBalance } from './petApi';
+
+export type ItemCategory = 'food' | 'toy' | 'streak_protection';
+
+// ショップのアイテム（ownedは所持数）
+export interface ShopItem {
+  id: number;
+  code: string;
+  name: string;
+  description: string | null;
+  category: ItemCategory;
+  price: number;
+  moodBoost: number | null;
+  imagePath: string | null;
+  owned: number;
+}
+
+export interface ShopResponse {
+  coins: CoinBalance;
+  items: ShopItem[];
+}
+
+export interface PurchaseRequest {
+  itemCode: string;
+  quantity?: number;
+}
+
+export interface PurchaseResponse {
+  item: ShopItem;
+  coins: CoinBalance;
+}
+
+const shopApi = {
+  /**
+   * 販売中のアイテム一覧（所持数・コイン残高を含む）
+   */
+  getItems: async (): Promise<ShopResponse> => {
+    const response = await api.get<ShopResponse>('/api/shop/items');
+    return response.data;
+  },
+
+  /**
+   * 所持しているアイテム一覧
+   */
+  getInventory: async (): Promise<ShopItem[]> => {
+    const response = await api.get<ShopItem[]>('/api/shop/inventory');
+    return response.data;
+  },
+
+  /**
+   * コインでアイテムを購入
+   */
+  purchase: async (data: PurchaseRequest): Promise<PurchaseResponse> => {
+    const response = await api.post<PurchaseResponse>('/api/shop/purchase', data);
+    return response.data;
+  },
+};
+
+export default shopApi;
@@ -1,4 +1,6 @@
 import api from './api';
+import type { HeatmapMode, SetTarget, WeightUnit } from '../types';
+import type { NewAchievement } from './achievementApi';
 
 // 型定義
 export interface StreakInfo {
@@ -7,6 +9,10 @@ export interface StreakInfo {
   lastActiveDate: string | null;
   graceDaysUsed: number;
   graceDaysAllowed: number;
+  // これまでに消費したストリークシールドの数
+  shieldsUsed: number;
+  // 最後にストリークシールドでカバーした日
+  lastShieldUsedDate: string | null;
 }
 
 export interface StreakResponse {
@@ -23,10 +29,59 @@ export interface LoginBonusResponse {
   expEarned: number;
   currentLoginStreak: number;
   totalExp: number;
+  coinsEarned: number;
+  // 受け取りで新たに達成した実績
+  newAchievements?: NewAchievement[];
+}
+
+// ストリークの修復（コインまたはEXPで支払う）
+export type RecoveryPayment = 'coins' | 'exp';
+
+export interface RecoverStreakResponse {
+  streakType: 'training' | 'login';
+  currentStreak: number;
+  bestStreak: number;
+  payWith: RecoveryPayment;
+  cost: number;
 }
 
 export interface SettingsResponse {
   graceDaysAllowed: number;
+  heatmapMode: HeatmapMode;
+  weightUnit: WeightUnit;
+  // 日付の境界を判定するタイムゾーン（IANA名）
+  timezone: string;
+}
+
+// 公開プロフィールのプライバシー設定
+export interface PrivacySettings {
+  profilePublic: boolean;
+  showLevel: boolean;
+  showStreak: boolean;
+  showPet: boolean;
+  showTotalVolume: boolean;
+  // ランキングに参加する（公開プロフィールとは別に選ぶ）
+  showOnLeaderboard: boolean;
+}
+
+// 通知の受け取り
+export interface NotificationPreferences {
+  workoutComments: boolean;
+  streakReminder: boolean;
+  weeklySummary: boolean;
+}
+
+export type Locale = 'ja' | 'en';
+
+// ユーザー設定（/api/settings・/api/settings/privacy の内容を含む）
+export interface Preferences {
+  weightUnit: WeightUnit;
+  locale: Locale;
+  timezone: string;
+  graceDaysAllowed: number;
+  heatmapMode: HeatmapMode;
+  notifications: NotificationPreferences;
+  privacy: PrivacySettings;
 }
 
 // API関数
@@ -49,6 +104,15 @@ export const streakApi = {
     return response.data;
   },
 
+  // 途切れたストリークを修復（途切れてから48時間以内）
+  recoverStreak: async (
+    streakType: 'training' | 'login',
+    payWith: RecoveryPayment
+  ): Promise<RecoverStreakResponse> => {
+    const response = await api.post('/api/streak/recover', { streakType, payWith });
+    return response.data;
+  },
+
   // ユーザー設定を取得
   getSettings: async (): Promise<SettingsResponse> => {
     const response = await api.get('/api/settings');
@@ -56,8 +120,54 @@ export const streakApi = {
   },
 
   // ユーザー設定を更新
-  updateSettings: async (graceDaysAllowed: number): Promise<SettingsResponse> => {
-    const response = await api.post('/api/settings', { graceDaysAllowed });
+  updateSettings: async (
+    graceDaysAllowed: number,
+    heatmapMode?: HeatmapMode,
+    weightUnit?: WeightUnit,
+    timezone?: string
+  ): Promise<SettingsResponse> => {
+    const response = await api.post('/api/settings', {
+      graceDaysAllowed,
+      heatmapMode,
+      weightUnit,
+      timezone,
+    });
+    return response.data;
+  },
+
+  // プライバシー設定を取得
+  getPrivacySettings: async (): Promise<PrivacySettings> => {
+    const response = await api.get('/api/settings/privacy');
+    return response.data;
+  },
+
+  // プライバシー設定を更新
+  updatePrivacySettings: async (settings: PrivacySettings): Promise<PrivacySettings> => {
+    const response = await api.put('/api/settings/privacy', settings);
+    return response.data;
+  },
+
+  // ユーザー設定をまとめて取得
+  getPreferences: async (): Promise<Preferences> => {
+    const response = await api.get('/api/user/preferences');
+    return response.data;
+  },
+
+  // ユーザー設定を更新（指定した項目のみ）
+  updatePreferences: async (preferences: Partial<Preferences>): Promise<Preferences> => {
+    const response = await api.put('/api/user/preferences', preferences);
+    return response.data;
+  },
+
+  // 週間セット数目標を取得
+  getSetTargets: async (): Promise<SetTarget[]> => {
+    const response = await api.get('/api/settings/set-targets');
+    return response.data;
+  },
+
+  // 週間セット数目標を更新（指定したグループのみ）
+  updateSetTargets: async (targets: SetTarget[]): Promise<SetTarget[]> => {
+    const response = await api.put('/api/settings/set-targets', targets);
     return response.data;
   },
 };
@@ -5,24 +5,49 @@ import type {
   Tag,
   MuscleGroup,
   SaveWorkoutRequest,
+  UpdateWorkoutRequest,
+  CopyWorkoutRequest,
+  BulkDeleteRecordsRequest,
+  BulkDeleteRecordsResponse,
+  UndoLastSaveResponse,
+  ImportResult,
+  RecordFilter,
+  WorkoutSession,
+  ExerciseOneRm,
+  OneRmFormula,
   CustomExerciseRequest,
+  UpdateCustomExerciseRequest,
   HeatmapResponse,
   UserStats,
+  RecordTiming,
+  ExerciseHistoryPage,
+  PersonalRecord,
+  PartnerInvite,
+  WorkoutComment,
+  SetTargetsResponse,
+  ExerciseProgression,
+  VolumeByMuscleResponse,
+  MuscleTargetRequest,
+  MuscleTargetsResponse,
+  Goal,
+  CreateGoalRequest,
+  UpdateGoalRequest,
 } from '../types';
 
 // ワークアウト記録取得
-export const getWorkoutRecords = async (): Promise<TrainingRecord[]> => {
-  const response = await api.get('/api/workout/records');
+export const getWorkoutRecords = async (filter?: RecordFilter): Promise<TrainingRecord[]> => {
+  const response = await api.get('/api/workout/records', { params: filter });
   return response.data;
 };
 
 // ワークアウト記録取得（ページネーション）
 export const getWorkoutRecordsPaged = async (
   page: number = 0,
-  size: number = 20
+  size: number = 20,
+  filter?: RecordFilter
 ): Promise<{ content: TrainingRecord[]; totalPages: number; last: boolean; hasNext?: boolean }> => {
   const response = await api.get('/api/workout/records/paged', {
-    params: { page, size },
+    params: { ...filter, page, size },
   });
   const data = response.data;
   
@@ -35,17 +60,191 @@ export const getWorkoutRecordsPaged = async (
   };
 };
 
+// 種目ごとの過去セット取得（推定1RM付き、ページネーション）
+export const getExerciseHistory = async (
+  exerciseId: number,
+  page: number = 0,
+  size: number = 50
+): Promise<ExerciseHistoryPage> => {
+  const response = await api.get(`/api/workout/exercises/${exerciseId}/history`, {
+    params: { page, size },
+  });
+  return response.data;
+};
+
+// 種目の推定1RMの推移取得
+export const getExerciseOneRm = async (
+  exerciseId: number,
+  formula: OneRmFormula = 'epley'
+): Promise<ExerciseOneRm> => {
+  const response = await api.get(`/api/workout/exercises/${exerciseId}/one-rm`, {
+    params: { formula },
+  });
+  return response.data;
+};
+
+// 自己ベスト一覧取得
+export const getPersonalRecords = async (): Promise<PersonalRecord[]> => {
+  const response = await api.get('/api/workout/prs');
+  return response.data;
+};
+
 // ワークアウト記録保存
+// 画面の重量はkgで入力するため、ユーザー設定の単位にかかわらずkgを明示する
 export const saveWorkoutRecord = async (data: SaveWorkoutRequest): Promise<TrainingRecord> => {
-  const response = await api.post('/api/workout/records', data);
+  const response = await api.post('/api/workout/records', { ...data, weightUnit: 'kg' });
+  return response.data;
+};
+
+// ワークアウト記録の複製（前回のメニューを別の日付に複製）
+export const copyWorkoutRecord = async (data: CopyWorkoutRequest): Promise<TrainingRecord> => {
+  const response = await api.post('/api/workout/records/copy', data);
+  return response.data;
+};
+
+// ワークアウト記録編集（EXPは差分で再計算される）
+export const updateWorkoutRecord = async (
+  id: number,
+  data: UpdateWorkoutRequest
+): Promise<TrainingRecord> => {
+  const response = await api.put(`/api/workout/records/${id}`, { ...data, weightUnit: 'kg' });
+  return response.data;
+};
+
+// ワークアウト記録の所要時間・休憩時間取得
+export const getWorkoutRecordTiming = async (id: number): Promise<RecordTiming> => {
+  const response = await api.get(`/api/workout/records/${id}/timing`);
+  return response.data;
+};
+
+// 記録中のワークアウトの下書き
+export interface WorkoutDraft<T = unknown> {
+  date: string;
+  payload: T;
+  updatedAt: string;
+}
+
+// 下書き取得（ない場合はnull）
+export const getWorkoutDraft = async <T = unknown>(date: string): Promise<WorkoutDraft<T> | null> => {
+  const response = await api.get('/api/workout/draft', { params: { date } });
+  return response.data;
+};
+
+// 下書き保存（同じ日付の下書きは上書き）
+export const saveWorkoutDraft = async <T = unknown>(date: string, payload: T): Promise<void> => {
+  await api.put('/api/workout/draft', { date, payload });
+};
+
+// 下書き破棄
+export const discardWorkoutDraft = async (date: string): Promise<void> => {
+  await api.delete('/api/workout/draft', { params: { date } });
+};
+
+// 合同トレーニングに招待
+export const invitePartner = async (
+  recordId: number,
+  loginId: string
+): Promise<{ success: boolean; sharedSessionId: string }> => {
+  const response = await api.post(`/api/workout/records/${recordId}/invite`, { loginId });
+  return response.data;
+};
+
+// 自分宛ての合同トレーニング招待一覧
+export const getPartnerInvites = async (): Promise<PartnerInvite[]> => {
+  const response = await api.get('/api/workout/invites');
+  return response.data;
+};
+
+// 合同トレーニング招待を承諾
+export const acceptPartnerInvite = async (
+  id: number
+): Promise<{ recordId: number; sharedSessionId: string }> => {
+  const response = await api.post(`/api/workout/invites/${id}/accept`);
   return response.data;
 };
 
+// 合同トレーニング招待を辞退
+export const declinePartnerInvite = async (id: number): Promise<void> => {
+  await api.post(`/api/workout/invites/${id}/decline`);
+};
+
+// 合同トレーニング記録のコメント一覧
+export const getWorkoutComments = async (recordId: number): Promise<WorkoutComment[]> => {
+  const response = await api.get(`/api/social/workouts/${recordId}/comments`);
+  return response.data;
+};
+
+// 合同トレーニング記録にコメント
+export const postWorkoutComment = async (
+  recordId: number,
+  content: string
+): Promise<{ success: boolean; id: number }> => {
+  const response = await api.post(`/api/social/workouts/${recordId}/comments`, { content });
+  return response.data;
+};
+
+// コメント削除（投稿者・記録の持ち主）
+export const deleteWorkoutComment = async (commentId: number): Promise<void> => {
+  await api.delete(`/api/social/comments/${commentId}`);
+};
+
 // ワークアウト記録削除
 export const deleteWorkoutRecord = async (id: number): Promise<void> => {
   await api.delete(`/api/workout/records/${id}`);
 };
 
+// ワークアウト記録の一括削除（記録IDの一覧、または期間のどちらか一方を指定）
+export const bulkDeleteWorkoutRecords = async (
+  data: BulkDeleteRecordsRequest
+): Promise<BulkDeleteRecordsResponse> => {
+  const response = await api.delete('/api/workout/records', { data });
+  return response.data;
+};
+
+// 直前の保存を取り消し（追加したセットと獲得EXPを戻す）
+export const undoLastWorkoutSave = async (recordId: number): Promise<UndoLastSaveResponse> => {
+  const response = await api.post(`/api/workout/records/${recordId}/undo-last`);
+  return response.data;
+};
+
+// トレーニング履歴のエクスポート（CSV/JSONファイル）
+export const exportTrainingHistory = async (format: 'csv' | 'json' = 'csv'): Promise<Blob> => {
+  const response = await api.get('/api/workout/export', {
+    params: { format },
+    responseType: 'blob',
+  });
+  return response.data;
+};
+
+// 他のアプリのCSVからトレーニング記録をインポート（dryRunは保存せずにプレビュー）
+export const importTrainingRecords = async (file: File, dryRun: boolean): Promise<ImportResult> => {
+  const formData = new FormData();
+  formData.append('file', file);
+  const response = await api.post('/api/workout/import', formData, {
+    params: { dryRun },
+    headers: { 'Content-Type': 'multipart/form-data' },
+  });
+  return response.data;
+};
+
+// 計測中のトレーニングセッション取得（なければnull）
+export const getCurrentWorkoutSession = async (): Promise<WorkoutSession | null> => {
+  const response = await api.get('/api/workout/sessions/current');
+  return response.data;
+};
+
+// トレーニングセッション開始（今日の記録に開始時刻を記録）
+export const startWorkoutSession = async (): Promise<WorkoutSession> => {
+  const response = await api.post('/api/workout/sessions/start');
+  return response.data;
+};
+
+// トレーニングセッション終了
+export const finishWorkoutSession = async (): Promise<WorkoutSession> => {
+  const response = await api.post('/api/workout/sessions/finish');
+  return response.data;
+};
+
 // セット削除
 export const deleteWorkoutSet = async (id: number): Promise<void> => {
   await api.delete(`/api/workout/sets/${id}`);
@@ -65,6 +264,15 @@ export const createCustomExercise = async (
   return response.data;
 };
 
+// カスタム種目更新
+export const updateCustomExercise = async (
+  id: number,
+  data: UpdateCustomExerciseRequest
+): Promise<WorkoutExercise> => {
+  const response = await api.put(`/api/workout/custom-exercises/${id}`, data);
+  return response.data;
+};
+
 // カスタム種目削除
 export const deleteCustomExercise = async (id: number): Promise<void> => {
   await api.delete(`/api/workout/custom-exercises/${id}`);
@@ -116,6 +324,79 @@ export const getHeatmapData = async (year: number): Promise<HeatmapResponse> =>
   return response.data;
 };
 
+// 今週の筋肉グループ別セット数と目標
+export const getSetTargets = async (): Promise<SetTargetsResponse> => {
+  const response = await api.get('/api/dashboard/set-targets');
+  return response.data;
+};
+
+// 種目のセッションごとの最高重量・推定1RM・ボリュームの推移
+export const getExerciseProgression = async (
+  exerciseId: number,
+  smoothing?: number,
+  formula: OneRmFormula = 'epley'
+): Promise<ExerciseProgression> => {
+  const response = await api.get('/api/dashboard/progression', {
+    params: { exerciseId, formula, smoothing },
+  });
+  return response.data;
+};
+
+// 期間内の筋肉グループ別ボリューム・セット数
+// @param range 7d / 30d / 90d など（最大365d）
+export const getVolumeByMuscle = async (range = '30d'): Promise<VolumeByMuscleResponse> => {
+  const response = await api.get('/api/dashboard/volume-by-muscle', {
+    params: { range },
+  });
+  return response.data;
+};
+
+// 筋肉グループ別の週間目標（一覧・追加・更新・削除）
+export const getMuscleTargets = async (): Promise<MuscleTargetsResponse> => {
+  const response = await api.get('/api/workout/targets');
+  return response.data;
+};
+
+export const createMuscleTarget = async (
+  data: MuscleTargetRequest
+): Promise<MuscleTargetsResponse> => {
+  const response = await api.post('/api/workout/targets', data);
+  return response.data;
+};
+
+export const updateMuscleTarget = async (
+  id: number,
+  data: MuscleTargetRequest
+): Promise<MuscleTargetsResponse> => {
+  const response = await api.put(`/api/workout/targets/${id}`, data);
+  return response.data;
+};
+
+export const deleteMuscleTarget = async (id: number): Promise<void> => {
+  await api.delete(`/api/workout/targets/${id}`);
+};
+
+// 目標（一覧・追加・更新・削除）
+// 一覧の取得時に達成した目標は達成済みになり、報酬EXPが付与される
+export const getGoals = async (): Promise<Goal[]> => {
+  const response = await api.get('/api/goals');
+  return response.data;
+};
+
+export const createGoal = async (data: CreateGoalRequest): Promise<Goal> => {
+  const response = await api.post('/api/goals', data);
+  return response.data;
+};
+
+export const updateGoal = async (id: number, data: UpdateGoalRequest): Promise<Goal> => {
+  const response = await api.put(`/api/goals/${id}`, data);
+  return response.data;
+};
+
+export const deleteGoal = async (id: number): Promise<void> => {
+  await api.delete(`/api/goals/${id}`);
+};
+
 // ユーザー統計取得
 export const getUserStats = async (): Promise<UserStats> => {
   const response = await api.get('/api/user/stats');
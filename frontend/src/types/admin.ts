@@ -21,3 +21,73 @@ export interface UpdateLevelResponse {
   totalExp: number;
   message: string;
 }
+
+/** レベル曲線（必要累計EXP = quadratic × L² + linear × L + constant） */
+export interface LevelCurve {
+  version: number;
+  quadratic: number;
+  linear: number;
+  constant: number;
+  active: boolean;
+  /** 旧曲線のまま移行待ちのユーザー数 */
+  pendingUsers: number;
+  /** [レベル, 必要累計EXP] */
+  sampleRequiredExp: [number, number][];
+}
+
+/** レベル曲線の移行方法 */
+export type LevelCurveMigrationStrategy = 'KEEP_EXP' | 'KEEP_LEVEL';
+
+/** レベル曲線移行レスポンス */
+export interface MigrateLevelCurveResponse {
+  version: number;
+  strategy: LevelCurveMigrationStrategy;
+  migratedUsers: number;
+}
+
+/** EXP設定 */
+export interface ExpConfig {
+  /** 1日に獲得できるEXPの上限 */
+  dailyLimit: number;
+  /** 過去の記録とみなす日数 */
+  pastDaysThreshold: number;
+  /** 過去の記録のEXP倍率 */
+  pastExpMultiplier: number;
+  /** 過去の記録の1日の上限の倍率 */
+  pastLimitMultiplier: number;
+  /** 1セットで獲得できるEXPの上限 */
+  maxExpPerSet: number;
+  /** EXP計算の係数 */
+  expCoefficient: number;
+}
+
+/** ペットの成長曲線（必要累計EXP = baseExp × (レベル - 1) ^ exponent） */
+export interface PetExpConfig {
+  baseExp: number;
+  exponent: number;
+}
+
+/** ペットの成長曲線の更新レスポンス */
+export interface UpdatePetExpConfigResponse extends PetExpConfig {
+  /** レベル・ステージを再計算したペットの数 */
+  recomputedPets: number;
+}
+
+/** デイリーリワードの報酬（管理用） */
+export interface AdminDailyReward {
+  day: number;
+  rewardType: 'exp' | 'coins' | 'item';
+  /** EXP・コインの量、またはアイテムの個数 */
+  amount: number;
+  itemCode: string | null;
+  itemName: string | null;
+  isBigReward: boolean;
+}
+
+/** デイリーリワードの報酬の登録・更新リクエスト */
+export interface AdminDailyRewardRequest {
+  rewardType: 'exp' | 'coins' | 'item';
+  amount: number;
+  itemCode?: string;
+  isBigReward?: boolean;
+}
@@ -4,11 +4,16 @@ export interface User {
   displayName: string | null;
   loginId: string;
   isOAuthUser: boolean;
+  email?: string | null;
   profileImageUrl?: string | null;
   oauthProvider?: string;
   level?: number;
   currentExp?: number;
   expToNextLevel?: number;
+  // 体重（kg、自重種目のEXP計算に使用）
+  bodyWeight?: number | null;
+  // メールアドレスの確認が済んでいるか（未確認のメールアドレスにはお問い合わせの返信をしない）
+  emailVerified?: boolean;
 }
 
 export interface LoginRequest {
@@ -26,6 +31,20 @@ export interface UpdateDisplayNameRequest {
   displayName: string;
 }
 
+export interface UpdateLoginIdRequest {
+  loginId: string;
+  // パスワードを持つアカウントのみ必須
+  currentPassword?: string;
+}
+
+export interface UpdateBodyWeightRequest {
+  bodyWeight: number | null;
+}
+
+export interface UpdateEmailRequest {
+  email: string;
+}
+
 export interface UpdatePasswordRequest {
   currentPassword: string;
   newPassword: string;
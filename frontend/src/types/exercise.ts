@@ -9,6 +9,26 @@ export interface Exercise {
   targetMuscles?: TargetMuscle[];
 }
 
+// 種目の解説
+export interface ExerciseInstructions {
+  steps: string[];
+  commonMistakes: string[];
+  safetyNotes: string[];
+}
+
+// 種目詳細
+export interface ExerciseDetail {
+  id: number;
+  name: string;
+  muscle: string;
+  muscleGroupId: number | null;
+  difficulty: number;
+  description?: string;
+  targetMuscles?: string;
+  videoPath?: string;
+  instructions: ExerciseInstructions;
+}
+
 export interface ExerciseMuscleGroup {
   id: number;
   name: string;
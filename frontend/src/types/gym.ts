@@ -22,4 +22,5 @@ export interface GymFilter {
   minFee?: number;
   maxFee?: number;
   tags?: string[];
+  groups?: string[];  // タググループ（各グループのタグを1つ以上持つジム）
 }
@@ -1,8 +1,10 @@
 // ワークアウト関連の型定義
+import type { NewAchievement } from '../services/achievementApi';
+
 export interface TrainingRecord {
   id: number;
   date: string;
-  memo?: string;
+  note?: string;               // 記録全体のメモ
   exercises: TrainingRecordExercise[];
   // 経験値関連（POST時のレスポンスに含まれる）
   expGained?: number;
@@ -10,6 +12,96 @@ export interface TrainingRecord {
   totalExp?: number;
   currentLevel?: number;
   levelProgress?: number;
+  // 重量を表示する単位（ユーザー設定。重量自体は常にkg）
+  weightUnit?: WeightUnit;
+  // 合同トレーニング
+  sharedSessionId?: string;
+  trainedWith?: TrainingPartner[];
+  // トレーニングの開始・終了時刻（セッション計測時のみ）
+  startedAt?: string;
+  finishedAt?: string;
+  // 保存・編集で更新した自己ベスト
+  newPersonalRecords?: PersonalRecord[];
+  // 保存・編集で達成した目標（報酬EXPはtotalExpに含まれる）
+  completedGoals?: Goal[];
+  // 保存で新たに達成した実績
+  newAchievements?: NewAchievement[];
+}
+
+// 種目の推定1RMの推移
+export type OneRmFormula = 'epley' | 'brzycki';
+
+export interface OneRmPoint {
+  date: string;
+  recordId: number;
+  weight: number;
+  reps: number;
+  estimated1rm: number;
+}
+
+export interface ExerciseOneRm {
+  exerciseId: number;
+  isCustom: boolean;
+  formula: OneRmFormula;
+  current: OneRmPoint | null;  // 直近のトレーニング日
+  best: OneRmPoint | null;     // 全期間の最高値
+  history: OneRmPoint[];       // トレーニング日ごとの最高値（古い順）
+}
+
+// トレーニングセッション（開始・終了時刻の計測）
+export interface WorkoutSession {
+  recordId: number;
+  date: string;
+  startedAt: string | null;
+  finishedAt: string | null;
+  durationSeconds: number | null;  // 終了済みの場合のみ
+}
+
+// 種目ごとの自己ベスト
+export type PersonalRecordType =
+  | 'MAX_WEIGHT'
+  | 'MAX_REPS'
+  | 'MAX_ESTIMATED_1RM'
+  | 'MAX_SESSION_VOLUME';
+
+export interface PersonalRecord {
+  exerciseId: number;
+  isCustom: boolean;
+  exerciseName: string;
+  recordType: PersonalRecordType;
+  value: number;
+  weight?: number;
+  reps?: number;
+  recordId: number;
+  achievedOn: string;
+  previousValue?: number;
+}
+
+// 一緒にトレーニングしたパートナー
+export interface TrainingPartner {
+  userId: number;
+  displayName: string;
+}
+
+// 合同トレーニングの招待
+export interface PartnerInvite {
+  id: number;
+  recordId: number;
+  date: string;
+  inviter: TrainingPartner;
+  exerciseCount: number;
+  createdAt: string;
+}
+
+// 合同トレーニング記録へのコメント
+export interface WorkoutComment {
+  id: number;
+  recordId: number;
+  userId: number;
+  displayName: string;
+  content: string;
+  createdAt: string;
+  canDelete: boolean;
 }
 
 export interface TrainingRecordExercise {
@@ -20,13 +112,21 @@ export interface TrainingRecordExercise {
   sets: TrainingSet[];
   tags?: Tag[];
   defaultTags?: string[];
+  supersetGroup?: number;      // スーパーセットの組番号（同じ番号の種目が1組）
 }
 
+// セットの種類（warmupはEXP・ボリュームの集計対象外）
+export type SetType = 'normal' | 'warmup' | 'dropset' | 'failure';
+
 export interface TrainingSet {
   id: number;
   setNumber: number;
   weight: number;
   reps: number;
+  setType: SetType;
+  rpe?: number;
+  memo?: string;
+  createdAt?: string;
 }
 
 export interface Tag {
@@ -49,31 +149,157 @@ export interface WorkoutExercise {
   defaultTags?: string[];      // 種目マスターのデフォルトタグ（緑色）
   userAddedDefaultTags?: string[];  // ユーザーが追加したデフォルトタグ（紫色）
   tags?: Tag[];                // カスタムタグ
+  isBodyweight?: boolean;      // 自重種目（重量は追加重量として入力）
+  difficultyCoef?: number;     // EXP計算の難易度係数（カスタム種目のみ）
   // 互換性のためのエイリアス（既存コード用）
   muscleGroupId?: number;
   muscleGroupName?: string;
   isCustom?: boolean;
 }
 
+// 記録一覧の絞り込み（部位・タグ・種目は条件を満たす種目を含む記録が対象）
+export interface RecordFilter {
+  from?: string;
+  to?: string;
+  muscle?: string;
+  tagId?: number;
+  exerciseId?: number;
+}
+
 export interface SaveWorkoutRequest {
   date: string;
-  memo?: string;
+  // 指定した場合のみ上書き（空文字で削除）
+  note?: string;
   exercises: SaveWorkoutExercise[];
 }
 
+// 記録の編集（種目・セットを丸ごと置き換える）
+export interface UpdateWorkoutRequest {
+  note?: string;
+  exercises: SaveWorkoutExercise[];
+}
+
+// 記録の複製（複製元の日付の種目・セットを複製先の日付に保存）
+export interface CopyWorkoutRequest {
+  sourceDate: string;
+  targetDate: string;
+  withoutExp?: boolean;        // trueの場合はEXPを付与しない
+}
+
+// 記録の一括削除（ids、または from・to のどちらか一方を指定）
+export interface BulkDeleteRecordsRequest {
+  ids?: number[];
+  from?: string;
+  to?: string;
+}
+
+export interface BulkDeleteRecordsResponse {
+  success: boolean;
+  deletedCount: number;
+  expDeducted: number;
+}
+
+// 直前の保存の取り消し結果
+export interface UndoLastSaveResponse {
+  success: boolean;
+  removedSetCount: number;
+  expDeducted: number;
+  recordDeleted: boolean;
+}
+
+// CSVインポートの結果（dryRunの場合はプレビュー）
+export interface ImportExerciseMapping {
+  sourceName: string;
+  exerciseId: number | null;   // dryRunで新規作成予定の場合はnull
+  exerciseName: string;
+  isCustom: boolean;
+  matchType: 'exact' | 'fuzzy' | 'created';
+}
+
+export interface ImportResult {
+  dryRun: boolean;
+  recordCount: number;
+  setCount: number;
+  skippedDates: string[];      // 既に記録がある日付（上書きしない）
+  skippedRows: number;
+  exercises: ImportExerciseMapping[];
+  errors: string[];
+}
+
 export interface SaveWorkoutExercise {
   exerciseId: number;
+  supersetGroup?: number;      // 1〜99、省略時は単独の種目
   sets: SaveWorkoutSet[];
 }
 
 export interface SaveWorkoutSet {
   weight: number;
   reps: number;
+  setType?: SetType;
+  // 主観的運動強度（1〜10、0.5刻み）
+  rpe?: number;
+  memo?: string;
+}
+
+// セットの登録時刻から算出したトレーニング時間・休憩時間
+export interface RestStats {
+  intervalCount: number;
+  averageSeconds: number | null;
+  medianSeconds: number | null;
+  longestSeconds: number | null;
+}
+
+export interface RecordTiming {
+  recordId: number;
+  date: string;
+  startedAt: string | null;
+  finishedAt: string | null;
+  durationSeconds: number | null;
+  setCount: number;
+  timedSetCount: number;
+  rest: RestStats;
+  exercises: {
+    id: number;
+    name: string;
+    setCount: number;
+    firstSetAt: string | null;
+    lastSetAt: string | null;
+    rest: RestStats;
+  }[];
+}
+
+// 種目ごとの過去セット（新しい日付順）
+export interface ExerciseHistorySet {
+  setId: number;
+  recordId: number;
+  date: string;
+  setNumber: number;
+  weight: number;
+  reps: number;
+  estimated1rm: number | null;
+}
+
+export interface ExerciseHistoryPage {
+  content: ExerciseHistorySet[];
+  page: number;
+  size: number;
+  totalElements: number;
+  totalPages: number;
+  hasNext: boolean;
+  hasPrevious: boolean;
 }
 
 export interface CustomExerciseRequest {
   name: string;
   muscle: string;
+  isBodyweight?: boolean;
+}
+
+export interface UpdateCustomExerciseRequest {
+  name?: string;
+  muscle?: string;
+  /** EXP計算の難易度係数（1〜30） */
+  difficultyCoef?: number;
 }
 
 export interface HeatmapData {
@@ -91,6 +317,140 @@ export interface HeatmapResponse {
   heatmapStart: string;
   heatmapEnd: string;
   year: number;
+  // レベル1→2、2→3、3→4の境界ボリューム（kg）
+  thresholds?: [number, number, number];
+  // ADAPTIVE: 自分のボリューム分位点 / FIXED: 1,000 / 2,500 / 5,000kg
+  thresholdMode?: HeatmapMode;
+}
+
+export type HeatmapMode = 'ADAPTIVE' | 'FIXED';
+
+// 重量の単位（記録は常にkgで保存される）
+export type WeightUnit = 'kg' | 'lb';
+
+// 筋肉グループごとの週間セット数目標
+export interface SetTarget {
+  muscle: string;
+  minSets: number;
+  maxSets: number;
+}
+
+export interface SetTargetProgress extends SetTarget {
+  sets: number;
+  status: 'BELOW' | 'WITHIN' | 'ABOVE';
+  // 最低セット数に対する達成率（0〜1）
+  progress: number;
+}
+
+export interface SetTargetsResponse {
+  weekStart: string;
+  weekEnd: string;
+  muscles: SetTargetProgress[];
+  summary: {
+    belowCount: number;
+    withinCount: number;
+    aboveCount: number;
+    complianceRate: number;
+  };
+}
+
+// 種目のセッションごとの推移（smoothed*は移動平均を指定した場合のみ）
+export interface ProgressionPoint {
+  date: string;
+  recordId: number;
+  topSetWeight: number;
+  estimated1rm: number | null;
+  volume: number;
+  sets: number;
+  smoothedTopSetWeight?: number | null;
+  smoothedEstimated1rm?: number | null;
+  smoothedVolume?: number | null;
+}
+
+export interface ExerciseProgression {
+  exerciseId: number;
+  isCustom: boolean;
+  formula: OneRmFormula;
+  smoothing: number | null;
+  points: ProgressionPoint[];  // 古い順
+}
+
+// 期間内の筋肉グループ別ボリューム（ウォームアップを除く）
+export interface MuscleVolume {
+  muscle: string;
+  volume: number;
+  sets: number;
+  // 全体のボリュームに占める割合（0〜1）
+  volumeShare: number;
+}
+
+export interface VolumeByMuscleResponse {
+  range: string;
+  startDate: string;
+  endDate: string;
+  totalVolume: number;
+  totalSets: number;
+  muscles: MuscleVolume[];
+}
+
+// 筋肉グループ別の週間目標（セット数・ボリューム）と今週の進捗
+export interface MuscleTargetRequest {
+  muscle: string;
+  targetSets?: number | null;
+  targetVolume?: number | null;
+}
+
+export interface MuscleTargetProgress {
+  id: number;
+  muscle: string;
+  targetSets: number | null;
+  targetVolume: number | null;
+  sets: number;
+  volume: number;
+  // 目標に対する達成率（0〜1）
+  progress: number;
+  achieved: boolean;
+}
+
+export interface MuscleTargetsResponse {
+  weekStart: string;
+  weekEnd: string;
+  targets: MuscleTargetProgress[];
+}
+
+// 目標（推定1RM・体重・週のトレーニング日数）と進捗
+export type GoalType = 'ONE_RM' | 'BODY_WEIGHT' | 'WEEKLY_WORKOUTS';
+
+export interface CreateGoalRequest {
+  goalType: GoalType;
+  exerciseId?: number;  // ONE_RMのみ
+  isCustom?: boolean;
+  targetValue: number;
+  deadline?: string | null;
+}
+
+export interface UpdateGoalRequest {
+  targetValue: number;
+  deadline?: string | null;
+}
+
+export interface Goal {
+  id: number;
+  goalType: GoalType;
+  exerciseId?: number;
+  isCustom?: boolean;
+  exerciseName?: string;
+  targetValue: number;
+  startValue: number | null;   // 設定時の推定1RM・体重
+  currentValue: number | null;
+  // 達成率（0〜1）
+  progress: number;
+  deadline: string | null;
+  expReward: number;
+  completed: boolean;
+  completedAt: string | null;
+  expired: boolean;             // 期限を過ぎた未達成の目標
+  createdAt: string;
 }
 
 export interface UserStats {
@@ -106,6 +466,7 @@ export interface UserStats {
   weeklyWorkoutsChange?: number;
   weeklyVolumeChangePercent?: number;
   bestRecordsCount?: number;
+  weeklyAverageDurationSeconds?: number | null;  // 今週の平均所要時間（秒）
   recentRecords?: {
     date: string;
     exerciseCount: number;
@@ -113,6 +474,7 @@ export interface UserStats {
     setCount: number;
     primaryMuscles: string[];
     expEarned: number;
+    durationSeconds: number | null;
   }[];
   weeklyVolumeHistory?: {
     date: string;
@@ -124,4 +486,5 @@ export interface UserStats {
     daysSinceLastTrained: number;
     status: 'recovering' | 'ready' | 'stale';
   }[];
+  muscleTargets?: MuscleTargetProgress[];
 }
@@ -13,6 +13,11 @@ export default defineConfig({
         target: 'http://localhost:5000',
         changeOrigin: true,
       },
+      // WebSocket（パートナーのイベント通知）
+      '/ws': {
+        target: 'ws://localhost:5000',
+        ws: true,
+      },
       // 認証関連エンドポイント（全てバックエンドにプロキシ）
       '/login': {
         target: 'http://localhost:5000',
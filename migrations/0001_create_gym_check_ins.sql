@@ -0,0 +1,10 @@
+-- ジムチェックイン（人気順ソートの利用回数として使用）
+CREATE TABLE IF NOT EXISTS gym_check_ins (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    gym_id BIGINT NOT NULL,
+    check_in_date DATE NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_gym_check_ins_user_gym_date (user_id, gym_id, check_in_date),
+    KEY idx_gym_check_ins_gym (gym_id)
+);
@@ -0,0 +1,18 @@
+-- 種目のターゲット筋肉（exercises.target_muscles のカンマ区切り値を正規化）
+CREATE TABLE IF NOT EXISTS exercise_target_muscles (
+    exercise_id BIGINT NOT NULL,
+    muscle_name VARCHAR(100) NOT NULL,
+    PRIMARY KEY (exercise_id, muscle_name),
+    KEY idx_exercise_target_muscles_muscle (muscle_name, exercise_id)
+);
+
+-- 既存データを移行
+INSERT IGNORE INTO exercise_target_muscles (exercise_id, muscle_name)
+SELECT e.id, TRIM(jt.muscle_name)
+FROM exercises e
+CROSS JOIN JSON_TABLE(
+    CONCAT('["', REPLACE(REPLACE(e.target_muscles, '"', ''), ',', '","'), '"]'),
+    '$[*]' COLUMNS (muscle_name VARCHAR(100) PATH '$')
+) jt
+WHERE e.target_muscles IS NOT NULL
+  AND TRIM(jt.muscle_name) <> '';
@@ -0,0 +1,10 @@
+-- 記録ごとにどのペットへEXPを付与したか（記録削除時に正しいペットから差し引くため）
+CREATE TABLE IF NOT EXISTS training_record_pet_exp (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    pet_id BIGINT NOT NULL,
+    exp_amount BIGINT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_training_record_pet_exp_record (record_id),
+    KEY idx_training_record_pet_exp_pet (pet_id)
+);
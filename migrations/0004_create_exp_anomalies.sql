@@ -0,0 +1,16 @@
+-- 不自然なEXP獲得の検知結果（夜間ジョブが登録し、管理者がレビューする）
+CREATE TABLE IF NOT EXISTS exp_anomalies (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    anomaly_type VARCHAR(50) NOT NULL,
+    detected_for DATE NOT NULL,
+    metric_value BIGINT NOT NULL,
+    detail TEXT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    review_note VARCHAR(500) NULL,
+    reviewed_by BIGINT NULL,
+    reviewed_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_exp_anomalies_user_type_date (user_id, anomaly_type, detected_for),
+    KEY idx_exp_anomalies_status (status, created_at)
+);
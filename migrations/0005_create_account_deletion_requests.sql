@@ -0,0 +1,17 @@
+-- 退会（アカウント削除）リクエスト
+-- メールの確認リンクをクリックした時点から30日後に削除される
+CREATE TABLE IF NOT EXISTS account_deletion_requests (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    confirmation_token VARCHAR(64) NULL,
+    token_expires_at DATETIME NULL,
+    status VARCHAR(30) NOT NULL,
+    requested_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    confirmed_at DATETIME NULL,
+    scheduled_deletion_at DATETIME NULL,
+    cancelled_at DATETIME NULL,
+    completed_at DATETIME NULL,
+    UNIQUE KEY uk_account_deletion_requests_token (confirmation_token),
+    KEY idx_account_deletion_requests_user (user_id, status),
+    KEY idx_account_deletion_requests_schedule (status, scheduled_deletion_at)
+);
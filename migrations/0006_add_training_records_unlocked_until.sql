@@ -0,0 +1,2 @@
+-- 管理者によるロック解除（この日時まではロック期間を過ぎた記録も変更可能）
+ALTER TABLE training_records ADD COLUMN unlocked_until DATETIME NULL;
@@ -0,0 +1,3 @@
+-- ヒートマップの強度しきい値モード
+-- ADAPTIVE: ユーザー自身の日別ボリュームの分位点 / FIXED: 1,000 / 2,500 / 5,000kg
+ALTER TABLE user_settings ADD COLUMN heatmap_mode VARCHAR(16) NOT NULL DEFAULT 'ADAPTIVE';
@@ -0,0 +1,3 @@
+-- ジム設備タグのグループ（例: フリーウェイト、設備・アメニティ）
+-- GROUPは予約語のためtag_groupとする
+ALTER TABLE tags ADD COLUMN tag_group VARCHAR(50) NULL;
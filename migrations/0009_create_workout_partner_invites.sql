@@ -0,0 +1,17 @@
+-- 合同トレーニング（トレーニングパートナーとの共有記録）
+-- 同じセッションの記録はshared_session_idで紐付ける
+ALTER TABLE training_records ADD COLUMN shared_session_id VARCHAR(36) NULL;
+ALTER TABLE training_records ADD KEY idx_training_records_shared_session (shared_session_id);
+
+CREATE TABLE IF NOT EXISTS workout_partner_invites (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    inviter_user_id BIGINT NOT NULL,
+    invitee_user_id BIGINT NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    partner_record_id BIGINT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    responded_at DATETIME NULL,
+    UNIQUE KEY uk_workout_partner_invites_record_invitee (record_id, invitee_user_id),
+    KEY idx_workout_partner_invites_invitee (invitee_user_id, status)
+);
@@ -0,0 +1,10 @@
+-- 種目の解説コンテンツ（手順・よくある間違い・安全上の注意）
+-- section: STEP / MISTAKE / SAFETY、同じsection内はdisplay_order順に表示
+CREATE TABLE IF NOT EXISTS exercise_instructions (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    exercise_id BIGINT NOT NULL,
+    section VARCHAR(20) NOT NULL,
+    display_order INT NOT NULL,
+    content TEXT NOT NULL,
+    KEY idx_exercise_instructions_exercise (exercise_id, section, display_order)
+);
@@ -0,0 +1,9 @@
+-- 記録中のワークアウトの下書き（ユーザー・日付ごとに1件、記録保存時に削除）
+CREATE TABLE IF NOT EXISTS workout_drafts (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    draft_date DATE NOT NULL,
+    payload MEDIUMTEXT NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_workout_drafts_user_date (user_id, draft_date)
+);
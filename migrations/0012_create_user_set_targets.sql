@@ -0,0 +1,9 @@
+-- 筋肉グループごとの週間セット数目標（未設定のグループは10〜20セット）
+CREATE TABLE IF NOT EXISTS user_set_targets (
+    user_id BIGINT NOT NULL,
+    muscle_group VARCHAR(20) NOT NULL,
+    min_sets INT NOT NULL,
+    max_sets INT NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, muscle_group)
+);
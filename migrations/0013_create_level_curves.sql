@@ -0,0 +1,17 @@
+-- レベル曲線（必要累計EXP = quadratic × L² + linear × L + constant、Lv1は0）
+-- 有効なバージョンは1件のみ。変更時は新しいバージョンを追加する
+CREATE TABLE IF NOT EXISTS level_curves (
+    version INT PRIMARY KEY,
+    quadratic BIGINT NOT NULL,
+    linear_coef BIGINT NOT NULL,
+    constant_term BIGINT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 2週間スピードラン用の初期曲線: 40 × L² + 100 × L - 140
+INSERT IGNORE INTO level_curves (version, quadratic, linear_coef, constant_term, is_active)
+VALUES (1, 40, 100, -140, TRUE);
+
+-- ユーザーのレベル・EXPがどの曲線で計算されたか（NULLは現在の曲線）
+ALTER TABLE user_stats ADD COLUMN level_curve_version INT NULL;
@@ -0,0 +1,37 @@
+-- 小屋の背景シーン（ジムチェックインで解放）
+-- unlock_type: default（最初から解放）/ check_in_count（チェックイン回数）/ gym_count（チェックインしたジムの数）
+CREATE TABLE IF NOT EXISTS barn_backgrounds (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    code VARCHAR(50) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(255) NULL,
+    image_path VARCHAR(255) NULL,
+    unlock_type VARCHAR(30) NOT NULL DEFAULT 'default',
+    unlock_value INT NOT NULL DEFAULT 0,
+    display_order INT NOT NULL DEFAULT 0,
+    UNIQUE KEY uk_barn_backgrounds_code (code)
+);
+
+INSERT IGNORE INTO barn_backgrounds
+    (code, name, description, image_path, unlock_type, unlock_value, display_order)
+VALUES
+    ('default', 'いつもの小屋', 'パートナーごとの背景', NULL, 'default', 0, 1),
+    ('gym', 'ジム', 'ジムに10回チェックインすると解放', '/images/pet/backgrounds/gym.webp', 'check_in_count', 10, 2),
+    ('gym_tour', 'ジム巡り', '5か所のジムにチェックインすると解放', '/images/pet/backgrounds/gym_tour.webp', 'gym_count', 5, 3),
+    ('iron_temple', '鉄の神殿', 'ジムに50回チェックインすると解放', '/images/pet/backgrounds/iron_temple.webp', 'check_in_count', 50, 4);
+
+-- ユーザーの解放済み背景
+CREATE TABLE IF NOT EXISTS user_barn_background_unlocks (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    background_id INT NOT NULL,
+    unlocked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_user_barn_background_unlocks (user_id, background_id)
+);
+
+-- ユーザーが選択中の背景（未選択はdefault）
+CREATE TABLE IF NOT EXISTS user_barn_settings (
+    user_id BIGINT PRIMARY KEY,
+    background_id INT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,16 @@
+-- 合同トレーニング記録へのコメント
+-- status: VISIBLE（表示）/ HIDDEN（管理者が非表示にした）
+CREATE TABLE IF NOT EXISTS workout_comments (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    content VARCHAR(1000) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'VISIBLE',
+    moderation_note VARCHAR(255) NULL,
+    moderated_by BIGINT NULL,
+    moderated_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_workout_comments_record (record_id, created_at),
+    KEY idx_workout_comments_user (user_id),
+    KEY idx_workout_comments_status (status, created_at)
+);
@@ -0,0 +1,19 @@
+-- 頻出クエリ向けの複合インデックス・一意制約
+-- 既存データに重複がある場合は適用に失敗するため、事前に重複行を解消しておくこと
+-- 想定する実行計画は tests/query_plan_test.rs を参照
+
+-- 日付ごとの記録（1ユーザー1日1記録。保存・合同トレーニング・日次EXP上限の集計）
+ALTER TABLE training_records
+    ADD UNIQUE KEY uk_training_records_user_date (user_id, record_date);
+
+-- 種目ごとのセット（セット番号の採番・並び順）
+ALTER TABLE training_sets
+    ADD UNIQUE KEY uk_training_sets_record_exercise_set (record_exercise_id, set_number);
+
+-- ストリーク（ユーザーごとに種類ごと1件）
+ALTER TABLE user_streaks
+    ADD UNIQUE KEY uk_user_streaks_user_type (user_id, streak_type);
+
+-- OAuthログイン（プロバイダーごとのIDでユーザーを特定。ローカルユーザーのNULLは重複可）
+ALTER TABLE users
+    ADD UNIQUE KEY uk_users_oauth (oauth_provider, oauth_id);
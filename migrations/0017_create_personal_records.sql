@@ -0,0 +1,17 @@
+-- 種目ごとの自己ベスト（PR）
+-- record_type: MAX_WEIGHT / MAX_REPS / MAX_ESTIMATED_1RM / MAX_SESSION_VOLUME
+-- exercise_id は is_custom に応じて exercises または user_custom_exercises のID
+CREATE TABLE IF NOT EXISTS personal_records (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    exercise_id BIGINT NOT NULL,
+    is_custom BOOLEAN NOT NULL DEFAULT FALSE,
+    record_type VARCHAR(32) NOT NULL,
+    best_value DOUBLE NOT NULL,
+    weight DOUBLE NULL,
+    reps INT NULL,
+    record_id BIGINT NOT NULL,
+    achieved_on DATE NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_personal_records_user_exercise_type (user_id, is_custom, exercise_id, record_type)
+);
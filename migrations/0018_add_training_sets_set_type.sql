@@ -0,0 +1,5 @@
+-- セットの種類（normal / warmup / dropset / failure）と主観的運動強度（RPE 1〜10）
+-- ウォームアップセットはEXP・ボリュームの集計から除外する
+ALTER TABLE training_sets
+    ADD COLUMN set_type VARCHAR(16) NOT NULL DEFAULT 'normal',
+    ADD COLUMN rpe DOUBLE NULL;
@@ -0,0 +1,11 @@
+-- 自重種目のEXP計算用の体重（kg、未設定の場合は追加重量のみで計算）
+ALTER TABLE users ADD COLUMN body_weight DOUBLE NULL;
+
+-- 自重種目フラグ（EXPは「体重 + 追加重量」× 回数で計算する）
+ALTER TABLE exercises ADD COLUMN is_bodyweight BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE user_custom_exercises ADD COLUMN is_bodyweight BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 代表的な自重種目（その他の種目は管理画面から設定する）
+UPDATE exercises SET is_bodyweight = TRUE
+WHERE name IN ('懸垂', 'チンニング', 'プルアップ', 'ディップス', '腕立て伏せ', 'プッシュアップ',
+               '自重スクワット', 'ハンギングレッグレイズ', 'クランチ', 'シットアップ');
@@ -0,0 +1,2 @@
+-- セットごとのメモ（記録全体のメモは training_records.note）
+ALTER TABLE training_sets ADD COLUMN memo VARCHAR(255) NULL;
@@ -0,0 +1,4 @@
+-- トレーニングの開始・終了時刻（所要時間の表示用）
+ALTER TABLE training_records
+    ADD COLUMN started_at DATETIME NULL,
+    ADD COLUMN finished_at DATETIME NULL;
@@ -0,0 +1,2 @@
+-- スーパーセット（同じ番号の種目を交互に行う組として扱う。NULLは単独の種目）
+ALTER TABLE training_record_exercises ADD COLUMN superset_group INT NULL;
@@ -0,0 +1,2 @@
+-- カスタム種目の難易度係数（EXP計算に使用。NULLはデフォルトの15）
+ALTER TABLE user_custom_exercises ADD COLUMN difficulty_coef INT NULL;
@@ -0,0 +1,13 @@
+-- 記録の保存単位（保存ごとに追加したセットと獲得EXPを残し、直前の保存を取り消せるようにする）
+-- created_record: この保存で記録自体を作成したか（取り消しで空になった記録は削除する）
+CREATE TABLE IF NOT EXISTS training_save_batches (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    exp_earned INT NOT NULL DEFAULT 0,
+    created_record BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_training_save_batches_record (record_id, id)
+);
+
+ALTER TABLE training_sets ADD COLUMN save_batch_id BIGINT NULL;
+ALTER TABLE training_sets ADD KEY idx_training_sets_save_batch (save_batch_id);
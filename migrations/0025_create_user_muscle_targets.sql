@@ -0,0 +1,11 @@
+-- 筋肉グループ別の週間目標（セット数・ボリュームの一方または両方）
+CREATE TABLE IF NOT EXISTS user_muscle_targets (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    muscle_group VARCHAR(20) NOT NULL,
+    target_sets INT NULL,
+    target_volume DOUBLE NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_user_muscle_targets_user_muscle (user_id, muscle_group)
+);
@@ -0,0 +1,15 @@
+-- コイン（累計EXPから算出した獲得数から、使用済みの数を差し引いたものが残高）
+ALTER TABLE user_stats ADD COLUMN coins_spent BIGINT NOT NULL DEFAULT 0;
+
+-- ペットのお世話（ごはん・あそぶ）の履歴（1日の回数制限・クールダウン・ムード上昇に使用）
+CREATE TABLE IF NOT EXISTS pet_care_log (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    pet_id BIGINT NOT NULL,
+    action VARCHAR(20) NOT NULL,
+    coins_spent INT NOT NULL,
+    mood_boost INT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    KEY idx_pet_care_log_pet_created (pet_id, created_at),
+    KEY idx_pet_care_log_user (user_id)
+);
@@ -0,0 +1,43 @@
+-- ショップのアイテム（コインで購入し、ペットのお世話やストリークの保護に使う）
+-- category: food（ごはん）/ toy（あそぶ）/ streak_protection（休んだ日を1日分カバー）
+-- mood_boost: お世話に使った時のムード上昇量（ムードの段階に合わせて20刻み）
+CREATE TABLE IF NOT EXISTS items (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    code VARCHAR(50) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(255) NULL,
+    category VARCHAR(20) NOT NULL,
+    price INT NOT NULL,
+    mood_boost INT NULL,
+    image_path VARCHAR(255) NULL,
+    display_order INT NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    UNIQUE KEY uk_items_code (code)
+);
+
+INSERT IGNORE INTO items (code, name, description, category, price, mood_boost, display_order) VALUES
+    ('protein_cookie', 'プロテインクッキー', 'パートナーのおやつ。ムードが上がる', 'food', 15, 20, 1),
+    ('chicken_breast', 'ささみ', 'パートナーのごちそう。ムードが大きく上がる', 'food', 30, 40, 2),
+    ('rubber_ball', 'ゴムボール', '一緒にあそぶおもちゃ。ムードが上がる', 'toy', 20, 20, 3),
+    ('mini_dumbbell', 'ミニダンベル', 'パートナーお気に入りのおもちゃ。ムードが大きく上がる', 'toy', 40, 40, 4),
+    ('streak_shield', 'ストリークシールド', 'トレーニングを休んだ日を1日分カバーし、連続記録を守る', 'streak_protection', 50, NULL, 5);
+
+-- ユーザーの所持アイテム
+CREATE TABLE IF NOT EXISTS user_inventory (
+    user_id BIGINT NOT NULL,
+    item_id INT NOT NULL,
+    quantity INT NOT NULL DEFAULT 0,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, item_id)
+);
+
+-- ストリークシールドでカバーした日（ストリーク再計算時にトレーニング日として扱う）
+CREATE TABLE IF NOT EXISTS user_streak_protected_dates (
+    user_id BIGINT NOT NULL,
+    protected_date DATE NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, protected_date)
+);
+
+-- お世話に使ったアイテム（コインで行った場合はNULL）
+ALTER TABLE pet_care_log ADD COLUMN item_id INT NULL;
@@ -0,0 +1,33 @@
+-- 覚醒したパートナーの進化先（トレーニングで最も鍛えた筋肉グループによって分岐する）
+-- dominant_muscle: 対象の筋肉グループ（NULLは特定の部位に偏らない場合の進化先）
+CREATE TABLE IF NOT EXISTS pet_evolution_branches (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    pet_type_id INT NOT NULL,
+    code VARCHAR(50) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(255) NULL,
+    dominant_muscle VARCHAR(20) NULL,
+    image_path VARCHAR(255) NULL,
+    display_order INT NOT NULL DEFAULT 0,
+    UNIQUE KEY uk_pet_evolution_branches_type_code (pet_type_id, code)
+);
+
+-- 進化したペットの進化先（未進化はNULL）
+ALTER TABLE pets ADD COLUMN branch_id INT NULL;
+
+-- 既存のペット種類に共通の進化先を登録（画像は未設定の間、覚醒の画像を使う）
+INSERT IGNORE INTO pet_evolution_branches
+    (pet_type_id, code, name, description, dominant_muscle, display_order)
+SELECT pt.id, b.code, CONCAT(pt.name, b.suffix), b.description, b.dominant_muscle, b.display_order
+FROM pet_types pt
+CROSS JOIN (
+    SELECT 'balanced' AS code, '・極' AS suffix,
+           '全身をバランスよく鍛えた進化形' AS description,
+           NULL AS dominant_muscle, 1 AS display_order
+    UNION ALL SELECT 'chest', '・鋼胸', '胸を中心に鍛えた進化形', '胸', 2
+    UNION ALL SELECT 'back', '・鬼背', '背中を中心に鍛えた進化形', '背中', 3
+    UNION ALL SELECT 'shoulders', '・巨肩', '肩を中心に鍛えた進化形', '肩', 4
+    UNION ALL SELECT 'arms', '・豪腕', '腕を中心に鍛えた進化形', '腕', 5
+    UNION ALL SELECT 'legs', '・韋駄天', '脚を中心に鍛えた進化形', '脚', 6
+    UNION ALL SELECT 'abs', '・鉄腹', '腹筋を中心に鍛えた進化形', '腹', 7
+) b;
@@ -0,0 +1,39 @@
+-- パートナーの着せ替えアクセサリー（ペットの画像に重ねて表示する）
+-- slot: back（背中）/ neck（首）/ face（顔）/ head（頭）、1スロットにつき1つまで装着できる
+-- unlock_type: default（最初から解放）/ user_level（ユーザーレベル）/ pet_level（いずれかのパートナーのレベル）
+--              / best_streak（トレーニングの最長連続日数）
+CREATE TABLE IF NOT EXISTS pet_accessories (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    code VARCHAR(50) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(255) NULL,
+    slot VARCHAR(20) NOT NULL,
+    image_path VARCHAR(255) NULL,
+    unlock_type VARCHAR(30) NOT NULL DEFAULT 'default',
+    unlock_value INT NOT NULL DEFAULT 0,
+    display_order INT NOT NULL DEFAULT 0,
+    UNIQUE KEY uk_pet_accessories_code (code)
+);
+
+INSERT IGNORE INTO pet_accessories
+    (code, name, description, slot, image_path, unlock_type, unlock_value, display_order)
+VALUES
+    ('sweatband', 'リストバンド', '最初から使えるトレーニングの相棒', 'neck', '/images/pet/accessories/sweatband.webp', 'default', 0, 1),
+    ('headband', 'ヘアバンド', 'ユーザーLv.5で解放', 'head', '/images/pet/accessories/headband.webp', 'user_level', 5, 2),
+    ('sunglasses', 'サングラス', 'ユーザーLv.15で解放', 'face', '/images/pet/accessories/sunglasses.webp', 'user_level', 15, 3),
+    ('cape', 'マント', 'パートナーをLv.31まで育てると解放', 'back', '/images/pet/accessories/cape.webp', 'pet_level', 31, 4),
+    ('crown', '王冠', 'パートナーをLv.51まで育てると解放', 'head', '/images/pet/accessories/crown.webp', 'pet_level', 51, 5),
+    ('champion_belt', 'チャンピオンベルト', '30日連続でトレーニングすると解放', 'neck', '/images/pet/accessories/champion_belt.webp', 'best_streak', 30, 6);
+
+-- ユーザーの解放済みアクセサリー
+-- pet_id: 装着中のパートナー（未装着はNULL、1つのアクセサリーは1匹にだけ装着できる）
+CREATE TABLE IF NOT EXISTS user_pet_accessories (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    accessory_id INT NOT NULL,
+    pet_id BIGINT NULL,
+    unlocked_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_user_pet_accessories (user_id, accessory_id),
+    INDEX idx_user_pet_accessories_pet (pet_id)
+);
@@ -0,0 +1,2 @@
+-- お別れしたペット（削除せずに残し、EXPの履歴を追えるようにする。NULLは所持中）
+ALTER TABLE pets ADD COLUMN released_at DATETIME NULL;
@@ -0,0 +1,11 @@
+-- パートナーの節目（Lv.10到達・覚醒・30日の付き合いなど）と達成時に付与したボーナスEXP
+CREATE TABLE IF NOT EXISTS pet_milestones (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    pet_id BIGINT NOT NULL,
+    user_id BIGINT NOT NULL,
+    milestone_code VARCHAR(50) NOT NULL,
+    bonus_exp INT NOT NULL DEFAULT 0,
+    achieved_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_pet_milestones_pet_code (pet_id, milestone_code),
+    INDEX idx_pet_milestones_user (user_id)
+);
@@ -0,0 +1,8 @@
+-- パートナーのムードの推移（ムードが変わったときに記録する）
+CREATE TABLE IF NOT EXISTS pet_mood_history (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    pet_id BIGINT NOT NULL,
+    mood_score INT NOT NULL,
+    recorded_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_pet_mood_history_pet_recorded (pet_id, recorded_at)
+);
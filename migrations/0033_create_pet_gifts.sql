@@ -0,0 +1,16 @@
+-- パートナーの贈り物（解放済みのペット種類、または所持アイテム）
+-- 受け取った側が承諾すると解放・所持品に反映される。アイテムは送信時に送り主の所持品から預かり、辞退されたら戻す
+CREATE TABLE IF NOT EXISTS pet_gifts (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    sender_user_id BIGINT NOT NULL,
+    recipient_user_id BIGINT NOT NULL,
+    pet_type_id INT NULL,
+    item_id INT NULL,
+    quantity INT NOT NULL DEFAULT 1,
+    message VARCHAR(200) NULL,
+    status VARCHAR(20) NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    responded_at DATETIME NULL,
+    KEY idx_pet_gifts_recipient (recipient_user_id, status),
+    KEY idx_pet_gifts_sender (sender_user_id)
+);
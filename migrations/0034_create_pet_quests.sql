@@ -0,0 +1,19 @@
+-- パートナーのデイリークエスト（1日ごとに生成し、その日の記録から達成を判定する）
+-- quest_type: train_muscle（部位を鍛える）/ log_sets（セット数）/ log_exercises（種目数）
+-- target_muscle: train_muscle の対象部位（胸・背中・肩・腕・脚・腹）
+-- pet_id: 生成時のアクティブパートナー
+CREATE TABLE IF NOT EXISTS pet_quests (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    pet_id BIGINT NOT NULL,
+    quest_date DATE NOT NULL,
+    quest_type VARCHAR(30) NOT NULL,
+    target_muscle VARCHAR(20) NULL,
+    target_value INT NOT NULL,
+    progress INT NOT NULL DEFAULT 0,
+    exp_reward INT NOT NULL DEFAULT 0,
+    completed_at DATETIME NULL,
+    claimed_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_pet_quests_user_date_type (user_id, quest_date, quest_type)
+);
@@ -0,0 +1,19 @@
+-- Bearer認証のリフレッシュトークン（JWTのjtiで照合する）
+-- family_id: 最初の発行から再発行（ローテーション）で引き継ぐID。使用済みのトークンが再度使われた場合は
+--            盗用とみなして同じファミリーをまとめて失効させる
+-- replaced_by_jti: ローテーションで発行した次のトークン
+CREATE TABLE IF NOT EXISTS refresh_tokens (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    jti VARCHAR(64) NOT NULL,
+    family_id VARCHAR(64) NOT NULL,
+    user_agent VARCHAR(255) NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at DATETIME NULL,
+    expires_at DATETIME NOT NULL,
+    revoked_at DATETIME NULL,
+    replaced_by_jti VARCHAR(64) NULL,
+    UNIQUE KEY uk_refresh_tokens_jti (jti),
+    INDEX idx_refresh_tokens_user (user_id),
+    INDEX idx_refresh_tokens_family (family_id)
+);
@@ -0,0 +1,17 @@
+-- メールアドレスの確認日時（NULLは未確認。メールアドレスを変更すると確認し直す）
+-- 既存のメールアドレスも確認済みとはみなさない（OAuthプロバイダーから取得したものを含む）
+ALTER TABLE users ADD COLUMN email_verified_at DATETIME NULL;
+
+-- メールアドレスの確認リンク
+-- email: 確認するメールアドレス（変更の場合は確認が済むまでusers.emailに反映しない）
+CREATE TABLE IF NOT EXISTS email_verifications (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    token VARCHAR(64) NOT NULL,
+    expires_at DATETIME NOT NULL,
+    verified_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_email_verifications_token (token),
+    INDEX idx_email_verifications_user (user_id)
+);
@@ -0,0 +1,21 @@
+-- 二段階認証（TOTP）の設定
+-- enabled_at: 有効にした日時（設定途中で認証アプリのコードを確認していない場合はNULL）
+-- last_used_step: 最後に使われたコードの時間ステップ（同じコードの再利用を防ぐ）
+CREATE TABLE IF NOT EXISTS user_two_factor (
+    user_id BIGINT PRIMARY KEY,
+    secret VARCHAR(64) NOT NULL,
+    enabled_at DATETIME NULL,
+    last_used_step BIGINT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 二段階認証のバックアップコード（1回だけ使える。コードはSHA-256のハッシュのみ保存する）
+CREATE TABLE IF NOT EXISTS user_backup_codes (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    code_hash CHAR(64) NOT NULL,
+    used_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_user_backup_codes_user (user_id)
+);
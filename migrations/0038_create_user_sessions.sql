@@ -0,0 +1,16 @@
+-- ログイン中のセッション一覧（Cookieセッションをサーバー側で管理し、他の端末をログアウトさせるため）
+-- session_id: セッションに保存するランダムなID
+-- last_seen_at: 最後にセッションを照合した日時（SESSION_REVALIDATE_MINUTESごとに更新）
+-- revoked_at: ログアウト・他の端末からのログアウトで無効にした日時
+CREATE TABLE IF NOT EXISTS user_sessions (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    session_id CHAR(32) NOT NULL,
+    user_id BIGINT NOT NULL,
+    user_agent VARCHAR(255) NULL,
+    ip_address VARCHAR(64) NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at DATETIME NULL,
+    UNIQUE KEY uk_user_sessions_session_id (session_id),
+    INDEX idx_user_sessions_user (user_id)
+);
@@ -0,0 +1,18 @@
+-- スクリプトからのアクセス用APIキー（X-Api-Keyヘッダーで送る）
+-- key_prefix: 一覧で見分けるためのキーの先頭部分（fh_ + 8文字）
+-- key_hash: キーのSHA-256（キー自体は作成時に一度だけ表示し、保存しない）
+-- scopes: カンマ区切りの権限（read / workout:write）
+-- revoked_at: 無効にした日時
+CREATE TABLE IF NOT EXISTS user_api_keys (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    name VARCHAR(50) NOT NULL,
+    key_prefix VARCHAR(16) NOT NULL,
+    key_hash CHAR(64) NOT NULL,
+    scopes VARCHAR(255) NOT NULL,
+    last_used_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at DATETIME NULL,
+    UNIQUE KEY uk_user_api_keys_key_hash (key_hash),
+    INDEX idx_user_api_keys_user (user_id)
+);
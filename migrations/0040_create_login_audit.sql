@@ -0,0 +1,20 @@
+-- ログイン履歴（成功・失敗をすべて記録し、ユーザーが不審なアクセスに気付けるようにする）
+-- user_id: 対象ユーザー（存在しないログインIDへの試行はNULL）
+-- login_id: パスワードログインで入力されたログインID
+-- provider: LOCAL（パスワード）またはOAuthプロバイダー（GOOGLE等）
+-- client: web（Cookieセッション）/ token（/api/auth/tokenでのトークン発行）
+-- failure_reason: invalid_credentials / two_factor_required / two_factor_failed（成功時はNULL）
+CREATE TABLE IF NOT EXISTS login_audit (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NULL,
+    login_id VARCHAR(100) NULL,
+    provider VARCHAR(20) NOT NULL,
+    client VARCHAR(10) NOT NULL,
+    success BOOLEAN NOT NULL,
+    failure_reason VARCHAR(30) NULL,
+    ip_address VARCHAR(64) NULL,
+    user_agent VARCHAR(255) NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_login_audit_user (user_id, created_at),
+    INDEX idx_login_audit_created (created_at)
+);
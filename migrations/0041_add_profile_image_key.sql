@@ -0,0 +1,3 @@
+-- アップロードしたプロフィール画像の保存先キー（NULLはOAuthプロバイダーの画像または未設定）
+-- 設定されている間は、OAuthログイン時にプロバイダー側の画像でprofile_image_urlを上書きしない
+ALTER TABLE users ADD COLUMN profile_image_key VARCHAR(255) NULL;
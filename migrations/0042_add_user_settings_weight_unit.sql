@@ -0,0 +1,3 @@
+-- 重量の表示・入力単位（kg / lb）
+-- 記録の重量は単位にかかわらずkgで保存する
+ALTER TABLE user_settings ADD COLUMN weight_unit VARCHAR(2) NOT NULL DEFAULT 'kg';
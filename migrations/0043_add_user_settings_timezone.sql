@@ -0,0 +1,2 @@
+-- 日付の境界を判定するタイムゾーン（IANA名）
+ALTER TABLE user_settings ADD COLUMN timezone VARCHAR(64) NOT NULL DEFAULT 'Asia/Tokyo';
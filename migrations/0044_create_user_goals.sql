@@ -0,0 +1,20 @@
+-- ユーザーの目標
+-- goal_type: ONE_RM（種目の推定1RM）/ BODY_WEIGHT（体重）/ WEEKLY_WORKOUTS（週のトレーニング日数）
+-- exercise_id は ONE_RM のみ。is_custom に応じて exercises または user_custom_exercises のID
+-- start_value: 設定時の推定1RM・体重（進捗の起点。体重は目標が軽ければ減量として判定する）
+-- exp_reward: 達成時に付与する報酬EXP（completed_at を設定したときに付与済み）
+CREATE TABLE IF NOT EXISTS user_goals (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    goal_type VARCHAR(32) NOT NULL,
+    exercise_id BIGINT NULL,
+    is_custom BOOLEAN NOT NULL DEFAULT FALSE,
+    target_value DOUBLE NOT NULL,
+    start_value DOUBLE NULL,
+    deadline DATE NULL,
+    exp_reward INT NOT NULL DEFAULT 0,
+    completed_at DATETIME NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_user_goals_user_completed (user_id, completed_at)
+);
@@ -0,0 +1,15 @@
+-- アカウントデータのエクスポート
+-- status: PENDING（受付済み）/ PROCESSING（作成中）/ COMPLETED（ダウンロード可能）/ FAILED
+-- file_data: 作成したZIP。個人データを含むため公開ストレージには置かず、expires_at を過ぎたらジョブで削除する
+CREATE TABLE IF NOT EXISTS account_exports (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    file_data LONGBLOB NULL,
+    file_size BIGINT NULL,
+    error_message VARCHAR(255) NULL,
+    requested_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at DATETIME NULL,
+    expires_at DATETIME NULL,
+    INDEX idx_account_exports_user (user_id, requested_at)
+);
@@ -0,0 +1,3 @@
+-- アカウントの無効化日時（NULLは有効）
+-- 無効化されたアカウントはログインできず、account_deletion_requests の削除予約（SCHEDULED）に従って削除される
+ALTER TABLE users ADD COLUMN deactivated_at DATETIME NULL;
@@ -0,0 +1,8 @@
+-- 公開プロフィール（GET /api/users/{loginId}/profile）のプライバシー設定
+-- profile_public が FALSE の間は公開しない。公開する項目もそれぞれ本人が選ぶ（既定はすべて非公開）
+ALTER TABLE user_settings
+    ADD COLUMN profile_public BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN show_level BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN show_streak BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN show_pet BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN show_total_volume BOOLEAN NOT NULL DEFAULT FALSE;
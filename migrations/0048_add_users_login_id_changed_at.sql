@@ -0,0 +1,3 @@
+-- ログインIDを最後に変更した日時（NULLは登録時から未変更）
+-- 変更は30日に1回まで（PUT /api/user/login-id）
+ALTER TABLE users ADD COLUMN login_id_changed_at DATETIME NULL;
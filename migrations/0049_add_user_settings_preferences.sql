@@ -0,0 +1,8 @@
+-- ユーザー設定（GET/PUT /api/user/preferences）の表示言語と通知設定
+-- locale: 表示言語（ja / en）
+-- notify_*: 通知の受け取り（送信する機能はそれぞれの通知の実装時に参照する）
+ALTER TABLE user_settings
+    ADD COLUMN locale VARCHAR(10) NOT NULL DEFAULT 'ja',
+    ADD COLUMN notify_workout_comments BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN notify_streak_reminder BOOLEAN NOT NULL DEFAULT TRUE,
+    ADD COLUMN notify_weekly_summary BOOLEAN NOT NULL DEFAULT FALSE;
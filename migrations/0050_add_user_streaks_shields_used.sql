@@ -0,0 +1,6 @@
+-- ストリークシールドの使用状況（GET /api/streak で表示する）
+-- shields_used: これまでに消費したシールドの数（カバーした日数）
+-- last_shield_used_date: 最後にシールドでカバーした日
+ALTER TABLE user_streaks
+    ADD COLUMN shields_used INT NOT NULL DEFAULT 0,
+    ADD COLUMN last_shield_used_date DATE NULL;
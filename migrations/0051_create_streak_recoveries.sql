@@ -0,0 +1,24 @@
+-- ストリークの修復（POST /api/streak/recover）
+-- 中休みの許容日数を1日だけ超えて途切れた場合、48時間以内ならコインまたはEXPを払って元に戻せる
+-- broken_streak: 途切れる前のストリーク（修復できない途切れ方・修復済みの場合はNULL）
+-- broken_missed_date: 休んだ日（修復時にトレーニングストリークではカバーした日として記録する）
+-- broken_at: 途切れた日時
+ALTER TABLE user_streaks
+    ADD COLUMN broken_streak INT NULL,
+    ADD COLUMN broken_missed_date DATE NULL,
+    ADD COLUMN broken_at DATETIME NULL;
+
+-- ストリークの修復履歴
+-- payment_type: coins / exp
+CREATE TABLE IF NOT EXISTS streak_recoveries (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    streak_type VARCHAR(20) NOT NULL,
+    missed_date DATE NOT NULL,
+    streak_before INT NOT NULL,
+    streak_after INT NOT NULL,
+    payment_type VARCHAR(10) NOT NULL,
+    cost INT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_streak_recoveries_user (user_id, created_at)
+);
@@ -0,0 +1,33 @@
+-- 実績（バッジ）のマスタ
+-- condition_type: TOTAL_WORKOUTS（記録した日数）/ SESSION_VOLUME（1回の記録の総挙上量kg、ウォームアップを除く）
+--                 / TRAINING_STREAK・LOGIN_STREAK（最長ストリーク）/ PET_LEVEL（パートナーの最高レベル）
+-- threshold: 達成に必要な値
+CREATE TABLE IF NOT EXISTS achievements (
+    id INT AUTO_INCREMENT PRIMARY KEY,
+    code VARCHAR(50) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(255) NULL,
+    condition_type VARCHAR(30) NOT NULL,
+    threshold BIGINT NOT NULL,
+    icon_path VARCHAR(255) NULL,
+    display_order INT NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    UNIQUE KEY uk_achievements_code (code)
+);
+
+INSERT IGNORE INTO achievements (code, name, description, condition_type, threshold, display_order) VALUES
+    ('first_workout', 'はじめの一歩', 'はじめてトレーニングを記録した', 'TOTAL_WORKOUTS', 1, 1),
+    ('workouts_100', '100回の積み重ね', 'トレーニングを100日記録した', 'TOTAL_WORKOUTS', 100, 2),
+    ('session_volume_10000', '1万キロの日', '1日の総挙上量が10,000kgに達した', 'SESSION_VOLUME', 10000, 3),
+    ('training_streak_7', '1週間継続', 'トレーニングストリークが7日に達した', 'TRAINING_STREAK', 7, 4),
+    ('training_streak_30', '30日継続', 'トレーニングストリークが30日に達した', 'TRAINING_STREAK', 30, 5),
+    ('login_streak_30', '毎日ログイン', 'ログインストリークが30日に達した', 'LOGIN_STREAK', 30, 6),
+    ('pet_level_30', '頼れる相棒', 'パートナーがLv.30に成長した', 'PET_LEVEL', 30, 7);
+
+-- ユーザーが達成した実績
+CREATE TABLE IF NOT EXISTS user_achievements (
+    user_id BIGINT NOT NULL,
+    achievement_id INT NOT NULL,
+    achieved_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, achievement_id)
+);
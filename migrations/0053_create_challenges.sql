@@ -0,0 +1,29 @@
+-- ウィークリーチャレンジ（週（月曜始まり）ごとに全ユーザー共通で生成し、その週の記録から達成を判定する）
+-- challenge_type: training_days（トレーニング日数）/ total_volume（総挙上量kg）/ total_sets（セット数）
+--                 / muscle_days（部位を鍛えた日数）
+-- target_muscle: muscle_days の対象部位（胸・背中・肩・腕・脚・腹）
+CREATE TABLE IF NOT EXISTS challenges (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    week_start DATE NOT NULL,
+    challenge_type VARCHAR(30) NOT NULL,
+    target_muscle VARCHAR(20) NULL,
+    target_value INT NOT NULL,
+    exp_reward INT NOT NULL DEFAULT 0,
+    coin_reward INT NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE KEY uk_challenges_week_type (week_start, challenge_type)
+);
+
+-- ユーザーごとのチャレンジの進捗
+CREATE TABLE IF NOT EXISTS user_challenge_progress (
+    user_id BIGINT NOT NULL,
+    challenge_id BIGINT NOT NULL,
+    progress INT NOT NULL DEFAULT 0,
+    completed_at DATETIME NULL,
+    claimed_at DATETIME NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, challenge_id)
+);
+
+-- EXP以外で獲得したコイン（チャレンジの報酬など）
+ALTER TABLE user_stats ADD COLUMN coins_awarded BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,17 @@
+-- ランキングへの参加（既定は不参加）
+ALTER TABLE user_settings ADD COLUMN show_on_leaderboard BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- ランキング（GET /api/leaderboards/{type}）
+-- リクエストのたびに記録を集計しないよう、ジョブが定期的に集計して置き換える
+-- board_type: weekly_exp（今週の獲得EXP）/ weekly_volume（今週の総挙上量kg）/ longest_streak（トレーニングの最長ストリーク）
+-- period_start: 週間ランキングの週の月曜日（longest_streakはNULL）
+CREATE TABLE IF NOT EXISTS leaderboard_rankings (
+    board_type VARCHAR(20) NOT NULL,
+    user_id BIGINT NOT NULL,
+    period_start DATE NULL,
+    score BIGINT NOT NULL,
+    rank_position INT NOT NULL,
+    refreshed_at DATETIME NOT NULL,
+    PRIMARY KEY (board_type, user_id),
+    INDEX idx_leaderboard_rankings_rank (board_type, rank_position)
+);
@@ -0,0 +1,14 @@
+-- EXPの増減履歴（記録の保存・削除・ログインボーナス・デイリーリワード・管理者の変更など）
+-- amount: 実際に増減したEXP（累計EXPが0未満にならないよう丸めた後の値）
+-- balance_after: 増減後の累計EXP
+-- reference_id: 記録ID・目標ID・チャレンジIDなど（sourceごとに異なる、ないものはNULL）
+CREATE TABLE IF NOT EXISTS exp_transactions (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    amount BIGINT NOT NULL,
+    balance_after BIGINT NOT NULL,
+    source VARCHAR(30) NOT NULL,
+    reference_id BIGINT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_exp_transactions_user_created (user_id, created_at)
+);
@@ -0,0 +1,26 @@
+-- コインを累計EXPからの算出から、user_stats.coinsの残高管理に切り替える
+-- これまでEXPから算出していた枚数は獲得済み（coins_awarded）に含め、残高に引き継ぐ
+-- coins_rewarded_level: レベルアップ報酬を付与済みのレベル（記録の削除で下がったレベルに再び上がっても二重に付与しない）
+ALTER TABLE user_stats
+    ADD COLUMN coins BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN coins_rewarded_level INT NOT NULL DEFAULT 1;
+
+UPDATE user_stats
+SET coins_awarded = coins_awarded + FLOOR(GREATEST(total_exp, 0) / 100),
+    coins_rewarded_level = level;
+
+UPDATE user_stats SET coins = GREATEST(coins_awarded - coins_spent, 0);
+
+-- コインの増減履歴（ログインボーナス・チャレンジ・レベルアップでの獲得、ショップ・お世話・ストリーク修復での使用）
+-- amount: 獲得はプラス、使用はマイナス
+-- reference_id: チャレンジID・アイテムID・ペットIDなど（sourceごとに異なる、ないものはNULL）
+CREATE TABLE IF NOT EXISTS coin_transactions (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    user_id BIGINT NOT NULL,
+    amount BIGINT NOT NULL,
+    balance_after BIGINT NOT NULL,
+    source VARCHAR(30) NOT NULL,
+    reference_id BIGINT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_coin_transactions_user_created (user_id, created_at)
+);
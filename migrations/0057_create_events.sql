@@ -0,0 +1,17 @@
+-- 期間限定イベント（開催期間中は記録の保存・デイリーリワードのEXPに倍率をかける）
+-- start_date / end_date: 開催期間（日本時間、両端を含む）
+-- special_pet_type_id: 開催期間中に記録・デイリーリワードを受け取ると解放されるペット（pet_types.unlock_type = 'event'）
+CREATE TABLE IF NOT EXISTS events (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    code VARCHAR(50) NOT NULL UNIQUE,
+    name VARCHAR(100) NOT NULL,
+    description VARCHAR(500) NULL,
+    banner_image_path VARCHAR(255) NULL,
+    start_date DATE NOT NULL,
+    end_date DATE NOT NULL,
+    exp_multiplier DOUBLE NOT NULL DEFAULT 1.0,
+    special_pet_type_id INT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    INDEX idx_events_period (start_date, end_date)
+);
@@ -0,0 +1,17 @@
+-- EXPの上限・係数（1行のみ、管理者が変更すると全インスタンスに反映する）
+CREATE TABLE IF NOT EXISTS exp_config (
+    id TINYINT PRIMARY KEY,
+    daily_limit INT NOT NULL,
+    past_days_threshold INT NOT NULL,
+    past_exp_multiplier DOUBLE NOT NULL,
+    past_limit_multiplier DOUBLE NOT NULL,
+    max_exp_per_set INT NOT NULL,
+    exp_coefficient DOUBLE NOT NULL,
+    updated_by BIGINT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT IGNORE INTO exp_config
+    (id, daily_limit, past_days_threshold, past_exp_multiplier, past_limit_multiplier,
+     max_exp_per_set, exp_coefficient)
+VALUES (1, 50000, 2, 0.25, 0.5, 2000, 1.0);
@@ -0,0 +1,27 @@
+-- デイリーリワードの日ごとの報酬（1日目から連続した日を登録し、最終日を受け取ると1日目に戻る）
+CREATE TABLE IF NOT EXISTS daily_reward_config (
+    day INT PRIMARY KEY,
+    reward_type VARCHAR(20) NOT NULL COMMENT 'exp, coins, item',
+    amount INT NOT NULL,
+    item_id INT NULL,
+    is_big_reward BOOLEAN NOT NULL DEFAULT FALSE,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    CONSTRAINT fk_daily_reward_config_item FOREIGN KEY (item_id) REFERENCES items(id)
+);
+
+-- これまでの14日間のEXP報酬
+INSERT IGNORE INTO daily_reward_config (day, reward_type, amount, is_big_reward) VALUES
+    (1, 'exp', 200, FALSE),
+    (2, 'exp', 200, FALSE),
+    (3, 'exp', 200, FALSE),
+    (4, 'exp', 200, FALSE),
+    (5, 'exp', 200, FALSE),
+    (6, 'exp', 200, FALSE),
+    (7, 'exp', 500, TRUE),
+    (8, 'exp', 200, FALSE),
+    (9, 'exp', 200, FALSE),
+    (10, 'exp', 200, FALSE),
+    (11, 'exp', 200, FALSE),
+    (12, 'exp', 200, FALSE),
+    (13, 'exp', 200, FALSE),
+    (14, 'exp', 1000, TRUE);
@@ -0,0 +1,13 @@
+-- ペットの成長曲線（1行のみ、管理者が変更すると保存済みのレベルを再計算して全インスタンスに反映する）
+CREATE TABLE IF NOT EXISTS pet_exp_config (
+    id TINYINT PRIMARY KEY,
+    base_exp DOUBLE NOT NULL,
+    exponent DOUBLE NOT NULL,
+    updated_by BIGINT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT IGNORE INTO pet_exp_config (id, base_exp, exponent) VALUES (1, 16.0, 2.3);
+
+-- 起動時の再計算でステージが戻された進化済みのペットを進化の姿に戻す
+UPDATE pets SET stage = 4, updated_at = NOW() WHERE branch_id IS NOT NULL AND stage <> 4;
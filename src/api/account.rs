@@ -0,0 +1,895 @@
+//! アカウント管理API（退会リクエスト）
+//!
+//! 退会は即時削除ではなく、以下の流れで行う。
+//! 1. DELETE /api/user/account で退会をリクエスト（活動サマリーと確認リンクをメール送信）
+//! 2. メールの確認リンクをクリックした時点から30日間の猶予期間を開始
+//! 3. 猶予期間の終了後、バックグラウンドジョブがデータを削除
+//!
+//! 乗っ取られたセッションだけでは削除を確定できないようにするため、確認リンクを必須とする。
+//!
+//! POST /api/user/account/deactivate でアカウントを無効化することもできる。
+//! 無効化したアカウントはログインできなくなり、30日後に同じジョブで削除される。
+//! 無効化中にログインすると、POST /api/user/account/reactivate で削除を取り消して再開できる。
+
+use actix_web::{delete, get, post, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::api::auth::{get_redirect_url, verify_password_hash};
+use crate::auth::session::{
+    clear_current_user, get_current_user, set_current_user, take_pending_reactivation, Session,
+};
+use crate::config::AppConfig;
+use crate::db::models::User;
+use crate::error::AppError;
+use crate::mailer::{MailMessage, Mailer};
+use crate::middleware::session_refresh::{bump_session_epoch, load_user};
+use crate::shared_store::SharedStore;
+
+/// 確認リンクの有効期限（時間）
+const CONFIRMATION_TOKEN_HOURS: i64 = 24;
+
+/// 削除確定から実際に削除されるまでの猶予期間（日）
+pub const DELETION_GRACE_DAYS: i64 = 30;
+
+/// 確認リンクのクリック待ち
+const STATUS_AWAITING_CONFIRMATION: &str = "AWAITING_CONFIRMATION";
+/// 削除予約済み（猶予期間中）
+pub const STATUS_SCHEDULED: &str = "SCHEDULED";
+const STATUS_CANCELLED: &str = "CANCELLED";
+pub const STATUS_COMPLETED: &str = "COMPLETED";
+
+/// 退会リクエスト（メールアドレス未登録のアカウントはパスワードで本人確認する）
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountRequest {
+    pub password: Option<String>,
+}
+
+/// 退会リクエストの状態
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDeletionStatusResponse {
+    /// NONE / AWAITING_CONFIRMATION / SCHEDULED
+    pub status: String,
+    pub requested_at: Option<chrono::NaiveDateTime>,
+    pub token_expires_at: Option<chrono::NaiveDateTime>,
+    pub scheduled_deletion_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(sqlx::FromRow)]
+struct DeletionRequestRow {
+    id: i64,
+    status: String,
+    requested_at: chrono::NaiveDateTime,
+    token_expires_at: Option<chrono::NaiveDateTime>,
+    scheduled_deletion_at: Option<chrono::NaiveDateTime>,
+}
+
+/// 有効な退会リクエスト（確認待ちは期限内のもののみ）を取得
+async fn find_active_request(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Option<DeletionRequestRow>, AppError> {
+    let row = sqlx::query_as::<_, DeletionRequestRow>(
+        r#"SELECT id, status, requested_at, token_expires_at, scheduled_deletion_at
+           FROM account_deletion_requests
+           WHERE user_id = ?
+             AND (status = ? OR (status = ? AND token_expires_at > NOW()))
+           ORDER BY id DESC
+           LIMIT 1"#,
+    )
+    .bind(user_id)
+    .bind(STATUS_SCHEDULED)
+    .bind(STATUS_AWAITING_CONFIRMATION)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// DELETE /api/user/account - 退会をリクエスト
+#[delete("/user/account")]
+async fn request_account_deletion(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    mailer: web::Data<Mailer>,
+    session: Session,
+    body: Option<web::Json<DeleteAccountRequest>>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let user: User = sqlx::query_as(
+        r#"SELECT id, login_id, password, email, display_name, gender, birthday,
+           profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at
+           FROM users WHERE id = ?"#,
+    )
+    .bind(session_user.id)
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if let Some(existing) = find_active_request(pool.get_ref(), user.id).await? {
+        if existing.status == STATUS_SCHEDULED {
+            return Err(AppError::BadRequest(
+                "既にアカウントの削除が予約されています".to_string(),
+            ));
+        }
+        // 確認待ちのリクエストは新しいリンクで置き換える
+        sqlx::query(
+            r#"UPDATE account_deletion_requests
+               SET status = ?, confirmation_token = NULL, cancelled_at = NOW()
+               WHERE id = ?"#,
+        )
+        .bind(STATUS_CANCELLED)
+        .bind(existing.id)
+        .execute(pool.get_ref())
+        .await?;
+    }
+
+    let email = user.email.as_deref().filter(|e| !e.is_empty());
+
+    let Some(email) = email else {
+        // メールアドレスがない場合はパスワードの再入力を確認の代わりとする
+        let stored_hash = user
+            .password
+            .as_deref()
+            .filter(|h| !h.is_empty())
+            .ok_or_else(|| {
+                AppError::BadRequest(
+                    "メールアドレスが登録されていないため、お問い合わせフォームから退会をご依頼ください"
+                        .to_string(),
+                )
+            })?;
+        let password = body
+            .as_ref()
+            .and_then(|b| b.password.as_deref())
+            .ok_or_else(|| AppError::BadRequest("パスワードを入力してください".to_string()))?;
+        if !verify_password_hash(password, stored_hash) {
+            return Err(AppError::BadRequest("パスワードが正しくありません".to_string()));
+        }
+
+        sqlx::query(
+            r#"INSERT INTO account_deletion_requests
+               (user_id, status, confirmed_at, scheduled_deletion_at)
+               VALUES (?, ?, NOW(), NOW() + INTERVAL ? DAY)"#,
+        )
+        .bind(user.id)
+        .bind(STATUS_SCHEDULED)
+        .bind(DELETION_GRACE_DAYS)
+        .execute(pool.get_ref())
+        .await?;
+
+        return Ok(HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "status": STATUS_SCHEDULED,
+            "emailSent": false,
+        })));
+    };
+
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+
+    sqlx::query(
+        r#"INSERT INTO account_deletion_requests
+           (user_id, confirmation_token, token_expires_at, status)
+           VALUES (?, ?, NOW() + INTERVAL ? HOUR, ?)"#,
+    )
+    .bind(user.id)
+    .bind(&token)
+    .bind(CONFIRMATION_TOKEN_HOURS)
+    .bind(STATUS_AWAITING_CONFIRMATION)
+    .execute(pool.get_ref())
+    .await?;
+
+    let summary = build_activity_summary(pool.get_ref(), &user).await?;
+    let message = MailMessage {
+        to: email.to_string(),
+        subject: "【Fithub】アカウント削除の確認".to_string(),
+        body: deletion_mail_body(&config, &user, &summary, &token),
+    };
+
+    mailer.send(&message).await.map_err(|e| {
+        tracing::error!("Failed to send account deletion mail: {}", e);
+        AppError::InternalError("確認メールの送信に失敗しました".to_string())
+    })?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "status": STATUS_AWAITING_CONFIRMATION,
+        "emailSent": true,
+    })))
+}
+
+#[derive(Deserialize)]
+struct ConfirmDeletionQuery {
+    token: String,
+}
+
+/// GET /api/user/account/deletion/confirm?token=... - メールの確認リンク
+/// ログイン状態に関係なくトークンで確認し、猶予期間を開始する
+#[get("/user/account/deletion/confirm")]
+async fn confirm_account_deletion(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    query: web::Query<ConfirmDeletionQuery>,
+) -> Result<HttpResponse, AppError> {
+    let result = sqlx::query(
+        r#"UPDATE account_deletion_requests
+           SET status = ?, confirmation_token = NULL, confirmed_at = NOW(),
+               scheduled_deletion_at = NOW() + INTERVAL ? DAY
+           WHERE confirmation_token = ? AND status = ? AND token_expires_at > NOW()"#,
+    )
+    .bind(STATUS_SCHEDULED)
+    .bind(DELETION_GRACE_DAYS)
+    .bind(&query.token)
+    .bind(STATUS_AWAITING_CONFIRMATION)
+    .execute(pool.get_ref())
+    .await?;
+
+    let outcome = if result.rows_affected() > 0 {
+        "scheduled"
+    } else {
+        "invalid"
+    };
+
+    Ok(HttpResponse::Found()
+        .append_header((
+            "Location",
+            get_redirect_url(&config, &format!("/login?accountDeletion={}", outcome)),
+        ))
+        .finish())
+}
+
+/// GET /api/user/account/deletion - 退会リクエストの状態
+#[get("/user/account/deletion")]
+async fn get_account_deletion_status(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let response = match find_active_request(pool.get_ref(), session_user.id).await? {
+        Some(row) => AccountDeletionStatusResponse {
+            status: row.status,
+            requested_at: Some(row.requested_at),
+            token_expires_at: row.token_expires_at,
+            scheduled_deletion_at: row.scheduled_deletion_at,
+        },
+        None => AccountDeletionStatusResponse {
+            status: "NONE".to_string(),
+            requested_at: None,
+            token_expires_at: None,
+            scheduled_deletion_at: None,
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/user/account/deletion/cancel - 退会リクエストを取り消す
+#[post("/user/account/deletion/cancel")]
+async fn cancel_account_deletion(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let result = sqlx::query(
+        r#"UPDATE account_deletion_requests
+           SET status = ?, confirmation_token = NULL, cancelled_at = NOW()
+           WHERE user_id = ? AND status IN (?, ?)"#,
+    )
+    .bind(STATUS_CANCELLED)
+    .bind(session_user.id)
+    .bind(STATUS_AWAITING_CONFIRMATION)
+    .bind(STATUS_SCHEDULED)
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound(
+            "取り消せる退会リクエストがありません".to_string(),
+        ));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+// ============================================
+// アカウントの無効化
+// ============================================
+
+/// 無効化されたアカウントの削除予定日時（無効化されていなければNone）
+pub(crate) async fn find_deactivation(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Option<NaiveDateTime>, AppError> {
+    let scheduled: Option<NaiveDateTime> = sqlx::query_scalar(
+        r#"SELECT COALESCE(
+                      (SELECT MAX(r.scheduled_deletion_at) FROM account_deletion_requests r
+                       WHERE r.user_id = u.id AND r.status = ?),
+                      u.deactivated_at + INTERVAL ? DAY)
+           FROM users u
+           WHERE u.id = ? AND u.deactivated_at IS NOT NULL"#,
+    )
+    .bind(STATUS_SCHEDULED)
+    .bind(DELETION_GRACE_DAYS)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(scheduled)
+}
+
+/// POST /api/user/account/deactivate - アカウントを無効化し、30日後の削除を予約する
+/// パスワードを持つアカウントはパスワードで本人確認する
+#[post("/user/account/deactivate")]
+async fn deactivate_account(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    body: Option<web::Json<DeleteAccountRequest>>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let stored_hash: Option<Option<String>> =
+        sqlx::query_scalar("SELECT password FROM users WHERE id = ?")
+            .bind(session_user.id)
+            .fetch_optional(pool.get_ref())
+            .await?;
+    let stored_hash = stored_hash.ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    if let Some(stored_hash) = stored_hash.as_deref().filter(|h| !h.is_empty()) {
+        let password = body
+            .as_ref()
+            .and_then(|b| b.password.as_deref())
+            .ok_or_else(|| AppError::BadRequest("パスワードを入力してください".to_string()))?;
+        if !verify_password_hash(password, stored_hash) {
+            return Err(AppError::BadRequest("パスワードが正しくありません".to_string()));
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+
+    // 確認待ちのリクエストは無効化で置き換え、削除予約済みならその日時を引き継ぐ
+    sqlx::query(
+        r#"UPDATE account_deletion_requests
+           SET status = ?, confirmation_token = NULL, cancelled_at = NOW()
+           WHERE user_id = ? AND status = ?"#,
+    )
+    .bind(STATUS_CANCELLED)
+    .bind(session_user.id)
+    .bind(STATUS_AWAITING_CONFIRMATION)
+    .execute(&mut *tx)
+    .await?;
+    let scheduled: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM account_deletion_requests WHERE user_id = ? AND status = ? LIMIT 1",
+    )
+    .bind(session_user.id)
+    .bind(STATUS_SCHEDULED)
+    .fetch_optional(&mut *tx)
+    .await?;
+    if scheduled.is_none() {
+        sqlx::query(
+            r#"INSERT INTO account_deletion_requests
+               (user_id, status, confirmed_at, scheduled_deletion_at)
+               VALUES (?, ?, NOW(), NOW() + INTERVAL ? DAY)"#,
+        )
+        .bind(session_user.id)
+        .bind(STATUS_SCHEDULED)
+        .bind(DELETION_GRACE_DAYS)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("UPDATE users SET deactivated_at = NOW(), updated_at = NOW() WHERE id = ?")
+        .bind(session_user.id)
+        .execute(&mut *tx)
+        .await?;
+
+    // すべての端末からログアウトさせる（APIキーは無効化中は使えず、再開すると使える）
+    sqlx::query(
+        "UPDATE user_sessions SET revoked_at = NOW() WHERE user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(session_user.id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(session_user.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    bump_session_epoch(&store, &config, session_user.id).await;
+    clear_current_user(&session);
+    session.purge();
+
+    let scheduled_deletion_at = find_deactivation(pool.get_ref(), session_user.id).await?;
+    tracing::info!("[ACCOUNT] user_id={} deactivated", session_user.id);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "scheduledDeletionAt": scheduled_deletion_at,
+    })))
+}
+
+/// POST /api/user/account/reactivate - 無効化したアカウントを再開する
+/// 無効化中のログイン（パスワード・二段階認証・OAuth）に成功した直後のセッションでのみ使える
+#[post("/user/account/reactivate")]
+async fn reactivate_account(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let user_id = take_pending_reactivation(&session).ok_or_else(|| {
+        AppError::Unauthorized("もう一度ログインしてからアカウントを再開してください".to_string())
+    })?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE users SET deactivated_at = NULL, updated_at = NOW() WHERE id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"UPDATE account_deletion_requests
+           SET status = ?, cancelled_at = NOW()
+           WHERE user_id = ? AND status = ?"#,
+    )
+    .bind(STATUS_CANCELLED)
+    .bind(user_id)
+    .bind(STATUS_SCHEDULED)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    // 猶予期間の終了後に削除されたアカウントは再開できない
+    let user = load_user(pool.get_ref(), user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    set_current_user(&session, user)
+        .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+    tracing::info!("[ACCOUNT] user_id={} reactivated", user_id);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "redirect": "/dashboard"
+    })))
+}
+
+// ============================================
+// 活動サマリー
+// ============================================
+
+struct ActivitySummary {
+    level: i32,
+    total_exp: i64,
+    record_days: i64,
+    first_record_date: Option<NaiveDate>,
+    last_record_date: Option<NaiveDate>,
+    total_sets: i64,
+}
+
+async fn build_activity_summary(pool: &MySqlPool, user: &User) -> Result<ActivitySummary, AppError> {
+    let stats: Option<(i32, i64)> =
+        sqlx::query_as("SELECT level, total_exp FROM user_stats WHERE user_id = ?")
+            .bind(user.id)
+            .fetch_optional(pool)
+            .await?;
+    let (level, total_exp) = stats.unwrap_or((1, 0));
+
+    let (record_days, first_record_date, last_record_date): (i64, Option<NaiveDate>, Option<NaiveDate>) =
+        sqlx::query_as(
+            r#"SELECT COUNT(DISTINCT record_date), MIN(record_date), MAX(record_date)
+               FROM training_records WHERE user_id = ?"#,
+        )
+        .bind(user.id)
+        .fetch_one(pool)
+        .await?;
+
+    let total_sets: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*) FROM training_sets ts
+           JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ?"#,
+    )
+    .bind(user.id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(ActivitySummary {
+        level,
+        total_exp,
+        record_days,
+        first_record_date,
+        last_record_date,
+        total_sets,
+    })
+}
+
+fn deletion_mail_body(
+    config: &AppConfig,
+    user: &User,
+    summary: &ActivitySummary,
+    token: &str,
+) -> String {
+    let name = user.display_name.as_deref().unwrap_or(&user.login_id);
+    let format_date = |d: Option<NaiveDate>| {
+        d.map(|d| d.format("%Y/%m/%d").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    let registered = user
+        .created_at
+        .map(|d| d.format("%Y/%m/%d").to_string())
+        .unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "{name} 様\n\n\
+         Fithubのアカウント削除リクエストを受け付けました。\n\n\
+         ■ これまでの記録\n\
+         ・登録日: {registered}\n\
+         ・レベル: Lv.{level}（累計EXP: {total_exp}）\n\
+         ・トレーニング記録: {record_days}日分（最初の記録: {first} / 最後の記録: {last}）\n\
+         ・記録したセット数: {total_sets}\n\n\
+         ■ データのエクスポート\n\
+         削除前にデータを保存する場合は、ログインした状態で次のURLを開いてエクスポートを作成してください。\n\
+         作成が完了すると、設定画面からZIPファイルをダウンロードできます。\n\
+         {base}/api/user/export\n\n\
+         ■ 削除の確定\n\
+         次のリンクを開くと削除が確定し、{grace}日後にアカウントとすべてのデータが削除されます。\n\
+         {base}/api/user/account/deletion/confirm?token={token}\n\
+         （リンクの有効期限: {hours}時間）\n\n\
+         削除が確定した後も、{grace}日以内であれば設定画面から取り消せます。\n\
+         このリクエストに心当たりがない場合はリンクを開かず、パスワードを変更してください。\n",
+        name = name,
+        registered = registered,
+        level = summary.level,
+        total_exp = summary.total_exp,
+        record_days = summary.record_days,
+        first = format_date(summary.first_record_date),
+        last = format_date(summary.last_record_date),
+        total_sets = summary.total_sets,
+        base = config.app_base_url,
+        grace = DELETION_GRACE_DAYS,
+        token = token,
+        hours = CONFIRMATION_TOKEN_HOURS,
+    )
+}
+
+// ============================================
+// データ削除
+// ============================================
+
+/// ユーザーと関連データを1つのトランザクションで削除（猶予期間終了後にジョブから呼ばれる）
+/// 途中で失敗した場合は何も削除せず、次回のジョブで再試行する
+/// ユーザーに紐付くテーブルを追加した場合はここにも追加すること
+pub async fn purge_user_data(pool: &MySqlPool, user_id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+
+    // 関連する全てのデータを順番に削除（外部キー制約のため）
+    // 記録へのコメント（自分の投稿・自分の記録へのコメント）
+    sqlx::query(
+        r#"DELETE c FROM workout_comments c
+           LEFT JOIN training_records tr ON c.record_id = tr.id
+           WHERE c.user_id = ? OR tr.user_id = ?"#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 記録の保存単位
+    sqlx::query(
+        r#"DELETE b FROM training_save_batches b
+           INNER JOIN training_records tr ON b.record_id = tr.id
+           WHERE tr.user_id = ?"#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 記録・パートナーごとの獲得EXP（自分の記録・自分のパートナーの分）
+    sqlx::query(
+        r#"DELETE e FROM training_record_pet_exp e
+           LEFT JOIN training_records tr ON e.record_id = tr.id
+           LEFT JOIN pets p ON e.pet_id = p.id
+           WHERE tr.user_id = ? OR p.user_id = ?"#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 1. トレーニングセット（training_record_exercises経由）
+    sqlx::query(
+        r#"DELETE ts FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ?"#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 2. トレーニングレコード種目
+    sqlx::query(
+        r#"DELETE tre FROM training_record_exercises tre
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ?"#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 3. トレーニングレコード
+    sqlx::query("DELETE FROM training_records WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 合同トレーニングの招待（送信・受信とも）
+    sqlx::query(
+        "DELETE FROM workout_partner_invites WHERE inviter_user_id = ? OR invitee_user_id = ?",
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 記録の下書き
+    sqlx::query("DELETE FROM workout_drafts WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 週間セット数目標
+    sqlx::query("DELETE FROM user_set_targets WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 筋肉グループ別の週間目標
+    sqlx::query("DELETE FROM user_muscle_targets WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 目標
+    sqlx::query("DELETE FROM user_goals WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // データのエクスポート
+    sqlx::query("DELETE FROM account_exports WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 所持アイテム・ストリークシールドでカバーした日
+    sqlx::query("DELETE FROM user_inventory WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM user_streak_protected_dates WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // ペットのお世話の履歴
+    sqlx::query("DELETE FROM pet_care_log WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // パートナーの贈り物（送った・受け取った）
+    sqlx::query("DELETE FROM pet_gifts WHERE sender_user_id = ? OR recipient_user_id = ?")
+        .bind(user_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // パートナーのデイリークエスト
+    sqlx::query("DELETE FROM pet_quests WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // パートナーの節目
+    sqlx::query("DELETE FROM pet_milestones WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // パートナーのアクセサリー（解放状況・装着状況）
+    sqlx::query("DELETE FROM user_pet_accessories WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 小屋の背景（解放状況・選択中の背景）
+    sqlx::query("DELETE FROM user_barn_background_unlocks WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM user_barn_settings WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // パートナーの機嫌の履歴・パートナー（手放したパートナーを含む）・種類の解放状況
+    sqlx::query(
+        r#"DELETE h FROM pet_mood_history h
+           INNER JOIN pets p ON h.pet_id = p.id
+           WHERE p.user_id = ?"#,
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query("DELETE FROM pets WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM user_pet_unlocks WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 自己ベスト
+    sqlx::query("DELETE FROM personal_records WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 4. トレーニング種目タグ
+    sqlx::query("DELETE FROM training_exercise_tags WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 5. トレーニングタグ
+    sqlx::query("DELETE FROM training_tags WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 6. ユーザー種目デフォルトタグ
+    sqlx::query("DELETE FROM user_exercise_default_tags WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 7. ユーザーカスタム種目
+    sqlx::query("DELETE FROM user_custom_exercises WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Bearer認証のリフレッシュトークン
+    sqlx::query("DELETE FROM refresh_tokens WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // APIキー
+    sqlx::query("DELETE FROM user_api_keys WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // ログイン中のセッション一覧
+    sqlx::query("DELETE FROM user_sessions WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // ログイン履歴
+    sqlx::query("DELETE FROM login_audit WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 二段階認証の設定・バックアップコード
+    sqlx::query("DELETE FROM user_backup_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM user_two_factor WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // メールアドレスの確認リンク
+    sqlx::query("DELETE FROM email_verifications WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 達成した実績・ウィークリーチャレンジの進捗
+    sqlx::query("DELETE FROM user_achievements WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM user_challenge_progress WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // ランキングの集計結果
+    sqlx::query("DELETE FROM leaderboard_rankings WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // EXP・コインの増減履歴
+    sqlx::query("DELETE FROM exp_transactions WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM coin_transactions WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // ストリーク・修復履歴・ログインボーナスの受け取り履歴
+    sqlx::query("DELETE FROM user_streaks WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM streak_recoveries WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM user_login_history WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // ジムのチェックイン
+    sqlx::query("DELETE FROM gym_check_ins WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // EXPの異常検知の記録
+    sqlx::query("DELETE FROM exp_anomalies WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // ユーザー設定
+    sqlx::query("DELETE FROM user_settings WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 8. ユーザー統計
+    sqlx::query("DELETE FROM user_stats WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 9. 最後にユーザーを削除
+    sqlx::query("DELETE FROM users WHERE id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(request_account_deletion)
+        .service(confirm_account_deletion)
+        .service(get_account_deletion_status)
+        .service(cancel_account_deletion)
+        .service(deactivate_account)
+        .service(reactivate_account);
+}
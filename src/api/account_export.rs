@@ -0,0 +1,507 @@
+//! アカウントデータのエクスポートAPI
+//!
+//! GET /api/user/export でエクスポートを受け付け、プロフィール・トレーニング記録・目標・
+//! パートナー・ストリーク・ログインボーナスをJSONファイルにまとめたZIPをバックグラウンドで作成する。
+//! 作成状況は GET /api/user/export/{id} で確認し、完了後に /download からダウンロードする。
+//! 個人データを含むためZIPは公開ストレージではなくDBに保存し、有効期限を過ぎたらジョブで削除する。
+
+use std::io::{Cursor, Write};
+
+use actix_web::{get, http::header, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use sqlx::MySqlPool;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::api::goal::fetch_goals;
+use crate::api::streak::{fetch_user_today, fetch_weight_unit};
+use crate::api::workout::{annotate_weight_unit, fetch_records_for_user, RecordFilter};
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::User;
+use crate::error::AppError;
+
+const STATUS_PENDING: &str = "PENDING";
+const STATUS_PROCESSING: &str = "PROCESSING";
+const STATUS_COMPLETED: &str = "COMPLETED";
+const STATUS_FAILED: &str = "FAILED";
+
+/// 作成したZIPをダウンロードできる期間
+const EXPORT_EXPIRY_DAYS: i64 = 7;
+/// 1ユーザーあたりのエクスポート上限（24時間）
+const MAX_EXPORTS_PER_DAY: i64 = 5;
+/// この時間を過ぎても作成中のエクスポートは失敗とする（作成中にサーバーが停止した場合）
+const STALE_PROCESSING_MINUTES: i64 = 60;
+/// この時間を過ぎても受付済みのままのエクスポートはジョブが作成する
+const STALE_PENDING_MINUTES: i64 = 5;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountExportDto {
+    id: i64,
+    status: String,
+    requested_at: String,
+    completed_at: Option<String>,
+    /// ダウンロードの有効期限（完了時のみ）
+    expires_at: Option<String>,
+    /// ZIPのサイズ（バイト、完了時のみ）
+    file_size: Option<i64>,
+    /// 完了時のみ
+    download_url: Option<String>,
+    error_message: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct AccountExportRow {
+    id: i64,
+    status: String,
+    file_size: Option<i64>,
+    error_message: Option<String>,
+    requested_at: NaiveDateTime,
+    completed_at: Option<NaiveDateTime>,
+    expires_at: Option<NaiveDateTime>,
+}
+
+impl From<AccountExportRow> for AccountExportDto {
+    fn from(row: AccountExportRow) -> Self {
+        let format = |at: NaiveDateTime| at.format("%Y-%m-%dT%H:%M:%S").to_string();
+        Self {
+            download_url: (row.status == STATUS_COMPLETED)
+                .then(|| format!("/api/user/export/{}/download", row.id)),
+            id: row.id,
+            status: row.status,
+            requested_at: format(row.requested_at),
+            completed_at: row.completed_at.map(format),
+            expires_at: row.expires_at.map(format),
+            file_size: row.file_size,
+            error_message: row.error_message,
+        }
+    }
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+struct PetExportRow {
+    id: i64,
+    name: String,
+    pet_type: Option<String>,
+    stage: i32,
+    level: i32,
+    total_exp: i64,
+    mood_score: i32,
+    is_active: bool,
+    created_at: Option<NaiveDateTime>,
+    /// 手放したパートナーのみ
+    released_at: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+struct StreakExportRow {
+    streak_type: String,
+    current_streak: i32,
+    best_streak: i32,
+    last_active_date: Option<NaiveDate>,
+    grace_days_used: i32,
+    shields_used: i32,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+struct LoginRewardExportRow {
+    login_date: NaiveDate,
+    reward_day: Option<i32>,
+    bonus_claimed: bool,
+    exp_earned: i64,
+}
+
+// ============================================
+// ZIPの作成
+// ============================================
+
+/// ZIPに含めるファイル（ファイル名と内容）
+async fn collect_export_files(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Vec<(&'static str, serde_json::Value)>, AppError> {
+    let user: User = sqlx::query_as(
+        r#"SELECT id, login_id, password, email, display_name, gender, birthday,
+           profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at
+           FROM users WHERE id = ?"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    let body_weight: Option<f64> = sqlx::query_scalar("SELECT body_weight FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    let stats: Option<(i32, i64)> =
+        sqlx::query_as("SELECT level, total_exp FROM user_stats WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    let settings: Option<(i32, String, String, String)> = sqlx::query_as(
+        r#"SELECT grace_days_allowed, heatmap_mode, weight_unit, timezone
+           FROM user_settings WHERE user_id = ?"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    let profile = serde_json::json!({
+        "exportedAt": chrono::Utc::now().to_rfc3339(),
+        "profile": {
+            "loginId": user.login_id,
+            "displayName": user.display_name,
+            "email": user.email,
+            "gender": user.gender,
+            "birthday": user.birthday,
+            "bodyWeight": body_weight,
+            "profileImageUrl": user.profile_image_url,
+            "oauthProvider": user.oauth_provider,
+            "createdAt": user.created_at,
+        },
+        "stats": stats.map(|(level, total_exp)| {
+            serde_json::json!({ "level": level, "totalExp": total_exp })
+        }),
+        "settings": settings.map(|(grace_days_allowed, heatmap_mode, weight_unit, timezone)| {
+            serde_json::json!({
+                "graceDaysAllowed": grace_days_allowed,
+                "heatmapMode": heatmap_mode,
+                "weightUnit": weight_unit,
+                "timezone": timezone,
+            })
+        }),
+    });
+
+    let mut records =
+        fetch_records_for_user(pool, user_id, &RecordFilter::default(), None, None).await?;
+    annotate_weight_unit(&mut records, fetch_weight_unit(pool, user_id).await?);
+
+    let today = fetch_user_today(pool, user_id).await?;
+    let goals = fetch_goals(&mut *pool.acquire().await?, user_id, today).await?;
+
+    let pets: Vec<PetExportRow> = sqlx::query_as(
+        r#"SELECT p.id, p.name, CAST(pt.name AS CHAR) AS pet_type, p.stage, p.level,
+                  p.total_exp, p.mood_score, p.is_active, p.created_at, p.released_at
+           FROM pets p
+           LEFT JOIN pet_types pt ON pt.id = p.pet_type_id
+           WHERE p.user_id = ?
+           ORDER BY p.created_at, p.id"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let streaks: Vec<StreakExportRow> = sqlx::query_as(
+        r#"SELECT streak_type, current_streak, best_streak, last_active_date, grace_days_used,
+                  shields_used
+           FROM user_streaks WHERE user_id = ?
+           ORDER BY streak_type"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let login_rewards: Vec<LoginRewardExportRow> = sqlx::query_as(
+        r#"SELECT login_date, reward_day, bonus_claimed,
+                  CAST(COALESCE(exp_earned, 0) AS SIGNED) AS exp_earned
+           FROM user_login_history WHERE user_id = ?
+           ORDER BY login_date"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(vec![
+        ("profile.json", profile),
+        ("training_records.json", to_json(records)?),
+        ("goals.json", to_json(goals)?),
+        ("pets.json", to_json(pets)?),
+        ("streaks.json", to_json(streaks)?),
+        ("login_rewards.json", to_json(login_rewards)?),
+    ])
+}
+
+fn to_json<T: Serialize>(value: T) -> Result<serde_json::Value, AppError> {
+    serde_json::to_value(value)
+        .map_err(|e| AppError::InternalError(format!("Failed to serialize export: {}", e)))
+}
+
+fn write_zip(files: &[(&'static str, serde_json::Value)]) -> zip::result::ZipResult<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, value) in files {
+        zip.start_file(*name, options)?;
+        let json = serde_json::to_vec_pretty(value).map_err(std::io::Error::from)?;
+        zip.write_all(&json)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn build_export(pool: &MySqlPool, user_id: i64) -> Result<Vec<u8>, AppError> {
+    let files = collect_export_files(pool, user_id).await?;
+    // 圧縮はCPUを使うためブロッキング用のスレッドで行う
+    tokio::task::spawn_blocking(move || write_zip(&files))
+        .await
+        .map_err(|e| AppError::InternalError(format!("Export task failed: {}", e)))?
+        .map_err(|e| AppError::InternalError(format!("Failed to create ZIP: {}", e)))
+}
+
+/// 受付済みのエクスポートを作成する（作成中・作成済みのものは何もしない）
+/// 失敗した場合はFAILEDにしてログに残す
+pub(crate) async fn process_export(pool: &MySqlPool, export_id: i64) {
+    let claimed = sqlx::query("UPDATE account_exports SET status = ? WHERE id = ? AND status = ?")
+        .bind(STATUS_PROCESSING)
+        .bind(export_id)
+        .bind(STATUS_PENDING)
+        .execute(pool)
+        .await;
+    match claimed {
+        Ok(result) if result.rows_affected() > 0 => {}
+        Ok(_) => return,
+        Err(e) => {
+            tracing::error!("Failed to start account export {}: {}", export_id, e);
+            return;
+        }
+    }
+
+    let built =
+        match sqlx::query_scalar::<_, i64>("SELECT user_id FROM account_exports WHERE id = ?")
+            .bind(export_id)
+            .fetch_one(pool)
+            .await
+        {
+            Ok(user_id) => build_export(pool, user_id).await,
+            Err(e) => Err(e.into()),
+        };
+
+    let result = match built {
+        Ok(data) => {
+            tracing::info!(
+                "[ACCOUNT EXPORT] export_id={} completed ({} bytes)",
+                export_id,
+                data.len()
+            );
+            sqlx::query(
+                r#"UPDATE account_exports
+                   SET status = ?, file_data = ?, file_size = ?, completed_at = NOW(),
+                       expires_at = NOW() + INTERVAL ? DAY
+                   WHERE id = ?"#,
+            )
+            .bind(STATUS_COMPLETED)
+            .bind(&data)
+            .bind(data.len() as i64)
+            .bind(EXPORT_EXPIRY_DAYS)
+            .bind(export_id)
+            .execute(pool)
+            .await
+        }
+        Err(e) => {
+            tracing::error!("Account export {} failed: {}", export_id, e);
+            sqlx::query(
+                r#"UPDATE account_exports SET status = ?, error_message = ?, completed_at = NOW()
+                   WHERE id = ?"#,
+            )
+            .bind(STATUS_FAILED)
+            .bind("エクスポートの作成に失敗しました。もう一度お試しください")
+            .bind(export_id)
+            .execute(pool)
+            .await
+        }
+    };
+    if let Err(e) = result {
+        tracing::error!("Failed to save account export {}: {}", export_id, e);
+    }
+}
+
+// ============================================
+// ジョブ
+// ============================================
+
+/// 作成中にサーバーが停止したエクスポートを失敗にし、
+/// 受付後に作成が始まらなかったエクスポートを作成する。作成した件数を返す
+pub async fn resume_stale_exports(pool: &MySqlPool) -> Result<usize, AppError> {
+    sqlx::query(
+        r#"UPDATE account_exports SET status = ?, error_message = ?, completed_at = NOW()
+           WHERE status = ? AND requested_at < NOW() - INTERVAL ? MINUTE"#,
+    )
+    .bind(STATUS_FAILED)
+    .bind("エクスポートの作成が中断されました。もう一度お試しください")
+    .bind(STATUS_PROCESSING)
+    .bind(STALE_PROCESSING_MINUTES)
+    .execute(pool)
+    .await?;
+
+    let pending: Vec<i64> = sqlx::query_scalar(
+        r#"SELECT id FROM account_exports
+           WHERE status = ? AND requested_at < NOW() - INTERVAL ? MINUTE
+           ORDER BY id"#,
+    )
+    .bind(STATUS_PENDING)
+    .bind(STALE_PENDING_MINUTES)
+    .fetch_all(pool)
+    .await?;
+    for &export_id in &pending {
+        process_export(pool, export_id).await;
+    }
+    Ok(pending.len())
+}
+
+/// 有効期限を過ぎたエクスポートと古い失敗履歴を削除し、削除した件数を返す
+pub async fn purge_expired_exports(pool: &MySqlPool) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        r#"DELETE FROM account_exports
+           WHERE expires_at < NOW()
+              OR (status = ? AND requested_at < NOW() - INTERVAL ? DAY)"#,
+    )
+    .bind(STATUS_FAILED)
+    .bind(EXPORT_EXPIRY_DAYS)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+async fn find_export(
+    pool: &MySqlPool,
+    user_id: i64,
+    export_id: i64,
+) -> Result<Option<AccountExportRow>, AppError> {
+    let row = sqlx::query_as(
+        r#"SELECT id, status, file_size, error_message, requested_at, completed_at, expires_at
+           FROM account_exports WHERE id = ? AND user_id = ?"#,
+    )
+    .bind(export_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+/// GET /api/user/export
+/// エクスポートを受け付けて202を返す（受付済み・作成中のものがあればそれを返す）
+#[get("/user/export")]
+async fn request_account_export(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let in_progress: Option<i64> = sqlx::query_scalar(
+        r#"SELECT id FROM account_exports
+           WHERE user_id = ? AND status IN (?, ?)
+           ORDER BY id DESC LIMIT 1"#,
+    )
+    .bind(session_user.id)
+    .bind(STATUS_PENDING)
+    .bind(STATUS_PROCESSING)
+    .fetch_optional(pool.get_ref())
+    .await?;
+
+    let export_id = match in_progress {
+        Some(export_id) => export_id,
+        None => {
+            let recent: i64 = sqlx::query_scalar(
+                r#"SELECT COUNT(*) FROM account_exports
+                   WHERE user_id = ? AND requested_at > NOW() - INTERVAL 1 DAY"#,
+            )
+            .bind(session_user.id)
+            .fetch_one(pool.get_ref())
+            .await?;
+            if recent >= MAX_EXPORTS_PER_DAY {
+                return Err(AppError::TooManyRequests(format!(
+                    "エクスポートは24時間に{}回までです",
+                    MAX_EXPORTS_PER_DAY
+                )));
+            }
+
+            let result = sqlx::query("INSERT INTO account_exports (user_id, status) VALUES (?, ?)")
+                .bind(session_user.id)
+                .bind(STATUS_PENDING)
+                .execute(pool.get_ref())
+                .await?;
+            let export_id = result.last_insert_id() as i64;
+            tracing::info!(
+                "[ACCOUNT EXPORT] user_id={} requested export_id={}",
+                session_user.id,
+                export_id
+            );
+
+            let pool = pool.get_ref().clone();
+            tokio::spawn(async move { process_export(&pool, export_id).await });
+            export_id
+        }
+    };
+
+    let export = find_export(pool.get_ref(), session_user.id, export_id)
+        .await?
+        .ok_or_else(|| AppError::InternalError("Created export not found".to_string()))?;
+    Ok(HttpResponse::Accepted().json(AccountExportDto::from(export)))
+}
+
+/// GET /api/user/export/{id}
+#[get("/user/export/{id}")]
+async fn get_account_export(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let export = find_export(pool.get_ref(), session_user.id, path.into_inner())
+        .await?
+        .ok_or_else(|| AppError::NotFound("Export not found".to_string()))?;
+    Ok(HttpResponse::Ok().json(AccountExportDto::from(export)))
+}
+
+/// GET /api/user/export/{id}/download
+#[get("/user/export/{id}/download")]
+async fn download_account_export(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let export: Option<(Vec<u8>, NaiveDateTime)> = sqlx::query_as(
+        r#"SELECT file_data, requested_at FROM account_exports
+           WHERE id = ? AND user_id = ? AND status = ? AND file_data IS NOT NULL
+             AND expires_at > NOW()"#,
+    )
+    .bind(path.into_inner())
+    .bind(session_user.id)
+    .bind(STATUS_COMPLETED)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    let (data, requested_at) =
+        export.ok_or_else(|| AppError::NotFound("Export not found or expired".to_string()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/zip")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"fithub-export-{}.zip\"",
+                requested_at.format("%Y%m%d")
+            ),
+        ))
+        .insert_header((header::CACHE_CONTROL, "no-store"))
+        .body(data))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(request_account_export)
+        .service(get_account_export)
+        .service(download_account_export);
+}
@@ -0,0 +1,248 @@
+//! 実績（バッジ）APIハンドラ
+//!
+//! 累計のトレーニング日数・1日の総挙上量・最長ストリーク・パートナーのレベルで達成する実績を
+//! achievementsテーブルで管理し、達成したものをuser_achievementsに記録する。
+//! 達成の判定は記録の保存・ログインボーナスの受け取り・パートナーのEXP加算のたびに
+//! 関係する条件のみ行い、実績一覧の取得時はすべての条件で行う。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::HashMap;
+
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::Achievement;
+use crate::error::AppError;
+
+/// 記録した日数
+pub(crate) const ACHIEVEMENT_TOTAL_WORKOUTS: &str = "TOTAL_WORKOUTS";
+/// 1回の記録の総挙上量（kg、ウォームアップを除く）
+pub(crate) const ACHIEVEMENT_SESSION_VOLUME: &str = "SESSION_VOLUME";
+/// トレーニングの最長ストリーク
+pub(crate) const ACHIEVEMENT_TRAINING_STREAK: &str = "TRAINING_STREAK";
+/// ログインの最長ストリーク
+pub(crate) const ACHIEVEMENT_LOGIN_STREAK: &str = "LOGIN_STREAK";
+/// パートナーの最高レベル
+pub(crate) const ACHIEVEMENT_PET_LEVEL: &str = "PET_LEVEL";
+
+const ALL_CONDITIONS: [&str; 5] = [
+    ACHIEVEMENT_TOTAL_WORKOUTS,
+    ACHIEVEMENT_SESSION_VOLUME,
+    ACHIEVEMENT_TRAINING_STREAK,
+    ACHIEVEMENT_LOGIN_STREAK,
+    ACHIEVEMENT_PET_LEVEL,
+];
+
+const ACHIEVEMENT_COLUMNS: &str = "a.id, a.code, a.name, a.description, a.condition_type, \
+                                   a.threshold, a.icon_path, a.display_order, a.is_active";
+
+// ============================================
+// DTOs
+// ============================================
+
+/// 新たに達成した実績（記録の保存・ログインボーナスのレスポンスに含める）
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementDto {
+    code: String,
+    name: String,
+    description: Option<String>,
+    icon_path: Option<String>,
+}
+
+impl From<Achievement> for AchievementDto {
+    fn from(achievement: Achievement) -> Self {
+        Self {
+            code: achievement.code,
+            name: achievement.name,
+            description: achievement.description,
+            icon_path: achievement.icon_path,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AchievementProgressDto {
+    code: String,
+    name: String,
+    description: Option<String>,
+    icon_path: Option<String>,
+    condition_type: String,
+    achieved: bool,
+    achieved_at: Option<String>,
+    /// 達成条件の現在値（目標値で頭打ち）
+    current: i64,
+    /// 達成条件の目標値
+    target: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AchievementsResponse {
+    achieved_count: usize,
+    total_count: usize,
+    achievements: Vec<AchievementProgressDto>,
+}
+
+#[derive(sqlx::FromRow)]
+struct AchievementRow {
+    #[sqlx(flatten)]
+    achievement: Achievement,
+    achieved_at: Option<NaiveDateTime>,
+}
+
+// ============================================
+// 実績の判定
+// ============================================
+
+/// 達成条件の現在値（未知の条件は0）
+async fn current_value(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    condition_type: &str,
+) -> Result<i64, AppError> {
+    let sql = match condition_type {
+        ACHIEVEMENT_TOTAL_WORKOUTS => "SELECT COUNT(*) FROM training_records WHERE user_id = ?",
+        ACHIEVEMENT_SESSION_VOLUME => {
+            r#"SELECT CAST(COALESCE(MAX(volume), 0) AS SIGNED) FROM (
+                   SELECT SUM(ts.weight * ts.reps) AS volume FROM training_sets ts
+                   INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+                   INNER JOIN training_records tr ON tre.record_id = tr.id
+                   WHERE tr.user_id = ? AND ts.set_type <> 'warmup'
+                   GROUP BY tr.id
+               ) volumes"#
+        }
+        ACHIEVEMENT_TRAINING_STREAK => {
+            r#"SELECT CAST(COALESCE(MAX(best_streak), 0) AS SIGNED) FROM user_streaks
+               WHERE user_id = ? AND streak_type = 'training'"#
+        }
+        ACHIEVEMENT_LOGIN_STREAK => {
+            r#"SELECT CAST(COALESCE(MAX(best_streak), 0) AS SIGNED) FROM user_streaks
+               WHERE user_id = ? AND streak_type = 'login'"#
+        }
+        ACHIEVEMENT_PET_LEVEL => {
+            "SELECT CAST(COALESCE(MAX(level), 0) AS SIGNED) FROM pets WHERE user_id = ?"
+        }
+        _ => return Ok(0),
+    };
+    let value: i64 = sqlx::query_scalar(sql)
+        .bind(user_id)
+        .fetch_one(conn)
+        .await?;
+    Ok(value)
+}
+
+/// 指定した条件の実績のうち、新たに達成したものを記録して返す
+pub(crate) async fn award_achievements(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    condition_types: &[&str],
+) -> Result<Vec<AchievementDto>, AppError> {
+    let mut earned = Vec::new();
+    for &condition_type in condition_types {
+        let pending: Vec<Achievement> = sqlx::query_as(&format!(
+            r#"SELECT {} FROM achievements a
+               LEFT JOIN user_achievements ua ON ua.achievement_id = a.id AND ua.user_id = ?
+               WHERE a.condition_type = ? AND a.is_active = TRUE AND ua.achievement_id IS NULL
+               ORDER BY a.display_order ASC, a.id ASC"#,
+            ACHIEVEMENT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(condition_type)
+        .fetch_all(&mut *conn)
+        .await?;
+        if pending.is_empty() {
+            continue;
+        }
+
+        let value = current_value(&mut *conn, user_id, condition_type).await?;
+        for achievement in pending.into_iter().filter(|a| value >= a.threshold) {
+            // 同時に判定されても二重に記録しない
+            let result = sqlx::query(
+                r#"INSERT IGNORE INTO user_achievements (user_id, achievement_id, achieved_at)
+                   VALUES (?, ?, NOW())"#,
+            )
+            .bind(user_id)
+            .bind(achievement.id)
+            .execute(&mut *conn)
+            .await?;
+            if result.rows_affected() > 0 {
+                tracing::info!("[ACHIEVEMENT] user_id={} {}", user_id, achievement.code);
+                earned.push(AchievementDto::from(achievement));
+            }
+        }
+    }
+    Ok(earned)
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/achievements
+/// 実績の一覧（達成日時と、未達成のものは進捗を含む）
+#[get("/achievements")]
+async fn get_achievements(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let mut tx = pool.begin().await?;
+    award_achievements(&mut tx, session_user.id, &ALL_CONDITIONS).await?;
+    tx.commit().await?;
+
+    let mut conn = pool.acquire().await?;
+    // 公開を終了した実績も達成済みなら表示する
+    let rows: Vec<AchievementRow> = sqlx::query_as(&format!(
+        r#"SELECT {}, ua.achieved_at FROM achievements a
+           LEFT JOIN user_achievements ua ON ua.achievement_id = a.id AND ua.user_id = ?
+           WHERE a.is_active = TRUE OR ua.achieved_at IS NOT NULL
+           ORDER BY a.display_order ASC, a.id ASC"#,
+        ACHIEVEMENT_COLUMNS
+    ))
+    .bind(session_user.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut values: HashMap<&str, i64> = HashMap::new();
+    for condition_type in ALL_CONDITIONS {
+        values.insert(
+            condition_type,
+            current_value(&mut conn, session_user.id, condition_type).await?,
+        );
+    }
+
+    let achievements: Vec<AchievementProgressDto> = rows
+        .into_iter()
+        .map(|row| {
+            let a = row.achievement;
+            let current = values.get(a.condition_type.as_str()).copied().unwrap_or(0);
+            AchievementProgressDto {
+                achieved: row.achieved_at.is_some(),
+                achieved_at: row
+                    .achieved_at
+                    .map(|at| at.format("%Y-%m-%dT%H:%M:%S").to_string()),
+                current: current.min(a.threshold),
+                target: a.threshold,
+                code: a.code,
+                name: a.name,
+                description: a.description,
+                icon_path: a.icon_path,
+                condition_type: a.condition_type,
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(AchievementsResponse {
+        achieved_count: achievements.iter().filter(|a| a.achieved).count(),
+        total_count: achievements.len(),
+        achievements,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_achievements);
+}
@@ -1,23 +1,54 @@
 //! 管理者専用API
 //! login_id = "220618" のユーザーのみアクセス可能
 
-use actix_session::Session;
 use actix_web::{web, HttpResponse};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 
-use crate::auth::session::get_current_user;
+use crate::api::daily_reward::{
+    fetch_reward_schedule, RewardScheduleRow, REWARD_TYPE_COINS, REWARD_TYPE_EXP, REWARD_TYPE_ITEM,
+};
+use crate::api::exercise::{
+    parse_target_muscles, sync_instructions, sync_target_muscles, ExerciseInstructionsDto,
+};
+use crate::api::exp_ledger::{record_exp_transaction, EXP_SOURCE_ADMIN, EXP_SOURCE_LEVEL_CURVE};
+use crate::api::gym::CACHE_KEY_GYM_TAGS;
+use crate::api::shop::find_item_by_code;
+use crate::api::workout_comment::{
+    STATUS_HIDDEN as COMMENT_HIDDEN, STATUS_VISIBLE as COMMENT_VISIBLE,
+};
+use crate::auth::session::{get_current_user, Session, SessionUser};
+use crate::config::{AppConfig, ExpConfig, PetExpConfig};
 use crate::db::models::UserStats;
 use crate::error::AppError;
+use crate::exp_config;
+use crate::level_curve::{self, LevelCurve};
+use crate::middleware::session_refresh::bump_session_epoch;
+use crate::pet_exp_config;
+use crate::shared_store::SharedStore;
 
 /// 特別管理者のログインID
 const SPECIAL_ADMIN_LOGIN_ID: [&str; 1] = ["220618"];
 
 /// 特別管理者かどうかをチェック
-fn is_special_admin(login_id: &str) -> bool {
+pub(crate) fn is_special_admin(login_id: &str) -> bool {
     SPECIAL_ADMIN_LOGIN_ID.contains(&login_id)
 }
 
+/// 管理者（ADMINロールまたは特別管理者）かどうか
+pub(crate) fn is_admin(user: &SessionUser) -> bool {
+    user.role == "ADMIN" || is_special_admin(&user.login_id)
+}
+
+/// ログイン中の特別管理者を取得（それ以外はForbidden）
+fn require_special_admin(session: &Session) -> Result<SessionUser, AppError> {
+    let current_user = get_current_user(session)?;
+    if !is_special_admin(&current_user.login_id) {
+        return Err(AppError::Forbidden("アクセス権限がありません".to_string()));
+    }
+    Ok(current_user)
+}
+
 /// 管理者ユーザー一覧のレスポンス
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -52,13 +83,8 @@ async fn get_users(
     session: Session,
     pool: web::Data<MySqlPool>,
 ) -> Result<HttpResponse, AppError> {
-    // 認証チェック
-    let current_user = get_current_user(&session)?;
-    
     // 特別管理者チェック
-    if !is_special_admin(&current_user.login_id) {
-        return Err(AppError::Forbidden("アクセス権限がありません".to_string()));
-    }
+    require_special_admin(&session)?;
 
     // ユーザー一覧を取得（user_statsと結合）
     let users = sqlx::query_as::<_, (i64, String, Option<String>, i32, i64)>(
@@ -99,19 +125,14 @@ async fn update_user_level(
     path: web::Path<i64>,
     body: web::Json<UpdateLevelRequest>,
 ) -> Result<HttpResponse, AppError> {
-    // 認証チェック
-    let current_user = get_current_user(&session)?;
-    
     // 特別管理者チェック
-    if !is_special_admin(&current_user.login_id) {
-        return Err(AppError::Forbidden("アクセス権限がありません".to_string()));
-    }
+    require_special_admin(&session)?;
 
     let user_id = path.into_inner();
     let new_level = body.level;
 
     // レベルのバリデーション
-    if new_level < 1 || new_level > 1000 {
+    if !(1..=1000).contains(&new_level) {
         return Err(AppError::BadRequest(
             "レベルは1〜1000の範囲で指定してください".to_string(),
         ));
@@ -131,20 +152,23 @@ async fn update_user_level(
     }
 
     // user_statsを更新（存在しない場合は作成）
-    let existing_stats =
-        sqlx::query_scalar::<_, i64>("SELECT id FROM user_stats WHERE user_id = ?")
+    let old_total_exp =
+        sqlx::query_scalar::<_, i64>("SELECT total_exp FROM user_stats WHERE user_id = ?")
             .bind(user_id)
             .fetch_optional(pool.get_ref())
             .await?;
 
-    if existing_stats.is_some() {
-        // 既存レコードを更新
-        sqlx::query("UPDATE user_stats SET level = ?, total_exp = ? WHERE user_id = ?")
-            .bind(new_level)
-            .bind(new_total_exp)
-            .bind(user_id)
-            .execute(pool.get_ref())
-            .await?;
+    if old_total_exp.is_some() {
+        // 既存レコードを更新（現在の曲線で計算したため、曲線バージョンの記録はクリア）
+        sqlx::query(
+            r#"UPDATE user_stats SET level = ?, total_exp = ?, level_curve_version = NULL
+               WHERE user_id = ?"#,
+        )
+        .bind(new_level)
+        .bind(new_total_exp)
+        .bind(user_id)
+        .execute(pool.get_ref())
+        .await?;
     } else {
         // 新規レコードを作成
         sqlx::query("INSERT INTO user_stats (user_id, level, total_exp) VALUES (?, ?, ?)")
@@ -154,6 +178,15 @@ async fn update_user_level(
             .execute(pool.get_ref())
             .await?;
     }
+    record_exp_transaction(
+        &mut *pool.acquire().await?,
+        user_id,
+        new_total_exp - old_total_exp.unwrap_or(0),
+        new_total_exp,
+        EXP_SOURCE_ADMIN,
+        None,
+    )
+    .await?;
 
     // レベル変更に伴うペット解放条件をチェック
     use crate::api::pet::check_and_unlock_pet_types;
@@ -169,11 +202,1190 @@ async fn update_user_level(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// ユーザーのセッションを次のリクエストでDBと再照合させる（ロール変更の即時反映用）
+/// POST /api/admin/users/{user_id}/session-refresh
+async fn refresh_user_sessions(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let user_id = path.into_inner();
+    let user_exists = sqlx::query_scalar::<_, i64>("SELECT id FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(pool.get_ref())
+        .await?;
+    if user_exists.is_none() {
+        return Err(AppError::NotFound("ユーザーが見つかりません".to_string()));
+    }
+
+    bump_session_epoch(&store, &config, user_id).await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// レベル曲線の一覧項目
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelCurveResponse {
+    #[serde(flatten)]
+    pub curve: LevelCurve,
+    pub active: bool,
+    /// 旧曲線のまま移行待ちのユーザー数
+    pub pending_users: i64,
+    /// Lv2・Lv10・Lv50・Lv100に必要な累計EXP（比較用）
+    pub sample_required_exp: Vec<(i32, i64)>,
+}
+
+/// レベル曲線作成リクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLevelCurveRequest {
+    pub quadratic: i64,
+    pub linear: i64,
+    pub constant: i64,
+}
+
+/// ユーザー移行リクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateLevelCurveRequest {
+    /// KEEP_EXP: 累計EXPを維持してレベルを再計算 / KEEP_LEVEL: レベルと進行度を維持してEXPを再計算
+    pub strategy: String,
+}
+
+fn to_level_curve_response(
+    curve: LevelCurve,
+    active: bool,
+    pending_users: i64,
+) -> LevelCurveResponse {
+    LevelCurveResponse {
+        curve,
+        active,
+        pending_users,
+        sample_required_exp: [2, 10, 50, 100]
+            .iter()
+            .map(|&level| (level, curve.required_exp(level)))
+            .collect(),
+    }
+}
+
+/// レベル曲線の一覧を取得
+/// GET /api/admin/level-curves
+async fn get_level_curves(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let pending: Vec<(i32, i64)> = sqlx::query_as(
+        r#"SELECT level_curve_version, COUNT(*) FROM user_stats
+           WHERE level_curve_version IS NOT NULL
+           GROUP BY level_curve_version"#,
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let response: Vec<LevelCurveResponse> = level_curve::fetch_all(pool.get_ref())
+        .await?
+        .into_iter()
+        .map(|(curve, active)| {
+            let pending_users = pending
+                .iter()
+                .find(|(version, _)| !active && *version == curve.version)
+                .map(|(_, count)| *count)
+                .unwrap_or(0);
+            to_level_curve_response(curve, active, pending_users)
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// 新しいレベル曲線を作成して有効化
+/// 既存ユーザーのレベル・EXPは変更せず、移行処理を実行するまで旧曲線の値を保持する
+/// POST /api/admin/level-curves
+async fn create_level_curve(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    body: web::Json<CreateLevelCurveRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let curve = level_curve::activate_new_version(
+        pool.get_ref(),
+        &store,
+        body.quadratic,
+        body.linear,
+        body.constant,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(to_level_curve_response(curve, true, 0)))
+}
+
+/// 旧曲線のユーザーを現在の曲線へ移行
+/// 移行済みユーザーは曲線バージョンがクリアされるため、再実行しても二重に変換されない
+/// POST /api/admin/level-curves/migrate
+async fn migrate_level_curve(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    body: web::Json<MigrateLevelCurveRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let keep_level = match body.strategy.as_str() {
+        "KEEP_EXP" => false,
+        "KEEP_LEVEL" => true,
+        _ => {
+            return Err(AppError::BadRequest(
+                "strategyはKEEP_EXPまたはKEEP_LEVELを指定してください".to_string(),
+            ))
+        }
+    };
+
+    let current = level_curve::current();
+    let old_curves: Vec<LevelCurve> = level_curve::fetch_all(pool.get_ref())
+        .await?
+        .into_iter()
+        .map(|(curve, _)| curve)
+        .collect();
+
+    let targets: Vec<(i64, i64, i32)> = sqlx::query_as(
+        r#"SELECT user_id, total_exp, level_curve_version FROM user_stats
+           WHERE level_curve_version IS NOT NULL"#,
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let mut tx = pool.begin().await?;
+    let mut migrated = 0;
+    let mut migrated_user_ids = Vec::new();
+    for (user_id, total_exp, version) in targets {
+        let new_total_exp = if keep_level {
+            let Some(old) = old_curves.iter().find(|c| c.version == version) else {
+                tracing::warn!("Unknown level curve v{} for user {}", version, user_id);
+                continue;
+            };
+            let level = old.level_for_exp(total_exp);
+            let start = current.required_exp(level);
+            let span = current.required_exp(level + 1) - start;
+            start + (old.progress(total_exp) * span as f64) as i64
+        } else {
+            total_exp
+        };
+
+        sqlx::query(
+            r#"UPDATE user_stats SET level = ?, total_exp = ?, level_curve_version = NULL
+               WHERE user_id = ? AND level_curve_version = ?"#,
+        )
+        .bind(current.level_for_exp(new_total_exp))
+        .bind(new_total_exp)
+        .bind(user_id)
+        .bind(version)
+        .execute(&mut *tx)
+        .await?;
+        record_exp_transaction(
+            &mut tx,
+            user_id,
+            new_total_exp - total_exp,
+            new_total_exp,
+            EXP_SOURCE_LEVEL_CURVE,
+            None,
+        )
+        .await?;
+        migrated += 1;
+        migrated_user_ids.push(user_id);
+    }
+    tx.commit().await?;
+
+    // レベルが上がったユーザーのペット解放条件をチェック
+    use crate::api::pet::check_and_unlock_pet_types;
+    for user_id in migrated_user_ids {
+        let _ = check_and_unlock_pet_types(pool.get_ref(), user_id).await;
+    }
+
+    tracing::info!(
+        "Migrated {} users to level curve v{} ({})",
+        migrated,
+        current.version,
+        body.strategy
+    );
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "version": current.version,
+        "strategy": body.strategy,
+        "migratedUsers": migrated,
+    })))
+}
+
+/// EXP設定を取得
+/// GET /api/admin/exp-config
+async fn get_exp_config(session: Session) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+    Ok(HttpResponse::Ok().json(exp_config::current()))
+}
+
+/// EXP設定を更新（全インスタンスに即時反映）
+/// 変更後に保存・編集した記録から新しい設定で計算し、獲得済みのEXPは再計算しない
+/// PUT /api/admin/exp-config
+async fn update_exp_config(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    body: web::Json<ExpConfig>,
+) -> Result<HttpResponse, AppError> {
+    let admin = require_special_admin(&session)?;
+
+    let config = exp_config::update(pool.get_ref(), &store, body.into_inner(), admin.id).await?;
+    Ok(HttpResponse::Ok().json(config))
+}
+
+/// ペットの成長曲線の更新結果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePetExpConfigResponse {
+    #[serde(flatten)]
+    pub config: PetExpConfig,
+    /// レベル・ステージを再計算したペットの数
+    pub recomputed_pets: u64,
+}
+
+/// ペットの成長曲線を取得
+/// GET /api/admin/pet-exp-config
+async fn get_pet_exp_config(session: Session) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+    Ok(HttpResponse::Ok().json(pet_exp_config::current()))
+}
+
+/// ペットの成長曲線を更新（全インスタンスに即時反映）
+/// 保存済みのペットのレベル・ステージも新しい曲線で再計算する（進化先を選んだペットは進化の姿のまま）
+/// PUT /api/admin/pet-exp-config
+async fn update_pet_exp_config(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    body: web::Json<PetExpConfig>,
+) -> Result<HttpResponse, AppError> {
+    let admin = require_special_admin(&session)?;
+
+    let (config, recomputed_pets) =
+        pet_exp_config::update(pool.get_ref(), &store, body.into_inner(), admin.id).await?;
+    Ok(HttpResponse::Ok().json(UpdatePetExpConfigResponse {
+        config,
+        recomputed_pets,
+    }))
+}
+
+/// デイリーリワードの日数の上限
+const MAX_DAILY_REWARD_DAYS: i32 = 31;
+
+/// デイリーリワードの報酬（管理用）
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminDailyRewardResponse {
+    pub day: i32,
+    pub reward_type: String,
+    pub amount: i32,
+    pub item_code: Option<String>,
+    pub item_name: Option<String>,
+    pub is_big_reward: bool,
+}
+
+impl From<RewardScheduleRow> for AdminDailyRewardResponse {
+    fn from(row: RewardScheduleRow) -> Self {
+        Self {
+            day: row.config.day,
+            reward_type: row.config.reward_type,
+            amount: row.config.amount,
+            item_code: row.item_code,
+            item_name: row.item_name,
+            is_big_reward: row.config.is_big_reward,
+        }
+    }
+}
+
+/// デイリーリワードの報酬の登録・更新リクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminDailyRewardRequest {
+    /// exp, coins, item
+    pub reward_type: String,
+    /// EXP・コインの量、またはアイテムの個数
+    pub amount: i32,
+    /// 報酬の種類がitemの場合のアイテムコード
+    pub item_code: Option<String>,
+    #[serde(default)]
+    pub is_big_reward: bool,
+}
+
+/// デイリーリワードの報酬一覧（1日目から順に）
+/// GET /api/admin/daily-rewards
+async fn get_daily_reward_config(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let schedule = fetch_reward_schedule(&mut *pool.acquire().await?).await?;
+    let rewards: Vec<AdminDailyRewardResponse> = schedule
+        .into_iter()
+        .map(AdminDailyRewardResponse::from)
+        .collect();
+    Ok(HttpResponse::Ok().json(rewards))
+}
+
+/// デイリーリワードの報酬を登録・更新
+/// 日は1日目から連続させるため、登録できるのは既存の日か最終日の翌日のみ
+/// PUT /api/admin/daily-rewards/{day}
+async fn upsert_daily_reward_config(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i32>,
+    body: web::Json<AdminDailyRewardRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let day = path.into_inner();
+    let reward_type = body.reward_type.trim();
+    if ![REWARD_TYPE_EXP, REWARD_TYPE_COINS, REWARD_TYPE_ITEM].contains(&reward_type) {
+        return Err(AppError::BadRequest(
+            "報酬の種類はexp・coins・itemのいずれかを指定してください".to_string(),
+        ));
+    }
+    if body.amount <= 0 {
+        return Err(AppError::BadRequest("報酬の量は1以上を指定してください".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let item_id = if reward_type == REWARD_TYPE_ITEM {
+        let code = body.item_code.as_deref().map(str::trim).unwrap_or_default();
+        let item = find_item_by_code(&mut tx, code)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("アイテムが見つかりません".to_string()))?;
+        Some(item.id)
+    } else {
+        None
+    };
+
+    let last_day: Option<i32> =
+        sqlx::query_scalar("SELECT MAX(day) FROM daily_reward_config FOR UPDATE")
+            .fetch_one(&mut *tx)
+            .await?;
+    let max_day = (last_day.unwrap_or(0) + 1).min(MAX_DAILY_REWARD_DAYS);
+    if day < 1 || day > max_day {
+        return Err(AppError::BadRequest(format!(
+            "日は1〜{}の範囲で指定してください",
+            max_day
+        )));
+    }
+
+    sqlx::query(
+        r#"INSERT INTO daily_reward_config (day, reward_type, amount, item_id, is_big_reward)
+           VALUES (?, ?, ?, ?, ?)
+           ON DUPLICATE KEY UPDATE
+               reward_type = VALUES(reward_type),
+               amount = VALUES(amount),
+               item_id = VALUES(item_id),
+               is_big_reward = VALUES(is_big_reward)"#,
+    )
+    .bind(day)
+    .bind(reward_type)
+    .bind(body.amount)
+    .bind(item_id)
+    .bind(body.is_big_reward)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true, "day": day })))
+}
+
+/// デイリーリワードの最終日を削除（サイクルを1日短くする）
+/// 途中の日を削除すると日が連続しなくなるため、削除できるのは最終日のみ
+/// DELETE /api/admin/daily-rewards/{day}
+async fn delete_daily_reward_config(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i32>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let day = path.into_inner();
+    let mut tx = pool.begin().await?;
+
+    let last_day: Option<i32> =
+        sqlx::query_scalar("SELECT MAX(day) FROM daily_reward_config FOR UPDATE")
+            .fetch_one(&mut *tx)
+            .await?;
+    let Some(last_day) = last_day.filter(|last| *last >= day) else {
+        return Err(AppError::NotFound("報酬が見つかりません".to_string()));
+    };
+    if day != last_day {
+        return Err(AppError::BadRequest("削除できるのは最終日のみです".to_string()));
+    }
+    if last_day <= 1 {
+        return Err(AppError::BadRequest(
+            "デイリーリワードには1日以上の報酬が必要です".to_string(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM daily_reward_config WHERE day = ?")
+        .bind(day)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// 種目作成・更新リクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminExerciseRequest {
+    pub name: String,
+    pub muscle: String,
+    pub muscle_group_id: Option<i32>,
+    pub difficulty: String,
+    pub difficulty_level_id: Option<i32>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub target_muscles: Vec<String>,
+    pub video_path: Option<String>,
+    pub display_order: Option<i32>,
+    /// 自重種目（更新時に未指定の場合は既存の設定を維持）
+    pub is_bodyweight: Option<bool>,
+    /// 解説（更新時に未指定の場合は既存の解説を維持）
+    pub instructions: Option<ExerciseInstructionsDto>,
+}
+
+impl AdminExerciseRequest {
+    fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() || self.muscle.trim().is_empty() {
+            return Err(AppError::BadRequest("種目名と部位は必須です".to_string()));
+        }
+        Ok(())
+    }
+
+    /// ターゲット筋肉を正規化（exercises.target_musclesのカンマ区切り形式と一致させる）
+    fn normalized_target_muscles(&self) -> Vec<String> {
+        parse_target_muscles(&self.target_muscles.join(","))
+    }
+
+    /// 解説を正規化（空行を除外）
+    fn normalized_instructions(&self) -> Result<Option<ExerciseInstructionsDto>, AppError> {
+        self.instructions.as_ref().map(|i| i.normalized()).transpose()
+    }
+}
+
+/// 種目を作成
+/// POST /api/admin/exercises
+async fn create_exercise(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    body: web::Json<AdminExerciseRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+    body.validate()?;
+
+    let target_muscles = body.normalized_target_muscles();
+    let instructions = body.normalized_instructions()?;
+
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        r#"INSERT INTO exercises
+           (name, muscle, muscle_group_id, difficulty, difficulty_level_id, description,
+            target_muscles, video_path, display_order, is_bodyweight)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(body.name.trim())
+    .bind(body.muscle.trim())
+    .bind(body.muscle_group_id)
+    .bind(body.difficulty.trim())
+    .bind(body.difficulty_level_id)
+    .bind(&body.description)
+    .bind(target_muscles.join(","))
+    .bind(&body.video_path)
+    .bind(body.display_order)
+    .bind(body.is_bodyweight.unwrap_or(false))
+    .execute(&mut *tx)
+    .await?;
+
+    let exercise_id = result.last_insert_id() as i64;
+    sync_target_muscles(&mut tx, exercise_id, &target_muscles).await?;
+    if let Some(instructions) = &instructions {
+        sync_instructions(&mut tx, exercise_id, instructions).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true, "id": exercise_id })))
+}
+
+/// 種目を更新
+/// PUT /api/admin/exercises/{exercise_id}
+async fn update_exercise(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+    body: web::Json<AdminExerciseRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+    body.validate()?;
+
+    let exercise_id = path.into_inner();
+    let target_muscles = body.normalized_target_muscles();
+    let instructions = body.normalized_instructions()?;
+
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query(
+        r#"UPDATE exercises
+           SET name = ?, muscle = ?, muscle_group_id = ?, difficulty = ?, difficulty_level_id = ?,
+               description = ?, target_muscles = ?, video_path = ?, display_order = ?,
+               is_bodyweight = COALESCE(?, is_bodyweight)
+           WHERE id = ?"#,
+    )
+    .bind(body.name.trim())
+    .bind(body.muscle.trim())
+    .bind(body.muscle_group_id)
+    .bind(body.difficulty.trim())
+    .bind(body.difficulty_level_id)
+    .bind(&body.description)
+    .bind(target_muscles.join(","))
+    .bind(&body.video_path)
+    .bind(body.display_order)
+    .bind(body.is_bodyweight)
+    .bind(exercise_id)
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        let exists = sqlx::query_scalar::<_, i64>("SELECT id FROM exercises WHERE id = ?")
+            .bind(exercise_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::NotFound("種目が見つかりません".to_string()));
+        }
+    }
+
+    sync_target_muscles(&mut tx, exercise_id, &target_muscles).await?;
+    if let Some(instructions) = &instructions {
+        sync_instructions(&mut tx, exercise_id, instructions).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// 種目を削除（記録で使用中の種目は削除不可）
+/// DELETE /api/admin/exercises/{exercise_id}
+async fn delete_exercise(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let exercise_id = path.into_inner();
+
+    let usage_count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM training_record_exercises WHERE exercise_id = ?",
+    )
+    .bind(exercise_id)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    if usage_count > 0 {
+        return Err(AppError::BadRequest(
+            "トレーニング記録で使用中の種目は削除できません".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM exercise_target_muscles WHERE exercise_id = ?")
+        .bind(exercise_id)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("DELETE FROM exercise_instructions WHERE exercise_id = ?")
+        .bind(exercise_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query("DELETE FROM exercises WHERE id = ?")
+        .bind(exercise_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("種目が見つかりません".to_string()));
+    }
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// EXP異常一覧のクエリ
+#[derive(Debug, Deserialize)]
+pub struct ExpAnomalyQuery {
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// EXP異常のレスポンス
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExpAnomalyResponse {
+    pub id: i64,
+    pub user_id: i64,
+    pub login_id: String,
+    pub display_name: Option<String>,
+    pub anomaly_type: String,
+    pub detected_for: chrono::NaiveDate,
+    pub metric_value: i64,
+    pub detail: Option<serde_json::Value>,
+    pub status: String,
+    pub review_note: Option<String>,
+    pub reviewed_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(sqlx::FromRow)]
+struct ExpAnomalyRow {
+    id: i64,
+    user_id: i64,
+    login_id: String,
+    display_name: Option<String>,
+    anomaly_type: String,
+    detected_for: chrono::NaiveDate,
+    metric_value: i64,
+    detail: Option<String>,
+    status: String,
+    review_note: Option<String>,
+    reviewed_at: Option<chrono::NaiveDateTime>,
+    created_at: chrono::NaiveDateTime,
+}
+
+/// EXP異常のレビューリクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewExpAnomalyRequest {
+    /// CONFIRMED（不正と判断）/ DISMISSED（問題なし）/ PENDING（差し戻し）
+    pub status: String,
+    pub note: Option<String>,
+}
+
+const EXP_ANOMALY_STATUSES: [&str; 3] = ["PENDING", "CONFIRMED", "DISMISSED"];
+
+/// EXP異常一覧を取得（デフォルトは未レビューのみ）
+/// GET /api/admin/exp-anomalies?status=PENDING
+async fn get_exp_anomalies(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    query: web::Query<ExpAnomalyQuery>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let status = query.status.as_deref().unwrap_or("PENDING");
+    if status != "ALL" && !EXP_ANOMALY_STATUSES.contains(&status) {
+        return Err(AppError::BadRequest("不正なステータスです".to_string()));
+    }
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+
+    let rows = sqlx::query_as::<_, ExpAnomalyRow>(
+        r#"SELECT a.id, a.user_id, u.login_id, u.display_name, a.anomaly_type, a.detected_for,
+                  a.metric_value, a.detail, a.status, a.review_note, a.reviewed_at, a.created_at
+           FROM exp_anomalies a
+           JOIN users u ON a.user_id = u.id
+           WHERE (? = 'ALL' OR a.status = ?)
+           ORDER BY a.created_at DESC, a.id DESC
+           LIMIT ?"#,
+    )
+    .bind(status)
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let response: Vec<ExpAnomalyResponse> = rows
+        .into_iter()
+        .map(|row| ExpAnomalyResponse {
+            id: row.id,
+            user_id: row.user_id,
+            login_id: row.login_id,
+            display_name: row.display_name,
+            anomaly_type: row.anomaly_type,
+            detected_for: row.detected_for,
+            metric_value: row.metric_value,
+            detail: row.detail.and_then(|d| serde_json::from_str(&d).ok()),
+            status: row.status,
+            review_note: row.review_note,
+            reviewed_at: row.reviewed_at,
+            created_at: row.created_at,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// EXP異常をレビュー
+/// PUT /api/admin/exp-anomalies/{anomaly_id}
+async fn review_exp_anomaly(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+    body: web::Json<ReviewExpAnomalyRequest>,
+) -> Result<HttpResponse, AppError> {
+    let admin = require_special_admin(&session)?;
+
+    let anomaly_id = path.into_inner();
+    let status = body.status.as_str();
+    if !EXP_ANOMALY_STATUSES.contains(&status) {
+        return Err(AppError::BadRequest("不正なステータスです".to_string()));
+    }
+
+    let result = sqlx::query(
+        r#"UPDATE exp_anomalies
+           SET status = ?, review_note = ?, reviewed_by = ?, reviewed_at = NOW()
+           WHERE id = ?"#,
+    )
+    .bind(status)
+    .bind(&body.note)
+    .bind(admin.id)
+    .bind(anomaly_id)
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("検知結果が見つかりません".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// EXP異常検知を手動実行
+/// POST /api/admin/exp-anomalies/scan
+async fn scan_exp_anomalies(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let detected = crate::jobs::exp_anomaly::detect_anomalies(pool.get_ref()).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true, "detected": detected })))
+}
+
+/// コメント一覧のクエリ
+#[derive(Debug, Deserialize)]
+pub struct CommentModerationQuery {
+    /// VISIBLE / HIDDEN / ALL（省略時はALL）
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// モデレーション用のコメント
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentModerationResponse {
+    pub id: i64,
+    pub record_id: i64,
+    pub user_id: i64,
+    pub login_id: String,
+    pub content: String,
+    pub status: String,
+    pub moderation_note: Option<String>,
+    pub moderated_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// コメントのモデレーションリクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerateCommentRequest {
+    /// VISIBLE（表示に戻す）/ HIDDEN（非表示）
+    pub status: String,
+    pub note: Option<String>,
+}
+
+/// 記録へのコメント一覧を取得（新しい順）
+/// GET /api/admin/comments?status=ALL
+async fn get_comments(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    query: web::Query<CommentModerationQuery>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let status = query.status.as_deref().unwrap_or("ALL");
+    if ![COMMENT_VISIBLE, COMMENT_HIDDEN, "ALL"].contains(&status) {
+        return Err(AppError::BadRequest("不正なステータスです".to_string()));
+    }
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+
+    let comments = sqlx::query_as::<_, CommentModerationResponse>(
+        r#"SELECT c.id, c.record_id, c.user_id, u.login_id, c.content, c.status,
+                  c.moderation_note, c.moderated_at, c.created_at
+           FROM workout_comments c
+           JOIN users u ON c.user_id = u.id
+           WHERE (? = 'ALL' OR c.status = ?)
+           ORDER BY c.created_at DESC, c.id DESC
+           LIMIT ?"#,
+    )
+    .bind(status)
+    .bind(status)
+    .bind(limit)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+/// コメントを非表示にする・表示に戻す
+/// PUT /api/admin/comments/{comment_id}
+async fn moderate_comment(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+    body: web::Json<ModerateCommentRequest>,
+) -> Result<HttpResponse, AppError> {
+    let admin = require_special_admin(&session)?;
+
+    let comment_id = path.into_inner();
+    let status = body.status.as_str();
+    if ![COMMENT_VISIBLE, COMMENT_HIDDEN].contains(&status) {
+        return Err(AppError::BadRequest("不正なステータスです".to_string()));
+    }
+
+    let result = sqlx::query(
+        r#"UPDATE workout_comments
+           SET status = ?, moderation_note = ?, moderated_by = ?, moderated_at = NOW()
+           WHERE id = ?"#,
+    )
+    .bind(status)
+    .bind(&body.note)
+    .bind(admin.id)
+    .bind(comment_id)
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("コメントが見つかりません".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// コメントを削除
+/// DELETE /api/admin/comments/{comment_id}
+async fn delete_comment(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let result = sqlx::query("DELETE FROM workout_comments WHERE id = ?")
+        .bind(path.into_inner())
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("コメントが見つかりません".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// 記録ロック解除リクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockRecordRequest {
+    /// ロック解除する時間（省略時は24時間）
+    pub hours: Option<i64>,
+}
+
+/// ロック期間を過ぎたトレーニング記録を一時的に変更可能にする
+/// PUT /api/admin/records/{record_id}/unlock
+async fn unlock_record(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+    body: Option<web::Json<UnlockRecordRequest>>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let record_id = path.into_inner();
+    let hours = body.and_then(|b| b.hours).unwrap_or(24);
+    if !(1..=24 * 30).contains(&hours) {
+        return Err(AppError::BadRequest(
+            "ロック解除時間は1〜720時間の範囲で指定してください".to_string(),
+        ));
+    }
+
+    let result = sqlx::query(
+        "UPDATE training_records SET unlocked_until = NOW() + INTERVAL ? HOUR WHERE id = ?",
+    )
+    .bind(hours)
+    .bind(record_id)
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("記録が見つかりません".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true, "hours": hours })))
+}
+
+/// 記録のロック解除を取り消す
+/// DELETE /api/admin/records/{record_id}/unlock
+async fn relock_record(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let result = sqlx::query("UPDATE training_records SET unlocked_until = NULL WHERE id = ?")
+        .bind(path.into_inner())
+        .execute(pool.get_ref())
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("記録が見つかりません".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// ジム設備タグ（管理用）
+#[derive(Debug, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminGymTagResponse {
+    pub id: i64,
+    pub name: Option<String>,
+    pub display_order: Option<i32>,
+    #[serde(rename = "group")]
+    pub tag_group: Option<String>,
+    /// このタグが付いたジム数
+    pub gym_count: i64,
+}
+
+/// ジム設備タグ作成・更新リクエスト
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminGymTagRequest {
+    pub name: String,
+    pub group: Option<String>,
+    pub display_order: Option<i32>,
+}
+
+impl AdminGymTagRequest {
+    fn name(&self) -> Result<&str, AppError> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest("タグ名は必須です".to_string()));
+        }
+        Ok(name)
+    }
+
+    /// 空文字のグループは未分類として扱う
+    fn group(&self) -> Option<&str> {
+        self.group.as_deref().map(str::trim).filter(|g| !g.is_empty())
+    }
+}
+
+/// 同名のタグが存在するか（ジム検索はタグ名で絞り込むため重複を許可しない）
+async fn gym_tag_name_exists(
+    pool: &MySqlPool,
+    name: &str,
+    exclude_id: Option<i64>,
+) -> Result<bool, AppError> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tags WHERE name = ? AND id != ?")
+        .bind(name)
+        .bind(exclude_id.unwrap_or(0))
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+/// ジム設備タグ一覧
+/// GET /api/admin/gym-tags
+async fn get_gym_tags(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let tags: Vec<AdminGymTagResponse> = sqlx::query_as(
+        r#"SELECT t.id, t.name, t.display_order, t.tag_group,
+                  (SELECT COUNT(*) FROM gym_tags gt WHERE gt.tag_id = t.id) AS gym_count
+           FROM tags t
+           ORDER BY t.tag_group IS NULL, t.tag_group, t.display_order, t.id"#,
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+/// ジム設備タグを作成
+/// POST /api/admin/gym-tags
+async fn create_gym_tag(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    body: web::Json<AdminGymTagRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let name = body.name()?;
+    if gym_tag_name_exists(pool.get_ref(), name, None).await? {
+        return Err(AppError::BadRequest("同じ名前のタグが既に存在します".to_string()));
+    }
+
+    let result =
+        sqlx::query("INSERT INTO tags (name, display_order, tag_group) VALUES (?, ?, ?)")
+            .bind(name)
+            .bind(body.display_order)
+            .bind(body.group())
+            .execute(pool.get_ref())
+            .await?;
+
+    store.invalidate(&[CACHE_KEY_GYM_TAGS]).await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "id": result.last_insert_id() as i64
+    })))
+}
+
+/// ジム設備タグを更新
+/// PUT /api/admin/gym-tags/{tag_id}
+async fn update_gym_tag(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    path: web::Path<i64>,
+    body: web::Json<AdminGymTagRequest>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let tag_id = path.into_inner();
+    let name = body.name()?;
+
+    let exists = sqlx::query_scalar::<_, i64>("SELECT id FROM tags WHERE id = ?")
+        .bind(tag_id)
+        .fetch_optional(pool.get_ref())
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::NotFound("タグが見つかりません".to_string()));
+    }
+    if gym_tag_name_exists(pool.get_ref(), name, Some(tag_id)).await? {
+        return Err(AppError::BadRequest("同じ名前のタグが既に存在します".to_string()));
+    }
+
+    sqlx::query("UPDATE tags SET name = ?, display_order = ?, tag_group = ? WHERE id = ?")
+        .bind(name)
+        .bind(body.display_order)
+        .bind(body.group())
+        .bind(tag_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    store.invalidate(&[CACHE_KEY_GYM_TAGS]).await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// ジム設備タグを削除（ジムへの紐付けも削除）
+/// DELETE /api/admin/gym-tags/{tag_id}
+async fn delete_gym_tag(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    require_special_admin(&session)?;
+
+    let tag_id = path.into_inner();
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM gym_tags WHERE tag_id = ?")
+        .bind(tag_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let result = sqlx::query("DELETE FROM tags WHERE id = ?")
+        .bind(tag_id)
+        .execute(&mut *tx)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("タグが見つかりません".to_string()));
+    }
+
+    tx.commit().await?;
+
+    store.invalidate(&[CACHE_KEY_GYM_TAGS]).await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
 /// 管理者APIルートを設定
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/admin")
             .route("/users", web::get().to(get_users))
-            .route("/users/{user_id}/level", web::put().to(update_user_level)),
+            .route("/users/{user_id}/level", web::put().to(update_user_level))
+            .route(
+                "/users/{user_id}/session-refresh",
+                web::post().to(refresh_user_sessions),
+            )
+            .route("/level-curves", web::get().to(get_level_curves))
+            .route("/level-curves", web::post().to(create_level_curve))
+            .route("/level-curves/migrate", web::post().to(migrate_level_curve))
+            .route("/exp-config", web::get().to(get_exp_config))
+            .route("/exp-config", web::put().to(update_exp_config))
+            .route("/pet-exp-config", web::get().to(get_pet_exp_config))
+            .route("/pet-exp-config", web::put().to(update_pet_exp_config))
+            .route("/daily-rewards", web::get().to(get_daily_reward_config))
+            .route(
+                "/daily-rewards/{day}",
+                web::put().to(upsert_daily_reward_config),
+            )
+            .route(
+                "/daily-rewards/{day}",
+                web::delete().to(delete_daily_reward_config),
+            )
+            .route("/exercises", web::post().to(create_exercise))
+            .route("/exercises/{exercise_id}", web::put().to(update_exercise))
+            .route("/exercises/{exercise_id}", web::delete().to(delete_exercise))
+            .route("/exp-anomalies", web::get().to(get_exp_anomalies))
+            .route("/exp-anomalies/scan", web::post().to(scan_exp_anomalies))
+            .route("/exp-anomalies/{anomaly_id}", web::put().to(review_exp_anomaly))
+            .route("/comments", web::get().to(get_comments))
+            .route("/comments/{comment_id}", web::put().to(moderate_comment))
+            .route("/comments/{comment_id}", web::delete().to(delete_comment))
+            .route("/records/{record_id}/unlock", web::put().to(unlock_record))
+            .route("/records/{record_id}/unlock", web::delete().to(relock_record))
+            .route("/gym-tags", web::get().to(get_gym_tags))
+            .route("/gym-tags", web::post().to(create_gym_tag))
+            .route("/gym-tags/{tag_id}", web::put().to(update_gym_tag))
+            .route("/gym-tags/{tag_id}", web::delete().to(delete_gym_tag)),
     );
 }
@@ -0,0 +1,227 @@
+//! APIキー管理APIハンドラ
+//!
+//! スクリプトからトレーニング記録をアップロードするためのユーザーごとのAPIキーを発行・一覧・無効化する。
+//! キー自体は作成時に一度だけ返し、DBにはハッシュのみ保存する。
+//! X-Api-Keyヘッダーでの認証はApiKeyAuthミドルウェアが行う。
+
+use actix_web::{delete, get, post, web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::auth::api_key::{
+    display_prefix, generate_api_key, hash_api_key, is_valid_scope, parse_scopes,
+};
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+/// 1ユーザーが持てる有効なAPIキーの上限
+const MAX_API_KEYS_PER_USER: i64 = 10;
+/// キーの名前の最大文字数
+const MAX_API_KEY_NAME_CHARS: usize = 50;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct CreateApiKeyRequest {
+    name: String,
+    scopes: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiKeyDto {
+    id: i64,
+    name: String,
+    /// キーの先頭部分（見分けるためのもの）
+    key_prefix: String,
+    scopes: Vec<String>,
+    last_used_at: Option<String>,
+    created_at: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    api_key: ApiKeyDto,
+    /// APIキー（この応答でのみ返す）
+    key: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct ApiKeyRow {
+    id: i64,
+    name: String,
+    key_prefix: String,
+    scopes: String,
+    last_used_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+}
+
+fn format_datetime(dt: NaiveDateTime) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+impl From<ApiKeyRow> for ApiKeyDto {
+    fn from(row: ApiKeyRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            key_prefix: row.key_prefix,
+            scopes: parse_scopes(&row.scopes),
+            last_used_at: row.last_used_at.map(format_datetime),
+            created_at: format_datetime(row.created_at),
+        }
+    }
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/user/api-keys
+/// 有効なAPIキーの一覧（新しい順）
+#[get("/user/api-keys")]
+async fn list_api_keys(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let rows: Vec<ApiKeyRow> = sqlx::query_as(
+        r#"SELECT id, name, key_prefix, scopes, last_used_at, created_at
+           FROM user_api_keys
+           WHERE user_id = ? AND revoked_at IS NULL
+           ORDER BY created_at DESC, id DESC"#,
+    )
+    .bind(session_user.id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let keys: Vec<ApiKeyDto> = rows.into_iter().map(ApiKeyDto::from).collect();
+    Ok(HttpResponse::Ok().json(keys))
+}
+
+/// POST /api/user/api-keys
+/// APIキーを発行する（キーは応答でのみ返す）
+#[post("/user/api-keys")]
+async fn create_api_key(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<CreateApiKeyRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let name = body.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_API_KEY_NAME_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "名前は1〜{}文字で入力してください",
+            MAX_API_KEY_NAME_CHARS
+        )));
+    }
+    if let Some(scope) = body.scopes.iter().find(|s| !is_valid_scope(s)) {
+        return Err(AppError::BadRequest(format!("不明な権限です: {}", scope)));
+    }
+    let mut scopes: Vec<&str> = body.scopes.iter().map(String::as_str).collect();
+    scopes.sort_unstable();
+    scopes.dedup();
+    if scopes.is_empty() {
+        return Err(AppError::BadRequest(
+            "権限を1つ以上選択してください".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    // 同時に作成されても上限を超えないよう、ユーザーの行をロックして数える
+    sqlx::query("SELECT id FROM users WHERE id = ? FOR UPDATE")
+        .bind(session_user.id)
+        .execute(&mut *tx)
+        .await?;
+    let active: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_api_keys WHERE user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(session_user.id)
+    .fetch_one(&mut *tx)
+    .await?;
+    if active >= MAX_API_KEYS_PER_USER {
+        return Err(AppError::BadRequest(format!(
+            "APIキーは{}個まで作成できます。使っていないキーを削除してください",
+            MAX_API_KEYS_PER_USER
+        )));
+    }
+
+    let key = generate_api_key();
+    let result = sqlx::query(
+        r#"INSERT INTO user_api_keys (user_id, name, key_prefix, key_hash, scopes)
+           VALUES (?, ?, ?, ?, ?)"#,
+    )
+    .bind(session_user.id)
+    .bind(name)
+    .bind(display_prefix(&key))
+    .bind(hash_api_key(&key))
+    .bind(scopes.join(","))
+    .execute(&mut *tx)
+    .await?;
+    let id = result.last_insert_id() as i64;
+
+    let row: ApiKeyRow = sqlx::query_as(
+        r#"SELECT id, name, key_prefix, scopes, last_used_at, created_at
+           FROM user_api_keys WHERE id = ?"#,
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        "[API KEY] user_id={} created key id={} scopes={}",
+        session_user.id,
+        id,
+        row.scopes
+    );
+    Ok(HttpResponse::Created().json(CreateApiKeyResponse {
+        api_key: ApiKeyDto::from(row),
+        key,
+    }))
+}
+
+/// DELETE /api/user/api-keys/{id}
+/// APIキーを無効にする（以降のリクエストは401になる）
+#[delete("/user/api-keys/{id}")]
+async fn revoke_api_key(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let id = path.into_inner();
+
+    let result = sqlx::query(
+        r#"UPDATE user_api_keys SET revoked_at = NOW()
+           WHERE id = ? AND user_id = ? AND revoked_at IS NULL"#,
+    )
+    .bind(id)
+    .bind(session_user.id)
+    .execute(pool.get_ref())
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("API key not found".to_string()));
+    }
+
+    tracing::info!(
+        "[API KEY] user_id={} revoked key id={}",
+        session_user.id,
+        id
+    );
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_api_keys)
+        .service(create_api_key)
+        .service(revoke_api_key);
+}
@@ -1,8 +1,7 @@
 //! 認証APIハンドラ
 //! ログイン、ログアウト、登録、OAuth2フローを処理
 
-use actix_session::Session;
-use actix_web::{get, post, web, HttpResponse};
+use actix_web::{get, post, web, HttpRequest, HttpResponse};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
@@ -10,20 +9,67 @@ use argon2::{
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 
+use crate::api::account::find_deactivation;
+use crate::api::email_verification::{find_verified_email, send_verification_mail};
+use crate::api::login_audit::{
+    record_login_attempt, CLIENT_WEB, FAILURE_ACCOUNT_DEACTIVATED, FAILURE_INVALID_CREDENTIALS,
+    FAILURE_TWO_FACTOR_FAILED, FAILURE_TWO_FACTOR_REQUIRED, PROVIDER_LOCAL,
+};
+use crate::api::profile_image::find_profile_image_key;
+use crate::api::two_factor::{is_two_factor_enabled, verify_second_factor};
+use crate::api::user_session::revoke_session;
+use crate::auth::login_throttle::{
+    check_login_attempt, client_ip, record_login_failure, record_login_success,
+};
+use crate::auth::oauth::{OAuthProviderConfig, OAuthRegistry};
+use crate::auth::oauth_apple::is_private_relay_email;
+use crate::auth::password_policy::{violations_response, PasswordPolicy};
 use crate::auth::session::{
-    clear_current_user, clear_pending_registration, get_pending_registration, set_current_user,
-    set_pending_registration, PendingRegistration, SessionUser,
+    clear_current_user, clear_pending_oauth_registration, clear_pending_registration,
+    clear_pending_two_factor, discard_expired_pending_registrations, get_current_user_opt,
+    get_pending_oauth_registration, get_pending_registration, get_pending_two_factor,
+    get_session_activity, get_session_id, pending_registration_deadline, set_current_user,
+    set_oauth_state, set_pending_oauth_registration, set_pending_reactivation,
+    set_pending_registration, set_pending_two_factor, take_oauth_state, OAuthState,
+    PendingOAuthRegistration, PendingRegistration, Session, SessionUser,
+    PENDING_REGISTRATION_TTL_SECS,
 };
+use crate::captcha::Captcha;
 use crate::config::AppConfig;
 use crate::db::models::User;
 use crate::error::AppError;
+use crate::mailer::Mailer;
+use crate::middleware::session_refresh::load_user;
+use crate::shared_store::SharedStore;
 
 // ============================================
 // ヘルパー関数
 // ============================================
 
+/// パスワードを検証（bcryptとargon2の両方をサポート）
+pub(crate) fn verify_password_hash(password: &str, stored_hash: &str) -> bool {
+    if stored_hash.starts_with("$2a$")
+        || stored_hash.starts_with("$2b$")
+        || stored_hash.starts_with("$2y$")
+    {
+        // bcryptハッシュ（Spring Bootから）
+        bcrypt::verify(password, stored_hash).unwrap_or(false)
+    } else {
+        // Argon2ハッシュ（新規登録）
+        match PasswordHash::new(stored_hash) {
+            Ok(parsed_hash) => Argon2::default()
+                .verify_password(password.as_bytes(), &parsed_hash)
+                .is_ok(),
+            Err(e) => {
+                tracing::error!("Invalid password hash format: {}", e);
+                false
+            }
+        }
+    }
+}
+
 /// フロントエンドURLを考慮したリダイレクトURLを生成
-fn get_redirect_url(config: &AppConfig, path: &str) -> String {
+pub(crate) fn get_redirect_url(config: &AppConfig, path: &str) -> String {
     if config.frontend_url.is_empty() {
         path.to_string()
     } else {
@@ -39,25 +85,139 @@ fn get_redirect_url(config: &AppConfig, path: &str) -> String {
 struct RegistrationStatus {
     #[serde(rename = "hasPendingRegistration")]
     has_pending_registration: bool,
+    /// LOCAL（ID/パスワード登録）またはOAUTH（初回OAuthログイン）
+    #[serde(rename = "registrationType", skip_serializing_if = "Option::is_none")]
+    registration_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<String>,
+    /// OAuthプロバイダーから取得した表示名（プロフィール入力の初期値）
+    #[serde(rename = "suggestedDisplayName", skip_serializing_if = "Option::is_none")]
+    suggested_display_name: Option<String>,
+    /// 登録の有効期限（この時刻までにプロフィールを保存する）
+    #[serde(rename = "expiresAt", skip_serializing_if = "Option::is_none")]
+    expires_at: Option<String>,
+    #[serde(rename = "expiresInSeconds", skip_serializing_if = "Option::is_none")]
+    expires_in_seconds: Option<i64>,
+    /// 有効期限切れの登録を破棄した
+    expired: bool,
 }
 
 /// GET /api/auth/registration-status
 #[get("/auth/registration-status")]
 async fn registration_status(session: Session) -> impl actix_web::Responder {
-    let has_pending = get_pending_registration(&session).is_some();
-    HttpResponse::Ok().json(RegistrationStatus {
-        has_pending_registration: has_pending,
-    })
+    let expired = discard_expired_pending_registrations(&session);
+    let now = chrono::Utc::now().timestamp();
+
+    let status = if let Some(pending) = get_pending_registration(&session) {
+        RegistrationStatus {
+            has_pending_registration: true,
+            registration_type: Some("LOCAL"),
+            provider: None,
+            suggested_display_name: None,
+            expires_at: format_timestamp(pending.expires_at),
+            expires_in_seconds: Some(pending.expires_at - now),
+            expired,
+        }
+    } else if let Some(pending) = get_pending_oauth_registration(&session) {
+        RegistrationStatus {
+            has_pending_registration: true,
+            registration_type: Some("OAUTH"),
+            provider: Some(pending.provider),
+            suggested_display_name: pending.name,
+            expires_at: format_timestamp(pending.expires_at),
+            expires_in_seconds: Some(pending.expires_at - now),
+            expired,
+        }
+    } else {
+        RegistrationStatus {
+            has_pending_registration: false,
+            registration_type: None,
+            provider: None,
+            suggested_display_name: None,
+            expires_at: None,
+            expires_in_seconds: None,
+            expired,
+        }
+    };
+    HttpResponse::Ok().json(status)
 }
 
 /// POST /api/auth/cancel-registration
 #[post("/auth/cancel-registration")]
 async fn cancel_registration(session: Session) -> impl actix_web::Responder {
     clear_pending_registration(&session);
+    clear_pending_oauth_registration(&session);
     session.purge();
     HttpResponse::Ok().json(serde_json::json!({ "success": true }))
 }
 
+// ============================================
+// セッション有効期限
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionInfoResponse {
+    authenticated: bool,
+    started_at: Option<String>,
+    last_activity_at: Option<String>,
+    /// 無操作のままの場合の期限（操作で延長される）
+    idle_expires_at: Option<String>,
+    /// ログインからの最大有効期限（延長されない）
+    absolute_expires_at: Option<String>,
+    /// 上記のうち早い方
+    expires_at: Option<String>,
+    expires_in_seconds: Option<i64>,
+    idle_timeout_seconds: i64,
+    max_lifetime_seconds: i64,
+}
+
+fn format_timestamp(secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(secs, 0).map(|dt| dt.to_rfc3339())
+}
+
+/// GET /api/auth/session - セッションの有効期限情報（このリクエスト自体は期限を延長しない）
+#[get("/auth/session")]
+async fn session_info(session: Session, config: web::Data<AppConfig>) -> impl actix_web::Responder {
+    let idle_timeout_seconds = config.session_idle_timeout_minutes * 60;
+    let max_lifetime_seconds = config.session_max_lifetime_hours * 3600;
+
+    let activity = get_current_user_opt(&session).and_then(|_| get_session_activity(&session));
+
+    let response = match activity {
+        Some(activity) => {
+            let now = chrono::Utc::now().timestamp();
+            let idle_expires_at = activity.last_activity_at + idle_timeout_seconds;
+            let absolute_expires_at = activity.started_at + max_lifetime_seconds;
+            let expires_at = idle_expires_at.min(absolute_expires_at);
+            SessionInfoResponse {
+                authenticated: true,
+                started_at: format_timestamp(activity.started_at),
+                last_activity_at: format_timestamp(activity.last_activity_at),
+                idle_expires_at: format_timestamp(idle_expires_at),
+                absolute_expires_at: format_timestamp(absolute_expires_at),
+                expires_at: format_timestamp(expires_at),
+                expires_in_seconds: Some((expires_at - now).max(0)),
+                idle_timeout_seconds,
+                max_lifetime_seconds,
+            }
+        }
+        None => SessionInfoResponse {
+            authenticated: false,
+            started_at: None,
+            last_activity_at: None,
+            idle_expires_at: None,
+            absolute_expires_at: None,
+            expires_at: None,
+            expires_in_seconds: None,
+            idle_timeout_seconds,
+            max_lifetime_seconds,
+        },
+    };
+
+    HttpResponse::Ok().json(response)
+}
+
 // ============================================
 // ユーザー登録（ステップ1）
 // ============================================
@@ -69,12 +229,18 @@ struct RegisterRequest {
     password: String,
     #[serde(rename = "confirmPassword")]
     confirm_password: String,
+    /// CAPTCHAのトークン（CAPTCHA有効時のみ必須）
+    #[serde(rename = "captchaToken")]
+    captcha_token: Option<String>,
 }
 
 /// POST /register - ステップ1: 資格情報をセッションに保存
 #[post("/register")]
 async fn register(
+    req: HttpRequest,
     pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    captcha: web::Data<Captcha>,
     session: Session,
     form: web::Form<RegisterRequest>,
 ) -> Result<HttpResponse, AppError> {
@@ -92,6 +258,23 @@ async fn register(
         })));
     }
 
+    // パスワードの強度を検証
+    let violations = PasswordPolicy::from_config(&config)
+        .validate(&form.password, &form.login_id)
+        .await;
+    if !violations.is_empty() {
+        return Ok(violations_response(&violations));
+    }
+
+    // ボットによる登録を防ぐ
+    let ip = client_ip(&req);
+    if !captcha.verify(form.captcha_token.as_deref(), Some(&ip)).await {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "CAPTCHAの確認に失敗しました。もう一度お試しください。",
+            "code": "CAPTCHA_FAILED"
+        })));
+    }
+
     // login_idが既に存在するか確認
     let existing: Option<(i64,)> = sqlx::query_as("SELECT id FROM users WHERE login_id = ?")
         .bind(&form.login_id)
@@ -116,6 +299,7 @@ async fn register(
     let pending = PendingRegistration {
         login_id: form.login_id.clone(),
         password_hash,
+        expires_at: pending_registration_deadline(),
     };
     set_pending_registration(&session, pending)
         .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
@@ -139,23 +323,42 @@ struct ProfileRequest {
 }
 
 /// POST /profile - ステップ2: プロフィールで登録を完了
+/// ID/パスワード登録と初回OAuthログインの両方で使用する
 #[post("/profile")]
 async fn save_profile(
     pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    mailer: web::Data<Mailer>,
     session: Session,
     form: web::Form<ProfileRequest>,
 ) -> Result<HttpResponse, AppError> {
+    // 期限切れの登録は破棄して、最初からやり直してもらう
+    if discard_expired_pending_registrations(&session) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!(
+                "登録の有効期限（{}分）が切れました。お手数ですが最初からやり直してください。",
+                PENDING_REGISTRATION_TTL_SECS / 60
+            ),
+            "code": "REGISTRATION_EXPIRED",
+            "redirect": "/register"
+        })));
+    }
+
     // セッションから保留中の登録情報を取得
-    let pending = match get_pending_registration(&session) {
-        Some(p) => p,
-        None => {
-            return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "登録セッションが期限切れです。最初からやり直してください。",
-                "redirect": "/register"
-            })));
-        }
+    let pending = get_pending_registration(&session);
+    let pending_oauth = if pending.is_none() {
+        get_pending_oauth_registration(&session)
+    } else {
+        None
     };
 
+    if pending.is_none() && pending_oauth.is_none() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "登録セッションが期限切れです。最初からやり直してください。",
+            "redirect": "/register"
+        })));
+    }
+
     // バリデーション
     let mut errors = Vec::new();
 
@@ -183,43 +386,59 @@ async fn save_profile(
         .as_ref()
         .and_then(|b| chrono::NaiveDate::parse_from_str(b, "%Y-%m-%d").ok());
 
-    // ユーザーをデータベースに挿入
-    let result = sqlx::query(
-        r#"INSERT INTO users (login_id, password, display_name, gender, birthday, oauth_provider, role, created_at, updated_at)
-           VALUES (?, ?, ?, ?, ?, 'LOCAL', 'USER', NOW(), NOW())"#,
-    )
-    .bind(&pending.login_id)
-    .bind(&pending.password_hash)
-    .bind(&form.display_name)
-    .bind(&form.gender)
-    .bind(&birthday)
-    .execute(pool.get_ref())
-    .await?;
+    let session_user = if let Some(pending) = pending {
+        // ユーザーをデータベースに挿入
+        let result = sqlx::query(
+            r#"INSERT INTO users (login_id, password, display_name, gender, birthday, oauth_provider, role, created_at, updated_at)
+               VALUES (?, ?, ?, ?, ?, 'LOCAL', 'USER', NOW(), NOW())"#,
+        )
+        .bind(&pending.login_id)
+        .bind(&pending.password_hash)
+        .bind(&form.display_name)
+        .bind(&form.gender)
+        .bind(birthday)
+        .execute(pool.get_ref())
+        .await?;
 
-    let user_id = result.last_insert_id() as i64;
+        let user_id = result.last_insert_id() as i64;
 
-    // 保留中の登録情報をクリア
-    clear_pending_registration(&session);
+        // 保留中の登録情報をクリア
+        clear_pending_registration(&session);
 
-    // ユーザー統計を作成
-    let _ = sqlx::query(
-        r#"INSERT INTO user_stats (user_id, total_exp, level, created_at, updated_at)
-           VALUES (?, 0, 1, NOW(), NOW())"#,
-    )
-    .bind(user_id)
-    .execute(pool.get_ref())
-    .await;
+        // ユーザー統計を作成
+        create_initial_user_stats(pool.get_ref(), user_id).await;
 
-    // セッションユーザーを設定
-    let session_user = SessionUser {
-        id: user_id,
-        login_id: pending.login_id.clone(),
-        display_name: form.display_name.clone(),
-        email: None,
-        profile_image_url: None,
-        oauth_provider: "LOCAL".to_string(),
-        role: "USER".to_string(),
+        SessionUser {
+            id: user_id,
+            login_id: pending.login_id.clone(),
+            display_name: form.display_name.clone(),
+            email: None,
+            profile_image_url: None,
+            oauth_provider: "LOCAL".to_string(),
+            role: "USER".to_string(),
+        }
+    } else {
+        let pending = pending_oauth.expect("pending OAuth registration checked above");
+        let user = create_oauth_user(pool.get_ref(), &pending, &form, birthday).await?;
+
+        // 保留中の登録情報をクリア
+        clear_pending_oauth_registration(&session);
+
+        // プロバイダーから取得したメールアドレスの確認リンクを送る（失敗しても登録は完了させる）
+        if let Some(email) = user.email.as_deref().filter(|e| !e.is_empty()) {
+            if find_verified_email(pool.get_ref(), user.id).await?.is_none() {
+                if let Err(e) =
+                    send_verification_mail(pool.get_ref(), &config, &mailer, user.id, email).await
+                {
+                    tracing::warn!("Email verification was not sent: user_id={} {}", user.id, e);
+                }
+            }
+        }
+
+        SessionUser::from(user)
     };
+
+    // セッションユーザーを設定
     set_current_user(&session, session_user)
         .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
 
@@ -237,84 +456,257 @@ async fn save_profile(
 struct LoginRequest {
     username: String,
     password: String,
+    /// 二段階認証のコード（認証アプリのコードまたはバックアップコード）
+    #[serde(rename = "totpCode", default)]
+    totp_code: Option<String>,
 }
 
-/// POST /login - フォームベースログイン
-#[post("/login")]
-async fn login(
-    pool: web::Data<MySqlPool>,
-    session: Session,
-    form: web::Form<LoginRequest>,
-) -> Result<HttpResponse, AppError> {
+/// ログインIDとパスワードでユーザーを認証（失敗時は表示用のメッセージをUnauthorizedで返す）
+pub(crate) async fn authenticate_password(
+    pool: &MySqlPool,
+    login_id: &str,
+    password: &str,
+) -> Result<User, AppError> {
     // login_idでユーザーを検索
     let user: Option<User> = sqlx::query_as(
         r#"SELECT id, login_id, password, email, display_name, gender, birthday,
            profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at
            FROM users WHERE login_id = ?"#,
     )
-    .bind(&form.username)
-    .fetch_optional(pool.get_ref())
+    .bind(login_id)
+    .fetch_optional(pool)
     .await?;
 
-    let user = match user {
-        Some(u) => u,
-        None => {
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": "ユーザーIDまたはパスワードが正しくありません。"
-            })));
-        }
-    };
+    let user = user.ok_or_else(|| {
+        AppError::Unauthorized("ユーザーIDまたはパスワードが正しくありません。".to_string())
+    })?;
 
     // ユーザーがパスワードを持っているか確認（OAuth専用ではない）
     let stored_hash = match &user.password {
         Some(h) if !h.is_empty() => h,
         _ => {
-            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": "このアカウントはソーシャルログインで登録されています。"
-            })));
+            return Err(AppError::Unauthorized(
+                "このアカウントはソーシャルログインで登録されています。".to_string(),
+            ));
         }
     };
 
-    // パスワードを検証（bcryptとargon2の両方をサポート）
-    let is_valid = if stored_hash.starts_with("$2a$")
-        || stored_hash.starts_with("$2b$")
-        || stored_hash.starts_with("$2y$")
-    {
-        // bcryptハッシュ（Spring Bootから）
-        bcrypt::verify(&form.password, stored_hash).unwrap_or(false)
-    } else {
-        // Argon2ハッシュ（新規登録）
-        let parsed_hash = match PasswordHash::new(stored_hash) {
-            Ok(h) => h,
-            Err(e) => {
-                tracing::error!("Invalid password hash format: {}", e);
+    if !verify_password_hash(password, stored_hash) {
+        return Err(AppError::Unauthorized(
+            "ユーザーIDまたはパスワードが正しくありません。".to_string(),
+        ));
+    }
+    Ok(user)
+}
+
+/// POST /login - フォームベースログイン
+/// 失敗が続くとIP・ログインIDごとに一定時間ロックし、429を返す
+#[post("/login")]
+async fn login(
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    req: HttpRequest,
+    form: web::Form<LoginRequest>,
+) -> Result<HttpResponse, AppError> {
+    let ip = client_ip(&req);
+    if let Err(throttled) = check_login_attempt(&store, &ip, &form.username).await {
+        return Ok(throttled.to_response());
+    }
+
+    let user = match authenticate_password(pool.get_ref(), &form.username, &form.password).await {
+        Ok(user) => user,
+        Err(AppError::Unauthorized(message)) => {
+            record_login_failure(&store, &ip, &form.username).await;
+            record_login_attempt(
+                pool.get_ref(),
+                &req,
+                None,
+                Some(&form.username),
+                PROVIDER_LOCAL,
+                CLIENT_WEB,
+                Some(FAILURE_INVALID_CREDENTIALS),
+            )
+            .await;
+            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({ "error": message })));
+        }
+        Err(e) => return Err(e),
+    };
+
+    // 二段階認証が有効な場合はコードを確認するまでセッションを作らない
+    if is_two_factor_enabled(pool.get_ref(), user.id).await? {
+        let code = form.totp_code.as_deref().map(str::trim).unwrap_or("");
+        let result = if code.is_empty() {
+            Err(AppError::Unauthorized(
+                "認証アプリのコードを入力してください".to_string(),
+            ))
+        } else {
+            verify_second_factor(pool.get_ref(), &store, user.id, code).await
+        };
+        match result {
+            Ok(()) => {}
+            Err(AppError::Unauthorized(message)) => {
+                let reason = if code.is_empty() {
+                    FAILURE_TWO_FACTOR_REQUIRED
+                } else {
+                    FAILURE_TWO_FACTOR_FAILED
+                };
+                record_login_attempt(
+                    pool.get_ref(),
+                    &req,
+                    Some(user.id),
+                    Some(&form.username),
+                    PROVIDER_LOCAL,
+                    CLIENT_WEB,
+                    Some(reason),
+                )
+                .await;
                 return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-                    "error": "ユーザーIDまたはパスワードが正しくありません。"
+                    "error": message,
+                    "twoFactorRequired": true
                 })));
             }
-        };
-        Argon2::default()
-            .verify_password(form.password.as_bytes(), &parsed_hash)
-            .is_ok()
-    };
+            Err(e) => return Err(e),
+        }
+    }
+    record_login_success(&store, &form.username).await;
+
+    // 無効化されたアカウントはログインさせず、このセッションから再開できるようにする
+    if let Some(scheduled_deletion_at) = find_deactivation(pool.get_ref(), user.id).await? {
+        record_login_attempt(
+            pool.get_ref(),
+            &req,
+            Some(user.id),
+            Some(&form.username),
+            PROVIDER_LOCAL,
+            CLIENT_WEB,
+            Some(FAILURE_ACCOUNT_DEACTIVATED),
+        )
+        .await;
+        set_pending_reactivation(&session, user.id)
+            .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "このアカウントは無効化されています",
+            "accountDeactivated": true,
+            "scheduledDeletionAt": scheduled_deletion_at,
+        })));
+    }
+
+    record_login_attempt(
+        pool.get_ref(),
+        &req,
+        Some(user.id),
+        Some(&form.username),
+        PROVIDER_LOCAL,
+        CLIENT_WEB,
+        None,
+    )
+    .await;
+
+    // セッションを作成
+    set_current_user(&session, SessionUser::from(user))
+        .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "redirect": "/dashboard"
+    })))
+}
+
+#[derive(Deserialize)]
+struct TwoFactorLoginRequest {
+    /// 認証アプリのコードまたはバックアップコード
+    #[serde(rename = "totpCode", default)]
+    totp_code: String,
+}
 
-    if !is_valid {
+/// POST /login/two-factor - ソーシャルログインの二段階認証を完了する
+/// 二段階認証が有効なアカウントのOAuthコールバック直後のセッションでのみ使える
+#[post("/login/two-factor")]
+async fn login_two_factor(
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    req: HttpRequest,
+    form: web::Form<TwoFactorLoginRequest>,
+) -> Result<HttpResponse, AppError> {
+    let Some(pending) = get_pending_two_factor(&session) else {
+        clear_pending_two_factor(&session);
         return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
-            "error": "ユーザーIDまたはパスワードが正しくありません。"
+            "error": "ログインの有効期限が切れました。もう一度お試しください",
+            "redirect": "/login"
+        })));
+    };
+
+    let code = form.totp_code.trim();
+    let result = if code.is_empty() {
+        Err(AppError::Unauthorized(
+            "認証アプリのコードを入力してください".to_string(),
+        ))
+    } else {
+        verify_second_factor(pool.get_ref(), &store, pending.user_id, code).await
+    };
+    match result {
+        Ok(()) => {}
+        Err(AppError::Unauthorized(message)) => {
+            if !code.is_empty() {
+                record_login_attempt(
+                    pool.get_ref(),
+                    &req,
+                    Some(pending.user_id),
+                    None,
+                    &pending.provider,
+                    CLIENT_WEB,
+                    Some(FAILURE_TWO_FACTOR_FAILED),
+                )
+                .await;
+            }
+            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": message,
+                "twoFactorRequired": true
+            })));
+        }
+        Err(e) => return Err(e),
+    }
+    clear_pending_two_factor(&session);
+
+    // 無効化されたアカウントはログインさせず、このセッションから再開できるようにする
+    if let Some(scheduled_deletion_at) = find_deactivation(pool.get_ref(), pending.user_id).await? {
+        record_login_attempt(
+            pool.get_ref(),
+            &req,
+            Some(pending.user_id),
+            None,
+            &pending.provider,
+            CLIENT_WEB,
+            Some(FAILURE_ACCOUNT_DEACTIVATED),
+        )
+        .await;
+        set_pending_reactivation(&session, pending.user_id)
+            .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+        return Ok(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "このアカウントは無効化されています",
+            "accountDeactivated": true,
+            "scheduledDeletionAt": scheduled_deletion_at,
         })));
     }
 
+    let user = load_user(pool.get_ref(), pending.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    record_login_attempt(
+        pool.get_ref(),
+        &req,
+        Some(user.id),
+        None,
+        &pending.provider,
+        CLIENT_WEB,
+        None,
+    )
+    .await;
+
     // セッションを作成
-    let session_user = SessionUser {
-        id: user.id,
-        login_id: user.login_id.clone(),
-        display_name: user.display_name.clone(),
-        email: user.email.clone(),
-        profile_image_url: user.profile_image_url.clone(),
-        oauth_provider: user.oauth_provider.clone(),
-        role: user.role.clone(),
-    };
-    set_current_user(&session, session_user)
+    set_current_user(&session, user)
         .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
@@ -329,7 +721,13 @@ async fn login(
 
 /// POST /logout
 #[post("/logout")]
-async fn logout(session: Session) -> impl actix_web::Responder {
+async fn logout(pool: web::Data<MySqlPool>, session: Session) -> impl actix_web::Responder {
+    // セッション一覧からも外す（失敗してもログアウトは続行する）
+    if let Some(session_id) = get_session_id(&session) {
+        if let Err(e) = revoke_session(pool.get_ref(), &session_id).await {
+            tracing::warn!("Failed to revoke session on logout: {}", e);
+        }
+    }
     clear_current_user(&session);
     session.purge();
     HttpResponse::Found()
@@ -341,204 +739,171 @@ async fn logout(session: Session) -> impl actix_web::Responder {
 // OAuth2開始
 // ============================================
 
-/// GET /oauth2/authorization/google
-#[get("/oauth2/authorization/google")]
-async fn google_oauth_start(
-    config: web::Data<AppConfig>,
-    session: Session,
-) -> impl actix_web::Responder {
-    let client = crate::auth::oauth_google::create_oauth_client(&config);
-    let (auth_url, csrf_token) = crate::auth::oauth_google::get_authorize_url(&client);
-
-    // CSRFトークンをセッションに保存
-    let _ = session.insert("oauth_csrf", csrf_token.secret().clone());
-
-    HttpResponse::Found()
-        .append_header(("Location", auth_url))
-        .finish()
-}
-
-/// GET /oauth2/authorization/github
-#[get("/oauth2/authorization/github")]
-async fn github_oauth_start(
-    config: web::Data<AppConfig>,
-    session: Session,
-) -> impl actix_web::Responder {
-    let client = crate::auth::oauth_github::create_oauth_client(&config);
-    let (auth_url, csrf_token) = crate::auth::oauth_github::get_authorize_url(&client);
-
-    // CSRFトークンをセッションに保存
-    let _ = session.insert("oauth_csrf", csrf_token.secret().clone());
-
-    HttpResponse::Found()
-        .append_header(("Location", auth_url))
-        .finish()
-}
-
-/// GET /oauth2/authorization/microsoft
-#[get("/oauth2/authorization/microsoft")]
-async fn microsoft_oauth_start(
-    config: web::Data<AppConfig>,
+/// GET /oauth2/authorization/{provider}
+#[get("/oauth2/authorization/{provider}")]
+async fn oauth_start(
+    registry: web::Data<OAuthRegistry>,
     session: Session,
-) -> impl actix_web::Responder {
-    let client = crate::auth::oauth_microsoft::create_oauth_client(&config);
-    let (auth_url, csrf_token) = crate::auth::oauth_microsoft::get_authorize_url(&client);
-
-    // CSRFトークンをセッションに保存
-    let _ = session.insert("oauth_csrf", csrf_token.secret().clone());
+    path: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    let provider = find_enabled_provider(&registry, &path)?;
+    let (auth_url, csrf_token) = provider
+        .authorize_url()
+        .map_err(AppError::InternalError)?;
+
+    // コールバックで照合するstateをセッションに保存
+    set_oauth_state(
+        &session,
+        OAuthState {
+            state: csrf_token.secret().clone(),
+            provider: provider.provider_code.to_string(),
+            issued_at: chrono::Utc::now().timestamp(),
+        },
+    )
+    .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
 
-    HttpResponse::Found()
+    Ok(HttpResponse::Found()
         .append_header(("Location", auth_url))
-        .finish()
+        .finish())
 }
 
 // ============================================
 // OAuth2コールバック
 // ============================================
 
-#[derive(Deserialize)]
+/// OAuth2コールバックのパラメータ
+/// form_postのプロバイダー（Sign in with Apple）ではPOSTで受け取ってからGETに転送する
+#[derive(Deserialize, Serialize)]
 struct OAuthCallback {
     code: String,
-    #[allow(dead_code)]
     state: Option<String>,
+    /// 初回認可時のみ送られるユーザー情報（JSON文字列、Sign in with Apple）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
 }
 
-/// GET /login/oauth2/code/google - OAuth2コールバック（Spring Boot互換）
-#[get("/login/oauth2/code/google")]
-async fn google_oauth_callback(
-    pool: web::Data<MySqlPool>,
-    config: web::Data<AppConfig>,
-    session: Session,
-    query: web::Query<OAuthCallback>,
-) -> Result<HttpResponse, AppError> {
-    let client = crate::auth::oauth_google::create_oauth_client(&config);
-
-    // コードをユーザー情報に交換
-    let user_info =
-        crate::auth::oauth_google::exchange_code_for_user_info(&client, query.code.clone())
-            .await
-            .map_err(|e| AppError::InternalError(e))?;
+/// 認可開始からコールバックまでの有効期限（秒）
+const OAUTH_STATE_TTL_SECS: i64 = 10 * 60;
 
-    // ユーザーを検索または作成
-    let user = find_or_create_oauth_user(
-        pool.get_ref(),
-        "GOOGLE",
-        &user_info.sub,
-        user_info.email.as_deref(),
-        user_info.name.as_deref(),
-        user_info.picture.as_deref(),
-    )
-    .await?;
-
-    // セッションを設定
-    let session_user = SessionUser {
-        id: user.id,
-        login_id: user.login_id.clone(),
-        display_name: user.display_name.clone(),
-        email: user.email.clone(),
-        profile_image_url: user.profile_image_url.clone(),
-        oauth_provider: user.oauth_provider.clone(),
-        role: user.role.clone(),
+/// コールバックのstateが、このブラウザで開始した認可フローのものか確認する
+/// stateは1回しか使えないため、照合の成否にかかわらずセッションから取り除く
+fn verify_oauth_state(session: &Session, provider: &str, state: Option<&str>) -> bool {
+    let Some(expected) = take_oauth_state(session) else {
+        return false;
     };
-    set_current_user(&session, session_user)
-        .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
-
-    let redirect_url = get_redirect_url(&config, "/dashboard");
-    Ok(HttpResponse::Found()
-        .append_header(("Location", redirect_url))
-        .finish())
+    let elapsed = chrono::Utc::now().timestamp() - expected.issued_at;
+    state == Some(expected.state.as_str())
+        && expected.provider == provider
+        && (0..OAUTH_STATE_TTL_SECS).contains(&elapsed)
 }
 
-/// GET /login/oauth2/code/github - OAuth2コールバック（Spring Boot互換）
-#[get("/login/oauth2/code/github")]
-async fn github_oauth_callback(
+/// GET /login/oauth2/code/{provider} - OAuth2コールバック（Spring Boot互換）
+#[get("/login/oauth2/code/{provider}")]
+async fn oauth_callback(
     pool: web::Data<MySqlPool>,
     config: web::Data<AppConfig>,
+    registry: web::Data<OAuthRegistry>,
     session: Session,
+    req: HttpRequest,
+    path: web::Path<String>,
     query: web::Query<OAuthCallback>,
 ) -> Result<HttpResponse, AppError> {
-    let client = crate::auth::oauth_github::create_oauth_client(&config);
+    let provider = find_enabled_provider(&registry, &path)?;
+    let query = query.into_inner();
+
+    // 他人が開始した認可フローの結果でログインさせられる攻撃（ログインCSRF）を防ぐ
+    if !verify_oauth_state(&session, provider.provider_code, query.state.as_deref()) {
+        tracing::warn!(
+            "[OAUTH] Rejected callback with invalid or expired state: provider={}",
+            provider.provider_code
+        );
+        return Ok(HttpResponse::Found()
+            .append_header(("Location", get_redirect_url(&config, "/login?error=oauth_state")))
+            .finish());
+    }
 
-    // コードをユーザー情報に交換
-    let user_info =
-        crate::auth::oauth_github::exchange_code_for_user_info(&client, query.code.clone())
-            .await
-            .map_err(|e| AppError::InternalError(e))?;
+    // userフィールドが壊れていても名前が取れないだけなのでログインは続行する
+    let callback_user = query
+        .user
+        .as_deref()
+        .and_then(|user| serde_json::from_str(user).ok());
 
-    // ユーザーを検索または作成
-    let user = find_or_create_oauth_user(
+    handle_oauth_callback(
         pool.get_ref(),
-        "GITHUB",
-        &user_info.id.to_string(),
-        user_info.email.as_deref(),
-        user_info.name.as_deref().or(Some(&user_info.login)),
-        user_info.avatar_url.as_deref(),
+        &config,
+        &session,
+        &req,
+        provider,
+        query.code,
+        callback_user,
     )
-    .await?;
-
-    // セッションを設定
-    let session_user = SessionUser {
-        id: user.id,
-        login_id: user.login_id.clone(),
-        display_name: user.display_name.clone(),
-        email: user.email.clone(),
-        profile_image_url: user.profile_image_url.clone(),
-        oauth_provider: user.oauth_provider.clone(),
-        role: user.role.clone(),
-    };
-    set_current_user(&session, session_user)
-        .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+    .await
+}
 
-    let redirect_url = get_redirect_url(&config, "/dashboard");
-    Ok(HttpResponse::Found()
-        .append_header(("Location", redirect_url))
+/// POST /login/oauth2/code/{provider} - form_postで返すプロバイダー用のOAuth2コールバック
+/// クロスサイトのPOSTにはセッションCookie（SameSite=Lax）が付かないため、
+/// 同じパラメータでGETのコールバックに転送し、セッションのstateと照合させる
+#[post("/login/oauth2/code/{provider}")]
+async fn oauth_form_callback(
+    registry: web::Data<OAuthRegistry>,
+    path: web::Path<String>,
+    form: web::Form<OAuthCallback>,
+) -> Result<HttpResponse, AppError> {
+    let provider = find_enabled_provider(&registry, &path)?;
+    let query = serde_urlencoded::to_string(form.into_inner())
+        .map_err(|e| AppError::InternalError(format!("Failed to encode callback: {}", e)))?;
+
+    Ok(HttpResponse::SeeOther()
+        .append_header((
+            "Location",
+            format!("/login/oauth2/code/{}?{}", provider.provider_code, query),
+        ))
         .finish())
 }
 
-/// GET /login/oauth2/code/microsoft - OAuth2コールバック
-#[get("/login/oauth2/code/microsoft")]
-async fn microsoft_oauth_callback(
-    pool: web::Data<MySqlPool>,
-    config: web::Data<AppConfig>,
-    session: Session,
-    query: web::Query<OAuthCallback>,
+/// 認可コードをユーザー情報に交換してログインを完了する
+async fn handle_oauth_callback(
+    pool: &MySqlPool,
+    config: &AppConfig,
+    session: &Session,
+    req: &HttpRequest,
+    provider: &OAuthProviderConfig,
+    code: String,
+    callback_user: Option<serde_json::Value>,
 ) -> Result<HttpResponse, AppError> {
-    let client = crate::auth::oauth_microsoft::create_oauth_client(&config);
-
     // コードをユーザー情報に交換
-    let user_info =
-        crate::auth::oauth_microsoft::exchange_code_for_user_info(&client, query.code.clone())
-            .await
-            .map_err(|e| AppError::InternalError(e))?;
-
-    // ユーザーを検索または作成
-    let user = find_or_create_oauth_user(
-        pool.get_ref(),
-        "MICROSOFT",
-        &user_info.id,
-        user_info.mail.as_deref().or(user_info.user_principal_name.as_deref()),
-        user_info.display_name.as_deref(),
-        None, // Microsoft Graph APIでは画像取得は別エンドポイントが必要なため、一旦None
+    let user_info = provider
+        .exchange_code_for_user_info(code, callback_user)
+        .await
+        .map_err(AppError::InternalError)?;
+
+    // 既存ユーザーならログイン、初回ならプロフィール入力へ
+    complete_oauth_login(
+        pool,
+        config,
+        session,
+        req,
+        PendingOAuthRegistration {
+            provider: provider.provider_code.to_string(),
+            oauth_id: user_info.oauth_id,
+            email: user_info.email,
+            name: user_info.name,
+            profile_image_url: user_info.profile_image_url,
+            expires_at: pending_registration_deadline(),
+        },
     )
-    .await?;
-
-    // セッションを設定
-    let session_user = SessionUser {
-        id: user.id,
-        login_id: user.login_id.clone(),
-        display_name: user.display_name.clone(),
-        email: user.email.clone(),
-        profile_image_url: user.profile_image_url.clone(),
-        oauth_provider: user.oauth_provider.clone(),
-        role: user.role.clone(),
-    };
-    set_current_user(&session, session_user)
-        .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+    .await
+}
 
-    let redirect_url = get_redirect_url(&config, "/dashboard");
-    Ok(HttpResponse::Found()
-        .append_header(("Location", redirect_url))
-        .finish())
+/// 登録済みかつ資格情報が設定されているプロバイダーを取得
+fn find_enabled_provider<'a>(
+    registry: &'a OAuthRegistry,
+    name: &str,
+) -> Result<&'a OAuthProviderConfig, AppError> {
+    registry
+        .get(name)
+        .filter(|p| p.is_enabled())
+        .ok_or_else(|| AppError::NotFound(format!("未対応のログインプロバイダーです: {}", name)))
 }
 
 // ============================================
@@ -559,14 +924,91 @@ async fn get_csrf_token() -> impl actix_web::Responder {
 // ヘルパー関数
 // ============================================
 
-async fn find_or_create_oauth_user(
+/// OAuthログインを完了する
+/// 既存ユーザー（またはメールアドレスで紐付け可能なユーザー）はそのままログインし、
+/// 初回ユーザーは保留中のOAuth登録としてセッションに保存してプロフィール入力へ誘導する
+async fn complete_oauth_login(
+    pool: &MySqlPool,
+    config: &AppConfig,
+    session: &Session,
+    req: &HttpRequest,
+    identity: PendingOAuthRegistration,
+) -> Result<HttpResponse, AppError> {
+    let existing = find_oauth_user(
+        pool,
+        &identity.provider,
+        &identity.oauth_id,
+        identity.email.as_deref(),
+        identity.profile_image_url.as_deref(),
+    )
+    .await?;
+
+    let redirect_path = match existing {
+        Some(user) => {
+            clear_pending_oauth_registration(session);
+            // 二段階認証が有効な場合はパスワードログインと同じくコードを確認するまでセッションを作らない
+            if is_two_factor_enabled(pool, user.id).await? {
+                record_login_attempt(
+                    pool,
+                    req,
+                    Some(user.id),
+                    None,
+                    &identity.provider,
+                    CLIENT_WEB,
+                    Some(FAILURE_TWO_FACTOR_REQUIRED),
+                )
+                .await;
+                set_pending_two_factor(session, user.id, &identity.provider)
+                    .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+                let redirect_url = get_redirect_url(config, "/login?twoFactorRequired=1");
+                return Ok(HttpResponse::Found()
+                    .append_header(("Location", redirect_url))
+                    .finish());
+            }
+            let deactivated = find_deactivation(pool, user.id).await?.is_some();
+            record_login_attempt(
+                pool,
+                req,
+                Some(user.id),
+                None,
+                &identity.provider,
+                CLIENT_WEB,
+                deactivated.then_some(FAILURE_ACCOUNT_DEACTIVATED),
+            )
+            .await;
+            if deactivated {
+                // 無効化されたアカウント: ログイン画面で再開を確認する
+                set_pending_reactivation(session, user.id)
+                    .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+                "/login?accountDeactivated=1"
+            } else {
+                set_current_user(session, SessionUser::from(user))
+                    .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+                "/dashboard"
+            }
+        }
+        None => {
+            clear_pending_registration(session);
+            set_pending_oauth_registration(session, identity)
+                .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+            "/profile"
+        }
+    };
+
+    let redirect_url = get_redirect_url(config, redirect_path);
+    Ok(HttpResponse::Found()
+        .append_header(("Location", redirect_url))
+        .finish())
+}
+
+/// OAuthユーザーを検索する（プロバイダーIDで一致しなければメールアドレスで既存アカウントに紐付け）
+async fn find_oauth_user(
     pool: &MySqlPool,
     provider: &str,
     oauth_id: &str,
     email: Option<&str>,
-    name: Option<&str>,
     image_url: Option<&str>,
-) -> Result<User, AppError> {
+) -> Result<Option<User>, AppError> {
     // oauth_providerとoauth_idで検索
     let existing: Option<User> = sqlx::query_as(
         r#"SELECT id, login_id, password, email, display_name, gender, birthday,
@@ -580,42 +1022,45 @@ async fn find_or_create_oauth_user(
 
     if let Some(mut user) = existing {
         // ユーザー情報が変更された場合は更新
+        // 表示名はプロフィール入力でユーザーが設定するため、プロバイダー側の名前では上書きしない
         let mut updated = false;
         if email.is_some() && user.email.as_deref() != email {
             user.email = email.map(|s| s.to_string());
             updated = true;
         }
-        if name.is_some() && user.display_name.as_deref() != name {
-            user.display_name = name.map(|s| s.to_string());
-            updated = true;
-        }
-        if image_url.is_some() && user.profile_image_url.as_deref() != image_url {
+        // アップロードした画像はプロバイダー側の画像で上書きしない
+        if image_url.is_some()
+            && user.profile_image_url.as_deref() != image_url
+            && find_profile_image_key(pool, user.id).await?.is_none()
+        {
             user.profile_image_url = image_url.map(|s| s.to_string());
             updated = true;
         }
 
         if updated {
             sqlx::query(
-                r#"UPDATE users SET email = ?, display_name = ?, profile_image_url = ?, updated_at = NOW()
+                r#"UPDATE users SET email_verified_at = IF(email <=> ?, email_verified_at, NULL),
+                       email = ?, profile_image_url = ?, updated_at = NOW()
                    WHERE id = ?"#,
             )
             .bind(&user.email)
-            .bind(&user.display_name)
+            .bind(&user.email)
             .bind(&user.profile_image_url)
             .bind(user.id)
             .execute(pool)
             .await?;
         }
 
-        return Ok(user);
+        return Ok(Some(user));
     }
 
-    // メールで検索
-    if let Some(email_str) = email {
+    // メールで検索（Appleのプライベートリレーアドレスはアプリ専用のため既存アカウントとは紐付けない）
+    // 所有が確認されていないメールアドレスでは、他人のアカウントに紐付かないよう検索しない
+    if let Some(email_str) = email.filter(|e| !is_private_relay_email(e)) {
         let existing_by_email: Option<User> = sqlx::query_as(
             r#"SELECT id, login_id, password, email, display_name, gender, birthday,
                profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at
-               FROM users WHERE email = ?"#,
+               FROM users WHERE email = ? AND email_verified_at IS NOT NULL"#,
         )
         .bind(email_str)
         .fetch_optional(pool)
@@ -624,7 +1069,10 @@ async fn find_or_create_oauth_user(
         if let Some(mut user) = existing_by_email {
             // OAuthを既存アカウントにリンク
             sqlx::query(
-                r#"UPDATE users SET oauth_provider = ?, oauth_id = ?, profile_image_url = COALESCE(?, profile_image_url), updated_at = NOW()
+                r#"UPDATE users SET oauth_provider = ?, oauth_id = ?,
+                       profile_image_url = IF(profile_image_key IS NULL,
+                           COALESCE(?, profile_image_url), profile_image_url),
+                       updated_at = NOW()
                    WHERE id = ?"#,
             )
             .bind(provider)
@@ -636,20 +1084,42 @@ async fn find_or_create_oauth_user(
 
             user.oauth_provider = provider.to_string();
             user.oauth_id = Some(oauth_id.to_string());
-            return Ok(user);
+            return Ok(Some(user));
         }
     }
 
+    Ok(None)
+}
+
+/// 保留中のOAuth登録とプロフィール入力からユーザーを作成する
+async fn create_oauth_user(
+    pool: &MySqlPool,
+    pending: &PendingOAuthRegistration,
+    profile: &ProfileRequest,
+    birthday: Option<chrono::NaiveDate>,
+) -> Result<User, AppError> {
+    let provider = pending.provider.as_str();
+    let oauth_id = pending.oauth_id.as_str();
+    let email = pending.email.as_deref();
+    let image_url = pending.profile_image_url.as_deref();
+
+    // 二重送信などで既に作成済みの場合はそのユーザーを返す
+    if let Some(user) = find_oauth_user(pool, provider, oauth_id, email, None).await? {
+        return Ok(user);
+    }
+
     // 新規ユーザーを作成
     let login_id = generate_unique_login_id(pool, provider, oauth_id, email).await?;
 
     let result = sqlx::query(
-        r#"INSERT INTO users (login_id, email, display_name, profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at)
-           VALUES (?, ?, ?, ?, ?, ?, 'USER', NOW(), NOW())"#,
+        r#"INSERT INTO users (login_id, email, display_name, gender, birthday, profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, 'USER', NOW(), NOW())"#,
     )
     .bind(&login_id)
     .bind(email)
-    .bind(name)
+    .bind(&profile.display_name)
+    .bind(&profile.gender)
+    .bind(birthday)
     .bind(image_url)
     .bind(provider)
     .bind(oauth_id)
@@ -659,22 +1129,16 @@ async fn find_or_create_oauth_user(
     let user_id = result.last_insert_id() as i64;
 
     // ユーザー統計を作成
-    let _ = sqlx::query(
-        r#"INSERT INTO user_stats (user_id, total_exp, level, created_at, updated_at)
-           VALUES (?, 0, 1, NOW(), NOW())"#,
-    )
-    .bind(user_id)
-    .execute(pool)
-    .await;
+    create_initial_user_stats(pool, user_id).await;
 
     Ok(User {
         id: user_id,
         login_id,
         password: None,
         email: email.map(|s| s.to_string()),
-        display_name: name.map(|s| s.to_string()),
-        gender: None,
-        birthday: None,
+        display_name: profile.display_name.clone(),
+        gender: profile.gender.clone(),
+        birthday,
         profile_image_url: image_url.map(|s| s.to_string()),
         oauth_provider: provider.to_string(),
         oauth_id: Some(oauth_id.to_string()),
@@ -684,8 +1148,20 @@ async fn find_or_create_oauth_user(
     })
 }
 
+/// 新規ユーザーのuser_statsを作成（失敗しても登録自体は継続）
+async fn create_initial_user_stats(pool: &MySqlPool, user_id: i64) {
+    let _ = sqlx::query(
+        r#"INSERT INTO user_stats (user_id, total_exp, level, created_at, updated_at)
+           VALUES (?, 0, 1, NOW(), NOW())"#,
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await;
+}
+
 fn generate_login_id(provider: &str, oauth_id: &str, email: Option<&str>) -> String {
-    if let Some(email_str) = email {
+    // プライベートリレーアドレスのローカル部はランダム文字列なのでlogin_idには使わない
+    if let Some(email_str) = email.filter(|e| !is_private_relay_email(e)) {
         if !email_str.is_empty() {
             let local_part: &str = email_str.split('@').next().unwrap_or("");
             if local_part.len() >= 6 {
@@ -747,6 +1223,7 @@ async fn generate_unique_login_id(
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(registration_status)
         .service(cancel_registration)
+        .service(session_info)
         .service(get_csrf_token);
 }
 
@@ -755,11 +1232,9 @@ pub fn configure_root(cfg: &mut web::ServiceConfig) {
     cfg.service(register)
         .service(save_profile)
         .service(login)
+        .service(login_two_factor)
         .service(logout)
-        .service(google_oauth_start)
-        .service(github_oauth_start)
-        .service(microsoft_oauth_start)
-        .service(google_oauth_callback)
-        .service(github_oauth_callback)
-        .service(microsoft_oauth_callback);
+        .service(oauth_start)
+        .service(oauth_callback)
+        .service(oauth_form_callback);
 }
@@ -0,0 +1,404 @@
+//! Bearerトークン（モバイルクライアント向け）APIハンドラ
+//!
+//! アクセストークンとリフレッシュトークンを発行する。リフレッシュトークンは1回限りで、
+//! 再発行のたびに新しいトークンへ置き換える（ローテーション）。
+//! 使用済みのトークンが再び使われた場合は盗用とみなし、同じ端末のトークンをまとめて失効させる。
+//! 失効させてもアクセストークンは有効期限（既定15分）まで使える。
+
+use actix_web::{get, http::header, post, web, HttpRequest, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::auth::authenticate_password;
+use crate::api::account::find_deactivation;
+use crate::api::login_audit::{
+    record_login_attempt, CLIENT_TOKEN, FAILURE_ACCOUNT_DEACTIVATED, FAILURE_INVALID_CREDENTIALS,
+    FAILURE_TWO_FACTOR_FAILED, FAILURE_TWO_FACTOR_REQUIRED, PROVIDER_LOCAL,
+};
+use crate::api::two_factor::{is_two_factor_enabled, verify_second_factor};
+use crate::auth::login_throttle::{
+    check_login_attempt, client_ip, record_login_failure, record_login_success,
+};
+use crate::auth::session::{get_current_user, Session, SessionUser};
+use crate::auth::token::{decode_token, issue_token_pair, TokenPair, TOKEN_TYPE_REFRESH};
+use crate::config::AppConfig;
+use crate::db::models::User;
+use crate::error::AppError;
+use crate::shared_store::SharedStore;
+
+/// 保存するUser-Agentの最大文字数
+const MAX_USER_AGENT_CHARS: usize = 255;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenRequest {
+    login_id: String,
+    password: String,
+    /// 二段階認証のコード（有効にしているアカウントのみ必要）
+    #[serde(default)]
+    totp_code: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RefreshRequest {
+    refresh_token: String,
+}
+
+/// 失効させるトークン（いずれか1つを指定する）
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RevokeRequest {
+    /// 手元のリフレッシュトークン（ログイン不要）
+    refresh_token: Option<String>,
+    /// 端末一覧のID（ログインが必要）
+    token_id: Option<i64>,
+    /// 全端末（ログインが必要）
+    #[serde(default)]
+    all: bool,
+}
+
+#[derive(Serialize)]
+struct RevokeResponse {
+    revoked: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RefreshTokenDto {
+    id: i64,
+    user_agent: Option<String>,
+    created_at: String,
+    last_used_at: Option<String>,
+    expires_at: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct RefreshTokenRow {
+    id: i64,
+    user_agent: Option<String>,
+    created_at: NaiveDateTime,
+    last_used_at: Option<NaiveDateTime>,
+    expires_at: NaiveDateTime,
+}
+
+fn format_datetime(dt: NaiveDateTime) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+impl From<RefreshTokenRow> for RefreshTokenDto {
+    fn from(row: RefreshTokenRow) -> Self {
+        Self {
+            id: row.id,
+            user_agent: row.user_agent,
+            created_at: format_datetime(row.created_at),
+            last_used_at: row.last_used_at.map(format_datetime),
+            expires_at: format_datetime(row.expires_at),
+        }
+    }
+}
+
+fn user_agent(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(MAX_USER_AGENT_CHARS).collect())
+}
+
+// ============================================
+// リフレッシュトークンの記録
+// ============================================
+
+/// 発行したリフレッシュトークンを記録する
+async fn record_refresh_token(
+    conn: &mut MySqlConnection,
+    config: &AppConfig,
+    user_id: i64,
+    tokens: &TokenPair,
+    family_id: &str,
+    user_agent: Option<&str>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"INSERT INTO refresh_tokens
+               (user_id, jti, family_id, user_agent, created_at, last_used_at, expires_at)
+           VALUES (?, ?, ?, ?, NOW(), NOW(), DATE_ADD(NOW(), INTERVAL ? DAY))"#,
+    )
+    .bind(user_id)
+    .bind(&tokens.refresh_jti)
+    .bind(family_id)
+    .bind(user_agent)
+    .bind(config.jwt_refresh_ttl_days)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// 同じファミリー（同じ端末で再発行し続けたトークン）をまとめて失効させる
+async fn revoke_family(conn: &mut MySqlConnection, family_id: &str) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        "UPDATE refresh_tokens SET revoked_at = NOW() WHERE family_id = ? AND revoked_at IS NULL",
+    )
+    .bind(family_id)
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// POST /api/auth/token
+/// ログインIDとパスワードでアクセストークンとリフレッシュトークンを発行する
+/// 二段階認証が有効でコードがない場合はTWO_FACTOR_REQUIREDを返す
+/// 失敗が続いた場合は/loginと同じくロックして429を返す
+#[post("/auth/token")]
+async fn issue_token(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    req: HttpRequest,
+    body: web::Json<TokenRequest>,
+) -> Result<HttpResponse, AppError> {
+    let ip = client_ip(&req);
+    if let Err(throttled) = check_login_attempt(&store, &ip, &body.login_id).await {
+        return Ok(throttled.to_response());
+    }
+
+    let user = match authenticate_password(pool.get_ref(), &body.login_id, &body.password).await {
+        Ok(user) => user,
+        Err(e @ AppError::Unauthorized(_)) => {
+            record_login_failure(&store, &ip, &body.login_id).await;
+            record_login_attempt(
+                pool.get_ref(),
+                &req,
+                None,
+                Some(&body.login_id),
+                PROVIDER_LOCAL,
+                CLIENT_TOKEN,
+                Some(FAILURE_INVALID_CREDENTIALS),
+            )
+            .await;
+            return Err(e);
+        }
+        Err(e) => return Err(e),
+    };
+    let user_id = user.id;
+    let record_attempt = |failure_reason: Option<&'static str>| {
+        record_login_attempt(
+            pool.get_ref(),
+            &req,
+            Some(user_id),
+            Some(&body.login_id),
+            PROVIDER_LOCAL,
+            CLIENT_TOKEN,
+            failure_reason,
+        )
+    };
+
+    if is_two_factor_enabled(pool.get_ref(), user_id).await? {
+        let code = body.totp_code.as_deref().map(str::trim).unwrap_or("");
+        if code.is_empty() {
+            record_attempt(Some(FAILURE_TWO_FACTOR_REQUIRED)).await;
+            return Ok(HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "TWO_FACTOR_REQUIRED",
+                "message": "認証アプリのコードを入力してください"
+            })));
+        }
+        if let Err(e) = verify_second_factor(pool.get_ref(), &store, user_id, code).await {
+            if matches!(e, AppError::Unauthorized(_)) {
+                record_attempt(Some(FAILURE_TWO_FACTOR_FAILED)).await;
+            }
+            return Err(e);
+        }
+    }
+    record_login_success(&store, &body.login_id).await;
+    // 無効化されたアカウントの再開はWebのログインからのみ行う
+    if find_deactivation(pool.get_ref(), user_id).await?.is_some() {
+        record_attempt(Some(FAILURE_ACCOUNT_DEACTIVATED)).await;
+        return Err(AppError::Forbidden(
+            "このアカウントは無効化されています".to_string(),
+        ));
+    }
+    record_attempt(None).await;
+
+    let tokens = issue_token_pair(&config, &SessionUser::from(user))?;
+    // 最初のトークンのIDをファミリーIDとして引き継ぐ
+    let family_id = tokens.refresh_jti.clone();
+    record_refresh_token(
+        &mut *pool.acquire().await?,
+        &config,
+        user_id,
+        &tokens,
+        &family_id,
+        user_agent(&req).as_deref(),
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+/// POST /api/auth/refresh
+/// リフレッシュトークンを新しいトークンの組に交換する（使ったトークンは失効する）
+#[post("/auth/refresh")]
+async fn refresh_token(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    req: HttpRequest,
+    body: web::Json<RefreshRequest>,
+) -> Result<HttpResponse, AppError> {
+    let claims = decode_token(&config, &body.refresh_token, TOKEN_TYPE_REFRESH)?;
+
+    let mut tx = pool.begin().await?;
+
+    let row: Option<(i64, String, Option<NaiveDateTime>)> = sqlx::query_as(
+        "SELECT user_id, family_id, revoked_at FROM refresh_tokens WHERE jti = ? FOR UPDATE",
+    )
+    .bind(&claims.jti)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let (user_id, family_id, revoked_at) =
+        row.ok_or_else(|| AppError::Unauthorized("Invalid or expired token".to_string()))?;
+
+    if revoked_at.is_some() {
+        // 使用済み・失効済みのトークン: 盗用の可能性があるため端末ごと失効させる
+        let revoked = revoke_family(&mut tx, &family_id).await?;
+        tx.commit().await?;
+        tracing::warn!(
+            "Refresh token reuse detected for user_id={} (revoked {} tokens)",
+            user_id,
+            revoked
+        );
+        return Err(AppError::Unauthorized(
+            "Invalid or expired token".to_string(),
+        ));
+    }
+
+    // 発行後の変更（ロール・表示名など）を反映するため読み直す（無効化されたアカウントは拒否）
+    let user: Option<User> = sqlx::query_as(
+        r#"SELECT id, login_id, password, email, display_name, gender, birthday,
+           profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at
+           FROM users WHERE id = ? AND deactivated_at IS NULL"#,
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let user = user.ok_or_else(|| AppError::Unauthorized("User not found".to_string()))?;
+
+    let tokens = issue_token_pair(&config, &SessionUser::from(user))?;
+    record_refresh_token(
+        &mut tx,
+        &config,
+        user_id,
+        &tokens,
+        &family_id,
+        user_agent(&req).as_deref(),
+    )
+    .await?;
+
+    sqlx::query(
+        r#"UPDATE refresh_tokens
+           SET revoked_at = NOW(), last_used_at = NOW(), replaced_by_jti = ?
+           WHERE jti = ?"#,
+    )
+    .bind(&tokens.refresh_jti)
+    .bind(&claims.jti)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+/// POST /api/auth/revoke
+/// リフレッシュトークンを失効させる（盗まれた端末のトークンの無効化など）
+#[post("/auth/revoke")]
+async fn revoke_token(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    session: Session,
+    body: web::Json<RevokeRequest>,
+) -> Result<HttpResponse, AppError> {
+    let mut conn = pool.acquire().await?;
+
+    let revoked = match (&body.refresh_token, body.token_id, body.all) {
+        (Some(token), None, false) => {
+            let claims = decode_token(&config, token, TOKEN_TYPE_REFRESH)?;
+            let family_id: Option<String> =
+                sqlx::query_scalar("SELECT family_id FROM refresh_tokens WHERE jti = ?")
+                    .bind(&claims.jti)
+                    .fetch_optional(&mut *conn)
+                    .await?;
+            match family_id {
+                Some(family_id) => revoke_family(&mut conn, &family_id).await?,
+                None => 0,
+            }
+        }
+        (None, Some(token_id), false) => {
+            let session_user = get_current_user(&session)?;
+            let family_id: Option<String> = sqlx::query_scalar(
+                "SELECT family_id FROM refresh_tokens WHERE id = ? AND user_id = ?",
+            )
+            .bind(token_id)
+            .bind(session_user.id)
+            .fetch_optional(&mut *conn)
+            .await?;
+            let family_id =
+                family_id.ok_or_else(|| AppError::NotFound("Token not found".to_string()))?;
+            revoke_family(&mut conn, &family_id).await?
+        }
+        (None, None, true) => {
+            let session_user = get_current_user(&session)?;
+            sqlx::query(
+                "UPDATE refresh_tokens SET revoked_at = NOW() WHERE user_id = ? AND revoked_at IS NULL",
+            )
+            .bind(session_user.id)
+            .execute(&mut *conn)
+            .await?
+            .rows_affected()
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "refreshToken・tokenId・allのいずれか1つを指定してください".to_string(),
+            ))
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(RevokeResponse { revoked }))
+}
+
+/// GET /api/auth/tokens
+/// 有効なリフレッシュトークンを持つ端末の一覧
+#[get("/auth/tokens")]
+async fn list_tokens(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let rows: Vec<RefreshTokenRow> = sqlx::query_as(
+        r#"SELECT id, user_agent, created_at, last_used_at, expires_at
+           FROM refresh_tokens
+           WHERE user_id = ? AND revoked_at IS NULL AND expires_at > NOW()
+           ORDER BY COALESCE(last_used_at, created_at) DESC"#,
+    )
+    .bind(session_user.id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let tokens: Vec<RefreshTokenDto> = rows.into_iter().map(RefreshTokenDto::from).collect();
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(issue_token)
+        .service(refresh_token)
+        .service(revoke_token)
+        .service(list_tokens);
+}
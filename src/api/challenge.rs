@@ -0,0 +1,439 @@
+//! ウィークリーチャレンジAPIハンドラ
+//!
+//! 「今週は胸を3日鍛えよう」「今週の総挙上量20,000kg」などのチャレンジを週（月曜始まり）ごとに生成する。
+//! チャレンジは全ユーザー共通で、週ごとにテンプレートと対象部位が入れ替わる。
+//! 達成状況は記録の保存時とチャレンジ一覧の取得時にその週の記録から判定し、
+//! 達成したチャレンジを受け取るとEXPとコインが入る（受け取れるのはその週の間のみ）。
+
+use actix_web::{get, post, web, HttpResponse};
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::{HashMap, HashSet};
+
+use crate::api::coin::{fetch_coin_balance, grant_coins, CoinBalanceDto, COIN_SOURCE_CHALLENGE};
+use crate::api::dashboard::{map_muscle_to_group, MUSCLE_GROUPS};
+use crate::api::exp_ledger::{award_exp, EXP_SOURCE_CHALLENGE};
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+const CHALLENGE_TRAINING_DAYS: &str = "training_days";
+const CHALLENGE_TOTAL_VOLUME: &str = "total_volume";
+const CHALLENGE_TOTAL_SETS: &str = "total_sets";
+const CHALLENGE_MUSCLE_DAYS: &str = "muscle_days";
+
+struct ChallengeTemplate {
+    challenge_type: &'static str,
+    target_value: i32,
+    exp_reward: i32,
+    coin_reward: i32,
+}
+
+/// 1週間に出すチャレンジの数
+const CHALLENGES_PER_WEEK: usize = 3;
+
+/// チャレンジのテンプレート（CHALLENGES_PER_WEEKずつ週替わりで使う。同じ週に同じ種類が並ばないようにする）
+const CHALLENGE_POOL: [ChallengeTemplate; 6] = [
+    ChallengeTemplate {
+        challenge_type: CHALLENGE_MUSCLE_DAYS,
+        target_value: 3,
+        exp_reward: 300,
+        coin_reward: 10,
+    },
+    ChallengeTemplate {
+        challenge_type: CHALLENGE_TOTAL_VOLUME,
+        target_value: 20000,
+        exp_reward: 500,
+        coin_reward: 20,
+    },
+    ChallengeTemplate {
+        challenge_type: CHALLENGE_TRAINING_DAYS,
+        target_value: 4,
+        exp_reward: 300,
+        coin_reward: 10,
+    },
+    ChallengeTemplate {
+        challenge_type: CHALLENGE_TOTAL_SETS,
+        target_value: 60,
+        exp_reward: 400,
+        coin_reward: 15,
+    },
+    ChallengeTemplate {
+        challenge_type: CHALLENGE_MUSCLE_DAYS,
+        target_value: 2,
+        exp_reward: 200,
+        coin_reward: 5,
+    },
+    ChallengeTemplate {
+        challenge_type: CHALLENGE_TRAINING_DAYS,
+        target_value: 5,
+        exp_reward: 500,
+        coin_reward: 20,
+    },
+];
+
+/// 日付を含む週の月曜日
+pub(crate) fn week_start_of(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+/// 週の通し番号（テンプレートと対象部位の入れ替えに使う）
+fn week_index(week_start: NaiveDate) -> usize {
+    (week_start.num_days_from_ce() / 7) as usize
+}
+
+/// その週のテンプレート
+fn templates_for(week_start: NaiveDate) -> &'static [ChallengeTemplate] {
+    let sets = CHALLENGE_POOL.len() / CHALLENGES_PER_WEEK;
+    let offset = (week_index(week_start) % sets) * CHALLENGES_PER_WEEK;
+    &CHALLENGE_POOL[offset..offset + CHALLENGES_PER_WEEK]
+}
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChallengeDto {
+    id: i64,
+    challenge_type: String,
+    title: String,
+    target_muscle: Option<String>,
+    target_value: i32,
+    progress: i32,
+    exp_reward: i32,
+    coin_reward: i32,
+    completed: bool,
+    claimed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChallengesResponse {
+    week_start: String,
+    week_end: String,
+    challenges: Vec<ChallengeDto>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClaimChallengeResponse {
+    challenge_id: i64,
+    exp_earned: i32,
+    coins_earned: i32,
+    total_exp: i64,
+    level: i32,
+    coins: CoinBalanceDto,
+}
+
+#[derive(sqlx::FromRow)]
+struct ChallengeRow {
+    id: i64,
+    week_start: NaiveDate,
+    challenge_type: String,
+    target_muscle: Option<String>,
+    target_value: i32,
+    exp_reward: i32,
+    coin_reward: i32,
+    progress: i32,
+    completed_at: Option<NaiveDateTime>,
+    claimed_at: Option<NaiveDateTime>,
+}
+
+const CHALLENGE_COLUMNS: &str = "c.id, c.week_start, c.challenge_type, c.target_muscle, \
+                                 c.target_value, c.exp_reward, c.coin_reward, \
+                                 COALESCE(ucp.progress, 0) AS progress, \
+                                 ucp.completed_at, ucp.claimed_at";
+
+impl From<ChallengeRow> for ChallengeDto {
+    fn from(row: ChallengeRow) -> Self {
+        let title = match (row.challenge_type.as_str(), row.target_muscle.as_deref()) {
+            (CHALLENGE_MUSCLE_DAYS, Some(muscle)) => {
+                format!("今週は{}を{}日鍛えよう", muscle, row.target_value)
+            }
+            (CHALLENGE_TRAINING_DAYS, _) => {
+                format!("今週は{}日トレーニングしよう", row.target_value)
+            }
+            (CHALLENGE_TOTAL_VOLUME, _) => {
+                format!("今週の総挙上量{}kgを目指そう", row.target_value)
+            }
+            (CHALLENGE_TOTAL_SETS, _) => format!("今週は{}セット記録しよう", row.target_value),
+            _ => String::new(),
+        };
+        Self {
+            id: row.id,
+            challenge_type: row.challenge_type,
+            title,
+            target_muscle: row.target_muscle,
+            target_value: row.target_value,
+            progress: row.progress.min(row.target_value),
+            exp_reward: row.exp_reward,
+            coin_reward: row.coin_reward,
+            completed: row.completed_at.is_some(),
+            claimed: row.claimed_at.is_some(),
+        }
+    }
+}
+
+// ============================================
+// チャレンジの生成・達成判定
+// ============================================
+
+/// その週のトレーニング量（ウォームアップを除く）
+#[derive(Default)]
+struct WeeklyTrainingSummary {
+    days: HashSet<NaiveDate>,
+    volume: f64,
+    sets: i32,
+    days_by_group: HashMap<&'static str, HashSet<NaiveDate>>,
+}
+
+impl WeeklyTrainingSummary {
+    async fn load(
+        conn: &mut MySqlConnection,
+        user_id: i64,
+        week_start: NaiveDate,
+    ) -> Result<Self, AppError> {
+        let rows: Vec<(NaiveDate, Option<String>, i64, Option<f64>)> = sqlx::query_as(
+            r#"SELECT tr.record_date, CAST(COALESCE(e.muscle, uce.muscle) AS CHAR) AS muscle,
+                      COUNT(ts.id) AS set_count, SUM(ts.weight * ts.reps) AS volume
+               FROM training_records tr
+               INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+               INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+               LEFT JOIN exercises e ON e.id = tre.exercise_id
+               LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+               WHERE tr.user_id = ? AND tr.record_date BETWEEN ? AND ?
+                 AND ts.set_type <> 'warmup'
+               GROUP BY tr.record_date, tre.id, muscle"#,
+        )
+        .bind(user_id)
+        .bind(week_start)
+        .bind(week_start + Days::new(6))
+        .fetch_all(conn)
+        .await?;
+
+        let mut summary = Self::default();
+        for (date, muscle, count, volume) in rows {
+            summary.days.insert(date);
+            summary.sets += count as i32;
+            summary.volume += volume.unwrap_or(0.0);
+            if let Some(group) = muscle.as_deref().and_then(map_muscle_to_group) {
+                summary.days_by_group.entry(group).or_default().insert(date);
+            }
+        }
+        Ok(summary)
+    }
+
+    fn value_for(&self, challenge_type: &str, target_muscle: Option<&str>) -> i32 {
+        match challenge_type {
+            CHALLENGE_TRAINING_DAYS => self.days.len() as i32,
+            CHALLENGE_TOTAL_VOLUME => self.volume.min(i32::MAX as f64) as i32,
+            CHALLENGE_TOTAL_SETS => self.sets,
+            CHALLENGE_MUSCLE_DAYS => target_muscle
+                .and_then(|m| self.days_by_group.get(m))
+                .map_or(0, |days| days.len() as i32),
+            _ => 0,
+        }
+    }
+}
+
+/// その週のチャレンジを生成する（全ユーザー共通）
+async fn ensure_challenges(
+    conn: &mut MySqlConnection,
+    week_start: NaiveDate,
+) -> Result<(), AppError> {
+    let index = week_index(week_start);
+    for template in templates_for(week_start) {
+        let target_muscle = (template.challenge_type == CHALLENGE_MUSCLE_DAYS)
+            .then(|| MUSCLE_GROUPS[index % MUSCLE_GROUPS.len()]);
+        sqlx::query(
+            r#"INSERT IGNORE INTO challenges
+                   (week_start, challenge_type, target_muscle, target_value, exp_reward,
+                    coin_reward, created_at)
+               VALUES (?, ?, ?, ?, ?, ?, NOW())"#,
+        )
+        .bind(week_start)
+        .bind(template.challenge_type)
+        .bind(target_muscle)
+        .bind(template.target_value)
+        .bind(template.exp_reward)
+        .bind(template.coin_reward)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// その週の記録からチャレンジの進捗・達成を更新する（記録の保存時に呼び出す）
+/// 受け取り前のチャレンジは記録の削除で目標を下回ると未達成に戻る
+pub(crate) async fn update_challenge_progress(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    date: NaiveDate,
+) -> Result<(), AppError> {
+    let week_start = week_start_of(date);
+    ensure_challenges(conn, week_start).await?;
+
+    let challenges: Vec<(i64, String, Option<String>, i32)> = sqlx::query_as(
+        r#"SELECT c.id, c.challenge_type, c.target_muscle, c.target_value
+           FROM challenges c
+           LEFT JOIN user_challenge_progress ucp ON ucp.challenge_id = c.id AND ucp.user_id = ?
+           WHERE c.week_start = ? AND ucp.claimed_at IS NULL"#,
+    )
+    .bind(user_id)
+    .bind(week_start)
+    .fetch_all(&mut *conn)
+    .await?;
+    if challenges.is_empty() {
+        return Ok(());
+    }
+
+    let summary = WeeklyTrainingSummary::load(conn, user_id, week_start).await?;
+    for (challenge_id, challenge_type, target_muscle, target_value) in challenges {
+        let value = summary.value_for(&challenge_type, target_muscle.as_deref());
+        let completed = value >= target_value;
+        sqlx::query(
+            r#"INSERT INTO user_challenge_progress
+                   (user_id, challenge_id, progress, completed_at, updated_at)
+               VALUES (?, ?, ?, IF(?, NOW(), NULL), NOW())
+               ON DUPLICATE KEY UPDATE
+                   progress = VALUES(progress),
+                   completed_at = IF(?, COALESCE(completed_at, NOW()), NULL),
+                   updated_at = NOW()"#,
+        )
+        .bind(user_id)
+        .bind(challenge_id)
+        .bind(value)
+        .bind(completed)
+        .bind(completed)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/challenges
+/// 今週のチャレンジ一覧（進捗を最新の記録で更新してから返す）
+#[get("/challenges")]
+async fn get_challenges(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    let week_start = week_start_of(today);
+
+    let mut tx = pool.begin().await?;
+    update_challenge_progress(&mut tx, session_user.id, today).await?;
+    tx.commit().await?;
+
+    let rows: Vec<ChallengeRow> = sqlx::query_as(&format!(
+        r#"SELECT {} FROM challenges c
+           LEFT JOIN user_challenge_progress ucp ON ucp.challenge_id = c.id AND ucp.user_id = ?
+           WHERE c.week_start = ?
+           ORDER BY c.id ASC"#,
+        CHALLENGE_COLUMNS
+    ))
+    .bind(session_user.id)
+    .bind(week_start)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(ChallengesResponse {
+        week_start: week_start.format("%Y-%m-%d").to_string(),
+        week_end: (week_start + Days::new(6)).format("%Y-%m-%d").to_string(),
+        challenges: rows.into_iter().map(ChallengeDto::from).collect(),
+    }))
+}
+
+/// POST /api/challenges/{id}/claim
+/// 達成した今週のチャレンジの報酬（EXP・コイン）を受け取る
+#[post("/challenges/{id}/claim")]
+async fn claim_challenge(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let challenge_id = path.into_inner();
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let challenge: Option<ChallengeRow> = sqlx::query_as(&format!(
+        r#"SELECT {} FROM challenges c
+           LEFT JOIN user_challenge_progress ucp ON ucp.challenge_id = c.id AND ucp.user_id = ?
+           WHERE c.id = ?
+           FOR UPDATE"#,
+        CHALLENGE_COLUMNS
+    ))
+    .bind(session_user.id)
+    .bind(challenge_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let challenge =
+        challenge.ok_or_else(|| AppError::NotFound("Challenge not found".to_string()))?;
+
+    if challenge.claimed_at.is_some() {
+        return Err(AppError::BadRequest(
+            "このチャレンジの報酬は受け取り済みです".to_string(),
+        ));
+    }
+    if challenge.week_start != week_start_of(today) {
+        return Err(AppError::BadRequest(
+            "このチャレンジの受け取り期限は過ぎています".to_string(),
+        ));
+    }
+    if challenge.completed_at.is_none() {
+        return Err(AppError::BadRequest(
+            "このチャレンジはまだ達成していません".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        "UPDATE user_challenge_progress SET claimed_at = NOW() WHERE user_id = ? AND challenge_id = ?",
+    )
+    .bind(session_user.id)
+    .bind(challenge.id)
+    .execute(&mut *tx)
+    .await?;
+
+    let change = award_exp(
+        &mut tx,
+        session_user.id,
+        challenge.exp_reward as i64,
+        EXP_SOURCE_CHALLENGE,
+        Some(challenge.id),
+    )
+    .await?;
+    grant_coins(
+        &mut tx,
+        session_user.id,
+        challenge.coin_reward as i64,
+        COIN_SOURCE_CHALLENGE,
+        Some(challenge.id),
+    )
+    .await?;
+
+    let coins = fetch_coin_balance(&mut tx, session_user.id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ClaimChallengeResponse {
+        challenge_id: challenge.id,
+        exp_earned: challenge.exp_reward,
+        coins_earned: challenge.coin_reward,
+        total_exp: change.total_exp,
+        level: change.new_level,
+        coins,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_challenges).service(claim_challenge);
+}
@@ -0,0 +1,390 @@
+//! コインAPIハンドラ
+//!
+//! コインの残高はuser_stats.coinsで管理する。
+//! ログインボーナス・デイリーリワード・ウィークリーチャレンジ・レベルアップで獲得し（累計はcoins_awarded）、
+//! ショップ・ペットのお世話・ストリークの修復で使用する（累計はcoins_spent）。
+//! 増減のたびにcoin_transactionsへ理由（source）と増減後の残高を記録する。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+/// ログインボーナスで獲得するコイン
+pub(crate) const LOGIN_BONUS_COINS: i64 = 10;
+/// レベルが1上がるごとに獲得するコイン
+pub(crate) const LEVEL_UP_COINS: i64 = 50;
+
+/// ログインボーナス
+pub(crate) const COIN_SOURCE_LOGIN_BONUS: &str = "login_bonus";
+/// デイリーリワード（reference_idはリワード日）
+pub(crate) const COIN_SOURCE_DAILY_REWARD: &str = "daily_reward";
+/// ウィークリーチャレンジの報酬
+pub(crate) const COIN_SOURCE_CHALLENGE: &str = "challenge";
+/// レベルアップ
+pub(crate) const COIN_SOURCE_LEVEL_UP: &str = "level_up";
+/// ショップでの購入
+pub(crate) const COIN_SOURCE_SHOP: &str = "shop";
+/// ペットのお世話
+pub(crate) const COIN_SOURCE_PET_CARE: &str = "pet_care";
+/// 途切れたストリークの復活
+pub(crate) const COIN_SOURCE_STREAK_RECOVERY: &str = "streak_recovery";
+
+/// 1ページの件数の上限
+const MAX_PAGE_SIZE: i32 = 100;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinBalanceDto {
+    pub balance: i64,
+    pub earned: i64,
+    pub spent: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct CoinStatsRow {
+    coins: i64,
+    coins_awarded: i64,
+    coins_spent: i64,
+}
+
+impl From<CoinStatsRow> for CoinBalanceDto {
+    fn from(row: CoinStatsRow) -> Self {
+        Self {
+            balance: row.coins,
+            earned: row.coins_awarded,
+            spent: row.coins_spent,
+        }
+    }
+}
+
+/// コイン残高を取得
+pub(crate) async fn fetch_coin_balance(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<CoinBalanceDto, AppError> {
+    let stats: Option<CoinStatsRow> = sqlx::query_as(
+        "SELECT coins, coins_awarded, coins_spent FROM user_stats WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(stats.map(CoinBalanceDto::from).unwrap_or(CoinBalanceDto {
+        balance: 0,
+        earned: 0,
+        spent: 0,
+    }))
+}
+
+/// user_statsをロックして残高を取得する（未作成の場合は作成する）
+async fn lock_coin_stats(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<CoinStatsRow, AppError> {
+    let stats: Option<CoinStatsRow> = sqlx::query_as(
+        "SELECT coins, coins_awarded, coins_spent FROM user_stats WHERE user_id = ? FOR UPDATE",
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some(stats) = stats {
+        return Ok(stats);
+    }
+
+    sqlx::query(
+        r#"INSERT INTO user_stats (user_id, total_exp, level, created_at, updated_at)
+           VALUES (?, 0, 1, NOW(), NOW())"#,
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(CoinStatsRow {
+        coins: 0,
+        coins_awarded: 0,
+        coins_spent: 0,
+    })
+}
+
+async fn record_coin_transaction(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    amount: i64,
+    balance_after: i64,
+    source: &str,
+    reference_id: Option<i64>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"INSERT INTO coin_transactions (user_id, amount, balance_after, source, reference_id, created_at)
+           VALUES (?, ?, ?, ?, ?, NOW())"#,
+    )
+    .bind(user_id)
+    .bind(amount)
+    .bind(balance_after)
+    .bind(source)
+    .bind(reference_id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// コインを使用する（残高不足の場合はBadRequest）
+/// 同時に使用されても残高を超えないよう、user_statsをロックしてから確認する
+pub(crate) async fn spend_coins(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    amount: i64,
+    source: &str,
+    reference_id: Option<i64>,
+) -> Result<CoinBalanceDto, AppError> {
+    let mut stats = lock_coin_stats(&mut *conn, user_id).await?;
+    if stats.coins < amount {
+        return Err(AppError::BadRequest("コインが足りません".to_string()));
+    }
+    if amount <= 0 {
+        return Ok(stats.into());
+    }
+
+    sqlx::query(
+        r#"UPDATE user_stats SET coins = coins - ?, coins_spent = coins_spent + ?, updated_at = NOW()
+           WHERE user_id = ?"#,
+    )
+    .bind(amount)
+    .bind(amount)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+    stats.coins -= amount;
+    stats.coins_spent += amount;
+
+    record_coin_transaction(
+        &mut *conn,
+        user_id,
+        -amount,
+        stats.coins,
+        source,
+        reference_id,
+    )
+    .await?;
+    Ok(stats.into())
+}
+
+/// コインを付与する
+pub(crate) async fn grant_coins(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    amount: i64,
+    source: &str,
+    reference_id: Option<i64>,
+) -> Result<(), AppError> {
+    if amount <= 0 {
+        return Ok(());
+    }
+    let stats = lock_coin_stats(&mut *conn, user_id).await?;
+
+    sqlx::query(
+        r#"UPDATE user_stats SET coins = coins + ?, coins_awarded = coins_awarded + ?, updated_at = NOW()
+           WHERE user_id = ?"#,
+    )
+    .bind(amount)
+    .bind(amount)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    record_coin_transaction(
+        &mut *conn,
+        user_id,
+        amount,
+        stats.coins + amount,
+        source,
+        reference_id,
+    )
+    .await?;
+    Ok(())
+}
+
+/// 報酬を付与済みのレベルから上がった分のコイン（上がっていなければ0）
+fn level_up_coins(rewarded_level: i32, new_level: i32) -> i64 {
+    (new_level - rewarded_level).max(0) as i64 * LEVEL_UP_COINS
+}
+
+/// まだ報酬を付与していないレベルに上がった分のコインを付与する
+pub(crate) async fn grant_level_up_coins(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    new_level: i32,
+) -> Result<(), AppError> {
+    let rewarded_level: Option<i32> = sqlx::query_scalar(
+        "SELECT coins_rewarded_level FROM user_stats WHERE user_id = ? FOR UPDATE",
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some(rewarded_level) = rewarded_level else {
+        return Ok(());
+    };
+    let coins = level_up_coins(rewarded_level, new_level);
+    if coins == 0 {
+        return Ok(());
+    }
+
+    sqlx::query("UPDATE user_stats SET coins_rewarded_level = ? WHERE user_id = ?")
+        .bind(new_level)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+    grant_coins(
+        conn,
+        user_id,
+        coins,
+        COIN_SOURCE_LEVEL_UP,
+        Some(new_level as i64),
+    )
+    .await
+}
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct CoinHistoryQuery {
+    page: Option<i32>,
+    size: Option<i32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoinTransactionDto {
+    id: i64,
+    amount: i64,
+    balance_after: i64,
+    source: String,
+    reference_id: Option<i64>,
+    created_at: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct CoinTransactionRow {
+    id: i64,
+    amount: i64,
+    balance_after: i64,
+    source: String,
+    reference_id: Option<i64>,
+    created_at: NaiveDateTime,
+}
+
+impl From<CoinTransactionRow> for CoinTransactionDto {
+    fn from(row: CoinTransactionRow) -> Self {
+        Self {
+            id: row.id,
+            amount: row.amount,
+            balance_after: row.balance_after,
+            source: row.source,
+            reference_id: row.reference_id,
+            created_at: row.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CoinHistoryResponse {
+    content: Vec<CoinTransactionDto>,
+    page: i32,
+    size: i32,
+    total_elements: i64,
+    total_pages: i32,
+    has_next: bool,
+    has_previous: bool,
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/coins
+/// コイン残高
+#[get("/coins")]
+async fn get_coins(pool: web::Data<MySqlPool>, session: Session) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let balance = fetch_coin_balance(&mut *pool.acquire().await?, session_user.id).await?;
+    Ok(HttpResponse::Ok().json(balance))
+}
+
+/// GET /api/coins/transactions?page=0&size=20
+/// コインの増減履歴（新しい順）
+#[get("/coins/transactions")]
+async fn get_coin_transactions(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<CoinHistoryQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let page = query.page.unwrap_or(0).max(0);
+    let size = query.size.unwrap_or(20).clamp(1, MAX_PAGE_SIZE);
+
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM coin_transactions WHERE user_id = ?")
+        .bind(session_user.id)
+        .fetch_one(pool.get_ref())
+        .await?;
+
+    let rows: Vec<CoinTransactionRow> = sqlx::query_as(
+        r#"SELECT id, amount, balance_after, source, reference_id, created_at
+           FROM coin_transactions
+           WHERE user_id = ?
+           ORDER BY created_at DESC, id DESC
+           LIMIT ? OFFSET ?"#,
+    )
+    .bind(session_user.id)
+    .bind(size)
+    .bind(page * size)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let total_pages = ((total as f64) / (size as f64)).ceil() as i32;
+    Ok(HttpResponse::Ok().json(CoinHistoryResponse {
+        content: rows.into_iter().map(CoinTransactionDto::from).collect(),
+        page,
+        size,
+        total_elements: total,
+        total_pages,
+        has_next: page < total_pages - 1,
+        has_previous: page > 0,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_coins).service(get_coin_transactions);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_up_coins_are_paid_per_new_level() {
+        assert_eq!(level_up_coins(4, 5), LEVEL_UP_COINS);
+        assert_eq!(level_up_coins(4, 7), 3 * LEVEL_UP_COINS);
+    }
+
+    #[test]
+    fn already_rewarded_levels_pay_nothing() {
+        assert_eq!(level_up_coins(7, 7), 0);
+        // 管理者がレベルを下げた後に上がり直しても二重には付与しない
+        assert_eq!(level_up_coins(7, 5), 0);
+    }
+
+    #[test]
+    fn balance_dto_maps_the_stats_columns() {
+        let dto = CoinBalanceDto::from(CoinStatsRow {
+            coins: 30,
+            coins_awarded: 100,
+            coins_spent: 70,
+        });
+        assert_eq!((dto.balance, dto.earned, dto.spent), (30, 100, 70));
+    }
+}
@@ -1,15 +1,19 @@
 use actix_multipart::Multipart;
-use actix_session::Session;
-use actix_web::{post, web, HttpResponse};
+use actix_web::{post, web, HttpRequest, HttpResponse};
 use chrono::Utc;
 use futures::StreamExt;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
 use std::fs;
 
-use crate::auth::session::get_current_user;
+use crate::api::email_verification::find_verified_email;
+use crate::auth::login_throttle::client_ip;
+use crate::auth::session::{get_current_user, Session};
+use crate::captcha::Captcha;
 use crate::config::AppConfig;
 use crate::error::AppError;
+use crate::shared_store::SharedStore;
 
 /// 禁止ワード設定
 #[derive(Deserialize, Clone)]
@@ -49,7 +53,7 @@ struct BannedWordErrorResponse {
 }
 
 /// テキストに禁止ワードが含まれているかチェック
-fn contains_banned_word(text: &str) -> bool {
+pub(crate) fn contains_banned_word(text: &str) -> bool {
     let config = &*BANNED_WORDS;
     if config.words.is_empty() {
         return false;
@@ -76,6 +80,8 @@ fn contains_banned_word(text: &str) -> bool {
 
 const MAX_IMAGE_SIZE: usize = 2 * 1024 * 1024; // 2MB
 const MAX_IMAGE_COUNT: usize = 4;
+/// 1ユーザーあたりの送信上限（1時間）
+const MAX_SUBMISSIONS_PER_HOUR: u64 = 5;
 const ALLOWED_MIMES: [&str; 4] = ["image/jpeg", "image/png", "image/gif", "image/webp"];
 
 #[derive(Deserialize)]
@@ -95,6 +101,9 @@ struct ContactRequest {
     screen_width: Option<i32>,
     #[serde(rename = "screenHeight")]
     screen_height: Option<i32>,
+    /// CAPTCHAのトークン（CAPTCHA有効時のみ必須）
+    #[serde(rename = "captchaToken")]
+    captcha_token: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -154,7 +163,7 @@ fn validate_optional(text: Option<String>, max: usize) -> Result<Option<String>,
     }
 }
 
-fn validate_email(text: &str) -> bool {
+pub(crate) fn validate_email(text: &str) -> bool {
     let trimmed = text.trim();
     if trimmed.len() < 5 || trimmed.len() > 200 {
         return false;
@@ -201,12 +210,24 @@ fn get_extension_from_mime(mime: &str) -> &'static str {
 
 #[post("/contact")]
 async fn submit_contact(
+    req: HttpRequest,
+    pool: web::Data<MySqlPool>,
     config: web::Data<AppConfig>,
+    captcha: web::Data<Captcha>,
+    store: web::Data<SharedStore>,
     session: Session,
     mut payload: Multipart,
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
 
+    store
+        .check_rate_limit(
+            &format!("contact:{}", session_user.id),
+            MAX_SUBMISSIONS_PER_HOUR,
+            std::time::Duration::from_secs(60 * 60),
+        )
+        .await?;
+
     if config.discord_webhook_url.trim().is_empty() {
         return Err(AppError::InternalError(
             "通知設定が未完了です".to_string(),
@@ -302,6 +323,14 @@ async fn submit_contact(
         AppError::BadRequest(format!("JSONの解析に失敗しました: {}", e))
     })?;
 
+    // ボットによるWebhookへのスパムを防ぐ
+    let ip = client_ip(&req);
+    if !captcha.verify(body.captcha_token.as_deref(), Some(&ip)).await {
+        return Err(AppError::BadRequest(
+            "CAPTCHAの確認に失敗しました。もう一度お試しください".to_string(),
+        ));
+    }
+
     // Validate fields
     let kind = body.kind.trim();
     let kind_display = kind_label(kind).ok_or_else(|| {
@@ -345,6 +374,14 @@ async fn submit_contact(
                 "返信用メールアドレスを正しい形式で入力してください".to_string(),
             ));
         }
+        // 第三者のメールアドレスに返信しないよう、確認済みのメールアドレスにだけ返信する
+        let verified = find_verified_email(pool.get_ref(), session_user.id).await?;
+        if !verified.is_some_and(|v| v.eq_ignore_ascii_case(email_value.trim())) {
+            return Err(AppError::BadRequest(
+                "返信を希望する場合は、設定画面で確認済みのメールアドレスを入力してください"
+                    .to_string(),
+            ));
+        }
     }
 
     let page_path = body.page_path.trim();
@@ -1,39 +1,33 @@
 //! デイリーリワードAPIハンドラ
-//! 14日連続ログインボーナスシステム
+//! 連続ログインボーナスシステム
+//!
+//! 日ごとの報酬（EXP・コイン・アイテム）はdaily_reward_configで管理する。
+//! 登録された最終日を受け取ると1日目に戻る。
 
-use actix_session::Session;
 use actix_web::{get, post, web, HttpResponse};
-use chrono::{NaiveDate, Utc};
+use chrono::NaiveDate;
 use serde::Serialize;
-use sqlx::MySqlPool;
-
-use crate::auth::session::get_current_user;
-use crate::db::models::UserStats;
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::coin::{grant_coins, COIN_SOURCE_DAILY_REWARD};
+use crate::api::exp_context::ExpContext;
+use crate::api::exp_ledger::{award_exp, EXP_SOURCE_DAILY_REWARD};
+use crate::api::shop::add_item;
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::DailyRewardConfig;
 use crate::error::AppError;
 
 // ============================================
-// 定数 - リワード設定
+// 定数 - 報酬の種類
 // ============================================
 
-/// 各日（1-14）のリワード（EXPのみ）
-/// 7日目: ビッグリワード
-/// 14日目: スーパーリワード
-const REWARDS: [i32; 14] = [
-    200,  // Day 1
-    200,  // Day 2
-    200,  // Day 3
-    200,  // Day 4
-    200,  // Day 5
-    200,  // Day 6
-    500,  // Day 7 (Big reward)
-    200,  // Day 8
-    200,  // Day 9
-    200,  // Day 10
-    200,  // Day 11
-    200,  // Day 12
-    200,  // Day 13
-    1000, // Day 14 (Super reward)
-];
+/// EXP（ストリーク倍率・イベント倍率を適用する）
+pub(crate) const REWARD_TYPE_EXP: &str = "exp";
+/// コイン
+pub(crate) const REWARD_TYPE_COINS: &str = "coins";
+/// アイテム（amountは個数）
+pub(crate) const REWARD_TYPE_ITEM: &str = "item";
 
 // ============================================
 // レスポンス型
@@ -45,7 +39,20 @@ pub struct DailyRewardDay {
     pub claimed: bool,
     #[serde(rename = "claimedDate")]
     pub claimed_date: Option<String>,
+    /// 報酬の種類（exp, coins, item）
+    #[serde(rename = "rewardType")]
+    pub reward_type: String,
+    /// EXP・コインの量、またはアイテムの個数
+    pub amount: i32,
+    #[serde(rename = "itemCode")]
+    pub item_code: Option<String>,
+    #[serde(rename = "itemName")]
+    pub item_name: Option<String>,
+    /// 基本EXP（EXP以外の報酬の日は0）
     pub exp: i32,
+    /// 倍率適用後のEXP（受取済みの日は実際に獲得したEXP）
+    #[serde(rename = "boostedExp")]
+    pub boosted_exp: i32,
     #[serde(rename = "isBigReward")]
     pub is_big_reward: bool,
 }
@@ -56,6 +63,11 @@ pub struct DailyRewardsResponse {
     pub current_day: i32,
     #[serde(rename = "todayClaimed")]
     pub today_claimed: bool,
+    /// 1サイクルの日数
+    #[serde(rename = "cycleLength")]
+    pub cycle_length: i32,
+    /// 現在のストリーク倍率×イベント倍率（受取時に基本EXPへ適用される）
+    pub multiplier: f64,
     pub days: Vec<DailyRewardDay>,
 }
 
@@ -66,8 +78,17 @@ pub struct ClaimRewardResponse {
     pub already_claimed: bool,
     #[serde(rename = "rewardDay")]
     pub reward_day: i32,
+    #[serde(rename = "rewardType")]
+    pub reward_type: Option<String>,
     #[serde(rename = "expEarned")]
     pub exp_earned: i32,
+    #[serde(rename = "coinsEarned")]
+    pub coins_earned: i32,
+    /// 獲得したアイテム
+    #[serde(rename = "itemCode")]
+    pub item_code: Option<String>,
+    #[serde(rename = "itemQuantity")]
+    pub item_quantity: i32,
     #[serde(rename = "totalExp")]
     pub total_exp: i64,
 }
@@ -76,10 +97,20 @@ pub struct ClaimRewardResponse {
 // データベース型
 // ============================================
 
+/// 日ごとの報酬（アイテムの場合はアイテム情報付き）
+#[derive(sqlx::FromRow)]
+pub(crate) struct RewardScheduleRow {
+    #[sqlx(flatten)]
+    pub config: DailyRewardConfig,
+    pub item_code: Option<String>,
+    pub item_name: Option<String>,
+}
+
 #[derive(sqlx::FromRow)]
 struct LoginHistoryRow {
     pub login_date: NaiveDate,
     pub reward_day: i32,
+    pub exp_earned: i64,
     #[allow(dead_code)]
     pub bonus_claimed: bool,
 }
@@ -88,8 +119,33 @@ struct LoginHistoryRow {
 // ヘルパー関数
 // ============================================
 
-/// 履歴に基づいてユーザーの現在のリワード日（1-14）を取得
-async fn get_current_reward_day(pool: &MySqlPool, user_id: i64) -> Result<i32, AppError> {
+/// 日ごとの報酬を1日目から順に取得
+pub(crate) async fn fetch_reward_schedule(
+    conn: &mut MySqlConnection,
+) -> Result<Vec<RewardScheduleRow>, AppError> {
+    let schedule = sqlx::query_as(
+        r#"SELECT c.day, c.reward_type, c.amount, c.item_id, c.is_big_reward,
+                  i.code AS item_code, i.name AS item_name
+           FROM daily_reward_config c
+           LEFT JOIN items i ON i.id = c.item_id
+           ORDER BY c.day"#,
+    )
+    .fetch_all(conn)
+    .await?;
+    Ok(schedule)
+}
+
+/// 1サイクルの日数（登録されている最終日）
+fn cycle_length(schedule: &[RewardScheduleRow]) -> i32 {
+    schedule.last().map(|r| r.config.day).unwrap_or(0)
+}
+
+/// 履歴に基づいてユーザーの現在のリワード日（1〜cycle_length）を取得
+async fn get_current_reward_day(
+    pool: &MySqlPool,
+    user_id: i64,
+    cycle_length: i32,
+) -> Result<i32, AppError> {
     // 最後に受け取ったリワード日を取得
     let last_claimed: Option<(i32,)> = sqlx::query_as(
         "SELECT reward_day FROM user_login_history 
@@ -100,16 +156,19 @@ async fn get_current_reward_day(pool: &MySqlPool, user_id: i64) -> Result<i32, A
     .fetch_optional(pool)
     .await?;
 
-    match last_claimed {
-        Some((day,)) => {
-            // 14日目が受け取られた場合、1日目に戻る
-            if day >= 14 {
-                Ok(1)
-            } else {
-                Ok(day + 1)
-            }
-        }
-        None => Ok(1), // 初回は1日目から
+    Ok(next_reward_day(
+        last_claimed.map(|(day,)| day),
+        cycle_length,
+    ))
+}
+
+/// 最後に受け取った日の次のリワード日
+fn next_reward_day(last_claimed_day: Option<i32>, cycle_length: i32) -> i32 {
+    match last_claimed_day {
+        // 最終日が受け取られた場合（サイクルが短縮された場合を含む）、1日目に戻る
+        Some(day) if day >= cycle_length => 1,
+        Some(day) => day + 1,
+        None => 1, // 初回は1日目から
     }
 }
 
@@ -117,14 +176,16 @@ async fn get_current_reward_day(pool: &MySqlPool, user_id: i64) -> Result<i32, A
 async fn get_claimed_days(
     pool: &MySqlPool,
     user_id: i64,
+    cycle_length: i32,
 ) -> Result<Vec<LoginHistoryRow>, AppError> {
-    // 最後の14日目受取を取得してサイクル開始を決定
+    // 最後の最終日受取を取得してサイクル開始を決定
     let cycle_start: Option<(NaiveDate,)> = sqlx::query_as(
         "SELECT login_date FROM user_login_history 
-         WHERE user_id = ? AND reward_day = 14 AND bonus_claimed = TRUE 
+         WHERE user_id = ? AND reward_day >= ? AND bonus_claimed = TRUE 
          ORDER BY login_date DESC LIMIT 1",
     )
     .bind(user_id)
+    .bind(cycle_length)
     .fetch_optional(pool)
     .await?;
 
@@ -132,7 +193,8 @@ async fn get_claimed_days(
         Some((start_date,)) => {
             // 最後のサイクルリセット後に受け取った日を取得
             sqlx::query_as(
-                "SELECT login_date, reward_day, bonus_claimed FROM user_login_history 
+                "SELECT login_date, reward_day, CAST(COALESCE(exp_earned, 0) AS SIGNED) AS exp_earned, bonus_claimed
+                 FROM user_login_history 
                  WHERE user_id = ? AND login_date > ? AND bonus_claimed = TRUE
                  ORDER BY reward_day ASC",
             )
@@ -144,7 +206,8 @@ async fn get_claimed_days(
         None => {
             // まだサイクルリセットなし、全ての受取日を取得
             sqlx::query_as(
-                "SELECT login_date, reward_day, bonus_claimed FROM user_login_history 
+                "SELECT login_date, reward_day, CAST(COALESCE(exp_earned, 0) AS SIGNED) AS exp_earned, bonus_claimed
+                 FROM user_login_history 
                  WHERE user_id = ? AND bonus_claimed = TRUE
                  ORDER BY reward_day ASC",
             )
@@ -158,8 +221,11 @@ async fn get_claimed_days(
 }
 
 /// 今日のリワードが既に受け取られたか確認
-async fn is_today_claimed(pool: &MySqlPool, user_id: i64) -> Result<bool, AppError> {
-    let today = Utc::now().date_naive();
+async fn is_today_claimed(
+    pool: &MySqlPool,
+    user_id: i64,
+    today: NaiveDate,
+) -> Result<bool, AppError> {
     let existing: Option<(bool,)> = sqlx::query_as(
         "SELECT bonus_claimed FROM user_login_history WHERE user_id = ? AND login_date = ?",
     )
@@ -176,7 +242,7 @@ async fn is_today_claimed(pool: &MySqlPool, user_id: i64) -> Result<bool, AppErr
 // ============================================
 
 /// GET /api/daily-rewards
-/// 14日間のリワードステータスを取得
+/// 1サイクル分のリワードステータスを取得
 #[get("/daily-rewards")]
 pub async fn get_daily_rewards(
     pool: web::Data<MySqlPool>,
@@ -185,21 +251,40 @@ pub async fn get_daily_rewards(
     let session_user = get_current_user(&session)?;
     let user_id = session_user.id;
 
-    let current_day = get_current_reward_day(pool.get_ref(), user_id).await?;
-    let claimed_history = get_claimed_days(pool.get_ref(), user_id).await?;
-    let today_claimed = is_today_claimed(pool.get_ref(), user_id).await?;
-
-    // 14日分のレスポンスを構築
-    let days: Vec<DailyRewardDay> = (1..=14)
-        .map(|day| {
+    let schedule = fetch_reward_schedule(&mut *pool.acquire().await?).await?;
+    let cycle_length = cycle_length(&schedule);
+    let current_day = get_current_reward_day(pool.get_ref(), user_id, cycle_length).await?;
+    let claimed_history = get_claimed_days(pool.get_ref(), user_id, cycle_length).await?;
+    let today = fetch_user_today(pool.get_ref(), user_id).await?;
+    let today_claimed = is_today_claimed(pool.get_ref(), user_id, today).await?;
+    let exp_context = ExpContext::load(pool.get_ref(), user_id).await?;
+
+    // 1サイクル分のレスポンスを構築
+    let days: Vec<DailyRewardDay> = schedule
+        .into_iter()
+        .map(|reward| {
+            let day = reward.config.day;
             let claimed_info = claimed_history.iter().find(|h| h.reward_day == day);
+            let exp = if reward.config.reward_type == REWARD_TYPE_EXP {
+                reward.config.amount
+            } else {
+                0
+            };
 
             DailyRewardDay {
                 day,
                 claimed: claimed_info.is_some(),
                 claimed_date: claimed_info.map(|h| h.login_date.format("%Y-%m-%d").to_string()),
-                exp: REWARDS[(day - 1) as usize],
-                is_big_reward: day == 7 || day == 14,
+                reward_type: reward.config.reward_type,
+                amount: reward.config.amount,
+                item_code: reward.item_code,
+                item_name: reward.item_name,
+                exp,
+                boosted_exp: match claimed_info {
+                    Some(h) => h.exp_earned as i32,
+                    None => exp_context.apply_reward_multiplier(exp),
+                },
+                is_big_reward: reward.config.is_big_reward,
             }
         })
         .collect();
@@ -207,6 +292,8 @@ pub async fn get_daily_rewards(
     Ok(HttpResponse::Ok().json(DailyRewardsResponse {
         current_day,
         today_claimed,
+        cycle_length,
+        multiplier: exp_context.reward_multiplier(),
         days,
     }))
 }
@@ -220,10 +307,10 @@ pub async fn claim_daily_reward(
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
     let user_id = session_user.id;
-    let today = Utc::now().date_naive();
+    let today = fetch_user_today(pool.get_ref(), user_id).await?;
 
     // 今日既に受け取ったか確認
-    if is_today_claimed(pool.get_ref(), user_id).await? {
+    if is_today_claimed(pool.get_ref(), user_id, today).await? {
         // 現在のステータスを取得して返す
         let stats: Option<(i64,)> = sqlx::query_as(
             "SELECT COALESCE(total_exp, 0) FROM user_stats WHERE user_id = ?",
@@ -238,22 +325,46 @@ pub async fn claim_daily_reward(
             success: true,
             already_claimed: true,
             reward_day: 0,
+            reward_type: None,
             exp_earned: 0,
+            coins_earned: 0,
+            item_code: None,
+            item_quantity: 0,
             total_exp,
         }));
     }
 
-    // 現在の日を取得
-    let current_day = get_current_reward_day(pool.get_ref(), user_id).await?;
-    let base_exp_reward = REWARDS[(current_day - 1) as usize];
-
-    // EXPにストリーク倍率を適用
-    let (training_mult, login_mult, _) =
-        crate::api::streak::get_user_multipliers(pool.get_ref(), user_id).await?;
-    let streak_multiplier = 1.0 + training_mult + login_mult;
-    let exp_reward = (base_exp_reward as f64 * streak_multiplier).round() as i32;
+    // 現在の日の報酬を取得
+    let schedule = fetch_reward_schedule(&mut *pool.acquire().await?).await?;
+    let current_day =
+        get_current_reward_day(pool.get_ref(), user_id, cycle_length(&schedule)).await?;
+    let reward = schedule
+        .into_iter()
+        .find(|r| r.config.day == current_day)
+        .ok_or_else(|| {
+            AppError::InternalError("デイリーリワードの報酬が設定されていません".to_string())
+        })?;
+
+    // EXPにストリーク倍率・イベント倍率を適用（コイン・アイテムには適用しない）
+    let exp_context = ExpContext::load(pool.get_ref(), user_id).await?;
+    let reward_type = reward.config.reward_type.as_str();
+    let exp_reward = if reward_type == REWARD_TYPE_EXP {
+        exp_context.apply_reward_multiplier(reward.config.amount)
+    } else {
+        0
+    };
+    let coins_reward = if reward_type == REWARD_TYPE_COINS {
+        reward.config.amount
+    } else {
+        0
+    };
+    let item_reward = match (reward_type, reward.config.item_id) {
+        (REWARD_TYPE_ITEM, Some(item_id)) => Some((item_id, reward.config.amount)),
+        _ => None,
+    };
 
-    // 受取を記録（ブーストEXPを保存）
+    // 受取の記録と報酬の付与を同時に行う（ブーストEXPを保存）
+    let mut tx = pool.begin().await?;
     sqlx::query(
         "INSERT INTO user_login_history (user_id, login_date, bonus_claimed, exp_earned, reward_day, created_at)
          VALUES (?, ?, TRUE, ?, ?, NOW())
@@ -265,46 +376,49 @@ pub async fn claim_daily_reward(
     .bind(current_day)
     .bind(exp_reward)
     .bind(current_day)
-    .execute(pool.get_ref())
+    .execute(&mut *tx)
     .await?;
 
     // user_statsにEXPを追加
     if exp_reward > 0 {
-        sqlx::query(
-            "UPDATE user_stats SET total_exp = total_exp + ?, updated_at = NOW() WHERE user_id = ?",
+        award_exp(
+            &mut tx,
+            user_id,
+            exp_reward as i64,
+            EXP_SOURCE_DAILY_REWARD,
+            None,
         )
-        .bind(exp_reward)
-        .bind(user_id)
-        .execute(pool.get_ref())
         .await?;
-
-        // レベルを再計算
-        let stats: (i64,) =
-            sqlx::query_as("SELECT COALESCE(total_exp, 0) FROM user_stats WHERE user_id = ?")
-                .bind(user_id)
-                .fetch_one(pool.get_ref())
-                .await?;
-
-        let new_level = UserStats::calculate_level(stats.0);
-        sqlx::query("UPDATE user_stats SET level = ? WHERE user_id = ?")
-            .bind(new_level)
-            .bind(user_id)
-            .execute(pool.get_ref())
-            .await?;
     }
+    if coins_reward > 0 {
+        grant_coins(
+            &mut tx,
+            user_id,
+            coins_reward as i64,
+            COIN_SOURCE_DAILY_REWARD,
+            Some(current_day as i64),
+        )
+        .await?;
+    }
+    if let Some((item_id, quantity)) = item_reward.filter(|(_, quantity)| *quantity > 0) {
+        add_item(&mut tx, user_id, item_id, quantity).await?;
+    }
+    tx.commit().await?;
 
     // アクティブペットにも同量の経験値を付与
+    use crate::api::pet::{add_exp_to_active_pet, check_and_unlock_pet_types};
+    let mut pet_matured = false;
     if exp_reward > 0 {
-        use crate::api::pet::{add_exp_to_active_pet, check_and_unlock_pet_types};
-        if let Ok(Some((_pet_level, _level_up, matured))) = 
-            add_exp_to_active_pet(pool.get_ref(), user_id, exp_reward as i64).await 
+        if let Ok(Some(gain)) =
+            add_exp_to_active_pet(&mut *pool.acquire().await?, user_id, exp_reward as i64).await
         {
-            // ペットが成熟したら解放条件をチェック
-            if matured {
-                let _ = check_and_unlock_pet_types(pool.get_ref(), user_id).await;
-            }
+            pet_matured = gain.matured;
         }
     }
+    // ペットが成熟した場合・イベントのペットがある場合は解放条件をチェック
+    if pet_matured || exp_context.has_event_pet() {
+        let _ = check_and_unlock_pet_types(pool.get_ref(), user_id).await;
+    }
 
     // 更新後のステータスを取得
     let stats: Option<(i64,)> = sqlx::query_as(
@@ -320,7 +434,11 @@ pub async fn claim_daily_reward(
         success: true,
         already_claimed: false,
         reward_day: current_day,
+        reward_type: Some(reward.config.reward_type),
         exp_earned: exp_reward,
+        coins_earned: coins_reward,
+        item_code: item_reward.and(reward.item_code),
+        item_quantity: item_reward.map(|(_, quantity)| quantity).unwrap_or(0),
         total_exp,
     }))
 }
@@ -328,3 +446,46 @@ pub async fn claim_daily_reward(
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_daily_rewards).service(claim_daily_reward);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule_day(day: i32, reward_type: &str) -> RewardScheduleRow {
+        RewardScheduleRow {
+            config: DailyRewardConfig {
+                day,
+                reward_type: reward_type.to_string(),
+                amount: 10,
+                item_id: None,
+                is_big_reward: false,
+            },
+            item_code: None,
+            item_name: None,
+        }
+    }
+
+    #[test]
+    fn cycle_length_is_the_last_configured_day() {
+        let schedule = vec![
+            schedule_day(1, REWARD_TYPE_EXP),
+            schedule_day(2, REWARD_TYPE_COINS),
+            schedule_day(3, REWARD_TYPE_ITEM),
+        ];
+        assert_eq!(cycle_length(&schedule), 3);
+        assert_eq!(cycle_length(&[]), 0);
+    }
+
+    #[test]
+    fn reward_day_advances_and_wraps_after_the_last_day() {
+        assert_eq!(next_reward_day(None, 7), 1);
+        assert_eq!(next_reward_day(Some(3), 7), 4);
+        assert_eq!(next_reward_day(Some(7), 7), 1);
+    }
+
+    #[test]
+    fn shortened_cycle_restarts_from_the_first_day() {
+        // 10日目まで受け取った後に管理者がサイクルを7日に短縮した場合
+        assert_eq!(next_reward_day(Some(10), 7), 1);
+    }
+}
@@ -1,13 +1,15 @@
 //! ダッシュボードAPIハンドラ
 
-use actix_session::Session;
 use actix_web::{get, web, HttpResponse};
-use chrono::{Datelike, Days, NaiveDate, Utc};
+use chrono::{Datelike, Days, NaiveDate};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 use std::collections::HashMap;
 
-use crate::auth::session::get_current_user;
+use crate::api::streak::fetch_user_today;
+use crate::api::workout::resolve_exercise;
+use crate::auth::session::{get_current_user, Session};
+use crate::domain::one_rm::OneRmFormula;
 use crate::error::AppError;
 
 #[derive(Serialize)]
@@ -21,8 +23,23 @@ struct HeatmapResponse {
     #[serde(rename = "endDate")]
     end_date: String,
     year: i32,
+    /// レベル1→2、2→3、3→4の境界ボリューム（kg）
+    thresholds: [f64; 3],
+    /// 実際に使用したしきい値モード（ADAPTIVE / FIXED）
+    #[serde(rename = "thresholdMode")]
+    threshold_mode: &'static str,
 }
 
+/// ユーザー自身のボリューム分位点からしきい値を決める
+pub(crate) const HEATMAP_MODE_ADAPTIVE: &str = "ADAPTIVE";
+/// 固定しきい値（1,000 / 2,500 / 5,000kg）
+pub(crate) const HEATMAP_MODE_FIXED: &str = "FIXED";
+
+const FIXED_THRESHOLDS: [f64; 3] = [1000.0, 2500.0, 5000.0];
+
+/// 分位点の算出に必要な最低トレーニング日数（未満の場合は固定しきい値）
+const MIN_ADAPTIVE_DAYS: usize = 7;
+
 #[derive(Deserialize)]
 struct HeatmapQuery {
     year: Option<i32>,
@@ -43,7 +60,12 @@ async fn get_heatmap(
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
 
-    let year = query.year.unwrap_or_else(|| chrono::Utc::now().year());
+    let year = match query.year {
+        Some(year) => year,
+        None => fetch_user_today(pool.get_ref(), session_user.id)
+            .await?
+            .year(),
+    };
     let start_date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
     let end_date = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
 
@@ -59,6 +81,7 @@ async fn get_heatmap(
         WHERE tr.user_id = ? 
           AND tr.record_date >= ?
           AND tr.record_date <= ?
+          AND ts.set_type <> 'warmup'
         GROUP BY tr.record_date
         ORDER BY tr.record_date
         "#,
@@ -69,6 +92,23 @@ async fn get_heatmap(
     .fetch_all(pool.get_ref())
     .await?;
 
+    let heatmap_mode: Option<(String,)> =
+        sqlx::query_as("SELECT heatmap_mode FROM user_settings WHERE user_id = ?")
+            .bind(session_user.id)
+            .fetch_optional(pool.get_ref())
+            .await?;
+    let adaptive = heatmap_mode.is_none_or(|(mode,)| mode != HEATMAP_MODE_FIXED);
+
+    let (thresholds, threshold_mode) = if adaptive {
+        let volumes: Vec<f64> = daily_volumes.iter().map(|dv| dv.volume).collect();
+        match adaptive_thresholds(&volumes) {
+            Some(t) => (t, HEATMAP_MODE_ADAPTIVE),
+            None => (FIXED_THRESHOLDS, HEATMAP_MODE_FIXED),
+        }
+    } else {
+        (FIXED_THRESHOLDS, HEATMAP_MODE_FIXED)
+    };
+
     // 日付 -> ボリュームのマップを作成
     let volume_by_date: HashMap<NaiveDate, f64> = daily_volumes
         .into_iter()
@@ -83,7 +123,7 @@ async fn get_heatmap(
     while current_date <= end_date {
         let date_str = current_date.format("%Y-%m-%d").to_string();
         let volume = volume_by_date.get(&current_date).copied().unwrap_or(0.0);
-        let level = calculate_activity_level(volume);
+        let level = calculate_activity_level(volume, &thresholds);
 
         heatmap_data.insert(date_str.clone(), level);
         volume_data.insert(date_str, volume);
@@ -97,32 +137,57 @@ async fn get_heatmap(
         start_date: start_date.format("%Y-%m-%d").to_string(),
         end_date: end_date.format("%Y-%m-%d").to_string(),
         year,
+        thresholds,
+        threshold_mode,
     }))
 }
 
 /// ボリュームからアクティビティレベル（0-4）を計算
 /// - 0: 0kg（休息日）
-/// - 1: 1〜1,000kg（軽い日）
-/// - 2: 1,000〜2,500kg（標準トレーニング）
-/// - 3: 2,500〜5,000kg（しっかりトレーニング）
-/// - 4: 5,000kg+（ハードな日）
-fn calculate_activity_level(volume: f64) -> i32 {
+/// - 1: thresholds[0]未満（軽い日）
+/// - 2: thresholds[1]未満（標準トレーニング）
+/// - 3: thresholds[2]未満（しっかりトレーニング）
+/// - 4: thresholds[2]以上（ハードな日）
+///
+/// 固定しきい値は1,000 / 2,500 / 5,000kg
+fn calculate_activity_level(volume: f64, thresholds: &[f64; 3]) -> i32 {
     if volume == 0.0 {
         0
-    } else if volume < 1000.0 {
+    } else if volume < thresholds[0] {
         1
-    } else if volume < 2500.0 {
+    } else if volume < thresholds[1] {
         2
-    } else if volume < 5000.0 {
+    } else if volume < thresholds[2] {
         3
     } else {
         4
     }
 }
 
+/// トレーニング日のボリュームの25/50/75パーセンタイルをしきい値にする
+/// トレーニング日数がMIN_ADAPTIVE_DAYS未満の場合はNone
+fn adaptive_thresholds(volumes: &[f64]) -> Option<[f64; 3]> {
+    let mut sorted: Vec<f64> = volumes.iter().copied().filter(|v| *v > 0.0).collect();
+    if sorted.len() < MIN_ADAPTIVE_DAYS {
+        return None;
+    }
+    sorted.sort_by(|a, b| a.total_cmp(b));
+
+    // nearest-rank法
+    let percentile = |p: usize| {
+        let rank = (p * sorted.len()).div_ceil(100).max(1);
+        sorted[rank - 1].round()
+    };
+
+    Some([percentile(25), percentile(50), percentile(75)])
+}
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_heatmap);
     cfg.service(get_muscle_heatmap);
+    cfg.service(get_set_targets);
+    cfg.service(get_progression);
+    cfg.service(get_volume_by_muscle);
 }
 
 // ============================================
@@ -159,7 +224,7 @@ async fn get_muscle_heatmap(
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
 
-    let today = Utc::now().date_naive();
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
     let thirty_days_ago = today.checked_sub_days(Days::new(30)).unwrap_or(today);
     let seven_days_ago = today.checked_sub_days(Days::new(7)).unwrap_or(today);
 
@@ -184,12 +249,9 @@ async fn get_muscle_heatmap(
     .fetch_all(pool.get_ref())
     .await?;
 
-    // 筋肉グループの定義
-    let muscle_groups = vec!["胸", "背中", "肩", "腕", "脚", "腹"];
-
     // 筋肉グループごとに集計
     let mut muscle_data: HashMap<&str, (Option<NaiveDate>, i32)> = HashMap::new();
-    for mg in &muscle_groups {
+    for mg in &MUSCLE_GROUPS {
         muscle_data.insert(mg, (None, 0));
     }
 
@@ -213,7 +275,7 @@ async fn get_muscle_heatmap(
     }
 
     // レスポンス構築
-    let muscles: Vec<MuscleHeatmapItem> = muscle_groups
+    let muscles: Vec<MuscleHeatmapItem> = MUSCLE_GROUPS
         .iter()
         .map(|&mg| {
             let (last_date, count_7days) = muscle_data.get(mg).copied().unwrap_or((None, 0));
@@ -233,8 +295,11 @@ async fn get_muscle_heatmap(
     Ok(HttpResponse::Ok().json(MuscleHeatmapResponse { muscles }))
 }
 
+/// 筋肉グループの定義
+pub(crate) const MUSCLE_GROUPS: [&str; 6] = ["胸", "背中", "肩", "腕", "脚", "腹"];
+
 /// 筋肉名をグループにマッピング
-fn map_muscle_to_group(muscle: &str) -> Option<&'static str> {
+pub(crate) fn map_muscle_to_group(muscle: &str) -> Option<&'static str> {
     match muscle {
         "胸" | "大胸筋" => Some("胸"),
         "背中" | "広背筋" | "僧帽筋" | "脊柱起立筋" => Some("背中"),
@@ -258,3 +323,506 @@ fn calculate_heat_level(days_since: Option<i64>) -> f32 {
         Some(_) => 0.0,
     }
 }
+
+// ============================================
+// 週間セット数目標
+// ============================================
+
+/// 週間セット数の推奨範囲（筋肥大の研究に基づく10〜20セット）
+pub(crate) const DEFAULT_SET_TARGET: (i32, i32) = (10, 20);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetTargetItem {
+    muscle: String,
+    sets: i32,
+    min_sets: i32,
+    max_sets: i32,
+    /// BELOW / WITHIN / ABOVE
+    status: &'static str,
+    /// 最低セット数に対する達成率（0.0〜1.0）
+    progress: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetTargetSummary {
+    below_count: usize,
+    within_count: usize,
+    above_count: usize,
+    /// 最低セット数を満たした筋肉グループの割合（0.0〜1.0）
+    compliance_rate: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetTargetsResponse {
+    week_start: String,
+    week_end: String,
+    muscles: Vec<SetTargetItem>,
+    summary: SetTargetSummary,
+}
+
+/// ユーザーの週間セット数目標（未設定のグループは既定値）
+pub(crate) async fn fetch_set_targets(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Vec<(&'static str, i32, i32)>, AppError> {
+    let rows: Vec<(String, i32, i32)> = sqlx::query_as(
+        "SELECT muscle_group, min_sets, max_sets FROM user_set_targets WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(MUSCLE_GROUPS
+        .iter()
+        .map(|&mg| {
+            let (min_sets, max_sets) = rows
+                .iter()
+                .find(|(group, _, _)| group == mg)
+                .map(|(_, min, max)| (*min, *max))
+                .unwrap_or(DEFAULT_SET_TARGET);
+            (mg, min_sets, max_sets)
+        })
+        .collect())
+}
+
+/// GET /api/dashboard/set-targets
+/// 今週（月曜始まり、ユーザーのタイムゾーン）の筋肉グループ別セット数と目標範囲の比較
+#[get("/dashboard/set-targets")]
+async fn get_set_targets(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    let week_start = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let week_end = week_start + Days::new(6);
+
+    let rows: Vec<(Option<String>, i64)> = sqlx::query_as(
+        r#"
+        SELECT
+            CAST(COALESCE(e.muscle, uce.muscle) AS CHAR) as muscle,
+            COUNT(ts.id) as set_count
+        FROM training_records tr
+        INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+        INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+        LEFT JOIN exercises e ON e.id = tre.exercise_id
+        LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+        WHERE tr.user_id = ?
+          AND tr.record_date >= ?
+          AND tr.record_date <= ?
+        GROUP BY muscle
+        "#,
+    )
+    .bind(session_user.id)
+    .bind(week_start)
+    .bind(week_end)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let mut sets_by_group: HashMap<&str, i32> = HashMap::new();
+    for (muscle, count) in rows {
+        if let Some(group) = muscle.as_deref().and_then(map_muscle_to_group) {
+            *sets_by_group.entry(group).or_default() += count as i32;
+        }
+    }
+
+    let targets = fetch_set_targets(pool.get_ref(), session_user.id).await?;
+    let muscles: Vec<SetTargetItem> = targets
+        .into_iter()
+        .map(|(mg, min_sets, max_sets)| {
+            let sets = sets_by_group.get(mg).copied().unwrap_or(0);
+            let status = if sets < min_sets {
+                "BELOW"
+            } else if sets > max_sets {
+                "ABOVE"
+            } else {
+                "WITHIN"
+            };
+            let progress = if min_sets > 0 {
+                (sets as f64 / min_sets as f64).min(1.0)
+            } else {
+                1.0
+            };
+            SetTargetItem {
+                muscle: mg.to_string(),
+                sets,
+                min_sets,
+                max_sets,
+                status,
+                progress,
+            }
+        })
+        .collect();
+
+    let count = |status: &str| muscles.iter().filter(|m| m.status == status).count();
+    let below_count = count("BELOW");
+    let summary = SetTargetSummary {
+        below_count,
+        within_count: count("WITHIN"),
+        above_count: count("ABOVE"),
+        compliance_rate: (muscles.len() - below_count) as f64 / muscles.len() as f64,
+    };
+
+    Ok(HttpResponse::Ok().json(SetTargetsResponse {
+        week_start: week_start.format("%Y-%m-%d").to_string(),
+        week_end: week_end.format("%Y-%m-%d").to_string(),
+        muscles,
+        summary,
+    }))
+}
+
+// ============================================
+// 種目別の推移
+// ============================================
+
+/// 移動平均の区間（セッション数）の上限
+const MAX_SMOOTHING_WINDOW: usize = 10;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressionQuery {
+    exercise_id: i64,
+    /// epley / brzycki（省略時はepley）
+    formula: Option<String>,
+    /// 直近何セッション分の移動平均を付けるか（省略時・1以下は付けない）
+    smoothing: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressionPoint {
+    date: String,
+    record_id: i64,
+    /// 最も重いセットの重量
+    top_set_weight: f64,
+    /// 推定1RMの最高値（重量0のセットのみの場合はnull）
+    estimated_1rm: Option<f64>,
+    /// 総ボリューム（kg）
+    volume: f64,
+    sets: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    smoothed_top_set_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    smoothed_estimated_1rm: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    smoothed_volume: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProgressionResponse {
+    exercise_id: i64,
+    is_custom: bool,
+    formula: &'static str,
+    /// 移動平均の区間（付けない場合はnull）
+    smoothing: Option<usize>,
+    /// セッション（記録）ごとの値（古い順）
+    points: Vec<ProgressionPoint>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SessionProgressRow {
+    record_id: i64,
+    record_date: NaiveDate,
+    top_set_weight: f64,
+    estimated_1rm: Option<f64>,
+    volume: f64,
+    sets: i64,
+}
+
+/// 小数第1位に丸める
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// 直近window件の移動平均（値がない件は除いて平均する）
+fn moving_average(values: &[Option<f64>], window: usize) -> Vec<Option<f64>> {
+    (0..values.len())
+        .map(|i| {
+            let start = (i + 1).saturating_sub(window);
+            let present: Vec<f64> = values[start..=i].iter().flatten().copied().collect();
+            (!present.is_empty())
+                .then(|| round1(present.iter().sum::<f64>() / present.len() as f64))
+        })
+        .collect()
+}
+
+/// GET /api/dashboard/progression?exerciseId=1&formula=epley&smoothing=3
+/// 種目のセッションごとの最高重量・推定1RM・ボリュームの推移（ウォームアップセットは除く）
+/// 自分のカスタム種目のIDであればカスタム種目として扱う
+#[get("/dashboard/progression")]
+async fn get_progression(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<ProgressionQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let formula = match query.formula.as_deref() {
+        Some(f) => OneRmFormula::parse(f).ok_or_else(|| {
+            AppError::BadRequest("formulaにはepleyまたはbrzyckiを指定してください".to_string())
+        })?,
+        None => OneRmFormula::default(),
+    };
+    let smoothing = match query.smoothing {
+        Some(window) if window > MAX_SMOOTHING_WINDOW => {
+            return Err(AppError::BadRequest(format!(
+                "smoothingは{}以下で指定してください",
+                MAX_SMOOTHING_WINDOW
+            )));
+        }
+        Some(window) if window > 1 => Some(window),
+        _ => None,
+    };
+
+    let mut conn = pool.acquire().await?;
+    let exercise = resolve_exercise(&mut conn, session_user.id, query.exercise_id).await?;
+
+    // 推定1RMの計算式がBrzyckiで37回以上の場合は0になるため除外する
+    let rows: Vec<SessionProgressRow> = sqlx::query_as(&format!(
+        r#"
+        SELECT
+            tr.id AS record_id,
+            tr.record_date,
+            MAX(ts.weight) AS top_set_weight,
+            NULLIF(MAX(CASE WHEN ts.weight > 0 AND ts.reps > 0 THEN {} END), 0) AS estimated_1rm,
+            COALESCE(SUM(ts.weight * ts.reps), 0) AS volume,
+            COUNT(ts.id) AS sets
+        FROM training_records tr
+        INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+        INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+        WHERE tr.user_id = ? AND {} = ? AND ts.set_type <> 'warmup'
+        GROUP BY tr.id, tr.record_date
+        ORDER BY tr.record_date ASC, tr.id ASC
+        "#,
+        formula.sql_expr(),
+        exercise.column()
+    ))
+    .bind(session_user.id)
+    .bind(exercise.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let smoothed = smoothing.map(|window| {
+        let series = |f: fn(&SessionProgressRow) -> Option<f64>| {
+            moving_average(&rows.iter().map(f).collect::<Vec<_>>(), window)
+        };
+        (
+            series(|r| Some(r.top_set_weight)),
+            series(|r| r.estimated_1rm),
+            series(|r| Some(r.volume)),
+        )
+    });
+
+    let points: Vec<ProgressionPoint> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| ProgressionPoint {
+            date: row.record_date.format("%Y-%m-%d").to_string(),
+            record_id: row.record_id,
+            top_set_weight: row.top_set_weight,
+            estimated_1rm: row.estimated_1rm.map(round1),
+            volume: row.volume,
+            sets: row.sets,
+            smoothed_top_set_weight: smoothed.as_ref().and_then(|s| s.0[i]),
+            smoothed_estimated_1rm: smoothed.as_ref().and_then(|s| s.1[i]),
+            smoothed_volume: smoothed.as_ref().and_then(|s| s.2[i]),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(ProgressionResponse {
+        exercise_id: exercise.id,
+        is_custom: exercise.is_custom,
+        formula: formula.name(),
+        smoothing,
+        points,
+    }))
+}
+
+// ============================================
+// 筋肉グループ別のボリューム
+// ============================================
+
+/// 集計期間の日数の上限
+const MAX_VOLUME_RANGE_DAYS: u64 = 365;
+
+#[derive(Deserialize)]
+struct VolumeByMuscleQuery {
+    /// 7d / 30d / 90d など（省略時は30d）
+    range: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MuscleVolumeItem {
+    muscle: String,
+    /// 総ボリューム（kg）
+    volume: f64,
+    sets: i64,
+    /// 全体のボリュームに占める割合（0.0〜1.0）
+    volume_share: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VolumeByMuscleResponse {
+    range: String,
+    start_date: String,
+    end_date: String,
+    total_volume: f64,
+    total_sets: i64,
+    muscles: Vec<MuscleVolumeItem>,
+}
+
+/// 期間指定（"30d"）を日数に変換
+fn parse_range_days(range: &str) -> Result<u64, AppError> {
+    range
+        .trim()
+        .strip_suffix('d')
+        .and_then(|days| days.parse::<u64>().ok())
+        .filter(|days| (1..=MAX_VOLUME_RANGE_DAYS).contains(days))
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "rangeは1d〜{}dの形式で指定してください",
+                MAX_VOLUME_RANGE_DAYS
+            ))
+        })
+}
+
+/// 筋肉ごとのボリューム・セット数を筋肉グループ別にまとめる（全体のボリューム・セット数も返す）
+/// グループに属さない筋肉は集計しない
+fn summarize_muscle_volume(
+    rows: Vec<(Option<String>, f64, i64)>,
+) -> (Vec<MuscleVolumeItem>, f64, i64) {
+    let mut totals_by_group: HashMap<&str, (f64, i64)> = HashMap::new();
+    for (muscle, volume, sets) in rows {
+        if let Some(group) = muscle.as_deref().and_then(map_muscle_to_group) {
+            let entry = totals_by_group.entry(group).or_default();
+            entry.0 += volume;
+            entry.1 += sets;
+        }
+    }
+
+    let total_volume: f64 = totals_by_group.values().map(|(volume, _)| volume).sum();
+    let total_sets: i64 = totals_by_group.values().map(|(_, sets)| sets).sum();
+    let muscles: Vec<MuscleVolumeItem> = MUSCLE_GROUPS
+        .iter()
+        .map(|&mg| {
+            let (volume, sets) = totals_by_group.get(mg).copied().unwrap_or((0.0, 0));
+            MuscleVolumeItem {
+                muscle: mg.to_string(),
+                volume,
+                sets,
+                volume_share: if total_volume > 0.0 {
+                    volume / total_volume
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+    (muscles, total_volume, total_sets)
+}
+
+/// GET /api/dashboard/volume-by-muscle?range=30d
+/// 今日（ユーザーのタイムゾーン）までの指定日数の筋肉グループ別ボリュームとセット数
+#[get("/dashboard/volume-by-muscle")]
+async fn get_volume_by_muscle(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<VolumeByMuscleQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let days = parse_range_days(query.range.as_deref().unwrap_or("30d"))?;
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    let start_date = today.checked_sub_days(Days::new(days - 1)).unwrap_or(today);
+
+    let rows: Vec<(Option<String>, f64, i64)> = sqlx::query_as(
+        r#"
+        SELECT
+            CAST(COALESCE(e.muscle, uce.muscle) AS CHAR) as muscle,
+            COALESCE(SUM(ts.weight * ts.reps), 0) as volume,
+            COUNT(ts.id) as set_count
+        FROM training_records tr
+        INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+        INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+        LEFT JOIN exercises e ON e.id = tre.exercise_id
+        LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+        WHERE tr.user_id = ?
+          AND tr.record_date >= ?
+          AND tr.record_date <= ?
+          AND ts.set_type <> 'warmup'
+        GROUP BY muscle
+        "#,
+    )
+    .bind(session_user.id)
+    .bind(start_date)
+    .bind(today)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let (muscles, total_volume, total_sets) = summarize_muscle_volume(rows);
+
+    Ok(HttpResponse::Ok().json(VolumeByMuscleResponse {
+        range: format!("{}d", days),
+        start_date: start_date.format("%Y-%m-%d").to_string(),
+        end_date: today.format("%Y-%m-%d").to_string(),
+        total_volume,
+        total_sets,
+        muscles,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moving_average_skips_sessions_without_a_value() {
+        let values = [Some(100.0), None, Some(110.0), Some(121.0)];
+        assert_eq!(
+            moving_average(&values, 2),
+            vec![Some(100.0), Some(100.0), Some(110.0), Some(115.5)]
+        );
+        assert_eq!(
+            moving_average(&[None, Some(50.0)], 1),
+            vec![None, Some(50.0)]
+        );
+    }
+
+    #[test]
+    fn parse_range_days_accepts_day_ranges_up_to_a_year() {
+        assert_eq!(parse_range_days("30d").unwrap(), 30);
+        assert_eq!(parse_range_days(" 365d ").unwrap(), 365);
+        for invalid in ["0d", "366d", "30", "1w", "-5d"] {
+            assert!(parse_range_days(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn muscle_volume_is_grouped_with_shares() {
+        let rows = vec![
+            (Some("大胸筋".to_string()), 300.0, 3),
+            (Some("胸".to_string()), 100.0, 1),
+            (Some("広背筋".to_string()), 400.0, 4),
+            (Some("不明".to_string()), 999.0, 9),
+            (None, 999.0, 9),
+        ];
+        let (muscles, total_volume, total_sets) = summarize_muscle_volume(rows);
+
+        assert_eq!((total_volume, total_sets), (800.0, 8));
+        assert_eq!(muscles.len(), MUSCLE_GROUPS.len());
+        let chest = muscles.iter().find(|m| m.muscle == "胸").unwrap();
+        assert_eq!(
+            (chest.volume, chest.sets, chest.volume_share),
+            (400.0, 4, 0.5)
+        );
+        let legs = muscles.iter().find(|m| m.muscle == "脚").unwrap();
+        assert_eq!((legs.volume, legs.sets, legs.volume_share), (0.0, 0, 0.0));
+    }
+}
@@ -0,0 +1,274 @@
+//! メールアドレス確認APIハンドラ
+//!
+//! 登録時・メールアドレス変更時に確認リンクをメールで送り、リンクが開かれたら確認済みにする。
+//! 変更後のメールアドレスは確認が済むまでアカウントに反映しない。
+//! 確認済みのメールアドレスだけがOAuthアカウントの紐付けやお問い合わせの返信先に使われる。
+
+use actix_web::{get, post, put, web, HttpResponse};
+use serde::Deserialize;
+use sqlx::MySqlPool;
+
+use crate::api::auth::get_redirect_url;
+use crate::api::contact::validate_email;
+use crate::auth::session::{get_current_user, Session};
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::mailer::{MailMessage, Mailer};
+use crate::middleware::session_refresh::bump_session_epoch;
+use crate::shared_store::SharedStore;
+
+/// 確認リンクの有効期限（時間）
+const VERIFICATION_TOKEN_HOURS: i64 = 24;
+/// 1ユーザーあたりの確認メールの送信上限（1時間）
+/// 任意のメールアドレス宛てに送れるため、送信を繰り返されないようにする
+const MAX_VERIFICATION_MAILS_PER_HOUR: u64 = 5;
+
+#[derive(Deserialize)]
+struct UpdateEmailRequest {
+    email: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyEmailQuery {
+    token: String,
+}
+
+// ============================================
+// 確認メール
+// ============================================
+
+/// 確認済みのメールアドレス（未登録・未確認の場合はNone）
+pub(crate) async fn find_verified_email(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Option<String>, AppError> {
+    let email: Option<Option<String>> = sqlx::query_scalar(
+        "SELECT email FROM users WHERE id = ? AND email_verified_at IS NOT NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(email.flatten().filter(|e| !e.is_empty()))
+}
+
+/// 確認メールの送信回数を確認する（変更・再送信で共通の上限）
+async fn check_mail_rate_limit(store: &SharedStore, user_id: i64) -> Result<(), AppError> {
+    store
+        .check_rate_limit(
+            &format!("email_verification:{}", user_id),
+            MAX_VERIFICATION_MAILS_PER_HOUR,
+            std::time::Duration::from_secs(60 * 60),
+        )
+        .await
+}
+
+/// 確認リンクを発行してメールで送る（未使用の古いリンクは無効にする）
+pub(crate) async fn send_verification_mail(
+    pool: &MySqlPool,
+    config: &AppConfig,
+    mailer: &Mailer,
+    user_id: i64,
+    email: &str,
+) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM email_verifications WHERE user_id = ? AND verified_at IS NULL")
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    let token = format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    sqlx::query(
+        r#"INSERT INTO email_verifications (user_id, email, token, expires_at)
+           VALUES (?, ?, ?, NOW() + INTERVAL ? HOUR)"#,
+    )
+    .bind(user_id)
+    .bind(email)
+    .bind(&token)
+    .bind(VERIFICATION_TOKEN_HOURS)
+    .execute(pool)
+    .await?;
+
+    let message = MailMessage {
+        to: email.to_string(),
+        subject: "【Fithub】メールアドレスの確認".to_string(),
+        body: format!(
+            "Fithubをご利用いただきありがとうございます。\n\n\
+             次のリンクを開いて、メールアドレスの確認を完了してください。\n\
+             {base}/api/user/email/verify?token={token}\n\
+             （リンクの有効期限: {hours}時間）\n\n\
+             このメールに心当たりがない場合は、リンクを開かずに破棄してください。\n",
+            base = config.app_base_url,
+            token = token,
+            hours = VERIFICATION_TOKEN_HOURS,
+        ),
+    };
+    mailer.send(&message).await.map_err(|e| {
+        tracing::error!("Failed to send email verification mail: {}", e);
+        AppError::InternalError("確認メールの送信に失敗しました".to_string())
+    })
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// PUT /api/user/email
+/// メールアドレスを変更する（確認リンクを開くまでは変更前のメールアドレスのまま）
+#[put("/user/email")]
+async fn update_email(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    mailer: web::Data<Mailer>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    body: web::Json<UpdateEmailRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let email = body.email.trim();
+
+    if !validate_email(email) {
+        return Err(AppError::BadRequest(
+            "メールアドレスを正しい形式で入力してください".to_string(),
+        ));
+    }
+    if find_verified_email(pool.get_ref(), session_user.id)
+        .await?
+        .as_deref()
+        == Some(email)
+    {
+        return Err(AppError::BadRequest(
+            "このメールアドレスは確認済みです".to_string(),
+        ));
+    }
+
+    let taken: Option<i64> = sqlx::query_scalar("SELECT id FROM users WHERE email = ? AND id <> ?")
+        .bind(email)
+        .bind(session_user.id)
+        .fetch_optional(pool.get_ref())
+        .await?;
+    if taken.is_some() {
+        return Err(AppError::BadRequest(
+            "このメールアドレスは既に使用されています".to_string(),
+        ));
+    }
+
+    check_mail_rate_limit(&store, session_user.id).await?;
+    send_verification_mail(pool.get_ref(), &config, &mailer, session_user.id, email).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "emailSent": true,
+    })))
+}
+
+/// POST /api/user/email/resend
+/// 登録済みで未確認のメールアドレスに確認リンクを送り直す
+#[post("/user/email/resend")]
+async fn resend_verification(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    mailer: web::Data<Mailer>,
+    store: web::Data<SharedStore>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let row: Option<(Option<String>, i64)> = sqlx::query_as(
+        "SELECT email, CAST(email_verified_at IS NOT NULL AS SIGNED) FROM users WHERE id = ?",
+    )
+    .bind(session_user.id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    let (email, verified) = row.ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let email = email
+        .filter(|e| !e.is_empty())
+        .ok_or_else(|| AppError::BadRequest("メールアドレスが登録されていません".to_string()))?;
+    if verified != 0 {
+        return Err(AppError::BadRequest(
+            "このメールアドレスは確認済みです".to_string(),
+        ));
+    }
+
+    check_mail_rate_limit(&store, session_user.id).await?;
+    send_verification_mail(pool.get_ref(), &config, &mailer, session_user.id, &email).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "emailSent": true,
+    })))
+}
+
+/// GET /api/user/email/verify?token=... - メールの確認リンク
+/// ログイン状態に関係なくトークンで確認し、メールアドレスを確認済みにする
+#[get("/user/email/verify")]
+async fn verify_email(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    query: web::Query<VerifyEmailQuery>,
+) -> Result<HttpResponse, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let verification: Option<(i64, i64, String)> = sqlx::query_as(
+        r#"SELECT id, user_id, email FROM email_verifications
+           WHERE token = ? AND verified_at IS NULL AND expires_at > NOW()
+           FOR UPDATE"#,
+    )
+    .bind(&query.token)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let outcome = match verification {
+        Some((verification_id, user_id, email)) => {
+            // リンクの発行後に他のアカウントで使われた場合は確認しない
+            let taken: Option<i64> =
+                sqlx::query_scalar("SELECT id FROM users WHERE email = ? AND id <> ?")
+                    .bind(&email)
+                    .bind(user_id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            if taken.is_some() {
+                "taken"
+            } else {
+                sqlx::query(
+                    r#"UPDATE users SET email = ?, email_verified_at = NOW(), updated_at = NOW()
+                       WHERE id = ?"#,
+                )
+                .bind(&email)
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query("UPDATE email_verifications SET verified_at = NOW() WHERE id = ?")
+                    .bind(verification_id)
+                    .execute(&mut *tx)
+                    .await?;
+                tx.commit().await?;
+
+                // セッションのメールアドレスを次のリクエストで更新させる
+                bump_session_epoch(&store, &config, user_id).await;
+                "verified"
+            }
+        }
+        None => "invalid",
+    };
+
+    Ok(HttpResponse::Found()
+        .append_header((
+            "Location",
+            get_redirect_url(
+                &config,
+                &format!("/settings?emailVerification={}", outcome),
+            ),
+        ))
+        .finish())
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(update_email)
+        .service(resend_verification)
+        .service(verify_email);
+}
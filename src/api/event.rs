@@ -0,0 +1,103 @@
+//! 期間限定イベントAPIハンドラ
+//!
+//! 開催中のイベントはEXPの倍率（記録の保存・デイリーリワード）と、
+//! 期間中に活動すると解放されるペットを持つ。期間は日本時間の日付で判定する。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::SeasonalEvent;
+use crate::domain::timezone::{self, DEFAULT_TIMEZONE};
+use crate::error::AppError;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventDto {
+    code: String,
+    name: String,
+    description: Option<String>,
+    banner_image_path: Option<String>,
+    start_date: String,
+    end_date: String,
+    exp_multiplier: f64,
+    /// 期間中に解放されるペットの名前
+    special_pet_name: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CurrentEventResponse {
+    /// 開催中のイベント（なければnull）
+    event: Option<EventDto>,
+}
+
+/// 指定日に開催中のイベント（重なっている場合は後から始まったもの）
+pub(crate) async fn find_active_event(
+    pool: &MySqlPool,
+    date: NaiveDate,
+) -> Result<Option<SeasonalEvent>, AppError> {
+    let event: Option<SeasonalEvent> = sqlx::query_as(
+        r#"SELECT id, code, name, description, banner_image_path, start_date, end_date,
+                  exp_multiplier, special_pet_type_id
+           FROM events
+           WHERE is_active = TRUE AND start_date <= ? AND end_date >= ?
+           ORDER BY start_date DESC, id DESC
+           LIMIT 1"#,
+    )
+    .bind(date)
+    .bind(date)
+    .fetch_optional(pool)
+    .await?;
+    Ok(event)
+}
+
+/// 今日（日本時間）開催中のイベント
+pub(crate) async fn find_current_event(
+    pool: &MySqlPool,
+) -> Result<Option<SeasonalEvent>, AppError> {
+    find_active_event(pool, timezone::today_in(DEFAULT_TIMEZONE)).await
+}
+
+/// GET /api/events/current
+/// 開催中のイベント（バナー表示用）
+#[get("/events/current")]
+async fn get_current_event(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    get_current_user(&session)?;
+
+    let Some(event) = find_current_event(pool.get_ref()).await? else {
+        return Ok(HttpResponse::Ok().json(CurrentEventResponse { event: None }));
+    };
+
+    let special_pet_name: Option<String> = match event.special_pet_type_id {
+        Some(pet_type_id) => {
+            sqlx::query_scalar("SELECT name FROM pet_types WHERE id = ?")
+                .bind(pet_type_id)
+                .fetch_optional(pool.get_ref())
+                .await?
+        }
+        None => None,
+    };
+
+    Ok(HttpResponse::Ok().json(CurrentEventResponse {
+        event: Some(EventDto {
+            code: event.code,
+            name: event.name,
+            description: event.description,
+            banner_image_path: event.banner_image_path,
+            start_date: event.start_date.format("%Y-%m-%d").to_string(),
+            end_date: event.end_date.format("%Y-%m-%d").to_string(),
+            exp_multiplier: event.exp_multiplier,
+            special_pet_name,
+        }),
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_current_event);
+}
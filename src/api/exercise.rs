@@ -1,11 +1,10 @@
 //! 種目APIハンドラ
 
-use actix_session::Session;
 use actix_web::{get, web, HttpResponse};
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
+use sqlx::{MySqlConnection, MySqlPool};
 
-use crate::auth::session::get_current_user;
+use crate::auth::session::{get_current_user, Session};
 use crate::error::AppError;
 
 // ============================================
@@ -18,6 +17,7 @@ pub struct ExercisePagedQuery {
     difficulties: Option<String>, // カンマ区切りの難易度レベルID
     #[serde(rename = "targetMuscles")]
     target_muscles: Option<String>, // カンマ区切りのターゲット筋肉名
+    sort: Option<String>,           // name | difficulty | recent | popularity
     page: Option<i32>,
     size: Option<i32>,
 }
@@ -35,6 +35,27 @@ struct ExerciseDto {
     video_path: Option<String>,
 }
 
+/// 種目の解説（手順・よくある間違い・安全上の注意）
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExerciseInstructionsDto {
+    #[serde(default)]
+    pub steps: Vec<String>,
+    #[serde(default)]
+    pub common_mistakes: Vec<String>,
+    #[serde(default)]
+    pub safety_notes: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ExerciseDetailDto {
+    #[serde(flatten)]
+    exercise: ExerciseDto,
+    #[serde(rename = "muscleGroupId")]
+    muscle_group_id: Option<i32>,
+    instructions: ExerciseInstructionsDto,
+}
+
 #[derive(Serialize)]
 struct ExercisePagedResponse {
     exercises: Vec<ExerciseDto>,
@@ -81,7 +102,6 @@ struct ExerciseRow {
     description: Option<String>,
     target_muscles: Option<String>,
     video_path: Option<String>,
-    #[allow(dead_code)]
     muscle_group_id: Option<i32>,
 }
 
@@ -101,6 +121,145 @@ struct DifficultyLevelRow {
     display_order: Option<i32>,
 }
 
+// ============================================
+// ターゲット筋肉
+// ============================================
+
+/// カンマ区切りのターゲット筋肉を分割（空要素・重複を除外）
+pub fn parse_target_muscles(csv: &str) -> Vec<String> {
+    let mut muscles: Vec<String> = Vec::new();
+    for name in csv.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        if !muscles.iter().any(|m| m == name) {
+            muscles.push(name.to_string());
+        }
+    }
+    muscles
+}
+
+/// exercise_target_musclesを種目のターゲット筋肉に同期
+pub async fn sync_target_muscles(
+    conn: &mut MySqlConnection,
+    exercise_id: i64,
+    muscles: &[String],
+) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM exercise_target_muscles WHERE exercise_id = ?")
+        .bind(exercise_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for name in muscles {
+        sqlx::query(
+            "INSERT IGNORE INTO exercise_target_muscles (exercise_id, muscle_name) VALUES (?, ?)",
+        )
+        .bind(exercise_id)
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+// ============================================
+// 解説
+// ============================================
+
+const SECTION_STEP: &str = "STEP";
+const SECTION_MISTAKE: &str = "MISTAKE";
+const SECTION_SAFETY: &str = "SAFETY";
+
+/// 1項目あたりの最大文字数
+const MAX_INSTRUCTION_LENGTH: usize = 2000;
+
+impl ExerciseInstructionsDto {
+    /// 空行を除外し、長さを検証
+    pub fn normalized(&self) -> Result<Self, AppError> {
+        let normalize = |items: &[String]| -> Result<Vec<String>, AppError> {
+            items
+                .iter()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    if s.chars().count() > MAX_INSTRUCTION_LENGTH {
+                        Err(AppError::BadRequest(format!(
+                            "解説は1項目{}文字以内で入力してください",
+                            MAX_INSTRUCTION_LENGTH
+                        )))
+                    } else {
+                        Ok(s.to_string())
+                    }
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            steps: normalize(&self.steps)?,
+            common_mistakes: normalize(&self.common_mistakes)?,
+            safety_notes: normalize(&self.safety_notes)?,
+        })
+    }
+}
+
+/// 種目の解説を取得
+pub async fn fetch_instructions(
+    pool: &MySqlPool,
+    exercise_id: i64,
+) -> Result<ExerciseInstructionsDto, AppError> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        r#"SELECT section, content FROM exercise_instructions
+           WHERE exercise_id = ?
+           ORDER BY display_order ASC, id ASC"#,
+    )
+    .bind(exercise_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut instructions = ExerciseInstructionsDto::default();
+    for (section, content) in rows {
+        match section.as_str() {
+            SECTION_STEP => instructions.steps.push(content),
+            SECTION_MISTAKE => instructions.common_mistakes.push(content),
+            SECTION_SAFETY => instructions.safety_notes.push(content),
+            _ => {}
+        }
+    }
+    Ok(instructions)
+}
+
+/// exercise_instructionsを置き換え
+pub async fn sync_instructions(
+    conn: &mut MySqlConnection,
+    exercise_id: i64,
+    instructions: &ExerciseInstructionsDto,
+) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM exercise_instructions WHERE exercise_id = ?")
+        .bind(exercise_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let sections = [
+        (SECTION_STEP, &instructions.steps),
+        (SECTION_MISTAKE, &instructions.common_mistakes),
+        (SECTION_SAFETY, &instructions.safety_notes),
+    ];
+    for (section, items) in sections {
+        for (order, content) in (1..).zip(items.iter()) {
+            sqlx::query(
+                r#"INSERT INTO exercise_instructions (exercise_id, section, display_order, content)
+                   VALUES (?, ?, ?, ?)"#,
+            )
+            .bind(exercise_id)
+            .bind(section)
+            .bind(order)
+            .bind(content)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
 // ============================================
 // 動画URL設定
 // ============================================
@@ -121,6 +280,48 @@ fn build_video_url(video_path: Option<String>) -> Option<String> {
         })
 }
 
+// ============================================
+// ソート
+// ============================================
+
+/// 種目一覧のソート順
+#[derive(Clone, Copy, PartialEq)]
+enum ExerciseSort {
+    DisplayOrder,
+    Name,
+    Difficulty,
+    Recent,
+    Popularity,
+}
+
+impl ExerciseSort {
+    /// sortパラメータをパース（未指定時は表示順）
+    fn parse(sort: Option<&str>) -> Result<Self, AppError> {
+        match sort.map(str::trim).filter(|s| !s.is_empty()) {
+            None => Ok(Self::DisplayOrder),
+            Some("name") => Ok(Self::Name),
+            Some("difficulty") => Ok(Self::Difficulty),
+            Some("recent") | Some("recently-added") => Ok(Self::Recent),
+            Some("popularity") => Ok(Self::Popularity),
+            Some(other) => Err(AppError::BadRequest(format!(
+                "Invalid sort parameter: {}",
+                other
+            ))),
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::DisplayOrder => "e.display_order ASC, e.id ASC",
+            Self::Name => "e.name ASC, e.id ASC",
+            Self::Difficulty => "e.difficulty_level_id ASC, e.display_order ASC, e.id ASC",
+            // 種目マスタには作成日時がないため、IDの降順を追加順とみなす
+            Self::Recent => "e.id DESC",
+            Self::Popularity => "COALESCE(u.usage_count, 0) DESC, e.display_order ASC, e.id ASC",
+        }
+    }
+}
+
 // ============================================
 // ハンドラ
 // ============================================
@@ -135,8 +336,8 @@ async fn get_exercises_paged(
     // 認証必須
     let _user = get_current_user(&session)?;
 
-    let page = query.page.unwrap_or(0);
-    let size = query.size.unwrap_or(16);
+    let page = query.page.unwrap_or(0).max(0);
+    let size = query.size.unwrap_or(16).max(1);
 
     // フィルターパラメータをパース
     let muscle_ids: Vec<i32> = query
@@ -170,126 +371,99 @@ async fn get_exercises_paged(
         })
         .unwrap_or_default();
 
-    let has_muscle_filter = !muscle_ids.is_empty();
-    let has_difficulty_filter = !difficulty_ids.is_empty();
-    let has_target_muscle_filter = !target_muscles.is_empty();
-
-    // フィルターに基づいてクエリを構築
-    // 注: target_musclesフィルターはRustで適用（複雑なLIKE条件）
-    let exercises: Vec<ExerciseRow> = if !has_muscle_filter && !has_difficulty_filter {
-        // DBフィルターなし
-        sqlx::query_as(
-            r#"SELECT id, name, muscle, difficulty_level_id, description, target_muscles, video_path, muscle_group_id
-               FROM exercises
-               ORDER BY display_order ASC, id ASC"#
-        )
-        .fetch_all(pool.get_ref())
-        .await?
-    } else if has_muscle_filter && has_difficulty_filter {
-        // 両方のフィルター
-        let muscle_placeholders = muscle_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let difficulty_placeholders = difficulty_ids
-            .iter()
-            .map(|_| "?")
-            .collect::<Vec<_>>()
-            .join(",");
-
-        let query_str = format!(
-            r#"SELECT id, name, muscle, difficulty_level_id, description, target_muscles, video_path, muscle_group_id
-               FROM exercises
-               WHERE muscle_group_id IN ({}) AND difficulty_level_id IN ({})
-               ORDER BY display_order ASC, id ASC"#,
-            muscle_placeholders, difficulty_placeholders
-        );
-
-        let mut q = sqlx::query_as::<_, ExerciseRow>(&query_str);
-        for id in &muscle_ids {
-            q = q.bind(id);
-        }
-        for id in &difficulty_ids {
-            q = q.bind(id);
-        }
-        q.fetch_all(pool.get_ref()).await?
-    } else if has_muscle_filter {
-        // 筋肉フィルターのみ
-        let placeholders = muscle_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let query_str = format!(
-            r#"SELECT id, name, muscle, difficulty_level_id, description, target_muscles, video_path, muscle_group_id
-               FROM exercises
-               WHERE muscle_group_id IN ({})
-               ORDER BY display_order ASC, id ASC"#,
-            placeholders
-        );
-
-        let mut q = sqlx::query_as::<_, ExerciseRow>(&query_str);
-        for id in &muscle_ids {
-            q = q.bind(id);
-        }
-        q.fetch_all(pool.get_ref()).await?
-    } else {
-        // 難易度フィルターのみ
-        let placeholders = difficulty_ids
-            .iter()
-            .map(|_| "?")
-            .collect::<Vec<_>>()
-            .join(",");
-        let query_str = format!(
-            r#"SELECT id, name, muscle, difficulty_level_id, description, target_muscles, video_path, muscle_group_id
-               FROM exercises
-               WHERE difficulty_level_id IN ({})
-               ORDER BY display_order ASC, id ASC"#,
-            placeholders
+    let sort = ExerciseSort::parse(query.sort.as_deref())?;
+
+    // フィルター条件（WHERE句）を構築
+    let mut where_clause = String::from(" WHERE 1=1");
+
+    if !muscle_ids.is_empty() {
+        where_clause.push_str(&format!(
+            " AND e.muscle_group_id IN ({})",
+            muscle_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
+        ));
+    }
+
+    if !difficulty_ids.is_empty() {
+        where_clause.push_str(&format!(
+            " AND e.difficulty_level_id IN ({})",
+            difficulty_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",")
+        ));
+    }
+
+    if !target_muscles.is_empty() {
+        // OR条件: 選択したターゲット筋肉のいずれかを含む種目
+        where_clause.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM exercise_target_muscles etm WHERE etm.exercise_id = e.id AND etm.muscle_name IN ({}))",
+            target_muscles.iter().map(|_| "?").collect::<Vec<_>>().join(",")
+        ));
+    }
+
+    // 合計件数を取得
+    let count_query = format!("SELECT COUNT(*) FROM exercises e{}", where_clause);
+    let mut cq = sqlx::query_as::<_, (i64,)>(&count_query);
+    for id in &muscle_ids {
+        cq = cq.bind(id);
+    }
+    for id in &difficulty_ids {
+        cq = cq.bind(id);
+    }
+    for name in &target_muscles {
+        cq = cq.bind(name);
+    }
+    let (total_elements,) = cq.fetch_one(pool.get_ref()).await?;
+
+    // ページ分の種目を取得
+    let mut query_str = String::from(
+        r#"SELECT e.id, e.name, e.muscle, e.difficulty_level_id, e.description, e.target_muscles, e.video_path, e.muscle_group_id
+           FROM exercises e"#,
+    );
+
+    if sort == ExerciseSort::Popularity {
+        // 人気順: トレーニング記録での使用回数
+        query_str.push_str(
+            r#"
+           LEFT JOIN (
+               SELECT exercise_id, COUNT(*) AS usage_count
+               FROM training_record_exercises
+               WHERE exercise_id IS NOT NULL
+               GROUP BY exercise_id
+           ) u ON u.exercise_id = e.id"#,
         );
+    }
+
+    query_str.push_str(&where_clause);
+    query_str.push_str(&format!(" ORDER BY {} LIMIT ? OFFSET ?", sort.order_by()));
+
+    let mut q = sqlx::query_as::<_, ExerciseRow>(&query_str);
+    for id in &muscle_ids {
+        q = q.bind(id);
+    }
+    for id in &difficulty_ids {
+        q = q.bind(id);
+    }
+    for name in &target_muscles {
+        q = q.bind(name);
+    }
+    let exercises: Vec<ExerciseRow> = q
+        .bind(size)
+        .bind(page * size)
+        .fetch_all(pool.get_ref())
+        .await?;
 
-        let mut q = sqlx::query_as::<_, ExerciseRow>(&query_str);
-        for id in &difficulty_ids {
-            q = q.bind(id);
-        }
-        q.fetch_all(pool.get_ref()).await?
-    };
-
-    // Rustでtarget_musclesフィルターを適用（複雑なLIKE OR条件）
-    let filtered_exercises: Vec<ExerciseRow> = if has_target_muscle_filter {
-        exercises
-            .into_iter()
-            .filter(|e| {
-                if let Some(ref tm) = e.target_muscles {
-                    let exercise_targets: Vec<&str> = tm.split(',').map(|s| s.trim()).collect();
-                    // OR条件: 選択したターゲット筋肉のいずれかが種目のターゲット筋肉に含まれていれば一致
-                    target_muscles
-                        .iter()
-                        .any(|selected| exercise_targets.contains(&selected.as_str()))
-                } else {
-                    false
-                }
-            })
-            .collect()
-    } else {
-        exercises
-    };
-
-    // 手動ページネーション
-    let total_elements = filtered_exercises.len() as i64;
     let total_pages = ((total_elements as f64) / (size as f64)).ceil() as i32;
-    let from_index = (page * size) as usize;
-    let to_index = std::cmp::min(from_index + size as usize, filtered_exercises.len());
-
-    let paged_exercises: Vec<ExerciseDto> = if from_index < filtered_exercises.len() {
-        filtered_exercises[from_index..to_index]
-            .iter()
-            .map(|e| ExerciseDto {
-                id: e.id,
-                name: e.name.clone(),
-                muscle: e.muscle.clone(),
-                difficulty: e.difficulty_level_id,
-                description: e.description.clone(),
-                target_muscles: e.target_muscles.clone(),
-                video_path: build_video_url(e.video_path.clone()),
-            })
-            .collect()
-    } else {
-        vec![]
-    };
+
+    let paged_exercises: Vec<ExerciseDto> = exercises
+        .into_iter()
+        .map(|e| ExerciseDto {
+            id: e.id,
+            name: e.name,
+            muscle: e.muscle,
+            difficulty: e.difficulty_level_id,
+            description: e.description,
+            target_muscles: e.target_muscles,
+            video_path: build_video_url(e.video_path),
+        })
+        .collect();
 
     Ok(HttpResponse::Ok().json(ExercisePagedResponse {
         exercises: paged_exercises,
@@ -301,6 +475,45 @@ async fn get_exercises_paged(
     }))
 }
 
+/// GET /api/exercises/{id} - 種目詳細（解説を含む）
+#[get("/exercises/{id:\\d+}")]
+async fn get_exercise_detail(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    // 認証必須
+    let _user = get_current_user(&session)?;
+    let exercise_id = path.into_inner();
+
+    let row: Option<ExerciseRow> = sqlx::query_as(
+        r#"SELECT e.id, e.name, e.muscle, e.difficulty_level_id, e.description, e.target_muscles, e.video_path, e.muscle_group_id
+           FROM exercises e WHERE e.id = ?"#,
+    )
+    .bind(exercise_id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    let Some(e) = row else {
+        return Err(AppError::NotFound("種目が見つかりません".to_string()));
+    };
+
+    let instructions = fetch_instructions(pool.get_ref(), exercise_id).await?;
+
+    Ok(HttpResponse::Ok().json(ExerciseDetailDto {
+        muscle_group_id: e.muscle_group_id,
+        exercise: ExerciseDto {
+            id: e.id,
+            name: e.name,
+            muscle: e.muscle,
+            difficulty: e.difficulty_level_id,
+            description: e.description,
+            target_muscles: e.target_muscles,
+            video_path: build_video_url(e.video_path),
+        },
+        instructions,
+    }))
+}
+
 /// GET /api/exercises/target-muscles - ユニークなターゲット筋肉リストを取得
 #[get("/exercises/target-muscles")]
 async fn get_target_muscles(
@@ -310,27 +523,12 @@ async fn get_target_muscles(
     // 認証必須
     let _user = get_current_user(&session)?;
 
-    let rows: Vec<(Option<String>,)> = sqlx::query_as(
-        r#"SELECT DISTINCT target_muscles FROM exercises WHERE target_muscles IS NOT NULL AND target_muscles != ''"#
+    let muscles: Vec<String> = sqlx::query_scalar(
+        r#"SELECT DISTINCT muscle_name FROM exercise_target_muscles ORDER BY muscle_name"#,
     )
     .fetch_all(pool.get_ref())
     .await?;
 
-    // カンマ区切り値をパースして重複を削除
-    let mut muscles: Vec<String> = rows
-        .into_iter()
-        .filter_map(|(tm,)| tm)
-        .flat_map(|t| {
-            t.split(',')
-                .map(|s| s.trim().to_string())
-                .collect::<Vec<_>>()
-        })
-        .filter(|s| !s.is_empty())
-        .collect();
-
-    muscles.sort();
-    muscles.dedup();
-
     Ok(HttpResponse::Ok().json(muscles))
 }
 
@@ -394,5 +592,6 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_exercises_paged)
         .service(get_target_muscles)
         .service(get_muscle_groups)
-        .service(get_difficulty_levels);
+        .service(get_difficulty_levels)
+        .service(get_exercise_detail);
 }
@@ -0,0 +1,168 @@
+//! EXP計算に必要なユーザー情報の一括取得
+//!
+//! 記録保存・デイリーリワード・ログインボーナスで共通して使う
+//! ユーザーステータス・ストリーク・設定・体重を1クエリでまとめて取得する。
+//! 開催中のイベントのEXP倍率もあわせて持つ。
+
+use sqlx::MySqlPool;
+
+use crate::api::event::find_current_event;
+use crate::api::streak::{calculate_login_multiplier, calculate_training_multiplier};
+use crate::config::ExpConfig;
+use crate::db::models::{SeasonalEvent, UserStats};
+use crate::error::AppError;
+use crate::exp_config;
+
+/// 猶予日数の既定値（user_settings未作成時）
+const DEFAULT_GRACE_DAYS_ALLOWED: i32 = 1;
+
+/// EXP付与時のユーザーごとのコンテキスト
+#[derive(Debug, Clone)]
+pub struct ExpContext {
+    pub config: ExpConfig,
+    /// user_stats（未作成の場合はNone）
+    pub stats: Option<UserStats>,
+    pub grace_days_allowed: i32,
+    pub training_multiplier: f64,
+    pub login_multiplier: f64,
+    /// 体重（自重種目のEXP計算用、未設定の場合はNone）
+    pub body_weight: Option<f64>,
+    /// 開催中のイベント
+    pub event: Option<SeasonalEvent>,
+}
+
+impl ExpContext {
+    /// ユーザーのEXPコンテキストを取得
+    pub async fn load(pool: &MySqlPool, user_id: i64) -> Result<Self, AppError> {
+        #[derive(sqlx::FromRow)]
+        struct ContextRow {
+            stats_id: Option<i64>,
+            total_exp: Option<i64>,
+            level: Option<i64>,
+            training_streak: Option<i64>,
+            login_streak: Option<i64>,
+            grace_days_allowed: Option<i64>,
+            body_weight: Option<f64>,
+        }
+
+        let row: ContextRow = sqlx::query_as(
+            r#"SELECT us.id AS stats_id,
+                      CAST(us.total_exp AS SIGNED) AS total_exp,
+                      CAST(us.level AS SIGNED) AS level,
+                      CAST(ts.current_streak AS SIGNED) AS training_streak,
+                      CAST(ls.current_streak AS SIGNED) AS login_streak,
+                      CAST(s.grace_days_allowed AS SIGNED) AS grace_days_allowed,
+                      usr.body_weight
+               FROM (SELECT ? AS user_id) u
+               LEFT JOIN users usr ON usr.id = u.user_id
+               LEFT JOIN user_stats us ON us.user_id = u.user_id
+               LEFT JOIN user_streaks ts ON ts.user_id = u.user_id AND ts.streak_type = 'training'
+               LEFT JOIN user_streaks ls ON ls.user_id = u.user_id AND ls.streak_type = 'login'
+               LEFT JOIN user_settings s ON s.user_id = u.user_id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        let stats = row.stats_id.map(|id| UserStats {
+            id,
+            user_id,
+            total_exp: row.total_exp.unwrap_or(0),
+            level: row.level.unwrap_or(1) as i32,
+        });
+        let training_streak = row.training_streak.unwrap_or(0) as i32;
+        let login_streak = row.login_streak.unwrap_or(0) as i32;
+
+        Ok(Self {
+            config: exp_config::current(),
+            stats,
+            grace_days_allowed: row
+                .grace_days_allowed
+                .map(|g| g as i32)
+                .unwrap_or(DEFAULT_GRACE_DAYS_ALLOWED),
+            training_multiplier: calculate_training_multiplier(training_streak),
+            login_multiplier: calculate_login_multiplier(login_streak),
+            body_weight: row.body_weight,
+            event: find_current_event(pool).await?,
+        })
+    }
+
+    /// ストリーク倍率: 1 + トレーニング + ログイン
+    pub fn streak_multiplier(&self) -> f64 {
+        1.0 + self.training_multiplier + self.login_multiplier
+    }
+
+    /// イベント倍率（開催中のイベントがなければ1.0）
+    pub fn event_multiplier(&self) -> f64 {
+        self.event.as_ref().map_or(1.0, |e| e.exp_multiplier)
+    }
+
+    /// 報酬倍率: ストリーク倍率 × イベント倍率
+    pub fn reward_multiplier(&self) -> f64 {
+        self.streak_multiplier() * self.event_multiplier()
+    }
+
+    /// 基本EXPにストリーク倍率とイベント倍率を適用
+    pub fn apply_reward_multiplier(&self, base_exp: i32) -> i32 {
+        (base_exp as f64 * self.reward_multiplier()).round() as i32
+    }
+
+    /// 開催中のイベントで解放されるペットがあるか
+    pub fn has_event_pet(&self) -> bool {
+        self.event
+            .as_ref()
+            .is_some_and(|e| e.special_pet_type_id.is_some())
+    }
+
+    /// 現在のレベル（user_stats未作成時は1）
+    pub fn current_level(&self) -> i32 {
+        self.stats.as_ref().map(|s| s.level).unwrap_or(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn context(event: Option<SeasonalEvent>) -> ExpContext {
+        ExpContext {
+            config: ExpConfig::DEFAULT,
+            stats: None,
+            grace_days_allowed: DEFAULT_GRACE_DAYS_ALLOWED,
+            training_multiplier: 0.2,
+            login_multiplier: 0.05,
+            body_weight: None,
+            event,
+        }
+    }
+
+    fn event(exp_multiplier: f64, special_pet_type_id: Option<i32>) -> SeasonalEvent {
+        SeasonalEvent {
+            id: 1,
+            code: "summer".to_string(),
+            name: "Summer".to_string(),
+            description: None,
+            banner_image_path: None,
+            start_date: NaiveDate::from_ymd_opt(2026, 7, 1).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2026, 7, 31).unwrap(),
+            exp_multiplier,
+            special_pet_type_id,
+        }
+    }
+
+    #[test]
+    fn rewards_use_only_the_streak_multiplier_without_an_event() {
+        let context = context(None);
+        assert_eq!(context.event_multiplier(), 1.0);
+        assert_eq!(context.apply_reward_multiplier(100), 125);
+        assert!(!context.has_event_pet());
+    }
+
+    #[test]
+    fn event_multiplier_stacks_on_the_streak_multiplier() {
+        let context = context(Some(event(2.0, Some(9))));
+        assert_eq!(context.apply_reward_multiplier(100), 250);
+        assert!(context.has_event_pet());
+    }
+}
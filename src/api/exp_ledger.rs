@@ -0,0 +1,289 @@
+//! EXP履歴APIハンドラ
+//!
+//! 累計EXP（user_stats.total_exp）の増減はaward_expを通して行い、
+//! 増減のたびにexp_transactionsへ理由（source）と増減後の累計を記録する。
+//! ユーザーはGET /api/user/exp-historyでEXPの獲得元を確認できる。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::coin::grant_level_up_coins;
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::UserStats;
+use crate::error::AppError;
+
+/// 記録の保存
+pub(crate) const EXP_SOURCE_WORKOUT: &str = "workout";
+/// 記録の編集（保存済みEXPとの差分）
+pub(crate) const EXP_SOURCE_WORKOUT_EDIT: &str = "workout_edit";
+/// 記録の削除
+pub(crate) const EXP_SOURCE_WORKOUT_DELETE: &str = "workout_delete";
+/// 直前の保存の取り消し
+pub(crate) const EXP_SOURCE_WORKOUT_UNDO: &str = "workout_undo";
+/// 目標の達成
+pub(crate) const EXP_SOURCE_GOAL: &str = "goal";
+/// ログインボーナス
+pub(crate) const EXP_SOURCE_LOGIN_BONUS: &str = "login_bonus";
+/// デイリーリワード
+pub(crate) const EXP_SOURCE_DAILY_REWARD: &str = "daily_reward";
+/// ウィークリーチャレンジの報酬
+pub(crate) const EXP_SOURCE_CHALLENGE: &str = "challenge";
+/// 途切れたストリークの復活
+pub(crate) const EXP_SOURCE_STREAK_RECOVERY: &str = "streak_recovery";
+/// 管理者によるレベルの変更
+pub(crate) const EXP_SOURCE_ADMIN: &str = "admin";
+/// レベル曲線の移行（レベル維持）
+pub(crate) const EXP_SOURCE_LEVEL_CURVE: &str = "level_curve";
+
+/// 1ページの件数の上限
+const MAX_PAGE_SIZE: i32 = 100;
+
+/// EXPの増減結果
+pub(crate) struct ExpChange {
+    /// 増減後の累計EXP
+    pub total_exp: i64,
+    pub old_level: i32,
+    pub new_level: i32,
+}
+
+/// 増減後の累計EXPと履歴に記録する増減量
+/// 累計EXPは0未満にしないため、履歴には実際に増減した分を記録する
+fn apply_exp_amount(old_total: i64, amount: i64) -> (i64, i64) {
+    let total_exp = (old_total + amount).max(0);
+    (total_exp, total_exp - old_total)
+}
+
+/// 累計EXPを増減してレベルを再計算し、履歴を記録する
+/// user_statsがなければ作成する。累計EXPは0未満にしない
+/// レベルが上がった場合はレベルアップのコインも付与する
+pub(crate) async fn award_exp(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    amount: i64,
+    source: &str,
+    reference_id: Option<i64>,
+) -> Result<ExpChange, AppError> {
+    let stats: Option<(i64, i32)> =
+        sqlx::query_as("SELECT total_exp, level FROM user_stats WHERE user_id = ? FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+    let (old_total, old_level) = match stats {
+        Some(stats) => stats,
+        None => {
+            sqlx::query(
+                r#"INSERT INTO user_stats (user_id, total_exp, level, created_at, updated_at)
+                   VALUES (?, 0, 1, NOW(), NOW())"#,
+            )
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+            (0, 1)
+        }
+    };
+
+    let (total_exp, recorded_amount) = apply_exp_amount(old_total, amount);
+    let new_level = UserStats::calculate_level(total_exp);
+    sqlx::query(
+        "UPDATE user_stats SET total_exp = ?, level = ?, updated_at = NOW() WHERE user_id = ?",
+    )
+    .bind(total_exp)
+    .bind(new_level)
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+
+    record_exp_transaction(
+        &mut *conn,
+        user_id,
+        recorded_amount,
+        total_exp,
+        source,
+        reference_id,
+    )
+    .await?;
+
+    if new_level > old_level {
+        grant_level_up_coins(&mut *conn, user_id, new_level).await?;
+    }
+
+    Ok(ExpChange {
+        total_exp,
+        old_level,
+        new_level,
+    })
+}
+
+/// 累計EXPの増減を履歴に記録する（増減がない場合は記録しない）
+/// award_expを通さずに累計EXPを書き換える場合（管理者のレベル変更など）に使う
+pub(crate) async fn record_exp_transaction(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    amount: i64,
+    balance_after: i64,
+    source: &str,
+    reference_id: Option<i64>,
+) -> Result<(), AppError> {
+    if amount == 0 {
+        return Ok(());
+    }
+    sqlx::query(
+        r#"INSERT INTO exp_transactions (user_id, amount, balance_after, source, reference_id, created_at)
+           VALUES (?, ?, ?, ?, ?, NOW())"#,
+    )
+    .bind(user_id)
+    .bind(amount)
+    .bind(balance_after)
+    .bind(source)
+    .bind(reference_id)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct ExpHistoryQuery {
+    page: Option<i32>,
+    size: Option<i32>,
+    source: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExpTransactionDto {
+    id: i64,
+    amount: i64,
+    balance_after: i64,
+    source: String,
+    reference_id: Option<i64>,
+    created_at: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct ExpTransactionRow {
+    id: i64,
+    amount: i64,
+    balance_after: i64,
+    source: String,
+    reference_id: Option<i64>,
+    created_at: NaiveDateTime,
+}
+
+impl From<ExpTransactionRow> for ExpTransactionDto {
+    fn from(row: ExpTransactionRow) -> Self {
+        Self {
+            id: row.id,
+            amount: row.amount,
+            balance_after: row.balance_after,
+            source: row.source,
+            reference_id: row.reference_id,
+            created_at: row.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExpHistoryResponse {
+    content: Vec<ExpTransactionDto>,
+    page: i32,
+    size: i32,
+    total_elements: i64,
+    total_pages: i32,
+    has_next: bool,
+    has_previous: bool,
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/user/exp-history?page=0&size=20&source=workout
+/// EXPの増減履歴（新しい順）
+#[get("/user/exp-history")]
+async fn get_exp_history(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<ExpHistoryQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let page = query.page.unwrap_or(0).max(0);
+    let size = query.size.unwrap_or(20).clamp(1, MAX_PAGE_SIZE);
+    let source = query.source.as_deref().filter(|s| !s.is_empty());
+
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM exp_transactions WHERE user_id = ? AND (? IS NULL OR source = ?)",
+    )
+    .bind(session_user.id)
+    .bind(source)
+    .bind(source)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    let rows: Vec<ExpTransactionRow> = sqlx::query_as(
+        r#"SELECT id, amount, balance_after, source, reference_id, created_at
+           FROM exp_transactions
+           WHERE user_id = ? AND (? IS NULL OR source = ?)
+           ORDER BY created_at DESC, id DESC
+           LIMIT ? OFFSET ?"#,
+    )
+    .bind(session_user.id)
+    .bind(source)
+    .bind(source)
+    .bind(size)
+    .bind(page * size)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let total_pages = ((total as f64) / (size as f64)).ceil() as i32;
+    Ok(HttpResponse::Ok().json(ExpHistoryResponse {
+        content: rows.into_iter().map(ExpTransactionDto::from).collect(),
+        page,
+        size,
+        total_elements: total,
+        total_pages,
+        has_next: page < total_pages - 1,
+        has_previous: page > 0,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_exp_history);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ledger_amount_is_the_actual_change() {
+        assert_eq!(apply_exp_amount(100, 50), (150, 50));
+        assert_eq!(apply_exp_amount(100, -30), (70, -30));
+        // 累計EXPを超える減算は、残っていた分だけを記録する
+        assert_eq!(apply_exp_amount(20, -50), (0, -20));
+        assert_eq!(apply_exp_amount(0, -10), (0, 0));
+    }
+
+    #[test]
+    fn transaction_dto_formats_created_at() {
+        let dto = ExpTransactionDto::from(ExpTransactionRow {
+            id: 1,
+            amount: -20,
+            balance_after: 0,
+            source: EXP_SOURCE_WORKOUT_DELETE.to_string(),
+            reference_id: Some(42),
+            created_at: chrono::NaiveDate::from_ymd_opt(2026, 3, 4)
+                .unwrap()
+                .and_hms_opt(5, 6, 7)
+                .unwrap(),
+        });
+        assert_eq!(dto.created_at, "2026-03-04T05:06:07");
+        assert_eq!(dto.source, "workout_delete");
+    }
+}
@@ -1,11 +1,10 @@
 //! ギアAPIハンドラ
 
-use actix_session::Session;
 use actix_web::{get, post, web, HttpResponse};
 use serde::Serialize;
 use sqlx::MySqlPool;
 
-use crate::auth::session::get_current_user;
+use crate::auth::session::{get_current_user, Session};
 use crate::db::models::{GearCategory, GearFeature, GearType};
 use crate::error::AppError;
 
@@ -0,0 +1,657 @@
+//! 目標APIハンドラ
+//!
+//! 種目の推定1RM・体重・週のトレーニング日数の目標を設定し、既存の記録から進捗を計算する。
+//! 記録の保存・編集、体重の更新、目標一覧の取得のたびに達成を判定し、
+//! 達成した目標は報酬EXPを付与して達成済みにする（記録を削除しても達成は取り消さない）。
+
+use actix_web::{delete, get, post, put, web, HttpResponse};
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::exp_ledger::{award_exp, EXP_SOURCE_GOAL};
+use crate::api::personal_record::ExerciseRef;
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::domain::one_rm::{estimate_one_rep_max, OneRmFormula};
+use crate::error::AppError;
+
+/// 種目の推定1RM（kg）
+pub(crate) const GOAL_ONE_RM: &str = "ONE_RM";
+/// 体重（kg、増量・減量とも）
+pub(crate) const GOAL_BODY_WEIGHT: &str = "BODY_WEIGHT";
+/// 1週間（月曜始まり）のトレーニング日数
+pub(crate) const GOAL_WEEKLY_WORKOUTS: &str = "WEEKLY_WORKOUTS";
+
+/// 達成時の報酬EXP
+const ONE_RM_EXP_REWARD: i32 = 500;
+const BODY_WEIGHT_EXP_REWARD: i32 = 500;
+const WEEKLY_WORKOUTS_EXP_REWARD: i32 = 200;
+
+/// 未達成の目標の上限
+const MAX_ACTIVE_GOALS: i64 = 20;
+/// 推定1RMの目標の上限（kg）
+const MAX_ONE_RM_TARGET: f64 = 1000.0;
+/// 体重の目標の範囲（kg、体重の登録と同じ）
+const BODY_WEIGHT_RANGE: std::ops::RangeInclusive<f64> = 20.0..=300.0;
+/// 体重の目標に必要な設定時の体重からの変化（kg）
+/// 体重は自己申告のため、わずかな差の目標を繰り返してEXPを得られないようにする
+const MIN_BODY_WEIGHT_CHANGE: f64 = 1.0;
+/// 体重の目標の報酬EXPを受け取れる間隔（日）
+/// 期間内に報酬を受け取っていれば、達成しても報酬EXPは0にする
+const BODY_WEIGHT_REWARD_INTERVAL_DAYS: i32 = 30;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateGoalRequest {
+    goal_type: String,
+    /// ONE_RMのみ
+    exercise_id: Option<i64>,
+    #[serde(default)]
+    is_custom: bool,
+    target_value: f64,
+    deadline: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateGoalRequest {
+    target_value: f64,
+    deadline: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GoalDto {
+    id: i64,
+    goal_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exercise_id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_custom: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exercise_name: Option<String>,
+    target_value: f64,
+    /// 設定時の値（ONE_RM・BODY_WEIGHTの進捗の起点）
+    start_value: Option<f64>,
+    /// 現在の値（記録・体重がない場合はnull）
+    current_value: Option<f64>,
+    /// 達成率（0.0〜1.0）
+    progress: f64,
+    deadline: Option<String>,
+    pub(crate) exp_reward: i32,
+    completed: bool,
+    completed_at: Option<String>,
+    /// 期限を過ぎた未達成の目標（以降は達成と判定しない）
+    expired: bool,
+    created_at: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct GoalRow {
+    id: i64,
+    goal_type: String,
+    exercise_id: Option<i64>,
+    is_custom: bool,
+    exercise_name: Option<String>,
+    target_value: f64,
+    start_value: Option<f64>,
+    deadline: Option<NaiveDate>,
+    exp_reward: i32,
+    completed_at: Option<NaiveDateTime>,
+    created_at: NaiveDateTime,
+}
+
+impl GoalRow {
+    fn exercise(&self) -> Option<ExerciseRef> {
+        self.exercise_id.map(|id| ExerciseRef {
+            id,
+            is_custom: self.is_custom,
+        })
+    }
+
+    /// 現在の値が目標に届いたか（体重は設定時より軽い目標なら減量として判定する）
+    fn is_reached(&self, current: f64) -> bool {
+        match (self.goal_type.as_str(), self.start_value) {
+            (GOAL_BODY_WEIGHT, Some(start)) if self.target_value < start => {
+                current <= self.target_value
+            }
+            _ => current >= self.target_value,
+        }
+    }
+
+    /// 達成率（0.0〜1.0）
+    fn progress(&self, current: Option<f64>) -> f64 {
+        let Some(current) = current else {
+            return 0.0;
+        };
+        if self.is_reached(current) {
+            return 1.0;
+        }
+        let start = match self.goal_type.as_str() {
+            GOAL_WEEKLY_WORKOUTS => 0.0,
+            _ => self.start_value.unwrap_or(0.0),
+        };
+        let span = self.target_value - start;
+        if span == 0.0 {
+            return 0.0;
+        }
+        ((current - start) / span).clamp(0.0, 1.0)
+    }
+
+    fn into_dto(self, current_value: Option<f64>, today: NaiveDate) -> GoalDto {
+        let progress = self.progress(current_value);
+        let completed = self.completed_at.is_some();
+        GoalDto {
+            exercise_id: self.exercise_id,
+            is_custom: self.exercise_id.map(|_| self.is_custom),
+            progress: if completed { 1.0 } else { progress },
+            expired: !completed && self.deadline.is_some_and(|d| d < today),
+            id: self.id,
+            goal_type: self.goal_type,
+            exercise_name: self.exercise_name,
+            target_value: self.target_value,
+            start_value: self.start_value,
+            current_value,
+            deadline: self.deadline.map(|d| d.format("%Y-%m-%d").to_string()),
+            exp_reward: self.exp_reward,
+            completed,
+            completed_at: self
+                .completed_at
+                .map(|at| at.format("%Y-%m-%dT%H:%M:%S").to_string()),
+            created_at: self.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+// ============================================
+// 進捗の計算
+// ============================================
+
+/// 種目の推定1RMの最高値（ウォームアップを除く）
+async fn best_one_rm(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    exercise: ExerciseRef,
+) -> Result<Option<f64>, AppError> {
+    let row: Option<(f64, i32)> = sqlx::query_as(&format!(
+        r#"SELECT ts.weight, ts.reps
+           FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ? AND {} = ? AND ts.weight > 0 AND ts.reps > 0
+             AND ts.set_type <> 'warmup'
+           ORDER BY {} DESC
+           LIMIT 1"#,
+        exercise.column(),
+        OneRmFormula::Epley.sql_expr()
+    ))
+    .bind(user_id)
+    .bind(exercise.id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(row.and_then(|(weight, reps)| estimate_one_rep_max(weight, reps)))
+}
+
+async fn current_body_weight(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<Option<f64>, AppError> {
+    let weight: Option<Option<f64>> =
+        sqlx::query_scalar("SELECT body_weight FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+    Ok(weight.flatten())
+}
+
+/// todayを含む週（月曜始まり）のトレーニング日数
+async fn workouts_this_week(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    today: NaiveDate,
+) -> Result<i64, AppError> {
+    let week_start = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let count: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(DISTINCT record_date) FROM training_records
+           WHERE user_id = ? AND record_date >= ? AND record_date <= ?"#,
+    )
+    .bind(user_id)
+    .bind(week_start)
+    .bind(week_start + Days::new(6))
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(count)
+}
+
+/// 目標の種類ごとの現在の値
+async fn current_value(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    goal_type: &str,
+    exercise: Option<ExerciseRef>,
+    today: NaiveDate,
+) -> Result<Option<f64>, AppError> {
+    match (goal_type, exercise) {
+        (GOAL_ONE_RM, Some(exercise)) => best_one_rm(conn, user_id, exercise).await,
+        (GOAL_BODY_WEIGHT, _) => current_body_weight(conn, user_id).await,
+        (GOAL_WEEKLY_WORKOUTS, _) => {
+            Ok(Some(workouts_this_week(conn, user_id, today).await? as f64))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn fetch_goal_rows(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    goal_id: Option<i64>,
+) -> Result<Vec<GoalRow>, AppError> {
+    let rows: Vec<GoalRow> = sqlx::query_as(
+        r#"SELECT g.id, g.goal_type, g.exercise_id, g.is_custom,
+                  CAST(COALESCE(uce.name, e.name) AS CHAR) AS exercise_name,
+                  g.target_value, g.start_value, g.deadline, g.exp_reward, g.completed_at,
+                  g.created_at
+           FROM user_goals g
+           LEFT JOIN exercises e ON e.id = g.exercise_id AND g.is_custom = FALSE
+           LEFT JOIN user_custom_exercises uce ON uce.id = g.exercise_id AND g.is_custom = TRUE
+           WHERE g.user_id = ? AND (? IS NULL OR g.id = ?)
+           ORDER BY g.completed_at IS NOT NULL, g.created_at DESC, g.id DESC"#,
+    )
+    .bind(user_id)
+    .bind(goal_id)
+    .bind(goal_id)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows)
+}
+
+/// 進捗を付けた目標一覧（未達成を先に、新しい順）
+pub(crate) async fn fetch_goals(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    today: NaiveDate,
+) -> Result<Vec<GoalDto>, AppError> {
+    let mut goals = Vec::new();
+    for row in fetch_goal_rows(conn, user_id, None).await? {
+        let current = current_value(conn, user_id, &row.goal_type, row.exercise(), today).await?;
+        goals.push(row.into_dto(current, today));
+    }
+    Ok(goals)
+}
+
+/// 期間内に体重の目標の報酬EXPを受け取っているか（goal_idの目標は除く）
+async fn body_weight_reward_claimed_recently(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    goal_id: i64,
+) -> Result<bool, AppError> {
+    let count: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*) FROM user_goals
+           WHERE user_id = ? AND goal_type = ? AND id <> ? AND exp_reward > 0
+             AND completed_at >= NOW() - INTERVAL ? DAY"#,
+    )
+    .bind(user_id)
+    .bind(GOAL_BODY_WEIGHT)
+    .bind(goal_id)
+    .bind(BODY_WEIGHT_REWARD_INTERVAL_DAYS)
+    .fetch_one(conn)
+    .await?;
+    Ok(count > 0)
+}
+
+/// 達成した目標を達成済みにして報酬EXPをユーザーに付与し、達成した目標を返す
+/// todayはユーザーのタイムゾーンでの今日の日付（期限・週の判定に使う）
+pub(crate) async fn complete_achieved_goals(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    today: NaiveDate,
+) -> Result<Vec<GoalDto>, AppError> {
+    let rows: Vec<GoalRow> = fetch_goal_rows(conn, user_id, None)
+        .await?
+        .into_iter()
+        .filter(|row| row.completed_at.is_none() && row.deadline.is_none_or(|d| d >= today))
+        .collect();
+
+    let mut completed = Vec::new();
+    for mut row in rows {
+        let current = current_value(conn, user_id, &row.goal_type, row.exercise(), today).await?;
+        if !current.is_some_and(|value| row.is_reached(value)) {
+            continue;
+        }
+        let result = sqlx::query(
+            "UPDATE user_goals SET completed_at = NOW() WHERE id = ? AND completed_at IS NULL",
+        )
+        .bind(row.id)
+        .execute(&mut *conn)
+        .await?;
+        if result.rows_affected() == 0 {
+            continue;
+        }
+        if row.goal_type == GOAL_BODY_WEIGHT
+            && row.exp_reward > 0
+            && body_weight_reward_claimed_recently(conn, user_id, row.id).await?
+        {
+            sqlx::query("UPDATE user_goals SET exp_reward = 0 WHERE id = ?")
+                .bind(row.id)
+                .execute(&mut *conn)
+                .await?;
+            row.exp_reward = 0;
+        }
+        row.completed_at = sqlx::query_scalar("SELECT completed_at FROM user_goals WHERE id = ?")
+            .bind(row.id)
+            .fetch_one(&mut *conn)
+            .await?;
+        tracing::info!(
+            "[GOAL] user_id={} completed goal_id={} ({})",
+            user_id,
+            row.id,
+            row.goal_type
+        );
+        completed.push(row.into_dto(current, today));
+    }
+
+    for goal in completed.iter().filter(|goal| goal.exp_reward > 0) {
+        award_exp(
+            &mut *conn,
+            user_id,
+            goal.exp_reward as i64,
+            EXP_SOURCE_GOAL,
+            Some(goal.id),
+        )
+        .await?;
+    }
+
+    Ok(completed)
+}
+
+// ============================================
+// 入力チェック
+// ============================================
+
+fn parse_deadline(deadline: Option<&str>, today: NaiveDate) -> Result<Option<NaiveDate>, AppError> {
+    let Some(deadline) = deadline.filter(|d| !d.is_empty()) else {
+        return Ok(None);
+    };
+    let deadline = NaiveDate::parse_from_str(deadline, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("Invalid date format".to_string()))?;
+    if deadline < today {
+        return Err(AppError::BadRequest(
+            "期限には今日以降の日付を指定してください".to_string(),
+        ));
+    }
+    Ok(Some(deadline))
+}
+
+fn validate_target_value(goal_type: &str, target_value: f64) -> Result<(), AppError> {
+    let valid = match goal_type {
+        GOAL_ONE_RM => target_value > 0.0 && target_value <= MAX_ONE_RM_TARGET,
+        GOAL_BODY_WEIGHT => BODY_WEIGHT_RANGE.contains(&target_value),
+        _ => target_value.fract() == 0.0 && (1.0..=7.0).contains(&target_value),
+    };
+    if valid {
+        return Ok(());
+    }
+    Err(AppError::BadRequest(match goal_type {
+        GOAL_ONE_RM => format!(
+            "推定1RMの目標は0より大きく{}kg以下で指定してください",
+            MAX_ONE_RM_TARGET
+        ),
+        GOAL_BODY_WEIGHT => "体重の目標は20〜300kgの範囲で指定してください".to_string(),
+        _ => "週のトレーニング日数の目標は1〜7日で指定してください".to_string(),
+    }))
+}
+
+/// 目標の種目が存在するか（カスタム種目は自分の種目のみ）
+async fn ensure_exercise_exists(
+    pool: &MySqlPool,
+    user_id: i64,
+    exercise: ExerciseRef,
+) -> Result<(), AppError> {
+    let exists: Option<i64> = if exercise.is_custom {
+        sqlx::query_scalar("SELECT id FROM user_custom_exercises WHERE id = ? AND user_id = ?")
+            .bind(exercise.id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?
+    } else {
+        sqlx::query_scalar("SELECT id FROM exercises WHERE id = ?")
+            .bind(exercise.id)
+            .fetch_optional(pool)
+            .await?
+    };
+    if exists.is_none() {
+        return Err(AppError::NotFound("Exercise not found".to_string()));
+    }
+    Ok(())
+}
+
+fn already_reached_error() -> AppError {
+    AppError::BadRequest("既に達成している目標は設定できません".to_string())
+}
+
+/// 体重の目標が設定時の体重から十分に離れているか
+fn validate_body_weight_change(start_value: f64, target_value: f64) -> Result<(), AppError> {
+    if (target_value - start_value).abs() < MIN_BODY_WEIGHT_CHANGE {
+        return Err(AppError::BadRequest(format!(
+            "体重の目標は現在の体重から{}kg以上離して設定してください",
+            MIN_BODY_WEIGHT_CHANGE
+        )));
+    }
+    Ok(())
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/goals
+/// 進捗を付けた目標一覧（達成した目標があればここで達成済みにする）
+#[get("/goals")]
+async fn list_goals(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+
+    let mut tx = pool.begin().await?;
+    complete_achieved_goals(&mut tx, session_user.id, today).await?;
+    let goals = fetch_goals(&mut tx, session_user.id, today).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(goals))
+}
+
+/// POST /api/goals
+#[post("/goals")]
+async fn create_goal(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<CreateGoalRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+
+    let (goal_type, exp_reward) = match body.goal_type.as_str() {
+        GOAL_ONE_RM => (GOAL_ONE_RM, ONE_RM_EXP_REWARD),
+        GOAL_BODY_WEIGHT => (GOAL_BODY_WEIGHT, BODY_WEIGHT_EXP_REWARD),
+        GOAL_WEEKLY_WORKOUTS => (GOAL_WEEKLY_WORKOUTS, WEEKLY_WORKOUTS_EXP_REWARD),
+        _ => {
+            return Err(AppError::BadRequest(
+                "goalTypeはONE_RM、BODY_WEIGHT、WEEKLY_WORKOUTSのいずれかを指定してください"
+                    .to_string(),
+            ))
+        }
+    };
+    validate_target_value(goal_type, body.target_value)?;
+    let deadline = parse_deadline(body.deadline.as_deref(), today)?;
+
+    let exercise = match (goal_type, body.exercise_id) {
+        (GOAL_ONE_RM, Some(id)) => {
+            let exercise = ExerciseRef {
+                id,
+                is_custom: body.is_custom,
+            };
+            ensure_exercise_exists(pool.get_ref(), session_user.id, exercise).await?;
+            Some(exercise)
+        }
+        (GOAL_ONE_RM, None) => {
+            return Err(AppError::BadRequest("種目を指定してください".to_string()));
+        }
+        _ => None,
+    };
+
+    let active: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_goals WHERE user_id = ? AND completed_at IS NULL",
+    )
+    .bind(session_user.id)
+    .fetch_one(pool.get_ref())
+    .await?;
+    if active >= MAX_ACTIVE_GOALS {
+        return Err(AppError::BadRequest(format!(
+            "未達成の目標は{}件まで設定できます",
+            MAX_ACTIVE_GOALS
+        )));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let current = current_value(&mut conn, session_user.id, goal_type, exercise, today).await?;
+    let start_value = match goal_type {
+        GOAL_BODY_WEIGHT => {
+            let Some(weight) = current else {
+                return Err(AppError::BadRequest(
+                    "体重の目標を設定する前に体重を登録してください".to_string(),
+                ));
+            };
+            validate_body_weight_change(weight, body.target_value)?;
+            Some(weight)
+        }
+        GOAL_ONE_RM => current,
+        _ => None,
+    };
+    // 設定した時点で達成している目標は報酬EXPの対象にしない
+    if goal_type != GOAL_BODY_WEIGHT && current.is_some_and(|value| value >= body.target_value) {
+        return Err(already_reached_error());
+    }
+
+    let result = sqlx::query(
+        r#"INSERT INTO user_goals
+               (user_id, goal_type, exercise_id, is_custom, target_value, start_value, deadline,
+                exp_reward, created_at, updated_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())"#,
+    )
+    .bind(session_user.id)
+    .bind(goal_type)
+    .bind(exercise.map(|e| e.id))
+    .bind(exercise.is_some_and(|e| e.is_custom))
+    .bind(body.target_value)
+    .bind(start_value)
+    .bind(deadline)
+    .bind(exp_reward)
+    .execute(&mut *conn)
+    .await?;
+
+    let goal = fetch_goal_rows(
+        &mut conn,
+        session_user.id,
+        Some(result.last_insert_id() as i64),
+    )
+    .await?
+    .pop()
+    .ok_or_else(|| AppError::InternalError("Created goal not found".to_string()))?;
+    Ok(HttpResponse::Ok().json(goal.into_dto(current, today)))
+}
+
+/// PUT /api/goals/{id}
+/// 未達成の目標の目標値・期限を変更する
+#[put("/goals/{id}")]
+async fn update_goal(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    body: web::Json<UpdateGoalRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let goal_id = path.into_inner();
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+
+    let mut conn = pool.acquire().await?;
+    let Some(mut goal) = fetch_goal_rows(&mut conn, session_user.id, Some(goal_id))
+        .await?
+        .pop()
+    else {
+        return Err(AppError::NotFound("Goal not found".to_string()));
+    };
+    if goal.completed_at.is_some() {
+        return Err(AppError::BadRequest(
+            "達成済みの目標は変更できません".to_string(),
+        ));
+    }
+    validate_target_value(&goal.goal_type, body.target_value)?;
+    let deadline = parse_deadline(body.deadline.as_deref(), today)?;
+
+    goal.target_value = body.target_value;
+    let current = current_value(
+        &mut conn,
+        session_user.id,
+        &goal.goal_type,
+        goal.exercise(),
+        today,
+    )
+    .await?;
+    if goal.goal_type == GOAL_BODY_WEIGHT {
+        if let Some(start_value) = goal.start_value {
+            validate_body_weight_change(start_value, body.target_value)?;
+        }
+    }
+    if current.is_some_and(|value| goal.is_reached(value)) {
+        return Err(already_reached_error());
+    }
+
+    sqlx::query(
+        r#"UPDATE user_goals SET target_value = ?, deadline = ?, updated_at = NOW()
+           WHERE id = ? AND user_id = ?"#,
+    )
+    .bind(body.target_value)
+    .bind(deadline)
+    .bind(goal_id)
+    .bind(session_user.id)
+    .execute(&mut *conn)
+    .await?;
+
+    goal.deadline = deadline;
+    Ok(HttpResponse::Ok().json(goal.into_dto(current, today)))
+}
+
+/// DELETE /api/goals/{id}
+#[delete("/goals/{id}")]
+async fn delete_goal(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let result = sqlx::query("DELETE FROM user_goals WHERE id = ? AND user_id = ?")
+        .bind(path.into_inner())
+        .bind(session_user.id)
+        .execute(pool.get_ref())
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Goal not found".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_goals)
+        .service(create_goal)
+        .service(update_goal)
+        .service(delete_goal);
+}
@@ -1,13 +1,22 @@
 //! ジムAPIハンドラ
 
-use actix_session::Session;
 use actix_web::{get, post, web, HttpResponse};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
+use std::time::Duration;
 
-use crate::auth::session::get_current_user;
+use crate::api::pet::check_and_unlock_barn_backgrounds;
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
 use crate::db::models::Tag;
 use crate::error::AppError;
+use crate::shared_store::SharedStore;
+
+/// マスタデータのキャッシュキー
+pub(crate) const CACHE_KEY_GYM_TAGS: &str = "gym-tags";
+pub(crate) const CACHE_KEY_GYM_AREAS: &str = "gym-areas";
+/// マスタデータのキャッシュ有効期間
+const MASTER_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
 
 // ============================================
 // DTOs
@@ -15,11 +24,13 @@ use crate::error::AppError;
 
 #[derive(Deserialize)]
 pub struct GymSearchQuery {
-    tags: Option<String>, // カンマ区切りのタグ名
+    tags: Option<String>,   // カンマ区切りのタグ名
+    groups: Option<String>, // カンマ区切りのタググループ
     #[serde(rename = "maxPrice")]
     max_price: Option<i32>,
     search: Option<String>,
     areas: Option<String>, // カンマ区切りのエリア
+    sort: Option<String>,  // name | recent | popularity
     page: Option<i32>,
     size: Option<i32>,
 }
@@ -62,12 +73,31 @@ struct GymPagedResponse {
     has_previous: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 struct TagListDto {
     id: i64,
     name: Option<String>,
     #[serde(rename = "displayOrder")]
     display_order: Option<i32>,
+    group: Option<String>,
+}
+
+impl From<Tag> for TagListDto {
+    fn from(t: Tag) -> Self {
+        Self {
+            id: t.id,
+            name: t.name,
+            display_order: t.display_order,
+            group: t.tag_group,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct TagGroupDto {
+    /// グループ名（未分類のタグはnull）
+    group: Option<String>,
+    tags: Vec<TagListDto>,
 }
 
 // ============================================
@@ -94,6 +124,155 @@ struct GymTagRow {
     tag_name: Option<String>,
 }
 
+// ============================================
+// ソート
+// ============================================
+
+/// ジム一覧のソート順
+#[derive(Clone, Copy)]
+enum GymSort {
+    Id,
+    Name,
+    Recent,
+    Popularity,
+}
+
+impl GymSort {
+    /// sortパラメータをパース（未指定時はID順）
+    fn parse(sort: Option<&str>) -> Result<Self, AppError> {
+        match sort.map(str::trim).filter(|s| !s.is_empty()) {
+            None => Ok(Self::Id),
+            Some("name") => Ok(Self::Name),
+            Some("recent") | Some("recently-added") => Ok(Self::Recent),
+            Some("popularity") => Ok(Self::Popularity),
+            Some(other) => Err(AppError::BadRequest(format!(
+                "Invalid sort parameter: {}",
+                other
+            ))),
+        }
+    }
+
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::Id => "g.id ASC",
+            Self::Name => "g.name ASC, g.id ASC",
+            // ジムマスタには作成日時がないため、IDの降順を追加順とみなす
+            Self::Recent => "g.id DESC",
+            // 人気順: チェックイン数
+            Self::Popularity => {
+                "(SELECT COUNT(*) FROM gym_check_ins ci WHERE ci.gym_id = g.id) DESC, g.id ASC"
+            }
+        }
+    }
+}
+
+// ============================================
+// 検索条件
+// ============================================
+
+/// ジム検索のフィルター条件
+struct GymFilters {
+    tag_names: Vec<String>,
+    group_names: Vec<String>,
+    area_list: Vec<String>,
+    search_query: Option<String>,
+    max_price: Option<i32>,
+}
+
+impl GymFilters {
+    fn is_empty(&self) -> bool {
+        self.tag_names.is_empty()
+            && self.group_names.is_empty()
+            && self.area_list.is_empty()
+            && self.search_query.is_none()
+            && self.max_price.is_none()
+    }
+
+    /// WHERE句に追加する条件
+    fn where_sql(&self) -> String {
+        let mut sql = String::new();
+        if self.max_price.is_some() {
+            sql.push_str(" AND (g.price_range IS NULL OR g.price_range <= ?)");
+        }
+        if self.search_query.is_some() {
+            sql.push_str(" AND (LOWER(g.name) LIKE ? OR LOWER(g.address) LIKE ?)");
+        }
+        if !self.area_list.is_empty() {
+            sql.push_str(&format!(" AND g.area IN ({})", placeholders(self.area_list.len())));
+        }
+        sql
+    }
+
+    /// GROUP BY g.id に続くHAVING句
+    /// - タグ: 指定された全てのタグを持つ（AND条件）
+    /// - グループ: 指定された各グループのタグを1つ以上持つ（グループ間はAND条件）
+    fn having_sql(&self) -> String {
+        let mut conditions = Vec::new();
+        if !self.tag_names.is_empty() {
+            conditions.push(format!(
+                "COUNT(DISTINCT CASE WHEN t.name IN ({}) THEN t.name END) = ?",
+                placeholders(self.tag_names.len())
+            ));
+        }
+        if !self.group_names.is_empty() {
+            conditions.push(format!(
+                "COUNT(DISTINCT CASE WHEN t.tag_group IN ({}) THEN t.tag_group END) = ?",
+                placeholders(self.group_names.len())
+            ));
+        }
+        if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" HAVING {}", conditions.join(" AND "))
+        }
+    }
+
+    /// where_sql・having_sqlの順にパラメータをバインド
+    fn bind<'q, O>(
+        &'q self,
+        mut q: sqlx::query::QueryAs<'q, sqlx::MySql, O, sqlx::mysql::MySqlArguments>,
+    ) -> sqlx::query::QueryAs<'q, sqlx::MySql, O, sqlx::mysql::MySqlArguments> {
+        if let Some(mp) = self.max_price {
+            q = q.bind(mp);
+        }
+        if let Some(ref sq) = self.search_query {
+            q = q.bind(sq).bind(sq);
+        }
+        for area in &self.area_list {
+            q = q.bind(area);
+        }
+        if !self.tag_names.is_empty() {
+            for tag in &self.tag_names {
+                q = q.bind(tag);
+            }
+            q = q.bind(self.tag_names.len() as i64);
+        }
+        if !self.group_names.is_empty() {
+            for group in &self.group_names {
+                q = q.bind(group);
+            }
+            q = q.bind(self.group_names.len() as i64);
+        }
+        q
+    }
+}
+
+/// カンマ区切りのパラメータを分割
+fn split_csv(value: Option<&str>) -> Vec<String> {
+    value
+        .map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(",")
+}
+
 // ============================================
 // ハンドラ
 // ============================================
@@ -113,211 +292,73 @@ async fn search_gyms_paged(
     let offset = page * size;
 
     // フィルターパラメータをパース
-    let tag_names: Vec<String> = query
-        .tags
-        .as_ref()
-        .map(|t| {
-            t.split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let area_list: Vec<String> = query
-        .areas
-        .as_ref()
-        .map(|a| {
-            a.split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let search_query = query
-        .search
-        .as_ref()
-        .filter(|s| !s.trim().is_empty())
-        .map(|s| format!("%{}%", s.trim().to_lowercase()));
-
-    let max_price = query.max_price;
-    let tag_count = tag_names.len() as i64;
+    let filters = GymFilters {
+        tag_names: split_csv(query.tags.as_deref()),
+        group_names: split_csv(query.groups.as_deref()),
+        area_list: split_csv(query.areas.as_deref()),
+        search_query: query
+            .search
+            .as_ref()
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| format!("%{}%", s.trim().to_lowercase())),
+        max_price: query.max_price,
+    };
+    let sort = GymSort::parse(query.sort.as_deref())?;
 
     // ジムID用の動的クエリを構築
     // このアプローチはSpring Data JPAのタグAND条件クエリを模倣
-    let gym_ids: Vec<(i64,)> = if tag_names.is_empty()
-        && area_list.is_empty()
-        && search_query.is_none()
-        && max_price.is_none()
-    {
+    let gym_ids: Vec<(i64,)> = if filters.is_empty() {
         // フィルターなし - シンプルなページネーション
-        sqlx::query_as(r#"SELECT id FROM gyms ORDER BY id ASC LIMIT ? OFFSET ?"#)
+        let query_str = format!(
+            "SELECT g.id FROM gyms g ORDER BY {} LIMIT ? OFFSET ?",
+            sort.order_by()
+        );
+        sqlx::query_as(&query_str)
             .bind(size)
             .bind(offset)
             .fetch_all(pool.get_ref())
             .await?
     } else {
-        // 動的クエリを構築
-        let mut conditions = Vec::new();
-        let mut query_str = String::from(
+        let query_str = format!(
             r#"SELECT g.id FROM gyms g
                LEFT JOIN gym_tags gt ON g.id = gt.gym_id
                LEFT JOIN tags t ON gt.tag_id = t.id
-               WHERE 1=1"#,
+               WHERE 1=1{} GROUP BY g.id{} ORDER BY {} LIMIT ? OFFSET ?"#,
+            filters.where_sql(),
+            filters.having_sql(),
+            sort.order_by()
         );
 
-        if max_price.is_some() {
-            conditions.push("max_price");
-            query_str.push_str(" AND (g.price_range IS NULL OR g.price_range <= ?)");
-        }
-
-        if search_query.is_some() {
-            conditions.push("search");
-            query_str.push_str(" AND (LOWER(g.name) LIKE ? OR LOWER(g.address) LIKE ?)");
-        }
-
-        if !area_list.is_empty() {
-            query_str.push_str(&format!(
-                " AND g.area IN ({})",
-                area_list.iter().map(|_| "?").collect::<Vec<_>>().join(",")
-            ));
-        }
-
-        query_str.push_str(" GROUP BY g.id");
-
-        if !tag_names.is_empty() {
-            // タグAND条件: ジムは指定された全てのタグを持つ必要がある
-            query_str.push_str(&format!(
-                " HAVING COUNT(DISTINCT CASE WHEN t.name IN ({}) THEN t.name END) = ?",
-                tag_names.iter().map(|_| "?").collect::<Vec<_>>().join(",")
-            ));
-        }
-
-        query_str.push_str(" ORDER BY g.id ASC LIMIT ? OFFSET ?");
-
-        // 動的クエリを構築して実行
-        let mut q = sqlx::query_as::<_, (i64,)>(&query_str);
-
-        if let Some(mp) = max_price {
-            q = q.bind(mp);
-        }
-
-        if let Some(ref sq) = search_query {
-            q = q.bind(sq);
-            q = q.bind(sq);
-        }
-
-        for area in &area_list {
-            q = q.bind(area);
-        }
-
-        for tag in &tag_names {
-            q = q.bind(tag);
-        }
-
-        if !tag_names.is_empty() {
-            q = q.bind(tag_count);
-        }
-
-        q = q.bind(size);
-        q = q.bind(offset);
-
-        q.fetch_all(pool.get_ref()).await?
+        filters
+            .bind(sqlx::query_as::<_, (i64,)>(&query_str))
+            .bind(size)
+            .bind(offset)
+            .fetch_all(pool.get_ref())
+            .await?
     };
 
     // ページネーション用の合計数を取得
-    let total: (i64,) = if tag_names.is_empty()
-        && area_list.is_empty()
-        && search_query.is_none()
-        && max_price.is_none()
-    {
+    let total: (i64,) = if filters.is_empty() {
         sqlx::query_as("SELECT COUNT(*) FROM gyms")
             .fetch_one(pool.get_ref())
             .await?
     } else {
-        // 同じ条件でカウントクエリを構築
-        let mut count_query = String::from(
-            r#"SELECT COUNT(DISTINCT g.id) FROM gyms g
-               LEFT JOIN gym_tags gt ON g.id = gt.gym_id
-               LEFT JOIN tags t ON gt.tag_id = t.id
-               WHERE 1=1"#,
+        // タグ・グループ条件はGROUP BY後に判定するためサブクエリでカウント
+        let count_query = format!(
+            r#"SELECT COUNT(*) FROM (
+                SELECT g.id FROM gyms g
+                LEFT JOIN gym_tags gt ON g.id = gt.gym_id
+                LEFT JOIN tags t ON gt.tag_id = t.id
+                WHERE 1=1{} GROUP BY g.id{}
+            ) AS filtered"#,
+            filters.where_sql(),
+            filters.having_sql()
         );
 
-        if max_price.is_some() {
-            count_query.push_str(" AND (g.price_range IS NULL OR g.price_range <= ?)");
-        }
-
-        if search_query.is_some() {
-            count_query.push_str(" AND (LOWER(g.name) LIKE ? OR LOWER(g.address) LIKE ?)");
-        }
-
-        if !area_list.is_empty() {
-            count_query.push_str(&format!(
-                " AND g.area IN ({})",
-                area_list.iter().map(|_| "?").collect::<Vec<_>>().join(",")
-            ));
-        }
-
-        if !tag_names.is_empty() {
-            // タグフィルター付きのカウントはサブクエリを使用
-            let tag_placeholders = tag_names.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-            count_query = format!(
-                r#"SELECT COUNT(*) FROM (
-                    SELECT g.id FROM gyms g
-                    LEFT JOIN gym_tags gt ON g.id = gt.gym_id
-                    LEFT JOIN tags t ON gt.tag_id = t.id
-                    WHERE 1=1
-                    {} {} {}
-                    GROUP BY g.id
-                    HAVING COUNT(DISTINCT CASE WHEN t.name IN ({}) THEN t.name END) = ?
-                ) AS filtered"#,
-                if max_price.is_some() {
-                    "AND (g.price_range IS NULL OR g.price_range <= ?)"
-                } else {
-                    ""
-                },
-                if search_query.is_some() {
-                    "AND (LOWER(g.name) LIKE ? OR LOWER(g.address) LIKE ?)"
-                } else {
-                    ""
-                },
-                if !area_list.is_empty() {
-                    format!(
-                        "AND g.area IN ({})",
-                        area_list.iter().map(|_| "?").collect::<Vec<_>>().join(",")
-                    )
-                } else {
-                    String::new()
-                },
-                tag_placeholders
-            );
-        }
-
-        let mut cq = sqlx::query_as::<_, (i64,)>(&count_query);
-
-        if let Some(mp) = max_price {
-            cq = cq.bind(mp);
-        }
-
-        if let Some(ref sq) = search_query {
-            cq = cq.bind(sq);
-            cq = cq.bind(sq);
-        }
-
-        for area in &area_list {
-            cq = cq.bind(area);
-        }
-
-        for tag in &tag_names {
-            cq = cq.bind(tag);
-        }
-
-        if !tag_names.is_empty() {
-            cq = cq.bind(tag_count);
-        }
-
-        cq.fetch_one(pool.get_ref()).await?
+        filters
+            .bind(sqlx::query_as::<_, (i64,)>(&count_query))
+            .fetch_one(pool.get_ref())
+            .await?
     };
 
     if gym_ids.is_empty() {
@@ -335,10 +376,10 @@ async fn search_gyms_paged(
 
     // ジム詳細を取得
     let id_list: Vec<i64> = gym_ids.iter().map(|(id,)| *id).collect();
-    let placeholders = id_list.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let placeholders = placeholders(id_list.len());
 
     let gym_query = format!(
-        "SELECT id, name, address, phone, price_range, open_hours, area, latitude, longitude FROM gyms WHERE id IN ({}) ORDER BY id",
+        "SELECT id, name, address, phone, price_range, open_hours, area, latitude, longitude FROM gyms WHERE id IN ({})",
         placeholders
     );
 
@@ -346,7 +387,10 @@ async fn search_gyms_paged(
     for id in &id_list {
         gq = gq.bind(id);
     }
-    let gyms: Vec<GymRow> = gq.fetch_all(pool.get_ref()).await?;
+    let mut gyms: Vec<GymRow> = gq.fetch_all(pool.get_ref()).await?;
+
+    // ソート済みID順に並べ替え
+    gyms.sort_by_key(|g| id_list.iter().position(|id| *id == g.id));
 
     // これらのジムのタグを取得
     let tag_query = format!(
@@ -406,34 +450,60 @@ async fn search_gyms_paged(
     }))
 }
 
-/// GET /api/gyms/tags - 全ジム設備タグを取得
+/// GET /api/gyms/tags - 全ジム設備タグをグループ別に取得
+/// グループは所属タグの表示順で並べ、未分類のタグは末尾にまとめる
 #[get("/gyms/tags")]
 async fn get_gym_tags(
     session: Session,
     pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
 ) -> Result<HttpResponse, AppError> {
     // 認証必須
     let _user = get_current_user(&session)?;
 
+    if let Some(groups) = store.get_cached::<Vec<TagGroupDto>>(CACHE_KEY_GYM_TAGS).await {
+        return Ok(HttpResponse::Ok().json(groups));
+    }
+
     let tags = sqlx::query_as::<_, Tag>(r#"SELECT * FROM tags ORDER BY display_order ASC, id ASC"#)
         .fetch_all(pool.get_ref())
         .await?;
 
-    let tag_dtos: Vec<TagListDto> = tags
-        .into_iter()
-        .map(|t| TagListDto {
-            id: t.id,
-            name: t.name,
-            display_order: t.display_order,
-        })
-        .collect();
+    let mut groups: Vec<TagGroupDto> = Vec::new();
+    let mut ungrouped: Vec<TagListDto> = Vec::new();
+    for tag in tags {
+        let dto = TagListDto::from(tag);
+        match &dto.group {
+            None => ungrouped.push(dto),
+            Some(name) => match groups.iter_mut().find(|g| g.group.as_ref() == Some(name)) {
+                Some(group) => group.tags.push(dto),
+                None => groups.push(TagGroupDto {
+                    group: Some(name.clone()),
+                    tags: vec![dto],
+                }),
+            },
+        }
+    }
+    if !ungrouped.is_empty() {
+        groups.push(TagGroupDto {
+            group: None,
+            tags: ungrouped,
+        });
+    }
+
+    store
+        .set_cached(CACHE_KEY_GYM_TAGS, &groups, MASTER_CACHE_TTL)
+        .await;
 
-    Ok(HttpResponse::Ok().json(tag_dtos))
+    Ok(HttpResponse::Ok().json(groups))
 }
 
-/// POST /api/cache/clear - キャッシュクリア（管理者のみ、Rust版では何もしない）
+/// POST /api/cache/clear - マスタデータのキャッシュクリア（管理者のみ）
 #[post("/cache/clear")]
-async fn clear_cache(session: Session) -> Result<HttpResponse, AppError> {
+async fn clear_cache(
+    session: Session,
+    store: web::Data<SharedStore>,
+) -> Result<HttpResponse, AppError> {
     // 認証必須
     let user = get_current_user(&session)?;
 
@@ -442,20 +512,76 @@ async fn clear_cache(session: Session) -> Result<HttpResponse, AppError> {
         return Err(AppError::Unauthorized("Admin access required".to_string()));
     }
 
-    // Rust版ではキャッシュを使用していない（まだ）
-    // このエンドポイントはAPI互換性のために存在
+    store
+        .invalidate(&[CACHE_KEY_GYM_TAGS, CACHE_KEY_GYM_AREAS])
+        .await;
+
     Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
 }
 
+/// POST /api/gyms/{id}/check-in - ジムにチェックイン（1日1回）
+#[post("/gyms/{id}/check-in")]
+async fn check_in_gym(
+    session: Session,
+    pool: web::Data<MySqlPool>,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let user = get_current_user(&session)?;
+    let gym_id = path.into_inner();
+
+    let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM gyms WHERE id = ?")
+        .bind(gym_id)
+        .fetch_optional(pool.get_ref())
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::NotFound("Gym not found".to_string()));
+    }
+
+    let today = fetch_user_today(pool.get_ref(), user.id).await?;
+    let result = sqlx::query(
+        "INSERT IGNORE INTO gym_check_ins (user_id, gym_id, check_in_date, created_at) VALUES (?, ?, ?, NOW())",
+    )
+    .bind(user.id)
+    .bind(gym_id)
+    .bind(today)
+    .execute(pool.get_ref())
+    .await?;
+
+    let (check_in_count,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM gym_check_ins WHERE gym_id = ?")
+            .bind(gym_id)
+            .fetch_one(pool.get_ref())
+            .await?;
+
+    // チェックイン実績による小屋の背景解放
+    let unlocked_backgrounds = if result.rows_affected() > 0 {
+        check_and_unlock_barn_backgrounds(pool.get_ref(), user.id).await?
+    } else {
+        Vec::new()
+    };
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "alreadyCheckedIn": result.rows_affected() == 0,
+        "checkInCount": check_in_count,
+        "unlockedBackgrounds": unlocked_backgrounds
+    })))
+}
+
 /// GET /api/gyms/areas - フィルタリング用のユニークエリアを取得
 #[get("/gyms/areas")]
 async fn get_gym_areas(
     session: Session,
     pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
 ) -> Result<HttpResponse, AppError> {
     // 認証必須
     let _user = get_current_user(&session)?;
 
+    if let Some(areas) = store.get_cached::<Vec<String>>(CACHE_KEY_GYM_AREAS).await {
+        return Ok(HttpResponse::Ok().json(areas));
+    }
+
     let areas: Vec<(Option<String>,)> = sqlx::query_as(
         r#"SELECT DISTINCT area FROM gyms WHERE area IS NOT NULL AND area != '' ORDER BY area"#,
     )
@@ -464,6 +590,10 @@ async fn get_gym_areas(
 
     let area_list: Vec<String> = areas.into_iter().filter_map(|(a,)| a).collect();
 
+    store
+        .set_cached(CACHE_KEY_GYM_AREAS, &area_list, MASTER_CACHE_TTL)
+        .await;
+
     Ok(HttpResponse::Ok().json(area_list))
 }
 
@@ -471,5 +601,6 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(search_gyms_paged)
         .service(get_gym_tags)
         .service(get_gym_areas)
+        .service(check_in_gym)
         .service(clear_cache);
 }
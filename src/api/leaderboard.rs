@@ -0,0 +1,175 @@
+//! ランキングAPIハンドラ
+//!
+//! 週間EXP・週間ボリューム・トレーニングの最長ストリークのランキングを返す。
+//! 載るのはプライバシー設定でランキングへの参加を選んだユーザーのみ。
+//! 順位はジョブ（jobs::leaderboard）が定期的に集計したleaderboard_rankingsから読む。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+/// 今週（月曜始まり）の獲得EXP
+pub(crate) const LEADERBOARD_WEEKLY_EXP: &str = "weekly_exp";
+/// 今週（月曜始まり）の総挙上量（kg、ウォームアップを除く）
+pub(crate) const LEADERBOARD_WEEKLY_VOLUME: &str = "weekly_volume";
+/// トレーニングの最長ストリーク
+pub(crate) const LEADERBOARD_LONGEST_STREAK: &str = "longest_streak";
+
+pub(crate) const LEADERBOARD_TYPES: [&str; 3] = [
+    LEADERBOARD_WEEKLY_EXP,
+    LEADERBOARD_WEEKLY_VOLUME,
+    LEADERBOARD_LONGEST_STREAK,
+];
+
+/// 1ページの件数の上限
+const MAX_PAGE_SIZE: i32 = 100;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct LeaderboardQuery {
+    page: Option<i32>,
+    size: Option<i32>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LeaderboardEntryDto {
+    rank: i32,
+    display_name: Option<String>,
+    profile_image_url: Option<String>,
+    score: i64,
+    is_me: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct LeaderboardEntryRow {
+    rank_position: i32,
+    user_id: i64,
+    display_name: Option<String>,
+    profile_image_url: Option<String>,
+    score: i64,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+struct MyRankDto {
+    #[sqlx(rename = "rank_position")]
+    rank: i32,
+    score: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LeaderboardResponse {
+    board_type: &'static str,
+    /// 週間ランキングの週の月曜日
+    period_start: Option<String>,
+    /// 最後に集計した日時
+    refreshed_at: Option<NaiveDateTime>,
+    entries: Vec<LeaderboardEntryDto>,
+    /// 自分の順位（参加していない・スコアがない場合はnull）
+    me: Option<MyRankDto>,
+    page: i32,
+    size: i32,
+    total_elements: i64,
+    total_pages: i32,
+    has_next: bool,
+    has_previous: bool,
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/leaderboards/{type}?page=0&size=20
+/// 集計後に参加をやめたユーザーは次の集計を待たずに除く
+#[get("/leaderboards/{board_type}")]
+async fn get_leaderboard(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<String>,
+    query: web::Query<LeaderboardQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let board_type = LEADERBOARD_TYPES
+        .into_iter()
+        .find(|t| *t == path.as_str())
+        .ok_or_else(|| AppError::NotFound("Leaderboard not found".to_string()))?;
+
+    let page = query.page.unwrap_or(0).max(0);
+    let size = query.size.unwrap_or(20).clamp(1, MAX_PAGE_SIZE);
+
+    let (total, period_start, refreshed_at): (i64, Option<NaiveDate>, Option<NaiveDateTime>) =
+        sqlx::query_as(
+            r#"SELECT COUNT(*), MAX(lr.period_start), MAX(lr.refreshed_at)
+               FROM leaderboard_rankings lr
+               INNER JOIN user_settings s ON s.user_id = lr.user_id AND s.show_on_leaderboard = TRUE
+               WHERE lr.board_type = ?"#,
+        )
+        .bind(board_type)
+        .fetch_one(pool.get_ref())
+        .await?;
+
+    let rows: Vec<LeaderboardEntryRow> = sqlx::query_as(
+        r#"SELECT lr.rank_position, lr.user_id, u.display_name, u.profile_image_url, lr.score
+           FROM leaderboard_rankings lr
+           INNER JOIN user_settings s ON s.user_id = lr.user_id AND s.show_on_leaderboard = TRUE
+           INNER JOIN users u ON u.id = lr.user_id
+           WHERE lr.board_type = ?
+           ORDER BY lr.rank_position ASC, lr.user_id ASC
+           LIMIT ? OFFSET ?"#,
+    )
+    .bind(board_type)
+    .bind(size)
+    .bind(page * size)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let me: Option<MyRankDto> = sqlx::query_as(
+        r#"SELECT lr.rank_position, lr.score
+           FROM leaderboard_rankings lr
+           INNER JOIN user_settings s ON s.user_id = lr.user_id AND s.show_on_leaderboard = TRUE
+           WHERE lr.board_type = ? AND lr.user_id = ?"#,
+    )
+    .bind(board_type)
+    .bind(session_user.id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+
+    let entries = rows
+        .into_iter()
+        .map(|row| LeaderboardEntryDto {
+            rank: row.rank_position,
+            display_name: row.display_name,
+            profile_image_url: row.profile_image_url,
+            score: row.score,
+            is_me: row.user_id == session_user.id,
+        })
+        .collect();
+
+    let total_pages = ((total as f64) / (size as f64)).ceil() as i32;
+    Ok(HttpResponse::Ok().json(LeaderboardResponse {
+        board_type,
+        period_start: period_start.map(|d| d.format("%Y-%m-%d").to_string()),
+        refreshed_at,
+        entries,
+        me,
+        page,
+        size,
+        total_elements: total,
+        total_pages,
+        has_next: page < total_pages - 1,
+        has_previous: page > 0,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_leaderboard);
+}
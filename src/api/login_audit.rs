@@ -0,0 +1,181 @@
+//! ログイン履歴APIハンドラ
+//!
+//! パスワード・OAuth・トークン発行によるログインの成功と失敗をlogin_auditに記録し、
+//! ユーザーが自分のアカウントへの不審なアクセスに気付けるよう直近の履歴を返す。
+//! 記録に失敗してもログイン自体は続行する。古い履歴はバックグラウンドジョブで削除する。
+
+use actix_web::{get, http::header, web, HttpRequest, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::api::user_session::describe_device;
+use crate::auth::login_throttle::client_ip;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+/// 保存するUser-Agentの最大文字数
+const MAX_USER_AGENT_CHARS: usize = 255;
+/// 一覧の既定件数と上限
+const DEFAULT_ACTIVITY_LIMIT: i64 = 50;
+const MAX_ACTIVITY_LIMIT: i64 = 100;
+/// 履歴の保存期間
+pub const LOGIN_AUDIT_RETENTION_DAYS: i64 = 180;
+
+/// パスワードログインのプロバイダー（OAuthはプロバイダーコードを使う）
+pub const PROVIDER_LOCAL: &str = "LOCAL";
+/// Cookieセッションでのログイン
+pub const CLIENT_WEB: &str = "web";
+/// /api/auth/tokenでのトークン発行
+pub const CLIENT_TOKEN: &str = "token";
+
+/// ログインIDまたはパスワードの誤り
+pub const FAILURE_INVALID_CREDENTIALS: &str = "invalid_credentials";
+/// 二段階認証のコードが未入力
+pub const FAILURE_TWO_FACTOR_REQUIRED: &str = "two_factor_required";
+/// 二段階認証のコードの誤り
+pub const FAILURE_TWO_FACTOR_FAILED: &str = "two_factor_failed";
+/// 無効化されたアカウント（認証には成功している）
+pub const FAILURE_ACCOUNT_DEACTIVATED: &str = "account_deactivated";
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct ActivityQuery {
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LoginActivityDto {
+    id: i64,
+    success: bool,
+    provider: String,
+    client: String,
+    failure_reason: Option<String>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    /// User-Agentから推定した端末（例: "Chrome / Windows"）
+    device: String,
+    created_at: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct LoginActivityRow {
+    id: i64,
+    success: bool,
+    provider: String,
+    client: String,
+    failure_reason: Option<String>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<LoginActivityRow> for LoginActivityDto {
+    fn from(row: LoginActivityRow) -> Self {
+        Self {
+            id: row.id,
+            success: row.success,
+            provider: row.provider,
+            client: row.client,
+            failure_reason: row.failure_reason,
+            device: describe_device(row.user_agent.as_deref()),
+            ip_address: row.ip_address,
+            user_agent: row.user_agent,
+            created_at: row.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+// ============================================
+// 記録
+// ============================================
+
+/// ログインの試行を記録する（失敗時はfailure_reasonを指定する）
+/// user_idが分からない場合はログインIDからユーザーを引く（存在しないログインIDならNULL）
+pub(crate) async fn record_login_attempt(
+    pool: &MySqlPool,
+    req: &HttpRequest,
+    user_id: Option<i64>,
+    login_id: Option<&str>,
+    provider: &str,
+    client: &str,
+    failure_reason: Option<&str>,
+) {
+    let login_id = login_id.map(|id| id.trim().chars().take(100).collect::<String>());
+    let user_agent: Option<String> = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.chars().take(MAX_USER_AGENT_CHARS).collect());
+
+    let result = sqlx::query(
+        r#"INSERT INTO login_audit
+             (user_id, login_id, provider, client, success, failure_reason, ip_address, user_agent)
+           VALUES (COALESCE(?, (SELECT id FROM users WHERE login_id = ?)), ?, ?, ?, ?, ?, ?, ?)"#,
+    )
+    .bind(user_id)
+    .bind(login_id.as_deref())
+    .bind(login_id.as_deref())
+    .bind(provider)
+    .bind(client)
+    .bind(failure_reason.is_none())
+    .bind(failure_reason)
+    .bind(client_ip(req))
+    .bind(user_agent)
+    .execute(pool)
+    .await;
+    if let Err(e) = result {
+        tracing::warn!("Failed to record login attempt: {}", e);
+    }
+}
+
+/// 保存期間を過ぎた履歴を削除し、削除した件数を返す
+pub async fn purge_old_login_audit(pool: &MySqlPool) -> Result<u64, AppError> {
+    let result = sqlx::query("DELETE FROM login_audit WHERE created_at < NOW() - INTERVAL ? DAY")
+        .bind(LOGIN_AUDIT_RETENTION_DAYS)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected())
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/user/security/activity?limit=50
+/// 自分のアカウントへのログイン履歴（新しい順、失敗した試行を含む）
+#[get("/user/security/activity")]
+async fn list_login_activity(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<ActivityQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .clamp(1, MAX_ACTIVITY_LIMIT);
+
+    let rows: Vec<LoginActivityRow> = sqlx::query_as(
+        r#"SELECT id, success, provider, client, failure_reason, ip_address, user_agent, created_at
+           FROM login_audit
+           WHERE user_id = ?
+           ORDER BY created_at DESC, id DESC
+           LIMIT ?"#,
+    )
+    .bind(session_user.id)
+    .bind(limit)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let activity: Vec<LoginActivityDto> = rows.into_iter().map(LoginActivityDto::from).collect();
+    Ok(HttpResponse::Ok().json(activity))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_login_activity);
+}
@@ -1,36 +1,108 @@
+pub mod account;
+pub mod achievement;
+pub mod account_export;
 pub mod admin;
+pub mod api_key;
 pub mod auth;
+pub mod auth_token;
+pub mod challenge;
+pub mod coin;
 pub mod contact;
 pub mod daily_reward;
 pub mod dashboard;
+pub mod email_verification;
+pub mod event;
 pub mod exercise;
+pub mod exp_context;
+pub mod exp_ledger;
 pub mod gear;
+pub mod goal;
 pub mod gym;
+pub mod leaderboard;
+pub mod login_audit;
+pub mod personal_record;
 pub mod pet;
+pub mod pet_accessory;
+pub mod pet_events;
+pub mod pet_gift;
+pub mod pet_milestone;
+pub mod pet_quest;
+pub mod preferences;
+pub mod profile_image;
+pub mod report;
 pub mod streak;
 pub mod supplement;
+pub mod two_factor;
 pub mod user;
+pub mod user_session;
 pub mod workout;
+pub mod workout_export;
+pub mod workout_import;
+pub mod workout_partner;
+pub mod workout_session;
+pub mod workout_target;
+pub mod workout_comment;
 pub mod public_config;
+pub mod public_profile;
+pub mod shop;
 
 use actix_web::web;
 
+/// 現行バージョンのAPIパス
+/// バージョンなしの/api/...は現行バージョンの別名として残す
+pub const API_V1_PREFIX: &str = "/api/v1";
+
+/// APIルート（バージョンごとのスコープに登録する）
+fn configure_routes(cfg: &mut web::ServiceConfig) {
+    cfg.configure(auth::configure)
+        .configure(auth_token::configure)
+        .configure(two_factor::configure)
+        .configure(contact::configure)
+        .configure(user::configure)
+        .configure(exp_ledger::configure)
+        .configure(preferences::configure)
+        .configure(user_session::configure)
+        .configure(profile_image::configure)
+        .configure(public_profile::configure)
+        .configure(api_key::configure)
+        .configure(login_audit::configure)
+        .configure(email_verification::configure)
+        .configure(account::configure)
+        .configure(account_export::configure)
+        .configure(workout::configure)
+        .configure(workout_export::configure)
+        .configure(workout_import::configure)
+        .configure(workout_partner::configure)
+        .configure(workout_session::configure)
+        .configure(workout_target::configure)
+        .configure(goal::configure)
+        .configure(workout_comment::configure)
+        .configure(personal_record::configure)
+        .configure(dashboard::configure)
+        .configure(report::configure)
+        .configure(gym::configure)
+        .configure(exercise::configure)
+        .configure(gear::configure)
+        .configure(supplement::configure)
+        .configure(streak::configure)
+        .configure(achievement::configure)
+        .configure(challenge::configure)
+        .configure(leaderboard::configure)
+        .configure(event::configure)
+        .configure(daily_reward::configure)
+        .configure(public_config::configure)
+        .configure(pet::configure)
+        .configure(pet_accessory::configure)
+        .configure(pet_gift::configure)
+        .configure(pet_milestone::configure)
+        .configure(pet_quest::configure)
+        .configure(coin::configure)
+        .configure(shop::configure)
+        .configure(admin::configure);
+}
+
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::scope("/api")
-            .configure(auth::configure)
-            .configure(contact::configure)
-            .configure(user::configure)
-            .configure(workout::configure)
-            .configure(dashboard::configure)
-            .configure(gym::configure)
-            .configure(exercise::configure)
-            .configure(gear::configure)
-            .configure(supplement::configure)
-            .configure(streak::configure)
-            .configure(daily_reward::configure)
-            .configure(public_config::configure)
-            .configure(pet::configure)
-            .configure(admin::configure),
-    );
+    // /apiスコープは/api/v1/...にも前方一致するため、バージョン付きを先に登録する
+    cfg.service(web::scope(API_V1_PREFIX).configure(configure_routes))
+        .service(web::scope("/api").configure(configure_routes));
 }
@@ -0,0 +1,392 @@
+//! 自己ベスト（PR）APIハンドラ
+//!
+//! 種目ごとに最高重量・最高回数・推定1RM・1回の記録での総ボリュームの自己ベストを保持する。
+//! 記録の保存・編集・削除のたびに対象種目の履歴から再集計するため、
+//! 記録を削除した場合も次点の記録に戻る。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::auth::session::{get_current_user, Session};
+use crate::domain::one_rm::{estimate_one_rep_max, OneRmFormula};
+use crate::error::AppError;
+
+pub(crate) const TYPE_MAX_WEIGHT: &str = "MAX_WEIGHT";
+pub(crate) const TYPE_MAX_REPS: &str = "MAX_REPS";
+pub(crate) const TYPE_MAX_ESTIMATED_1RM: &str = "MAX_ESTIMATED_1RM";
+pub(crate) const TYPE_MAX_SESSION_VOLUME: &str = "MAX_SESSION_VOLUME";
+
+/// 記録された種目（デフォルト種目またはカスタム種目）
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ExerciseRef {
+    pub id: i64,
+    pub is_custom: bool,
+}
+
+impl ExerciseRef {
+    /// training_record_exercises上の種目IDカラム
+    pub(crate) fn column(&self) -> &'static str {
+        if self.is_custom {
+            "tre.custom_exercise_id"
+        } else {
+            "tre.exercise_id"
+        }
+    }
+}
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize, Clone)]
+pub(crate) struct PersonalRecordDto {
+    #[serde(rename = "exerciseId")]
+    exercise_id: i64,
+    #[serde(rename = "isCustom")]
+    is_custom: bool,
+    #[serde(rename = "exerciseName")]
+    exercise_name: String,
+    #[serde(rename = "recordType")]
+    record_type: String,
+    value: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reps: Option<i32>,
+    #[serde(rename = "recordId")]
+    record_id: i64,
+    #[serde(rename = "achievedOn")]
+    achieved_on: String,
+    /// 更新前の自己ベスト（保存・編集で更新した場合のみ）
+    #[serde(rename = "previousValue", skip_serializing_if = "Option::is_none")]
+    previous_value: Option<f64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PersonalRecordRow {
+    exercise_id: i64,
+    is_custom: bool,
+    exercise_name: String,
+    record_type: String,
+    best_value: f64,
+    weight: Option<f64>,
+    reps: Option<i32>,
+    record_id: i64,
+    achieved_on: NaiveDate,
+}
+
+/// 履歴から集計した自己ベスト
+struct Best {
+    value: f64,
+    weight: Option<f64>,
+    reps: Option<i32>,
+    record_id: i64,
+    record_date: NaiveDate,
+}
+
+#[derive(sqlx::FromRow)]
+struct BestSetRow {
+    weight: f64,
+    reps: i32,
+    record_id: i64,
+    record_date: NaiveDate,
+}
+
+#[derive(sqlx::FromRow)]
+struct BestVolumeRow {
+    volume: f64,
+    record_id: i64,
+    record_date: NaiveDate,
+}
+
+// ============================================
+// 集計
+// ============================================
+
+/// 記録に含まれる種目
+pub(crate) async fn exercises_in_record(
+    conn: &mut MySqlConnection,
+    record_id: i64,
+) -> Result<Vec<ExerciseRef>, AppError> {
+    let rows: Vec<(Option<i64>, Option<i64>)> = sqlx::query_as(
+        "SELECT exercise_id, custom_exercise_id FROM training_record_exercises WHERE record_id = ?",
+    )
+    .bind(record_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(
+            |(exercise_id, custom_exercise_id)| match custom_exercise_id {
+                Some(id) => Some(ExerciseRef {
+                    id,
+                    is_custom: true,
+                }),
+                None => exercise_id.map(|id| ExerciseRef {
+                    id,
+                    is_custom: false,
+                }),
+            },
+        )
+        .collect())
+}
+
+/// 1セット単位の自己ベスト（最高重量・最高回数・推定1RM）を履歴から取得
+async fn find_best_set(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    exercise: ExerciseRef,
+    record_type: &str,
+) -> Result<Option<BestSetRow>, AppError> {
+    let one_rm_order = format!("{} DESC", OneRmFormula::Epley.sql_expr());
+    let (condition, order) = match record_type {
+        TYPE_MAX_WEIGHT => ("ts.weight > 0", "ts.weight DESC, ts.reps DESC"),
+        TYPE_MAX_REPS => ("ts.reps > 0", "ts.reps DESC, ts.weight DESC"),
+        _ => ("ts.weight > 0 AND ts.reps > 0", one_rm_order.as_str()),
+    };
+
+    let row: Option<BestSetRow> = sqlx::query_as(&format!(
+        r#"SELECT ts.weight, ts.reps, tr.id AS record_id, tr.record_date
+           FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ? AND {} = ? AND {}
+           ORDER BY {}, tr.record_date ASC, ts.id ASC
+           LIMIT 1"#,
+        exercise.column(),
+        condition,
+        order
+    ))
+    .bind(user_id)
+    .bind(exercise.id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    Ok(row)
+}
+
+/// 種目の4種類の自己ベストを履歴から集計
+async fn calculate_bests(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    exercise: ExerciseRef,
+) -> Result<Vec<(&'static str, Option<Best>)>, AppError> {
+    let mut bests = Vec::new();
+
+    for record_type in [TYPE_MAX_WEIGHT, TYPE_MAX_REPS, TYPE_MAX_ESTIMATED_1RM] {
+        let best = find_best_set(conn, user_id, exercise, record_type)
+            .await?
+            .and_then(|row| {
+                let value = match record_type {
+                    TYPE_MAX_WEIGHT => Some(row.weight),
+                    TYPE_MAX_REPS => Some(row.reps as f64),
+                    _ => estimate_one_rep_max(row.weight, row.reps),
+                }?;
+                Some(Best {
+                    value,
+                    weight: Some(row.weight),
+                    reps: Some(row.reps),
+                    record_id: row.record_id,
+                    record_date: row.record_date,
+                })
+            });
+        bests.push((record_type, best));
+    }
+
+    let volume: Option<BestVolumeRow> = sqlx::query_as(&format!(
+        r#"SELECT SUM(ts.weight * ts.reps) AS volume, tr.id AS record_id, tr.record_date
+           FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ? AND {} = ? AND ts.set_type <> 'warmup'
+           GROUP BY tr.id, tr.record_date
+           HAVING volume > 0
+           ORDER BY volume DESC, tr.record_date ASC
+           LIMIT 1"#,
+        exercise.column()
+    ))
+    .bind(user_id)
+    .bind(exercise.id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    bests.push((
+        TYPE_MAX_SESSION_VOLUME,
+        volume.map(|row| Best {
+            value: row.volume,
+            weight: None,
+            reps: None,
+            record_id: row.record_id,
+            record_date: row.record_date,
+        }),
+    ));
+
+    Ok(bests)
+}
+
+/// 種目の自己ベストを履歴から再集計して保存する
+/// 以前の自己ベストを上回った記録を返す（初めての種目は比較対象がないため含めない）
+pub(crate) async fn refresh_personal_records(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    exercise: ExerciseRef,
+) -> Result<Vec<PersonalRecordDto>, AppError> {
+    let mut improved = Vec::new();
+
+    for (record_type, best) in calculate_bests(conn, user_id, exercise).await? {
+        let previous: Option<f64> = sqlx::query_scalar(
+            r#"SELECT best_value FROM personal_records
+               WHERE user_id = ? AND is_custom = ? AND exercise_id = ? AND record_type = ?
+               FOR UPDATE"#,
+        )
+        .bind(user_id)
+        .bind(exercise.is_custom)
+        .bind(exercise.id)
+        .bind(record_type)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let Some(best) = best else {
+            if previous.is_some() {
+                sqlx::query(
+                    r#"DELETE FROM personal_records
+                       WHERE user_id = ? AND is_custom = ? AND exercise_id = ? AND record_type = ?"#,
+                )
+                .bind(user_id)
+                .bind(exercise.is_custom)
+                .bind(exercise.id)
+                .bind(record_type)
+                .execute(&mut *conn)
+                .await?;
+            }
+            continue;
+        };
+
+        sqlx::query(
+            r#"INSERT INTO personal_records
+                   (user_id, exercise_id, is_custom, record_type, best_value, weight, reps,
+                    record_id, achieved_on, updated_at)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, NOW())
+               ON DUPLICATE KEY UPDATE best_value = VALUES(best_value), weight = VALUES(weight),
+                   reps = VALUES(reps), record_id = VALUES(record_id),
+                   achieved_on = VALUES(achieved_on), updated_at = NOW()"#,
+        )
+        .bind(user_id)
+        .bind(exercise.id)
+        .bind(exercise.is_custom)
+        .bind(record_type)
+        .bind(best.value)
+        .bind(best.weight)
+        .bind(best.reps)
+        .bind(best.record_id)
+        .bind(best.record_date)
+        .execute(&mut *conn)
+        .await?;
+
+        if let Some(previous) = previous.filter(|p| best.value > *p) {
+            improved.push(PersonalRecordDto {
+                exercise_id: exercise.id,
+                is_custom: exercise.is_custom,
+                exercise_name: String::new(),
+                record_type: record_type.to_string(),
+                value: best.value,
+                weight: best.weight,
+                reps: best.reps,
+                record_id: best.record_id,
+                achieved_on: best.record_date.format("%Y-%m-%d").to_string(),
+                previous_value: Some(previous),
+            });
+        }
+    }
+
+    if !improved.is_empty() {
+        let name = exercise_name(conn, exercise).await?;
+        for pr in &mut improved {
+            pr.exercise_name = name.clone();
+        }
+    }
+
+    Ok(improved)
+}
+
+async fn exercise_name(
+    conn: &mut MySqlConnection,
+    exercise: ExerciseRef,
+) -> Result<String, AppError> {
+    let sql = if exercise.is_custom {
+        "SELECT CAST(name AS CHAR) FROM user_custom_exercises WHERE id = ?"
+    } else {
+        "SELECT CAST(name AS CHAR) FROM exercises WHERE id = ?"
+    };
+    let name: Option<String> = sqlx::query_scalar(sql)
+        .bind(exercise.id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    Ok(name.unwrap_or_else(|| "Unknown".to_string()))
+}
+
+/// 自己ベストのある種目数
+pub(crate) async fn count_exercises_with_records(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<i64, AppError> {
+    let count: (i64,) = sqlx::query_as(
+        r#"SELECT COUNT(DISTINCT is_custom, exercise_id)
+           FROM personal_records WHERE user_id = ?"#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(count.0)
+}
+
+// ============================================
+// Handlers
+// ============================================
+
+/// GET /api/workout/prs
+/// 自己ベスト一覧（達成日の新しい順）
+#[get("/workout/prs")]
+async fn get_personal_records(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let rows: Vec<PersonalRecordRow> = sqlx::query_as(
+        r#"SELECT pr.exercise_id, pr.is_custom,
+                  CAST(COALESCE(e.name, uce.name, 'Unknown') AS CHAR) AS exercise_name,
+                  pr.record_type, pr.best_value, pr.weight, pr.reps, pr.record_id, pr.achieved_on
+           FROM personal_records pr
+           LEFT JOIN exercises e ON NOT pr.is_custom AND e.id = pr.exercise_id
+           LEFT JOIN user_custom_exercises uce ON pr.is_custom AND uce.id = pr.exercise_id
+           WHERE pr.user_id = ?
+           ORDER BY pr.achieved_on DESC, pr.id DESC"#,
+    )
+    .bind(session_user.id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let records: Vec<PersonalRecordDto> = rows
+        .into_iter()
+        .map(|row| PersonalRecordDto {
+            exercise_id: row.exercise_id,
+            is_custom: row.is_custom,
+            exercise_name: row.exercise_name,
+            record_type: row.record_type,
+            value: row.best_value,
+            weight: row.weight,
+            reps: row.reps,
+            record_id: row.record_id,
+            achieved_on: row.achieved_on.format("%Y-%m-%d").to_string(),
+            previous_value: None,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(records))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_personal_records);
+}
@@ -1,20 +1,32 @@
 //! ペット（トレーニングパートナー）小屋システム APIハンドラ
 
-use actix_session::Session;
 use actix_web::{delete, get, post, put, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
-
-use crate::api::streak::get_or_create_streak;
-use crate::auth::session::get_current_user;
-use crate::db::models::{Pet, PetType, UserStats, UserPetUnlock};
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::HashMap;
+
+use crate::api::achievement::{award_achievements, ACHIEVEMENT_PET_LEVEL};
+use crate::api::coin::{fetch_coin_balance, spend_coins, CoinBalanceDto, COIN_SOURCE_PET_CARE};
+use crate::api::dashboard::map_muscle_to_group;
+use crate::api::pet_accessory::{
+    compose_image_layers, fetch_equipped_accessories, EquippedAccessoryResponse,
+};
+use crate::api::pet_milestone::{
+    award_pet_milestones, fetch_achieved_milestones, has_pending_milestone,
+};
+use crate::api::shop::{consume_item, find_item_by_code, ITEM_CATEGORY_FOOD, ITEM_CATEGORY_TOY};
+use crate::api::streak::{fetch_timezone, get_or_create_streak};
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::{BarnBackground, Pet, PetEvolutionBranch, PetType, UserPetUnlock};
+use crate::domain::timezone;
 use crate::error::AppError;
 
 // ============================================
 // レスポンス型
 // ============================================
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct PetTypeResponse {
     pub id: i32,
     pub name: String,
@@ -36,9 +48,25 @@ pub struct PetTypeResponse {
     pub unlock_pet_code: Option<String>,
     #[serde(rename = "isStarter")]
     pub is_starter: Option<bool>,
+    /// 覚醒後の進化先
+    pub branches: Vec<PetBranchResponse>,
 }
 
-#[derive(Serialize)]
+/// 進化先
+#[derive(Serialize, Clone)]
+pub struct PetBranchResponse {
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// この筋肉グループを最も鍛えた場合の進化先（Noneは偏りがない場合）
+    #[serde(rename = "dominantMuscle")]
+    pub dominant_muscle: Option<String>,
+    #[serde(rename = "imagePath")]
+    pub image_path: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
 pub struct PetResponse {
     pub id: i64,
     pub name: String,
@@ -64,8 +92,18 @@ pub struct PetResponse {
     pub mood_label: String,
     #[serde(rename = "imageUrl")]
     pub image_url: Option<String>,
+    /// 装着中のアクセサリー
+    pub accessories: Vec<EquippedAccessoryResponse>,
+    /// 基本画像とアクセサリー画像を奥から順に重ねた画像レイヤー
+    #[serde(rename = "imageLayers")]
+    pub image_layers: Vec<String>,
     #[serde(rename = "isActive")]
     pub is_active: bool,
+    /// 進化先（進化済みの場合のみ）
+    pub branch: Option<PetBranchResponse>,
+    /// 進化ステージに到達し、進化先を選べる状態か
+    #[serde(rename = "canEvolve")]
+    pub can_evolve: bool,
     #[serde(rename = "createdAt")]
     pub created_at: Option<String>,
 }
@@ -89,6 +127,25 @@ pub struct BarnResponse {
     pub unlocked_types: Vec<PetTypeResponse>,
     #[serde(rename = "lockedTypes")]
     pub locked_types: Vec<LockedPetTypeResponse>,
+    /// 選択中の背景（defaultの場合はパートナーごとの背景）
+    #[serde(rename = "selectedBackground")]
+    pub selected_background: BarnBackgroundResponse,
+    pub backgrounds: Vec<BarnBackgroundResponse>,
+}
+
+/// 小屋の背景シーン
+#[derive(Serialize, Clone)]
+pub struct BarnBackgroundResponse {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    #[serde(rename = "imagePath")]
+    pub image_path: Option<String>,
+    pub unlocked: bool,
+    #[serde(rename = "unlockType")]
+    pub unlock_type: String,
+    #[serde(rename = "unlockProgress")]
+    pub unlock_progress: String,
 }
 
 #[derive(Serialize)]
@@ -111,6 +168,58 @@ pub struct LockedPetTypeResponse {
     pub unlock_progress: String,
 }
 
+/// 次ステージの情報
+#[derive(Serialize)]
+pub struct NextStageResponse {
+    pub stage: i32,
+    #[serde(rename = "stageName")]
+    pub stage_name: String,
+    #[serde(rename = "imageUrl")]
+    pub image_url: Option<String>,
+    #[serde(rename = "requiredLevel")]
+    pub required_level: i32,
+    #[serde(rename = "requiredExp")]
+    pub required_exp: i64,
+    #[serde(rename = "remainingExp")]
+    pub remaining_exp: i64,
+    #[serde(rename = "remainingLevels")]
+    pub remaining_levels: i32,
+}
+
+/// 進化プレビューレスポンス
+#[derive(Serialize)]
+pub struct EvolutionPreviewResponse {
+    #[serde(rename = "petId")]
+    pub pet_id: i64,
+    pub stage: i32,
+    #[serde(rename = "stageName")]
+    pub stage_name: String,
+    #[serde(rename = "imageUrl")]
+    pub image_url: Option<String>,
+    #[serde(rename = "backgroundImage")]
+    pub background_image: Option<String>,
+    pub level: i32,
+    #[serde(rename = "totalExp")]
+    pub total_exp: i64,
+    /// 現ステージ開始から次ステージまでの進捗（0.0〜1.0、最終ステージは1.0）
+    #[serde(rename = "stageProgress")]
+    pub stage_progress: f64,
+    #[serde(rename = "isFinalStage")]
+    pub is_final_stage: bool,
+    #[serde(rename = "nextStage")]
+    pub next_stage: Option<NextStageResponse>,
+}
+
+/// 進化の結果
+#[derive(Serialize)]
+pub struct EvolveResponse {
+    pub pet: PetResponse,
+    pub branch: PetBranchResponse,
+    /// 判定に使った最も鍛えた筋肉グループ（偏りがない場合はNone）
+    #[serde(rename = "dominantMuscle")]
+    pub dominant_muscle: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub struct CreatePetRequest {
     #[serde(rename = "petTypeId")]
@@ -123,6 +232,99 @@ pub struct UpdatePetRequest {
     pub name: Option<String>,
 }
 
+/// お世話（ごはん・あそぶ）の結果
+#[derive(Serialize)]
+pub struct PetCareResponse {
+    pub pet: PetResponse,
+    pub action: &'static str,
+    #[serde(rename = "moodBoost")]
+    pub mood_boost: i32,
+    /// 今日あと何回できるか
+    #[serde(rename = "remainingToday")]
+    pub remaining_today: i64,
+    pub coins: CoinBalanceDto,
+}
+
+/// お別れの確認（confirm=trueの場合のみ実行する）
+#[derive(Deserialize)]
+pub struct ReleasePetQuery {
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// お世話に使うアイテム（省略時はコインを消費する）
+#[derive(Deserialize)]
+pub struct PetCareQuery {
+    pub item: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateBarnBackgroundRequest {
+    /// 背景コード（未指定の場合はdefaultに戻す）
+    pub code: Option<String>,
+}
+
+// ============================================
+// お世話
+// ============================================
+
+/// お世話の種類ごとのコスト・効果・制限
+struct CareAction {
+    code: &'static str,
+    name: &'static str,
+    coin_cost: i64,
+    /// 代わりに使えるアイテムの種類
+    item_category: &'static str,
+    /// ムードの上昇量（その日のうちのみ有効）
+    mood_boost: i32,
+    daily_limit: i64,
+    /// 同じお世話を続けて行うまでの間隔（分）
+    cooldown_minutes: i64,
+}
+
+/// ムード上昇量はcalculate_moodの段階（20刻み）に合わせる
+const CARE_FEED: CareAction = CareAction {
+    code: "feed",
+    name: "ごはん",
+    coin_cost: 10,
+    item_category: ITEM_CATEGORY_FOOD,
+    mood_boost: 20,
+    daily_limit: 2,
+    cooldown_minutes: 180,
+};
+
+const CARE_PLAY: CareAction = CareAction {
+    code: "play",
+    name: "あそぶ",
+    coin_cost: 15,
+    item_category: ITEM_CATEGORY_TOY,
+    mood_boost: 20,
+    daily_limit: 1,
+    cooldown_minutes: 0,
+};
+
+/// ペットごとの今日のお世話によるムード上昇量の合計
+/// `day_start`はユーザーのタイムゾーンでの今日の開始時刻（UTC）
+async fn todays_care_boosts(
+    pool: &MySqlPool,
+    user_id: i64,
+    day_start: NaiveDateTime,
+) -> Result<HashMap<i64, i32>, AppError> {
+    let rows: Vec<(i64, i64)> = sqlx::query_as(
+        r#"SELECT pet_id, CAST(SUM(mood_boost) AS SIGNED) FROM pet_care_log
+           WHERE user_id = ? AND created_at >= ?
+           GROUP BY pet_id"#,
+    )
+    .bind(user_id)
+    .bind(day_start)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .map(|(pet_id, boost)| (pet_id, boost as i32))
+        .collect())
+}
+
 // ============================================
 // ヘルパー関数
 // ============================================
@@ -138,7 +340,30 @@ async fn get_pet_type(pool: &MySqlPool, pet_type_id: i32) -> Result<Option<PetTy
     .bind(pet_type_id)
     .fetch_optional(pool)
     .await?;
-    Ok(pet_type)
+    let Some(mut pet_type) = pet_type else {
+        return Ok(None);
+    };
+    pet_type.branches = get_evolution_branches(pool, Some(pet_type_id)).await?;
+    Ok(Some(pet_type))
+}
+
+/// 進化先を取得（pet_type_idを省略した場合は全種類）
+async fn get_evolution_branches(
+    pool: &MySqlPool,
+    pet_type_id: Option<i32>,
+) -> Result<Vec<PetEvolutionBranch>, AppError> {
+    let branches: Vec<PetEvolutionBranch> = sqlx::query_as(
+        r#"SELECT id, pet_type_id, code, name, description, dominant_muscle, image_path,
+                  display_order
+           FROM pet_evolution_branches
+           WHERE ? IS NULL OR pet_type_id = ?
+           ORDER BY display_order ASC, id ASC"#,
+    )
+    .bind(pet_type_id)
+    .bind(pet_type_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(branches)
 }
 
 /// 有効なペット種類を全て取得
@@ -153,14 +378,26 @@ async fn get_all_pet_types(pool: &MySqlPool) -> Result<Vec<PetType>, AppError> {
     )
     .fetch_all(pool)
     .await?;
-    Ok(pet_types)
+
+    let branches = get_evolution_branches(pool, None).await?;
+    Ok(pet_types
+        .into_iter()
+        .map(|mut pt| {
+            pt.branches = branches
+                .iter()
+                .filter(|b| b.pet_type_id == pt.id)
+                .cloned()
+                .collect();
+            pt
+        })
+        .collect())
 }
 
 /// ユーザーのアクティブペットを取得
 async fn find_active_pet(pool: &MySqlPool, user_id: i64) -> Result<Option<Pet>, AppError> {
     let pet: Option<Pet> = sqlx::query_as(
-        "SELECT id, user_id, pet_type_id, name, stage, mood_score, total_exp, level, is_active, created_at, updated_at 
-         FROM pets WHERE user_id = ? AND is_active = TRUE",
+        "SELECT id, user_id, pet_type_id, name, stage, mood_score, total_exp, level, is_active, branch_id, created_at, updated_at 
+         FROM pets WHERE user_id = ? AND is_active = TRUE AND released_at IS NULL",
     )
     .bind(user_id)
     .fetch_optional(pool)
@@ -171,8 +408,9 @@ async fn find_active_pet(pool: &MySqlPool, user_id: i64) -> Result<Option<Pet>,
 /// ユーザーの全ペットを取得
 async fn find_all_pets_by_user(pool: &MySqlPool, user_id: i64) -> Result<Vec<Pet>, AppError> {
     let pets: Vec<Pet> = sqlx::query_as(
-        "SELECT id, user_id, pet_type_id, name, stage, mood_score, total_exp, level, is_active, created_at, updated_at 
-         FROM pets WHERE user_id = ? ORDER BY is_active DESC, created_at ASC",
+        "SELECT id, user_id, pet_type_id, name, stage, mood_score, total_exp, level, is_active, branch_id, created_at, updated_at 
+         FROM pets WHERE user_id = ? AND released_at IS NULL
+         ORDER BY is_active DESC, created_at ASC",
     )
     .bind(user_id)
     .fetch_all(pool)
@@ -181,10 +419,10 @@ async fn find_all_pets_by_user(pool: &MySqlPool, user_id: i64) -> Result<Vec<Pet
 }
 
 /// 特定のペットを取得
-async fn find_pet_by_id(pool: &MySqlPool, pet_id: i64, user_id: i64) -> Result<Option<Pet>, AppError> {
+pub(crate) async fn find_pet_by_id(pool: &MySqlPool, pet_id: i64, user_id: i64) -> Result<Option<Pet>, AppError> {
     let pet: Option<Pet> = sqlx::query_as(
-        "SELECT id, user_id, pet_type_id, name, stage, mood_score, total_exp, level, is_active, created_at, updated_at 
-         FROM pets WHERE id = ? AND user_id = ?",
+        "SELECT id, user_id, pet_type_id, name, stage, mood_score, total_exp, level, is_active, branch_id, created_at, updated_at 
+         FROM pets WHERE id = ? AND user_id = ? AND released_at IS NULL",
     )
     .bind(pet_id)
     .bind(user_id)
@@ -224,16 +462,56 @@ async fn update_pet_state(
     Ok(())
 }
 
+/// ムードの推移を記録
+pub(crate) async fn record_mood_change(
+    pool: &MySqlPool,
+    pet_id: i64,
+    mood_score: i32,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO pet_mood_history (pet_id, mood_score, recorded_at) VALUES (?, ?, NOW())",
+    )
+    .bind(pet_id)
+    .bind(mood_score)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
 /// ステージに応じた画像URLを取得
 fn get_image_for_stage(pet_type: &PetType, stage: i32) -> Option<String> {
     match stage {
         1 => pet_type.image_egg.clone(),
         2 => pet_type.image_child.clone(),
-        3 => pet_type.image_adult.clone(),
+        // 進化先の画像はget_pet_imageで優先する
+        3 | 4 => pet_type.image_adult.clone(),
         _ => None,
     }
 }
 
+/// ペットの画像URL（進化済みで進化先の画像があればそれを使う）
+fn get_pet_image(pet_type: &PetType, stage: i32, branch_id: Option<i32>) -> Option<String> {
+    find_branch(pet_type, branch_id)
+        .filter(|_| stage >= Pet::EVOLUTION_STAGE)
+        .and_then(|b| b.image_path.clone())
+        .or_else(|| get_image_for_stage(pet_type, stage))
+}
+
+fn find_branch(pet_type: &PetType, branch_id: Option<i32>) -> Option<&PetEvolutionBranch> {
+    branch_id.and_then(|id| pet_type.branches.iter().find(|b| b.id == id))
+}
+
+fn to_branch_response(branch: &PetEvolutionBranch) -> PetBranchResponse {
+    PetBranchResponse {
+        id: branch.id,
+        code: branch.code.clone(),
+        name: branch.name.clone(),
+        description: branch.description.clone(),
+        dominant_muscle: branch.dominant_muscle.clone(),
+        image_path: branch.image_path.clone(),
+    }
+}
+
 /// PetTypeをレスポンス用に変換
 fn to_pet_type_response(pt: &PetType) -> PetTypeResponse {
     PetTypeResponse {
@@ -249,64 +527,171 @@ fn to_pet_type_response(pt: &PetType) -> PetTypeResponse {
         unlock_level: pt.unlock_level,
         unlock_pet_code: pt.unlock_pet_code.clone(),
         is_starter: pt.is_starter,
+        branches: pt.branches.iter().map(to_branch_response).collect(),
+    }
+}
+
+/// ペット情報の組み立てに使うデータ（ユーザー単位でまとめて取得し、ペットごとのクエリを避ける）
+struct PetResponseContext {
+    /// ユーザーのタイムゾーンでの今日
+    today: NaiveDate,
+    last_active_date: Option<NaiveDate>,
+    pet_types: Vec<PetType>,
+    care_boosts: HashMap<i64, i32>,
+    accessories: HashMap<i64, Vec<EquippedAccessoryResponse>>,
+    achieved_milestones: HashMap<i64, Vec<String>>,
+}
+
+impl PetResponseContext {
+    async fn load(pool: &MySqlPool, user_id: i64) -> Result<Self, AppError> {
+        let streak = get_or_create_streak(&mut *pool.acquire().await?, user_id, "training").await?;
+        let tz = fetch_timezone(pool, user_id).await?;
+        let today = timezone::today_in(tz);
+        Ok(Self {
+            today,
+            last_active_date: streak.last_active_date,
+            pet_types: get_all_pet_types(pool).await?,
+            care_boosts: todays_care_boosts(pool, user_id, timezone::day_start_utc(tz, today))
+                .await?,
+            accessories: fetch_equipped_accessories(pool, user_id).await?,
+            achieved_milestones: fetch_achieved_milestones(pool, user_id).await?,
+        })
+    }
+
+    fn pet_type(&self, pet_type_id: i32) -> Option<&PetType> {
+        self.pet_types.iter().find(|pt| pt.id == pet_type_id)
+    }
+
+    /// ムード・レベル・ステージを再計算する（ムードは今日のお世話の分を加える）
+    fn refreshed_state(&self, pet: &Pet) -> PetState {
+        let care_boost = self.care_boosts.get(&pet.id).copied().unwrap_or(0);
+        let level = Pet::calculate_level(pet.total_exp);
+        PetState {
+            level,
+            stage: Pet::calculate_stage_with_branch(level, pet.branch_id),
+            mood_score: Pet::calculate_mood_with_care(
+                self.last_active_date,
+                care_boost,
+                self.today,
+            ),
+        }
     }
+
+    /// 再計算済みの状態からレスポンスを組み立てる（DBにはアクセスしない）
+    fn compose_response(&self, pet: Pet, state: &PetState) -> PetResponse {
+        // レベル進捗計算（ペット独自EXP）
+        let current_level_exp = Pet::get_required_exp_for_level(state.level);
+        let next_level_exp = Pet::get_required_exp_for_level(state.level + 1);
+        let exp_in_current_level = pet.total_exp - current_level_exp;
+        let exp_needed = next_level_exp - current_level_exp;
+        let level_progress = if exp_needed > 0 {
+            exp_in_current_level as f64 / exp_needed as f64
+        } else {
+            1.0
+        };
+        let exp_to_next = Pet::get_exp_to_next_level(state.level);
+
+        // ペット種類情報
+        let pet_type = self.pet_type(pet.pet_type_id);
+        let image_url = pet_type.and_then(|pt| get_pet_image(pt, state.stage, pet.branch_id));
+        let pet_type_code = pet_type.map(|pt| pt.code.clone());
+        let branch = pet_type
+            .and_then(|pt| find_branch(pt, pet.branch_id))
+            .map(to_branch_response);
+        let can_evolve = state.stage >= Pet::EVOLUTION_STAGE
+            && pet.branch_id.is_none()
+            && pet_type.is_some_and(|pt| !pt.branches.is_empty());
+
+        let accessories = self.accessories.get(&pet.id).cloned().unwrap_or_default();
+        let image_layers = compose_image_layers(image_url.as_deref(), &accessories);
+
+        PetResponse {
+            id: pet.id,
+            name: pet.name,
+            pet_type_id: pet.pet_type_id,
+            pet_type_code,
+            pet_type: pet_type.map(to_pet_type_response),
+            stage: state.stage,
+            stage_name: Pet::get_stage_name(state.stage).to_string(),
+            level: state.level,
+            total_exp: pet.total_exp,
+            exp_to_next_level: exp_to_next,
+            level_progress,
+            mood_score: state.mood_score,
+            mood_label: Pet::get_mood_label(state.mood_score).to_string(),
+            image_url,
+            accessories,
+            image_layers,
+            is_active: pet.is_active,
+            branch,
+            can_evolve,
+            created_at: pet
+                .created_at
+                .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S").to_string()),
+        }
+    }
+}
+
+/// 取得済みのデータから再計算したペットの状態
+#[derive(Debug, PartialEq)]
+struct PetState {
+    level: i32,
+    stage: i32,
+    mood_score: i32,
 }
 
 /// ペット情報を取得する内部ロジック（ペット独自レベル版）
-async fn build_pet_response(
+pub(crate) async fn build_pet_response(
     pool: &MySqlPool,
     pet: Pet,
 ) -> Result<PetResponse, AppError> {
-    // UserStreak から最終アクティブ日取得
-    let streak = get_or_create_streak(pool, pet.user_id, "training").await?;
-
-    // ムード再計算（オンデマンド）
-    let new_mood = Pet::calculate_mood(streak.last_active_date);
-
-    // ペットのレベルから新ステージを計算
-    let new_level = Pet::calculate_level(pet.total_exp);
-    let new_stage = Pet::calculate_stage(new_level);
-
-    // 変更があれば更新
-    if pet.stage != new_stage || pet.mood_score != new_mood || pet.level != new_level {
-        update_pet_state(pool, pet.id, new_stage, new_mood, new_level).await?;
-    }
-
-    // レベル進捗計算（ペット独自EXP）
-    let current_level_exp = UserStats::get_required_exp_for_level(new_level);
-    let next_level_exp = UserStats::get_required_exp_for_level(new_level + 1);
-    let exp_in_current_level = pet.total_exp - current_level_exp;
-    let exp_needed = next_level_exp - current_level_exp;
-    let level_progress = if exp_needed > 0 {
-        exp_in_current_level as f64 / exp_needed as f64
-    } else {
-        1.0
-    };
-    let exp_to_next = UserStats::get_exp_to_next_level(new_level);
-
-    // ペット種類情報取得
-    let pet_type = get_pet_type(pool, pet.pet_type_id).await?;
-    let image_url = pet_type.as_ref().and_then(|pt| get_image_for_stage(pt, new_stage));
-    let pet_type_code = pet_type.as_ref().map(|pt| pt.code.clone());
-
-    Ok(PetResponse {
-        id: pet.id,
-        name: pet.name,
-        pet_type_id: pet.pet_type_id,
-        pet_type_code,
-        pet_type: pet_type.as_ref().map(to_pet_type_response),
-        stage: new_stage,
-        stage_name: Pet::get_stage_name(new_stage).to_string(),
-        level: new_level,
-        total_exp: pet.total_exp,
-        exp_to_next_level: exp_to_next,
-        level_progress,
-        mood_score: new_mood,
-        mood_label: Pet::get_mood_label(new_mood).to_string(),
-        image_url,
-        is_active: pet.is_active,
-        created_at: pet.created_at.map(|dt| dt.format("%Y-%m-%dT%H:%M:%S").to_string()),
-    })
+    let context = PetResponseContext::load(pool, pet.user_id).await?;
+    build_pet_response_with(pool, pet, &context).await
+}
+
+/// 取得済みのデータからペット情報を組み立てる（状態に変化があったペットのみ更新する）
+async fn build_pet_response_with(
+    pool: &MySqlPool,
+    pet: Pet,
+    context: &PetResponseContext,
+) -> Result<PetResponse, AppError> {
+    // 日数の節目はEXP加算がなくても達成するため、取得時にも判定する
+    let mut pet = pet;
+    let achieved = context
+        .achieved_milestones
+        .get(&pet.id)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if has_pending_milestone(&pet, achieved, context.today) {
+        let mut tx = pool.begin().await?;
+        pet.total_exp += award_pet_milestones(&mut tx, pet.id).await?;
+        tx.commit().await?;
+    }
+
+    // 変更があれば更新（取得済みのデータと一致していればクエリは発行しない）
+    let state = context.refreshed_state(&pet);
+    if pet.stage != state.stage || pet.mood_score != state.mood_score || pet.level != state.level {
+        update_pet_state(pool, pet.id, state.stage, state.mood_score, state.level).await?;
+    }
+    if pet.mood_score != state.mood_score {
+        record_mood_change(pool, pet.id, state.mood_score).await?;
+    }
+
+    Ok(context.compose_response(pet, &state))
+}
+
+/// ユーザーのペット一覧のレスポンスを組み立てる
+/// 取得済みのデータを使い、状態に変化がなければペットごとのクエリは発行しない
+async fn build_pet_responses(
+    pool: &MySqlPool,
+    pets: &[Pet],
+    context: &PetResponseContext,
+) -> Result<Vec<PetResponse>, AppError> {
+    let mut responses = Vec::with_capacity(pets.len());
+    for pet in pets {
+        responses.push(build_pet_response_with(pool, pet.clone(), context).await?);
+    }
+    Ok(responses)
 }
 
 /// 解放条件の進捗テキストを生成
@@ -333,6 +718,172 @@ fn get_unlock_progress(pt: &PetType, user_level: i32, adult_pet_codes: &[String]
     }
 }
 
+// ============================================
+// 小屋の背景
+// ============================================
+
+/// 既定の背景コード（パートナーごとの背景を表示）
+const DEFAULT_BACKGROUND_CODE: &str = "default";
+
+/// 全背景を取得
+async fn get_all_barn_backgrounds(pool: &MySqlPool) -> Result<Vec<BarnBackground>, AppError> {
+    let backgrounds: Vec<BarnBackground> = sqlx::query_as(
+        r#"SELECT id, code, name, description, image_path, unlock_type, unlock_value, display_order
+           FROM barn_backgrounds ORDER BY display_order, id"#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(backgrounds)
+}
+
+/// ユーザーの解放済み背景ID
+async fn get_unlocked_background_ids(pool: &MySqlPool, user_id: i64) -> Result<Vec<i32>, AppError> {
+    let ids: Vec<i32> = sqlx::query_scalar(
+        "SELECT background_id FROM user_barn_background_unlocks WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(ids)
+}
+
+/// ジムチェックインの累計回数とチェックインしたジムの数
+async fn get_check_in_counts(pool: &MySqlPool, user_id: i64) -> Result<(i64, i64), AppError> {
+    let counts: (i64, i64) = sqlx::query_as(
+        "SELECT COUNT(*), COUNT(DISTINCT gym_id) FROM gym_check_ins WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(counts)
+}
+
+/// 背景の解放条件を満たしているか
+fn meets_background_condition(bg: &BarnBackground, check_ins: i64, gyms: i64) -> bool {
+    match bg.unlock_type.as_str() {
+        "default" => true,
+        "check_in_count" => check_ins >= bg.unlock_value as i64,
+        "gym_count" => gyms >= bg.unlock_value as i64,
+        _ => false,
+    }
+}
+
+/// 背景の解放条件の進捗テキストを生成
+fn get_background_unlock_progress(
+    bg: &BarnBackground,
+    unlocked: bool,
+    check_ins: i64,
+    gyms: i64,
+) -> String {
+    if unlocked {
+        return "解放済み".to_string();
+    }
+    match bg.unlock_type.as_str() {
+        "check_in_count" => format!(
+            "ジムに{}回チェックインで解放 (現在{}回)",
+            bg.unlock_value, check_ins
+        ),
+        "gym_count" => format!(
+            "{}か所のジムにチェックインで解放 (現在{}か所)",
+            bg.unlock_value, gyms
+        ),
+        _ => "解放条件未設定".to_string(),
+    }
+}
+
+/// ジムチェックインの実績から背景を解放（新たに解放した背景名を返す）
+pub async fn check_and_unlock_barn_backgrounds(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Vec<String>, AppError> {
+    let (check_ins, gyms) = get_check_in_counts(pool, user_id).await?;
+    let backgrounds = get_all_barn_backgrounds(pool).await?;
+
+    let unlocked_ids = get_unlocked_background_ids(pool, user_id).await?;
+
+    let mut newly_unlocked = Vec::new();
+    for bg in &backgrounds {
+        if bg.unlock_type == "default"
+            || unlocked_ids.contains(&bg.id)
+            || !meets_background_condition(bg, check_ins, gyms)
+        {
+            continue;
+        }
+
+        sqlx::query(
+            r#"INSERT IGNORE INTO user_barn_background_unlocks (user_id, background_id, unlocked_at)
+               VALUES (?, ?, NOW())"#,
+        )
+        .bind(user_id)
+        .bind(bg.id)
+        .execute(pool)
+        .await?;
+
+        newly_unlocked.push(bg.name.clone());
+        tracing::info!("[UNLOCK] user_id={} unlocked barn background: {}", user_id, bg.code);
+    }
+
+    Ok(newly_unlocked)
+}
+
+/// 背景一覧と選択中の背景を取得（解放済みの判定は記録済みの解放状況による）
+async fn build_barn_backgrounds(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<(BarnBackgroundResponse, Vec<BarnBackgroundResponse>), AppError> {
+    let (check_ins, gyms) = get_check_in_counts(pool, user_id).await?;
+    let backgrounds = get_all_barn_backgrounds(pool).await?;
+
+    let unlocked_ids = get_unlocked_background_ids(pool, user_id).await?;
+
+    let selected_id: Option<i32> = sqlx::query_scalar(
+        "SELECT background_id FROM user_barn_settings WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    let mut selected = None;
+    let mut responses = Vec::new();
+    for bg in &backgrounds {
+        let unlocked = bg.unlock_type == "default" || unlocked_ids.contains(&bg.id);
+        let response = BarnBackgroundResponse {
+            code: bg.code.clone(),
+            name: bg.name.clone(),
+            description: bg.description.clone(),
+            image_path: bg.image_path.clone(),
+            unlocked,
+            unlock_type: bg.unlock_type.clone(),
+            unlock_progress: get_background_unlock_progress(bg, unlocked, check_ins, gyms),
+        };
+        if unlocked && selected_id == Some(bg.id) {
+            selected = Some(response.clone());
+        }
+        responses.push(response);
+    }
+
+    // 未選択・選択中の背景が削除された場合はdefault
+    let selected = selected
+        .or_else(|| {
+            responses
+                .iter()
+                .find(|bg| bg.code == DEFAULT_BACKGROUND_CODE)
+                .cloned()
+        })
+        .unwrap_or_else(|| BarnBackgroundResponse {
+            code: DEFAULT_BACKGROUND_CODE.to_string(),
+            name: "いつもの小屋".to_string(),
+            description: None,
+            image_path: None,
+            unlocked: true,
+            unlock_type: "default".to_string(),
+            unlock_progress: "解放済み".to_string(),
+        });
+
+    Ok((selected, responses))
+}
+
 // ============================================
 // API Handlers
 // ============================================
@@ -349,7 +900,7 @@ pub async fn get_pet_types(
 }
 
 /// GET /api/pet
-/// アクティブペット情報を取得（旧API互換）
+/// アクティブペット情報を取得（旧API互換、非推奨: GET /api/pet/barnへ移行）
 #[get("/pet")]
 pub async fn get_pet(
     pool: web::Data<MySqlPool>,
@@ -398,42 +949,22 @@ pub async fn get_barn(
     // 全ペット取得
     let pets = find_all_pets_by_user(pool.get_ref(), user_id).await?;
     
-    // アクティブペットを探す
-    let active_pet = pets.iter().find(|p| p.is_active);
-    let active_pet_response = match active_pet {
-        Some(p) => Some(build_pet_response(pool.get_ref(), p.clone()).await?),
-        None => None,
-    };
+    // 所持ペット一覧（ペット種類・ムード・装着品などはまとめて取得する）
+    let context = PetResponseContext::load(pool.get_ref(), user_id).await?;
+    let owned_pets = build_pet_responses(pool.get_ref(), &pets, &context).await?;
 
-    // 所持ペット一覧
-    let mut owned_pets = Vec::new();
-    for p in &pets {
-        owned_pets.push(build_pet_response(pool.get_ref(), p.clone()).await?);
-    }
+    // アクティブペット
+    let active_pet_response = owned_pets.iter().find(|p| p.is_active).cloned();
 
-    // 成熟済みペットのコード一覧（解放条件判定用）
-    let adult_pet_codes: Vec<String> = pets
+    // 成熟済みペットのコード
+    let adult_codes: Vec<String> = owned_pets
         .iter()
-        .filter(|p| p.stage >= 3 || Pet::calculate_stage(Pet::calculate_level(p.total_exp)) >= 3)
-        .filter_map(|p| {
-            // pet_type_idからcodeを取得する必要があるが、ここでは別途処理
-            None::<String>
-        })
+        .filter(|p| p.stage >= 3)
+        .filter_map(|p| p.pet_type_code.clone())
         .collect();
-    
-    // 成熟済みペットのコードを実際に取得
-    let mut adult_codes: Vec<String> = Vec::new();
-    for p in &pets {
-        let level = Pet::calculate_level(p.total_exp);
-        if Pet::calculate_stage(level) >= 3 {
-            if let Some(pt) = get_pet_type(pool.get_ref(), p.pet_type_id).await? {
-                adult_codes.push(pt.code);
-            }
-        }
-    }
 
-    // 全ペット種類取得
-    let all_types = get_all_pet_types(pool.get_ref()).await?;
+    // 全ペット種類
+    let all_types = &context.pet_types;
     
     // ユーザーの解放済みペット種類ID
     let unlocks = get_user_unlocks(pool.get_ref(), user_id).await?;
@@ -446,7 +977,7 @@ pub async fn get_barn(
     let mut unlocked_types = Vec::new();
     let mut locked_types = Vec::new();
 
-    for pt in &all_types {
+    for pt in all_types {
         let is_unlocked = unlocked_type_ids.contains(&pt.id) 
             || pt.is_starter.unwrap_or(false)
             || pt.unlock_type.as_deref() == Some("default");
@@ -474,14 +1005,79 @@ pub async fn get_barn(
         }
     }
 
+    // 背景（導入前のチェックイン実績もここで解放する）
+    check_and_unlock_barn_backgrounds(pool.get_ref(), user_id).await?;
+    let (selected_background, backgrounds) =
+        build_barn_backgrounds(pool.get_ref(), user_id).await?;
+
     Ok(HttpResponse::Ok().json(BarnResponse {
         active_pet: active_pet_response,
         owned_pets,
         unlocked_types,
         locked_types,
+        selected_background,
+        backgrounds,
     }))
 }
 
+/// PUT /api/pet/barn/background
+/// 小屋の背景を変更（解放済みの背景のみ）
+#[put("/pet/barn/background")]
+pub async fn update_barn_background(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<UpdateBarnBackgroundRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+
+    let code = body
+        .code
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .unwrap_or(DEFAULT_BACKGROUND_CODE);
+
+    let background = get_all_barn_backgrounds(pool.get_ref())
+        .await?
+        .into_iter()
+        .find(|bg| bg.code == code)
+        .ok_or_else(|| AppError::NotFound("背景が見つかりません".to_string()))?;
+
+    if background.unlock_type != "default" {
+        let unlocked: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM user_barn_background_unlocks WHERE user_id = ? AND background_id = ?",
+        )
+        .bind(user_id)
+        .bind(background.id)
+        .fetch_optional(pool.get_ref())
+        .await?;
+        if unlocked.is_none() {
+            return Err(AppError::BadRequest(
+                "この背景はまだ解放されていません".to_string(),
+            ));
+        }
+    }
+
+    sqlx::query(
+        r#"INSERT INTO user_barn_settings (user_id, background_id, updated_at)
+           VALUES (?, ?, NOW())
+           ON DUPLICATE KEY UPDATE background_id = VALUES(background_id), updated_at = NOW()"#,
+    )
+    .bind(user_id)
+    .bind(background.id)
+    .execute(pool.get_ref())
+    .await?;
+
+    let (selected_background, backgrounds) =
+        build_barn_backgrounds(pool.get_ref(), user_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "selectedBackground": selected_background,
+        "backgrounds": backgrounds,
+    })))
+}
+
 /// POST /api/pet
 /// ペットを作成（新しい卵を入手）
 #[post("/pet")]
@@ -566,7 +1162,7 @@ pub async fn activate_pet(
     let pet_id = path.into_inner();
 
     // 対象ペットが存在するか確認
-    let pet = find_pet_by_id(pool.get_ref(), pet_id, user_id).await?
+    find_pet_by_id(pool.get_ref(), pet_id, user_id).await?
         .ok_or_else(|| AppError::BadRequest("パートナーが見つかりません".to_string()))?;
 
     // 全ペットのis_activeをFALSEに
@@ -594,6 +1190,183 @@ pub async fn activate_pet(
     }))
 }
 
+/// GET /api/pet/{id}/evolution-preview
+/// 次ステージの名前・画像・必要レベル・残りEXPを返す（進化演出・進捗画面用）
+#[get("/pet/{id}/evolution-preview")]
+pub async fn get_evolution_preview(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let pet_id = path.into_inner();
+
+    let pet = find_pet_by_id(pool.get_ref(), pet_id, session_user.id).await?
+        .ok_or_else(|| AppError::BadRequest("パートナーが見つかりません".to_string()))?;
+    let pet_type = get_pet_type(pool.get_ref(), pet.pet_type_id).await?;
+
+    let level = Pet::calculate_level(pet.total_exp);
+    let stage = Pet::calculate_stage_with_branch(level, pet.branch_id);
+
+    let next_stage = (stage < Pet::MAX_STAGE).then(|| {
+        let next = stage + 1;
+        let required_level = Pet::get_stage_start_level(next);
+        let required_exp = Pet::get_required_exp_for_level(required_level);
+        NextStageResponse {
+            stage: next,
+            stage_name: Pet::get_stage_name(next).to_string(),
+            image_url: pet_type.as_ref().and_then(|pt| get_image_for_stage(pt, next)),
+            required_level,
+            required_exp,
+            remaining_exp: (required_exp - pet.total_exp).max(0),
+            remaining_levels: (required_level - level).max(0),
+        }
+    });
+
+    let stage_progress = match &next_stage {
+        Some(next) => {
+            let stage_start_exp =
+                Pet::get_required_exp_for_level(Pet::get_stage_start_level(stage));
+            let span = next.required_exp - stage_start_exp;
+            if span > 0 {
+                ((pet.total_exp - stage_start_exp) as f64 / span as f64).clamp(0.0, 1.0)
+            } else {
+                1.0
+            }
+        }
+        None => 1.0,
+    };
+
+    Ok(HttpResponse::Ok().json(EvolutionPreviewResponse {
+        pet_id: pet.id,
+        stage,
+        stage_name: Pet::get_stage_name(stage).to_string(),
+        image_url: pet_type.as_ref().and_then(|pt| get_pet_image(pt, stage, pet.branch_id)),
+        background_image: pet_type.as_ref().and_then(|pt| pt.background_image.clone()),
+        level,
+        total_exp: pet.total_exp,
+        stage_progress,
+        is_final_stage: next_stage.is_none(),
+        next_stage,
+    }))
+}
+
+/// 最も鍛えた筋肉グループとみなすボリュームの割合
+const DOMINANT_MUSCLE_SHARE: f64 = 0.35;
+
+/// ペットを迎えてからのボリューム（重量×回数）が最も多い筋肉グループ
+/// 全体に占める割合がDOMINANT_MUSCLE_SHAREに満たない場合は偏りなしとしてNone
+async fn dominant_muscle_since(
+    pool: &MySqlPool,
+    user_id: i64,
+    since: Option<chrono::NaiveDateTime>,
+) -> Result<Option<&'static str>, AppError> {
+    let rows: Vec<(Option<String>, Option<f64>)> = sqlx::query_as(
+        r#"SELECT CAST(COALESCE(e.muscle, uce.muscle) AS CHAR) AS muscle,
+                  SUM(ts.weight * ts.reps) AS volume
+           FROM training_records tr
+           INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+           INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+           LEFT JOIN exercises e ON e.id = tre.exercise_id
+           LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+           WHERE tr.user_id = ? AND (? IS NULL OR tr.record_date >= DATE(?))
+             AND ts.set_type <> 'warmup'
+           GROUP BY muscle"#,
+    )
+    .bind(user_id)
+    .bind(since)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    let mut volume_by_group: std::collections::HashMap<&'static str, f64> =
+        std::collections::HashMap::new();
+    for (muscle, volume) in rows {
+        if let Some(group) = muscle.as_deref().and_then(map_muscle_to_group) {
+            *volume_by_group.entry(group).or_default() += volume.unwrap_or(0.0);
+        }
+    }
+
+    let total: f64 = volume_by_group.values().sum();
+    let top = volume_by_group
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+    Ok(top
+        .filter(|(_, volume)| total > 0.0 && volume / total >= DOMINANT_MUSCLE_SHARE)
+        .map(|(group, _)| group))
+}
+
+/// POST /api/pet/{id}/evolve
+/// 進化ステージに到達したペットを、最も鍛えた筋肉グループに応じた進化先へ進化させる
+#[post("/pet/{id}/evolve")]
+pub async fn evolve_pet(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+    let pet_id = path.into_inner();
+
+    let pet = find_pet_by_id(pool.get_ref(), pet_id, user_id).await?
+        .ok_or_else(|| AppError::BadRequest("パートナーが見つかりません".to_string()))?;
+
+    if pet.branch_id.is_some() {
+        return Err(AppError::BadRequest("このパートナーは既に進化しています".to_string()));
+    }
+    let level = Pet::calculate_level(pet.total_exp);
+    if Pet::calculate_stage(level) < Pet::EVOLUTION_STAGE {
+        return Err(AppError::BadRequest(format!(
+            "進化できるのはレベル{}からです",
+            Pet::get_stage_start_level(Pet::EVOLUTION_STAGE)
+        )));
+    }
+
+    let branches = get_evolution_branches(pool.get_ref(), Some(pet.pet_type_id)).await?;
+    let dominant_muscle = dominant_muscle_since(pool.get_ref(), user_id, pet.created_at).await?;
+
+    // 最も鍛えた部位の進化先、なければ偏りなしの進化先、それもなければ最初の進化先
+    let branch = dominant_muscle
+        .and_then(|muscle| {
+            branches
+                .iter()
+                .find(|b| b.dominant_muscle.as_deref() == Some(muscle))
+        })
+        .or_else(|| branches.iter().find(|b| b.dominant_muscle.is_none()))
+        .or_else(|| branches.first())
+        .ok_or_else(|| {
+            AppError::BadRequest("このパートナーの進化先はまだありません".to_string())
+        })?;
+
+    // 同時に実行されても進化先が上書きされないよう、未進化の場合のみ更新する
+    let result = sqlx::query(
+        "UPDATE pets SET branch_id = ?, updated_at = NOW() WHERE id = ? AND branch_id IS NULL",
+    )
+    .bind(branch.id)
+    .bind(pet.id)
+    .execute(pool.get_ref())
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::BadRequest("このパートナーは既に進化しています".to_string()));
+    }
+
+    tracing::info!(
+        "[POST /pet/evolve] user_id={} pet_id={} branch={}",
+        user_id,
+        pet.id,
+        branch.code
+    );
+
+    let evolved = find_pet_by_id(pool.get_ref(), pet_id, user_id).await?
+        .ok_or_else(|| AppError::InternalError("ペットの取得に失敗しました".to_string()))?;
+    let response = build_pet_response(pool.get_ref(), evolved).await?;
+    Ok(HttpResponse::Ok().json(EvolveResponse {
+        pet: response,
+        branch: to_branch_response(branch),
+        dominant_muscle: dominant_muscle.map(str::to_string),
+    }))
+}
+
 /// PUT /api/pet/{id}
 /// ペット情報を更新（名前変更など）
 #[put("/pet/{id}")]
@@ -636,7 +1409,7 @@ pub async fn update_pet(
     }))
 }
 
-/// PUT /api/pet (旧API互換 - アクティブペットの名前変更)
+/// PUT /api/pet (旧API互換 - アクティブペットの名前変更、非推奨: PUT /api/pet/{id}へ移行)
 #[put("/pet")]
 pub async fn update_active_pet(
     pool: web::Data<MySqlPool>,
@@ -703,19 +1476,254 @@ pub async fn deactivate_pet(
     }))
 }
 
+/// DELETE /api/pet/{id}?confirm=true
+/// ペットとお別れする（唯一のペットは不可）
+/// EXPの履歴を追えるよう削除はせず、お別れ日時を記録して一覧から外す
+#[delete("/pet/{id}")]
+pub async fn release_pet(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    query: web::Query<ReleasePetQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+    let pet_id = path.into_inner();
+
+    if !query.confirm {
+        return Err(AppError::BadRequest(
+            "お別れするにはconfirm=trueを指定してください".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    // 同時に実行されて所持ペットがいなくならないよう、所持中のペットをまとめてロックする
+    let owned_ids: Vec<i64> = sqlx::query_scalar(
+        "SELECT id FROM pets WHERE user_id = ? AND released_at IS NULL FOR UPDATE",
+    )
+    .bind(user_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    if !owned_ids.contains(&pet_id) {
+        return Err(AppError::BadRequest("パートナーが見つかりません".to_string()));
+    }
+    if owned_ids.len() <= 1 {
+        return Err(AppError::BadRequest(
+            "最後のパートナーとはお別れできません".to_string(),
+        ));
+    }
+
+    sqlx::query(
+        "UPDATE pets SET is_active = FALSE, released_at = NOW(), updated_at = NOW() WHERE id = ?",
+    )
+    .bind(pet_id)
+    .execute(&mut *tx)
+    .await?;
+
+    // 装着中のアクセサリーは外して他のパートナーが使えるようにする
+    sqlx::query(
+        "UPDATE user_pet_accessories SET pet_id = NULL, updated_at = NOW() WHERE pet_id = ?",
+    )
+    .bind(pet_id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!("[DELETE /pet/{}] user_id={} released", pet_id, user_id);
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "releasedPetId": pet_id,
+    })))
+}
+
+/// お世話を行う（コインまたはアイテムを消費してムードを上げる）
+async fn care_for_pet(
+    pool: &MySqlPool,
+    user_id: i64,
+    pet_id: i64,
+    action: &CareAction,
+    item_code: Option<&str>,
+) -> Result<PetCareResponse, AppError> {
+    // 回数制限はユーザーのタイムゾーンでの今日の分で数える
+    let tz = fetch_timezone(pool, user_id).await?;
+    let day_start = timezone::day_start_utc(tz, timezone::today_in(tz));
+
+    let mut tx = pool.begin().await?;
+
+    // 同時に実行されても回数制限を超えないようペットをロックする
+    let locked: Option<i64> =
+        sqlx::query_scalar(
+            "SELECT id FROM pets WHERE id = ? AND user_id = ? AND released_at IS NULL FOR UPDATE",
+        )
+        .bind(pet_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+    if locked.is_none() {
+        return Err(AppError::BadRequest("パートナーが見つかりません".to_string()));
+    }
+
+    let (count_today, minutes_since_last): (i64, Option<i64>) = sqlx::query_as(
+        r#"SELECT COUNT(*), CAST(TIMESTAMPDIFF(MINUTE, MAX(created_at), NOW()) AS SIGNED)
+           FROM pet_care_log
+           WHERE pet_id = ? AND action = ? AND created_at >= ?"#,
+    )
+    .bind(pet_id)
+    .bind(action.code)
+    .bind(day_start)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if count_today >= action.daily_limit {
+        return Err(AppError::BadRequest(format!(
+            "今日の{}はもう済んでいます（1日{}回まで）",
+            action.name, action.daily_limit
+        )));
+    }
+    if let Some(minutes) = minutes_since_last {
+        if minutes < action.cooldown_minutes {
+            return Err(AppError::BadRequest(format!(
+                "次の{}まであと{}分お待ちください",
+                action.name,
+                action.cooldown_minutes - minutes
+            )));
+        }
+    }
+
+    // アイテムを指定した場合はコインの代わりに所持アイテムを1つ消費する
+    let (item_id, coins_spent, mood_boost, coins) = match item_code {
+        Some(code) => {
+            let item = find_item_by_code(&mut tx, code)
+                .await?
+                .filter(|item| item.category == action.item_category)
+                .ok_or_else(|| {
+                    AppError::BadRequest(format!("{}に使えないアイテムです", action.name))
+                })?;
+            if !consume_item(&mut tx, user_id, item.id, 1).await? {
+                return Err(AppError::BadRequest(format!(
+                    "{}を持っていません",
+                    item.name
+                )));
+            }
+            let coins = fetch_coin_balance(&mut tx, user_id).await?;
+            let boost = item.mood_boost.unwrap_or(action.mood_boost);
+            (Some(item.id), 0, boost, coins)
+        }
+        None => {
+            let coins = spend_coins(
+                &mut tx,
+                user_id,
+                action.coin_cost,
+                COIN_SOURCE_PET_CARE,
+                Some(pet_id),
+            )
+            .await?;
+            (None, action.coin_cost, action.mood_boost, coins)
+        }
+    };
+
+    sqlx::query(
+        r#"INSERT INTO pet_care_log
+               (user_id, pet_id, action, item_id, coins_spent, mood_boost, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, NOW())"#,
+    )
+    .bind(user_id)
+    .bind(pet_id)
+    .bind(action.code)
+    .bind(item_id)
+    .bind(coins_spent)
+    .bind(mood_boost)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let pet = find_pet_by_id(pool, pet_id, user_id)
+        .await?
+        .ok_or_else(|| AppError::InternalError("ペットの取得に失敗しました".to_string()))?;
+    let pet = build_pet_response(pool, pet).await?;
+
+    Ok(PetCareResponse {
+        pet,
+        action: action.code,
+        mood_boost,
+        remaining_today: action.daily_limit - count_today - 1,
+        coins,
+    })
+}
+
+/// POST /api/pet/{id}/feed?item=
+/// ごはんをあげる（コインまたはごはんアイテムを消費してムードを上げる）
+#[post("/pet/{id}/feed")]
+pub async fn feed_pet(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    query: web::Query<PetCareQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let response = care_for_pet(
+        pool.get_ref(),
+        session_user.id,
+        path.into_inner(),
+        &CARE_FEED,
+        query.item.as_deref(),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST /api/pet/{id}/play?item=
+/// 一緒にあそぶ（コインまたはおもちゃアイテムを消費してムードを上げる）
+#[post("/pet/{id}/play")]
+pub async fn play_with_pet(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    query: web::Query<PetCareQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let response = care_for_pet(
+        pool.get_ref(),
+        session_user.id,
+        path.into_inner(),
+        &CARE_PLAY,
+        query.item.as_deref(),
+    )
+    .await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// ペットへの経験値付与結果
+#[allow(dead_code)]
+pub struct PetExpGain {
+    pub pet_id: i64,
+    pub level: i32,
+    pub level_up: bool,
+    pub matured: bool,
+}
+
 /// アクティブペットに経験値を付与し、レベルアップを処理する
-/// 戻り値: (新レベル, レベルアップしたか, 成熟したか)
 pub async fn add_exp_to_active_pet(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     user_id: i64,
     exp_amount: i64,
-) -> Result<Option<(i32, bool, bool)>, AppError> {
+) -> Result<Option<PetExpGain>, AppError> {
     if exp_amount <= 0 {
         return Ok(None);
     }
 
     // アクティブペット取得
-    let pet = find_active_pet(pool, user_id).await?;
+    let pet: Option<Pet> = sqlx::query_as(
+        "SELECT id, user_id, pet_type_id, name, stage, mood_score, total_exp, level, is_active, branch_id, created_at, updated_at 
+         FROM pets WHERE user_id = ? AND is_active = TRUE FOR UPDATE",
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
     let pet = match pet {
         Some(p) => p,
         None => return Ok(None), // アクティブペットがいない場合はスキップ
@@ -725,11 +1733,8 @@ pub async fn add_exp_to_active_pet(
     let new_total_exp = pet.total_exp + exp_amount;
     let old_level = Pet::calculate_level(pet.total_exp);
     let new_level = Pet::calculate_level(new_total_exp);
-    let old_stage = Pet::calculate_stage(old_level);
-    let new_stage = Pet::calculate_stage(new_level);
-    
-    let level_up = new_level > old_level;
-    let matured = new_stage >= 3 && old_stage < 3; // 成熟期に到達
+    let old_stage = Pet::calculate_stage_with_branch(old_level, pet.branch_id);
+    let new_stage = Pet::calculate_stage_with_branch(new_level, pet.branch_id);
 
     // ペットを更新
     sqlx::query(
@@ -739,15 +1744,164 @@ pub async fn add_exp_to_active_pet(
     .bind(new_level)
     .bind(new_stage)
     .bind(pet.id)
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
+    // 節目に到達した場合はボーナスEXPが加わる
+    let bonus_exp = award_pet_milestones(conn, pet.id).await?;
+    let new_level = Pet::calculate_level(new_total_exp + bonus_exp);
+    let new_stage = Pet::calculate_stage_with_branch(new_level, pet.branch_id);
+
+    let level_up = new_level > old_level;
+    let matured = new_stage >= 3 && old_stage < 3; // 成熟期に到達
+
+    // パートナーのレベルの実績（達成は実績一覧で確認する）
+    if level_up {
+        award_achievements(conn, user_id, &[ACHIEVEMENT_PET_LEVEL]).await?;
+    }
+
     tracing::debug!(
         "[PET EXP] user_id={} pet_id={} +{} exp, level {} -> {}, stage {} -> {}",
         user_id, pet.id, exp_amount, old_level, new_level, old_stage, new_stage
     );
 
-    Ok(Some((new_level, level_up, matured)))
+    Ok(Some(PetExpGain {
+        pet_id: pet.id,
+        level: new_level,
+        level_up,
+        matured,
+    }))
+}
+
+/// トレーニング記録でペットに付与した経験値を記録する
+pub async fn record_pet_exp_for_record(
+    conn: &mut MySqlConnection,
+    record_id: i64,
+    pet_id: i64,
+    exp_amount: i64,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO training_record_pet_exp (record_id, pet_id, exp_amount, created_at) VALUES (?, ?, ?, NOW())",
+    )
+    .bind(record_id)
+    .bind(pet_id)
+    .bind(exp_amount)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// 記録削除時に、その記録でEXPを得たペットから差し引く
+/// 付与履歴のない古い記録や、既に存在しないペットはスキップする
+pub async fn deduct_record_exp_from_pets(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    record_id: i64,
+) -> Result<(), AppError> {
+    let grants: Vec<(i64, i64)> = sqlx::query_as(
+        r#"SELECT pet_id, CAST(SUM(exp_amount) AS SIGNED)
+           FROM training_record_pet_exp
+           WHERE record_id = ?
+           GROUP BY pet_id"#,
+    )
+    .bind(record_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for (pet_id, exp_amount) in grants {
+        let pet: Option<(i64, Option<i32>)> = sqlx::query_as(
+            "SELECT total_exp, branch_id FROM pets WHERE id = ? AND user_id = ? FOR UPDATE",
+        )
+        .bind(pet_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+        let Some((pet_exp, branch_id)) = pet else {
+            continue;
+        };
+
+        let new_total = std::cmp::max(0, pet_exp - exp_amount);
+        let new_level = Pet::calculate_level(new_total);
+        let new_stage = Pet::calculate_stage_with_branch(new_level, branch_id);
+
+        sqlx::query(
+            "UPDATE pets SET total_exp = ?, level = ?, stage = ?, updated_at = NOW() WHERE id = ?",
+        )
+        .bind(new_total)
+        .bind(new_level)
+        .bind(new_stage)
+        .bind(pet_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM training_record_pet_exp WHERE record_id = ?")
+        .bind(record_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// 記録の編集でEXPが減った分を、その記録でEXPを得たペットから差し引く
+/// 直近に付与したペットから順に、付与済みの量を上限として差し引き、付与履歴にマイナスで残す
+pub async fn deduct_pet_exp_for_record(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    record_id: i64,
+    exp_amount: i64,
+) -> Result<(), AppError> {
+    let grants: Vec<(i64, i64)> = sqlx::query_as(
+        r#"SELECT pet_id, CAST(SUM(exp_amount) AS SIGNED)
+           FROM training_record_pet_exp
+           WHERE record_id = ?
+           GROUP BY pet_id
+           ORDER BY MAX(id) DESC"#,
+    )
+    .bind(record_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut remaining = exp_amount;
+    for (pet_id, granted) in grants {
+        if remaining <= 0 {
+            break;
+        }
+        let deduct = std::cmp::min(remaining, granted);
+        if deduct <= 0 {
+            continue;
+        }
+
+        let pet: Option<(i64, Option<i32>)> = sqlx::query_as(
+            "SELECT total_exp, branch_id FROM pets WHERE id = ? AND user_id = ? FOR UPDATE",
+        )
+        .bind(pet_id)
+        .bind(user_id)
+        .fetch_optional(&mut *conn)
+        .await?;
+        let Some((pet_exp, branch_id)) = pet else {
+            continue;
+        };
+
+        let new_total = std::cmp::max(0, pet_exp - deduct);
+        let new_level = Pet::calculate_level(new_total);
+        let new_stage = Pet::calculate_stage_with_branch(new_level, branch_id);
+
+        sqlx::query(
+            "UPDATE pets SET total_exp = ?, level = ?, stage = ?, updated_at = NOW() WHERE id = ?",
+        )
+        .bind(new_total)
+        .bind(new_level)
+        .bind(new_stage)
+        .bind(pet_id)
+        .execute(&mut *conn)
+        .await?;
+
+        record_pet_exp_for_record(conn, record_id, pet_id, -deduct).await?;
+        remaining -= deduct;
+    }
+
+    Ok(())
 }
 
 /// POST /api/pet/unlock-check
@@ -772,13 +1926,18 @@ pub async fn check_and_unlock_pet_types(
     let mut adult_codes: Vec<String> = Vec::new();
     for p in &pets {
         let level = Pet::calculate_level(p.total_exp);
-        if Pet::calculate_stage(level) >= 3 {
+        if Pet::calculate_stage_with_branch(level, p.branch_id) >= 3 {
             if let Some(pt) = get_pet_type(pool, p.pet_type_id).await? {
                 adult_codes.push(pt.code);
             }
         }
     }
 
+    // 開催中のイベントで解放されるペット
+    let event_pet_type_id = crate::api::event::find_current_event(pool)
+        .await?
+        .and_then(|e| e.special_pet_type_id);
+
     // 既存の解放済み
     let unlocks = get_user_unlocks(pool, user_id).await?;
     let unlocked_ids: Vec<i32> = unlocks.iter().map(|u| u.pet_type_id).collect();
@@ -800,6 +1959,7 @@ pub async fn check_and_unlock_pet_types(
                 let required_code = pt.unlock_pet_code.as_deref().unwrap_or("");
                 adult_codes.contains(&required_code.to_string())
             }
+            "event" => event_pet_type_id == Some(pt.id),
             "default" => true,
             _ => false,
         };
@@ -826,9 +1986,261 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_pet_types)
         .service(get_pet)
         .service(get_barn)
+        .service(update_barn_background)
+        .service(get_evolution_preview)
         .service(create_pet)
         .service(activate_pet)
         .service(update_pet)
         .service(update_active_pet)
-        .service(deactivate_pet);
+        .service(deactivate_pet)
+        .service(release_pet)
+        .service(evolve_pet)
+        .service(feed_pet)
+        .service(play_with_pet);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+    fn pet_type(id: i32, code: &str, branches: Vec<PetEvolutionBranch>) -> PetType {
+        PetType {
+            id,
+            name: code.to_string(),
+            code: code.to_string(),
+            description: None,
+            image_egg: Some(format!("/pets/{}/egg.png", code)),
+            image_child: Some(format!("/pets/{}/child.png", code)),
+            image_adult: Some(format!("/pets/{}/adult.png", code)),
+            background_image: None,
+            display_order: Some(id),
+            is_active: Some(true),
+            unlock_type: Some("default".to_string()),
+            unlock_level: None,
+            unlock_pet_code: None,
+            is_starter: Some(true),
+            created_at: None,
+            updated_at: None,
+            branches,
+        }
+    }
+
+    fn branch(id: i32, pet_type_id: i32) -> PetEvolutionBranch {
+        PetEvolutionBranch {
+            id,
+            pet_type_id,
+            code: format!("branch_{}", id),
+            name: format!("Branch {}", id),
+            description: None,
+            dominant_muscle: Some("chest".to_string()),
+            image_path: Some(format!("/pets/branches/{}.png", id)),
+            display_order: 0,
+        }
+    }
+
+    /// 保存済みの状態が再計算結果と一致しているペット
+    fn pet(id: i64, pet_type_id: i32, total_exp: i64, branch_id: Option<i32>) -> Pet {
+        let level = Pet::calculate_level(total_exp);
+        Pet {
+            id,
+            user_id: 1,
+            pet_type_id,
+            name: format!("Pet {}", id),
+            stage: Pet::calculate_stage_with_branch(level, branch_id),
+            mood_score: 60,
+            total_exp,
+            level,
+            is_active: id == 1,
+            branch_id,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    /// 3日前に最後にトレーニングしたユーザーの小屋（ムードは60）
+    fn context() -> PetResponseContext {
+        let today = NaiveDate::from_ymd_opt(2026, 6, 1).unwrap();
+        PetResponseContext {
+            today,
+            last_active_date: Some(today - Duration::days(3)),
+            pet_types: vec![
+                pet_type(10, "dog", Vec::new()),
+                pet_type(20, "cat", vec![branch(200, 20)]),
+            ],
+            care_boosts: HashMap::from([(2, 15)]),
+            accessories: HashMap::from([(
+                1,
+                vec![EquippedAccessoryResponse {
+                    code: "cap".to_string(),
+                    name: "Cap".to_string(),
+                    slot: "head".to_string(),
+                    image_path: Some("/accessories/cap.png".to_string()),
+                }],
+            )]),
+            achieved_milestones: HashMap::new(),
+        }
+    }
+
+    /// sqlxが実行したクエリの数を数える（sqlx::queryのログイベントを数える）
+    struct QueryCounter(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for QueryCounter {
+        fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            if event.metadata().target() == "sqlx::query" {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// 状態が更新済みのペット一覧を組み立てるのにかかったクエリ数（get_barnと同じ手順）
+    async fn count_barn_queries(pool: &MySqlPool, user_id: i64) -> usize {
+        let pets = find_all_pets_by_user(pool, user_id).await.unwrap();
+        // ムード・節目の更新を済ませておく
+        let context = PetResponseContext::load(pool, user_id).await.unwrap();
+        build_pet_responses(pool, &pets, &context).await.unwrap();
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let _guard = tracing::subscriber::set_default(
+            tracing_subscriber::registry().with(QueryCounter(count.clone())),
+        );
+        let context = PetResponseContext::load(pool, user_id).await.unwrap();
+        let responses = build_pet_responses(pool, &pets, &context).await.unwrap();
+        assert_eq!(responses.len(), pets.len());
+        count.load(Ordering::Relaxed)
+    }
+
+    /// 小屋のペット一覧はペットの数に関わらず同じ数のクエリで組み立てる（N+1にならない）
+    /// 本番と同じスキーマのテスト用DBが必要なため、TEST_DATABASE_URLが未設定の場合はスキップ
+    #[tokio::test]
+    async fn barn_query_count_does_not_grow_with_pets() {
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL is not set; skipping barn query count test");
+            return;
+        };
+        let pool = MySqlPool::connect(&url)
+            .await
+            .expect("Failed to connect to test database");
+        crate::db::pool::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        let pet_type_ids: Vec<i32> =
+            sqlx::query_scalar("SELECT id FROM pet_types ORDER BY id LIMIT 5")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        if pet_type_ids.len() < 3 {
+            eprintln!("test database has fewer than 3 pet types; skipping barn query count test");
+            return;
+        }
+
+        let user_id = sqlx::query(
+            r#"INSERT INTO users (login_id, display_name, oauth_provider, role, created_at, updated_at)
+               VALUES (?, 'barn query test', 'LOCAL', 'USER', NOW(), NOW())"#,
+        )
+        .bind(format!("barn_test_{}", uuid::Uuid::new_v4().simple()))
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_id() as i64;
+
+        let mut counts = Vec::new();
+        for (i, pet_type_id) in pet_type_ids.iter().enumerate() {
+            sqlx::query(
+                r#"INSERT INTO pets (user_id, pet_type_id, name, stage, mood_score, total_exp, level,
+                                     is_active, created_at, updated_at)
+                   VALUES (?, ?, 'pet', 1, 100, 0, 1, ?, NOW(), NOW())"#,
+            )
+            .bind(user_id)
+            .bind(pet_type_id)
+            .bind(i == 0)
+            .execute(&pool)
+            .await
+            .unwrap();
+            counts.push(count_barn_queries(&pool, user_id).await);
+        }
+
+        for sql in [
+            "DELETE FROM pet_mood_history WHERE pet_id IN (SELECT id FROM pets WHERE user_id = ?)",
+            "DELETE FROM pet_milestones WHERE user_id = ?",
+            "DELETE FROM pets WHERE user_id = ?",
+            "DELETE FROM user_streaks WHERE user_id = ?",
+            "DELETE FROM users WHERE id = ?",
+        ] {
+            sqlx::query(sql).bind(user_id).execute(&pool).await.unwrap();
+        }
+
+        assert!(counts[0] > 0, "no queries were counted");
+        assert!(
+            counts.iter().all(|&count| count == counts[0]),
+            "query count grew with the number of pets: {:?}",
+            counts
+        );
+    }
+
+    #[test]
+    fn refreshed_state_of_up_to_date_pet_needs_no_update() {
+        let context = context();
+        let pet = pet(1, 10, 500, None);
+        let state = context.refreshed_state(&pet);
+        assert_eq!(
+            state,
+            PetState {
+                level: pet.level,
+                stage: pet.stage,
+                mood_score: pet.mood_score,
+            }
+        );
+    }
+
+    #[test]
+    fn refreshed_state_adds_only_the_pets_own_care_boost() {
+        let context = context();
+        assert_eq!(context.refreshed_state(&pet(1, 10, 0, None)).mood_score, 60);
+        assert_eq!(context.refreshed_state(&pet(2, 10, 0, None)).mood_score, 75);
+    }
+
+    #[test]
+    fn evolved_pet_keeps_evolution_stage_and_branch_image() {
+        let context = context();
+        let pet = pet(3, 20, 0, Some(200));
+        let state = context.refreshed_state(&pet);
+        assert_eq!(state.stage, Pet::EVOLUTION_STAGE);
+
+        let response = context.compose_response(pet, &state);
+        assert_eq!(response.branch.map(|b| b.id), Some(200));
+        assert_eq!(
+            response.image_url.as_deref(),
+            Some("/pets/branches/200.png")
+        );
+        assert!(!response.can_evolve);
+    }
+
+    #[test]
+    fn compose_response_picks_each_pets_batched_data() {
+        let context = context();
+        let responses: Vec<PetResponse> = [pet(1, 10, 0, None), pet(2, 20, 0, None)]
+            .into_iter()
+            .map(|p| {
+                let state = context.refreshed_state(&p);
+                context.compose_response(p, &state)
+            })
+            .collect();
+
+        assert_eq!(responses[0].pet_type_code.as_deref(), Some("dog"));
+        assert_eq!(responses[1].pet_type_code.as_deref(), Some("cat"));
+
+        assert_eq!(responses[0].accessories.len(), 1);
+        let base_image = responses[0].image_url.clone().expect("dog has an image");
+        assert_eq!(
+            responses[0].image_layers,
+            vec![base_image, "/accessories/cap.png".to_string()]
+        );
+        assert!(responses[1].accessories.is_empty());
+        assert_eq!(responses[1].image_layers.len(), 1);
+    }
 }
@@ -0,0 +1,405 @@
+//! パートナーの着せ替えアクセサリーAPIハンドラ
+//!
+//! レベルやストリークの実績でアクセサリーを解放し、パートナーに装着する。
+//! 装着中のアクセサリーはペット情報の画像レイヤー（imageLayers）として基本画像に重ねて返す。
+
+use actix_web::{get, put, web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+
+use crate::api::pet::{build_pet_response, find_pet_by_id, PetResponse};
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::{Pet, PetAccessory};
+use crate::error::AppError;
+
+/// 装着スロット（画像を重ねる順、後ろほど手前に描画する）
+const ACCESSORY_SLOTS: [&str; 4] = ["back", "neck", "face", "head"];
+
+const ACCESSORY_COLUMNS: &str = "a.id, a.code, a.name, a.description, a.slot, a.image_path, \
+                                 a.unlock_type, a.unlock_value, a.display_order";
+
+// ============================================
+// DTOs
+// ============================================
+
+/// パートナーが装着中のアクセサリー
+#[derive(Serialize, Clone)]
+pub struct EquippedAccessoryResponse {
+    pub code: String,
+    pub name: String,
+    pub slot: String,
+    #[serde(rename = "imagePath")]
+    pub image_path: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetAccessoryResponse {
+    code: String,
+    name: String,
+    description: Option<String>,
+    slot: String,
+    image_path: Option<String>,
+    unlocked: bool,
+    unlock_type: String,
+    unlock_progress: String,
+    /// 対象のパートナーが装着中か
+    equipped: bool,
+    /// 装着中のパートナー（他のパートナーが装着中の場合も含む）
+    equipped_pet_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct PetAccessoriesResponse {
+    pet: PetResponse,
+    accessories: Vec<PetAccessoryResponse>,
+}
+
+#[derive(Deserialize)]
+struct EquipAccessoriesRequest {
+    /// 装着するアクセサリーのコード（空の場合はすべて外す）
+    #[serde(rename = "accessoryCodes", default)]
+    accessory_codes: Vec<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct EquippedAccessoryRow {
+    pet_id: i64,
+    #[sqlx(flatten)]
+    accessory: PetAccessory,
+}
+
+#[derive(sqlx::FromRow)]
+struct UserAccessoryRow {
+    accessory_id: i32,
+    pet_id: Option<i64>,
+}
+
+/// 解放条件の判定に使う実績
+struct AccessoryProgress {
+    user_level: i32,
+    pet_level: i32,
+    best_streak: i32,
+}
+
+// ============================================
+// 装着中のアクセサリー・画像レイヤー
+// ============================================
+
+fn slot_order(slot: &str) -> usize {
+    ACCESSORY_SLOTS
+        .iter()
+        .position(|&s| s == slot)
+        .unwrap_or(ACCESSORY_SLOTS.len())
+}
+
+/// ユーザーのパートナーごとの装着中のアクセサリー（描画順）
+pub(crate) async fn fetch_equipped_accessories(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<HashMap<i64, Vec<EquippedAccessoryResponse>>, AppError> {
+    let mut rows: Vec<EquippedAccessoryRow> = sqlx::query_as(&format!(
+        r#"SELECT upa.pet_id, {}
+           FROM user_pet_accessories upa
+           INNER JOIN pet_accessories a ON a.id = upa.accessory_id
+           WHERE upa.user_id = ? AND upa.pet_id IS NOT NULL"#,
+        ACCESSORY_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    rows.sort_by_key(|r| (slot_order(&r.accessory.slot), r.accessory.display_order));
+
+    let mut equipped: HashMap<i64, Vec<EquippedAccessoryResponse>> = HashMap::new();
+    for row in rows {
+        let a = row.accessory;
+        equipped
+            .entry(row.pet_id)
+            .or_default()
+            .push(EquippedAccessoryResponse {
+                code: a.code,
+                name: a.name,
+                slot: a.slot,
+                image_path: a.image_path,
+            });
+    }
+    Ok(equipped)
+}
+
+/// 基本画像に装着中のアクセサリー画像を重ねた画像レイヤー（奥から順）
+pub(crate) fn compose_image_layers(
+    base_image: Option<&str>,
+    equipped: &[EquippedAccessoryResponse],
+) -> Vec<String> {
+    base_image
+        .into_iter()
+        .chain(equipped.iter().filter_map(|a| a.image_path.as_deref()))
+        .map(str::to_string)
+        .collect()
+}
+
+// ============================================
+// 解放
+// ============================================
+
+async fn get_all_accessories(pool: &MySqlPool) -> Result<Vec<PetAccessory>, AppError> {
+    let accessories: Vec<PetAccessory> = sqlx::query_as(&format!(
+        "SELECT {} FROM pet_accessories a ORDER BY a.display_order, a.id",
+        ACCESSORY_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+    Ok(accessories)
+}
+
+async fn get_user_accessories(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Vec<UserAccessoryRow>, AppError> {
+    let rows: Vec<UserAccessoryRow> =
+        sqlx::query_as("SELECT accessory_id, pet_id FROM user_pet_accessories WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+    Ok(rows)
+}
+
+async fn get_accessory_progress(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<AccessoryProgress, AppError> {
+    let user_level: Option<i32> =
+        sqlx::query_scalar("SELECT level FROM user_stats WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    let max_pet_exp: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(total_exp) FROM pets WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+    let best_streak: Option<i32> = sqlx::query_scalar(
+        "SELECT best_streak FROM user_streaks WHERE user_id = ? AND streak_type = 'training'",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(AccessoryProgress {
+        user_level: user_level.unwrap_or(1),
+        pet_level: max_pet_exp.map(Pet::calculate_level).unwrap_or(0),
+        best_streak: best_streak.unwrap_or(0),
+    })
+}
+
+fn meets_accessory_condition(accessory: &PetAccessory, progress: &AccessoryProgress) -> bool {
+    match accessory.unlock_type.as_str() {
+        "default" => true,
+        "user_level" => progress.user_level >= accessory.unlock_value,
+        "pet_level" => progress.pet_level >= accessory.unlock_value,
+        "best_streak" => progress.best_streak >= accessory.unlock_value,
+        _ => false,
+    }
+}
+
+fn get_accessory_unlock_progress(
+    accessory: &PetAccessory,
+    unlocked: bool,
+    progress: &AccessoryProgress,
+) -> String {
+    if unlocked {
+        return "解放済み".to_string();
+    }
+    match accessory.unlock_type.as_str() {
+        "user_level" => format!(
+            "ユーザーLv.{}で解放 (現在Lv.{})",
+            accessory.unlock_value, progress.user_level
+        ),
+        "pet_level" => format!(
+            "パートナーをLv.{}まで育てると解放 (現在Lv.{})",
+            accessory.unlock_value, progress.pet_level
+        ),
+        "best_streak" => format!(
+            "{}日連続でトレーニングすると解放 (最長{}日)",
+            accessory.unlock_value, progress.best_streak
+        ),
+        _ => "解放条件未設定".to_string(),
+    }
+}
+
+/// 実績からアクセサリーを解放（最初から使えるものも装着状態を持てるよう記録する）
+async fn check_and_unlock_pet_accessories(
+    pool: &MySqlPool,
+    user_id: i64,
+    accessories: &[PetAccessory],
+    progress: &AccessoryProgress,
+) -> Result<(), AppError> {
+    let owned: Vec<i32> = get_user_accessories(pool, user_id)
+        .await?
+        .into_iter()
+        .map(|row| row.accessory_id)
+        .collect();
+
+    for accessory in accessories {
+        if owned.contains(&accessory.id) || !meets_accessory_condition(accessory, progress) {
+            continue;
+        }
+        sqlx::query(
+            r#"INSERT IGNORE INTO user_pet_accessories
+                   (user_id, accessory_id, unlocked_at, updated_at)
+               VALUES (?, ?, NOW(), NOW())"#,
+        )
+        .bind(user_id)
+        .bind(accessory.id)
+        .execute(pool)
+        .await?;
+        tracing::info!(
+            "[UNLOCK] user_id={} unlocked pet accessory: {}",
+            user_id,
+            accessory.code
+        );
+    }
+    Ok(())
+}
+
+/// アクセサリー一覧（解放状況と対象パートナーの装着状況）
+async fn build_accessories_response(
+    pool: &MySqlPool,
+    user_id: i64,
+    pet: Pet,
+) -> Result<PetAccessoriesResponse, AppError> {
+    let accessories = get_all_accessories(pool).await?;
+    let progress = get_accessory_progress(pool, user_id).await?;
+    check_and_unlock_pet_accessories(pool, user_id, &accessories, &progress).await?;
+    let owned = get_user_accessories(pool, user_id).await?;
+
+    let responses = accessories
+        .iter()
+        .map(|a| {
+            let row = owned.iter().find(|row| row.accessory_id == a.id);
+            let equipped_pet_id = row.and_then(|row| row.pet_id);
+            PetAccessoryResponse {
+                code: a.code.clone(),
+                name: a.name.clone(),
+                description: a.description.clone(),
+                slot: a.slot.clone(),
+                image_path: a.image_path.clone(),
+                unlocked: row.is_some(),
+                unlock_type: a.unlock_type.clone(),
+                unlock_progress: get_accessory_unlock_progress(a, row.is_some(), &progress),
+                equipped: equipped_pet_id == Some(pet.id),
+                equipped_pet_id,
+            }
+        })
+        .collect();
+
+    Ok(PetAccessoriesResponse {
+        pet: build_pet_response(pool, pet).await?,
+        accessories: responses,
+    })
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/pet/{id}/accessories
+/// アクセサリー一覧と対象パートナーの装着状況
+#[get("/pet/{id}/accessories")]
+async fn get_pet_accessories(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let pet = find_pet_by_id(pool.get_ref(), path.into_inner(), session_user.id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("パートナーが見つかりません".to_string()))?;
+
+    let response = build_accessories_response(pool.get_ref(), session_user.id, pet).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// PUT /api/pet/{id}/accessories
+/// 装着するアクセサリーを指定する（指定しなかったものは外す、他のパートナーが装着中なら付け替える）
+#[put("/pet/{id}/accessories")]
+async fn update_pet_accessories(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    body: web::Json<EquipAccessoriesRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+
+    let pet = find_pet_by_id(pool.get_ref(), path.into_inner(), user_id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("パートナーが見つかりません".to_string()))?;
+
+    let accessories = get_all_accessories(pool.get_ref()).await?;
+    let progress = get_accessory_progress(pool.get_ref(), user_id).await?;
+    check_and_unlock_pet_accessories(pool.get_ref(), user_id, &accessories, &progress).await?;
+    let owned = get_user_accessories(pool.get_ref(), user_id).await?;
+
+    let mut selected: Vec<&PetAccessory> = Vec::new();
+    for code in &body.accessory_codes {
+        let accessory = accessories
+            .iter()
+            .find(|a| &a.code == code)
+            .ok_or_else(|| AppError::NotFound(format!("アクセサリーが見つかりません: {}", code)))?;
+        if !owned.iter().any(|row| row.accessory_id == accessory.id) {
+            return Err(AppError::BadRequest(format!(
+                "{}はまだ解放されていません",
+                accessory.name
+            )));
+        }
+        if selected.iter().any(|s| s.id == accessory.id) {
+            continue;
+        }
+        if selected.iter().any(|s| s.slot == accessory.slot) {
+            return Err(AppError::BadRequest(
+                "同じ部位のアクセサリーは1つまでです".to_string(),
+            ));
+        }
+        selected.push(accessory);
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"UPDATE user_pet_accessories SET pet_id = NULL, updated_at = NOW()
+           WHERE user_id = ? AND pet_id = ?"#,
+    )
+    .bind(user_id)
+    .bind(pet.id)
+    .execute(&mut *tx)
+    .await?;
+    for accessory in &selected {
+        sqlx::query(
+            r#"UPDATE user_pet_accessories SET pet_id = ?, updated_at = NOW()
+               WHERE user_id = ? AND accessory_id = ?"#,
+        )
+        .bind(pet.id)
+        .bind(user_id)
+        .bind(accessory.id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    tracing::info!(
+        "[PUT /pet/accessories] user_id={} pet_id={} equipped={}",
+        user_id,
+        pet.id,
+        selected.len()
+    );
+
+    let response = build_accessories_response(pool.get_ref(), user_id, pet).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_pet_accessories)
+        .service(update_pet_accessories);
+}
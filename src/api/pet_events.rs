@@ -0,0 +1,146 @@
+//! パートナーのリアルタイム通知（WebSocket）
+//!
+//! 記録の保存でパートナーがレベルアップ・覚醒したときや新しいペット種類が解放されたときに、
+//! 通知バス経由で接続中のクライアントへイベントを送る。
+//! 通知バスは共有ストアのものを使うため、複数インスタンス構成でも接続先のインスタンスに届く。
+
+use std::time::{Duration, Instant};
+
+use actix_web::{get, rt, web, HttpRequest, HttpResponse};
+use actix_ws::Message;
+use futures::StreamExt;
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::api::pet::PetExpGain;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+use crate::shared_store::{BusMessage, SharedStore};
+
+pub(crate) const TOPIC_PET_LEVEL_UP: &str = "pet.levelUp";
+pub(crate) const TOPIC_PET_MATURED: &str = "pet.matured";
+pub(crate) const TOPIC_PET_UNLOCKED: &str = "pet.unlocked";
+
+/// 接続確認のPingを送る間隔
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// この時間クライアントから応答がなければ切断する
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// クライアントへ送るイベント
+#[derive(Serialize)]
+struct PetEvent<'a> {
+    #[serde(rename = "type")]
+    event_type: &'a str,
+    payload: &'a serde_json::Value,
+}
+
+/// 記録の保存で起きたパートナーの変化を通知する
+pub(crate) async fn publish_pet_events(
+    store: &SharedStore,
+    user_id: i64,
+    gain: Option<&PetExpGain>,
+    unlocked_pet_types: &[String],
+) {
+    let mut messages = Vec::new();
+    if let Some(gain) = gain {
+        let payload = serde_json::json!({ "petId": gain.pet_id, "level": gain.level });
+        if gain.level_up {
+            messages.push((TOPIC_PET_LEVEL_UP, payload.clone()));
+        }
+        if gain.matured {
+            messages.push((TOPIC_PET_MATURED, payload));
+        }
+    }
+    for name in unlocked_pet_types {
+        messages.push((
+            TOPIC_PET_UNLOCKED,
+            serde_json::json!({ "petTypeName": name }),
+        ));
+    }
+
+    for (topic, payload) in messages {
+        store
+            .publish(BusMessage {
+                topic: topic.to_string(),
+                user_id: Some(user_id),
+                payload,
+            })
+            .await;
+    }
+}
+
+/// GET /ws/pet
+/// ログイン中のユーザー宛てのパートナーのイベントをWebSocketで受け取る
+#[get("/ws/pet")]
+async fn pet_events(
+    req: HttpRequest,
+    body: web::Payload,
+    session: Session,
+    store: web::Data<SharedStore>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let (response, mut ws_session, mut msg_stream) = actix_ws::handle(&req, body)
+        .map_err(|e| AppError::BadRequest(format!("WebSocket handshake failed: {}", e)))?;
+
+    let user_id = session_user.id;
+    let mut receiver = store.subscribe();
+    rt::spawn(async move {
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let mut last_seen = Instant::now();
+
+        loop {
+            tokio::select! {
+                event = receiver.recv() => match event {
+                    Ok(message)
+                        if message.user_id == Some(user_id)
+                            && message.topic.starts_with("pet.") =>
+                    {
+                        let event = PetEvent {
+                            event_type: &message.topic,
+                            payload: &message.payload,
+                        };
+                        let Ok(json) = serde_json::to_string(&event) else {
+                            continue;
+                        };
+                        if ws_session.text(json).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Pet events lagged for user_id={}: {}", user_id, skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+                msg = msg_stream.next() => match msg {
+                    Some(Ok(Message::Ping(bytes))) => {
+                        last_seen = Instant::now();
+                        if ws_session.pong(&bytes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    // クライアントからのメッセージは使わず、生存確認にのみ使う
+                    Some(Ok(_)) => last_seen = Instant::now(),
+                },
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() > CLIENT_TIMEOUT {
+                        break;
+                    }
+                    if ws_session.ping(b"").await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+
+        let _ = ws_session.close(None).await;
+    });
+
+    Ok(response)
+}
+
+/// ルートレベルのルート（/api配下ではない）
+pub fn configure_root(cfg: &mut web::ServiceConfig) {
+    cfg.service(pet_events);
+}
@@ -0,0 +1,380 @@
+//! パートナーの贈り物APIハンドラ
+//!
+//! 解放済みのペット種類、または所持アイテムをフレンドに贈る。
+//! 贈り物は受け取った側の未回答一覧に届き、承諾すると解放・所持品に反映される。
+//! アイテムは送信時に送り主の所持品から預かり、辞退された場合は送り主に戻す。
+
+use actix_web::{get, post, web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::shop::{add_item, consume_item, find_item_by_code};
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+const STATUS_PENDING: &str = "PENDING";
+const STATUS_ACCEPTED: &str = "ACCEPTED";
+const STATUS_DECLINED: &str = "DECLINED";
+
+/// 1回に贈れるアイテムの個数の上限
+const MAX_GIFT_QUANTITY: i32 = 10;
+/// メッセージの最大文字数
+const MAX_GIFT_MESSAGE_CHARS: usize = 200;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SendGiftRequest {
+    /// 贈り先のログインID
+    login_id: String,
+    /// 贈るペット種類（itemCodeとどちらか一方）
+    pet_type_id: Option<i32>,
+    /// 贈るアイテム
+    item_code: Option<String>,
+    #[serde(default = "default_quantity")]
+    quantity: i32,
+    message: Option<String>,
+}
+
+fn default_quantity() -> i32 {
+    1
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetGiftDto {
+    id: i64,
+    sender_name: String,
+    /// ペット種類の贈り物の場合
+    pet_type_id: Option<i32>,
+    pet_type_name: Option<String>,
+    /// アイテムの贈り物の場合
+    item_code: Option<String>,
+    item_name: Option<String>,
+    quantity: i32,
+    message: Option<String>,
+    created_at: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct PetGiftRow {
+    id: i64,
+    sender_user_id: i64,
+    sender_name: String,
+    pet_type_id: Option<i32>,
+    pet_type_name: Option<String>,
+    item_id: Option<i32>,
+    item_code: Option<String>,
+    item_name: Option<String>,
+    quantity: i32,
+    message: Option<String>,
+    created_at: NaiveDateTime,
+}
+
+impl From<PetGiftRow> for PetGiftDto {
+    fn from(row: PetGiftRow) -> Self {
+        Self {
+            id: row.id,
+            sender_name: row.sender_name,
+            pet_type_id: row.pet_type_id,
+            pet_type_name: row.pet_type_name,
+            item_code: row.item_code,
+            item_name: row.item_name,
+            quantity: row.quantity,
+            message: row.message,
+            created_at: row.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }
+    }
+}
+
+const GIFT_SELECT: &str = r#"SELECT g.id, g.sender_user_id,
+                  CAST(COALESCE(u.display_name, u.login_id) AS CHAR) AS sender_name,
+                  g.pet_type_id, pt.name AS pet_type_name,
+                  g.item_id, i.code AS item_code, i.name AS item_name,
+                  g.quantity, g.message, g.created_at
+           FROM pet_gifts g
+           INNER JOIN users u ON u.id = g.sender_user_id
+           LEFT JOIN pet_types pt ON pt.id = g.pet_type_id
+           LEFT JOIN items i ON i.id = g.item_id"#;
+
+// ============================================
+// 解放状況
+// ============================================
+
+/// ペット種類を解放済みか（解放記録があるか、その種類のペットを所持している）
+async fn has_unlocked_pet_type(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    pet_type_id: i32,
+) -> Result<bool, AppError> {
+    let unlocked: i64 = sqlx::query_scalar(
+        r#"SELECT CAST(
+                      EXISTS(SELECT 1 FROM user_pet_unlocks WHERE user_id = ? AND pet_type_id = ?)
+                      OR EXISTS(SELECT 1 FROM pets WHERE user_id = ? AND pet_type_id = ?)
+                  AS SIGNED)"#,
+    )
+    .bind(user_id)
+    .bind(pet_type_id)
+    .bind(user_id)
+    .bind(pet_type_id)
+    .fetch_one(conn)
+    .await?;
+    Ok(unlocked != 0)
+}
+
+/// 自分宛ての未回答の贈り物に回答する（同時に回答されても一度だけ反映されるよう状態を先に更新する）
+async fn respond_to_gift(
+    conn: &mut MySqlConnection,
+    gift_id: i64,
+    user_id: i64,
+    status: &str,
+) -> Result<PetGiftRow, AppError> {
+    let gift: Option<PetGiftRow> = sqlx::query_as(&format!(
+        "{} WHERE g.id = ? AND g.recipient_user_id = ? AND g.status = ?",
+        GIFT_SELECT
+    ))
+    .bind(gift_id)
+    .bind(user_id)
+    .bind(STATUS_PENDING)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let gift = gift.ok_or_else(|| AppError::NotFound("Gift not found".to_string()))?;
+
+    let result = sqlx::query(
+        "UPDATE pet_gifts SET status = ?, responded_at = NOW() WHERE id = ? AND status = ?",
+    )
+    .bind(status)
+    .bind(gift.id)
+    .bind(STATUS_PENDING)
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Gift not found".to_string()));
+    }
+    Ok(gift)
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// POST /api/pet/gift
+/// 解放済みのペット種類、または所持アイテムを贈る
+#[post("/pet/gift")]
+async fn send_gift(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<SendGiftRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+
+    let message = body
+        .message
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty());
+    if message.is_some_and(|m| m.chars().count() > MAX_GIFT_MESSAGE_CHARS) {
+        return Err(AppError::BadRequest(format!(
+            "メッセージは{}文字以内で入力してください",
+            MAX_GIFT_MESSAGE_CHARS
+        )));
+    }
+
+    let recipient_id: Option<i64> = sqlx::query_scalar("SELECT id FROM users WHERE login_id = ?")
+        .bind(body.login_id.trim())
+        .fetch_optional(pool.get_ref())
+        .await?;
+    let Some(recipient_id) = recipient_id else {
+        return Err(AppError::NotFound("ユーザーが見つかりません".to_string()));
+    };
+    if recipient_id == user_id {
+        return Err(AppError::BadRequest("自分自身には贈れません".to_string()));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let (pet_type_id, item_id, quantity) = match (body.pet_type_id, body.item_code.as_deref()) {
+        (Some(pet_type_id), None) => {
+            let pet_type: Option<(Option<bool>, Option<String>)> = sqlx::query_as(
+                "SELECT is_starter, unlock_type FROM pet_types WHERE id = ? AND is_active = TRUE",
+            )
+            .bind(pet_type_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some((is_starter, unlock_type)) = pet_type else {
+                return Err(AppError::BadRequest("無効なペット種類です".to_string()));
+            };
+            // 最初から選べる種類は誰でも解放済み
+            if is_starter.unwrap_or(false) || unlock_type.as_deref() == Some("default") {
+                return Err(AppError::BadRequest(
+                    "このペット種類は贈り物にできません".to_string(),
+                ));
+            }
+            if !has_unlocked_pet_type(&mut tx, user_id, pet_type_id).await? {
+                return Err(AppError::BadRequest(
+                    "解放済みのペット種類のみ贈れます".to_string(),
+                ));
+            }
+            if has_unlocked_pet_type(&mut tx, recipient_id, pet_type_id).await? {
+                return Err(AppError::BadRequest(
+                    "相手は既にこのペット種類を解放しています".to_string(),
+                ));
+            }
+            let pending: Option<i64> = sqlx::query_scalar(
+                r#"SELECT id FROM pet_gifts
+                   WHERE recipient_user_id = ? AND pet_type_id = ? AND status = ?"#,
+            )
+            .bind(recipient_id)
+            .bind(pet_type_id)
+            .bind(STATUS_PENDING)
+            .fetch_optional(&mut *tx)
+            .await?;
+            if pending.is_some() {
+                return Err(AppError::BadRequest(
+                    "このペット種類は既に贈られています".to_string(),
+                ));
+            }
+            (Some(pet_type_id), None, 1)
+        }
+        (None, Some(item_code)) => {
+            if !(1..=MAX_GIFT_QUANTITY).contains(&body.quantity) {
+                return Err(AppError::BadRequest(format!(
+                    "贈る個数は1〜{}で指定してください",
+                    MAX_GIFT_QUANTITY
+                )));
+            }
+            let item = find_item_by_code(&mut tx, item_code)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Item not found".to_string()))?;
+            // 承諾・辞退まで送り主の所持品から預かる
+            if !consume_item(&mut tx, user_id, item.id, body.quantity).await? {
+                return Err(AppError::BadRequest(format!(
+                    "{}の所持数が足りません",
+                    item.name
+                )));
+            }
+            (None, Some(item.id), body.quantity)
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "ペット種類かアイテムのどちらか一方を指定してください".to_string(),
+            ));
+        }
+    };
+
+    let result = sqlx::query(
+        r#"INSERT INTO pet_gifts
+               (sender_user_id, recipient_user_id, pet_type_id, item_id, quantity, message,
+                status, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, NOW())"#,
+    )
+    .bind(user_id)
+    .bind(recipient_id)
+    .bind(pet_type_id)
+    .bind(item_id)
+    .bind(quantity)
+    .bind(message)
+    .bind(STATUS_PENDING)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        "[POST /pet/gift] user_id={} sent gift to user_id={}",
+        user_id,
+        recipient_id
+    );
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "giftId": result.last_insert_id(),
+    })))
+}
+
+/// GET /api/pet/gifts
+/// 自分宛ての未回答の贈り物一覧
+#[get("/pet/gifts")]
+async fn get_gifts(pool: web::Data<MySqlPool>, session: Session) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let rows: Vec<PetGiftRow> = sqlx::query_as(&format!(
+        "{} WHERE g.recipient_user_id = ? AND g.status = ? ORDER BY g.created_at DESC",
+        GIFT_SELECT
+    ))
+    .bind(session_user.id)
+    .bind(STATUS_PENDING)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let gifts: Vec<PetGiftDto> = rows.into_iter().map(PetGiftDto::from).collect();
+    Ok(HttpResponse::Ok().json(gifts))
+}
+
+/// POST /api/pet/gifts/{id}/accept
+/// 贈り物を受け取る（ペット種類は解放、アイテムは所持品に追加）
+#[post("/pet/gifts/{id}/accept")]
+async fn accept_gift(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+
+    let mut tx = pool.begin().await?;
+    let gift = respond_to_gift(&mut tx, path.into_inner(), user_id, STATUS_ACCEPTED).await?;
+
+    if let Some(pet_type_id) = gift.pet_type_id {
+        sqlx::query(
+            r#"INSERT IGNORE INTO user_pet_unlocks (user_id, pet_type_id, unlocked_at)
+               VALUES (?, ?, NOW())"#,
+        )
+        .bind(user_id)
+        .bind(pet_type_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    if let Some(item_id) = gift.item_id {
+        add_item(&mut tx, user_id, item_id, gift.quantity).await?;
+    }
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "gift": PetGiftDto::from(gift),
+    })))
+}
+
+/// POST /api/pet/gifts/{id}/decline
+/// 贈り物を辞退する（アイテムは送り主に戻す）
+#[post("/pet/gifts/{id}/decline")]
+async fn decline_gift(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let mut tx = pool.begin().await?;
+    let gift =
+        respond_to_gift(&mut tx, path.into_inner(), session_user.id, STATUS_DECLINED).await?;
+
+    if let Some(item_id) = gift.item_id {
+        add_item(&mut tx, gift.sender_user_id, item_id, gift.quantity).await?;
+    }
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(send_gift)
+        .service(get_gifts)
+        .service(accept_gift)
+        .service(decline_gift);
+}
@@ -0,0 +1,294 @@
+//! パートナーの節目（マイルストーン）APIハンドラ
+//!
+//! Lv.10到達・覚醒・30日の付き合いなどの節目を記録し、初めて達成したときにボーナスEXPを付与する。
+//! 節目の判定はペットのEXP加算時とペット情報の取得時に行う。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::HashMap;
+
+use crate::api::pet::find_pet_by_id;
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::Pet;
+use crate::domain::timezone::{self, DEFAULT_TIMEZONE};
+use crate::error::AppError;
+
+/// 節目の達成条件
+enum MilestoneCondition {
+    /// ペットのレベル
+    Level(i32),
+    /// ペットのステージ
+    Stage(i32),
+    /// 迎えてからの日数
+    DaysTogether(i64),
+}
+
+struct PetMilestone {
+    code: &'static str,
+    name: &'static str,
+    description: &'static str,
+    condition: MilestoneCondition,
+    bonus_exp: i64,
+}
+
+/// 節目の一覧（ボーナスEXPで次の節目に届く場合も同じ判定で達成できるよう、到達順に並べる）
+const PET_MILESTONES: [PetMilestone; 3] = [
+    PetMilestone {
+        code: "level_10",
+        name: "Lv.10到達",
+        description: "パートナーがLv.10に成長した",
+        condition: MilestoneCondition::Level(10),
+        bonus_exp: 100,
+    },
+    PetMilestone {
+        code: "matured",
+        name: "覚醒",
+        description: "パートナーが覚醒ステージに到達した",
+        condition: MilestoneCondition::Stage(3),
+        bonus_exp: 500,
+    },
+    PetMilestone {
+        code: "companion_30_days",
+        name: "30日の絆",
+        description: "パートナーを迎えてから30日が経った",
+        condition: MilestoneCondition::DaysTogether(30),
+        bonus_exp: 300,
+    },
+];
+
+impl PetMilestone {
+    fn is_met(&self, total_exp: i64, days_together: i64) -> bool {
+        let level = Pet::calculate_level(total_exp);
+        match self.condition {
+            MilestoneCondition::Level(required) => level >= required,
+            MilestoneCondition::Stage(required) => Pet::calculate_stage(level) >= required,
+            MilestoneCondition::DaysTogether(required) => days_together >= required,
+        }
+    }
+
+    /// 達成条件の現在値と目標値
+    fn progress(&self, total_exp: i64, days_together: i64) -> (i64, i64) {
+        let level = Pet::calculate_level(total_exp);
+        match self.condition {
+            MilestoneCondition::Level(required) => (level as i64, required as i64),
+            MilestoneCondition::Stage(required) => {
+                (level as i64, Pet::get_stage_start_level(required) as i64)
+            }
+            MilestoneCondition::DaysTogether(required) => (days_together, required),
+        }
+    }
+}
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetMilestoneResponse {
+    code: &'static str,
+    name: &'static str,
+    description: &'static str,
+    bonus_exp: i64,
+    achieved: bool,
+    achieved_at: Option<String>,
+    /// 達成条件の現在値（レベル・日数）
+    current: i64,
+    /// 達成条件の目標値（レベル・日数）
+    target: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetMilestonesResponse {
+    pet_id: i64,
+    milestones: Vec<PetMilestoneResponse>,
+}
+
+// ============================================
+// 節目の判定
+// ============================================
+
+/// ユーザーのペットごとの達成済みの節目
+pub(crate) async fn fetch_achieved_milestones(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<HashMap<i64, Vec<String>>, AppError> {
+    let rows: Vec<(i64, String)> =
+        sqlx::query_as("SELECT pet_id, milestone_code FROM pet_milestones WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(pool)
+            .await?;
+
+    let mut achieved: HashMap<i64, Vec<String>> = HashMap::new();
+    for (pet_id, code) in rows {
+        achieved.entry(pet_id).or_default().push(code);
+    }
+    Ok(achieved)
+}
+
+/// 迎えてからの日数（todayはユーザーのタイムゾーンでの今日）
+fn days_together(created_at: Option<NaiveDateTime>, today: NaiveDate) -> i64 {
+    created_at
+        .map(|at| (today - at.date()).num_days())
+        .unwrap_or(0)
+}
+
+/// 未記録の節目に到達している可能性があるか（award_pet_milestonesを呼ぶ前の絞り込み用）
+pub(crate) fn has_pending_milestone(pet: &Pet, achieved: &[String], today: NaiveDate) -> bool {
+    let days_together = days_together(pet.created_at, today);
+    PET_MILESTONES.iter().any(|m| {
+        !achieved.iter().any(|code| code == m.code) && m.is_met(pet.total_exp, days_together)
+    })
+}
+
+/// 新たに達成した節目を記録してボーナスEXPを付与する（付与したEXPの合計を返す）
+/// 同時に実行されても二重に付与しないよう、ペットをロックして判定する
+pub(crate) async fn award_pet_milestones(
+    conn: &mut MySqlConnection,
+    pet_id: i64,
+) -> Result<i64, AppError> {
+    let pet: Option<(i64, i64, Option<NaiveDateTime>, Option<i32>)> = sqlx::query_as(
+        r#"SELECT user_id, total_exp, created_at, branch_id
+           FROM pets WHERE id = ? AND released_at IS NULL FOR UPDATE"#,
+    )
+    .bind(pet_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some((user_id, mut total_exp, created_at, branch_id)) = pet else {
+        return Ok(0);
+    };
+
+    // 迎えてからの日数はユーザーのタイムゾーンでの今日から数える
+    let tz: Option<String> =
+        sqlx::query_scalar("SELECT timezone FROM user_settings WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+    let tz = tz
+        .as_deref()
+        .map_or(DEFAULT_TIMEZONE, timezone::from_setting);
+    let days_together = days_together(created_at, timezone::today_in(tz));
+
+    let achieved: Vec<String> =
+        sqlx::query_scalar("SELECT milestone_code FROM pet_milestones WHERE pet_id = ?")
+            .bind(pet_id)
+            .fetch_all(&mut *conn)
+            .await?;
+
+    let mut bonus_total = 0;
+    for milestone in &PET_MILESTONES {
+        if achieved.iter().any(|code| code == milestone.code)
+            || !milestone.is_met(total_exp, days_together)
+        {
+            continue;
+        }
+        sqlx::query(
+            r#"INSERT INTO pet_milestones
+                   (pet_id, user_id, milestone_code, bonus_exp, achieved_at)
+               VALUES (?, ?, ?, ?, NOW())"#,
+        )
+        .bind(pet_id)
+        .bind(user_id)
+        .bind(milestone.code)
+        .bind(milestone.bonus_exp)
+        .execute(&mut *conn)
+        .await?;
+        total_exp += milestone.bonus_exp;
+        bonus_total += milestone.bonus_exp;
+        tracing::info!(
+            "[PET MILESTONE] user_id={} pet_id={} {} +{} exp",
+            user_id,
+            pet_id,
+            milestone.code,
+            milestone.bonus_exp
+        );
+    }
+
+    if bonus_total > 0 {
+        let level = Pet::calculate_level(total_exp);
+        sqlx::query(
+            "UPDATE pets SET total_exp = ?, level = ?, stage = ?, updated_at = NOW() WHERE id = ?",
+        )
+        .bind(total_exp)
+        .bind(level)
+        .bind(Pet::calculate_stage_with_branch(level, branch_id))
+        .bind(pet_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(bonus_total)
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/pet/{id}/milestones
+/// 節目の一覧（達成済みは達成日時順、未達成は進捗付きで後ろに並べる）
+#[get("/pet/{id}/milestones")]
+async fn get_pet_milestones(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let pet_id = path.into_inner();
+
+    let pet = find_pet_by_id(pool.get_ref(), pet_id, session_user.id)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("パートナーが見つかりません".to_string()))?;
+
+    let mut tx = pool.begin().await?;
+    award_pet_milestones(&mut tx, pet.id).await?;
+    tx.commit().await?;
+
+    // ボーナスEXPを付与した場合に備えて取得し直す
+    let total_exp: i64 = sqlx::query_scalar("SELECT total_exp FROM pets WHERE id = ?")
+        .bind(pet.id)
+        .fetch_one(pool.get_ref())
+        .await?;
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    let days_together = days_together(pet.created_at, today);
+
+    let achieved: Vec<(String, chrono::NaiveDateTime)> =
+        sqlx::query_as("SELECT milestone_code, achieved_at FROM pet_milestones WHERE pet_id = ?")
+            .bind(pet.id)
+            .fetch_all(pool.get_ref())
+            .await?;
+
+    let mut milestones: Vec<PetMilestoneResponse> = PET_MILESTONES
+        .iter()
+        .map(|m| {
+            let achieved_at = achieved
+                .iter()
+                .find(|(code, _)| code == m.code)
+                .map(|(_, at)| at.format("%Y-%m-%dT%H:%M:%S").to_string());
+            let (current, target) = m.progress(total_exp, days_together);
+            PetMilestoneResponse {
+                code: m.code,
+                name: m.name,
+                description: m.description,
+                bonus_exp: m.bonus_exp,
+                achieved: achieved_at.is_some(),
+                achieved_at,
+                current: current.min(target),
+                target,
+            }
+        })
+        .collect();
+    // 未達成（None）は後ろに並べる
+    milestones.sort_by_key(|m| (m.achieved_at.is_none(), m.achieved_at.clone()));
+
+    Ok(HttpResponse::Ok().json(PetMilestonesResponse {
+        pet_id: pet.id,
+        milestones,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_pet_milestones);
+}
@@ -0,0 +1,357 @@
+//! パートナーのデイリークエストAPIハンドラ
+//!
+//! 「今日は脚を鍛えよう」「10セット記録しよう」などの小さなクエストを1日ごとに生成する。
+//! 達成状況は記録の保存時にその日のセットから判定し、達成したクエストを受け取るとパートナーにEXPが入る。
+
+use actix_web::{get, post, web, HttpResponse};
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::HashMap;
+
+use crate::api::dashboard::{map_muscle_to_group, MUSCLE_GROUPS};
+use crate::api::pet::{add_exp_to_active_pet, check_and_unlock_pet_types};
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+const QUEST_TRAIN_MUSCLE: &str = "train_muscle";
+const QUEST_LOG_SETS: &str = "log_sets";
+const QUEST_LOG_EXERCISES: &str = "log_exercises";
+
+struct QuestTemplate {
+    quest_type: &'static str,
+    target_value: i32,
+    exp_reward: i32,
+}
+
+/// 毎日生成するクエスト（train_muscleの部位は日ごとに入れ替わる）
+const DAILY_QUESTS: [QuestTemplate; 3] = [
+    QuestTemplate {
+        quest_type: QUEST_TRAIN_MUSCLE,
+        target_value: 3,
+        exp_reward: 60,
+    },
+    QuestTemplate {
+        quest_type: QUEST_LOG_SETS,
+        target_value: 10,
+        exp_reward: 80,
+    },
+    QuestTemplate {
+        quest_type: QUEST_LOG_EXERCISES,
+        target_value: 3,
+        exp_reward: 50,
+    },
+];
+
+/// その日に鍛える部位（ユーザーごとにずらして日替わりにする）
+fn target_muscle_for(user_id: i64, date: NaiveDate) -> &'static str {
+    let index = (user_id + date.num_days_from_ce() as i64).rem_euclid(MUSCLE_GROUPS.len() as i64);
+    MUSCLE_GROUPS[index as usize]
+}
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetQuestDto {
+    id: i64,
+    quest_type: String,
+    title: String,
+    target_muscle: Option<String>,
+    target_value: i32,
+    progress: i32,
+    exp_reward: i32,
+    completed: bool,
+    claimed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetQuestsResponse {
+    date: String,
+    quests: Vec<PetQuestDto>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClaimPetQuestResponse {
+    quest_id: i64,
+    exp_earned: i32,
+    pet_id: i64,
+    pet_level: i32,
+    level_up: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct PetQuestRow {
+    id: i64,
+    quest_type: String,
+    target_muscle: Option<String>,
+    target_value: i32,
+    progress: i32,
+    exp_reward: i32,
+    completed_at: Option<chrono::NaiveDateTime>,
+    claimed_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<PetQuestRow> for PetQuestDto {
+    fn from(row: PetQuestRow) -> Self {
+        let title = match (row.quest_type.as_str(), row.target_muscle.as_deref()) {
+            (QUEST_TRAIN_MUSCLE, Some(muscle)) => {
+                format!("今日は{}を{}セット鍛えよう", muscle, row.target_value)
+            }
+            (QUEST_LOG_SETS, _) => format!("{}セット記録しよう", row.target_value),
+            (QUEST_LOG_EXERCISES, _) => format!("{}種目に取り組もう", row.target_value),
+            _ => String::new(),
+        };
+        Self {
+            id: row.id,
+            quest_type: row.quest_type,
+            title,
+            target_muscle: row.target_muscle,
+            target_value: row.target_value,
+            progress: row.progress,
+            exp_reward: row.exp_reward,
+            completed: row.completed_at.is_some(),
+            claimed: row.claimed_at.is_some(),
+        }
+    }
+}
+
+// ============================================
+// クエストの生成・達成判定
+// ============================================
+
+/// その日のトレーニング量（ウォームアップを除く）
+#[derive(Default)]
+struct DailyTrainingSummary {
+    sets: i32,
+    exercises: i32,
+    sets_by_group: HashMap<&'static str, i32>,
+}
+
+impl DailyTrainingSummary {
+    async fn load(
+        conn: &mut MySqlConnection,
+        user_id: i64,
+        date: NaiveDate,
+    ) -> Result<Self, AppError> {
+        let rows: Vec<(i64, Option<String>, i64)> = sqlx::query_as(
+            r#"SELECT tre.id, CAST(COALESCE(e.muscle, uce.muscle) AS CHAR) AS muscle,
+                      COUNT(ts.id) AS set_count
+               FROM training_records tr
+               INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+               INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+               LEFT JOIN exercises e ON e.id = tre.exercise_id
+               LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+               WHERE tr.user_id = ? AND tr.record_date = ? AND ts.set_type <> 'warmup'
+               GROUP BY tre.id, muscle"#,
+        )
+        .bind(user_id)
+        .bind(date)
+        .fetch_all(conn)
+        .await?;
+
+        let mut summary = Self::default();
+        for (_, muscle, count) in rows {
+            summary.sets += count as i32;
+            summary.exercises += 1;
+            if let Some(group) = muscle.as_deref().and_then(map_muscle_to_group) {
+                *summary.sets_by_group.entry(group).or_default() += count as i32;
+            }
+        }
+        Ok(summary)
+    }
+
+    fn value_for(&self, quest_type: &str, target_muscle: Option<&str>) -> i32 {
+        match quest_type {
+            QUEST_TRAIN_MUSCLE => target_muscle
+                .and_then(|m| self.sets_by_group.get(m))
+                .copied()
+                .unwrap_or(0),
+            QUEST_LOG_SETS => self.sets,
+            QUEST_LOG_EXERCISES => self.exercises,
+            _ => 0,
+        }
+    }
+}
+
+/// その日のクエストを生成する（アクティブパートナーがいない場合は生成しない）
+async fn ensure_pet_quests(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    date: NaiveDate,
+) -> Result<(), AppError> {
+    let pet_id: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM pets WHERE user_id = ? AND is_active = TRUE AND released_at IS NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some(pet_id) = pet_id else {
+        return Ok(());
+    };
+
+    for quest in &DAILY_QUESTS {
+        let target_muscle =
+            (quest.quest_type == QUEST_TRAIN_MUSCLE).then(|| target_muscle_for(user_id, date));
+        sqlx::query(
+            r#"INSERT IGNORE INTO pet_quests
+                   (user_id, pet_id, quest_date, quest_type, target_muscle, target_value,
+                    exp_reward)
+               VALUES (?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(user_id)
+        .bind(pet_id)
+        .bind(date)
+        .bind(quest.quest_type)
+        .bind(target_muscle)
+        .bind(quest.target_value)
+        .bind(quest.exp_reward)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// その日の記録からクエストの進捗・達成を更新する（記録の保存時に呼び出す）
+/// 受け取り前のクエストは記録の削除で目標を下回ると未達成に戻る
+pub(crate) async fn update_pet_quest_progress(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    date: NaiveDate,
+) -> Result<(), AppError> {
+    ensure_pet_quests(conn, user_id, date).await?;
+
+    let quests: Vec<(i64, String, Option<String>)> = sqlx::query_as(
+        r#"SELECT id, quest_type, target_muscle FROM pet_quests
+           WHERE user_id = ? AND quest_date = ? AND claimed_at IS NULL"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .fetch_all(&mut *conn)
+    .await?;
+    if quests.is_empty() {
+        return Ok(());
+    }
+
+    let summary = DailyTrainingSummary::load(conn, user_id, date).await?;
+    for (quest_id, quest_type, target_muscle) in quests {
+        let value = summary.value_for(&quest_type, target_muscle.as_deref());
+        sqlx::query(
+            r#"UPDATE pet_quests
+               SET progress = LEAST(?, target_value),
+                   completed_at = CASE WHEN ? >= target_value
+                                       THEN COALESCE(completed_at, NOW()) ELSE NULL END
+               WHERE id = ?"#,
+        )
+        .bind(value)
+        .bind(value)
+        .bind(quest_id)
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/pet/quests
+/// 今日のクエスト一覧（未生成の場合は生成する）
+#[get("/pet/quests")]
+async fn get_pet_quests(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    // 記録保存時の進捗更新と同じく、ユーザーのタイムゾーンでの今日
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+
+    let mut tx = pool.begin().await?;
+    update_pet_quest_progress(&mut tx, session_user.id, today).await?;
+    tx.commit().await?;
+
+    let rows: Vec<PetQuestRow> = sqlx::query_as(
+        r#"SELECT id, quest_type, target_muscle, target_value, progress, exp_reward,
+                  completed_at, claimed_at
+           FROM pet_quests WHERE user_id = ? AND quest_date = ?
+           ORDER BY id ASC"#,
+    )
+    .bind(session_user.id)
+    .bind(today)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(PetQuestsResponse {
+        date: today.format("%Y-%m-%d").to_string(),
+        quests: rows.into_iter().map(PetQuestDto::from).collect(),
+    }))
+}
+
+/// POST /api/pet/quests/{id}/claim
+/// 達成したクエストの報酬EXPをアクティブパートナーに付与する
+#[post("/pet/quests/{id}/claim")]
+async fn claim_pet_quest(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let quest_id = path.into_inner();
+
+    let mut tx = pool.begin().await?;
+
+    let quest: Option<PetQuestRow> = sqlx::query_as(
+        r#"SELECT id, quest_type, target_muscle, target_value, progress, exp_reward,
+                  completed_at, claimed_at
+           FROM pet_quests WHERE id = ? AND user_id = ? FOR UPDATE"#,
+    )
+    .bind(quest_id)
+    .bind(session_user.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let quest = quest.ok_or_else(|| AppError::NotFound("Quest not found".to_string()))?;
+
+    if quest.claimed_at.is_some() {
+        return Err(AppError::BadRequest(
+            "このクエストの報酬は受け取り済みです".to_string(),
+        ));
+    }
+    if quest.completed_at.is_none() {
+        return Err(AppError::BadRequest(
+            "このクエストはまだ達成していません".to_string(),
+        ));
+    }
+
+    let gain = add_exp_to_active_pet(&mut tx, session_user.id, quest.exp_reward as i64)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("アクティブなパートナーがいません".to_string()))?;
+
+    sqlx::query("UPDATE pet_quests SET claimed_at = NOW() WHERE id = ?")
+        .bind(quest.id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    if gain.matured {
+        let _ = check_and_unlock_pet_types(pool.get_ref(), session_user.id).await;
+    }
+
+    Ok(HttpResponse::Ok().json(ClaimPetQuestResponse {
+        quest_id: quest.id,
+        exp_earned: quest.exp_reward,
+        pet_id: gain.pet_id,
+        pet_level: gain.level,
+        level_up: gain.level_up,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_pet_quests).service(claim_pet_quest);
+}
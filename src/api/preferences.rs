@@ -0,0 +1,230 @@
+//! ユーザー設定APIハンドラ
+//!
+//! 単位・表示言語・タイムゾーン・ストリーク・通知・プライバシーの設定を
+//! GET/PUT /api/user/preferences でまとめて取得・更新する（保存先はuser_settings）。
+//! /api/settings と /api/settings/privacy は同じ列を読み書きする個別のAPIとして残す。
+
+use actix_web::{get, put, web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::public_profile::PrivacySettings;
+use crate::api::streak::{
+    get_or_create_settings, parse_heatmap_mode, parse_timezone, parse_weight_unit, MAX_GRACE_DAYS,
+};
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+/// 対応している表示言語
+const SUPPORTED_LOCALES: [&str; 2] = ["ja", "en"];
+
+// ============================================
+// DTOs
+// ============================================
+
+/// 通知の受け取り
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NotificationPreferences {
+    /// トレーニング記録へのコメント
+    workout_comments: bool,
+    /// ストリークが途切れそうなときのリマインダー
+    streak_reminder: bool,
+    /// 週間のトレーニングのまとめ
+    weekly_summary: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PreferencesDto {
+    /// 重量の単位（kg / lb）
+    weight_unit: String,
+    /// 表示言語（ja / en）
+    locale: String,
+    /// 日付の境界を判定するタイムゾーン（IANA名）
+    timezone: String,
+    grace_days_allowed: i32,
+    heatmap_mode: String,
+    notifications: NotificationPreferences,
+    privacy: PrivacySettings,
+}
+
+/// 指定した項目のみ更新する（notifications・privacyは項目ごとまとめて置き換える）
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdatePreferencesRequest {
+    weight_unit: Option<String>,
+    locale: Option<String>,
+    timezone: Option<String>,
+    grace_days_allowed: Option<i32>,
+    heatmap_mode: Option<String>,
+    notifications: Option<NotificationPreferences>,
+    privacy: Option<PrivacySettings>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PreferencesRow {
+    weight_unit: String,
+    locale: String,
+    timezone: String,
+    grace_days_allowed: i32,
+    heatmap_mode: String,
+    notify_workout_comments: bool,
+    notify_streak_reminder: bool,
+    notify_weekly_summary: bool,
+    profile_public: bool,
+    show_level: bool,
+    show_streak: bool,
+    show_pet: bool,
+    show_total_volume: bool,
+    show_on_leaderboard: bool,
+}
+
+impl From<PreferencesRow> for PreferencesDto {
+    fn from(row: PreferencesRow) -> Self {
+        Self {
+            weight_unit: row.weight_unit,
+            locale: row.locale,
+            timezone: row.timezone,
+            grace_days_allowed: row.grace_days_allowed,
+            heatmap_mode: row.heatmap_mode,
+            notifications: NotificationPreferences {
+                workout_comments: row.notify_workout_comments,
+                streak_reminder: row.notify_streak_reminder,
+                weekly_summary: row.notify_weekly_summary,
+            },
+            privacy: PrivacySettings {
+                profile_public: row.profile_public,
+                show_level: row.show_level,
+                show_streak: row.show_streak,
+                show_pet: row.show_pet,
+                show_total_volume: row.show_total_volume,
+                show_on_leaderboard: row.show_on_leaderboard,
+            },
+        }
+    }
+}
+
+// ============================================
+// ヘルパー関数
+// ============================================
+
+/// ユーザー設定を取得する（未作成の場合は既定値で作成する）
+async fn fetch_preferences(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<PreferencesDto, AppError> {
+    get_or_create_settings(&mut *conn, user_id).await?;
+    let row: PreferencesRow = sqlx::query_as(
+        r#"SELECT weight_unit, locale, timezone, grace_days_allowed, heatmap_mode,
+                  notify_workout_comments, notify_streak_reminder, notify_weekly_summary,
+                  profile_public, show_level, show_streak, show_pet, show_total_volume,
+                  show_on_leaderboard
+           FROM user_settings WHERE user_id = ?"#,
+    )
+    .bind(user_id)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(row.into())
+}
+
+fn parse_locale(locale: &str) -> Result<String, AppError> {
+    if SUPPORTED_LOCALES.contains(&locale) {
+        Ok(locale.to_string())
+    } else {
+        Err(AppError::BadRequest(format!(
+            "localeには{}のいずれかを指定してください",
+            SUPPORTED_LOCALES.join(" / ")
+        )))
+    }
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/user/preferences
+#[get("/user/preferences")]
+async fn get_preferences(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let preferences = fetch_preferences(&mut *pool.acquire().await?, session_user.id).await?;
+    Ok(HttpResponse::Ok().json(preferences))
+}
+
+/// PUT /api/user/preferences
+/// 指定した項目のみ更新し、更新後の設定をすべて返す
+#[put("/user/preferences")]
+async fn update_preferences(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<UpdatePreferencesRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let body = body.into_inner();
+
+    let mut conn = pool.acquire().await?;
+    let current = fetch_preferences(&mut conn, session_user.id).await?;
+
+    // 値を検証してから現在の設定に重ねる
+    let preferences = PreferencesDto {
+        weight_unit: match body.weight_unit.as_deref() {
+            Some(unit) => parse_weight_unit(unit)?,
+            None => current.weight_unit,
+        },
+        locale: match body.locale.as_deref() {
+            Some(locale) => parse_locale(locale)?,
+            None => current.locale,
+        },
+        timezone: match body.timezone.as_deref() {
+            Some(name) => parse_timezone(name)?,
+            None => current.timezone,
+        },
+        grace_days_allowed: body
+            .grace_days_allowed
+            .map_or(current.grace_days_allowed, |days| {
+                days.clamp(0, MAX_GRACE_DAYS)
+            }),
+        heatmap_mode: match body.heatmap_mode.as_deref() {
+            Some(mode) => parse_heatmap_mode(mode)?,
+            None => current.heatmap_mode,
+        },
+        notifications: body.notifications.unwrap_or(current.notifications),
+        privacy: body.privacy.unwrap_or(current.privacy),
+    };
+
+    sqlx::query(
+        r#"UPDATE user_settings
+           SET weight_unit = ?, locale = ?, timezone = ?, grace_days_allowed = ?,
+               heatmap_mode = ?, notify_workout_comments = ?, notify_streak_reminder = ?,
+               notify_weekly_summary = ?, profile_public = ?, show_level = ?,
+               show_streak = ?, show_pet = ?, show_total_volume = ?, show_on_leaderboard = ?,
+               updated_at = NOW()
+           WHERE user_id = ?"#,
+    )
+    .bind(&preferences.weight_unit)
+    .bind(&preferences.locale)
+    .bind(&preferences.timezone)
+    .bind(preferences.grace_days_allowed)
+    .bind(&preferences.heatmap_mode)
+    .bind(preferences.notifications.workout_comments)
+    .bind(preferences.notifications.streak_reminder)
+    .bind(preferences.notifications.weekly_summary)
+    .bind(preferences.privacy.profile_public)
+    .bind(preferences.privacy.show_level)
+    .bind(preferences.privacy.show_streak)
+    .bind(preferences.privacy.show_pet)
+    .bind(preferences.privacy.show_total_volume)
+    .bind(preferences.privacy.show_on_leaderboard)
+    .bind(session_user.id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(HttpResponse::Ok().json(preferences))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_preferences).service(update_preferences);
+}
@@ -0,0 +1,316 @@
+//! プロフィール画像APIハンドラ
+//!
+//! アップロードされた画像を正方形に切り抜き、表示サイズごとのJPEGに変換して保存する。
+//! 再エンコードするため、位置情報などのメタデータは保存されない。
+//! アップロードした画像はOAuthログイン時にプロバイダー側の画像で上書きしない。
+
+use std::io::Cursor;
+
+use actix_multipart::Multipart;
+use actix_web::{post, web, HttpResponse};
+use futures::StreamExt;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits, RgbImage};
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::auth::session::{get_current_user, replace_current_user, Session, SessionUser};
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::middleware::session_refresh::bump_session_epoch;
+use crate::shared_store::SharedStore;
+use crate::storage::Storage;
+
+const MAX_IMAGE_SIZE: usize = 5 * 1024 * 1024; // 5MB
+/// 受け付ける画像の最大辺（ピクセル）
+const MAX_IMAGE_DIMENSION: u32 = 8192;
+/// デコード時のメモリ上限
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+/// 1ユーザーあたりのアップロード上限（1時間）
+const MAX_UPLOADS_PER_HOUR: u64 = 10;
+
+/// 生成する画像の一辺（ピクセル）。profile_image_urlにはPROFILE_IMAGE_SIZEを保存する
+const VARIANT_SIZES: [u32; 3] = [512, 256, 64];
+const PROFILE_IMAGE_SIZE: u32 = 256;
+const JPEG_QUALITY: u8 = 85;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+struct ProfileImageVariant {
+    size: u32,
+    url: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileImageResponse {
+    profile_image_url: String,
+    /// サイズの大きい順
+    variants: Vec<ProfileImageVariant>,
+}
+
+// ============================================
+// 画像処理
+// ============================================
+
+/// 受け付ける画像の種類
+fn format_from_mime(mime: &str) -> Option<ImageFormat> {
+    match mime {
+        "image/jpeg" => Some(ImageFormat::Jpeg),
+        "image/png" => Some(ImageFormat::Png),
+        "image/gif" => Some(ImageFormat::Gif),
+        "image/webp" => Some(ImageFormat::WebP),
+        _ => None,
+    }
+}
+
+/// 透過部分を白で塗りつぶす（JPEGは透過を扱えないため）
+fn flatten_on_white(image: &DynamicImage) -> RgbImage {
+    let rgba = image.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32)) / 255) as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+/// 画像をデコードし、各サイズの正方形JPEGを作る（サイズの大きい順）
+/// 宣言された形式と中身が一致しない画像や壊れた画像はエラー
+fn build_variants(data: &[u8], declared: ImageFormat) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+    if reader.format() != Some(declared) {
+        return Err("画像の形式がファイルの種類と一致しません".to_string());
+    }
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder().map_err(|e| e.to_string())?;
+    // スマートフォンの写真は向きをEXIFで持っているため、デコード後に回転させる
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    image.apply_orientation(orientation);
+
+    VARIANT_SIZES
+        .iter()
+        .map(|&size| {
+            let resized = image.resize_to_fill(size, size, FilterType::Lanczos3);
+            let mut jpeg = Vec::new();
+            JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY)
+                .encode_image(&flatten_on_white(&resized))
+                .map_err(|e| e.to_string())?;
+            Ok((size, jpeg))
+        })
+        .collect()
+}
+
+fn variant_key(prefix: &str, size: u32) -> String {
+    format!("{}/{}.jpg", prefix, size)
+}
+
+/// アップロードしたプロフィール画像の保存先キー
+pub async fn find_profile_image_key(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Option<String>, AppError> {
+    let key: Option<Option<String>> =
+        sqlx::query_scalar("SELECT profile_image_key FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(key.flatten())
+}
+
+/// 保存済みのプロフィール画像を削除する（失敗はログに残して続行する）
+pub async fn delete_profile_image_files(storage: &Storage, prefix: &str) {
+    for size in VARIANT_SIZES {
+        if let Err(e) = storage.delete(&variant_key(prefix, size)).await {
+            tracing::warn!("Failed to delete profile image: {}", e);
+        }
+    }
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// POST /api/user/profile-image
+/// multipart/form-dataのimageフィールドで画像（JPEG・PNG・GIF・WebP、5MBまで）を受け取る
+#[post("/user/profile-image")]
+async fn upload_profile_image(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    storage: web::Data<Storage>,
+    session: Session,
+    mut payload: Multipart,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    store
+        .check_rate_limit(
+            &format!("profile_image:{}", session_user.id),
+            MAX_UPLOADS_PER_HOUR,
+            std::time::Duration::from_secs(60 * 60),
+        )
+        .await?;
+
+    let mut image: Option<(ImageFormat, Vec<u8>)> = None;
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| {
+            AppError::BadRequest(format!("マルチパートの解析に失敗しました: {}", e))
+        })?;
+        let field_name = field
+            .content_disposition()
+            .and_then(|cd| cd.get_name())
+            .unwrap_or("");
+        if field_name != "image" {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .map(|m| m.to_string())
+            .unwrap_or_default();
+        let format = format_from_mime(&content_type).ok_or_else(|| {
+            AppError::BadRequest("画像はJPEG、PNG、GIF、WebP形式のみ対応しています".to_string())
+        })?;
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| {
+                AppError::BadRequest(format!("画像の読み取りに失敗しました: {}", e))
+            })?;
+            data.extend_from_slice(&chunk);
+
+            if data.len() > MAX_IMAGE_SIZE {
+                return Err(AppError::BadRequest(format!(
+                    "画像サイズは{}MB以下にしてください",
+                    MAX_IMAGE_SIZE / 1024 / 1024
+                )));
+            }
+        }
+        image = Some((format, data));
+    }
+    let (format, data) =
+        image.ok_or_else(|| AppError::BadRequest("画像を選択してください".to_string()))?;
+
+    // デコード・リサイズはCPUを使うためワーカースレッドで行う
+    let variants = web::block(move || build_variants(&data, format))
+        .await
+        .map_err(|e| AppError::InternalError(format!("Image processing failed: {}", e)))?
+        .map_err(|e| {
+            tracing::info!(
+                "[PROFILE IMAGE] user_id={} rejected image: {}",
+                session_user.id,
+                e
+            );
+            AppError::BadRequest("画像を読み込めませんでした".to_string())
+        })?;
+
+    // 毎回新しいキーに保存し、CDN等のキャッシュに古い画像が残らないようにする
+    let prefix = format!(
+        "profile-images/{}/{}",
+        session_user.id,
+        uuid::Uuid::new_v4().simple()
+    );
+    for (size, jpeg) in variants {
+        storage
+            .put(&variant_key(&prefix, size), jpeg, "image/jpeg")
+            .await
+            .map_err(AppError::InternalError)?;
+    }
+
+    let previous_key = find_profile_image_key(pool.get_ref(), session_user.id).await?;
+    let profile_image_url = storage.public_url(&variant_key(&prefix, PROFILE_IMAGE_SIZE));
+    sqlx::query(
+        r#"UPDATE users SET profile_image_url = ?, profile_image_key = ?, updated_at = NOW()
+           WHERE id = ?"#,
+    )
+    .bind(&profile_image_url)
+    .bind(&prefix)
+    .bind(session_user.id)
+    .execute(pool.get_ref())
+    .await?;
+
+    if let Some(previous_key) = previous_key {
+        delete_profile_image_files(&storage, &previous_key).await;
+    }
+
+    // セッションを更新（他の端末のセッションも次のリクエストで更新される）
+    let user_id = session_user.id;
+    let updated_session_user = SessionUser {
+        profile_image_url: Some(profile_image_url.clone()),
+        ..session_user
+    };
+    replace_current_user(&session, updated_session_user)
+        .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+    bump_session_epoch(&store, &config, user_id).await;
+
+    tracing::info!("[PROFILE IMAGE] user_id={} uploaded {}", user_id, prefix);
+    Ok(HttpResponse::Ok().json(ProfileImageResponse {
+        profile_image_url,
+        variants: VARIANT_SIZES
+            .iter()
+            .map(|&size| ProfileImageVariant {
+                size,
+                url: storage.public_url(&variant_key(&prefix, size)),
+            })
+            .collect(),
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(upload_profile_image);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut data = Vec::new();
+        image.write_to(&mut Cursor::new(&mut data), format).unwrap();
+        data
+    }
+
+    #[test]
+    fn build_variants_makes_square_jpegs_largest_first() {
+        let data = encode(DynamicImage::new_rgba8(300, 120), ImageFormat::Png);
+        let variants = build_variants(&data, ImageFormat::Png).unwrap();
+
+        let sizes: Vec<u32> = variants.iter().map(|(size, _)| *size).collect();
+        assert_eq!(sizes, VARIANT_SIZES);
+        for (size, jpeg) in &variants {
+            let decoded = image::load_from_memory_with_format(jpeg, ImageFormat::Jpeg).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (*size, *size));
+        }
+    }
+
+    #[test]
+    fn build_variants_rejects_mismatched_format() {
+        let data = encode(DynamicImage::new_rgb8(10, 10), ImageFormat::Png);
+        assert!(build_variants(&data, ImageFormat::Jpeg).is_err());
+        assert!(build_variants(b"not an image", ImageFormat::Png).is_err());
+    }
+
+    #[test]
+    fn transparent_pixels_are_flattened_on_white() {
+        let flattened = flatten_on_white(&DynamicImage::new_rgba8(1, 1));
+        assert_eq!(flattened.get_pixel(0, 0).0, [255, 255, 255]);
+    }
+
+    #[test]
+    fn only_supported_mime_types_are_accepted() {
+        assert_eq!(format_from_mime("image/png"), Some(ImageFormat::Png));
+        assert_eq!(format_from_mime("image/svg+xml"), None);
+    }
+}
@@ -3,19 +3,31 @@
 use actix_web::{get, web, HttpResponse};
 use serde::Serialize;
 
+use crate::captcha::Captcha;
 use crate::config::AppConfig;
 
 #[derive(Serialize)]
 struct PublicConfigResponse {
     #[serde(rename = "googleMapsApiKey")]
     google_maps_api_key: String,
+    /// CAPTCHAの種類とサイトキー（CAPTCHAを使わない場合は含めない）
+    #[serde(rename = "captchaProvider", skip_serializing_if = "Option::is_none")]
+    captcha_provider: Option<String>,
+    #[serde(rename = "captchaSiteKey", skip_serializing_if = "Option::is_none")]
+    captcha_site_key: Option<String>,
 }
 
 /// GET /api/public-config - フロント向け公開設定
 #[get("/public-config")]
-async fn get_public_config(config: web::Data<AppConfig>) -> HttpResponse {
+async fn get_public_config(
+    config: web::Data<AppConfig>,
+    captcha: web::Data<Captcha>,
+) -> HttpResponse {
+    let captcha_enabled = matches!(captcha.get_ref(), Captcha::Enabled { .. });
     HttpResponse::Ok().json(PublicConfigResponse {
         google_maps_api_key: config.google_maps_api_key.clone(),
+        captcha_provider: captcha_enabled.then(|| config.captcha_provider.clone()),
+        captcha_site_key: captcha_enabled.then(|| config.captcha_site_key.clone()),
     })
 }
 
@@ -0,0 +1,273 @@
+//! 公開プロフィールAPIハンドラ
+//!
+//! GET /api/users/{loginId}/profile は、本人がプライバシー設定で公開を選んだ場合のみ、
+//! 選んだ項目（レベル・ストリーク・パートナー・累計ボリューム）を返す。ログインは不要。
+//! 存在しないユーザーと非公開のユーザーはどちらも404とし、アカウントの有無を区別できないようにする。
+
+use actix_web::{get, put, web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::api::streak::get_or_create_settings;
+use crate::auth::login_throttle::client_ip;
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::Pet;
+use crate::error::AppError;
+use crate::shared_store::SharedStore;
+
+/// 公開プロフィールの取得上限（IPアドレスごと、1分）
+const MAX_PROFILE_VIEWS_PER_MINUTE: u64 = 60;
+
+// ============================================
+// DTOs
+// ============================================
+
+/// プライバシー設定（user_settingsの公開プロフィールの項目）
+#[derive(Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PrivacySettings {
+    /// 公開プロフィールを公開する
+    pub(crate) profile_public: bool,
+    pub(crate) show_level: bool,
+    pub(crate) show_streak: bool,
+    pub(crate) show_pet: bool,
+    pub(crate) show_total_volume: bool,
+    /// ランキングに参加する（公開プロフィールとは別に選ぶ）
+    #[serde(default)]
+    pub(crate) show_on_leaderboard: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicLevel {
+    level: i32,
+    total_exp: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicStreak {
+    current: i32,
+    best: i32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicPet {
+    name: String,
+    pet_type: Option<String>,
+    stage: i32,
+    stage_name: String,
+    level: i32,
+}
+
+/// 公開しない項目はnull
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicProfileDto {
+    login_id: String,
+    display_name: Option<String>,
+    profile_image_url: Option<String>,
+    level: Option<PublicLevel>,
+    /// トレーニングのストリーク
+    streak: Option<PublicStreak>,
+    /// アクティブなパートナー
+    pet: Option<PublicPet>,
+    /// 累計ボリューム（kg、ウォームアップを除く）
+    total_volume: Option<f64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PublicUserRow {
+    id: i64,
+    login_id: String,
+    display_name: Option<String>,
+    profile_image_url: Option<String>,
+    show_level: bool,
+    show_streak: bool,
+    show_pet: bool,
+    show_total_volume: bool,
+}
+
+// ============================================
+// 公開項目の取得
+// ============================================
+
+async fn fetch_public_level(pool: &MySqlPool, user_id: i64) -> Result<PublicLevel, AppError> {
+    let stats: Option<(i32, i64)> =
+        sqlx::query_as("SELECT level, total_exp FROM user_stats WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    let (level, total_exp) = stats.unwrap_or((1, 0));
+    Ok(PublicLevel { level, total_exp })
+}
+
+async fn fetch_public_streak(pool: &MySqlPool, user_id: i64) -> Result<PublicStreak, AppError> {
+    let streak: Option<(i32, i32)> = sqlx::query_as(
+        r#"SELECT current_streak, best_streak FROM user_streaks
+           WHERE user_id = ? AND streak_type = 'training'"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    let (current, best) = streak.unwrap_or((0, 0));
+    Ok(PublicStreak { current, best })
+}
+
+async fn fetch_public_pet(pool: &MySqlPool, user_id: i64) -> Result<Option<PublicPet>, AppError> {
+    let pet: Option<(String, Option<String>, i32, i32)> = sqlx::query_as(
+        r#"SELECT p.name, CAST(pt.name AS CHAR), p.stage, p.level
+           FROM pets p
+           LEFT JOIN pet_types pt ON pt.id = p.pet_type_id
+           WHERE p.user_id = ? AND p.is_active = TRUE AND p.released_at IS NULL
+           LIMIT 1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(pet.map(|(name, pet_type, stage, level)| PublicPet {
+        name,
+        pet_type,
+        stage,
+        stage_name: Pet::get_stage_name(stage).to_string(),
+        level,
+    }))
+}
+
+async fn fetch_total_volume(pool: &MySqlPool, user_id: i64) -> Result<f64, AppError> {
+    let volume: Option<f64> = sqlx::query_scalar(
+        r#"SELECT SUM(ts.weight * ts.reps) FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ? AND ts.set_type <> 'warmup'"#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(volume.unwrap_or(0.0))
+}
+
+async fn fetch_privacy_settings(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<PrivacySettings, AppError> {
+    get_or_create_settings(&mut *pool.acquire().await?, user_id).await?;
+    let settings = sqlx::query_as(
+        r#"SELECT profile_public, show_level, show_streak, show_pet, show_total_volume,
+                  show_on_leaderboard
+           FROM user_settings WHERE user_id = ?"#,
+    )
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(settings)
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/users/{loginId}/profile
+/// 公開プロフィール（ログイン不要）
+#[get("/users/{login_id}/profile")]
+async fn get_public_profile(
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    req: HttpRequest,
+    path: web::Path<String>,
+) -> Result<HttpResponse, AppError> {
+    store
+        .check_rate_limit(
+            &format!("public_profile:{}", client_ip(&req)),
+            MAX_PROFILE_VIEWS_PER_MINUTE,
+            std::time::Duration::from_secs(60),
+        )
+        .await?;
+
+    let user: PublicUserRow = sqlx::query_as(
+        r#"SELECT u.id, u.login_id, u.display_name, u.profile_image_url,
+                  s.show_level, s.show_streak, s.show_pet, s.show_total_volume
+           FROM users u
+           INNER JOIN user_settings s ON s.user_id = u.id
+           WHERE u.login_id = ? AND s.profile_public = TRUE AND u.deactivated_at IS NULL"#,
+    )
+    .bind(path.into_inner())
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| AppError::NotFound("Profile not found".to_string()))?;
+
+    let pool = pool.get_ref();
+    let level = match user.show_level {
+        true => Some(fetch_public_level(pool, user.id).await?),
+        false => None,
+    };
+    let streak = match user.show_streak {
+        true => Some(fetch_public_streak(pool, user.id).await?),
+        false => None,
+    };
+    let pet = match user.show_pet {
+        true => fetch_public_pet(pool, user.id).await?,
+        false => None,
+    };
+    let total_volume = match user.show_total_volume {
+        true => Some(fetch_total_volume(pool, user.id).await?),
+        false => None,
+    };
+
+    Ok(HttpResponse::Ok().json(PublicProfileDto {
+        login_id: user.login_id,
+        display_name: user.display_name,
+        profile_image_url: user.profile_image_url,
+        level,
+        streak,
+        pet,
+        total_volume,
+    }))
+}
+
+/// GET /api/settings/privacy
+#[get("/settings/privacy")]
+async fn get_privacy_settings(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let settings = fetch_privacy_settings(pool.get_ref(), session_user.id).await?;
+    Ok(HttpResponse::Ok().json(settings))
+}
+
+/// PUT /api/settings/privacy
+#[put("/settings/privacy")]
+async fn update_privacy_settings(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<PrivacySettings>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    get_or_create_settings(&mut *pool.acquire().await?, session_user.id).await?;
+    sqlx::query(
+        r#"UPDATE user_settings
+           SET profile_public = ?, show_level = ?, show_streak = ?, show_pet = ?,
+               show_total_volume = ?, show_on_leaderboard = ?, updated_at = NOW()
+           WHERE user_id = ?"#,
+    )
+    .bind(body.profile_public)
+    .bind(body.show_level)
+    .bind(body.show_streak)
+    .bind(body.show_pet)
+    .bind(body.show_total_volume)
+    .bind(body.show_on_leaderboard)
+    .bind(session_user.id)
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(body.into_inner()))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_public_profile)
+        .service(get_privacy_settings)
+        .service(update_privacy_settings);
+}
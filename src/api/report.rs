@@ -0,0 +1,445 @@
+//! レポートAPIハンドラ
+//!
+//! 月末の振り返り画面向けに、1か月分のトレーニングを集計する。
+//! 集計はSQLで行い、前月との比較も同じクエリで求める。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+/// よく行った種目の件数
+const TOP_EXERCISE_LIMIT: i64 = 5;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct MonthlyReportQuery {
+    /// YYYY-MM（未指定の場合は今月）
+    month: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct MonthSummaryDto {
+    /// 記録数
+    workouts: i64,
+    /// トレーニングした日数
+    training_days: i64,
+    /// セット数（ウォームアップを除く）
+    total_sets: i64,
+    total_reps: i64,
+    /// 総ボリューム（kg、ウォームアップを除く）
+    total_volume: f64,
+    /// 獲得EXP（管理者による変更・レベル曲線の移行・ストリークの復活を除く）
+    exp_earned: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TopExerciseDto {
+    exercise_id: i64,
+    is_custom: bool,
+    exercise_name: String,
+    sets: i64,
+    volume: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonthlyPersonalRecordDto {
+    exercise_id: i64,
+    is_custom: bool,
+    exercise_name: String,
+    record_id: i64,
+    achieved_on: String,
+    /// その記録での最高重量
+    weight: f64,
+    /// それ以前の最高重量
+    previous_best: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreakSummaryDto {
+    /// 月内で最も長く連続してトレーニングした日数
+    longest_training_run: i64,
+    /// ログインした日数
+    login_days: i64,
+    /// 現在のトレーニングストリーク
+    current_training_streak: i32,
+    /// これまでの最長トレーニングストリーク
+    best_training_streak: i32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonthComparisonDto {
+    month: String,
+    summary: MonthSummaryDto,
+    total_volume_diff: f64,
+    training_days_diff: i64,
+    total_sets_diff: i64,
+    exp_earned_diff: i64,
+    /// 総ボリュームの増減率（前月が0の場合はnull）
+    total_volume_change_rate: Option<f64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MonthlyReportResponse {
+    month: String,
+    start_date: String,
+    end_date: String,
+    summary: MonthSummaryDto,
+    top_exercises: Vec<TopExerciseDto>,
+    /// 月内に更新した最高重量の自己ベスト（初めて行った種目は含めない）
+    personal_records: Vec<MonthlyPersonalRecordDto>,
+    streak: StreakSummaryDto,
+    previous_month: MonthComparisonDto,
+}
+
+// ============================================
+// データベース型
+// ============================================
+
+#[derive(sqlx::FromRow)]
+struct MonthTotalsRow {
+    /// 1: 対象月、0: 前月
+    is_current: i64,
+    workouts: i64,
+    training_days: i64,
+    total_sets: i64,
+    total_reps: i64,
+    total_volume: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct TopExerciseRow {
+    exercise_id: i64,
+    is_custom: i64,
+    exercise_name: String,
+    sets: i64,
+    volume: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct MonthlyPersonalRecordRow {
+    exercise_id: i64,
+    is_custom: i64,
+    exercise_name: String,
+    record_id: i64,
+    record_date: NaiveDate,
+    top_weight: f64,
+    previous_best: f64,
+}
+
+// ============================================
+// ヘルパー関数
+// ============================================
+
+/// YYYY-MMを月初の日付に変換
+fn parse_month(month: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(&format!("{}-01", month.trim()), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("monthはYYYY-MM形式で指定してください".to_string()))
+}
+
+fn format_month(date: NaiveDate) -> String {
+    date.format("%Y-%m").to_string()
+}
+
+/// 増減率（基準が0の場合はNone）
+fn change_rate(current: f64, previous: f64) -> Option<f64> {
+    (previous > 0.0).then(|| (current - previous) / previous)
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/reports/monthly?month=2026-09
+/// 1か月分のトレーニングの集計と前月との比較
+#[get("/reports/monthly")]
+async fn get_monthly_report(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<MonthlyReportQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+
+    let month_start = match query.month.as_deref().filter(|m| !m.is_empty()) {
+        Some(month) => parse_month(month)?,
+        None => {
+            let today = fetch_user_today(pool.get_ref(), user_id).await?;
+            today.with_day(1).unwrap_or(today)
+        }
+    };
+    let next_month_start = month_start + Months::new(1);
+    let previous_month_start = month_start - Months::new(1);
+    let month_end = next_month_start.pred_opt().unwrap_or(month_start);
+
+    // 対象月・前月の合計
+    let totals: Vec<MonthTotalsRow> = sqlx::query_as(
+        r#"
+        SELECT
+            CAST(tr.record_date >= ? AS SIGNED) AS is_current,
+            COUNT(DISTINCT tr.id) AS workouts,
+            COUNT(DISTINCT tr.record_date) AS training_days,
+            COUNT(ts.id) AS total_sets,
+            CAST(COALESCE(SUM(ts.reps), 0) AS SIGNED) AS total_reps,
+            COALESCE(SUM(ts.weight * ts.reps), 0) AS total_volume
+        FROM training_records tr
+        INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+        INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+        WHERE tr.user_id = ?
+          AND tr.record_date >= ?
+          AND tr.record_date < ?
+          AND ts.set_type <> 'warmup'
+        GROUP BY is_current
+        "#,
+    )
+    .bind(month_start)
+    .bind(user_id)
+    .bind(previous_month_start)
+    .bind(next_month_start)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    // 対象月・前月の獲得EXP（ランキングの週間EXPと同じ対象）
+    let (exp_earned, previous_exp_earned): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            CAST(COALESCE(SUM(CASE WHEN created_at >= ? THEN amount END), 0) AS SIGNED),
+            CAST(COALESCE(SUM(CASE WHEN created_at < ? THEN amount END), 0) AS SIGNED)
+        FROM exp_transactions
+        WHERE user_id = ?
+          AND created_at >= ?
+          AND created_at < ?
+          AND source NOT IN ('admin', 'level_curve', 'streak_recovery')
+        "#,
+    )
+    .bind(month_start)
+    .bind(month_start)
+    .bind(user_id)
+    .bind(previous_month_start)
+    .bind(next_month_start)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    let summary_of = |is_current: i64, exp_earned: i64| {
+        totals
+            .iter()
+            .find(|t| t.is_current == is_current)
+            .map(|t| MonthSummaryDto {
+                workouts: t.workouts,
+                training_days: t.training_days,
+                total_sets: t.total_sets,
+                total_reps: t.total_reps,
+                total_volume: t.total_volume,
+                exp_earned,
+            })
+            .unwrap_or(MonthSummaryDto {
+                exp_earned,
+                ..Default::default()
+            })
+    };
+    let summary = summary_of(1, exp_earned);
+    let previous = summary_of(0, previous_exp_earned);
+
+    // ボリュームの多い種目
+    let top_exercises: Vec<TopExerciseRow> = sqlx::query_as(
+        r#"
+        SELECT
+            CAST(COALESCE(tre.custom_exercise_id, tre.exercise_id) AS SIGNED) AS exercise_id,
+            CAST(tre.custom_exercise_id IS NOT NULL AS SIGNED) AS is_custom,
+            CAST(COALESCE(e.name, uce.name, 'Unknown') AS CHAR) AS exercise_name,
+            COUNT(ts.id) AS sets,
+            COALESCE(SUM(ts.weight * ts.reps), 0) AS volume
+        FROM training_records tr
+        INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+        INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+        LEFT JOIN exercises e ON e.id = tre.exercise_id
+        LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+        WHERE tr.user_id = ?
+          AND tr.record_date >= ?
+          AND tr.record_date < ?
+          AND ts.set_type <> 'warmup'
+        GROUP BY exercise_id, is_custom, exercise_name
+        ORDER BY volume DESC, sets DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(month_start)
+    .bind(next_month_start)
+    .bind(TOP_EXERCISE_LIMIT)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    // 記録ごとの最高重量を、それ以前の記録の最高重量と比較する
+    let personal_records: Vec<MonthlyPersonalRecordRow> = sqlx::query_as(
+        r#"
+        SELECT exercise_id, is_custom, exercise_name, record_id, record_date,
+               top_weight, previous_best
+        FROM (
+            SELECT per_record.*,
+                   MAX(top_weight) OVER (
+                       PARTITION BY is_custom, exercise_id
+                       ORDER BY record_date, record_id
+                       ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING
+                   ) AS previous_best
+            FROM (
+                SELECT
+                    CAST(COALESCE(tre.custom_exercise_id, tre.exercise_id) AS SIGNED) AS exercise_id,
+                    CAST(tre.custom_exercise_id IS NOT NULL AS SIGNED) AS is_custom,
+                    CAST(COALESCE(e.name, uce.name, 'Unknown') AS CHAR) AS exercise_name,
+                    tr.id AS record_id,
+                    tr.record_date,
+                    MAX(ts.weight) AS top_weight
+                FROM training_records tr
+                INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+                INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+                LEFT JOIN exercises e ON e.id = tre.exercise_id
+                LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+                WHERE tr.user_id = ?
+                  AND tr.record_date < ?
+                  AND ts.set_type <> 'warmup'
+                  AND ts.weight > 0
+                GROUP BY exercise_id, is_custom, exercise_name, tr.id, tr.record_date
+            ) per_record
+        ) history
+        WHERE record_date >= ?
+          AND previous_best IS NOT NULL
+          AND top_weight > previous_best
+        ORDER BY record_date, exercise_name
+        "#,
+    )
+    .bind(user_id)
+    .bind(next_month_start)
+    .bind(month_start)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    // 月内の連続トレーニング日数（日付から連番を引いた値が同じ日は連続している）
+    let longest_training_run: i64 = sqlx::query_scalar(
+        r#"
+        SELECT CAST(COALESCE(MAX(run_length), 0) AS SIGNED)
+        FROM (
+            SELECT COUNT(*) AS run_length
+            FROM (
+                SELECT DATE_SUB(record_date, INTERVAL ROW_NUMBER() OVER (ORDER BY record_date) DAY) AS run_key
+                FROM (
+                    SELECT DISTINCT record_date
+                    FROM training_records
+                    WHERE user_id = ? AND record_date >= ? AND record_date < ?
+                ) days
+            ) numbered
+            GROUP BY run_key
+        ) runs
+        "#,
+    )
+    .bind(user_id)
+    .bind(month_start)
+    .bind(next_month_start)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    let login_days: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*) FROM user_login_history
+           WHERE user_id = ? AND login_date >= ? AND login_date < ?"#,
+    )
+    .bind(user_id)
+    .bind(month_start)
+    .bind(next_month_start)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    let (current_training_streak, best_training_streak): (i32, i32) = sqlx::query_as(
+        "SELECT current_streak, best_streak FROM user_streaks WHERE user_id = ? AND streak_type = 'training'",
+    )
+    .bind(user_id)
+    .fetch_optional(pool.get_ref())
+    .await?
+    .unwrap_or((0, 0));
+
+    let previous_month = MonthComparisonDto {
+        month: format_month(previous_month_start),
+        total_volume_diff: summary.total_volume - previous.total_volume,
+        training_days_diff: summary.training_days - previous.training_days,
+        total_sets_diff: summary.total_sets - previous.total_sets,
+        exp_earned_diff: summary.exp_earned - previous.exp_earned,
+        total_volume_change_rate: change_rate(summary.total_volume, previous.total_volume),
+        summary: previous,
+    };
+
+    Ok(HttpResponse::Ok().json(MonthlyReportResponse {
+        month: format_month(month_start),
+        start_date: month_start.format("%Y-%m-%d").to_string(),
+        end_date: month_end.format("%Y-%m-%d").to_string(),
+        summary,
+        top_exercises: top_exercises
+            .into_iter()
+            .map(|row| TopExerciseDto {
+                exercise_id: row.exercise_id,
+                is_custom: row.is_custom != 0,
+                exercise_name: row.exercise_name,
+                sets: row.sets,
+                volume: row.volume,
+            })
+            .collect(),
+        personal_records: personal_records
+            .into_iter()
+            .map(|row| MonthlyPersonalRecordDto {
+                exercise_id: row.exercise_id,
+                is_custom: row.is_custom != 0,
+                exercise_name: row.exercise_name,
+                record_id: row.record_id,
+                achieved_on: row.record_date.format("%Y-%m-%d").to_string(),
+                weight: row.top_weight,
+                previous_best: row.previous_best,
+            })
+            .collect(),
+        streak: StreakSummaryDto {
+            longest_training_run,
+            login_days,
+            current_training_streak,
+            best_training_streak,
+        },
+        previous_month,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_monthly_report);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_month_returns_the_first_day() {
+        assert_eq!(
+            parse_month(" 2026-09 ").unwrap(),
+            NaiveDate::from_ymd_opt(2026, 9, 1).unwrap()
+        );
+        assert_eq!(format_month(parse_month("2026-01").unwrap()), "2026-01");
+        for invalid in ["2026-13", "2026/09", "202609", ""] {
+            assert!(parse_month(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn change_rate_is_relative_to_the_previous_month() {
+        assert_eq!(change_rate(150.0, 100.0), Some(0.5));
+        assert_eq!(change_rate(50.0, 100.0), Some(-0.5));
+        assert_eq!(change_rate(50.0, 0.0), None);
+    }
+}
@@ -0,0 +1,302 @@
+//! ショップ・所持アイテムAPIハンドラ
+//!
+//! コインでアイテム（ごはん・おもちゃ・ストリークシールド）を購入する。
+//! ごはん・おもちゃはペットのお世話、ストリークシールドはトレーニングストリークの保護に使う。
+
+use actix_web::{get, post, web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::coin::{fetch_coin_balance, spend_coins, CoinBalanceDto, COIN_SOURCE_SHOP};
+use crate::auth::session::{get_current_user, Session};
+use crate::db::models::Item;
+use crate::error::AppError;
+
+/// アイテムの種類
+pub(crate) const ITEM_CATEGORY_FOOD: &str = "food";
+pub(crate) const ITEM_CATEGORY_TOY: &str = "toy";
+pub(crate) const ITEM_CATEGORY_STREAK_PROTECTION: &str = "streak_protection";
+
+/// 1回に購入できる個数の上限
+const MAX_PURCHASE_QUANTITY: i32 = 10;
+
+const ITEM_COLUMNS: &str = "i.id, i.code, i.name, i.description, i.category, i.price, \
+                            i.mood_boost, i.image_path, i.display_order, i.is_active";
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemDto {
+    id: i32,
+    code: String,
+    name: String,
+    description: Option<String>,
+    category: String,
+    price: i32,
+    mood_boost: Option<i32>,
+    image_path: Option<String>,
+    /// 所持数
+    owned: i32,
+}
+
+impl ItemDto {
+    fn new(item: Item, owned: i32) -> Self {
+        Self {
+            id: item.id,
+            code: item.code,
+            name: item.name,
+            description: item.description,
+            category: item.category,
+            price: item.price,
+            mood_boost: item.mood_boost,
+            image_path: item.image_path,
+            owned,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ShopResponse {
+    coins: CoinBalanceDto,
+    items: Vec<ItemDto>,
+}
+
+#[derive(Deserialize)]
+struct PurchaseRequest {
+    #[serde(rename = "itemCode")]
+    item_code: String,
+    #[serde(default = "default_quantity")]
+    quantity: i32,
+}
+
+fn default_quantity() -> i32 {
+    1
+}
+
+#[derive(Serialize)]
+struct PurchaseResponse {
+    item: ItemDto,
+    coins: CoinBalanceDto,
+}
+
+#[derive(sqlx::FromRow)]
+struct OwnedItemRow {
+    #[sqlx(flatten)]
+    item: Item,
+    quantity: i32,
+}
+
+// ============================================
+// アイテムの取得・消費
+// ============================================
+
+/// コードからアイテムを取得（販売終了したアイテムも所持品として使えるよう含める）
+pub(crate) async fn find_item_by_code(
+    conn: &mut MySqlConnection,
+    code: &str,
+) -> Result<Option<Item>, AppError> {
+    let item = sqlx::query_as(&format!(
+        "SELECT {} FROM items i WHERE i.code = ?",
+        ITEM_COLUMNS
+    ))
+    .bind(code)
+    .fetch_optional(conn)
+    .await?;
+    Ok(item)
+}
+
+/// 所持アイテムを追加する
+pub(crate) async fn add_item(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    item_id: i32,
+    quantity: i32,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"INSERT INTO user_inventory (user_id, item_id, quantity, updated_at)
+           VALUES (?, ?, ?, NOW())
+           ON DUPLICATE KEY UPDATE quantity = quantity + VALUES(quantity), updated_at = NOW()"#,
+    )
+    .bind(user_id)
+    .bind(item_id)
+    .bind(quantity)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// 所持アイテムを消費する（所持数が足りない場合はfalse）
+pub(crate) async fn consume_item(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    item_id: i32,
+    quantity: i32,
+) -> Result<bool, AppError> {
+    let result = sqlx::query(
+        r#"UPDATE user_inventory SET quantity = quantity - ?, updated_at = NOW()
+           WHERE user_id = ? AND item_id = ? AND quantity >= ?"#,
+    )
+    .bind(quantity)
+    .bind(user_id)
+    .bind(item_id)
+    .bind(quantity)
+    .execute(conn)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// ストリーク保護アイテムを指定日数分消費する（所持数が足りない場合は消費せずfalse）
+pub(crate) async fn consume_streak_protection(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    days: i64,
+) -> Result<bool, AppError> {
+    let item_id: Option<i32> = sqlx::query_scalar(
+        r#"SELECT ui.item_id FROM user_inventory ui
+           INNER JOIN items i ON i.id = ui.item_id
+           WHERE ui.user_id = ? AND i.category = ? AND ui.quantity >= ?
+           ORDER BY i.display_order ASC LIMIT 1
+           FOR UPDATE"#,
+    )
+    .bind(user_id)
+    .bind(ITEM_CATEGORY_STREAK_PROTECTION)
+    .bind(days)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some(item_id) = item_id else {
+        return Ok(false);
+    };
+    consume_item(conn, user_id, item_id, days as i32).await
+}
+
+/// アイテムの所持数
+async fn owned_quantity(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    item_id: i32,
+) -> Result<i32, AppError> {
+    let quantity: Option<i32> =
+        sqlx::query_scalar("SELECT quantity FROM user_inventory WHERE user_id = ? AND item_id = ?")
+            .bind(user_id)
+            .bind(item_id)
+            .fetch_optional(conn)
+            .await?;
+    Ok(quantity.unwrap_or(0))
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/shop/items
+/// 販売中のアイテム一覧（所持数とコイン残高を含む）
+#[get("/shop/items")]
+async fn get_shop_items(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let mut conn = pool.acquire().await?;
+
+    let rows: Vec<OwnedItemRow> = sqlx::query_as(&format!(
+        r#"SELECT {}, COALESCE(ui.quantity, 0) AS quantity
+           FROM items i
+           LEFT JOIN user_inventory ui ON ui.item_id = i.id AND ui.user_id = ?
+           WHERE i.is_active = TRUE
+           ORDER BY i.display_order ASC, i.id ASC"#,
+        ITEM_COLUMNS
+    ))
+    .bind(session_user.id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let coins = fetch_coin_balance(&mut conn, session_user.id).await?;
+    Ok(HttpResponse::Ok().json(ShopResponse {
+        coins,
+        items: rows
+            .into_iter()
+            .map(|r| ItemDto::new(r.item, r.quantity))
+            .collect(),
+    }))
+}
+
+/// GET /api/shop/inventory
+/// 所持しているアイテム一覧
+#[get("/shop/inventory")]
+async fn get_inventory(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let rows: Vec<OwnedItemRow> = sqlx::query_as(&format!(
+        r#"SELECT {}, ui.quantity
+           FROM user_inventory ui
+           INNER JOIN items i ON i.id = ui.item_id
+           WHERE ui.user_id = ? AND ui.quantity > 0
+           ORDER BY i.display_order ASC, i.id ASC"#,
+        ITEM_COLUMNS
+    ))
+    .bind(session_user.id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let items: Vec<ItemDto> = rows
+        .into_iter()
+        .map(|r| ItemDto::new(r.item, r.quantity))
+        .collect();
+    Ok(HttpResponse::Ok().json(items))
+}
+
+/// POST /api/shop/purchase
+/// コインでアイテムを購入する
+#[post("/shop/purchase")]
+async fn purchase_item(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<PurchaseRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    if !(1..=MAX_PURCHASE_QUANTITY).contains(&body.quantity) {
+        return Err(AppError::BadRequest(format!(
+            "購入数は1〜{}で指定してください",
+            MAX_PURCHASE_QUANTITY
+        )));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let item = find_item_by_code(&mut tx, &body.item_code)
+        .await?
+        .filter(|item| item.is_active)
+        .ok_or_else(|| AppError::NotFound("Item not found".to_string()))?;
+
+    let coins = spend_coins(
+        &mut tx,
+        session_user.id,
+        item.price as i64 * body.quantity as i64,
+        COIN_SOURCE_SHOP,
+        Some(item.id as i64),
+    )
+    .await?;
+
+    add_item(&mut tx, session_user.id, item.id, body.quantity).await?;
+
+    let owned = owned_quantity(&mut tx, session_user.id, item.id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(PurchaseResponse {
+        item: ItemDto::new(item, owned),
+        coins,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_shop_items)
+        .service(get_inventory)
+        .service(purchase_item);
+}
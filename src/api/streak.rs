@@ -1,13 +1,25 @@
 //! ストリークとログインボーナスAPIハンドラ
 
-use actix_session::Session;
-use actix_web::{get, post, web, HttpResponse};
-use chrono::{NaiveDate, Utc};
+use actix_web::{get, post, put, web, HttpResponse};
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
-
-use crate::auth::session::get_current_user;
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::dashboard::{
+    fetch_set_targets, HEATMAP_MODE_ADAPTIVE, HEATMAP_MODE_FIXED, MUSCLE_GROUPS,
+};
+use crate::api::achievement::{award_achievements, AchievementDto, ACHIEVEMENT_LOGIN_STREAK};
+use crate::api::coin::{
+    grant_coins, spend_coins, COIN_SOURCE_LOGIN_BONUS, COIN_SOURCE_STREAK_RECOVERY, LOGIN_BONUS_COINS,
+};
+use crate::api::exp_context::ExpContext;
+use crate::api::exp_ledger::{award_exp, EXP_SOURCE_LOGIN_BONUS, EXP_SOURCE_STREAK_RECOVERY};
+use crate::api::shop::consume_streak_protection;
+use crate::auth::session::{get_current_user, Session};
 use crate::db::models::{UserLoginHistory, UserSettings, UserStreak};
+use crate::domain::timezone::{self, DEFAULT_TIMEZONE};
+use crate::domain::weight_unit::WeightUnit;
 use crate::error::AppError;
 
 // ============================================
@@ -36,6 +48,30 @@ pub struct StreakInfo {
     pub grace_days_used: i32,
     #[serde(rename = "graceDaysAllowed")]
     pub grace_days_allowed: i32,
+    /// これまでに消費したストリークシールドの数
+    #[serde(rename = "shieldsUsed")]
+    pub shields_used: i32,
+    /// 最後にストリークシールドでカバーした日
+    #[serde(rename = "lastShieldUsedDate")]
+    pub last_shield_used_date: Option<String>,
+}
+
+impl StreakInfo {
+    fn new(streak: UserStreak, grace_days_allowed: i32) -> Self {
+        Self {
+            current: streak.current_streak,
+            best: streak.best_streak,
+            last_active_date: streak
+                .last_active_date
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+            grace_days_used: streak.grace_days_used,
+            grace_days_allowed,
+            shields_used: streak.shields_used,
+            last_shield_used_date: streak
+                .last_shield_used_date
+                .map(|d| d.format("%Y-%m-%d").to_string()),
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -49,31 +85,126 @@ pub struct LoginBonusResponse {
     pub current_login_streak: i32,
     #[serde(rename = "totalExp")]
     pub total_exp: i64,
+    #[serde(rename = "coinsEarned")]
+    pub coins_earned: i64,
+    /// 受け取りで新たに達成した実績
+    #[serde(rename = "newAchievements", skip_serializing_if = "Vec::is_empty")]
+    pub new_achievements: Vec<AchievementDto>,
 }
 
 #[derive(Serialize)]
 pub struct SettingsResponse {
     #[serde(rename = "graceDaysAllowed")]
     pub grace_days_allowed: i32,
+    #[serde(rename = "heatmapMode")]
+    pub heatmap_mode: String,
+    /// 重量の単位（kg / lb）
+    #[serde(rename = "weightUnit")]
+    pub weight_unit: String,
+    /// 日付の境界を判定するタイムゾーン（IANA名）
+    pub timezone: String,
 }
 
 #[derive(Deserialize)]
 pub struct UpdateSettingsRequest {
     #[serde(rename = "graceDaysAllowed")]
     pub grace_days_allowed: i32,
+    #[serde(rename = "heatmapMode")]
+    pub heatmap_mode: Option<String>,
+    #[serde(rename = "weightUnit")]
+    pub weight_unit: Option<String>,
+    pub timezone: Option<String>,
+}
+
+/// ストリークの修復に使う支払い方法
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecoveryPayment {
+    Coins,
+    Exp,
+}
+
+impl RecoveryPayment {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Coins => "coins",
+            Self::Exp => "exp",
+        }
+    }
+
+    fn cost(self) -> i64 {
+        match self {
+            Self::Coins => STREAK_RECOVERY_COIN_COST,
+            Self::Exp => STREAK_RECOVERY_EXP_COST,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverStreakRequest {
+    /// training / login
+    pub streak_type: String,
+    pub pay_with: RecoveryPayment,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverStreakResponse {
+    pub streak_type: String,
+    pub current_streak: i32,
+    pub best_streak: i32,
+    pub pay_with: &'static str,
+    pub cost: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct BrokenStreakRow {
+    current_streak: i32,
+    best_streak: i32,
+    broken_streak: Option<i32>,
+    broken_missed_date: Option<NaiveDate>,
+    /// 途切れてから修復期限（STREAK_RECOVERY_HOURS）内か
+    recoverable: bool,
 }
 
+/// 筋肉グループごとの週間セット数目標
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetTargetDto {
+    pub muscle: String,
+    pub min_sets: i32,
+    pub max_sets: i32,
+}
+
+/// 週間セット数目標の上限
+const MAX_WEEKLY_SETS: i32 = 50;
+/// 中休み許容日数の上限
+pub(crate) const MAX_GRACE_DAYS: i32 = 3;
+
+/// 途切れたストリークを修復できる期限（時間）
+const STREAK_RECOVERY_HOURS: i64 = 48;
+/// ストリークの修復に必要なコイン
+const STREAK_RECOVERY_COIN_COST: i64 = 100;
+/// ストリークの修復に必要なEXP（コインの代わりに累計EXPから差し引く）
+const STREAK_RECOVERY_EXP_COST: i64 = 1000;
+
 // ============================================
 // ヘルパー関数
 // ============================================
 
 /// ユーザー設定を取得または作成
-async fn get_or_create_settings(pool: &MySqlPool, user_id: i64) -> Result<UserSettings, AppError> {
+pub(crate) async fn get_or_create_settings(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<UserSettings, AppError> {
     let settings: Option<UserSettings> = sqlx::query_as(
-        "SELECT id, user_id, grace_days_allowed, created_at, updated_at FROM user_settings WHERE user_id = ?",
+        r#"SELECT id, user_id, grace_days_allowed, heatmap_mode, weight_unit, timezone,
+                  created_at, updated_at
+           FROM user_settings WHERE user_id = ?"#,
     )
     .bind(user_id)
-    .fetch_optional(pool)
+    .fetch_optional(&mut *conn)
     .await?;
 
     match settings {
@@ -84,13 +215,16 @@ async fn get_or_create_settings(pool: &MySqlPool, user_id: i64) -> Result<UserSe
                 "INSERT INTO user_settings (user_id, grace_days_allowed, created_at, updated_at) VALUES (?, 1, NOW(), NOW())",
             )
             .bind(user_id)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
             Ok(UserSettings {
                 id: 0,
                 user_id,
                 grace_days_allowed: 1,
+                heatmap_mode: HEATMAP_MODE_ADAPTIVE.to_string(),
+                weight_unit: WeightUnit::default().name().to_string(),
+                timezone: DEFAULT_TIMEZONE.name().to_string(),
                 created_at: None,
                 updated_at: None,
             })
@@ -98,6 +232,68 @@ async fn get_or_create_settings(pool: &MySqlPool, user_id: i64) -> Result<UserSe
     }
 }
 
+/// ヒートマップモードを検証する
+pub(crate) fn parse_heatmap_mode(mode: &str) -> Result<String, AppError> {
+    match mode {
+        HEATMAP_MODE_ADAPTIVE | HEATMAP_MODE_FIXED => Ok(mode.to_string()),
+        _ => Err(AppError::BadRequest(
+            "heatmapModeはADAPTIVEまたはFIXEDを指定してください".to_string(),
+        )),
+    }
+}
+
+/// 重量の単位を検証する（kg / lb）
+pub(crate) fn parse_weight_unit(unit: &str) -> Result<String, AppError> {
+    WeightUnit::parse(unit)
+        .map(|unit| unit.name().to_string())
+        .ok_or_else(|| AppError::BadRequest("weightUnitはkgまたはlbを指定してください".to_string()))
+}
+
+/// タイムゾーン名を検証する（IANA名）
+pub(crate) fn parse_timezone(name: &str) -> Result<String, AppError> {
+    timezone::parse(name)
+        .map(|tz| tz.name().to_string())
+        .ok_or_else(|| {
+            AppError::BadRequest(
+                "timezoneにはタイムゾーン名（例: Asia/Tokyo）を指定してください".to_string(),
+            )
+        })
+}
+
+/// ユーザーの重量の単位（user_settings未作成時はkg）
+pub(crate) async fn fetch_weight_unit(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<WeightUnit, AppError> {
+    let unit: Option<String> =
+        sqlx::query_scalar("SELECT weight_unit FROM user_settings WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(unit
+        .as_deref()
+        .and_then(WeightUnit::parse)
+        .unwrap_or_default())
+}
+
+/// ユーザーのタイムゾーン（user_settings未作成時は日本時間）
+pub(crate) async fn fetch_timezone(pool: &MySqlPool, user_id: i64) -> Result<Tz, AppError> {
+    let name: Option<String> =
+        sqlx::query_scalar("SELECT timezone FROM user_settings WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(name.as_deref().map_or(DEFAULT_TIMEZONE, timezone::from_setting))
+}
+
+/// ユーザーのタイムゾーンでの今日の日付
+pub(crate) async fn fetch_user_today(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<NaiveDate, AppError> {
+    Ok(timezone::today_in(fetch_timezone(pool, user_id).await?))
+}
+
 // ============================================
 // 倍率計算（他モジュールから公開）
 // ============================================
@@ -118,34 +314,20 @@ pub fn calculate_combined_multiplier(training_streak: i32, login_streak: i32) ->
     1.0 + calculate_training_multiplier(training_streak) + calculate_login_multiplier(login_streak)
 }
 
-/// ユーザーの倍率を取得（DBからストリークを取得）
-pub async fn get_user_multipliers(
-    pool: &MySqlPool,
-    user_id: i64,
-) -> Result<(f64, f64, f64), AppError> {
-    let training_streak = get_or_create_streak(pool, user_id, "training").await?;
-    let login_streak = get_or_create_streak(pool, user_id, "login").await?;
-
-    let training_mult = calculate_training_multiplier(training_streak.current_streak);
-    let login_mult = calculate_login_multiplier(login_streak.current_streak);
-    let combined = 1.0 + training_mult + login_mult;
-
-    Ok((training_mult, login_mult, combined))
-}
-
 /// ストリークレコードを取得または作成
 pub async fn get_or_create_streak(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     user_id: i64,
     streak_type: &str,
 ) -> Result<UserStreak, AppError> {
     let streak: Option<UserStreak> = sqlx::query_as(
-        "SELECT id, user_id, streak_type, current_streak, best_streak, last_active_date, grace_days_used, created_at, updated_at 
+        "SELECT id, user_id, streak_type, current_streak, best_streak, last_active_date, grace_days_used,
+                shields_used, last_shield_used_date, created_at, updated_at
          FROM user_streaks WHERE user_id = ? AND streak_type = ?",
     )
     .bind(user_id)
     .bind(streak_type)
-    .fetch_optional(pool)
+    .fetch_optional(&mut *conn)
     .await?;
 
     match streak {
@@ -157,7 +339,7 @@ pub async fn get_or_create_streak(
             )
             .bind(user_id)
             .bind(streak_type)
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
 
             Ok(UserStreak {
@@ -168,6 +350,8 @@ pub async fn get_or_create_streak(
                 best_streak: 0,
                 last_active_date: None,
                 grace_days_used: 0,
+                shields_used: 0,
+                last_shield_used_date: None,
                 created_at: None,
                 updated_at: None,
             })
@@ -186,15 +370,90 @@ fn calculate_login_bonus_exp(streak: i32) -> i32 {
     base + streak_bonus + weekly_bonus
 }
 
+/// 中休みの許容日数を超えた分の日数だけストリークシールドを消費し、カバーした日を記録する
+/// 消費した数を返す（所持数が足りない場合は消費せず0）
+async fn protect_training_streak(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    last_date: NaiveDate,
+    days_since_last: i64,
+    grace_days_allowed: i32,
+) -> Result<i32, AppError> {
+    let uncovered_days = days_since_last - 1 - grace_days_allowed as i64;
+    if uncovered_days <= 0
+        || !consume_streak_protection(&mut *conn, user_id, uncovered_days).await?
+    {
+        return Ok(0);
+    }
+
+    // 休み始めの日からカバーし、残りは中休みとして扱う
+    for offset in 1..=uncovered_days {
+        sqlx::query(
+            r#"INSERT IGNORE INTO user_streak_protected_dates (user_id, protected_date, created_at)
+               VALUES (?, ?, NOW())"#,
+        )
+        .bind(user_id)
+        .bind(last_date + chrono::Duration::days(offset))
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(uncovered_days as i32)
+}
+
+/// 途切れる前のストリークを記録する（修復できない場合はNoneで以前の記録を消す）
+async fn record_streak_break(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    streak_type: &str,
+    broken_streak: Option<i32>,
+    missed_date: NaiveDate,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"UPDATE user_streaks
+           SET broken_streak = ?, broken_missed_date = ?, broken_at = NOW()
+           WHERE user_id = ? AND streak_type = ?"#,
+    )
+    .bind(broken_streak)
+    .bind(broken_streak.map(|_| missed_date))
+    .bind(user_id)
+    .bind(streak_type)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// 累計EXPを差し引く（足りない場合はBadRequest）
+async fn spend_exp(conn: &mut MySqlConnection, user_id: i64, amount: i64) -> Result<(), AppError> {
+    let total_exp: Option<i64> =
+        sqlx::query_scalar("SELECT total_exp FROM user_stats WHERE user_id = ? FOR UPDATE")
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+    let total_exp = total_exp.unwrap_or(0);
+    if total_exp < amount {
+        return Err(AppError::BadRequest("EXPが足りません".to_string()));
+    }
+
+    award_exp(
+        &mut *conn,
+        user_id,
+        -amount,
+        EXP_SOURCE_STREAK_RECOVERY,
+        None,
+    )
+    .await?;
+    Ok(())
+}
+
 /// Update streak based on activity
 async fn update_streak(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     user_id: i64,
     streak_type: &str,
     activity_date: NaiveDate,
     grace_days_allowed: i32,
 ) -> Result<UserStreak, AppError> {
-    let mut streak = get_or_create_streak(pool, user_id, streak_type).await?;
+    let mut streak = get_or_create_streak(conn, user_id, streak_type).await?;
 
     match streak.last_active_date {
         None => {
@@ -211,6 +470,13 @@ async fn update_streak(
             }
 
             let days_since_last = (activity_date - last_date).num_days();
+            // 中休みの許容日数を超えた分はストリークシールドでカバーする
+            let shields_consumed = if streak_type == "training" {
+                protect_training_streak(conn, user_id, last_date, days_since_last, grace_days_allowed)
+                    .await?
+            } else {
+                0
+            };
 
             if days_since_last == 1 {
                 // Consecutive day
@@ -221,7 +487,25 @@ async fn update_streak(
                 let grace_used = (days_since_last - 1) as i32;
                 streak.current_streak += 1;
                 streak.grace_days_used = grace_used;
+            } else if shields_consumed > 0 {
+                // ストリークシールドで休んだ日をカバー
+                streak.current_streak += 1;
+                streak.grace_days_used = grace_days_allowed;
+                streak.shields_used += shields_consumed;
+                streak.last_shield_used_date =
+                    Some(last_date + chrono::Duration::days(shields_consumed as i64));
             } else {
+                // 1日だけ超えて途切れた場合は修復できるよう途切れる前のストリークを残す
+                let recoverable = days_since_last == grace_days_allowed as i64 + 2;
+                record_streak_break(
+                    conn,
+                    user_id,
+                    streak_type,
+                    recoverable.then_some(streak.current_streak),
+                    last_date + chrono::Duration::days(1),
+                )
+                .await?;
+
                 // Streak broken - reset to 1 (counting today's activity)
                 streak.current_streak = 1;
                 streak.grace_days_used = 0;
@@ -238,16 +522,19 @@ async fn update_streak(
 
     // Save to DB
     sqlx::query(
-        "UPDATE user_streaks SET current_streak = ?, best_streak = ?, last_active_date = ?, grace_days_used = ?, updated_at = NOW() 
+        "UPDATE user_streaks SET current_streak = ?, best_streak = ?, last_active_date = ?, grace_days_used = ?,
+                shields_used = ?, last_shield_used_date = ?, updated_at = NOW()
          WHERE user_id = ? AND streak_type = ?",
     )
     .bind(streak.current_streak)
     .bind(streak.best_streak)
     .bind(streak.last_active_date)
     .bind(streak.grace_days_used)
+    .bind(streak.shields_used)
+    .bind(streak.last_shield_used_date)
     .bind(user_id)
     .bind(streak_type)
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     Ok(streak)
@@ -267,9 +554,11 @@ pub async fn get_streaks(
     let session_user = get_current_user(&session)?;
     let user_id = session_user.id;
 
-    let settings = get_or_create_settings(pool.get_ref(), user_id).await?;
-    let training_streak = get_or_create_streak(pool.get_ref(), user_id, "training").await?;
-    let login_streak = get_or_create_streak(pool.get_ref(), user_id, "login").await?;
+    let mut conn = pool.acquire().await?;
+    let settings = get_or_create_settings(&mut conn, user_id).await?;
+    let training_streak = get_or_create_streak(&mut conn, user_id, "training").await?;
+    let login_streak = get_or_create_streak(&mut conn, user_id, "login").await?;
+    drop(conn);
 
     // Calculate multipliers
     let training_multiplier = calculate_training_multiplier(training_streak.current_streak);
@@ -277,24 +566,8 @@ pub async fn get_streaks(
     let combined_multiplier = 1.0 + training_multiplier + login_multiplier;
 
     Ok(HttpResponse::Ok().json(StreakResponse {
-        training_streak: StreakInfo {
-            current: training_streak.current_streak,
-            best: training_streak.best_streak,
-            last_active_date: training_streak
-                .last_active_date
-                .map(|d| d.format("%Y-%m-%d").to_string()),
-            grace_days_used: training_streak.grace_days_used,
-            grace_days_allowed: settings.grace_days_allowed,
-        },
-        login_streak: StreakInfo {
-            current: login_streak.current_streak,
-            best: login_streak.best_streak,
-            last_active_date: login_streak
-                .last_active_date
-                .map(|d| d.format("%Y-%m-%d").to_string()),
-            grace_days_used: login_streak.grace_days_used,
-            grace_days_allowed: settings.grace_days_allowed,
-        },
+        training_streak: StreakInfo::new(training_streak, settings.grace_days_allowed),
+        login_streak: StreakInfo::new(login_streak, settings.grace_days_allowed),
         training_multiplier,
         login_multiplier,
         combined_multiplier,
@@ -310,7 +583,7 @@ pub async fn claim_login_bonus(
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
     let user_id = session_user.id;
-    let today = Utc::now().date_naive();
+    let today = fetch_user_today(pool.get_ref(), user_id).await?;
 
     // Check if already claimed today
     let existing: Option<UserLoginHistory> = sqlx::query_as(
@@ -325,7 +598,8 @@ pub async fn claim_login_bonus(
     if let Some(ref history) = existing {
         if history.bonus_claimed {
             // Already claimed
-            let login_streak = get_or_create_streak(pool.get_ref(), user_id, "login").await?;
+            let login_streak =
+                get_or_create_streak(&mut *pool.acquire().await?, user_id, "login").await?;
 
             // Get current total exp
             let stats: (i64,) =
@@ -341,20 +615,22 @@ pub async fn claim_login_bonus(
                 exp_earned: 0,
                 current_login_streak: login_streak.current_streak,
                 total_exp: stats.0,
+                coins_earned: 0,
+                new_achievements: vec![],
             }));
         }
     }
 
     // Get settings for grace days
-    let settings = get_or_create_settings(pool.get_ref(), user_id).await?;
+    let exp_context = ExpContext::load(pool.get_ref(), user_id).await?;
 
     // Update login streak
     let login_streak = update_streak(
-        pool.get_ref(),
+        &mut *pool.acquire().await?,
         user_id,
         "login",
         today,
-        settings.grace_days_allowed,
+        exp_context.grace_days_allowed,
     )
     .await?;
 
@@ -383,35 +659,38 @@ pub async fn claim_login_bonus(
     }
 
     // Add EXP to user_stats
-    sqlx::query(
-        "UPDATE user_stats SET total_exp = total_exp + ?, updated_at = NOW() WHERE user_id = ?",
+    let change = award_exp(
+        &mut *pool.acquire().await?,
+        user_id,
+        exp_earned as i64,
+        EXP_SOURCE_LOGIN_BONUS,
+        None,
+    )
+    .await?;
+    grant_coins(
+        &mut *pool.acquire().await?,
+        user_id,
+        LOGIN_BONUS_COINS,
+        COIN_SOURCE_LOGIN_BONUS,
+        None,
     )
-    .bind(exp_earned)
-    .bind(user_id)
-    .execute(pool.get_ref())
     .await?;
 
-    // Recalculate level
-    use crate::db::models::UserStats;
-    let stats: (i64,) =
-        sqlx::query_as("SELECT COALESCE(total_exp, 0) FROM user_stats WHERE user_id = ?")
-            .bind(user_id)
-            .fetch_one(pool.get_ref())
-            .await?;
-
-    let new_level = UserStats::calculate_level(stats.0);
-    sqlx::query("UPDATE user_stats SET level = ? WHERE user_id = ?")
-        .bind(new_level)
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
+    let new_achievements = award_achievements(
+        &mut *pool.acquire().await?,
+        user_id,
+        &[ACHIEVEMENT_LOGIN_STREAK],
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(LoginBonusResponse {
         success: true,
         already_claimed: false,
         exp_earned,
         current_login_streak: login_streak.current_streak,
-        total_exp: stats.0,
+        total_exp: change.total_exp,
+        coins_earned: LOGIN_BONUS_COINS,
+        new_achievements,
     }))
 }
 
@@ -423,12 +702,13 @@ pub async fn record_login(
     session: Session,
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
-    let today = Utc::now().date_naive();
-    let settings = get_or_create_settings(pool.get_ref(), session_user.id).await?;
+    let mut conn = pool.acquire().await?;
+    let settings = get_or_create_settings(&mut conn, session_user.id).await?;
+    let today = timezone::today_in(timezone::from_setting(&settings.timezone));
 
     // Update login streak only (no EXP)
     let login_streak = update_streak(
-        pool.get_ref(),
+        &mut conn,
         session_user.id,
         "login",
         today,
@@ -442,6 +722,119 @@ pub async fn record_login(
     })))
 }
 
+/// POST /api/streak/recover
+/// 1日だけ途切れたストリークを、途切れてから48時間以内にコインまたはEXPを払って元に戻す
+#[post("/streak/recover")]
+pub async fn recover_streak(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<RecoverStreakRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let user_id = session_user.id;
+    let streak_type = body.streak_type.as_str();
+    if !matches!(streak_type, "training" | "login") {
+        return Err(AppError::BadRequest(
+            "streakTypeはtrainingまたはloginを指定してください".to_string(),
+        ));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    let row: Option<BrokenStreakRow> = sqlx::query_as(&format!(
+        r#"SELECT current_streak, best_streak, broken_streak, broken_missed_date,
+                  COALESCE(broken_at >= NOW() - INTERVAL {} HOUR, FALSE) AS recoverable
+           FROM user_streaks WHERE user_id = ? AND streak_type = ?
+           FOR UPDATE"#,
+        STREAK_RECOVERY_HOURS
+    ))
+    .bind(user_id)
+    .bind(streak_type)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let no_recoverable_streak =
+        || AppError::BadRequest("修復できるストリークがありません".to_string());
+    let row = row
+        .filter(|r| r.recoverable)
+        .ok_or_else(no_recoverable_streak)?;
+    let (Some(broken_streak), Some(missed_date)) = (row.broken_streak, row.broken_missed_date)
+    else {
+        return Err(no_recoverable_streak());
+    };
+
+    let cost = body.pay_with.cost();
+    match body.pay_with {
+        RecoveryPayment::Coins => {
+            spend_coins(&mut tx, user_id, cost, COIN_SOURCE_STREAK_RECOVERY, None).await?;
+        }
+        RecoveryPayment::Exp => spend_exp(&mut tx, user_id, cost).await?,
+    }
+
+    // 途切れてからの日数分を途切れる前のストリークに足す
+    let current_streak = broken_streak + row.current_streak;
+    let best_streak = row.best_streak.max(current_streak);
+    sqlx::query(
+        r#"UPDATE user_streaks
+           SET current_streak = ?, best_streak = ?,
+               broken_streak = NULL, broken_missed_date = NULL, broken_at = NULL, updated_at = NOW()
+           WHERE user_id = ? AND streak_type = ?"#,
+    )
+    .bind(current_streak)
+    .bind(best_streak)
+    .bind(user_id)
+    .bind(streak_type)
+    .execute(&mut *tx)
+    .await?;
+
+    // 記録の削除でストリークを再計算しても修復が残るよう、休んだ日をカバーした日として記録
+    if streak_type == "training" {
+        sqlx::query(
+            r#"INSERT IGNORE INTO user_streak_protected_dates (user_id, protected_date, created_at)
+               VALUES (?, ?, NOW())"#,
+        )
+        .bind(user_id)
+        .bind(missed_date)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        r#"INSERT INTO streak_recoveries
+               (user_id, streak_type, missed_date, streak_before, streak_after, payment_type, cost, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, NOW())"#,
+    )
+    .bind(user_id)
+    .bind(streak_type)
+    .bind(missed_date)
+    .bind(row.current_streak)
+    .bind(current_streak)
+    .bind(body.pay_with.name())
+    .bind(cost)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        "[STREAK] user_id={} recovered {} streak {} -> {} (paid {} {})",
+        user_id,
+        streak_type,
+        row.current_streak,
+        current_streak,
+        cost,
+        body.pay_with.name()
+    );
+
+    Ok(HttpResponse::Ok().json(RecoverStreakResponse {
+        streak_type: streak_type.to_string(),
+        current_streak,
+        best_streak,
+        pay_with: body.pay_with.name(),
+        cost,
+    }))
+}
+
 /// GET /api/settings
 #[get("/settings")]
 pub async fn get_settings(
@@ -449,10 +842,13 @@ pub async fn get_settings(
     session: Session,
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
-    let settings = get_or_create_settings(pool.get_ref(), session_user.id).await?;
+    let settings = get_or_create_settings(&mut *pool.acquire().await?, session_user.id).await?;
 
     Ok(HttpResponse::Ok().json(SettingsResponse {
         grace_days_allowed: settings.grace_days_allowed,
+        heatmap_mode: settings.heatmap_mode,
+        weight_unit: settings.weight_unit,
+        timezone: settings.timezone,
     }))
 }
 
@@ -467,34 +863,136 @@ pub async fn update_settings(
     let user_id = session_user.id;
 
     // Validate grace days (0-3)
-    let grace_days = body.grace_days_allowed.clamp(0, 3);
+    let grace_days = body.grace_days_allowed.clamp(0, MAX_GRACE_DAYS);
 
     // Ensure settings exist
-    let _ = get_or_create_settings(pool.get_ref(), user_id).await?;
+    let settings = get_or_create_settings(&mut *pool.acquire().await?, user_id).await?;
+
+    // ヒートマップモード（未指定時は現在の設定を維持）
+    let heatmap_mode = match body.heatmap_mode.as_deref() {
+        None => settings.heatmap_mode,
+        Some(mode) => parse_heatmap_mode(mode)?,
+    };
+
+    // 重量の単位（未指定時は現在の設定を維持）
+    let weight_unit = match body.weight_unit.as_deref() {
+        None => settings.weight_unit,
+        Some(unit) => parse_weight_unit(unit)?,
+    };
+
+    // タイムゾーン（未指定時は現在の設定を維持）
+    let timezone = match body.timezone.as_deref() {
+        None => settings.timezone,
+        Some(name) => parse_timezone(name)?,
+    };
 
     // Update
     sqlx::query(
-        "UPDATE user_settings SET grace_days_allowed = ?, updated_at = NOW() WHERE user_id = ?",
+        r#"UPDATE user_settings
+           SET grace_days_allowed = ?, heatmap_mode = ?, weight_unit = ?, timezone = ?,
+               updated_at = NOW()
+           WHERE user_id = ?"#,
     )
     .bind(grace_days)
+    .bind(&heatmap_mode)
+    .bind(&weight_unit)
+    .bind(&timezone)
     .bind(user_id)
     .execute(pool.get_ref())
     .await?;
 
     Ok(HttpResponse::Ok().json(SettingsResponse {
         grace_days_allowed: grace_days,
+        heatmap_mode,
+        weight_unit,
+        timezone,
     }))
 }
 
+/// 全筋肉グループの週間セット数目標
+async fn get_set_target_dtos(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Vec<SetTargetDto>, AppError> {
+    Ok(fetch_set_targets(pool, user_id)
+        .await?
+        .into_iter()
+        .map(|(muscle, min_sets, max_sets)| SetTargetDto {
+            muscle: muscle.to_string(),
+            min_sets,
+            max_sets,
+        })
+        .collect())
+}
+
+/// GET /api/settings/set-targets
+#[get("/settings/set-targets")]
+pub async fn get_set_target_settings(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let targets = get_set_target_dtos(pool.get_ref(), session_user.id).await?;
+    Ok(HttpResponse::Ok().json(targets))
+}
+
+/// PUT /api/settings/set-targets
+/// 指定した筋肉グループの目標を更新（未指定のグループは変更しない）
+#[put("/settings/set-targets")]
+pub async fn update_set_target_settings(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<Vec<SetTargetDto>>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    for target in body.iter() {
+        if !MUSCLE_GROUPS.contains(&target.muscle.as_str()) {
+            return Err(AppError::BadRequest(format!(
+                "不明な筋肉グループです: {}",
+                target.muscle
+            )));
+        }
+        if target.min_sets < 0
+            || target.min_sets > target.max_sets
+            || target.max_sets > MAX_WEEKLY_SETS
+        {
+            return Err(AppError::BadRequest(format!(
+                "セット数は0〜{}の範囲で、最小値が最大値以下になるよう指定してください",
+                MAX_WEEKLY_SETS
+            )));
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    for target in body.iter() {
+        sqlx::query(
+            r#"INSERT INTO user_set_targets (user_id, muscle_group, min_sets, max_sets, updated_at)
+               VALUES (?, ?, ?, ?, NOW())
+               ON DUPLICATE KEY UPDATE min_sets = VALUES(min_sets), max_sets = VALUES(max_sets), updated_at = NOW()"#,
+        )
+        .bind(session_user.id)
+        .bind(&target.muscle)
+        .bind(target.min_sets)
+        .bind(target.max_sets)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    let targets = get_set_target_dtos(pool.get_ref(), session_user.id).await?;
+    Ok(HttpResponse::Ok().json(targets))
+}
+
 /// Public function to update training streak (called from workout API)
 pub async fn record_training_activity(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     user_id: i64,
     training_date: NaiveDate,
 ) -> Result<(), AppError> {
-    let settings = get_or_create_settings(pool, user_id).await?;
+    let settings = get_or_create_settings(conn, user_id).await?;
     let _ = update_streak(
-        pool,
+        conn,
         user_id,
         "training",
         training_date,
@@ -507,26 +1005,32 @@ pub async fn record_training_activity(
 /// Recalculate training streak based on actual training records
 /// Called when a training record is deleted
 pub async fn recalculate_training_streak(
-    pool: &MySqlPool,
+    conn: &mut MySqlConnection,
     user_id: i64,
 ) -> Result<(), AppError> {
-    let settings = get_or_create_settings(pool, user_id).await?;
+    let settings = get_or_create_settings(&mut *conn, user_id).await?;
     let grace_days = settings.grace_days_allowed;
 
     // Get all training dates for this user, ordered descending
+    // ストリークシールドでカバーした日もトレーニング日として数える
     let training_dates: Vec<(NaiveDate,)> = sqlx::query_as(
-        "SELECT DISTINCT DATE(record_date) as d FROM training_records 
-         WHERE user_id = ? ORDER BY d DESC",
+        r#"SELECT d FROM (
+               SELECT DATE(record_date) AS d FROM training_records WHERE user_id = ?
+               UNION
+               SELECT protected_date AS d FROM user_streak_protected_dates WHERE user_id = ?
+           ) dates
+           ORDER BY d DESC"#,
     )
     .bind(user_id)
-    .fetch_all(pool)
+    .bind(user_id)
+    .fetch_all(&mut *conn)
     .await?;
 
     let (current_streak, last_active_date) = if training_dates.is_empty() {
         // No training records - reset streak to 0
         (0, None)
     } else {
-        let today = chrono::Local::now().date_naive();
+        let today = timezone::today_in(timezone::from_setting(&settings.timezone));
         let most_recent = training_dates[0].0;
         
         // Check if streak is still valid from today's perspective
@@ -539,8 +1043,7 @@ pub async fn recalculate_training_streak(
             let mut streak = 1;
             let mut prev_date = most_recent;
             
-            for i in 1..training_dates.len() {
-                let curr_date = training_dates[i].0;
+            for &(curr_date,) in training_dates.iter().skip(1) {
                 let gap = (prev_date - curr_date).num_days();
                 
                 if gap <= (grace_days as i64 + 1) {
@@ -562,7 +1065,7 @@ pub async fn recalculate_training_streak(
     .bind(current_streak)
     .bind(last_active_date)
     .bind(user_id)
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     Ok(())
@@ -572,6 +1075,9 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_streaks)
         .service(claim_login_bonus)
         .service(record_login)
+        .service(recover_streak)
         .service(get_settings)
-        .service(update_settings);
+        .service(update_settings)
+        .service(get_set_target_settings)
+        .service(update_set_target_settings);
 }
@@ -1,11 +1,10 @@
 //! サプリメントAPIハンドラ
 
-use actix_session::Session;
 use actix_web::{get, web, HttpResponse};
 use serde::Serialize;
 use sqlx::MySqlPool;
 
-use crate::auth::session::get_current_user;
+use crate::auth::session::{get_current_user, Session};
 use crate::db::models::{Category, Effect, Supplement, SupplementLink};
 use crate::error::AppError;
 
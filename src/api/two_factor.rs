@@ -0,0 +1,378 @@
+//! 二段階認証（TOTP）APIハンドラ
+//!
+//! パスワードでログインするアカウントで、認証アプリのコードによる二段階認証を有効にできる。
+//! 有効にするとパスワードログイン（/login・/api/auth/token）でコードの入力が必要になる。
+//! 認証アプリを使えなくなったときのために、1回だけ使えるバックアップコードを発行する。
+
+use actix_web::{get, post, web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::auth::verify_password_hash;
+use crate::auth::session::{get_current_user, Session};
+use crate::auth::totp::{
+    generate_backup_codes, generate_secret, hash_backup_code, otpauth_uri, verify_code,
+};
+use crate::error::AppError;
+use crate::shared_store::SharedStore;
+
+/// 認証アプリに表示する発行者名
+const TOTP_ISSUER: &str = "Fithub";
+
+/// コードの試行回数の上限（ユーザーごと）
+const MAX_CODE_ATTEMPTS: u64 = 5;
+const CODE_ATTEMPT_WINDOW_SECS: u64 = 5 * 60;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct CodeRequest {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct DisableRequest {
+    password: String,
+    code: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TwoFactorStatusResponse {
+    enabled: bool,
+    /// 未使用のバックアップコードの数
+    backup_codes_remaining: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetupResponse {
+    /// 認証アプリに手入力する場合のシークレット（base32）
+    secret: String,
+    /// QRコードにするURI
+    otpauth_uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupCodesResponse {
+    enabled: bool,
+    /// 発行したバックアップコード（この応答でしか表示できない）
+    backup_codes: Vec<String>,
+}
+
+// ============================================
+// 二段階認証の確認
+// ============================================
+
+/// 二段階認証が有効か
+pub(crate) async fn is_two_factor_enabled(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<bool, AppError> {
+    let enabled: Option<i64> = sqlx::query_scalar(
+        "SELECT user_id FROM user_two_factor WHERE user_id = ? AND enabled_at IS NOT NULL",
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(enabled.is_some())
+}
+
+/// 認証アプリのコードまたはバックアップコードを確認する（使ったコードは再利用できなくなる）
+async fn consume_second_factor(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    code: &str,
+) -> Result<bool, AppError> {
+    let row: Option<(String, Option<i64>)> = sqlx::query_as(
+        r#"SELECT secret, last_used_step FROM user_two_factor
+           WHERE user_id = ? AND enabled_at IS NOT NULL FOR UPDATE"#,
+    )
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    let Some((secret, last_used_step)) = row else {
+        return Ok(false);
+    };
+
+    if let Some(step) = verify_code(&secret, code) {
+        if last_used_step.is_some_and(|last| step <= last) {
+            return Ok(false);
+        }
+        sqlx::query(
+            "UPDATE user_two_factor SET last_used_step = ?, updated_at = NOW() WHERE user_id = ?",
+        )
+        .bind(step)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+        return Ok(true);
+    }
+
+    let result = sqlx::query(
+        r#"UPDATE user_backup_codes SET used_at = NOW()
+           WHERE user_id = ? AND code_hash = ? AND used_at IS NULL
+           LIMIT 1"#,
+    )
+    .bind(user_id)
+    .bind(hash_backup_code(code))
+    .execute(&mut *conn)
+    .await?;
+    if result.rows_affected() > 0 {
+        tracing::info!("[2FA] user_id={} signed in with a backup code", user_id);
+    }
+    Ok(result.rows_affected() > 0)
+}
+
+/// 二段階認証のコードを確認する（誤りはUnauthorized、試行回数の超過はTooManyRequests）
+pub(crate) async fn verify_second_factor(
+    pool: &MySqlPool,
+    store: &SharedStore,
+    user_id: i64,
+    code: &str,
+) -> Result<(), AppError> {
+    store
+        .check_rate_limit(
+            &format!("2fa:{}", user_id),
+            MAX_CODE_ATTEMPTS,
+            std::time::Duration::from_secs(CODE_ATTEMPT_WINDOW_SECS),
+        )
+        .await?;
+
+    let mut tx = pool.begin().await?;
+    let verified = consume_second_factor(&mut tx, user_id, code).await?;
+    tx.commit().await?;
+
+    if !verified {
+        return Err(AppError::Unauthorized(
+            "認証コードが正しくありません".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// バックアップコードを発行し直す（未使用のものも含めて古いコードは使えなくなる）
+async fn replace_backup_codes(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<Vec<String>, AppError> {
+    sqlx::query("DELETE FROM user_backup_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let codes = generate_backup_codes();
+    for code in &codes {
+        sqlx::query("INSERT INTO user_backup_codes (user_id, code_hash) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(hash_backup_code(code))
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(codes)
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/auth/2fa
+/// 二段階認証の設定状況
+#[get("/auth/2fa")]
+async fn get_two_factor_status(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let enabled = is_two_factor_enabled(pool.get_ref(), session_user.id).await?;
+    let backup_codes_remaining: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM user_backup_codes WHERE user_id = ? AND used_at IS NULL",
+    )
+    .bind(session_user.id)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(TwoFactorStatusResponse {
+        enabled,
+        backup_codes_remaining: if enabled { backup_codes_remaining } else { 0 },
+    }))
+}
+
+/// POST /api/auth/2fa/setup
+/// 新しいシークレットを発行する（/verifyでコードを確認するまでは有効にならない）
+#[post("/auth/2fa/setup")]
+async fn setup_two_factor(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let password: Option<Option<String>> =
+        sqlx::query_scalar("SELECT password FROM users WHERE id = ?")
+            .bind(session_user.id)
+            .fetch_optional(pool.get_ref())
+            .await?;
+    if password.flatten().filter(|p| !p.is_empty()).is_none() {
+        return Err(AppError::BadRequest(
+            "二段階認証はパスワードでログインするアカウントでのみ使えます".to_string(),
+        ));
+    }
+    if is_two_factor_enabled(pool.get_ref(), session_user.id).await? {
+        return Err(AppError::BadRequest("二段階認証は既に有効です".to_string()));
+    }
+
+    let secret = generate_secret();
+    sqlx::query(
+        r#"INSERT INTO user_two_factor (user_id, secret, created_at, updated_at)
+           VALUES (?, ?, NOW(), NOW())
+           ON DUPLICATE KEY UPDATE secret = VALUES(secret), last_used_step = NULL,
+               updated_at = NOW()"#,
+    )
+    .bind(session_user.id)
+    .bind(&secret)
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(SetupResponse {
+        otpauth_uri: otpauth_uri(TOTP_ISSUER, &session_user.login_id, &secret),
+        secret,
+    }))
+}
+
+/// POST /api/auth/2fa/verify
+/// 認証アプリのコードを確認して二段階認証を有効にし、バックアップコードを発行する
+#[post("/auth/2fa/verify")]
+async fn verify_two_factor(
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    body: web::Json<CodeRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    store
+        .check_rate_limit(
+            &format!("2fa:{}", session_user.id),
+            MAX_CODE_ATTEMPTS,
+            std::time::Duration::from_secs(CODE_ATTEMPT_WINDOW_SECS),
+        )
+        .await?;
+
+    let mut tx = pool.begin().await?;
+
+    let secret: Option<String> = sqlx::query_scalar(
+        r#"SELECT secret FROM user_two_factor
+           WHERE user_id = ? AND enabled_at IS NULL FOR UPDATE"#,
+    )
+    .bind(session_user.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let secret = secret.ok_or_else(|| {
+        AppError::BadRequest("先に二段階認証の設定を開始してください".to_string())
+    })?;
+
+    let step = verify_code(&secret, &body.code)
+        .ok_or_else(|| AppError::BadRequest("認証コードが正しくありません".to_string()))?;
+
+    sqlx::query(
+        r#"UPDATE user_two_factor SET enabled_at = NOW(), last_used_step = ?, updated_at = NOW()
+           WHERE user_id = ?"#,
+    )
+    .bind(step)
+    .bind(session_user.id)
+    .execute(&mut *tx)
+    .await?;
+    let backup_codes = replace_backup_codes(&mut tx, session_user.id).await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(BackupCodesResponse {
+        enabled: true,
+        backup_codes,
+    }))
+}
+
+/// POST /api/auth/2fa/backup-codes
+/// バックアップコードを発行し直す（認証アプリのコードかバックアップコードが必要）
+#[post("/auth/2fa/backup-codes")]
+async fn regenerate_backup_codes(
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    body: web::Json<CodeRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    if !is_two_factor_enabled(pool.get_ref(), session_user.id).await? {
+        return Err(AppError::BadRequest(
+            "二段階認証が有効になっていません".to_string(),
+        ));
+    }
+    verify_second_factor(pool.get_ref(), &store, session_user.id, &body.code).await?;
+
+    let mut tx = pool.begin().await?;
+    let backup_codes = replace_backup_codes(&mut tx, session_user.id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(BackupCodesResponse {
+        enabled: true,
+        backup_codes,
+    }))
+}
+
+/// POST /api/auth/2fa/disable
+/// 二段階認証を無効にする（パスワードと、認証アプリのコードかバックアップコードが必要）
+#[post("/auth/2fa/disable")]
+async fn disable_two_factor(
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    body: web::Json<DisableRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    if !is_two_factor_enabled(pool.get_ref(), session_user.id).await? {
+        return Err(AppError::BadRequest(
+            "二段階認証が有効になっていません".to_string(),
+        ));
+    }
+
+    let stored_hash: Option<String> = sqlx::query_scalar("SELECT password FROM users WHERE id = ?")
+        .bind(session_user.id)
+        .fetch_one(pool.get_ref())
+        .await?;
+    if !stored_hash.is_some_and(|h| verify_password_hash(&body.password, &h)) {
+        return Err(AppError::BadRequest(
+            "パスワードが正しくありません".to_string(),
+        ));
+    }
+    verify_second_factor(pool.get_ref(), &store, session_user.id, &body.code).await?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM user_backup_codes WHERE user_id = ?")
+        .bind(session_user.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM user_two_factor WHERE user_id = ?")
+        .bind(session_user.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "enabled": false,
+    })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_two_factor_status)
+        .service(setup_two_factor)
+        .service(verify_two_factor)
+        .service(regenerate_backup_codes)
+        .service(disable_two_factor);
+}
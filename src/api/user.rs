@@ -1,16 +1,27 @@
 //! ユーザーAPIハンドラ
 
-use actix_session::Session;
-use actix_web::{delete, get, put, web, HttpResponse};
+use actix_web::{get, put, web, HttpResponse};
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
 };
-use chrono::{Datelike, Duration, NaiveDate, Utc};
+use chrono::{Duration, NaiveDate};
 use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 
-use crate::auth::session::{clear_current_user, get_current_user, set_current_user, SessionUser};
+use crate::api::admin::is_special_admin;
+use crate::api::auth::verify_password_hash;
+use crate::api::email_verification::find_verified_email;
+use crate::api::goal::complete_achieved_goals;
+use crate::api::streak::fetch_user_today;
+use crate::api::workout_target::{
+    fetch_muscle_target_progress, target_week, MuscleTargetProgressDto,
+};
+use crate::auth::password_policy::{violations_response, PasswordPolicy};
+use crate::auth::session::{get_current_user, replace_current_user, Session, SessionUser};
+use crate::config::AppConfig;
+use crate::middleware::session_refresh::bump_session_epoch;
+use crate::shared_store::SharedStore;
 use crate::db::models::{User, UserStats};
 use crate::error::AppError;
 
@@ -33,6 +44,12 @@ struct UserInfoResponse {
     current_exp: i64,
     #[serde(rename = "expToNextLevel")]
     exp_to_next_level: i32,
+    /// 体重（kg、自重種目のEXP計算に使用）
+    #[serde(rename = "bodyWeight")]
+    body_weight: Option<f64>,
+    /// メールアドレスの確認が済んでいるか
+    #[serde(rename = "emailVerified")]
+    email_verified: bool,
 }
 
 #[derive(Serialize)]
@@ -63,12 +80,18 @@ struct UserStatsResponse {
     current_streak: i32,
     #[serde(rename = "bestRecordsCount")]
     best_records_count: i32,
+    /// 今週のトレーニングの平均所要時間（秒、開始・終了を記録した日のみ）
+    #[serde(rename = "weeklyAverageDurationSeconds")]
+    weekly_average_duration_seconds: Option<i64>,
     #[serde(rename = "recentRecords")]
     recent_records: Vec<RecentRecordDto>,
     #[serde(rename = "weeklyVolumeHistory")]
     weekly_volume_history: Vec<DailyVolumeDto>,
     #[serde(rename = "muscleStatuses")]
     muscle_statuses: Vec<MuscleStatusDto>,
+    /// 筋肉グループ別の週間目標と今週の進捗
+    #[serde(rename = "muscleTargets")]
+    muscle_targets: Vec<MuscleTargetProgressDto>,
 }
 
 #[derive(Serialize)]
@@ -84,6 +107,9 @@ struct RecentRecordDto {
     primary_muscles: Vec<String>,
     #[serde(rename = "expEarned")]
     exp_earned: i32,
+    /// トレーニングの所要時間（秒、開始・終了を記録した場合のみ）
+    #[serde(rename = "durationSeconds")]
+    duration_seconds: Option<i64>,
 }
 
 #[derive(Serialize)]
@@ -123,6 +149,15 @@ async fn get_user_info(
 
     let user = user.ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
+    let body_weight: Option<f64> =
+        sqlx::query_scalar("SELECT body_weight FROM users WHERE id = ?")
+            .bind(session_user.id)
+            .fetch_one(pool.get_ref())
+            .await?;
+    let email_verified = find_verified_email(pool.get_ref(), session_user.id)
+        .await?
+        .is_some();
+
     // レベル情報用のユーザー統計を取得
     let stats: Option<UserStats> = sqlx::query_as(
         r#"SELECT id, user_id, total_exp, level
@@ -156,6 +191,8 @@ async fn get_user_info(
         level,
         current_exp,
         exp_to_next_level,
+        body_weight,
+        email_verified,
     }))
 }
 
@@ -209,12 +246,10 @@ async fn get_user_stats(
     };
 
     // ダッシュボード統計を計算
-    let today = Utc::now().date_naive();
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
 
     // 今日のデイリーEXPをtraining_records.exp_earnedから計算
-    use crate::config::ExpConfig;
-    let exp_config = ExpConfig::default();
-    let daily_limit = exp_config.daily_limit;
+    let daily_limit = crate::exp_config::current().daily_limit;
 
     let today_exp: (i64,) = sqlx::query_as(
         "SELECT CAST(COALESCE(SUM(exp_earned), 0) AS SIGNED) FROM training_records WHERE user_id = ? AND record_date = ?",
@@ -225,10 +260,8 @@ async fn get_user_stats(
     .await?;
     let daily_exp = today_exp.0 as i32;
 
-    // 今週の開始（月曜日）を取得
-    let days_since_monday = today.weekday().num_days_from_monday() as i64;
-    let current_week_start = today - Duration::days(days_since_monday);
-    let current_week_end = current_week_start + Duration::days(6);
+    // 今週（月曜始まり）の期間（週間目標の進捗と同じ週）
+    let (current_week_start, current_week_end) = target_week(today);
 
     // 先週の開始を取得
     let prev_week_start = current_week_start - Duration::days(7);
@@ -262,7 +295,8 @@ async fn get_user_stats(
         r#"SELECT SUM(ts.weight * ts.reps) FROM training_sets ts
            INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
            INNER JOIN training_records tr ON tre.record_id = tr.id
-           WHERE tr.user_id = ? AND tr.record_date >= ? AND tr.record_date <= ?"#,
+           WHERE tr.user_id = ? AND tr.record_date >= ? AND tr.record_date <= ?
+             AND ts.set_type <> 'warmup'"#,
     )
     .bind(session_user.id)
     .bind(current_week_start)
@@ -275,7 +309,7 @@ async fn get_user_stats(
         r#"SELECT SUM(ts.weight * ts.reps) FROM training_sets ts
            INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
            INNER JOIN training_records tr ON tre.record_id = tr.id
-           WHERE tr.user_id = ?"#,
+           WHERE tr.user_id = ? AND ts.set_type <> 'warmup'"#,
     )
     .bind(session_user.id)
     .fetch_one(pool.get_ref())
@@ -286,7 +320,8 @@ async fn get_user_stats(
         r#"SELECT SUM(ts.weight * ts.reps) FROM training_sets ts
            INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
            INNER JOIN training_records tr ON tre.record_id = tr.id
-           WHERE tr.user_id = ? AND tr.record_date >= ? AND tr.record_date <= ?"#,
+           WHERE tr.user_id = ? AND tr.record_date >= ? AND tr.record_date <= ?
+             AND ts.set_type <> 'warmup'"#,
     )
     .bind(session_user.id)
     .bind(prev_week_start)
@@ -382,7 +417,7 @@ async fn get_user_stats(
 
         // ボリュームを取得
         let total_vol: (Option<f64>,) = sqlx::query_as(
-            "SELECT SUM(ts.weight * ts.reps) FROM training_sets ts INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id INNER JOIN training_records tr ON tre.record_id = tr.id WHERE tr.user_id = ? AND tr.record_date = ?",
+            "SELECT SUM(ts.weight * ts.reps) FROM training_sets ts INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id INNER JOIN training_records tr ON tre.record_id = tr.id WHERE tr.user_id = ? AND tr.record_date = ? AND ts.set_type <> 'warmup'",
         )
         .bind(session_user.id)
         .bind(date)
@@ -417,6 +452,18 @@ async fn get_user_stats(
 
         let primary_muscles: Vec<String> = muscles.into_iter().map(|(m,)| m).collect();
 
+        // 所要時間を取得
+        let duration: (Option<i64>,) = sqlx::query_as(
+            r#"SELECT CAST(SUM(TIMESTAMPDIFF(SECOND, started_at, finished_at)) AS SIGNED)
+               FROM training_records
+               WHERE user_id = ? AND record_date = ? AND finished_at IS NOT NULL"#,
+        )
+        .bind(session_user.id)
+        .bind(date)
+        .fetch_one(pool.get_ref())
+        .await
+        .unwrap_or((None,));
+
         recent_records.push(RecentRecordDto {
             date: date.format("%Y-%m-%d").to_string(),
             exercise_count: exercise_count.0 as i32,
@@ -424,6 +471,7 @@ async fn get_user_stats(
             total_volume: total_vol.0.unwrap_or(0.0),
             primary_muscles,
             exp_earned: exp_earned.0 as i32,
+            duration_seconds: duration.0,
         });
     }
 
@@ -438,7 +486,7 @@ async fn get_user_stats(
                FROM training_sets ts
                INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
                INNER JOIN training_records tr ON tre.record_id = tr.id
-               WHERE tr.user_id = ? AND tr.record_date = ?"#,
+               WHERE tr.user_id = ? AND tr.record_date = ? AND ts.set_type <> 'warmup'"#,
         )
         .bind(session_user.id)
         .bind(check_date)
@@ -451,6 +499,24 @@ async fn get_user_stats(
         });
     }
 
+    // 今週の平均所要時間
+    let weekly_average_duration: (Option<i64>,) = sqlx::query_as(
+        r#"SELECT CAST(AVG(TIMESTAMPDIFF(SECOND, started_at, finished_at)) AS SIGNED)
+           FROM training_records
+           WHERE user_id = ? AND record_date >= ? AND record_date <= ?
+             AND started_at IS NOT NULL AND finished_at IS NOT NULL"#,
+    )
+    .bind(session_user.id)
+    .bind(current_week_start)
+    .bind(current_week_end)
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    // 自己ベストのある種目数
+    let best_records_count =
+        crate::api::personal_record::count_exercises_with_records(pool.get_ref(), session_user.id)
+            .await? as i32;
+
     // 部位別コンディション（最終トレーニング日からの経過日数で判定）
     let target_muscles = vec!["胸", "背中", "脚", "肩", "腕"];
     let mut muscle_statuses: Vec<MuscleStatusDto> = Vec::new();
@@ -493,6 +559,15 @@ async fn get_user_stats(
         });
     }
 
+    // 筋肉グループ別の週間目標の進捗
+    let muscle_targets = fetch_muscle_target_progress(
+        pool.get_ref(),
+        session_user.id,
+        current_week_start,
+        current_week_end,
+    )
+    .await?;
+
     Ok(HttpResponse::Ok().json(UserStatsResponse {
         level: stats.level,
         total_exp: stats.total_exp,
@@ -506,10 +581,12 @@ async fn get_user_stats(
         total_volume,
         weekly_volume_change_percent,
         current_streak,
-        best_records_count: 0, // TODO: PRトラッキングを実装
+        best_records_count,
+        weekly_average_duration_seconds: weekly_average_duration.0,
         recent_records,
         weekly_volume_history,
         muscle_statuses,
+        muscle_targets,
     }))
 }
 
@@ -523,6 +600,8 @@ struct UpdateDisplayNameRequest {
 #[put("/user/display-name")]
 async fn update_display_name(
     pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
     session: Session,
     body: web::Json<UpdateDisplayNameRequest>,
 ) -> Result<HttpResponse, AppError> {
@@ -542,13 +621,15 @@ async fn update_display_name(
         .execute(pool.get_ref())
         .await?;
 
-    // セッションを更新
+    // セッションを更新（他の端末のセッションも次のリクエストで更新される）
+    let user_id = session_user.id;
     let updated_session_user = SessionUser {
         display_name: Some(body.display_name.clone()),
         ..session_user
     };
-    set_current_user(&session, updated_session_user)
+    replace_current_user(&session, updated_session_user)
         .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+    bump_session_epoch(&store, &config, user_id).await;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "success": true,
@@ -556,6 +637,188 @@ async fn update_display_name(
     })))
 }
 
+/// ログインIDを変更できる間隔（日）
+const LOGIN_ID_CHANGE_COOLDOWN_DAYS: i64 = 30;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateLoginIdRequest {
+    login_id: String,
+    /// パスワードを持つアカウントのみ必須
+    current_password: Option<String>,
+}
+
+/// PUT /api/user/login-id
+/// ログインIDを変更する（30日に1回まで）。パスワードを持つアカウントはパスワードで本人確認する
+#[put("/user/login-id")]
+async fn update_login_id(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    body: web::Json<UpdateLoginIdRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let login_id = body.login_id.trim();
+
+    // 登録時と同じ長さに加え、URLに使えるよう文字種も制限する
+    if login_id.len() < 4 || login_id.len() > 20 {
+        return Err(AppError::BadRequest(
+            "ユーザーIDは4〜20文字で入力してください".to_string(),
+        ));
+    }
+    if !login_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(AppError::BadRequest(
+            "ユーザーIDは半角英数字と _ - . のみ使用できます".to_string(),
+        ));
+    }
+    if login_id == session_user.login_id {
+        return Err(AppError::BadRequest(
+            "現在のユーザーIDと同じです".to_string(),
+        ));
+    }
+    // 特別管理者の権限はログインIDで判定しているため、付け替えられないようにする
+    if is_special_admin(login_id) || is_special_admin(&session_user.login_id) {
+        return Err(AppError::Forbidden(
+            "このユーザーIDは変更できません".to_string(),
+        ));
+    }
+
+    let (stored_hash, changed_at): (Option<String>, Option<chrono::NaiveDateTime>) =
+        sqlx::query_as("SELECT password, login_id_changed_at FROM users WHERE id = ?")
+            .bind(session_user.id)
+            .fetch_optional(pool.get_ref())
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    if let Some(stored_hash) = stored_hash.as_deref().filter(|h| !h.is_empty()) {
+        let password = body
+            .current_password
+            .as_deref()
+            .ok_or_else(|| AppError::BadRequest("パスワードを入力してください".to_string()))?;
+        if !verify_password_hash(password, stored_hash) {
+            return Err(AppError::BadRequest(
+                "パスワードが正しくありません".to_string(),
+            ));
+        }
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    if let Some(changed_at) = changed_at {
+        let available_at = changed_at + Duration::days(LOGIN_ID_CHANGE_COOLDOWN_DAYS);
+        if available_at > now {
+            return Err(AppError::TooManyRequests(format!(
+                "ユーザーIDは{}日に1回まで変更できます（次回は{}以降）",
+                LOGIN_ID_CHANGE_COOLDOWN_DAYS,
+                available_at.format("%Y-%m-%d %H:%M UTC")
+            )));
+        }
+    }
+
+    let existing: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM users WHERE login_id = ? AND id <> ?")
+            .bind(login_id)
+            .bind(session_user.id)
+            .fetch_optional(pool.get_ref())
+            .await?;
+    if existing.is_some() {
+        return Err(AppError::BadRequest(
+            "このユーザーIDは既に使用されています".to_string(),
+        ));
+    }
+
+    // 同時に変更された場合に備え、更新時にも間隔を確認する
+    let result = sqlx::query(
+        r#"UPDATE users SET login_id = ?, login_id_changed_at = NOW(), updated_at = NOW()
+           WHERE id = ?
+             AND (login_id_changed_at IS NULL
+                  OR login_id_changed_at <= NOW() - INTERVAL ? DAY)"#,
+    )
+    .bind(login_id)
+    .bind(session_user.id)
+    .bind(LOGIN_ID_CHANGE_COOLDOWN_DAYS)
+    .execute(pool.get_ref())
+    .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::TooManyRequests(format!(
+            "ユーザーIDは{}日に1回まで変更できます",
+            LOGIN_ID_CHANGE_COOLDOWN_DAYS
+        )));
+    }
+
+    tracing::info!(
+        "[LOGIN ID] user_id={} changed login_id from {} to {}",
+        session_user.id,
+        session_user.login_id,
+        login_id
+    );
+
+    // セッションを更新（他の端末のセッションも次のリクエストで更新される）
+    let user_id = session_user.id;
+    let login_id = login_id.to_string();
+    let updated_session_user = SessionUser {
+        login_id: login_id.clone(),
+        ..session_user
+    };
+    replace_current_user(&session, updated_session_user)
+        .map_err(|e| AppError::InternalError(format!("Session error: {}", e)))?;
+    bump_session_epoch(&store, &config, user_id).await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "loginId": login_id,
+        "nextChangeAvailableAt": (now + Duration::days(LOGIN_ID_CHANGE_COOLDOWN_DAYS))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string()
+    })))
+}
+
+#[derive(Deserialize)]
+struct UpdateBodyWeightRequest {
+    /// nullで未設定に戻す
+    #[serde(rename = "bodyWeight")]
+    body_weight: Option<f64>,
+}
+
+/// PUT /api/user/body-weight
+/// 体重を更新（自重種目のEXPは「体重 + 追加重量」で計算される）
+#[put("/user/body-weight")]
+async fn update_body_weight(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<UpdateBodyWeightRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    if let Some(weight) = body.body_weight {
+        if !(20.0..=300.0).contains(&weight) {
+            return Err(AppError::BadRequest(
+                "体重は20〜300kgの範囲で入力してください".to_string(),
+            ));
+        }
+    }
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE users SET body_weight = ?, updated_at = NOW() WHERE id = ?")
+        .bind(body.body_weight)
+        .bind(session_user.id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 体重の目標の達成を判定
+    let completed_goals = complete_achieved_goals(&mut tx, session_user.id, today).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "bodyWeight": body.body_weight,
+        "completedGoals": completed_goals
+    })))
+}
+
 #[derive(Deserialize)]
 struct UpdatePasswordRequest {
     #[serde(rename = "currentPassword")]
@@ -568,6 +831,7 @@ struct UpdatePasswordRequest {
 #[put("/user/password")]
 async fn update_password(
     pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
     session: Session,
     body: web::Json<UpdatePasswordRequest>,
 ) -> Result<HttpResponse, AppError> {
@@ -611,11 +875,12 @@ async fn update_password(
         })));
     }
 
-    // 新しいパスワードを検証
-    if body.new_password.len() < 6 {
-        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "New password must be at least 6 characters"
-        })));
+    // 新しいパスワードの強度を検証
+    let violations = PasswordPolicy::from_config(&config)
+        .validate(&body.new_password, &user.login_id)
+        .await;
+    if !violations.is_empty() {
+        return Ok(violations_response(&violations));
     }
 
     // 新しいパスワードをハッシュ化
@@ -638,93 +903,11 @@ async fn update_password(
     })))
 }
 
-/// DELETE /api/user/account
-#[delete("/user/account")]
-async fn delete_account(
-    pool: web::Data<MySqlPool>,
-    session: Session,
-) -> Result<HttpResponse, AppError> {
-    let session_user = get_current_user(&session)?;
-
-    let user_id = session_user.id;
-
-    // 関連する全てのデータを順番に削除（外部キー制約のため）
-    // 1. トレーニングセット（training_record_exercises経由）
-    sqlx::query(
-        r#"DELETE ts FROM training_sets ts
-           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
-           INNER JOIN training_records tr ON tre.record_id = tr.id
-           WHERE tr.user_id = ?"#,
-    )
-    .bind(user_id)
-    .execute(pool.get_ref())
-    .await?;
-
-    // 2. トレーニングレコード種目
-    sqlx::query(
-        r#"DELETE tre FROM training_record_exercises tre
-           INNER JOIN training_records tr ON tre.record_id = tr.id
-           WHERE tr.user_id = ?"#,
-    )
-    .bind(user_id)
-    .execute(pool.get_ref())
-    .await?;
-
-    // 3. トレーニングレコード
-    sqlx::query("DELETE FROM training_records WHERE user_id = ?")
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
-
-    // 4. トレーニング種目タグ
-    sqlx::query("DELETE FROM training_exercise_tags WHERE user_id = ?")
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
-
-    // 5. トレーニングタグ
-    sqlx::query("DELETE FROM training_tags WHERE user_id = ?")
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
-
-    // 6. ユーザー種目デフォルトタグ
-    sqlx::query("DELETE FROM user_exercise_default_tags WHERE user_id = ?")
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
-
-    // 7. ユーザーカスタム種目
-    sqlx::query("DELETE FROM user_custom_exercises WHERE user_id = ?")
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
-
-    // 8. ユーザー統計
-    sqlx::query("DELETE FROM user_stats WHERE user_id = ?")
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
-
-    // 9. 最後にユーザーを削除
-    sqlx::query("DELETE FROM users WHERE id = ?")
-        .bind(user_id)
-        .execute(pool.get_ref())
-        .await?;
-
-    // セッションをクリア
-    clear_current_user(&session);
-    session.purge();
-
-    Ok(HttpResponse::Ok().json(serde_json::json!({
-        "success": true
-    })))
-}
-
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_user_info)
         .service(get_user_stats)
         .service(update_display_name)
-        .service(update_password)
-        .service(delete_account);
+        .service(update_login_id)
+        .service(update_body_weight)
+        .service(update_password);
 }
@@ -0,0 +1,277 @@
+//! ログイン中のセッション管理APIハンドラ
+//!
+//! CookieセッションにランダムなセッションIDを持たせ、user_sessionsでサーバー側から管理する。
+//! 登録と照合はSessionRefreshミドルウェアが行い、無効にしたセッションは次のリクエストで破棄される。
+//! Bearerトークンの端末は/api/auth/tokensで管理する。
+
+use actix_web::{delete, get, web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::auth::session::{get_current_user, get_session_id, Session};
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::middleware::session_refresh::bump_session_epoch;
+use crate::shared_store::SharedStore;
+
+/// 保存するUser-Agentの最大文字数
+const MAX_USER_AGENT_CHARS: usize = 255;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UserSessionDto {
+    id: i64,
+    /// User-Agentから推定した端末（例: "Chrome / Windows"）
+    device: String,
+    user_agent: Option<String>,
+    ip_address: Option<String>,
+    created_at: String,
+    last_seen_at: String,
+    /// このリクエストのセッションか
+    current: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct UserSessionRow {
+    id: i64,
+    session_id: String,
+    user_agent: Option<String>,
+    ip_address: Option<String>,
+    created_at: NaiveDateTime,
+    last_seen_at: NaiveDateTime,
+}
+
+fn format_datetime(dt: NaiveDateTime) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// User-AgentからブラウザとOSを推定する
+pub(crate) fn describe_device(user_agent: Option<&str>) -> String {
+    let Some(ua) = user_agent else {
+        return "不明な端末".to_string();
+    };
+    let os = if ua.contains("iPhone") {
+        "iPhone"
+    } else if ua.contains("iPad") {
+        "iPad"
+    } else if ua.contains("Android") {
+        "Android"
+    } else if ua.contains("Windows") {
+        "Windows"
+    } else if ua.contains("Mac OS X") {
+        "Mac"
+    } else if ua.contains("Linux") {
+        "Linux"
+    } else {
+        "不明なOS"
+    };
+    // Chrome系のUser-AgentはSafariも含むため、判定順に注意する
+    let browser = if ua.contains("Edg/") {
+        "Edge"
+    } else if ua.contains("Firefox/") || ua.contains("FxiOS/") {
+        "Firefox"
+    } else if ua.contains("Chrome/") || ua.contains("CriOS/") {
+        "Chrome"
+    } else if ua.contains("Safari/") {
+        "Safari"
+    } else {
+        "ブラウザ"
+    };
+    format!("{} / {}", browser, os)
+}
+
+// ============================================
+// セッションの登録・照合
+// ============================================
+
+/// セッションを一覧に登録してセッションIDを返す（ついでに期限切れのセッションを掃除する）
+pub(crate) async fn register_session(
+    pool: &MySqlPool,
+    config: &AppConfig,
+    user_id: i64,
+    user_agent: Option<&str>,
+    ip_address: Option<&str>,
+) -> Result<String, AppError> {
+    sqlx::query(
+        r#"DELETE FROM user_sessions
+           WHERE user_id = ? AND (revoked_at IS NOT NULL OR created_at < NOW() - INTERVAL ? HOUR)"#,
+    )
+    .bind(user_id)
+    .bind(config.session_max_lifetime_hours)
+    .execute(pool)
+    .await?;
+
+    let session_id = uuid::Uuid::new_v4().simple().to_string();
+    let user_agent: Option<String> =
+        user_agent.map(|ua| ua.chars().take(MAX_USER_AGENT_CHARS).collect());
+    sqlx::query(
+        r#"INSERT INTO user_sessions (session_id, user_id, user_agent, ip_address)
+           VALUES (?, ?, ?, ?)"#,
+    )
+    .bind(&session_id)
+    .bind(user_id)
+    .bind(user_agent)
+    .bind(ip_address)
+    .execute(pool)
+    .await?;
+    Ok(session_id)
+}
+
+/// セッションが有効なら最終利用日時を更新してtrueを返す（無効にされていればfalse）
+pub(crate) async fn touch_session(
+    pool: &MySqlPool,
+    session_id: &str,
+    ip_address: Option<&str>,
+) -> Result<bool, AppError> {
+    let active: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM user_sessions WHERE session_id = ? AND revoked_at IS NULL",
+    )
+    .bind(session_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(id) = active else {
+        return Ok(false);
+    };
+
+    sqlx::query(
+        r#"UPDATE user_sessions SET last_seen_at = NOW(), ip_address = COALESCE(?, ip_address)
+           WHERE id = ?"#,
+    )
+    .bind(ip_address)
+    .bind(id)
+    .execute(pool)
+    .await?;
+    Ok(true)
+}
+
+/// ログアウトしたセッションを無効にする
+pub(crate) async fn revoke_session(pool: &MySqlPool, session_id: &str) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE user_sessions SET revoked_at = NOW() WHERE session_id = ? AND revoked_at IS NULL",
+    )
+    .bind(session_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/user/sessions
+/// ログイン中のセッション一覧（最近使われた順）
+#[get("/user/sessions")]
+async fn list_sessions(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let current_session_id = get_session_id(&session);
+
+    // 無操作タイムアウトしたセッションは、照合の間隔の分だけ余裕を見て除く
+    let rows: Vec<UserSessionRow> = sqlx::query_as(
+        r#"SELECT id, session_id, user_agent, ip_address, created_at, last_seen_at
+           FROM user_sessions
+           WHERE user_id = ? AND revoked_at IS NULL
+             AND last_seen_at > NOW() - INTERVAL ? MINUTE
+             AND created_at > NOW() - INTERVAL ? HOUR
+           ORDER BY last_seen_at DESC"#,
+    )
+    .bind(session_user.id)
+    .bind(config.session_idle_timeout_minutes + config.session_revalidate_minutes)
+    .bind(config.session_max_lifetime_hours)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let sessions: Vec<UserSessionDto> = rows
+        .into_iter()
+        .map(|row| UserSessionDto {
+            id: row.id,
+            device: describe_device(row.user_agent.as_deref()),
+            current: current_session_id.as_deref() == Some(row.session_id.as_str()),
+            user_agent: row.user_agent,
+            ip_address: row.ip_address,
+            created_at: format_datetime(row.created_at),
+            last_seen_at: format_datetime(row.last_seen_at),
+        })
+        .collect();
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+/// DELETE /api/user/sessions/{id}
+/// セッションをログアウトさせる（このセッションを指定した場合はログアウトと同じ）
+#[delete("/user/sessions/{id}")]
+async fn revoke_user_session(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let id = path.into_inner();
+
+    let target: Option<String> = sqlx::query_scalar(
+        "SELECT session_id FROM user_sessions WHERE id = ? AND user_id = ? AND revoked_at IS NULL",
+    )
+    .bind(id)
+    .bind(session_user.id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    let target = target.ok_or_else(|| AppError::NotFound("Session not found".to_string()))?;
+
+    revoke_session(pool.get_ref(), &target).await?;
+    // 対象のセッションを次のリクエストで照合させて破棄する
+    bump_session_epoch(&store, &config, session_user.id).await;
+
+    let current = get_session_id(&session).as_deref() == Some(target.as_str());
+    if current {
+        session.purge();
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "current": current,
+    })))
+}
+
+/// DELETE /api/user/sessions
+/// このセッション以外をすべてログアウトさせる
+#[delete("/user/sessions")]
+async fn revoke_other_sessions(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let current_session_id = get_session_id(&session).unwrap_or_default();
+
+    let result = sqlx::query(
+        r#"UPDATE user_sessions SET revoked_at = NOW()
+           WHERE user_id = ? AND session_id <> ? AND revoked_at IS NULL"#,
+    )
+    .bind(session_user.id)
+    .bind(&current_session_id)
+    .execute(pool.get_ref())
+    .await?;
+    bump_session_epoch(&store, &config, session_user.id).await;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "revoked": result.rows_affected(),
+    })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(list_sessions)
+        .service(revoke_user_session)
+        .service(revoke_other_sessions);
+}
@@ -1,31 +1,74 @@
 //! ワークアウトAPIハンドラ
 
-use actix_session::Session;
-use actix_web::{delete, get, post, web, HttpResponse};
-use chrono::NaiveDate;
+use actix_web::{delete, get, post, put, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
-use sqlx::MySqlPool;
-
-use crate::auth::session::get_current_user;
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::achievement::{
+    award_achievements, AchievementDto, ACHIEVEMENT_SESSION_VOLUME, ACHIEVEMENT_TOTAL_WORKOUTS,
+    ACHIEVEMENT_TRAINING_STREAK,
+};
+use crate::api::admin::is_admin;
+use crate::api::exp_ledger::{
+    award_exp, ExpChange, EXP_SOURCE_WORKOUT, EXP_SOURCE_WORKOUT_DELETE, EXP_SOURCE_WORKOUT_EDIT,
+    EXP_SOURCE_WORKOUT_UNDO,
+};
+use crate::api::goal::{complete_achieved_goals, GoalDto};
+use crate::api::personal_record::{
+    exercises_in_record, refresh_personal_records, ExerciseRef, PersonalRecordDto,
+};
+use crate::api::streak::{fetch_user_today, fetch_weight_unit};
+use crate::api::workout_partner::{fetch_partners_for_records, TrainingPartnerDto};
+use crate::auth::session::{get_current_user, Session, SessionUser};
+use crate::config::{AppConfig, ExpConfig};
 use crate::db::models::*;
+use crate::domain::one_rm::{estimate_one_rep_max, OneRmFormula};
+use crate::domain::weight_unit::WeightUnit;
 use crate::error::AppError;
+use crate::shared_store::SharedStore;
+
+/// セットの種類（ウォームアップはEXP・ボリュームの集計から除外）
+const SET_TYPE_NORMAL: &str = "normal";
+const SET_TYPE_WARMUP: &str = "warmup";
+const SET_TYPES: [&str; 4] = [SET_TYPE_NORMAL, SET_TYPE_WARMUP, "dropset", "failure"];
+
+/// 記録メモ・セットメモの最大文字数
+const MAX_NOTE_CHARS: usize = 1000;
+const MAX_SET_MEMO_CHARS: usize = 255;
+
+/// カスタム種目の難易度係数（未設定時のデフォルトと許容範囲。上限はマスタ種目の上級=30）
+const DEFAULT_CUSTOM_DIFFICULTY_COEF: i32 = 15;
+const CUSTOM_DIFFICULTY_COEF_RANGE: std::ops::RangeInclusive<i32> = 1..=30;
+
+/// カスタム種目名の最大文字数
+const MAX_CUSTOM_EXERCISE_NAME_CHARS: usize = 100;
 
 // ============================================
 // DTOs
 // ============================================
 
 #[derive(Serialize, Clone)]
-struct WorkoutExerciseDto {
+pub(crate) struct WorkoutExerciseDto {
     id: i64,
     name: String,
     muscle: String,
     #[serde(rename = "isCustom")]
     is_custom: bool,
+    /// 自重種目（重量は追加重量として扱う）
+    #[serde(rename = "isBodyweight")]
+    is_bodyweight: bool,
     #[serde(rename = "defaultTags")]
     default_tags: Vec<String>,
     #[serde(rename = "userAddedDefaultTags")]
     user_added_default_tags: Vec<String>,
     tags: Vec<WorkoutTagDto>,
+    /// EXP計算の難易度係数（カスタム種目のみ）
+    #[serde(rename = "difficultyCoef", skip_serializing_if = "Option::is_none")]
+    difficulty_coef: Option<i32>,
+    /// スーパーセットの組番号（記録内で同じ番号の種目が1組）
+    #[serde(rename = "supersetGroup", skip_serializing_if = "Option::is_none")]
+    superset_group: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     sets: Option<Vec<WorkoutSetDto>>,
 }
@@ -38,18 +81,29 @@ struct WorkoutTagDto {
 }
 
 #[derive(Serialize, Clone)]
-struct WorkoutSetDto {
+pub(crate) struct WorkoutSetDto {
     id: i64,
     #[serde(rename = "setNumber")]
     set_number: i32,
     weight: f64,
     reps: i32,
+    #[serde(rename = "setType")]
+    set_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rpe: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
 }
 
 #[derive(Serialize)]
-struct WorkoutRecordDto {
+pub(crate) struct WorkoutRecordDto {
     id: i64,
     date: String,
+    /// 記録全体のメモ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
     exercises: Vec<WorkoutExerciseDto>,
     #[serde(rename = "expGained", skip_serializing_if = "Option::is_none")]
     exp_gained: Option<i32>,
@@ -61,6 +115,29 @@ struct WorkoutRecordDto {
     current_level: Option<i32>,
     #[serde(rename = "levelProgress", skip_serializing_if = "Option::is_none")]
     level_progress: Option<f64>,
+    /// 合同トレーニングの共有セッションID
+    #[serde(rename = "sharedSessionId", skip_serializing_if = "Option::is_none")]
+    shared_session_id: Option<String>,
+    /// トレーニングの開始・終了時刻（POST /workout/sessions/start・finish で記録）
+    #[serde(rename = "startedAt", skip_serializing_if = "Option::is_none")]
+    started_at: Option<NaiveDateTime>,
+    #[serde(rename = "finishedAt", skip_serializing_if = "Option::is_none")]
+    finished_at: Option<NaiveDateTime>,
+    /// 一緒にトレーニングしたパートナー
+    #[serde(rename = "trainedWith", skip_serializing_if = "Vec::is_empty")]
+    trained_with: Vec<TrainingPartnerDto>,
+    /// 保存・編集で更新した自己ベスト
+    #[serde(rename = "newPersonalRecords", skip_serializing_if = "Vec::is_empty")]
+    new_personal_records: Vec<PersonalRecordDto>,
+    /// 重量を表示する単位（ユーザー設定のkg / lb。重量自体は常にkgで返す）
+    #[serde(rename = "weightUnit", skip_serializing_if = "Option::is_none")]
+    weight_unit: Option<&'static str>,
+    /// 保存・編集で達成した目標（報酬EXPはtotalExpに含まれる）
+    #[serde(rename = "completedGoals", skip_serializing_if = "Vec::is_empty")]
+    completed_goals: Vec<GoalDto>,
+    /// 保存で新たに達成した実績
+    #[serde(rename = "newAchievements", skip_serializing_if = "Vec::is_empty")]
+    new_achievements: Vec<AchievementDto>,
 }
 
 #[derive(Serialize)]
@@ -96,6 +173,17 @@ struct PagedResponse<T> {
 struct CreateCustomExerciseRequest {
     name: String,
     muscle: Option<String>,
+    #[serde(rename = "isBodyweight", default)]
+    is_bodyweight: bool,
+}
+
+/// カスタム種目の更新（省略した項目は変更しない）
+#[derive(Deserialize)]
+struct UpdateCustomExerciseRequest {
+    name: Option<String>,
+    muscle: Option<String>,
+    #[serde(rename = "difficultyCoef")]
+    difficulty_coef: Option<i32>,
 }
 
 #[derive(Deserialize)]
@@ -104,16 +192,159 @@ struct PagedRequest {
     size: Option<i32>,
 }
 
+/// 記録一覧の絞り込み条件（クエリパラメータ）
+#[derive(Deserialize)]
+struct RecordFilterQuery {
+    /// 期間の開始日（YYYY-MM-DD、この日を含む）
+    from: Option<String>,
+    /// 期間の終了日（YYYY-MM-DD、この日を含む）
+    to: Option<String>,
+    muscle: Option<String>,
+    #[serde(rename = "tagId")]
+    tag_id: Option<i64>,
+    #[serde(rename = "exerciseId")]
+    exercise_id: Option<i64>,
+}
+
+/// 記録一覧の絞り込み条件
+/// 部位・タグ・種目は、いずれかの種目が全ての条件を満たす記録を対象とする（記録内の種目は全て返す）
+#[derive(Default)]
+pub(crate) struct RecordFilter {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    muscle: Option<String>,
+    tag_id: Option<i64>,
+    exercise_id: Option<i64>,
+}
+
+impl RecordFilter {
+    fn from_query(query: &RecordFilterQuery) -> Result<Self, AppError> {
+        let parse_date = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d"))
+                .transpose()
+                .map_err(|_| AppError::BadRequest("Invalid date format".to_string()))
+        };
+        Ok(Self {
+            from: parse_date(&query.from)?,
+            to: parse_date(&query.to)?,
+            muscle: query
+                .muscle
+                .as_deref()
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(str::to_string),
+            tag_id: query.tag_id,
+            exercise_id: query.exercise_id,
+        })
+    }
+
+    /// WHERE句に追加する条件（training_recordsの別名はtr）
+    fn where_sql(&self) -> String {
+        let mut sql = String::new();
+        if self.from.is_some() {
+            sql.push_str(" AND tr.record_date >= ?");
+        }
+        if self.to.is_some() {
+            sql.push_str(" AND tr.record_date <= ?");
+        }
+
+        let mut exercise_conditions = String::new();
+        if self.exercise_id.is_some() {
+            exercise_conditions
+                .push_str(" AND (tre.exercise_id = ? OR tre.custom_exercise_id = ?)");
+        }
+        if self.muscle.is_some() {
+            exercise_conditions.push_str(" AND COALESCE(e.muscle, uce.muscle) = ?");
+        }
+        if self.tag_id.is_some() {
+            exercise_conditions.push_str(
+                r#" AND COALESCE(tre.exercise_id, tre.custom_exercise_id) IN (
+                       SELECT tet.exercise_id FROM training_exercise_tags tet
+                       WHERE tet.user_id = tr.user_id AND tet.tag_id = ?)"#,
+            );
+        }
+        if !exercise_conditions.is_empty() {
+            sql.push_str(&format!(
+                r#" AND EXISTS (
+                       SELECT 1 FROM training_record_exercises tre
+                       LEFT JOIN exercises e ON e.id = tre.exercise_id
+                       LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+                       WHERE tre.record_id = tr.id{})"#,
+                exercise_conditions
+            ));
+        }
+        sql
+    }
+
+    /// where_sqlの順にパラメータをバインド
+    fn bind<'q, O>(
+        &'q self,
+        mut q: sqlx::query::QueryAs<'q, sqlx::MySql, O, sqlx::mysql::MySqlArguments>,
+    ) -> sqlx::query::QueryAs<'q, sqlx::MySql, O, sqlx::mysql::MySqlArguments> {
+        if let Some(from) = self.from {
+            q = q.bind(from);
+        }
+        if let Some(to) = self.to {
+            q = q.bind(to);
+        }
+        if let Some(exercise_id) = self.exercise_id {
+            q = q.bind(exercise_id).bind(exercise_id);
+        }
+        if let Some(ref muscle) = self.muscle {
+            q = q.bind(muscle);
+        }
+        if let Some(tag_id) = self.tag_id {
+            q = q.bind(tag_id);
+        }
+        q
+    }
+}
+
 #[derive(Deserialize)]
 struct SaveWorkoutRequest {
     date: String,
+    /// 記録全体のメモ（指定した場合のみ上書き、空文字で削除）
+    #[serde(alias = "memo")]
+    note: Option<String>,
+    exercises: Vec<SaveWorkoutExerciseDto>,
+    /// 重量の単位（kg / lb、省略時はユーザー設定の単位）
+    #[serde(rename = "weightUnit")]
+    weight_unit: Option<String>,
+}
+
+/// 記録の編集（日付は変更不可、種目・セットを丸ごと置き換える）
+#[derive(Deserialize)]
+struct UpdateWorkoutRequest {
+    /// 記録全体のメモ（指定した場合のみ上書き、空文字で削除）
+    #[serde(alias = "memo")]
+    note: Option<String>,
     exercises: Vec<SaveWorkoutExerciseDto>,
+    /// 重量の単位（kg / lb、省略時はユーザー設定の単位）
+    #[serde(rename = "weightUnit")]
+    weight_unit: Option<String>,
+}
+
+/// 記録の複製（複製元の日付の種目・セットを複製先の日付に保存）
+#[derive(Deserialize)]
+struct CopyWorkoutRequest {
+    #[serde(rename = "sourceDate")]
+    source_date: String,
+    #[serde(rename = "targetDate")]
+    target_date: String,
+    /// trueの場合はEXPを付与しない
+    #[serde(rename = "withoutExp", default)]
+    without_exp: bool,
 }
 
 #[derive(Deserialize)]
 struct SaveWorkoutExerciseDto {
     #[serde(rename = "exerciseId")]
     exercise_id: i64,
+    /// スーパーセットの組番号（記録内で同じ番号の種目が1組、省略時は単独）
+    #[serde(rename = "supersetGroup")]
+    superset_group: Option<i32>,
     sets: Vec<SaveSetDto>,
 }
 
@@ -121,6 +352,31 @@ struct SaveWorkoutExerciseDto {
 struct SaveSetDto {
     weight: f64,
     reps: i32,
+    /// normal / warmup / dropset / failure（省略時はnormal）
+    #[serde(rename = "setType")]
+    set_type: Option<String>,
+    /// 主観的運動強度（1〜10、0.5刻み）
+    rpe: Option<f64>,
+    /// セットごとのメモ
+    memo: Option<String>,
+}
+
+impl SaveSetDto {
+    fn set_type(&self) -> &str {
+        self.set_type.as_deref().unwrap_or(SET_TYPE_NORMAL)
+    }
+
+    fn is_warmup(&self) -> bool {
+        self.set_type() == SET_TYPE_WARMUP
+    }
+
+    /// 前後の空白を除いたメモ（空の場合はNone）
+    fn memo(&self) -> Option<&str> {
+        self.memo
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+    }
 }
 
 #[derive(Deserialize)]
@@ -152,7 +408,7 @@ async fn get_exercises(
     // 1. デフォルト種目を取得
     let default_exercises: Vec<Exercise> = sqlx::query_as(
         r#"SELECT id, name, muscle, muscle_group_id, difficulty, difficulty_level_id, 
-           description, target_muscles, video_path, display_order 
+           description, target_muscles, video_path, display_order, is_bodyweight
            FROM exercises ORDER BY display_order ASC, id ASC"#,
     )
     .fetch_all(pool.get_ref())
@@ -248,9 +504,12 @@ async fn get_exercises(
             name: ex.name,
             muscle: ex.muscle,
             is_custom: false,
+            is_bodyweight: ex.is_bodyweight,
             default_tags: master_tags,
             user_added_default_tags: user_added_tags,
             tags,
+            difficulty_coef: None,
+            superset_group: None,
             sets: None,
         });
     }
@@ -267,9 +526,12 @@ async fn get_exercises(
             name: ex.name.clone(),
             muscle: ex.muscle.clone(),
             is_custom: true,
+            is_bodyweight: ex.is_bodyweight,
             default_tags: vec![],
             user_added_default_tags: vec![],
             tags,
+            difficulty_coef: Some(ex.difficulty_coef.unwrap_or(DEFAULT_CUSTOM_DIFFICULTY_COEF)),
+            superset_group: None,
             sets: None,
         });
     }
@@ -289,12 +551,14 @@ async fn create_custom_exercise(
     let muscle = body.muscle.as_deref().unwrap_or("other");
 
     let result = sqlx::query(
-        r#"INSERT INTO user_custom_exercises (user_id, name, muscle, created_at, updated_at)
-           VALUES (?, ?, ?, NOW(), NOW())"#,
+        r#"INSERT INTO user_custom_exercises
+               (user_id, name, muscle, is_bodyweight, created_at, updated_at)
+           VALUES (?, ?, ?, ?, NOW(), NOW())"#,
     )
     .bind(session_user.id)
     .bind(&body.name)
     .bind(muscle)
+    .bind(body.is_bodyweight)
     .execute(pool.get_ref())
     .await?;
 
@@ -305,9 +569,112 @@ async fn create_custom_exercise(
         name: body.name.clone(),
         muscle: muscle.to_string(),
         is_custom: true,
+        is_bodyweight: body.is_bodyweight,
         default_tags: vec![],
         user_added_default_tags: vec![],
         tags: vec![],
+        difficulty_coef: Some(DEFAULT_CUSTOM_DIFFICULTY_COEF),
+        superset_group: None,
+        sets: None,
+    }))
+}
+
+/// PUT /api/workout/custom-exercises/{id}
+/// 名前・部位・難易度係数を変更する（係数は以降に保存するセットのEXPに反映）
+#[put("/workout/custom-exercises/{id}")]
+async fn update_custom_exercise(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    body: web::Json<UpdateCustomExerciseRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let exercise_id = path.into_inner();
+
+    let name = body.name.as_deref().map(str::trim);
+    if let Some(name) = name {
+        if name.is_empty() {
+            return Err(AppError::BadRequest("種目名を入力してください".to_string()));
+        }
+        if name.chars().count() > MAX_CUSTOM_EXERCISE_NAME_CHARS {
+            return Err(AppError::BadRequest(format!(
+                "種目名は{}文字以内で入力してください",
+                MAX_CUSTOM_EXERCISE_NAME_CHARS
+            )));
+        }
+    }
+    let muscle = body.muscle.as_deref().map(str::trim).filter(|m| !m.is_empty());
+    if let Some(coef) = body.difficulty_coef {
+        if !CUSTOM_DIFFICULTY_COEF_RANGE.contains(&coef) {
+            return Err(AppError::BadRequest(format!(
+                "難易度係数は{}〜{}で指定してください",
+                CUSTOM_DIFFICULTY_COEF_RANGE.start(),
+                CUSTOM_DIFFICULTY_COEF_RANGE.end()
+            )));
+        }
+    }
+
+    let result = sqlx::query(
+        r#"UPDATE user_custom_exercises
+           SET name = COALESCE(?, name), muscle = COALESCE(?, muscle),
+               difficulty_coef = COALESCE(?, difficulty_coef), updated_at = NOW()
+           WHERE id = ? AND user_id = ?"#,
+    )
+    .bind(name)
+    .bind(muscle)
+    .bind(body.difficulty_coef)
+    .bind(exercise_id)
+    .bind(session_user.id)
+    .execute(pool.get_ref())
+    .await?;
+    if result.rows_affected() == 0 {
+        // 変更がない場合も0件になるため、存在確認で区別する
+        let exists: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM user_custom_exercises WHERE id = ? AND user_id = ?")
+                .bind(exercise_id)
+                .bind(session_user.id)
+                .fetch_optional(pool.get_ref())
+                .await?;
+        if exists.is_none() {
+            return Err(AppError::NotFound("Custom exercise not found".to_string()));
+        }
+    }
+
+    let exercise: UserCustomExercise =
+        sqlx::query_as("SELECT * FROM user_custom_exercises WHERE id = ?")
+            .bind(exercise_id)
+            .fetch_one(pool.get_ref())
+            .await?;
+
+    let tags: Vec<WorkoutTagDto> = sqlx::query_as::<_, (i64, String, Option<String>)>(
+        r#"SELECT t.id, t.name, t.color FROM training_tags t
+           INNER JOIN training_exercise_tags tet ON tet.tag_id = t.id
+           WHERE tet.exercise_id = ? AND tet.user_id = ?
+           ORDER BY t.id ASC"#,
+    )
+    .bind(exercise_id)
+    .bind(session_user.id)
+    .fetch_all(pool.get_ref())
+    .await?
+    .into_iter()
+    .map(|(id, name, color)| WorkoutTagDto { id, name, color })
+    .collect();
+
+    Ok(HttpResponse::Ok().json(WorkoutExerciseDto {
+        id: exercise.id,
+        name: exercise.name,
+        muscle: exercise.muscle,
+        is_custom: true,
+        is_bodyweight: exercise.is_bodyweight,
+        default_tags: vec![],
+        user_added_default_tags: vec![],
+        tags,
+        difficulty_coef: Some(
+            exercise
+                .difficulty_coef
+                .unwrap_or(DEFAULT_CUSTOM_DIFFICULTY_COEF),
+        ),
+        superset_group: None,
         sets: None,
     }))
 }
@@ -338,6 +705,15 @@ async fn delete_custom_exercise(
         .execute(pool.get_ref())
         .await?;
 
+    // Delete personal records of the exercise
+    sqlx::query(
+        "DELETE FROM personal_records WHERE user_id = ? AND is_custom = TRUE AND exercise_id = ?",
+    )
+    .bind(session_user.id)
+    .bind(exercise_id)
+    .execute(pool.get_ref())
+    .await?;
+
     // Delete custom exercise
     sqlx::query("DELETE FROM user_custom_exercises WHERE id = ?")
         .bind(exercise_id)
@@ -356,10 +732,15 @@ async fn delete_custom_exercise(
 async fn get_records(
     pool: web::Data<MySqlPool>,
     session: Session,
+    filter: web::Query<RecordFilterQuery>,
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
+    let filter = RecordFilter::from_query(&filter)?;
 
-    let records = fetch_records_for_user(pool.get_ref(), session_user.id, None, None).await?;
+    let mut records =
+        fetch_records_for_user(pool.get_ref(), session_user.id, &filter, None, None).await?;
+    let unit = fetch_weight_unit(pool.get_ref(), session_user.id).await?;
+    annotate_weight_unit(&mut records, unit);
     Ok(HttpResponse::Ok().json(records))
 }
 
@@ -369,20 +750,34 @@ async fn get_records_paged(
     pool: web::Data<MySqlPool>,
     session: Session,
     query: web::Query<PagedRequest>,
+    filter: web::Query<RecordFilterQuery>,
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
+    let filter = RecordFilter::from_query(&filter)?;
 
     let page = query.page.unwrap_or(0);
     let size = query.size.unwrap_or(20);
 
     // 合計数を取得
-    let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM training_records WHERE user_id = ?")
-        .bind(session_user.id)
+    let count_sql = format!(
+        "SELECT COUNT(*) FROM training_records tr WHERE tr.user_id = ?{}",
+        filter.where_sql()
+    );
+    let total: (i64,) = filter
+        .bind(sqlx::query_as(&count_sql).bind(session_user.id))
         .fetch_one(pool.get_ref())
         .await?;
 
-    let records =
-        fetch_records_for_user(pool.get_ref(), session_user.id, Some(page), Some(size)).await?;
+    let mut records = fetch_records_for_user(
+        pool.get_ref(),
+        session_user.id,
+        &filter,
+        Some(page),
+        Some(size),
+    )
+    .await?;
+    let unit = fetch_weight_unit(pool.get_ref(), session_user.id).await?;
+    annotate_weight_unit(&mut records, unit);
     let total_pages = ((total.0 as f64) / (size as f64)).ceil() as i32;
 
     Ok(HttpResponse::Ok().json(PagedResponse {
@@ -396,9 +791,10 @@ async fn get_records_paged(
     }))
 }
 
-async fn fetch_records_for_user(
+pub(crate) async fn fetch_records_for_user(
     pool: &MySqlPool,
     user_id: i64,
+    filter: &RecordFilter,
     page: Option<i32>,
     size: Option<i32>,
 ) -> Result<Vec<WorkoutRecordDto>, AppError> {
@@ -406,29 +802,41 @@ async fn fetch_records_for_user(
     struct RecordRow {
         id: i64,
         record_date: NaiveDate,
+        note: Option<String>,
+        shared_session_id: Option<String>,
+        started_at: Option<NaiveDateTime>,
+        finished_at: Option<NaiveDateTime>,
     }
 
     let records: Vec<RecordRow> = if let (Some(p), Some(s)) = (page, size) {
-        sqlx::query_as(
-            r#"SELECT id, record_date FROM training_records
-               WHERE user_id = ?
-               ORDER BY record_date DESC, id DESC
+        let sql = format!(
+            r#"SELECT tr.id, tr.record_date, tr.note, tr.shared_session_id,
+                      tr.started_at, tr.finished_at
+               FROM training_records tr
+               WHERE tr.user_id = ?{}
+               ORDER BY tr.record_date DESC, tr.id DESC
                LIMIT ? OFFSET ?"#,
-        )
-        .bind(user_id)
-        .bind(s)
-        .bind(p * s)
-        .fetch_all(pool)
-        .await?
+            filter.where_sql()
+        );
+        filter
+            .bind(sqlx::query_as(&sql).bind(user_id))
+            .bind(s)
+            .bind(p * s)
+            .fetch_all(pool)
+            .await?
     } else {
-        sqlx::query_as(
-            r#"SELECT id, record_date FROM training_records
-               WHERE user_id = ?
-               ORDER BY record_date DESC, id DESC"#,
-        )
-        .bind(user_id)
-        .fetch_all(pool)
-        .await?
+        let sql = format!(
+            r#"SELECT tr.id, tr.record_date, tr.note, tr.shared_session_id,
+                      tr.started_at, tr.finished_at
+               FROM training_records tr
+               WHERE tr.user_id = ?{}
+               ORDER BY tr.record_date DESC, tr.id DESC"#,
+            filter.where_sql()
+        );
+        filter
+            .bind(sqlx::query_as(&sql).bind(user_id))
+            .fetch_all(pool)
+            .await?
     };
 
     if records.is_empty() {
@@ -436,6 +844,12 @@ async fn fetch_records_for_user(
     }
 
     let record_ids: Vec<i64> = records.iter().map(|r| r.id).collect();
+    let shared_ids: Vec<i64> = records
+        .iter()
+        .filter(|r| r.shared_session_id.is_some())
+        .map(|r| r.id)
+        .collect();
+    let mut partners = fetch_partners_for_records(pool, &shared_ids).await?;
     let placeholders = record_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
 
     // 記録の種目を取得
@@ -447,12 +861,16 @@ async fn fetch_records_for_user(
         custom_exercise_id: Option<i64>,
         exercise_name: String,
         muscle: String,
+        is_bodyweight: i64,
+        superset_group: Option<i32>,
     }
 
     let query = format!(
         r#"SELECT tre.id, tre.record_id, tre.exercise_id, tre.custom_exercise_id,
+           tre.superset_group,
            CAST(COALESCE(e.name, uce.name, 'Unknown') AS CHAR) as exercise_name,
-           CAST(COALESCE(e.muscle, uce.muscle, 'other') AS CHAR) as muscle
+           CAST(COALESCE(e.muscle, uce.muscle, 'other') AS CHAR) as muscle,
+           CAST(COALESCE(e.is_bodyweight, uce.is_bodyweight, 0) AS SIGNED) as is_bodyweight
            FROM training_record_exercises tre
            LEFT JOIN exercises e ON e.id = tre.exercise_id
            LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
@@ -475,12 +893,21 @@ async fn fetch_records_for_user(
             .map(|r| WorkoutRecordDto {
                 id: r.id,
                 date: r.record_date.format("%Y-%m-%d").to_string(),
+                note: r.note,
                 exercises: vec![],
                 exp_gained: None,
                 new_level: None,
                 total_exp: None,
                 current_level: None,
                 level_progress: None,
+                trained_with: partners.remove(&r.id).unwrap_or_default(),
+                shared_session_id: r.shared_session_id,
+                started_at: r.started_at,
+                finished_at: r.finished_at,
+                new_personal_records: vec![],
+                weight_unit: None,
+                completed_goals: vec![],
+                new_achievements: vec![],
             })
             .collect();
         return Ok(result);
@@ -493,11 +920,15 @@ async fn fetch_records_for_user(
         set_number: i32,
         weight: f64,
         reps: i32,
+        set_type: String,
+        rpe: Option<f64>,
+        memo: Option<String>,
+        created_at: Option<NaiveDateTime>,
     }
 
     let set_placeholders = re_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
     let set_query = format!(
-        r#"SELECT id, record_exercise_id, set_number, weight, reps
+        r#"SELECT id, record_exercise_id, set_number, weight, reps, set_type, rpe, memo, created_at
            FROM training_sets
            WHERE record_exercise_id IN ({})
            ORDER BY set_number ASC"#,
@@ -522,6 +953,12 @@ async fn fetch_records_for_user(
                 set_number: s.set_number,
                 weight: s.weight,
                 reps: s.reps,
+                set_type: s.set_type,
+                rpe: s.rpe,
+                memo: s.memo,
+                created_at: s
+                    .created_at
+                    .map(|dt| dt.format("%Y-%m-%dT%H:%M:%S").to_string()),
             });
     }
 
@@ -540,12 +977,18 @@ async fn fetch_records_for_user(
                 name: re.exercise_name,
                 muscle: re.muscle,
                 is_custom,
+                is_bodyweight: re.is_bodyweight != 0,
                 default_tags: vec![],
                 user_added_default_tags: vec![],
                 tags: vec![],
+                difficulty_coef: None,
+                superset_group: re.superset_group,
                 sets: Some(sets),
             });
     }
+    for exercises in exercises_by_record.values_mut() {
+        group_supersets(exercises);
+    }
 
     // 結果を構築
     let result: Vec<WorkoutRecordDto> = records
@@ -553,45 +996,359 @@ async fn fetch_records_for_user(
         .map(|r| WorkoutRecordDto {
             id: r.id,
             date: r.record_date.format("%Y-%m-%d").to_string(),
+            note: r.note,
             exercises: exercises_by_record.get(&r.id).cloned().unwrap_or_default(),
             exp_gained: None,
             new_level: None,
             total_exp: None,
             current_level: None,
             level_progress: None,
+            trained_with: partners.remove(&r.id).unwrap_or_default(),
+            shared_session_id: r.shared_session_id,
+            started_at: r.started_at,
+            finished_at: r.finished_at,
+            new_personal_records: vec![],
+            weight_unit: None,
+            completed_goals: vec![],
+            new_achievements: vec![],
         })
         .collect();
 
     Ok(result)
 }
 
+/// 記録メモを検証し、前後の空白を除いた値を返す
+fn validate_note(note: &str) -> Result<&str, AppError> {
+    let note = note.trim();
+    if note.chars().count() > MAX_NOTE_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "メモは{}文字以内で入力してください",
+            MAX_NOTE_CHARS
+        )));
+    }
+    Ok(note)
+}
+
+/// 記録メモを更新（空文字の場合は削除）
+async fn update_record_note(
+    conn: &mut MySqlConnection,
+    record_id: i64,
+    note: &str,
+) -> Result<(), AppError> {
+    sqlx::query("UPDATE training_records SET note = NULLIF(?, '') WHERE id = ?")
+        .bind(note)
+        .bind(record_id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// 記録のロック確認
+/// 記録日からrecord_lock_days日を過ぎた記録は一般ユーザーは追加・削除できない。
+/// 管理者本人、または管理者がロック解除（unlocked_until）した記録は対象外
+/// todayはユーザーのタイムゾーンでの今日の日付
+pub(crate) fn ensure_record_unlocked(
+    config: &AppConfig,
+    user: &SessionUser,
+    today: NaiveDate,
+    record_date: NaiveDate,
+    unlocked: bool,
+) -> Result<(), AppError> {
+    if config.record_lock_days == 0 || unlocked || is_admin(user) {
+        return Ok(());
+    }
+
+    if (today - record_date).num_days() > config.record_lock_days {
+        return Err(AppError::RecordLocked(format!(
+            "{}日以上前の記録は変更できません",
+            config.record_lock_days
+        )));
+    }
+    Ok(())
+}
+
+/// 種目のEXP計算に使う情報
+struct ExerciseExpInfo {
+    is_custom: bool,
+    /// Difficulty: 上級=30, 中級=20, 初級=10, custom=ユーザー設定（デフォルト15）
+    difficulty_coef: i32,
+    /// 自重種目（体重 + 追加重量で計算する）
+    is_bodyweight: bool,
+}
+
+impl ExerciseExpInfo {
+    /// EXP計算上の重量（自重種目は体重 + 追加重量、体重未設定なら追加重量のみ）
+    fn load_weight(&self, set: &SaveSetDto, body_weight: Option<f64>) -> f64 {
+        if self.is_bodyweight {
+            body_weight.unwrap_or(0.0) + set.weight
+        } else {
+            set.weight
+        }
+    }
+}
+
+/// カスタム種目かどうか・難易度係数・自重種目かどうかを取得
+async fn exercise_exp_info(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    exercise_id: i64,
+) -> Result<ExerciseExpInfo, AppError> {
+    let custom: Option<(bool, Option<i32>)> = sqlx::query_as(
+        "SELECT is_bodyweight, difficulty_coef FROM user_custom_exercises WHERE id = ? AND user_id = ?",
+    )
+    .bind(exercise_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some((is_bodyweight, difficulty_coef)) = custom {
+        return Ok(ExerciseExpInfo {
+            is_custom: true,
+            difficulty_coef: difficulty_coef.unwrap_or(DEFAULT_CUSTOM_DIFFICULTY_COEF),
+            is_bodyweight,
+        });
+    }
+
+    let exercise: Option<(String, bool)> =
+        sqlx::query_as("SELECT difficulty, is_bodyweight FROM exercises WHERE id = ?")
+            .bind(exercise_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    let difficulty_coef = match exercise.as_ref().map(|(d, _)| d.as_str()) {
+        Some("上級") | Some("hard") => 30,
+        Some("中級") | Some("medium") => 20,
+        Some("初級") | Some("easy") => 10,
+        _ => 15,
+    };
+    Ok(ExerciseExpInfo {
+        is_custom: false,
+        difficulty_coef,
+        is_bodyweight: exercise.is_some_and(|(_, bw)| bw),
+    })
+}
+
+/// セットの入力値チェック
+fn validate_set(set: &SaveSetDto) -> Result<(), AppError> {
+    // バリデーション: 重量は0〜500kgの範囲
+    if set.weight < 0.0 || set.weight > 500.0 {
+        return Err(AppError::BadRequest(
+            "重量は0〜500kgの範囲で入力してください".into(),
+        ));
+    }
+    // バリデーション: 回数は0〜20の範囲
+    if set.reps < 0 || set.reps > 20 {
+        return Err(AppError::BadRequest(
+            "回数は0〜20の範囲で入力してください".into(),
+        ));
+    }
+    if !SET_TYPES.contains(&set.set_type()) {
+        return Err(AppError::BadRequest(format!(
+            "セットの種類が不正です: {}",
+            set.set_type()
+        )));
+    }
+    if set
+        .memo()
+        .is_some_and(|m| m.chars().count() > MAX_SET_MEMO_CHARS)
+    {
+        return Err(AppError::BadRequest(format!(
+            "セットのメモは{}文字以内で入力してください",
+            MAX_SET_MEMO_CHARS
+        )));
+    }
+    // バリデーション: RPEは1〜10の0.5刻み
+    if let Some(rpe) = set.rpe {
+        if !(1.0..=10.0).contains(&rpe) || (rpe * 2.0).fract() != 0.0 {
+            return Err(AppError::BadRequest(
+                "RPEは1〜10の範囲（0.5刻み）で入力してください".into(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// スーパーセットの組番号の上限
+const MAX_SUPERSET_GROUP: i32 = 99;
+
+fn validate_superset_group(ex: &SaveWorkoutExerciseDto) -> Result<(), AppError> {
+    if ex
+        .superset_group
+        .is_some_and(|g| !(1..=MAX_SUPERSET_GROUP).contains(&g))
+    {
+        return Err(AppError::BadRequest(format!(
+            "スーパーセットの組番号は1〜{}で指定してください",
+            MAX_SUPERSET_GROUP
+        )));
+    }
+    Ok(())
+}
+
+/// 入力された重量の単位（省略時はユーザー設定の単位）
+fn input_weight_unit(
+    requested: Option<&str>,
+    preferred: WeightUnit,
+) -> Result<WeightUnit, AppError> {
+    match requested {
+        None => Ok(preferred),
+        Some(unit) => WeightUnit::parse(unit).ok_or_else(|| {
+            AppError::BadRequest("weightUnitはkgまたはlbを指定してください".to_string())
+        }),
+    }
+}
+
+/// セットの重量をkgに揃える（記録は常にkgで保存する）
+fn convert_weights_to_kg(exercises: &mut [SaveWorkoutExerciseDto], unit: WeightUnit) {
+    for set in exercises.iter_mut().flat_map(|ex| ex.sets.iter_mut()) {
+        set.weight = unit.to_kg(set.weight);
+    }
+}
+
+/// 記録に表示用の重量の単位を付ける
+pub(crate) fn annotate_weight_unit(records: &mut [WorkoutRecordDto], unit: WeightUnit) {
+    for record in records {
+        record.weight_unit = Some(unit.name());
+    }
+}
+
+/// スーパーセットの種目を組ごとに隣接させる（組の位置は最初の種目の位置、それ以外の順序は維持）
+fn group_supersets(exercises: &mut Vec<WorkoutExerciseDto>) {
+    let mut grouped: Vec<WorkoutExerciseDto> = Vec::with_capacity(exercises.len());
+    for ex in exercises.drain(..) {
+        let insert_at = ex.superset_group.and_then(|group| {
+            grouped
+                .iter()
+                .rposition(|g| g.superset_group == Some(group))
+                .map(|i| i + 1)
+        });
+        match insert_at {
+            Some(i) => grouped.insert(i, ex),
+            None => grouped.push(ex),
+        }
+    }
+    *exercises = grouped;
+}
+
+/// 1セットの基本EXP（レベル・ストリーク倍率の適用前）
+fn calculate_set_exp(
+    exercise: &ExerciseExpInfo,
+    set: &SaveSetDto,
+    body_weight: Option<f64>,
+    exp_config: &ExpConfig,
+    exp_multiplier: f64,
+) -> i32 {
+    // EXP = difficulty_coef × weight × reps × coefficient × multiplier
+    // 自重種目のweightは体重 + 追加重量
+    // Apply per-set cap (max_exp_per_set) to prevent abuse
+    let raw_set_exp = (exercise.difficulty_coef as f64
+        * exercise.load_weight(set, body_weight)
+        * set.reps as f64
+        * exp_config.exp_coefficient
+        * exp_multiplier)
+        .round() as i32;
+    let set_exp = std::cmp::min(raw_set_exp, exp_config.max_exp_per_set);
+    std::cmp::max(1, set_exp)
+}
+
+/// 記録の保存・編集の後に共通で行う判定の結果
+struct RecordHookOutcome {
+    new_personal_records: Vec<PersonalRecordDto>,
+    new_achievements: Vec<AchievementDto>,
+}
+
+/// 記録の保存・編集の後に共通で行う判定（記録と同じトランザクションで実行する）
+/// ストリーク・パートナーのデイリークエスト・ウィークリーチャレンジ・自己ベスト・実績を更新する
+async fn run_record_hooks(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    record_date: NaiveDate,
+    today: NaiveDate,
+    touched_exercises: Vec<ExerciseRef>,
+) -> Result<RecordHookOutcome, AppError> {
+    use crate::api::challenge::{update_challenge_progress, week_start_of};
+    use crate::api::pet_quest::update_pet_quest_progress;
+    use crate::api::streak::record_training_activity;
+
+    // Update training streak
+    record_training_activity(conn, user_id, record_date).await?;
+
+    // 今日の記録ならパートナーのデイリークエストの達成を判定
+    if record_date == today {
+        update_pet_quest_progress(conn, user_id, today).await?;
+    }
+
+    // 今週の記録ならウィークリーチャレンジの達成を判定
+    if record_date >= week_start_of(today) {
+        update_challenge_progress(conn, user_id, today).await?;
+    }
+
+    // 自己ベストを再集計し、更新したものをレスポンスに含める
+    let mut new_personal_records = Vec::new();
+    for exercise in touched_exercises {
+        new_personal_records.extend(refresh_personal_records(conn, user_id, exercise).await?);
+    }
+
+    // 記録日数・総挙上量・ストリークの実績の達成を判定
+    let new_achievements = award_achievements(
+        conn,
+        user_id,
+        &[
+            ACHIEVEMENT_TOTAL_WORKOUTS,
+            ACHIEVEMENT_SESSION_VOLUME,
+            ACHIEVEMENT_TRAINING_STREAK,
+        ],
+    )
+    .await?;
+
+    Ok(RecordHookOutcome {
+        new_personal_records,
+        new_achievements,
+    })
+}
+
 /// POST /api/workout/records
 #[post("/workout/records")]
 async fn save_record(
     pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
     session: Session,
     body: web::Json<SaveWorkoutRequest>,
 ) -> Result<HttpResponse, AppError> {
-    use crate::api::streak::get_user_multipliers;
-    use crate::config::ExpConfig;
-    use chrono::{FixedOffset, Utc};
-
     let session_user = get_current_user(&session)?;
-    let exp_config = ExpConfig::default();
-
-    // Get streak multipliers for EXP bonus
-    let (training_mult, login_mult, _) =
-        get_user_multipliers(pool.get_ref(), session_user.id).await?;
-    let streak_multiplier = 1.0 + training_mult + login_mult; // Combined multiplier
+    let preferred_unit = fetch_weight_unit(pool.get_ref(), session_user.id).await?;
+    let mut body = body.into_inner();
+    let input_unit = input_weight_unit(body.weight_unit.as_deref(), preferred_unit)?;
+    convert_weights_to_kg(&mut body.exercises, input_unit);
+
+    let mut record = save_workout(&pool, &config, &store, &session_user, &body, true).await?;
+    record.weight_unit = Some(preferred_unit.name());
+    Ok(HttpResponse::Ok().json(record))
+}
 
-    // Use JST (UTC+9) with 4:00 AM reset
-    // If current time is before 4:00 AM JST, consider it as previous day
-    let jst = FixedOffset::east_opt(9 * 3600).unwrap();
-    let now_jst = Utc::now().with_timezone(&jst);
-    let today = now_jst.date_naive();
+/// 記録の保存（同日の記録があれば追記）
+/// grant_expがfalseの場合はEXPを付与しない（ストリーク・自己ベストは通常どおり更新）
+/// パートナーのレベルアップ・覚醒・種類の解放はWebSocketの購読者へ通知する
+async fn save_workout(
+    pool: &MySqlPool,
+    config: &AppConfig,
+    store: &SharedStore,
+    session_user: &SessionUser,
+    body: &SaveWorkoutRequest,
+    grant_exp: bool,
+) -> Result<WorkoutRecordDto, AppError> {
+    use crate::api::exp_context::ExpContext;
+
+    // EXP設定・ストリーク倍率・ユーザーステータスを一括取得
+    let exp_context = ExpContext::load(pool, session_user.id).await?;
+    let exp_config = &exp_context.config;
+    let reward_multiplier = exp_context.reward_multiplier(); // Combined multiplier (streak × event)
+
+    // 今日の判定はユーザーのタイムゾーンで行う
+    let today = fetch_user_today(pool, session_user.id).await?;
 
     let record_date = NaiveDate::parse_from_str(&body.date, "%Y-%m-%d")
         .map_err(|_| AppError::BadRequest("Invalid date format".to_string()))?;
+    let note = body.note.as_deref().map(validate_note).transpose()?;
 
     // Reject future dates
     if record_date > today {
@@ -603,25 +1360,43 @@ async fn save_record(
     // Determine if this is a "past record" (2+ days ago from today)
     let days_ago = (today - record_date).num_days();
     let is_past_record = days_ago >= exp_config.past_days_threshold;
-    let exp_multiplier = exp_config.get_exp_multiplier(is_past_record);
+    let exp_multiplier = if grant_exp {
+        exp_config.get_exp_multiplier(is_past_record)
+    } else {
+        0.0
+    };
     let daily_limit = exp_config.get_daily_limit(is_past_record);
 
+    // 記録・種目・セット・EXP・ストリーク・ペットEXPは1トランザクションで保存する
+    // 途中で失敗した場合はロールバックされ、記録やexp_earnedが中途半端に残らない
+    let mut tx = pool.begin().await?;
+
     // Find existing record or create new one (APPEND mode like Spring Boot)
-    let existing_record: Option<(i64, i32)> = sqlx::query_as(
-        "SELECT id, COALESCE(exp_earned, 0) FROM training_records WHERE user_id = ? AND record_date = ?",
+    let existing_record: Option<(i64, i32, i64)> = sqlx::query_as(
+        r#"SELECT id, COALESCE(exp_earned, 0),
+                  CAST(COALESCE(unlocked_until > NOW(), 0) AS SIGNED)
+           FROM training_records WHERE user_id = ? AND record_date = ?
+           FOR UPDATE"#,
     )
     .bind(session_user.id)
     .bind(record_date)
-    .fetch_optional(pool.get_ref())
+    .fetch_optional(&mut *tx)
     .await?;
 
-    let old_exp_earned = existing_record.as_ref().map(|(_, exp)| *exp).unwrap_or(0);
+    // ロック期間を過ぎた日付への追加（新規作成を含む）は不可
+    let unlocked = existing_record.as_ref().is_some_and(|(_, _, u)| *u != 0);
+    ensure_record_unlocked(config, session_user, today, record_date, unlocked)?;
 
-    let record_id = if let Some((id, _)) = existing_record {
+    let old_exp_earned = existing_record
+        .as_ref()
+        .map(|(_, exp, _)| *exp)
+        .unwrap_or(0);
+
+    let record_id = if let Some((id, _, _)) = existing_record {
         // Update existing record's timestamp (NO DELETE - APPEND mode)
         sqlx::query("UPDATE training_records SET updated_at = NOW() WHERE id = ?")
             .bind(id)
-            .execute(pool.get_ref())
+            .execute(&mut *tx)
             .await?;
         id
     } else {
@@ -632,53 +1407,51 @@ async fn save_record(
         )
         .bind(session_user.id)
         .bind(record_date)
-        .execute(pool.get_ref())
+        .execute(&mut *tx)
         .await?;
         result.last_insert_id() as i64
     };
 
+    if let Some(note) = note {
+        update_record_note(&mut tx, record_id, note).await?;
+    }
+
+    // 今回の保存で追加するセットをまとめる単位（undo-lastで取り消せるようにする）
+    let save_batch_id = sqlx::query(
+        "INSERT INTO training_save_batches (record_id, created_record, created_at) VALUES (?, ?, NOW())",
+    )
+    .bind(record_id)
+    .bind(existing_record.is_none())
+    .execute(&mut *tx)
+    .await?
+    .last_insert_id() as i64;
+
     // Get current max order_index for this record
     let max_order: Option<(Option<i32>,)> = sqlx::query_as(
         "SELECT MAX(order_index) FROM training_record_exercises WHERE record_id = ?",
     )
     .bind(record_id)
-    .fetch_optional(pool.get_ref())
+    .fetch_optional(&mut *tx)
     .await?;
     let mut next_order_index = max_order.and_then(|o| o.0).map(|v| v + 1).unwrap_or(0);
 
     // Calculate EXP per set with difficulty coefficient
     // Formula: difficulty_coef × weight × reps × 0.01 × multiplier
-    // Difficulty: 上級=30, 中級=20, 初級=10, custom=15
+    // Difficulty: 上級=30, 中級=20, 初級=10, custom=ユーザー設定（デフォルト15）
     let mut total_exp_earned = 0i32;
+    let mut touched_exercises: Vec<ExerciseRef> = Vec::new();
 
     for ex in body.exercises.iter() {
-        // Check if exercise is custom and get difficulty
-        let is_custom: (i64,) = sqlx::query_as(
-            "SELECT COUNT(*) FROM user_custom_exercises WHERE id = ? AND user_id = ?",
-        )
-        .bind(ex.exercise_id)
-        .bind(session_user.id)
-        .fetch_one(pool.get_ref())
-        .await?;
-        let is_custom = is_custom.0 > 0;
-
-        // Get difficulty coefficient
-        let difficulty_coef: i32 = if is_custom {
-            15 // カスタム種目のデフォルト
-        } else {
-            let diff: Option<(String,)> =
-                sqlx::query_as("SELECT difficulty FROM exercises WHERE id = ?")
-                    .bind(ex.exercise_id)
-                    .fetch_optional(pool.get_ref())
-                    .await?;
-
-            match diff.as_ref().map(|(d,)| d.as_str()) {
-                Some("上級") | Some("hard") => 30,
-                Some("中級") | Some("medium") => 20,
-                Some("初級") | Some("easy") => 10,
-                _ => 15,
-            }
+        validate_superset_group(ex)?;
+        let exp_info = exercise_exp_info(&mut tx, session_user.id, ex.exercise_id).await?;
+        let is_custom = exp_info.is_custom;
+        let exercise = ExerciseRef {
+            id: ex.exercise_id,
+            is_custom,
         };
+        if !touched_exercises.contains(&exercise) {
+            touched_exercises.push(exercise);
+        }
 
         // Check if this exercise already exists in this record (APPEND mode)
         let existing_record_exercise: Option<(i64,)> = if is_custom {
@@ -687,7 +1460,7 @@ async fn save_record(
             )
             .bind(record_id)
             .bind(ex.exercise_id)
-            .fetch_optional(pool.get_ref())
+            .fetch_optional(&mut *tx)
             .await?
         } else {
             sqlx::query_as(
@@ -695,34 +1468,45 @@ async fn save_record(
             )
             .bind(record_id)
             .bind(ex.exercise_id)
-            .fetch_optional(pool.get_ref())
+            .fetch_optional(&mut *tx)
             .await?
         };
 
         let record_exercise_id = if let Some((id,)) = existing_record_exercise {
             // Use existing record exercise
+            if ex.superset_group.is_some() {
+                sqlx::query("UPDATE training_record_exercises SET superset_group = ? WHERE id = ?")
+                    .bind(ex.superset_group)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
             id
         } else {
             // Create new record exercise
             let re_result = if is_custom {
                 sqlx::query(
-                    r#"INSERT INTO training_record_exercises (record_id, custom_exercise_id, order_index)
-                       VALUES (?, ?, ?)"#,
+                    r#"INSERT INTO training_record_exercises
+                           (record_id, custom_exercise_id, order_index, superset_group)
+                       VALUES (?, ?, ?, ?)"#,
                 )
                 .bind(record_id)
                 .bind(ex.exercise_id)
                 .bind(next_order_index)
-                .execute(pool.get_ref())
+                .bind(ex.superset_group)
+                .execute(&mut *tx)
                 .await?
             } else {
                 sqlx::query(
-                    r#"INSERT INTO training_record_exercises (record_id, exercise_id, order_index)
-                       VALUES (?, ?, ?)"#,
+                    r#"INSERT INTO training_record_exercises
+                           (record_id, exercise_id, order_index, superset_group)
+                       VALUES (?, ?, ?, ?)"#,
                 )
                 .bind(record_id)
                 .bind(ex.exercise_id)
                 .bind(next_order_index)
-                .execute(pool.get_ref())
+                .bind(ex.superset_group)
+                .execute(&mut *tx)
                 .await?
             };
             next_order_index += 1;
@@ -734,63 +1518,51 @@ async fn save_record(
             "SELECT MAX(set_number) FROM training_sets WHERE record_exercise_id = ?",
         )
         .bind(record_exercise_id)
-        .fetch_optional(pool.get_ref())
+        .fetch_optional(&mut *tx)
         .await?;
-        let mut next_set_number = max_set.and_then(|s| s.0).map(|v| v + 1).unwrap_or(1);
+        let first_set_number = max_set.and_then(|s| s.0).map(|v| v + 1).unwrap_or(1);
 
         // Insert sets and calculate EXP
-        for set in ex.sets.iter() {
-            // バリデーション: 重量は0〜500kgの範囲
-            if set.weight < 0.0 || set.weight > 500.0 {
-                return Err(AppError::BadRequest(
-                    "重量は0〜500kgの範囲で入力してください".into(),
-                ));
-            }
-            // バリデーション: 回数は0〜20の範囲
-            if set.reps < 0 || set.reps > 20 {
-                return Err(AppError::BadRequest(
-                    "回数は0〜20の範囲で入力してください".into(),
-                ));
-            }
+        for (set_number, set) in (first_set_number..).zip(ex.sets.iter()) {
+            validate_set(set)?;
 
             sqlx::query(
-                r#"INSERT INTO training_sets (record_exercise_id, set_number, weight, reps)
-                   VALUES (?, ?, ?, ?)"#,
+                r#"INSERT INTO training_sets
+                       (record_exercise_id, set_number, weight, reps, set_type, rpe, memo,
+                        save_batch_id, created_at, updated_at)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, ?, NOW(), NOW())"#,
             )
             .bind(record_exercise_id)
-            .bind(next_set_number)
+            .bind(set_number)
             .bind(set.weight)
             .bind(set.reps)
-            .execute(pool.get_ref())
+            .bind(set.set_type())
+            .bind(set.rpe)
+            .bind(set.memo())
+            .bind(save_batch_id)
+            .execute(&mut *tx)
             .await?;
 
-            // EXP = difficulty_coef × weight × reps × coefficient × multiplier
-            // Apply per-set cap (max_exp_per_set) to prevent abuse
-            let raw_set_exp = (difficulty_coef as f64
-                * set.weight
-                * set.reps as f64
-                * exp_config.exp_coefficient
-                * exp_multiplier)
-                .round() as i32;
-            let set_exp = std::cmp::min(raw_set_exp, exp_config.max_exp_per_set);
-            total_exp_earned += std::cmp::max(1, set_exp);
-            next_set_number += 1;
+            if !set.is_warmup() {
+                total_exp_earned += calculate_set_exp(
+                    &exp_info,
+                    set,
+                    exp_context.body_weight,
+                    exp_config,
+                    exp_multiplier,
+                );
+            }
         }
     }
 
     // Get current user level for level multiplier
-    let current_stats: Option<UserStats> =
-        sqlx::query_as("SELECT id, user_id, total_exp, level FROM user_stats WHERE user_id = ?")
-            .bind(session_user.id)
-            .fetch_optional(pool.get_ref())
-            .await?;
-    let current_level = current_stats.as_ref().map(|s| s.level).unwrap_or(1);
+    let current_level = exp_context.current_level();
     let level_multiplier = 1.0 + (current_level as f64 / 100.0); // +1% per level, max +100% at Lv100
 
-    // Apply level multiplier and streak multiplier to total EXP
-    // Formula: base_exp × level_mult × streak_mult
+    // Apply level multiplier, streak multiplier and event multiplier to total EXP
+    // Formula: base_exp × level_mult × streak_mult × event_mult
     let boosted_exp =
-        (total_exp_earned as f64 * level_multiplier * streak_multiplier).round() as i32;
+        (total_exp_earned as f64 * level_multiplier * reward_multiplier).round() as i32;
     let total_exp_earned = boosted_exp;
 
     // Calculate daily EXP already earned for this date (including current record's old exp)
@@ -799,7 +1571,7 @@ async fn save_record(
     )
     .bind(session_user.id)
     .bind(record_date)
-    .fetch_one(pool.get_ref())
+    .fetch_one(&mut *tx)
     .await?;
     let existing_daily_exp = existing_daily_exp.0 as i32;
 
@@ -812,41 +1584,35 @@ async fn save_record(
     sqlx::query("UPDATE training_records SET exp_earned = ? WHERE id = ?")
         .bind(new_record_exp)
         .bind(record_id)
-        .execute(pool.get_ref())
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("UPDATE training_save_batches SET exp_earned = ? WHERE id = ?")
+        .bind(actual_exp)
+        .bind(save_batch_id)
+        .execute(&mut *tx)
         .await?;
 
-    // Update user stats (reuse current_stats from earlier)
-    let (new_total_exp, old_level, new_level) = match current_stats {
-        Some(s) => {
-            // Add new exp to total
-            let new_total = s.total_exp + actual_exp as i64;
-            let new_total = std::cmp::max(0, new_total);
-            let new_lvl = UserStats::calculate_level(new_total);
+    // Update user stats
+    let ExpChange {
+        total_exp: mut new_total_exp,
+        old_level,
+        mut new_level,
+    } = award_exp(
+        &mut tx,
+        session_user.id,
+        actual_exp as i64,
+        EXP_SOURCE_WORKOUT,
+        Some(record_id),
+    )
+    .await?;
 
-            sqlx::query(
-                r#"UPDATE user_stats SET total_exp = ?, level = ?, updated_at = NOW() WHERE user_id = ?"#,
-            )
-            .bind(new_total)
-            .bind(new_lvl)
-            .bind(session_user.id)
-            .execute(pool.get_ref())
-            .await?;
-            (new_total, s.level, new_lvl)
-        }
-        None => {
-            let new_lvl = UserStats::calculate_level(actual_exp as i64);
-            sqlx::query(
-                r#"INSERT INTO user_stats (user_id, total_exp, level, created_at, updated_at)
-                   VALUES (?, ?, ?, NOW(), NOW())"#,
-            )
-            .bind(session_user.id)
-            .bind(actual_exp as i64)
-            .bind(new_lvl)
-            .execute(pool.get_ref())
-            .await?;
-            (actual_exp as i64, 1, new_lvl)
-        }
-    };
+    // 達成した目標の報酬EXPを加算（1日の上限の対象外）
+    let completed_goals = complete_achieved_goals(&mut tx, session_user.id, today).await?;
+    let goal_exp: i64 = completed_goals.iter().map(|g| g.exp_reward as i64).sum();
+    if goal_exp > 0 {
+        new_total_exp += goal_exp;
+        new_level = UserStats::calculate_level(new_total_exp);
+    }
 
     let level_up = if new_level > old_level {
         Some(new_level)
@@ -865,43 +1631,1006 @@ async fn save_record(
         }
     };
 
-    // Update training streak
-    use crate::api::streak::record_training_activity;
-    let _ = record_training_activity(pool.get_ref(), session_user.id, record_date).await;
+    // 保存済みの日付の下書きは不要
+    delete_draft(&mut tx, session_user.id, record_date).await?;
+
+    // アクティブペットにも同量の経験値を付与
+    use crate::api::pet::{
+        add_exp_to_active_pet, check_and_unlock_pet_types, record_pet_exp_for_record,
+    };
+    use crate::api::pet_events::publish_pet_events;
+    let pet_gain = if actual_exp > 0 {
+        add_exp_to_active_pet(&mut tx, session_user.id, actual_exp as i64).await?
+    } else {
+        None
+    };
+    if let Some(gain) = &pet_gain {
+        // 記録削除時に正しいペットから差し引けるよう付与先を記録
+        record_pet_exp_for_record(&mut tx, record_id, gain.pet_id, actual_exp as i64).await?;
+    }
+    let pet_matured = pet_gain.as_ref().is_some_and(|gain| gain.matured);
+
+    let hooks = run_record_hooks(
+        &mut tx,
+        session_user.id,
+        record_date,
+        today,
+        touched_exercises,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    // ペットの成熟・ユーザーのレベルアップ時、イベントのペットがある場合は解放条件をチェック
+    let gained_exp = actual_exp > 0 || goal_exp > 0;
+    let unlocked_pet_types = if pet_matured
+        || (gained_exp && (level_up.is_some() || exp_context.has_event_pet()))
+    {
+        check_and_unlock_pet_types(pool, session_user.id)
+            .await
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    publish_pet_events(store, session_user.id, pet_gain.as_ref(), &unlocked_pet_types).await;
+
+    Ok(WorkoutRecordDto {
+        id: record_id,
+        date: body.date.clone(),
+        note: note.filter(|n| !n.is_empty()).map(str::to_string),
+        exercises: vec![],
+        exp_gained: Some(actual_exp),
+        new_level: level_up,
+        total_exp: Some(new_total_exp),
+        current_level: Some(new_level),
+        level_progress: Some(level_progress),
+        shared_session_id: None,
+        started_at: None,
+        finished_at: None,
+        trained_with: vec![],
+        new_personal_records: hooks.new_personal_records,
+        weight_unit: None,
+        completed_goals,
+        new_achievements: hooks.new_achievements,
+    })
+}
+
+/// POST /api/workout/records/copy
+/// 指定日の種目・セットを別の日付に複製する（メモは複製しない）
+/// 複製先に記録がある場合は通常の保存と同じく追記する
+#[post("/workout/records/copy")]
+async fn copy_record(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    body: web::Json<CopyWorkoutRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let source_date = NaiveDate::parse_from_str(&body.source_date, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("Invalid date format".to_string()))?;
+    if body.source_date == body.target_date {
+        return Err(AppError::BadRequest(
+            "複製元と複製先に同じ日付は指定できません".to_string(),
+        ));
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct SourceSetRow {
+        exercise_id: i64,
+        superset_group: Option<i32>,
+        weight: f64,
+        reps: i32,
+        set_type: String,
+        rpe: Option<f64>,
+    }
+
+    let rows: Vec<SourceSetRow> = sqlx::query_as(
+        r#"SELECT COALESCE(tre.exercise_id, tre.custom_exercise_id) AS exercise_id,
+                  tre.superset_group, ts.weight, ts.reps, ts.set_type, ts.rpe
+           FROM training_records tr
+           INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+           INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+           WHERE tr.user_id = ? AND tr.record_date = ?
+           ORDER BY tre.order_index, ts.set_number"#,
+    )
+    .bind(session_user.id)
+    .bind(source_date)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    if rows.is_empty() {
+        return Err(AppError::NotFound(
+            "複製元の日付に記録がありません".to_string(),
+        ));
+    }
+
+    // 種目ごとにまとめる（並び順は複製元のまま）
+    let mut exercises: Vec<SaveWorkoutExerciseDto> = Vec::new();
+    for row in rows {
+        let set = SaveSetDto {
+            weight: row.weight,
+            reps: row.reps,
+            set_type: Some(row.set_type),
+            rpe: row.rpe,
+            memo: None,
+        };
+        match exercises.last_mut() {
+            Some(ex) if ex.exercise_id == row.exercise_id => ex.sets.push(set),
+            _ => exercises.push(SaveWorkoutExerciseDto {
+                exercise_id: row.exercise_id,
+                superset_group: row.superset_group,
+                sets: vec![set],
+            }),
+        }
+    }
+
+    let request = SaveWorkoutRequest {
+        date: body.target_date.clone(),
+        note: None,
+        exercises,
+        weight_unit: None,
+    };
+    let grant_exp = !body.without_exp;
+    let record =
+        save_workout(&pool, &config, &store, &session_user, &request, grant_exp).await?;
+    Ok(HttpResponse::Ok().json(record))
+}
+
+/// PUT /api/workout/records/{id}
+/// 記録の種目・セットを送信内容で置き換え、EXPを再計算する
+/// 再計算前との差分をユーザーステータスとペットに反映するため、累計EXPは編集後も整合する
+#[put("/workout/records/{id}")]
+async fn update_record(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    path: web::Path<i64>,
+    body: web::Json<UpdateWorkoutRequest>,
+) -> Result<HttpResponse, AppError> {
+    use crate::api::exp_context::ExpContext;
+
+    let session_user = get_current_user(&session)?;
+    let record_id = path.into_inner();
+    let preferred_unit = fetch_weight_unit(pool.get_ref(), session_user.id).await?;
+    let mut body = body.into_inner();
+    let input_unit = input_weight_unit(body.weight_unit.as_deref(), preferred_unit)?;
+    convert_weights_to_kg(&mut body.exercises, input_unit);
+
+    if body.exercises.iter().all(|ex| ex.sets.is_empty()) {
+        return Err(AppError::BadRequest(
+            "セットがありません。記録を空にする場合は削除してください".to_string(),
+        ));
+    }
+    for ex in body.exercises.iter() {
+        validate_superset_group(ex)?;
+        for set in ex.sets.iter() {
+            validate_set(set)?;
+        }
+    }
+    let note = body.note.as_deref().map(validate_note).transpose()?;
+
+    let exp_context = ExpContext::load(pool.get_ref(), session_user.id).await?;
+    let exp_config = &exp_context.config;
+
+    let mut tx = pool.begin().await?;
+
+    let record: Option<(i32, NaiveDate, i64)> = sqlx::query_as(
+        r#"SELECT COALESCE(exp_earned, 0), record_date,
+                  CAST(COALESCE(unlocked_until > NOW(), 0) AS SIGNED)
+           FROM training_records WHERE id = ? AND user_id = ?
+           FOR UPDATE"#,
+    )
+    .bind(record_id)
+    .bind(session_user.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((old_exp_earned, record_date, unlocked)) = record else {
+        return Err(AppError::NotFound("Record not found".to_string()));
+    };
+
+    // 過去記録かどうかは保存時と同じくユーザーのタイムゾーンでの今日からの日数で判定する
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    ensure_record_unlocked(&config, &session_user, today, record_date, unlocked != 0)?;
+
+    let is_past_record = (today - record_date).num_days() >= exp_config.past_days_threshold;
+    let exp_multiplier = exp_config.get_exp_multiplier(is_past_record);
+    let daily_limit = exp_config.get_daily_limit(is_past_record);
+
+    if let Some(note) = note {
+        update_record_note(&mut tx, record_id, note).await?;
+    }
+
+    // 編集前後どちらかに含まれる種目は自己ベストを再集計する
+    let mut touched_exercises = exercises_in_record(&mut tx, record_id).await?;
+
+    // 既存の種目・セットを削除して送信内容で置き換える
+    sqlx::query(
+        r#"DELETE ts FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           WHERE tre.record_id = ?"#,
+    )
+    .bind(record_id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query("DELETE FROM training_record_exercises WHERE record_id = ?")
+        .bind(record_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 編集で置き換えた後は、それ以前の保存単位では取り消せない
+    sqlx::query("DELETE FROM training_save_batches WHERE record_id = ?")
+        .bind(record_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let mut base_exp = 0i32;
+    let exercises = body.exercises.iter().filter(|ex| !ex.sets.is_empty());
+    for (order_index, ex) in (0i32..).zip(exercises) {
+        let exp_info = exercise_exp_info(&mut tx, session_user.id, ex.exercise_id).await?;
+        let is_custom = exp_info.is_custom;
+        let exercise = ExerciseRef {
+            id: ex.exercise_id,
+            is_custom,
+        };
+        if !touched_exercises.contains(&exercise) {
+            touched_exercises.push(exercise);
+        }
+
+        let result = if is_custom {
+            sqlx::query(
+                r#"INSERT INTO training_record_exercises
+                       (record_id, custom_exercise_id, order_index, superset_group)
+                   VALUES (?, ?, ?, ?)"#,
+            )
+            .bind(record_id)
+            .bind(ex.exercise_id)
+            .bind(order_index)
+            .bind(ex.superset_group)
+            .execute(&mut *tx)
+            .await?
+        } else {
+            sqlx::query(
+                r#"INSERT INTO training_record_exercises
+                       (record_id, exercise_id, order_index, superset_group)
+                   VALUES (?, ?, ?, ?)"#,
+            )
+            .bind(record_id)
+            .bind(ex.exercise_id)
+            .bind(order_index)
+            .bind(ex.superset_group)
+            .execute(&mut *tx)
+            .await?
+        };
+        let record_exercise_id = result.last_insert_id() as i64;
+
+        for (set_number, set) in (1..).zip(ex.sets.iter()) {
+            sqlx::query(
+                r#"INSERT INTO training_sets
+                       (record_exercise_id, set_number, weight, reps, set_type, rpe, memo,
+                        created_at, updated_at)
+                   VALUES (?, ?, ?, ?, ?, ?, ?, NOW(), NOW())"#,
+            )
+            .bind(record_exercise_id)
+            .bind(set_number)
+            .bind(set.weight)
+            .bind(set.reps)
+            .bind(set.set_type())
+            .bind(set.rpe)
+            .bind(set.memo())
+            .execute(&mut *tx)
+            .await?;
+
+            if !set.is_warmup() {
+                base_exp += calculate_set_exp(
+                    &exp_info,
+                    set,
+                    exp_context.body_weight,
+                    exp_config,
+                    exp_multiplier,
+                );
+            }
+        }
+    }
+
+    // レベル倍率・ストリーク倍率・イベント倍率を適用し、同日の他の記録と合わせて1日の上限に収める
+    let level_multiplier = 1.0 + (exp_context.current_level() as f64 / 100.0);
+    let boosted_exp =
+        (base_exp as f64 * level_multiplier * exp_context.reward_multiplier()).round() as i32;
+
+    let other_daily_exp: (i64,) = sqlx::query_as(
+        r#"SELECT CAST(COALESCE(SUM(exp_earned), 0) AS SIGNED) FROM training_records
+           WHERE user_id = ? AND record_date = ? AND id <> ?"#,
+    )
+    .bind(session_user.id)
+    .bind(record_date)
+    .bind(record_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let remaining_daily = std::cmp::max(daily_limit - other_daily_exp.0 as i32, 0);
+    let new_record_exp = std::cmp::min(boosted_exp, remaining_daily);
+    let exp_delta = new_record_exp - old_exp_earned;
+
+    sqlx::query("UPDATE training_records SET exp_earned = ?, updated_at = NOW() WHERE id = ?")
+        .bind(new_record_exp)
+        .bind(record_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 差分をユーザーステータスに反映
+    let change = award_exp(
+        &mut tx,
+        session_user.id,
+        exp_delta as i64,
+        EXP_SOURCE_WORKOUT_EDIT,
+        Some(record_id),
+    )
+    .await?;
+    let old_level = change.old_level;
+    let mut stats = UserStats {
+        id: 0,
+        user_id: session_user.id,
+        total_exp: change.total_exp,
+        level: change.new_level,
+    };
+
+    // 達成した目標の報酬EXPを加算
+    let completed_goals = complete_achieved_goals(&mut tx, session_user.id, today).await?;
+    let goal_exp: i64 = completed_goals.iter().map(|g| g.exp_reward as i64).sum();
+    if goal_exp > 0 {
+        stats.total_exp += goal_exp;
+        stats.level = UserStats::calculate_level(stats.total_exp);
+    }
+
+    // 差分をペットに反映（増えた分はアクティブペット、減った分は付与済みのペットから）
+    use crate::api::pet::{
+        add_exp_to_active_pet, check_and_unlock_pet_types, deduct_pet_exp_for_record,
+        record_pet_exp_for_record,
+    };
+    use crate::api::pet_events::publish_pet_events;
+    let pet_gain = if exp_delta > 0 {
+        add_exp_to_active_pet(&mut tx, session_user.id, exp_delta as i64).await?
+    } else {
+        None
+    };
+    if let Some(gain) = &pet_gain {
+        record_pet_exp_for_record(&mut tx, record_id, gain.pet_id, exp_delta as i64).await?;
+    } else if exp_delta < 0 {
+        deduct_pet_exp_for_record(&mut tx, session_user.id, record_id, -exp_delta as i64).await?;
+    }
+    let pet_matured = pet_gain.as_ref().is_some_and(|gain| gain.matured);
+
+    let hooks = run_record_hooks(
+        &mut tx,
+        session_user.id,
+        record_date,
+        today,
+        touched_exercises,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    let level_up = (stats.level > old_level).then_some(stats.level);
+    let unlocked_pet_types = if pet_matured || level_up.is_some() {
+        check_and_unlock_pet_types(pool.get_ref(), session_user.id)
+            .await
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    publish_pet_events(
+        &store,
+        session_user.id,
+        pet_gain.as_ref(),
+        &unlocked_pet_types,
+    )
+    .await;
+
+    Ok(HttpResponse::Ok().json(WorkoutRecordDto {
+        id: record_id,
+        date: record_date.format("%Y-%m-%d").to_string(),
+        note: note.filter(|n| !n.is_empty()).map(str::to_string),
+        exercises: vec![],
+        exp_gained: Some(exp_delta),
+        new_level: level_up,
+        total_exp: Some(stats.total_exp),
+        current_level: Some(stats.level),
+        level_progress: Some(stats.get_level_progress()),
+        shared_session_id: None,
+        started_at: None,
+        finished_at: None,
+        trained_with: vec![],
+        new_personal_records: hooks.new_personal_records,
+        weight_unit: Some(preferred_unit.name()),
+        completed_goals,
+        new_achievements: hooks.new_achievements,
+    }))
+}
+
+/// DELETE /api/workout/records/{id}
+#[delete("/workout/records/{id}")]
+async fn delete_record(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let record_id = path.into_inner();
+
+    let mut tx = pool.begin().await?;
+
+    // Verify ownership and get exp_earned
+    let record: Option<(i32, NaiveDate, i64)> = sqlx::query_as(
+        r#"SELECT COALESCE(exp_earned, 0), record_date,
+                  CAST(COALESCE(unlocked_until > NOW(), 0) AS SIGNED)
+           FROM training_records WHERE id = ? AND user_id = ?
+           FOR UPDATE"#,
+    )
+    .bind(record_id)
+    .bind(session_user.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let (exp_to_deduct, record_date, unlocked) = match record {
+        Some(r) => r,
+        None => return Err(AppError::NotFound("Record not found".to_string())),
+    };
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    ensure_record_unlocked(&config, &session_user, today, record_date, unlocked != 0)?;
+
+    let touched_exercises = delete_record_rows(&mut tx, session_user.id, record_id).await?;
+    award_exp(
+        &mut tx,
+        session_user.id,
+        -(exp_to_deduct as i64),
+        EXP_SOURCE_WORKOUT_DELETE,
+        Some(record_id),
+    )
+    .await?;
+
+    // 削除した記録の自己ベストは次点の記録に戻す
+    for exercise in touched_exercises {
+        refresh_personal_records(&mut tx, session_user.id, exercise).await?;
+    }
+
+    // Recalculate training streak after deletion
+    use crate::api::streak::recalculate_training_streak;
+    recalculate_training_streak(&mut tx, session_user.id).await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// 一括削除の対象（記録IDの一覧、または期間のどちらか一方を指定）
+#[derive(Deserialize)]
+struct BulkDeleteRecordsRequest {
+    ids: Option<Vec<i64>>,
+    /// 期間の開始日（YYYY-MM-DD、この日を含む）
+    from: Option<String>,
+    /// 期間の終了日（YYYY-MM-DD、この日を含む）
+    to: Option<String>,
+}
+
+/// 一括削除で一度に指定できる記録IDの上限
+const MAX_BULK_DELETE_IDS: usize = 500;
+
+/// DELETE /api/workout/records
+/// 複数の記録を1トランザクションで削除し、EXP・ペットEXP・自己ベスト・ストリークを再計算する
+/// ロック期間を過ぎた記録が1件でも含まれる場合は何も削除しない
+#[delete("/workout/records")]
+async fn bulk_delete_records(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    session: Session,
+    body: web::Json<BulkDeleteRecordsRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let mut tx = pool.begin().await?;
+
+    // 対象の記録をロックして取得（他ユーザーの記録は含めない）
+    let records: Vec<(i64, i32, NaiveDate, i64)> = match (&body.ids, &body.from, &body.to) {
+        (Some(ids), None, None) => {
+            if ids.is_empty() || ids.len() > MAX_BULK_DELETE_IDS {
+                return Err(AppError::BadRequest(format!(
+                    "記録IDは1〜{}件で指定してください",
+                    MAX_BULK_DELETE_IDS
+                )));
+            }
+            let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+            let sql = format!(
+                r#"SELECT id, COALESCE(exp_earned, 0), record_date,
+                          CAST(COALESCE(unlocked_until > NOW(), 0) AS SIGNED)
+                   FROM training_records WHERE user_id = ? AND id IN ({})
+                   FOR UPDATE"#,
+                placeholders
+            );
+            let mut query = sqlx::query_as(&sql).bind(session_user.id);
+            for id in ids {
+                query = query.bind(id);
+            }
+            let records: Vec<(i64, i32, NaiveDate, i64)> = query.fetch_all(&mut *tx).await?;
+
+            // 存在しない・他ユーザーの記録が含まれる場合は何も削除しない
+            if ids.iter().any(|id| !records.iter().any(|r| r.0 == *id)) {
+                return Err(AppError::NotFound("Record not found".to_string()));
+            }
+            records
+        }
+        (None, Some(from), Some(to)) => {
+            let from = NaiveDate::parse_from_str(from, "%Y-%m-%d")
+                .map_err(|_| AppError::BadRequest("Invalid date format".to_string()))?;
+            let to = NaiveDate::parse_from_str(to, "%Y-%m-%d")
+                .map_err(|_| AppError::BadRequest("Invalid date format".to_string()))?;
+            if from > to {
+                return Err(AppError::BadRequest(
+                    "開始日は終了日以前の日付を指定してください".to_string(),
+                ));
+            }
+            sqlx::query_as(
+                r#"SELECT id, COALESCE(exp_earned, 0), record_date,
+                          CAST(COALESCE(unlocked_until > NOW(), 0) AS SIGNED)
+                   FROM training_records
+                   WHERE user_id = ? AND record_date BETWEEN ? AND ?
+                   FOR UPDATE"#,
+            )
+            .bind(session_user.id)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&mut *tx)
+            .await?
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "記録IDの一覧、または期間（from・to）のどちらか一方を指定してください".to_string(),
+            ));
+        }
+    };
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    for (_, _, record_date, unlocked) in &records {
+        ensure_record_unlocked(&config, &session_user, today, *record_date, *unlocked != 0)?;
+    }
+
+    let mut touched_exercises: Vec<ExerciseRef> = Vec::new();
+    let mut exp_to_deduct = 0i64;
+    for (record_id, exp_earned, _, _) in &records {
+        for exercise in delete_record_rows(&mut tx, session_user.id, *record_id).await? {
+            if !touched_exercises.contains(&exercise) {
+                touched_exercises.push(exercise);
+            }
+        }
+        exp_to_deduct += *exp_earned as i64;
+    }
+
+    award_exp(
+        &mut tx,
+        session_user.id,
+        -exp_to_deduct,
+        EXP_SOURCE_WORKOUT_DELETE,
+        None,
+    )
+    .await?;
+
+    for exercise in touched_exercises {
+        refresh_personal_records(&mut tx, session_user.id, exercise).await?;
+    }
+
+    if !records.is_empty() {
+        use crate::api::streak::recalculate_training_streak;
+        recalculate_training_streak(&mut tx, session_user.id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "deletedCount": records.len(),
+        "expDeducted": exp_to_deduct,
+    })))
+}
+
+/// 記録と、その種目・セット・コメント・合同トレーニングの招待を削除し、記録でペットが得たEXPを差し引く
+/// 自己ベストの再集計対象として、記録に含まれていた種目を返す
+async fn delete_record_rows(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    record_id: i64,
+) -> Result<Vec<ExerciseRef>, AppError> {
+    let touched_exercises = exercises_in_record(&mut *conn, record_id).await?;
+
+    // Delete sets first
+    sqlx::query(
+        r#"DELETE ts FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           WHERE tre.record_id = ?"#,
+    )
+    .bind(record_id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Delete record exercises
+    sqlx::query("DELETE FROM training_record_exercises WHERE record_id = ?")
+        .bind(record_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Delete comments on the record
+    sqlx::query("DELETE FROM workout_comments WHERE record_id = ?")
+        .bind(record_id)
+        .execute(&mut *conn)
+        .await?;
+
+    sqlx::query("DELETE FROM training_save_batches WHERE record_id = ?")
+        .bind(record_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // Delete partner invites sent from or accepted into the record
+    sqlx::query("DELETE FROM workout_partner_invites WHERE record_id = ? OR partner_record_id = ?")
+        .bind(record_id)
+        .bind(record_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let shared_session_id: Option<(Option<String>,)> =
+        sqlx::query_as("SELECT shared_session_id FROM training_records WHERE id = ?")
+            .bind(record_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+
+    // Delete record
+    sqlx::query("DELETE FROM training_records WHERE id = ?")
+        .bind(record_id)
+        .execute(&mut *conn)
+        .await?;
+
+    // 相手が一人だけ残る合同トレーニングは、残った記録の紐付けも外す
+    if let Some((Some(shared_session_id),)) = shared_session_id {
+        let (remaining,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM training_records WHERE shared_session_id = ?")
+                .bind(&shared_session_id)
+                .fetch_one(&mut *conn)
+                .await?;
+        if remaining < 2 {
+            sqlx::query(
+                "UPDATE training_records SET shared_session_id = NULL WHERE shared_session_id = ?",
+            )
+            .bind(&shared_session_id)
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    // Deduct EXP from the pet(s) that actually earned it on this record
+    crate::api::pet::deduct_record_exp_from_pets(&mut *conn, user_id, record_id).await?;
+
+    Ok(touched_exercises)
+}
+
+/// 直前の保存の取り消しで差し引くEXPと、記録ごと削除するか
+#[derive(Debug, PartialEq)]
+struct UndoOutcome {
+    exp_deducted: i32,
+    record_deleted: bool,
+}
+
+/// 取り消し後にセットが残っていない記録は、追記先の既存の記録でも記録ごと削除する
+/// （空の記録がストリーク・履歴に残らないようにする）
+/// 記録ごと削除する場合は記録の削除と同じく記録のEXPをすべて差し引き、
+/// それ以外は記録のEXPを超えない範囲で保存分を差し引く（編集でEXPが減っている場合など）
+fn undo_outcome(record_exp: i32, batch_exp: i32, remaining_sets: i64) -> UndoOutcome {
+    let record_deleted = remaining_sets == 0;
+    let exp_deducted = if record_deleted {
+        record_exp.max(0)
+    } else {
+        batch_exp.clamp(0, record_exp.max(0))
+    };
+    UndoOutcome {
+        exp_deducted,
+        record_deleted,
+    }
+}
+
+/// POST /api/workout/records/{id}/undo-last
+/// 直前の保存で追加したセットを削除し、その保存で獲得したEXPを差し引く
+/// （追記保存のため、二重送信で重複したセットを取り消せるようにする）
+#[post("/workout/records/{id}/undo-last")]
+async fn undo_last_save(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let record_id = path.into_inner();
+
+    let mut tx = pool.begin().await?;
+
+    let record: Option<(i32, NaiveDate, i64)> = sqlx::query_as(
+        r#"SELECT COALESCE(exp_earned, 0), record_date,
+                  CAST(COALESCE(unlocked_until > NOW(), 0) AS SIGNED)
+           FROM training_records WHERE id = ? AND user_id = ?
+           FOR UPDATE"#,
+    )
+    .bind(record_id)
+    .bind(session_user.id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((record_exp, record_date, unlocked)) = record else {
+        return Err(AppError::NotFound("Record not found".to_string()));
+    };
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    ensure_record_unlocked(&config, &session_user, today, record_date, unlocked != 0)?;
+
+    let batch: Option<(i64, i32)> = sqlx::query_as(
+        r#"SELECT id, exp_earned FROM training_save_batches
+           WHERE record_id = ? ORDER BY id DESC LIMIT 1"#,
+    )
+    .bind(record_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some((batch_id, batch_exp)) = batch else {
+        return Err(AppError::BadRequest(
+            "取り消せる保存がありません".to_string(),
+        ));
+    };
+
+    // 取り消すセットの種目（自己ベストの再集計と、空になった種目の削除に使う）
+    let batch_exercises: Vec<(i64, Option<i64>, Option<i64>)> = sqlx::query_as(
+        r#"SELECT DISTINCT tre.id, tre.exercise_id, tre.custom_exercise_id
+           FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           WHERE ts.save_batch_id = ?"#,
+    )
+    .bind(batch_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let removed_set_count = sqlx::query("DELETE FROM training_sets WHERE save_batch_id = ?")
+        .bind(batch_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // セットがなくなった種目は記録から外す
+    for (record_exercise_id, _, _) in &batch_exercises {
+        sqlx::query(
+            r#"DELETE FROM training_record_exercises
+               WHERE id = ? AND NOT EXISTS (
+                   SELECT 1 FROM training_sets WHERE record_exercise_id = ?
+               )"#,
+        )
+        .bind(record_exercise_id)
+        .bind(record_exercise_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM training_save_batches WHERE id = ?")
+        .bind(batch_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let remaining_sets: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*) FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           WHERE tre.record_id = ?"#,
+    )
+    .bind(record_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let UndoOutcome {
+        exp_deducted,
+        record_deleted,
+    } = undo_outcome(record_exp, batch_exp, remaining_sets);
+    award_exp(
+        &mut tx,
+        session_user.id,
+        -(exp_deducted as i64),
+        EXP_SOURCE_WORKOUT_UNDO,
+        Some(record_id),
+    )
+    .await?;
+
+    if record_deleted {
+        delete_record_rows(&mut tx, session_user.id, record_id).await?;
+    } else {
+        sqlx::query("UPDATE training_records SET exp_earned = ?, updated_at = NOW() WHERE id = ?")
+            .bind(record_exp - exp_deducted)
+            .bind(record_id)
+            .execute(&mut *tx)
+            .await?;
+        crate::api::pet::deduct_pet_exp_for_record(
+            &mut tx,
+            session_user.id,
+            record_id,
+            exp_deducted as i64,
+        )
+        .await?;
+    }
+
+    for (_, exercise_id, custom_exercise_id) in batch_exercises {
+        let exercise = match (custom_exercise_id, exercise_id) {
+            (Some(id), _) => ExerciseRef { id, is_custom: true },
+            (None, Some(id)) => ExerciseRef {
+                id,
+                is_custom: false,
+            },
+            (None, None) => continue,
+        };
+        refresh_personal_records(&mut tx, session_user.id, exercise).await?;
+    }
+
+    if record_deleted {
+        use crate::api::streak::recalculate_training_streak;
+        recalculate_training_streak(&mut tx, session_user.id).await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "removedSetCount": removed_set_count,
+        "expDeducted": exp_deducted,
+        "recordDeleted": record_deleted,
+    })))
+}
+
+/// DELETE /api/workout/sets/{id}
+#[delete("/workout/sets/{id}")]
+async fn delete_set(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let set_id = path.into_inner();
+
+    // Verify ownership
+    let ownership: Option<(NaiveDate, i64, Option<i64>, Option<i64>)> = sqlx::query_as(
+        r#"SELECT tr.record_date, CAST(COALESCE(tr.unlocked_until > NOW(), 0) AS SIGNED),
+                  tre.exercise_id, tre.custom_exercise_id
+           FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE ts.id = ? AND tr.user_id = ?"#,
+    )
+    .bind(set_id)
+    .bind(session_user.id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+
+    let Some((record_date, unlocked, exercise_id, custom_exercise_id)) = ownership else {
+        return Err(AppError::NotFound("Set not found".to_string()));
+    };
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    ensure_record_unlocked(&config, &session_user, today, record_date, unlocked != 0)?;
+
+    sqlx::query("DELETE FROM training_sets WHERE id = ?")
+        .bind(set_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    let exercise = match (custom_exercise_id, exercise_id) {
+        (Some(id), _) => Some(ExerciseRef { id, is_custom: true }),
+        (None, Some(id)) => Some(ExerciseRef {
+            id,
+            is_custom: false,
+        }),
+        (None, None) => None,
+    };
+    if let Some(exercise) = exercise {
+        refresh_personal_records(&mut *pool.acquire().await?, session_user.id, exercise).await?;
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+// ============================================
+// Timing
+// ============================================
+
+/// この間隔を超える空きは休憩ではなく中断とみなし、レスト統計から除外する（秒）
+const MAX_REST_SECONDS: i64 = 30 * 60;
+
+#[derive(Serialize)]
+struct RecordTimingDto {
+    #[serde(rename = "recordId")]
+    record_id: i64,
+    date: String,
+    #[serde(rename = "startedAt")]
+    started_at: Option<String>,
+    #[serde(rename = "finishedAt")]
+    finished_at: Option<String>,
+    #[serde(rename = "durationSeconds")]
+    duration_seconds: Option<i64>,
+    #[serde(rename = "setCount")]
+    set_count: usize,
+    #[serde(rename = "timedSetCount")]
+    timed_set_count: usize,
+    rest: RestStatsDto,
+    exercises: Vec<ExerciseTimingDto>,
+}
+
+#[derive(Serialize, Default)]
+struct RestStatsDto {
+    #[serde(rename = "intervalCount")]
+    interval_count: usize,
+    #[serde(rename = "averageSeconds")]
+    average_seconds: Option<i64>,
+    #[serde(rename = "medianSeconds")]
+    median_seconds: Option<i64>,
+    #[serde(rename = "longestSeconds")]
+    longest_seconds: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ExerciseTimingDto {
+    id: i64,
+    name: String,
+    #[serde(rename = "setCount")]
+    set_count: usize,
+    #[serde(rename = "firstSetAt")]
+    first_set_at: Option<String>,
+    #[serde(rename = "lastSetAt")]
+    last_set_at: Option<String>,
+    rest: RestStatsDto,
+}
+
+/// 時刻列から休憩統計を計算
+/// 同じ保存リクエストで登録されたセットは同時刻になるため、時刻が変わった間隔のみを休憩として扱う
+fn rest_stats(times: &[NaiveDateTime]) -> RestStatsDto {
+    let mut sorted = times.to_vec();
+    sorted.sort();
+    sorted.dedup();
+
+    let mut rests: Vec<i64> = sorted
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_seconds())
+        .filter(|secs| *secs > 0 && *secs <= MAX_REST_SECONDS)
+        .collect();
 
-    // アクティブペットにも同量の経験値を付与
-    if actual_exp > 0 {
-        use crate::api::pet::{add_exp_to_active_pet, check_and_unlock_pet_types};
-        if let Ok(Some((_pet_level, _level_up, matured))) = 
-            add_exp_to_active_pet(pool.get_ref(), session_user.id, actual_exp as i64).await 
-        {
-            // ペットが成熟したら解放条件をチェック
-            if matured {
-                let _ = check_and_unlock_pet_types(pool.get_ref(), session_user.id).await;
-            }
-        }
-        // ユーザーがレベルアップした場合も解放条件をチェック
-        if level_up.is_some() {
-            use crate::api::pet::check_and_unlock_pet_types;
-            let _ = check_and_unlock_pet_types(pool.get_ref(), session_user.id).await;
-        }
+    if rests.is_empty() {
+        return RestStatsDto::default();
     }
 
-    Ok(HttpResponse::Ok().json(WorkoutRecordDto {
-        id: record_id,
-        date: body.date.clone(),
-        exercises: vec![],
-        exp_gained: Some(actual_exp),
-        new_level: level_up,
-        total_exp: Some(new_total_exp),
-        current_level: Some(new_level),
-        level_progress: Some(level_progress),
-    }))
+    rests.sort_unstable();
+    let count = rests.len();
+    let median = if count.is_multiple_of(2) {
+        (rests[count / 2 - 1] + rests[count / 2]) / 2
+    } else {
+        rests[count / 2]
+    };
+
+    RestStatsDto {
+        interval_count: count,
+        average_seconds: Some(rests.iter().sum::<i64>() / count as i64),
+        median_seconds: Some(median),
+        longest_seconds: rests.last().copied(),
+    }
 }
 
-/// DELETE /api/workout/records/{id}
-#[delete("/workout/records/{id}")]
-async fn delete_record(
+fn format_datetime(dt: NaiveDateTime) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%S").to_string()
+}
+
+/// GET /api/workout/records/{id}/timing
+/// セットの登録時刻からトレーニング時間と休憩時間を算出する（セッション計測なしの過去記録にも対応）
+#[get("/workout/records/{id}/timing")]
+async fn get_record_timing(
     pool: web::Data<MySqlPool>,
     session: Session,
     path: web::Path<i64>,
@@ -909,124 +2638,456 @@ async fn delete_record(
     let session_user = get_current_user(&session)?;
     let record_id = path.into_inner();
 
-    // Verify ownership and get exp_earned
-    let record: Option<(i64, i32)> = sqlx::query_as(
-        "SELECT id, COALESCE(exp_earned, 0) FROM training_records WHERE id = ? AND user_id = ?",
-    )
-    .bind(record_id)
-    .bind(session_user.id)
-    .fetch_optional(pool.get_ref())
-    .await?;
+    let record: Option<(NaiveDate, Option<NaiveDateTime>, Option<NaiveDateTime>)> =
+        sqlx::query_as(
+            r#"SELECT record_date, started_at, finished_at
+               FROM training_records WHERE id = ? AND user_id = ?"#,
+        )
+        .bind(record_id)
+        .bind(session_user.id)
+        .fetch_optional(pool.get_ref())
+        .await?;
 
-    let exp_to_deduct = match record {
-        Some((_, exp)) => exp,
-        None => return Err(AppError::NotFound("Record not found".to_string())),
+    let Some((record_date, session_started_at, session_finished_at)) = record else {
+        return Err(AppError::NotFound("Record not found".to_string()));
     };
 
-    // Delete sets first
-    sqlx::query(
-        r#"DELETE ts FROM training_sets ts
+    #[derive(sqlx::FromRow)]
+    struct TimedSetRow {
+        record_exercise_id: i64,
+        exercise_id: i64,
+        exercise_name: String,
+        created_at: Option<NaiveDateTime>,
+    }
+
+    let sets: Vec<TimedSetRow> = sqlx::query_as(
+        r#"SELECT ts.record_exercise_id,
+                  CAST(COALESCE(tre.custom_exercise_id, tre.exercise_id, 0) AS SIGNED) as exercise_id,
+                  CAST(COALESCE(e.name, uce.name, 'Unknown') AS CHAR) as exercise_name,
+                  ts.created_at
+           FROM training_sets ts
            INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
-           WHERE tre.record_id = ?"#,
+           LEFT JOIN exercises e ON e.id = tre.exercise_id
+           LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+           WHERE tre.record_id = ?
+           ORDER BY tre.order_index ASC, tre.id ASC, ts.set_number ASC"#,
     )
     .bind(record_id)
-    .execute(pool.get_ref())
+    .fetch_all(pool.get_ref())
     .await?;
 
-    // Delete record exercises
-    sqlx::query("DELETE FROM training_record_exercises WHERE record_id = ?")
-        .bind(record_id)
-        .execute(pool.get_ref())
-        .await?;
+    let all_times: Vec<NaiveDateTime> = sets.iter().filter_map(|s| s.created_at).collect();
+    // 開始・終了を記録したセッションはその時刻を優先する
+    let started_at = session_started_at.or_else(|| all_times.iter().min().copied());
+    let finished_at = session_finished_at.or_else(|| all_times.iter().max().copied());
+    let duration_seconds = match (started_at, finished_at) {
+        (Some(start), Some(end)) if end > start => Some((end - start).num_seconds()),
+        _ => None,
+    };
 
-    // Delete record
-    sqlx::query("DELETE FROM training_records WHERE id = ?")
-        .bind(record_id)
-        .execute(pool.get_ref())
-        .await?;
+    // 種目ごとに集計（記録内の並び順を維持）
+    let mut exercises: Vec<ExerciseTimingDto> = Vec::new();
+    let mut exercise_times: Vec<Vec<NaiveDateTime>> = Vec::new();
+    let mut current_re_id = None;
+    for s in &sets {
+        if current_re_id != Some(s.record_exercise_id) {
+            current_re_id = Some(s.record_exercise_id);
+            exercises.push(ExerciseTimingDto {
+                id: s.exercise_id,
+                name: s.exercise_name.clone(),
+                set_count: 0,
+                first_set_at: None,
+                last_set_at: None,
+                rest: RestStatsDto::default(),
+            });
+            exercise_times.push(Vec::new());
+        }
+        if let (Some(ex), Some(times)) = (exercises.last_mut(), exercise_times.last_mut()) {
+            ex.set_count += 1;
+            if let Some(t) = s.created_at {
+                times.push(t);
+            }
+        }
+    }
+    for (ex, times) in exercises.iter_mut().zip(&exercise_times) {
+        ex.first_set_at = times.iter().min().copied().map(format_datetime);
+        ex.last_set_at = times.iter().max().copied().map(format_datetime);
+        ex.rest = rest_stats(times);
+    }
 
-    // Deduct EXP from user stats
-    let stats: Option<UserStats> =
-        sqlx::query_as("SELECT id, user_id, total_exp, level FROM user_stats WHERE user_id = ?")
-            .bind(session_user.id)
-            .fetch_optional(pool.get_ref())
-            .await?;
+    Ok(HttpResponse::Ok().json(RecordTimingDto {
+        record_id,
+        date: record_date.format("%Y-%m-%d").to_string(),
+        started_at: started_at.map(format_datetime),
+        finished_at: finished_at.map(format_datetime),
+        duration_seconds,
+        set_count: sets.len(),
+        timed_set_count: all_times.len(),
+        rest: rest_stats(&all_times),
+        exercises,
+    }))
+}
 
-    if let Some(s) = stats {
-        let new_total = std::cmp::max(0, s.total_exp - exp_to_deduct as i64);
-        let new_level = UserStats::calculate_level(new_total);
-        sqlx::query(
-            r#"UPDATE user_stats SET total_exp = ?, level = ?, updated_at = NOW() WHERE user_id = ?"#,
-        )
-        .bind(new_total)
-        .bind(new_level)
-        .bind(session_user.id)
-        .execute(pool.get_ref())
-        .await?;
-    }
+// ============================================
+// History
+// ============================================
 
-    // Deduct EXP from active pet
-    let active_pet: Option<Pet> =
-        sqlx::query_as("SELECT * FROM pets WHERE user_id = ? AND is_active = true")
-            .bind(session_user.id)
-            .fetch_optional(pool.get_ref())
-            .await?;
+#[derive(Serialize)]
+struct ExerciseHistorySetDto {
+    #[serde(rename = "setId")]
+    set_id: i64,
+    #[serde(rename = "recordId")]
+    record_id: i64,
+    date: String,
+    #[serde(rename = "setNumber")]
+    set_number: i32,
+    weight: f64,
+    reps: i32,
+    /// 推定1RM（Epley式、0回・0kgのセットはnull）
+    #[serde(rename = "estimated1rm")]
+    estimated_1rm: Option<f64>,
+}
 
-    if let Some(pet) = active_pet {
-        let new_total = std::cmp::max(0, pet.total_exp - exp_to_deduct as i64);
-        let new_level = Pet::calculate_level(new_total);
-        let new_stage = Pet::calculate_stage(new_level);
+#[derive(sqlx::FromRow)]
+struct ExerciseHistoryRow {
+    id: i64,
+    record_id: i64,
+    record_date: NaiveDate,
+    set_number: i32,
+    weight: f64,
+    reps: i32,
+}
 
-        sqlx::query(
-            r#"UPDATE pets SET total_exp = ?, level = ?, stage = ?, updated_at = NOW() WHERE id = ?"#,
-        )
-        .bind(new_total)
-        .bind(new_level)
-        .bind(new_stage)
-        .bind(pet.id)
-        .execute(pool.get_ref())
-        .await?;
+/// 種目IDを解決（自分のカスタム種目のIDであればカスタム種目として扱う）
+pub(crate) async fn resolve_exercise(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    exercise_id: i64,
+) -> Result<ExerciseRef, AppError> {
+    let is_custom = exercise_exp_info(&mut *conn, user_id, exercise_id)
+        .await?
+        .is_custom;
+    if !is_custom {
+        let exists: Option<(i64,)> = sqlx::query_as("SELECT id FROM exercises WHERE id = ?")
+            .bind(exercise_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::NotFound("Exercise not found".to_string()));
+        }
     }
 
-    // Recalculate training streak after deletion
-    {
-        use crate::api::streak::recalculate_training_streak;
-        let _ = recalculate_training_streak(pool.get_ref(), session_user.id).await;
-    }
+    Ok(ExerciseRef {
+        id: exercise_id,
+        is_custom,
+    })
+}
 
-    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+/// GET /api/workout/exercises/{id}/history
+/// 種目の過去のセット（新しい日付順、同日内はセット番号順）をページングで返す
+/// 自分のカスタム種目のIDであればカスタム種目として扱う
+#[get("/workout/exercises/{id}/history")]
+async fn get_exercise_history(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    query: web::Query<PagedRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let exercise_id = path.into_inner();
+    let page = query.page.unwrap_or(0).max(0);
+    let size = query.size.unwrap_or(50).clamp(1, 200);
+
+    let mut conn = pool.acquire().await?;
+    let exercise = resolve_exercise(&mut conn, session_user.id, exercise_id).await?;
+
+    let total: (i64,) = sqlx::query_as(&format!(
+        r#"SELECT COUNT(*)
+           FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ? AND {} = ?"#,
+        exercise.column()
+    ))
+    .bind(session_user.id)
+    .bind(exercise_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let rows: Vec<ExerciseHistoryRow> = sqlx::query_as(&format!(
+        r#"SELECT ts.id, tr.id AS record_id, tr.record_date, ts.set_number, ts.weight, ts.reps
+           FROM training_sets ts
+           INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           INNER JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.user_id = ? AND {} = ?
+           ORDER BY tr.record_date DESC, tre.order_index ASC, ts.set_number ASC
+           LIMIT ? OFFSET ?"#,
+        exercise.column()
+    ))
+    .bind(session_user.id)
+    .bind(exercise_id)
+    .bind(size)
+    .bind(page * size)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let content: Vec<ExerciseHistorySetDto> = rows
+        .into_iter()
+        .map(|row| ExerciseHistorySetDto {
+            set_id: row.id,
+            record_id: row.record_id,
+            date: row.record_date.format("%Y-%m-%d").to_string(),
+            set_number: row.set_number,
+            weight: row.weight,
+            reps: row.reps,
+            estimated_1rm: estimate_one_rep_max(row.weight, row.reps),
+        })
+        .collect();
+
+    let total_pages = ((total.0 as f64) / (size as f64)).ceil() as i32;
+
+    Ok(HttpResponse::Ok().json(PagedResponse {
+        content,
+        page,
+        size,
+        total_elements: total.0,
+        total_pages,
+        has_next: page < total_pages - 1,
+        has_previous: page > 0,
+    }))
 }
 
-/// DELETE /api/workout/sets/{id}
-#[delete("/workout/sets/{id}")]
-async fn delete_set(
+#[derive(Deserialize)]
+struct OneRmQuery {
+    /// epley / brzycki（省略時はepley）
+    formula: Option<String>,
+}
+
+/// 推定1RMの算出元のセット
+#[derive(Serialize, Clone)]
+struct OneRmPointDto {
+    date: String,
+    #[serde(rename = "recordId")]
+    record_id: i64,
+    weight: f64,
+    reps: i32,
+    #[serde(rename = "estimated1rm")]
+    estimated_1rm: f64,
+}
+
+#[derive(Serialize)]
+struct OneRmResponse {
+    #[serde(rename = "exerciseId")]
+    exercise_id: i64,
+    #[serde(rename = "isCustom")]
+    is_custom: bool,
+    formula: &'static str,
+    /// 直近のトレーニング日の推定1RM
+    current: Option<OneRmPointDto>,
+    /// 全期間の最高値
+    best: Option<OneRmPointDto>,
+    /// トレーニング日ごとの最高値（古い日付順）
+    history: Vec<OneRmPointDto>,
+}
+
+/// GET /api/workout/exercises/{id}/one-rm?formula=epley|brzycki
+/// 種目の推定1RMの推移（ウォームアップセットは除く）
+#[get("/workout/exercises/{id}/one-rm")]
+async fn get_exercise_one_rm(
     pool: web::Data<MySqlPool>,
     session: Session,
     path: web::Path<i64>,
+    query: web::Query<OneRmQuery>,
 ) -> Result<HttpResponse, AppError> {
     let session_user = get_current_user(&session)?;
-    let set_id = path.into_inner();
+    let exercise_id = path.into_inner();
 
-    // Verify ownership
-    let ownership: Option<(i64,)> = sqlx::query_as(
-        r#"SELECT ts.id FROM training_sets ts
+    let formula = match query.formula.as_deref() {
+        Some(f) => OneRmFormula::parse(f).ok_or_else(|| {
+            AppError::BadRequest("formulaにはepleyまたはbrzyckiを指定してください".to_string())
+        })?,
+        None => OneRmFormula::default(),
+    };
+
+    let mut conn = pool.acquire().await?;
+    let exercise = resolve_exercise(&mut conn, session_user.id, exercise_id).await?;
+
+    let rows: Vec<(i64, NaiveDate, f64, i32)> = sqlx::query_as(&format!(
+        r#"SELECT tr.id, tr.record_date, ts.weight, ts.reps
+           FROM training_sets ts
            INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
            INNER JOIN training_records tr ON tre.record_id = tr.id
-           WHERE ts.id = ? AND tr.user_id = ?"#,
+           WHERE tr.user_id = ? AND {} = ? AND ts.weight > 0 AND ts.reps > 0
+             AND ts.set_type <> 'warmup'
+           ORDER BY tr.record_date ASC, ts.id ASC"#,
+        exercise.column()
+    ))
+    .bind(session_user.id)
+    .bind(exercise_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    // トレーニング日ごとに最高値のセットを残す
+    let mut history: Vec<OneRmPointDto> = Vec::new();
+    for (record_id, record_date, weight, reps) in rows {
+        let Some(estimated_1rm) = formula.estimate(weight, reps) else {
+            continue;
+        };
+        let date = record_date.format("%Y-%m-%d").to_string();
+        match history.last_mut() {
+            Some(last) if last.date == date => {
+                if estimated_1rm > last.estimated_1rm {
+                    *last = OneRmPointDto {
+                        date,
+                        record_id,
+                        weight,
+                        reps,
+                        estimated_1rm,
+                    };
+                }
+            }
+            _ => history.push(OneRmPointDto {
+                date,
+                record_id,
+                weight,
+                reps,
+                estimated_1rm,
+            }),
+        }
+    }
+
+    let current = history.last().cloned();
+    let best = history
+        .iter()
+        .max_by(|a, b| a.estimated_1rm.total_cmp(&b.estimated_1rm))
+        .cloned();
+
+    Ok(HttpResponse::Ok().json(OneRmResponse {
+        exercise_id,
+        is_custom: exercise.is_custom,
+        formula: formula.name(),
+        current,
+        best,
+        history,
+    }))
+}
+
+// ============================================
+// Drafts
+// ============================================
+
+/// 下書きの最大サイズ（JSON文字列のバイト数）
+const MAX_DRAFT_BYTES: usize = 64 * 1024;
+
+#[derive(Deserialize)]
+struct DraftQuery {
+    date: String,
+}
+
+#[derive(Deserialize)]
+struct SaveDraftRequest {
+    date: String,
+    payload: serde_json::Value,
+}
+
+#[derive(Serialize)]
+struct WorkoutDraftDto {
+    date: String,
+    payload: serde_json::Value,
+    #[serde(rename = "updatedAt")]
+    updated_at: String,
+}
+
+fn parse_draft_date(date: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("Invalid date format".to_string()))
+}
+
+/// 記録保存後に下書きを削除
+async fn delete_draft(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+    date: NaiveDate,
+) -> Result<(), AppError> {
+    sqlx::query("DELETE FROM workout_drafts WHERE user_id = ? AND draft_date = ?")
+        .bind(user_id)
+        .bind(date)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// GET /api/workout/draft?date=YYYY-MM-DD
+/// 下書きがない場合はnullを返す
+#[get("/workout/draft")]
+async fn get_draft(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<DraftQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let date = parse_draft_date(&query.date)?;
+
+    let draft: Option<(String, NaiveDateTime)> = sqlx::query_as(
+        "SELECT payload, updated_at FROM workout_drafts WHERE user_id = ? AND draft_date = ?",
     )
-    .bind(set_id)
     .bind(session_user.id)
+    .bind(date)
     .fetch_optional(pool.get_ref())
     .await?;
 
-    if ownership.is_none() {
-        return Err(AppError::NotFound("Set not found".to_string()));
+    let draft = draft.and_then(|(payload, updated_at)| {
+        Some(WorkoutDraftDto {
+            date: date.format("%Y-%m-%d").to_string(),
+            payload: serde_json::from_str(&payload).ok()?,
+            updated_at: format_datetime(updated_at),
+        })
+    });
+
+    Ok(HttpResponse::Ok().json(draft))
+}
+
+/// PUT /api/workout/draft
+/// 記録中のワークアウトを保存（同じ日付の下書きは上書き）
+#[put("/workout/draft")]
+async fn save_draft(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<SaveDraftRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let date = parse_draft_date(&body.date)?;
+
+    let payload = body.payload.to_string();
+    if payload.len() > MAX_DRAFT_BYTES {
+        return Err(AppError::BadRequest("下書きのサイズが大きすぎます".to_string()));
     }
 
-    sqlx::query("DELETE FROM training_sets WHERE id = ?")
-        .bind(set_id)
-        .execute(pool.get_ref())
-        .await?;
+    sqlx::query(
+        r#"INSERT INTO workout_drafts (user_id, draft_date, payload, updated_at)
+           VALUES (?, ?, ?, NOW())
+           ON DUPLICATE KEY UPDATE payload = VALUES(payload), updated_at = NOW()"#,
+    )
+    .bind(session_user.id)
+    .bind(date)
+    .bind(payload)
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+/// DELETE /api/workout/draft?date=YYYY-MM-DD
+#[delete("/workout/draft")]
+async fn discard_draft(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<DraftQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let date = parse_draft_date(&query.date)?;
+
+    delete_draft(&mut *pool.acquire().await?, session_user.id, date).await?;
 
     Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
 }
@@ -1248,12 +3309,23 @@ async fn get_default_tags(pool: web::Data<MySqlPool>) -> Result<HttpResponse, Ap
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_exercises)
         .service(create_custom_exercise)
+        .service(update_custom_exercise)
         .service(delete_custom_exercise)
         .service(get_records)
         .service(get_records_paged)
+        .service(get_record_timing)
+        .service(get_exercise_history)
+        .service(get_exercise_one_rm)
         .service(save_record)
+        .service(copy_record)
+        .service(update_record)
         .service(delete_record)
+        .service(bulk_delete_records)
+        .service(undo_last_save)
         .service(delete_set)
+        .service(get_draft)
+        .service(save_draft)
+        .service(discard_draft)
         .service(get_tags)
         .service(create_tag)
         .service(delete_tag)
@@ -1261,3 +3333,37 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(get_muscle_groups)
         .service(get_default_tags);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undoing_the_only_batch_on_an_existing_empty_record_deletes_it() {
+        // セットのない既存の記録に追記した保存を取り消すと、セットが残らない
+        assert_eq!(
+            undo_outcome(30, 30, 0),
+            UndoOutcome {
+                exp_deducted: 30,
+                record_deleted: true,
+            }
+        );
+    }
+
+    #[test]
+    fn undoing_when_sets_remain_keeps_the_record() {
+        assert_eq!(
+            undo_outcome(100, 30, 4),
+            UndoOutcome {
+                exp_deducted: 30,
+                record_deleted: false,
+            }
+        );
+    }
+
+    #[test]
+    fn undo_does_not_deduct_more_than_the_record_earned() {
+        assert_eq!(undo_outcome(10, 30, 2).exp_deducted, 10);
+        assert_eq!(undo_outcome(-5, 30, 0).exp_deducted, 0);
+    }
+}
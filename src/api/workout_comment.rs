@@ -0,0 +1,237 @@
+//! 合同トレーニング記録のコメントAPIハンドラ
+//!
+//! 共有された記録（shared_session_idを持つ記録）に、同じセッションの参加者がコメントできる。
+//! 投稿時は問い合わせと同じ禁止ワードチェックを行い、連投はレート制限で抑止する。
+//! 記録の持ち主とコメント投稿者は削除でき、管理者は非表示・削除でモデレーションする。
+
+use std::time::Duration;
+
+use actix_web::{delete, get, post, web, HttpResponse};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::api::admin::is_admin;
+use crate::api::contact::contains_banned_word;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+use crate::shared_store::SharedStore;
+
+pub(crate) const STATUS_VISIBLE: &str = "VISIBLE";
+pub(crate) const STATUS_HIDDEN: &str = "HIDDEN";
+
+/// コメントの最大文字数
+const MAX_COMMENT_CHARS: usize = 500;
+/// 1ユーザーあたりの投稿上限（1分・1時間）
+const MAX_COMMENTS_PER_MINUTE: u64 = 5;
+const MAX_COMMENTS_PER_HOUR: u64 = 60;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+struct WorkoutCommentDto {
+    id: i64,
+    #[serde(rename = "recordId")]
+    record_id: i64,
+    #[serde(rename = "userId")]
+    user_id: i64,
+    #[serde(rename = "displayName")]
+    display_name: String,
+    content: String,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    /// 自分が削除できるか（投稿者または記録の持ち主）
+    #[serde(rename = "canDelete")]
+    can_delete: bool,
+}
+
+#[derive(Deserialize)]
+struct CreateCommentRequest {
+    content: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct CommentRow {
+    id: i64,
+    record_id: i64,
+    user_id: i64,
+    display_name: String,
+    content: String,
+    created_at: NaiveDateTime,
+}
+
+// ============================================
+// 権限
+// ============================================
+
+/// 共有記録の持ち主を取得（参加者以外・共有されていない記録はNotFound）
+async fn find_shared_record_owner(
+    pool: &MySqlPool,
+    record_id: i64,
+    user_id: i64,
+) -> Result<i64, AppError> {
+    let owner: Option<i64> = sqlx::query_scalar(
+        r#"SELECT tr.user_id FROM training_records tr
+           WHERE tr.id = ? AND tr.shared_session_id IS NOT NULL
+             AND (tr.user_id = ? OR EXISTS (
+                   SELECT 1 FROM training_records other
+                   WHERE other.shared_session_id = tr.shared_session_id AND other.user_id = ?))"#,
+    )
+    .bind(record_id)
+    .bind(user_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    owner.ok_or_else(|| AppError::NotFound("Record not found".to_string()))
+}
+
+// ============================================
+// Handlers
+// ============================================
+
+/// GET /api/social/workouts/{id}/comments
+/// 共有記録のコメント一覧（古い順、非表示のコメントは除く）
+#[get("/social/workouts/{id}/comments")]
+async fn get_comments(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let record_id = path.into_inner();
+    let owner_id = find_shared_record_owner(pool.get_ref(), record_id, session_user.id).await?;
+
+    let rows: Vec<CommentRow> = sqlx::query_as(
+        r#"SELECT c.id, c.record_id, c.user_id,
+                  CAST(COALESCE(u.display_name, u.login_id) AS CHAR) AS display_name,
+                  c.content, c.created_at
+           FROM workout_comments c
+           INNER JOIN users u ON u.id = c.user_id
+           WHERE c.record_id = ? AND c.status = ?
+           ORDER BY c.created_at ASC, c.id ASC"#,
+    )
+    .bind(record_id)
+    .bind(STATUS_VISIBLE)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let comments: Vec<WorkoutCommentDto> = rows
+        .into_iter()
+        .map(|row| WorkoutCommentDto {
+            can_delete: row.user_id == session_user.id || owner_id == session_user.id,
+            id: row.id,
+            record_id: row.record_id,
+            user_id: row.user_id,
+            display_name: row.display_name,
+            content: row.content,
+            created_at: row.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+/// POST /api/social/workouts/{id}/comments
+/// 共有記録にコメントを投稿
+#[post("/social/workouts/{id}/comments")]
+async fn create_comment(
+    pool: web::Data<MySqlPool>,
+    store: web::Data<SharedStore>,
+    session: Session,
+    path: web::Path<i64>,
+    body: web::Json<CreateCommentRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let record_id = path.into_inner();
+    find_shared_record_owner(pool.get_ref(), record_id, session_user.id).await?;
+
+    let content = body.content.trim();
+    if content.is_empty() || content.chars().count() > MAX_COMMENT_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "コメントは1〜{}文字で入力してください",
+            MAX_COMMENT_CHARS
+        )));
+    }
+    if contains_banned_word(content) {
+        return Err(AppError::BadRequest(
+            "不適切な内容が含まれています".to_string(),
+        ));
+    }
+
+    store
+        .check_rate_limit(
+            &format!("comment:minute:{}", session_user.id),
+            MAX_COMMENTS_PER_MINUTE,
+            Duration::from_secs(60),
+        )
+        .await?;
+    store
+        .check_rate_limit(
+            &format!("comment:hour:{}", session_user.id),
+            MAX_COMMENTS_PER_HOUR,
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+
+    let result = sqlx::query(
+        r#"INSERT INTO workout_comments (record_id, user_id, content, status, created_at)
+           VALUES (?, ?, ?, ?, NOW())"#,
+    )
+    .bind(record_id)
+    .bind(session_user.id)
+    .bind(content)
+    .bind(STATUS_VISIBLE)
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "id": result.last_insert_id(),
+    })))
+}
+
+/// DELETE /api/social/comments/{id}
+/// コメントを削除（投稿者・記録の持ち主・管理者）
+#[delete("/social/comments/{id}")]
+async fn delete_comment(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let comment_id = path.into_inner();
+
+    let comment: Option<(i64, i64)> = sqlx::query_as(
+        r#"SELECT c.user_id, tr.user_id FROM workout_comments c
+           INNER JOIN training_records tr ON tr.id = c.record_id
+           WHERE c.id = ?"#,
+    )
+    .bind(comment_id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    let Some((author_id, owner_id)) = comment else {
+        return Err(AppError::NotFound("コメントが見つかりません".to_string()));
+    };
+
+    if session_user.id != author_id && session_user.id != owner_id && !is_admin(&session_user) {
+        return Err(AppError::Forbidden(
+            "このコメントを削除する権限がありません".to_string(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM workout_comments WHERE id = ?")
+        .bind(comment_id)
+        .execute(pool.get_ref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_comments)
+        .service(create_comment)
+        .service(delete_comment);
+}
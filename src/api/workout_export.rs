@@ -0,0 +1,363 @@
+//! トレーニング履歴のエクスポートAPIハンドラ（CSV/JSON）
+//!
+//! 履歴全体をメモリに載せないよう、記録をEXPORT_BATCH_SIZE件ずつ取得して
+//! ストリーミングレスポンスとして順に書き出す。
+//! 重量はユーザー設定の単位（kg / lb）に変換して書き出す。
+
+use actix_web::{get, web, HttpResponse};
+use chrono::NaiveDate;
+use futures::stream;
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+
+use crate::api::streak::fetch_weight_unit;
+use crate::auth::session::{get_current_user, Session};
+use crate::domain::weight_unit::WeightUnit;
+use crate::error::AppError;
+
+/// 1回のクエリで取得する記録数
+const EXPORT_BATCH_SIZE: i64 = 100;
+
+/// CSVの見出し行（lbの場合は重量の列名をweight_lbにする）
+fn csv_header(unit: WeightUnit) -> String {
+    let weight_column = match unit {
+        WeightUnit::Kg => "weight",
+        WeightUnit::Lb => "weight_lb",
+    };
+    format!(
+        "date,exp_earned,exercise,set_number,{},reps,set_type,rpe,memo\n",
+        weight_column
+    )
+}
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    /// csv / json（省略時はcsv）
+    format: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Serialize)]
+struct ExportRecordDto {
+    /// 次のバッチの開始位置
+    #[serde(skip)]
+    id: i64,
+    #[serde(skip)]
+    record_date: NaiveDate,
+    date: String,
+    #[serde(rename = "expEarned")]
+    exp_earned: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    note: Option<String>,
+    /// 重量の単位（kg / lb）
+    #[serde(rename = "weightUnit")]
+    weight_unit: &'static str,
+    exercises: Vec<ExportExerciseDto>,
+}
+
+#[derive(Serialize)]
+struct ExportExerciseDto {
+    name: String,
+    sets: Vec<ExportSetDto>,
+}
+
+#[derive(Serialize)]
+struct ExportSetDto {
+    #[serde(rename = "setNumber")]
+    set_number: i32,
+    weight: f64,
+    reps: i32,
+    #[serde(rename = "setType")]
+    set_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rpe: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+}
+
+// ============================================
+// 履歴の取得
+// ============================================
+
+/// 記録を日付順に、指定位置（日付・ID）より後からEXPORT_BATCH_SIZE件取得
+/// 重量は指定の単位に変換する
+async fn fetch_batch(
+    pool: &MySqlPool,
+    user_id: i64,
+    unit: WeightUnit,
+    after: Option<(NaiveDate, i64)>,
+) -> Result<Vec<ExportRecordDto>, AppError> {
+    #[derive(sqlx::FromRow)]
+    struct RecordRow {
+        id: i64,
+        record_date: NaiveDate,
+        exp_earned: i32,
+        note: Option<String>,
+    }
+
+    let records: Vec<RecordRow> = match after {
+        None => {
+            sqlx::query_as(
+                r#"SELECT id, record_date, COALESCE(exp_earned, 0) AS exp_earned, note
+                   FROM training_records WHERE user_id = ?
+                   ORDER BY record_date ASC, id ASC LIMIT ?"#,
+            )
+            .bind(user_id)
+            .bind(EXPORT_BATCH_SIZE)
+            .fetch_all(pool)
+            .await?
+        }
+        Some((last_date, last_id)) => {
+            sqlx::query_as(
+                r#"SELECT id, record_date, COALESCE(exp_earned, 0) AS exp_earned, note
+                   FROM training_records
+                   WHERE user_id = ? AND (record_date > ? OR (record_date = ? AND id > ?))
+                   ORDER BY record_date ASC, id ASC LIMIT ?"#,
+            )
+            .bind(user_id)
+            .bind(last_date)
+            .bind(last_date)
+            .bind(last_id)
+            .bind(EXPORT_BATCH_SIZE)
+            .fetch_all(pool)
+            .await?
+        }
+    };
+
+    if records.is_empty() {
+        return Ok(vec![]);
+    }
+
+    #[derive(sqlx::FromRow)]
+    struct SetRow {
+        record_id: i64,
+        record_exercise_id: i64,
+        exercise_name: String,
+        set_number: i32,
+        weight: f64,
+        reps: i32,
+        set_type: String,
+        rpe: Option<f64>,
+        memo: Option<String>,
+    }
+
+    let placeholders = records.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let query = format!(
+        r#"SELECT tre.record_id, tre.id AS record_exercise_id,
+           CAST(COALESCE(e.name, uce.name, 'Unknown') AS CHAR) AS exercise_name,
+           ts.set_number, ts.weight, ts.reps, ts.set_type, ts.rpe, ts.memo
+           FROM training_record_exercises tre
+           INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+           LEFT JOIN exercises e ON e.id = tre.exercise_id
+           LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+           WHERE tre.record_id IN ({})
+           ORDER BY tre.record_id ASC, tre.order_index ASC, tre.id ASC, ts.set_number ASC"#,
+        placeholders
+    );
+    let mut q = sqlx::query_as::<_, SetRow>(&query);
+    for r in &records {
+        q = q.bind(r.id);
+    }
+    let sets: Vec<SetRow> = q.fetch_all(pool).await?;
+
+    let mut result: Vec<ExportRecordDto> = Vec::with_capacity(records.len());
+    for r in records {
+        let mut exercises: Vec<ExportExerciseDto> = Vec::new();
+        let mut current_exercise_id = None;
+        for s in sets.iter().filter(|s| s.record_id == r.id) {
+            let set = ExportSetDto {
+                set_number: s.set_number,
+                weight: unit.convert_from_kg(s.weight),
+                reps: s.reps,
+                set_type: s.set_type.clone(),
+                rpe: s.rpe,
+                memo: s.memo.clone(),
+            };
+            match exercises.last_mut() {
+                Some(ex) if current_exercise_id == Some(s.record_exercise_id) => ex.sets.push(set),
+                _ => {
+                    current_exercise_id = Some(s.record_exercise_id);
+                    exercises.push(ExportExerciseDto {
+                        name: s.exercise_name.clone(),
+                        sets: vec![set],
+                    });
+                }
+            }
+        }
+        result.push(ExportRecordDto {
+            id: r.id,
+            record_date: r.record_date,
+            date: r.record_date.format("%Y-%m-%d").to_string(),
+            exp_earned: r.exp_earned,
+            note: r.note,
+            weight_unit: unit.name(),
+            exercises,
+        });
+    }
+    Ok(result)
+}
+
+// ============================================
+// 書き出し
+// ============================================
+
+/// CSVのフィールド（カンマ・改行・ダブルクォートを含む場合はクォートする）
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 記録をCSVの行（1セット1行）に変換
+fn write_csv_rows(out: &mut String, record: &ExportRecordDto) {
+    for ex in &record.exercises {
+        for set in &ex.sets {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                record.date,
+                record.exp_earned,
+                csv_field(&ex.name),
+                set.set_number,
+                set.weight,
+                set.reps,
+                set.set_type,
+                set.rpe.map(|r| r.to_string()).unwrap_or_default(),
+                csv_field(set.memo.as_deref().unwrap_or("")),
+            ));
+        }
+    }
+}
+
+/// ストリームの状態
+enum ExportState {
+    /// ヘッダー（CSVの見出し行・JSONの開き括弧）を書き出す
+    Start,
+    /// 指定位置より後の記録を書き出す（Noneは先頭から）
+    Next {
+        after: Option<(NaiveDate, i64)>,
+        first: bool,
+    },
+    Done,
+}
+
+/// 記録をバッチごとに取得してチャンクとして返すストリーム
+fn export_stream(
+    pool: MySqlPool,
+    user_id: i64,
+    unit: WeightUnit,
+    format: ExportFormat,
+) -> impl futures::Stream<Item = Result<web::Bytes, actix_web::Error>> {
+    stream::unfold(ExportState::Start, move |state| {
+        let pool = pool.clone();
+        async move {
+            match state {
+                ExportState::Start => {
+                    let header = match format {
+                        // Excelで文字化けしないようBOMを付ける
+                        ExportFormat::Csv => format!("\u{feff}{}", csv_header(unit)),
+                        ExportFormat::Json => "[".to_string(),
+                    };
+                    let next = ExportState::Next {
+                        after: None,
+                        first: true,
+                    };
+                    Some((Ok(web::Bytes::from(header)), next))
+                }
+                ExportState::Next { after, first } => {
+                    let records = match fetch_batch(&pool, user_id, unit, after).await {
+                        Ok(records) => records,
+                        Err(e) => {
+                            tracing::error!("Failed to export training history: {}", e);
+                            return Some((Err(e.into()), ExportState::Done));
+                        }
+                    };
+                    let Some(last) = records.last() else {
+                        let footer = match format {
+                            ExportFormat::Csv => "",
+                            ExportFormat::Json => "]",
+                        };
+                        return Some((Ok(web::Bytes::from(footer)), ExportState::Done));
+                    };
+                    let next = ExportState::Next {
+                        after: Some((last.record_date, last.id)),
+                        first: false,
+                    };
+
+                    let mut chunk = String::new();
+                    for (i, record) in records.iter().enumerate() {
+                        match format {
+                            ExportFormat::Csv => write_csv_rows(&mut chunk, record),
+                            ExportFormat::Json => {
+                                if !(first && i == 0) {
+                                    chunk.push(',');
+                                }
+                                // DTOはシリアライズに失敗しない
+                                chunk.push_str(&serde_json::to_string(record).unwrap_or_default());
+                            }
+                        }
+                    }
+                    Some((Ok(web::Bytes::from(chunk)), next))
+                }
+                ExportState::Done => None,
+            }
+        }
+    })
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/workout/export?format=csv|json
+/// トレーニング履歴（日付・種目・セット・重量・回数・EXP）をダウンロード用に書き出す
+#[get("/workout/export")]
+async fn export_history(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let format = match query.format.as_deref().unwrap_or("csv") {
+        "csv" => ExportFormat::Csv,
+        "json" => ExportFormat::Json,
+        _ => {
+            return Err(AppError::BadRequest(
+                "formatにはcsvまたはjsonを指定してください".to_string(),
+            ))
+        }
+    };
+    let unit = fetch_weight_unit(pool.get_ref(), session_user.id).await?;
+    let (content_type, filename) = match format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "fithub-training-history.csv"),
+        ExportFormat::Json => ("application/json", "fithub-training-history.json"),
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(content_type)
+        .insert_header((
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", filename),
+        ))
+        .streaming(export_stream(
+            pool.get_ref().clone(),
+            session_user.id,
+            unit,
+            format,
+        )))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(export_history);
+}
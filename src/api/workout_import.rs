@@ -0,0 +1,633 @@
+//! トレーニングデータのインポートAPIハンドラ
+//!
+//! 他のアプリ（Strong・Hevyなど）や本アプリのエクスポートから書き出したCSVを読み込み、
+//! 種目名を既存の種目に対応付けて記録を作成する。
+//! 対応する種目がない場合はカスタム種目を作成する。
+//! dryRun=trueの場合は対応付けと件数のプレビューのみを返し、何も保存しない。
+
+use std::collections::BTreeMap;
+
+use actix_multipart::Multipart;
+use actix_web::{post, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::personal_record::{refresh_personal_records, ExerciseRef};
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::domain::weight_unit::WeightUnit;
+use crate::error::AppError;
+
+/// アップロードできるCSVの最大サイズ
+const MAX_IMPORT_SIZE: usize = 5 * 1024 * 1024;
+/// 1回でインポートできる最大行数
+const MAX_IMPORT_ROWS: usize = 20_000;
+/// レスポンスに含める行エラーの最大件数
+const MAX_REPORTED_ERRORS: usize = 20;
+/// あいまい一致とみなす類似度の下限（編集距離 / 長い方の文字数）
+const FUZZY_MATCH_THRESHOLD: f64 = 0.8;
+/// 作成するカスタム種目名の最大文字数
+const MAX_EXERCISE_NAME_CHARS: usize = 100;
+/// セットメモの最大文字数（training_sets.memo）
+const MAX_SET_MEMO_CHARS: usize = 255;
+
+// 列名の候補（小文字で比較）
+const DATE_COLUMNS: [&str; 4] = ["date", "start_time", "workout date", "日付"];
+const EXERCISE_COLUMNS: [&str; 4] = ["exercise name", "exercise_title", "exercise", "種目"];
+const WEIGHT_COLUMNS: [&str; 4] = ["weight", "weight_kg", "weight (kg)", "重量"];
+const WEIGHT_LB_COLUMNS: [&str; 4] = ["weight_lb", "weight_lbs", "weight (lb)", "weight (lbs)"];
+const REPS_COLUMNS: [&str; 2] = ["reps", "回数"];
+const SET_TYPE_COLUMNS: [&str; 3] = ["set_type", "set type", "set order"];
+const RPE_COLUMNS: [&str; 1] = ["rpe"];
+const MEMO_COLUMNS: [&str; 3] = ["memo", "notes", "メモ"];
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct ImportQuery {
+    #[serde(rename = "dryRun", default)]
+    dry_run: bool,
+}
+
+/// CSVの種目名と、対応付けた種目
+#[derive(Serialize)]
+struct ExerciseMappingDto {
+    #[serde(rename = "sourceName")]
+    source_name: String,
+    /// 対応付けた種目のID（dryRunで新規作成予定の場合はNone）
+    #[serde(rename = "exerciseId")]
+    exercise_id: Option<i64>,
+    #[serde(rename = "exerciseName")]
+    exercise_name: String,
+    #[serde(rename = "isCustom")]
+    is_custom: bool,
+    /// exact / fuzzy / created
+    #[serde(rename = "matchType")]
+    match_type: &'static str,
+}
+
+#[derive(Serialize)]
+struct ImportResultDto {
+    #[serde(rename = "dryRun")]
+    dry_run: bool,
+    /// 作成した（dryRunでは作成予定の）記録数
+    #[serde(rename = "recordCount")]
+    record_count: usize,
+    #[serde(rename = "setCount")]
+    set_count: usize,
+    /// 既に記録がある日付（上書きしないためスキップ）
+    #[serde(rename = "skippedDates")]
+    skipped_dates: Vec<String>,
+    /// 読み込めなかった行数
+    #[serde(rename = "skippedRows")]
+    skipped_rows: usize,
+    exercises: Vec<ExerciseMappingDto>,
+    /// 読み込めなかった行の理由（先頭MAX_REPORTED_ERRORS件）
+    errors: Vec<String>,
+}
+
+// ============================================
+// CSVの解析
+// ============================================
+
+/// CSVの1セット分
+struct ImportSet {
+    date: NaiveDate,
+    exercise_name: String,
+    weight: f64,
+    reps: i32,
+    set_type: &'static str,
+    rpe: Option<f64>,
+    memo: Option<String>,
+}
+
+/// CSVを行・フィールドに分割（ダブルクォート内の区切り文字・改行に対応）
+fn parse_csv(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+        } else if c == '"' {
+            in_quotes = true;
+        } else if c == delimiter {
+            row.push(std::mem::take(&mut field));
+        } else if c == '\n' || c == '\r' {
+            if c == '\r' && chars.peek() == Some(&'\n') {
+                chars.next();
+            }
+            row.push(std::mem::take(&mut field));
+            if row.iter().any(|f| !f.trim().is_empty()) {
+                rows.push(std::mem::take(&mut row));
+            } else {
+                row.clear();
+            }
+        } else {
+            field.push(c);
+        }
+    }
+    row.push(field);
+    if row.iter().any(|f| !f.trim().is_empty()) {
+        rows.push(row);
+    }
+    rows
+}
+
+/// 見出し行から候補に一致する列の位置を探す
+fn find_column(header: &[String], candidates: &[&str]) -> Option<usize> {
+    header
+        .iter()
+        .position(|h| candidates.contains(&h.trim().to_lowercase().as_str()))
+}
+
+/// 日付（時刻付きの形式にも対応）
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    let value = value.trim();
+    const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%d %b %Y"];
+    const DATETIME_FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y/%m/%d %H:%M",
+        "%d %b %Y, %H:%M",
+    ];
+    DATE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(value, f).ok())
+        .or_else(|| {
+            DATETIME_FORMATS
+                .iter()
+                .find_map(|f| NaiveDateTime::parse_from_str(value, f).ok())
+                .map(|dt| dt.date())
+        })
+}
+
+/// 数値（小数点がカンマの形式にも対応）
+fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    value.replace(',', ".").parse::<f64>().ok()
+}
+
+/// セットの種類（Strongの W/D/F 表記にも対応）
+fn parse_set_type(value: &str) -> &'static str {
+    match value.trim().to_lowercase().as_str() {
+        "warmup" | "warm_up" | "warm up" | "w" => "warmup",
+        "dropset" | "drop_set" | "drop" | "d" => "dropset",
+        "failure" | "f" => "failure",
+        _ => "normal",
+    }
+}
+
+/// CSV全体を解析してセットの一覧にする（読み込めない行はerrorsに理由を追加）
+/// todayはユーザーのタイムゾーンでの今日の日付
+fn parse_import_csv(
+    text: &str,
+    today: NaiveDate,
+    errors: &mut Vec<String>,
+) -> Result<Vec<ImportSet>, AppError> {
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or("");
+    let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+        ';'
+    } else {
+        ','
+    };
+
+    let rows = parse_csv(text, delimiter);
+    let Some((header, rows)) = rows.split_first() else {
+        return Err(AppError::BadRequest("CSVが空です".to_string()));
+    };
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::BadRequest(format!(
+            "一度にインポートできるのは{}行までです",
+            MAX_IMPORT_ROWS
+        )));
+    }
+
+    // 重量はkgの列を優先し、なければlbの列をkgに変換して読み込む
+    let weight_col = find_column(header, &WEIGHT_COLUMNS)
+        .map(|col| (col, WeightUnit::Kg))
+        .or_else(|| find_column(header, &WEIGHT_LB_COLUMNS).map(|col| (col, WeightUnit::Lb)));
+    let (Some(date_col), Some(exercise_col), Some((weight_col, weight_unit)), Some(reps_col)) = (
+        find_column(header, &DATE_COLUMNS),
+        find_column(header, &EXERCISE_COLUMNS),
+        weight_col,
+        find_column(header, &REPS_COLUMNS),
+    ) else {
+        return Err(AppError::BadRequest(
+            "日付・種目・重量・回数の列が見つかりません".to_string(),
+        ));
+    };
+    let set_type_col = find_column(header, &SET_TYPE_COLUMNS);
+    let rpe_col = find_column(header, &RPE_COLUMNS);
+    let memo_col = find_column(header, &MEMO_COLUMNS);
+
+    // 未来の日付は保存時と同じく受け付けない
+    let mut sets = Vec::with_capacity(rows.len());
+    for (line, row) in (2..).zip(rows) {
+        let field = |col: usize| row.get(col).map(String::as_str).unwrap_or("");
+
+        let Some(date) = parse_date(field(date_col)) else {
+            errors.push(format!("{}行目: 日付を読み取れません", line));
+            continue;
+        };
+        if date > today {
+            errors.push(format!("{}行目: 未来の日付は登録できません", line));
+            continue;
+        }
+        let exercise_name = field(exercise_col).trim();
+        if exercise_name.is_empty() {
+            errors.push(format!("{}行目: 種目名がありません", line));
+            continue;
+        }
+        // 有酸素種目など回数のない行は対象外
+        let Some(reps) = parse_number(field(reps_col)).filter(|r| *r > 0.0) else {
+            errors.push(format!("{}行目: 回数がありません", line));
+            continue;
+        };
+        let weight = parse_number(field(weight_col))
+            .map(|w| weight_unit.to_kg(w))
+            .unwrap_or(0.0);
+        if !(0.0..=500.0).contains(&weight) || reps > 20.0 {
+            errors.push(format!(
+                "{}行目: 重量は0〜500kg、回数は1〜20回の範囲のみインポートできます",
+                line
+            ));
+            continue;
+        }
+        let rpe = rpe_col
+            .and_then(|col| parse_number(field(col)))
+            .filter(|rpe| (1.0..=10.0).contains(rpe) && (rpe * 2.0).fract() == 0.0);
+        let memo = memo_col
+            .map(|col| field(col).trim())
+            .filter(|m| !m.is_empty())
+            .map(|m| m.chars().take(MAX_SET_MEMO_CHARS).collect());
+
+        sets.push(ImportSet {
+            date,
+            exercise_name: exercise_name.to_string(),
+            weight,
+            reps: reps as i32,
+            set_type: set_type_col
+                .map(|col| parse_set_type(field(col)))
+                .unwrap_or("normal"),
+            rpe,
+            memo,
+        });
+    }
+    Ok(sets)
+}
+
+// ============================================
+// 種目の対応付け
+// ============================================
+
+/// 比較用に種目名を正規化（大文字小文字・空白・記号の違いを無視）
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// 編集距離（文字単位）
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// 名前の類似度（1.0で一致）
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 0.0;
+    }
+    1.0 - edit_distance(&a, &b) as f64 / len as f64
+}
+
+/// 対応付けの候補となる種目
+struct KnownExercise {
+    id: i64,
+    name: String,
+    normalized: String,
+    is_custom: bool,
+}
+
+/// 種目マスターとユーザーのカスタム種目を取得
+async fn load_known_exercises(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<Vec<KnownExercise>, AppError> {
+    let master: Vec<(i64, String)> = sqlx::query_as("SELECT id, CAST(name AS CHAR) FROM exercises")
+        .fetch_all(&mut *conn)
+        .await?;
+    let custom: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT id, CAST(name AS CHAR) FROM user_custom_exercises WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let to_known = |is_custom: bool| {
+        move |(id, name): (i64, String)| KnownExercise {
+            id,
+            normalized: normalize_name(&name),
+            name,
+            is_custom,
+        }
+    };
+    Ok(custom
+        .into_iter()
+        .map(to_known(true))
+        .chain(master.into_iter().map(to_known(false)))
+        .collect())
+}
+
+/// 種目名を既存の種目に対応付ける（完全一致 → あいまい一致の順）
+fn match_exercise<'a>(
+    known: &'a [KnownExercise],
+    source_name: &str,
+) -> Option<(&'a KnownExercise, &'static str)> {
+    let normalized = normalize_name(source_name);
+    if normalized.is_empty() {
+        return None;
+    }
+    if let Some(exact) = known.iter().find(|k| k.normalized == normalized) {
+        return Some((exact, "exact"));
+    }
+    known
+        .iter()
+        .map(|k| (k, similarity(&k.normalized, &normalized)))
+        .filter(|(_, score)| *score >= FUZZY_MATCH_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(k, _)| (k, "fuzzy"))
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// POST /api/workout/import?dryRun=true|false
+/// multipartのfileフィールドでCSVを受け取り、記録として取り込む
+/// 既に記録がある日付は上書きせずスキップし、取り込んだ記録にはEXPを付与しない
+#[post("/workout/import")]
+async fn import_records(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    query: web::Query<ImportQuery>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let mut file_data: Option<Vec<u8>> = None;
+    while let Some(item) = payload.next().await {
+        let mut field = item.map_err(|e| {
+            AppError::BadRequest(format!("マルチパートの解析に失敗しました: {}", e))
+        })?;
+        let field_name = field
+            .content_disposition()
+            .and_then(|cd| cd.get_name())
+            .unwrap_or("");
+        if field_name != "file" {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| {
+                AppError::BadRequest(format!("ファイルの読み取りに失敗しました: {}", e))
+            })?;
+            data.extend_from_slice(&chunk);
+            if data.len() > MAX_IMPORT_SIZE {
+                return Err(AppError::BadRequest(format!(
+                    "ファイルサイズは{}MB以下にしてください",
+                    MAX_IMPORT_SIZE / 1024 / 1024
+                )));
+            }
+        }
+        file_data = Some(data);
+    }
+
+    let data = file_data
+        .ok_or_else(|| AppError::BadRequest("CSVファイルを指定してください".to_string()))?;
+    let text = String::from_utf8(data)
+        .map_err(|_| AppError::BadRequest("CSVはUTF-8で保存してください".to_string()))?;
+
+    let mut errors = Vec::new();
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+    let sets = parse_import_csv(&text, today, &mut errors)?;
+    let skipped_rows = errors.len();
+    errors.truncate(MAX_REPORTED_ERRORS);
+
+    // 日付ごと・種目ごとにまとめる（種目の並び順はCSVの出現順）
+    let mut by_date: BTreeMap<NaiveDate, Vec<(String, Vec<ImportSet>)>> = BTreeMap::new();
+    for set in sets {
+        let exercises = by_date.entry(set.date).or_default();
+        match exercises
+            .iter_mut()
+            .find(|(name, _)| *name == set.exercise_name)
+        {
+            Some((_, sets)) => sets.push(set),
+            None => exercises.push((set.exercise_name.clone(), vec![set])),
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+
+    // 既に記録がある日付はスキップ
+    let existing_dates: Vec<NaiveDate> =
+        sqlx::query_scalar("SELECT record_date FROM training_records WHERE user_id = ?")
+            .bind(session_user.id)
+            .fetch_all(&mut *tx)
+            .await?;
+    let mut skipped_dates = Vec::new();
+    by_date.retain(|date, _| {
+        let exists = existing_dates.contains(date);
+        if exists {
+            skipped_dates.push(date.format("%Y-%m-%d").to_string());
+        }
+        !exists
+    });
+
+    // 種目名の対応付け（一致しないものはカスタム種目を作成）
+    let mut known = load_known_exercises(&mut tx, session_user.id).await?;
+    let mut mappings: Vec<ExerciseMappingDto> = Vec::new();
+    for (source_name, _) in by_date.values().flatten() {
+        if mappings.iter().any(|m| m.source_name == *source_name) {
+            continue;
+        }
+        if let Some((exercise, match_type)) = match_exercise(&known, source_name) {
+            mappings.push(ExerciseMappingDto {
+                source_name: source_name.clone(),
+                exercise_id: Some(exercise.id),
+                exercise_name: exercise.name.clone(),
+                is_custom: exercise.is_custom,
+                match_type,
+            });
+            continue;
+        }
+
+        let name: String = source_name
+            .trim()
+            .chars()
+            .take(MAX_EXERCISE_NAME_CHARS)
+            .collect();
+        let exercise_id = if query.dry_run {
+            None
+        } else {
+            let result = sqlx::query(
+                r#"INSERT INTO user_custom_exercises
+                       (user_id, name, muscle, is_bodyweight, created_at, updated_at)
+                   VALUES (?, ?, 'other', FALSE, NOW(), NOW())"#,
+            )
+            .bind(session_user.id)
+            .bind(&name)
+            .execute(&mut *tx)
+            .await?;
+            let id = result.last_insert_id() as i64;
+            // 同じ名前の後続の種目は作成したカスタム種目に対応付ける
+            known.push(KnownExercise {
+                id,
+                normalized: normalize_name(&name),
+                name: name.clone(),
+                is_custom: true,
+            });
+            Some(id)
+        };
+        mappings.push(ExerciseMappingDto {
+            source_name: source_name.clone(),
+            exercise_id,
+            exercise_name: name,
+            is_custom: true,
+            match_type: "created",
+        });
+    }
+
+    let set_count = by_date.values().flatten().map(|(_, sets)| sets.len()).sum();
+
+    if !query.dry_run {
+        let mut touched_exercises: Vec<ExerciseRef> = Vec::new();
+        for (date, exercises) in &by_date {
+            let result = sqlx::query(
+                r#"INSERT INTO training_records (user_id, record_date, exp_earned, created_at, updated_at)
+                   VALUES (?, ?, 0, NOW(), NOW())"#,
+            )
+            .bind(session_user.id)
+            .bind(date)
+            .execute(&mut *tx)
+            .await?;
+            let record_id = result.last_insert_id() as i64;
+
+            for (order_index, (source_name, sets)) in (0i32..).zip(exercises) {
+                let Some((exercise_id, is_custom)) = mappings
+                    .iter()
+                    .find(|m| m.source_name == *source_name)
+                    .and_then(|m| m.exercise_id.map(|id| (id, m.is_custom)))
+                else {
+                    continue;
+                };
+                let exercise = ExerciseRef {
+                    id: exercise_id,
+                    is_custom,
+                };
+                if !touched_exercises.contains(&exercise) {
+                    touched_exercises.push(exercise);
+                }
+
+                let result = if is_custom {
+                    sqlx::query(
+                        r#"INSERT INTO training_record_exercises (record_id, custom_exercise_id, order_index)
+                           VALUES (?, ?, ?)"#,
+                    )
+                    .bind(record_id)
+                    .bind(exercise_id)
+                    .bind(order_index)
+                    .execute(&mut *tx)
+                    .await?
+                } else {
+                    sqlx::query(
+                        r#"INSERT INTO training_record_exercises (record_id, exercise_id, order_index)
+                           VALUES (?, ?, ?)"#,
+                    )
+                    .bind(record_id)
+                    .bind(exercise_id)
+                    .bind(order_index)
+                    .execute(&mut *tx)
+                    .await?
+                };
+                let record_exercise_id = result.last_insert_id() as i64;
+
+                for (set_number, set) in (1i32..).zip(sets) {
+                    sqlx::query(
+                        r#"INSERT INTO training_sets
+                               (record_exercise_id, set_number, weight, reps, set_type, rpe, memo,
+                                created_at, updated_at)
+                           VALUES (?, ?, ?, ?, ?, ?, ?, NOW(), NOW())"#,
+                    )
+                    .bind(record_exercise_id)
+                    .bind(set_number)
+                    .bind(set.weight)
+                    .bind(set.reps)
+                    .bind(set.set_type)
+                    .bind(set.rpe)
+                    .bind(&set.memo)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+
+        for exercise in touched_exercises {
+            refresh_personal_records(&mut tx, session_user.id, exercise).await?;
+        }
+        if !by_date.is_empty() {
+            use crate::api::streak::recalculate_training_streak;
+            recalculate_training_streak(&mut tx, session_user.id).await?;
+        }
+
+        tx.commit().await?;
+    }
+
+    Ok(HttpResponse::Ok().json(ImportResultDto {
+        dry_run: query.dry_run,
+        record_count: by_date.len(),
+        set_count,
+        skipped_dates,
+        skipped_rows,
+        exercises: mappings,
+        errors,
+    }))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(import_records);
+}
@@ -0,0 +1,515 @@
+//! 合同トレーニングAPIハンドラ
+//!
+//! 一緒にトレーニングしたユーザーを記録に招待し、承諾したパートナーには
+//! 同じ種目・回数の記録（重量はパートナー自身の直近の重量）を作成する。
+//! 両方の記録は同じshared_session_idで紐付けられる。
+
+use std::collections::HashMap;
+
+use actix_web::{get, post, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::streak::fetch_user_today;
+use crate::api::workout::ensure_record_unlocked;
+use crate::auth::session::{get_current_user, Session};
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+const STATUS_PENDING: &str = "PENDING";
+const STATUS_ACCEPTED: &str = "ACCEPTED";
+const STATUS_DECLINED: &str = "DECLINED";
+
+// ============================================
+// DTOs
+// ============================================
+
+/// 一緒にトレーニングしたパートナー
+#[derive(Serialize, Clone)]
+pub(crate) struct TrainingPartnerDto {
+    #[serde(rename = "userId")]
+    user_id: i64,
+    #[serde(rename = "displayName")]
+    display_name: String,
+}
+
+#[derive(Serialize)]
+struct PartnerInviteDto {
+    id: i64,
+    #[serde(rename = "recordId")]
+    record_id: i64,
+    date: String,
+    inviter: TrainingPartnerDto,
+    #[serde(rename = "exerciseCount")]
+    exercise_count: i64,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+}
+
+#[derive(Deserialize)]
+struct InvitePartnerRequest {
+    #[serde(rename = "loginId")]
+    login_id: String,
+}
+
+// ============================================
+// 共有セッション
+// ============================================
+
+/// 記録ごとのパートナー一覧（同じshared_session_idを持つ他ユーザーの記録）
+pub(crate) async fn fetch_partners_for_records(
+    pool: &MySqlPool,
+    record_ids: &[i64],
+) -> Result<HashMap<i64, Vec<TrainingPartnerDto>>, AppError> {
+    let mut partners: HashMap<i64, Vec<TrainingPartnerDto>> = HashMap::new();
+    if record_ids.is_empty() {
+        return Ok(partners);
+    }
+
+    let placeholders = record_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+    let query = format!(
+        r#"SELECT tr.id, u.id, CAST(COALESCE(u.display_name, u.login_id) AS CHAR)
+           FROM training_records tr
+           INNER JOIN training_records other
+                   ON other.shared_session_id = tr.shared_session_id
+                  AND other.user_id <> tr.user_id
+           INNER JOIN users u ON u.id = other.user_id
+           WHERE tr.id IN ({}) AND tr.shared_session_id IS NOT NULL
+           ORDER BY other.id ASC"#,
+        placeholders
+    );
+
+    let mut q = sqlx::query_as::<_, (i64, i64, String)>(&query);
+    for id in record_ids {
+        q = q.bind(id);
+    }
+    for (record_id, user_id, display_name) in q.fetch_all(pool).await? {
+        partners
+            .entry(record_id)
+            .or_default()
+            .push(TrainingPartnerDto {
+                user_id,
+                display_name,
+            });
+    }
+
+    Ok(partners)
+}
+
+/// POST /api/workout/records/{id}/invite
+#[post("/workout/records/{id}/invite")]
+async fn invite_partner(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    body: web::Json<InvitePartnerRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let record_id = path.into_inner();
+
+    let record: Option<(Option<String>,)> = sqlx::query_as(
+        "SELECT shared_session_id FROM training_records WHERE id = ? AND user_id = ?",
+    )
+    .bind(record_id)
+    .bind(session_user.id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    let Some((shared_session_id,)) = record else {
+        return Err(AppError::NotFound("Record not found".to_string()));
+    };
+
+    let login_id = body.login_id.trim();
+    let partner_id: Option<i64> = sqlx::query_scalar("SELECT id FROM users WHERE login_id = ?")
+        .bind(login_id)
+        .fetch_optional(pool.get_ref())
+        .await?;
+    let Some(partner_id) = partner_id else {
+        return Err(AppError::NotFound("ユーザーが見つかりません".to_string()));
+    };
+    if partner_id == session_user.id {
+        return Err(AppError::BadRequest("自分自身は招待できません".to_string()));
+    }
+
+    let existing: Option<(String,)> = sqlx::query_as(
+        "SELECT status FROM workout_partner_invites WHERE record_id = ? AND invitee_user_id = ?",
+    )
+    .bind(record_id)
+    .bind(partner_id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+
+    // 初回招待時に共有セッションIDを発行
+    let shared_session_id = match shared_session_id {
+        Some(id) => id,
+        None => {
+            let id = uuid::Uuid::new_v4().to_string();
+            sqlx::query("UPDATE training_records SET shared_session_id = ? WHERE id = ?")
+                .bind(&id)
+                .bind(record_id)
+                .execute(pool.get_ref())
+                .await?;
+            id
+        }
+    };
+
+    match existing.as_ref().map(|(s,)| s.as_str()) {
+        Some(STATUS_PENDING) | Some(STATUS_ACCEPTED) => {
+            return Err(AppError::BadRequest(
+                "このユーザーは既に招待済みです".to_string(),
+            ));
+        }
+        // 辞退された招待は再送できる
+        Some(_) => {
+            sqlx::query(
+                r#"UPDATE workout_partner_invites
+                   SET status = ?, inviter_user_id = ?, created_at = NOW(), responded_at = NULL
+                   WHERE record_id = ? AND invitee_user_id = ?"#,
+            )
+            .bind(STATUS_PENDING)
+            .bind(session_user.id)
+            .bind(record_id)
+            .bind(partner_id)
+            .execute(pool.get_ref())
+            .await?;
+        }
+        None => {
+            sqlx::query(
+                r#"INSERT INTO workout_partner_invites
+                   (record_id, inviter_user_id, invitee_user_id, status, created_at)
+                   VALUES (?, ?, ?, ?, NOW())"#,
+            )
+            .bind(record_id)
+            .bind(session_user.id)
+            .bind(partner_id)
+            .bind(STATUS_PENDING)
+            .execute(pool.get_ref())
+            .await?;
+        }
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "sharedSessionId": shared_session_id,
+    })))
+}
+
+/// GET /api/workout/invites
+/// 自分宛ての未回答の招待一覧
+#[get("/workout/invites")]
+async fn get_invites(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    #[derive(sqlx::FromRow)]
+    struct InviteRow {
+        id: i64,
+        record_id: i64,
+        record_date: NaiveDate,
+        inviter_id: i64,
+        inviter_name: String,
+        exercise_count: i64,
+        created_at: NaiveDateTime,
+    }
+
+    let rows: Vec<InviteRow> = sqlx::query_as(
+        r#"SELECT i.id, i.record_id, tr.record_date, u.id AS inviter_id,
+                  CAST(COALESCE(u.display_name, u.login_id) AS CHAR) AS inviter_name,
+                  (SELECT COUNT(*) FROM training_record_exercises tre
+                   WHERE tre.record_id = i.record_id) AS exercise_count,
+                  i.created_at
+           FROM workout_partner_invites i
+           INNER JOIN training_records tr ON tr.id = i.record_id
+           INNER JOIN users u ON u.id = i.inviter_user_id
+           WHERE i.invitee_user_id = ? AND i.status = ?
+           ORDER BY i.created_at DESC"#,
+    )
+    .bind(session_user.id)
+    .bind(STATUS_PENDING)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let invites: Vec<PartnerInviteDto> = rows
+        .into_iter()
+        .map(|r| PartnerInviteDto {
+            id: r.id,
+            record_id: r.record_id,
+            date: r.record_date.format("%Y-%m-%d").to_string(),
+            inviter: TrainingPartnerDto {
+                user_id: r.inviter_id,
+                display_name: r.inviter_name,
+            },
+            exercise_count: r.exercise_count,
+            created_at: r.created_at.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(invites))
+}
+
+/// 自分宛ての未回答の招待を条件付きで回答済みにする（既に回答済み・他人宛てならNotFound）
+async fn respond_to_pending_invite(
+    conn: &mut MySqlConnection,
+    invite_id: i64,
+    user_id: i64,
+    status: &str,
+) -> Result<(), AppError> {
+    let result = sqlx::query(
+        r#"UPDATE workout_partner_invites
+           SET status = ?, responded_at = NOW()
+           WHERE id = ? AND invitee_user_id = ? AND status = ?"#,
+    )
+    .bind(status)
+    .bind(invite_id)
+    .bind(user_id)
+    .bind(STATUS_PENDING)
+    .execute(conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Invite not found".to_string()));
+    }
+    Ok(())
+}
+
+/// 招待元の記録を取得（記録ID・記録日・共有セッションID・招待者）
+async fn find_invite_source(
+    conn: &mut MySqlConnection,
+    invite_id: i64,
+) -> Result<(i64, NaiveDate, Option<String>, i64), AppError> {
+    let invite: Option<(i64, NaiveDate, Option<String>, i64)> = sqlx::query_as(
+        r#"SELECT i.record_id, tr.record_date, tr.shared_session_id, tr.user_id
+           FROM workout_partner_invites i
+           INNER JOIN training_records tr ON tr.id = i.record_id
+           WHERE i.id = ?
+           FOR UPDATE"#,
+    )
+    .bind(invite_id)
+    .fetch_optional(conn)
+    .await?;
+
+    invite.ok_or_else(|| AppError::NotFound("Invite not found".to_string()))
+}
+
+/// POST /api/workout/invites/{id}/accept
+/// 招待元の記録と同じ日付に、同じ種目・回数の記録を作成する。
+/// 重量は各種目で自分が直近に記録した最大重量を使う（経験値は付与しない）
+#[post("/workout/invites/{id}/accept")]
+async fn accept_invite(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let invite_id = path.into_inner();
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    // 二重承諾を防ぐため、先に未回答の招待だけを承諾済みにする
+    respond_to_pending_invite(&mut tx, invite_id, session_user.id, STATUS_ACCEPTED).await?;
+
+    let (source_record_id, record_date, shared_session_id, inviter_id) =
+        find_invite_source(&mut tx, invite_id).await?;
+    let shared_session_id = shared_session_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    // 同じ日付の自分の記録があれば追記する
+    let existing: Option<(i64, Option<String>, i64)> = sqlx::query_as(
+        r#"SELECT id, shared_session_id, CAST(COALESCE(unlocked_until > NOW(), 0) AS SIGNED)
+           FROM training_records WHERE user_id = ? AND record_date = ?
+           FOR UPDATE"#,
+    )
+    .bind(session_user.id)
+    .bind(record_date)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let unlocked = existing.as_ref().is_some_and(|(_, _, u)| *u != 0);
+    ensure_record_unlocked(&config, &session_user, today, record_date, unlocked)?;
+
+    if let Some((_, Some(other), _)) = &existing {
+        if *other != shared_session_id {
+            return Err(AppError::BadRequest(
+                "この日の記録は既に別の合同トレーニングに含まれています".to_string(),
+            ));
+        }
+    }
+
+    // 招待元の種目・セット（カスタム種目は招待者専用のため対象外）
+    #[derive(sqlx::FromRow)]
+    struct SourceSetRow {
+        exercise_id: i64,
+        reps: i32,
+        set_type: String,
+    }
+
+    let source_sets: Vec<SourceSetRow> = sqlx::query_as(
+        r#"SELECT tre.exercise_id, ts.reps, ts.set_type
+           FROM training_record_exercises tre
+           INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+           WHERE tre.record_id = ? AND tre.exercise_id IS NOT NULL
+           ORDER BY tre.order_index ASC, tre.id ASC, ts.set_number ASC"#,
+    )
+    .bind(source_record_id)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let record_id = match existing {
+        Some((id, _, _)) => {
+            sqlx::query(
+                "UPDATE training_records SET shared_session_id = ?, updated_at = NOW() WHERE id = ?",
+            )
+            .bind(&shared_session_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+            id
+        }
+        None => {
+            let result = sqlx::query(
+                r#"INSERT INTO training_records (user_id, record_date, exp_earned, shared_session_id, created_at, updated_at)
+                   VALUES (?, ?, 0, ?, NOW(), NOW())"#,
+            )
+            .bind(session_user.id)
+            .bind(record_date)
+            .bind(&shared_session_id)
+            .execute(&mut *tx)
+            .await?;
+            result.last_insert_id() as i64
+        }
+    };
+
+    sqlx::query("UPDATE training_records SET shared_session_id = ? WHERE id = ?")
+        .bind(&shared_session_id)
+        .bind(source_record_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let max_order: (Option<i32>,) = sqlx::query_as(
+        "SELECT MAX(order_index) FROM training_record_exercises WHERE record_id = ?",
+    )
+    .bind(record_id)
+    .fetch_one(&mut *tx)
+    .await?;
+    let mut next_order_index = max_order.0.map(|v| v + 1).unwrap_or(0);
+
+    // 種目ごとに回数・セットの種類をまとめる（招待元の並び順を維持）
+    let mut exercises: Vec<(i64, Vec<(i32, String)>)> = Vec::new();
+    for set in source_sets {
+        let entry = (set.reps, set.set_type);
+        match exercises.last_mut() {
+            Some((exercise_id, sets)) if *exercise_id == set.exercise_id => sets.push(entry),
+            _ => exercises.push((set.exercise_id, vec![entry])),
+        }
+    }
+
+    // 種目ごとにセットを作成（既に記録済みの種目はスキップ）
+    for (exercise_id, set_list) in exercises {
+        let already: Option<(i64,)> = sqlx::query_as(
+            "SELECT id FROM training_record_exercises WHERE record_id = ? AND exercise_id = ?",
+        )
+        .bind(record_id)
+        .bind(exercise_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if already.is_some() {
+            continue;
+        }
+
+        let last_weight: Option<(f64,)> = sqlx::query_as(
+            r#"SELECT MAX(ts.weight)
+               FROM training_sets ts
+               INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+               INNER JOIN training_records tr ON tre.record_id = tr.id
+               WHERE tr.user_id = ? AND tre.exercise_id = ? AND tr.id <> ?
+               GROUP BY tr.record_date
+               ORDER BY tr.record_date DESC
+               LIMIT 1"#,
+        )
+        .bind(session_user.id)
+        .bind(exercise_id)
+        .bind(record_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let weight = last_weight.map(|(w,)| w).unwrap_or(0.0);
+
+        let result = sqlx::query(
+            r#"INSERT INTO training_record_exercises (record_id, exercise_id, order_index)
+               VALUES (?, ?, ?)"#,
+        )
+        .bind(record_id)
+        .bind(exercise_id)
+        .bind(next_order_index)
+        .execute(&mut *tx)
+        .await?;
+        next_order_index += 1;
+        let record_exercise_id = result.last_insert_id() as i64;
+
+        for (set_number, (reps, set_type)) in (1..).zip(set_list) {
+            sqlx::query(
+                r#"INSERT INTO training_sets
+                       (record_exercise_id, set_number, weight, reps, set_type,
+                        created_at, updated_at)
+                   VALUES (?, ?, ?, ?, ?, NOW(), NOW())"#,
+            )
+            .bind(record_exercise_id)
+            .bind(set_number)
+            .bind(weight)
+            .bind(reps)
+            .bind(set_type)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    sqlx::query("UPDATE workout_partner_invites SET partner_record_id = ? WHERE id = ?")
+        .bind(record_id)
+        .bind(invite_id)
+        .execute(&mut *tx)
+        .await?;
+
+    // 記録日のトレーニングストリークを更新
+    use crate::api::streak::record_training_activity;
+    record_training_activity(&mut tx, session_user.id, record_date).await?;
+
+    tx.commit().await?;
+
+    tracing::info!(
+        "User {} joined shared session {} with user {}",
+        session_user.id,
+        shared_session_id,
+        inviter_id
+    );
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "recordId": record_id,
+        "sharedSessionId": shared_session_id,
+    })))
+}
+
+/// POST /api/workout/invites/{id}/decline
+#[post("/workout/invites/{id}/decline")]
+async fn decline_invite(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let invite_id = path.into_inner();
+
+    let mut conn = pool.acquire().await?;
+    respond_to_pending_invite(&mut conn, invite_id, session_user.id, STATUS_DECLINED).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(invite_partner)
+        .service(get_invites)
+        .service(accept_invite)
+        .service(decline_invite);
+}
@@ -0,0 +1,184 @@
+//! トレーニングセッション（開始・終了時刻）APIハンドラ
+//!
+//! 開始時に当日の記録（なければ作成）へstarted_atを記録し、終了時にfinished_atを記録する。
+//! 所要時間は記録一覧・ダッシュボード統計・最近の記録に表示する。
+
+use actix_web::{get, post, web, HttpResponse};
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use sqlx::{MySqlConnection, MySqlPool};
+
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Serialize)]
+struct WorkoutSessionDto {
+    #[serde(rename = "recordId")]
+    record_id: i64,
+    date: String,
+    #[serde(rename = "startedAt")]
+    started_at: Option<NaiveDateTime>,
+    #[serde(rename = "finishedAt")]
+    finished_at: Option<NaiveDateTime>,
+    /// 所要時間（秒、終了済みの場合のみ）
+    #[serde(rename = "durationSeconds")]
+    duration_seconds: Option<i64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SessionRow {
+    id: i64,
+    record_date: NaiveDate,
+    started_at: Option<NaiveDateTime>,
+    finished_at: Option<NaiveDateTime>,
+}
+
+impl From<SessionRow> for WorkoutSessionDto {
+    fn from(row: SessionRow) -> Self {
+        let duration_seconds = match (row.started_at, row.finished_at) {
+            (Some(start), Some(end)) if end >= start => Some((end - start).num_seconds()),
+            _ => None,
+        };
+        Self {
+            record_id: row.id,
+            date: row.record_date.format("%Y-%m-%d").to_string(),
+            started_at: row.started_at,
+            finished_at: row.finished_at,
+            duration_seconds,
+        }
+    }
+}
+
+/// 記録の開始・終了時刻を取得
+async fn fetch_session(conn: &mut MySqlConnection, record_id: i64) -> Result<SessionRow, AppError> {
+    let row = sqlx::query_as(
+        "SELECT id, record_date, started_at, finished_at FROM training_records WHERE id = ?",
+    )
+    .bind(record_id)
+    .fetch_one(conn)
+    .await?;
+    Ok(row)
+}
+
+/// 開始済みで終了していないセッション（最も新しいもの）
+async fn find_active_session(
+    conn: &mut MySqlConnection,
+    user_id: i64,
+) -> Result<Option<SessionRow>, AppError> {
+    let row = sqlx::query_as(
+        r#"SELECT id, record_date, started_at, finished_at FROM training_records
+           WHERE user_id = ? AND started_at IS NOT NULL AND finished_at IS NULL
+           ORDER BY started_at DESC LIMIT 1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(conn)
+    .await?;
+    Ok(row)
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/workout/sessions/current
+/// 計測中のセッション（なければnull）
+#[get("/workout/sessions/current")]
+async fn get_current_session(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let active = find_active_session(&mut *pool.acquire().await?, session_user.id).await?;
+    Ok(HttpResponse::Ok().json(active.map(WorkoutSessionDto::from)))
+}
+
+/// POST /api/workout/sessions/start
+/// 今日（JST）の記録に開始時刻を記録する（記録がなければ作成）
+/// 終了済みの場合は終了時刻をクリアして再開する（開始時刻は最初の開始のまま）
+#[post("/workout/sessions/start")]
+async fn start_session(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let today = fetch_user_today(pool.get_ref(), session_user.id).await?;
+
+    let mut tx = pool.begin().await?;
+
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM training_records WHERE user_id = ? AND record_date = ? FOR UPDATE",
+    )
+    .bind(session_user.id)
+    .bind(today)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let record_id = match existing {
+        Some(id) => {
+            sqlx::query(
+                r#"UPDATE training_records
+                   SET started_at = COALESCE(started_at, NOW()), finished_at = NULL
+                   WHERE id = ?"#,
+            )
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+            id
+        }
+        None => {
+            let result = sqlx::query(
+                r#"INSERT INTO training_records
+                       (user_id, record_date, exp_earned, started_at, created_at, updated_at)
+                   VALUES (?, ?, 0, NOW(), NOW(), NOW())"#,
+            )
+            .bind(session_user.id)
+            .bind(today)
+            .execute(&mut *tx)
+            .await?;
+            result.last_insert_id() as i64
+        }
+    };
+
+    let row = fetch_session(&mut tx, record_id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(WorkoutSessionDto::from(row)))
+}
+
+/// POST /api/workout/sessions/finish
+/// 計測中のセッションに終了時刻を記録する
+#[post("/workout/sessions/finish")]
+async fn finish_session(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let mut conn = pool.acquire().await?;
+    let Some(active) = find_active_session(&mut conn, session_user.id).await? else {
+        return Err(AppError::BadRequest(
+            "計測中のトレーニングがありません".to_string(),
+        ));
+    };
+
+    sqlx::query("UPDATE training_records SET finished_at = NOW() WHERE id = ?")
+        .bind(active.id)
+        .execute(&mut *conn)
+        .await?;
+
+    let row = fetch_session(&mut conn, active.id).await?;
+    Ok(HttpResponse::Ok().json(WorkoutSessionDto::from(row)))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_current_session)
+        .service(start_session)
+        .service(finish_session);
+}
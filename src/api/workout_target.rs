@@ -0,0 +1,342 @@
+//! 筋肉グループ別の週間目標（セット数・ボリューム）APIハンドラ
+//!
+//! ユーザーが設定した目標と今週（月曜始まり）の実績を比較し、
+//! 「胸 12/16セット」のような進捗としてダッシュボードに表示する。
+
+use actix_web::{delete, get, post, put, web, HttpResponse};
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::MySqlPool;
+use std::collections::HashMap;
+
+use crate::api::challenge::week_start_of;
+use crate::api::dashboard::{map_muscle_to_group, MUSCLE_GROUPS};
+use crate::api::streak::fetch_user_today;
+use crate::auth::session::{get_current_user, Session};
+use crate::error::AppError;
+
+/// 週間セット数目標の上限
+const MAX_TARGET_SETS: i32 = 50;
+/// 週間ボリューム目標の上限（kg）
+const MAX_TARGET_VOLUME: f64 = 1_000_000.0;
+
+// ============================================
+// DTOs
+// ============================================
+
+#[derive(Deserialize)]
+struct MuscleTargetRequest {
+    muscle: String,
+    #[serde(rename = "targetSets")]
+    target_sets: Option<i32>,
+    #[serde(rename = "targetVolume")]
+    target_volume: Option<f64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct MuscleTargetRow {
+    id: i64,
+    muscle_group: String,
+    target_sets: Option<i32>,
+    target_volume: Option<f64>,
+}
+
+/// 目標と今週の実績
+#[derive(Serialize)]
+pub(crate) struct MuscleTargetProgressDto {
+    id: i64,
+    muscle: String,
+    #[serde(rename = "targetSets")]
+    target_sets: Option<i32>,
+    #[serde(rename = "targetVolume")]
+    target_volume: Option<f64>,
+    /// 今週のセット数（ウォームアップを除く）
+    sets: i32,
+    /// 今週のボリューム（重量×回数、ウォームアップを除く）
+    volume: f64,
+    /// 目標に対する達成率（0.0〜1.0、セット数とボリュームの両方がある場合は低い方）
+    progress: f64,
+    achieved: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MuscleTargetsResponse {
+    week_start: String,
+    week_end: String,
+    targets: Vec<MuscleTargetProgressDto>,
+}
+
+// ============================================
+// 進捗の集計
+// ============================================
+
+/// `today`を含む週（月曜始まり）の期間
+/// `today`はユーザーのタイムゾーンでの日付（ユーザー統計の目標進捗と同じ週にする）
+pub(crate) fn target_week(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let week_start = week_start_of(today);
+    (week_start, week_start + Days::new(6))
+}
+
+/// 目標ごとに期間内のセット数・ボリュームを集計
+pub(crate) async fn fetch_muscle_target_progress(
+    pool: &MySqlPool,
+    user_id: i64,
+    week_start: NaiveDate,
+    week_end: NaiveDate,
+) -> Result<Vec<MuscleTargetProgressDto>, AppError> {
+    let targets: Vec<MuscleTargetRow> = sqlx::query_as(
+        r#"SELECT id, muscle_group, target_sets, target_volume
+           FROM user_muscle_targets WHERE user_id = ?"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    if targets.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let rows: Vec<(Option<String>, i64, Option<f64>)> = sqlx::query_as(
+        r#"SELECT CAST(COALESCE(e.muscle, uce.muscle) AS CHAR) AS muscle,
+                  COUNT(ts.id) AS set_count,
+                  SUM(ts.weight * ts.reps) AS volume
+           FROM training_records tr
+           INNER JOIN training_record_exercises tre ON tre.record_id = tr.id
+           INNER JOIN training_sets ts ON ts.record_exercise_id = tre.id
+           LEFT JOIN exercises e ON e.id = tre.exercise_id
+           LEFT JOIN user_custom_exercises uce ON uce.id = tre.custom_exercise_id
+           WHERE tr.user_id = ? AND tr.record_date >= ? AND tr.record_date <= ?
+             AND ts.set_type <> 'warmup'
+           GROUP BY muscle"#,
+    )
+    .bind(user_id)
+    .bind(week_start)
+    .bind(week_end)
+    .fetch_all(pool)
+    .await?;
+
+    let mut totals: HashMap<&str, (i32, f64)> = HashMap::new();
+    for (muscle, set_count, volume) in rows {
+        if let Some(group) = muscle.as_deref().and_then(map_muscle_to_group) {
+            let entry = totals.entry(group).or_default();
+            entry.0 += set_count as i32;
+            entry.1 += volume.unwrap_or(0.0);
+        }
+    }
+
+    let mut result: Vec<MuscleTargetProgressDto> = targets
+        .into_iter()
+        .map(|t| {
+            let (sets, volume) = totals
+                .get(t.muscle_group.as_str())
+                .copied()
+                .unwrap_or_default();
+            let rates = [
+                t.target_sets
+                    .filter(|&target| target > 0)
+                    .map(|target| sets as f64 / target as f64),
+                t.target_volume
+                    .filter(|&target| target > 0.0)
+                    .map(|target| volume / target),
+            ];
+            let progress = rates.into_iter().flatten().reduce(f64::min).unwrap_or(1.0);
+            MuscleTargetProgressDto {
+                id: t.id,
+                muscle: t.muscle_group,
+                target_sets: t.target_sets,
+                target_volume: t.target_volume,
+                sets,
+                volume,
+                progress: progress.min(1.0),
+                achieved: progress >= 1.0,
+            }
+        })
+        .collect();
+
+    // 筋肉グループの定義順に並べる
+    result.sort_by_key(|t| MUSCLE_GROUPS.iter().position(|&mg| mg == t.muscle));
+    Ok(result)
+}
+
+/// 目標の入力値チェック
+fn validate_target(body: &MuscleTargetRequest) -> Result<(), AppError> {
+    if !MUSCLE_GROUPS.contains(&body.muscle.as_str()) {
+        return Err(AppError::BadRequest(format!(
+            "不明な筋肉グループです: {}",
+            body.muscle
+        )));
+    }
+    if body.target_sets.is_none() && body.target_volume.is_none() {
+        return Err(AppError::BadRequest(
+            "セット数かボリュームの目標を指定してください".to_string(),
+        ));
+    }
+    if body
+        .target_sets
+        .is_some_and(|sets| !(1..=MAX_TARGET_SETS).contains(&sets))
+    {
+        return Err(AppError::BadRequest(format!(
+            "セット数の目標は1〜{}で指定してください",
+            MAX_TARGET_SETS
+        )));
+    }
+    if body
+        .target_volume
+        .is_some_and(|volume| !(volume > 0.0 && volume <= MAX_TARGET_VOLUME))
+    {
+        return Err(AppError::BadRequest(format!(
+            "ボリュームの目標は0より大きく{}kg以下で指定してください",
+            MAX_TARGET_VOLUME
+        )));
+    }
+    Ok(())
+}
+
+/// 今週の進捗を付けた目標一覧のレスポンス
+async fn targets_response(pool: &MySqlPool, user_id: i64) -> Result<HttpResponse, AppError> {
+    let today = fetch_user_today(pool, user_id).await?;
+    let (week_start, week_end) = target_week(today);
+    let targets = fetch_muscle_target_progress(pool, user_id, week_start, week_end).await?;
+    Ok(HttpResponse::Ok().json(MuscleTargetsResponse {
+        week_start: week_start.format("%Y-%m-%d").to_string(),
+        week_end: week_end.format("%Y-%m-%d").to_string(),
+        targets,
+    }))
+}
+
+// ============================================
+// ハンドラ
+// ============================================
+
+/// GET /api/workout/targets
+/// 目標一覧と今週の進捗
+#[get("/workout/targets")]
+async fn get_targets(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    targets_response(pool.get_ref(), session_user.id).await
+}
+
+/// POST /api/workout/targets
+/// 筋肉グループの目標を追加（1グループにつき1件）
+#[post("/workout/targets")]
+async fn create_target(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    body: web::Json<MuscleTargetRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    validate_target(&body)?;
+
+    let exists: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM user_muscle_targets WHERE user_id = ? AND muscle_group = ?",
+    )
+    .bind(session_user.id)
+    .bind(&body.muscle)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    if exists.is_some() {
+        return Err(AppError::BadRequest(format!(
+            "{}の目標は既に設定されています",
+            body.muscle
+        )));
+    }
+
+    sqlx::query(
+        r#"INSERT INTO user_muscle_targets
+               (user_id, muscle_group, target_sets, target_volume, created_at, updated_at)
+           VALUES (?, ?, ?, ?, NOW(), NOW())"#,
+    )
+    .bind(session_user.id)
+    .bind(&body.muscle)
+    .bind(body.target_sets)
+    .bind(body.target_volume)
+    .execute(pool.get_ref())
+    .await?;
+
+    targets_response(pool.get_ref(), session_user.id).await
+}
+
+/// PUT /api/workout/targets/{id}
+#[put("/workout/targets/{id}")]
+async fn update_target(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+    body: web::Json<MuscleTargetRequest>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+    let target_id = path.into_inner();
+    validate_target(&body)?;
+
+    let current: Option<String> = sqlx::query_scalar(
+        "SELECT muscle_group FROM user_muscle_targets WHERE id = ? AND user_id = ?",
+    )
+    .bind(target_id)
+    .bind(session_user.id)
+    .fetch_optional(pool.get_ref())
+    .await?;
+    let Some(current_muscle) = current else {
+        return Err(AppError::NotFound("Target not found".to_string()));
+    };
+
+    if current_muscle != body.muscle {
+        let duplicate: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM user_muscle_targets WHERE user_id = ? AND muscle_group = ?",
+        )
+        .bind(session_user.id)
+        .bind(&body.muscle)
+        .fetch_optional(pool.get_ref())
+        .await?;
+        if duplicate.is_some() {
+            return Err(AppError::BadRequest(format!(
+                "{}の目標は既に設定されています",
+                body.muscle
+            )));
+        }
+    }
+
+    sqlx::query(
+        r#"UPDATE user_muscle_targets
+           SET muscle_group = ?, target_sets = ?, target_volume = ?, updated_at = NOW()
+           WHERE id = ?"#,
+    )
+    .bind(&body.muscle)
+    .bind(body.target_sets)
+    .bind(body.target_volume)
+    .bind(target_id)
+    .execute(pool.get_ref())
+    .await?;
+
+    targets_response(pool.get_ref(), session_user.id).await
+}
+
+/// DELETE /api/workout/targets/{id}
+#[delete("/workout/targets/{id}")]
+async fn delete_target(
+    pool: web::Data<MySqlPool>,
+    session: Session,
+    path: web::Path<i64>,
+) -> Result<HttpResponse, AppError> {
+    let session_user = get_current_user(&session)?;
+
+    let result = sqlx::query("DELETE FROM user_muscle_targets WHERE id = ? AND user_id = ?")
+        .bind(path.into_inner())
+        .bind(session_user.id)
+        .execute(pool.get_ref())
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Target not found".to_string()));
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({ "success": true })))
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_targets)
+        .service(create_target)
+        .service(update_target)
+        .service(delete_target);
+}
@@ -0,0 +1,151 @@
+//! User-scoped API keys for scripted access
+//!
+//! Keys look like `fh_` followed by 40 random characters and are sent in the `X-Api-Key` header.
+//! Only the SHA-256 hash is stored, together with a short plain-text prefix so users can tell
+//! their keys apart. Each key carries scopes that limit which API requests it may make, and keys
+//! can never reach account, credential or admin endpoints.
+
+use actix_web::http::Method;
+use rand::distributions::{Alphanumeric, DistString};
+use sha2::{Digest, Sha256};
+
+/// Request header carrying the key
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+const API_KEY_PREFIX: &str = "fh_";
+const API_KEY_RANDOM_CHARS: usize = 40;
+/// Characters of the key kept in plain text for display (`fh_` plus 8 characters)
+const DISPLAY_PREFIX_CHARS: usize = 11;
+
+/// Read any data the user can see
+pub const SCOPE_READ: &str = "read";
+/// Create, update and delete workout records (including imports)
+pub const SCOPE_WORKOUT_WRITE: &str = "workout:write";
+pub const API_KEY_SCOPES: &[&str] = &[SCOPE_READ, SCOPE_WORKOUT_WRITE];
+
+/// API paths (without the `/api` or `/api/v1` prefix) that keys can never use
+const RESTRICTED_PATHS: &[&str] = &[
+    "/admin",
+    "/auth",
+    "/user/account",
+    "/user/api-keys",
+    "/user/email",
+    "/user/export",
+    "/user/password",
+    "/user/security",
+    "/user/sessions",
+];
+
+/// Generate a new key (shown to the user once)
+pub fn generate_api_key() -> String {
+    format!(
+        "{}{}",
+        API_KEY_PREFIX,
+        Alphanumeric.sample_string(&mut rand::thread_rng(), API_KEY_RANDOM_CHARS)
+    )
+}
+
+/// Hash stored in place of the key
+pub fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.trim().as_bytes()))
+}
+
+/// Plain-text prefix shown in the key list
+pub fn display_prefix(key: &str) -> String {
+    key.chars().take(DISPLAY_PREFIX_CHARS).collect()
+}
+
+pub fn is_valid_scope(scope: &str) -> bool {
+    API_KEY_SCOPES.contains(&scope)
+}
+
+/// Scopes stored as a comma-separated list
+pub fn parse_scopes(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|scope| is_valid_scope(scope))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether a key with these scopes may make the request
+pub fn scope_allows(scopes: &[String], method: &Method, path: &str) -> bool {
+    let Some(path) = path
+        .strip_prefix("/api/v1")
+        .or_else(|| path.strip_prefix("/api"))
+        .filter(|rest| rest.starts_with('/'))
+    else {
+        return false;
+    };
+    let restricted = RESTRICTED_PATHS.iter().any(|restricted| {
+        path.strip_prefix(restricted)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    });
+    if restricted {
+        return false;
+    }
+
+    let has = |scope: &str| scopes.iter().any(|s| s == scope);
+    if method == Method::GET || method == Method::HEAD {
+        return has(SCOPE_READ);
+    }
+    path.starts_with("/workout/") && has(SCOPE_WORKOUT_WRITE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn generated_keys_hash_to_a_stable_value() {
+        let key = generate_api_key();
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_eq!(key.len(), API_KEY_PREFIX.len() + API_KEY_RANDOM_CHARS);
+        assert_eq!(display_prefix(&key).len(), DISPLAY_PREFIX_CHARS);
+        assert_eq!(hash_api_key(&key), hash_api_key(&format!(" {}\n", key)));
+        assert_ne!(hash_api_key(&key), hash_api_key(&generate_api_key()));
+    }
+
+    #[test]
+    fn parse_scopes_drops_unknown_scopes() {
+        assert_eq!(
+            parse_scopes("read, admin ,workout:write,"),
+            scopes(&[SCOPE_READ, SCOPE_WORKOUT_WRITE])
+        );
+    }
+
+    #[test]
+    fn read_scope_only_allows_reads() {
+        let read = scopes(&[SCOPE_READ]);
+        assert!(scope_allows(&read, &Method::GET, "/api/workout/records"));
+        assert!(scope_allows(
+            &read,
+            &Method::GET,
+            "/api/v1/dashboard/heatmap"
+        ));
+        assert!(!scope_allows(&read, &Method::POST, "/api/workout/records"));
+    }
+
+    #[test]
+    fn workout_write_scope_is_limited_to_workout_paths() {
+        let write = scopes(&[SCOPE_WORKOUT_WRITE]);
+        assert!(scope_allows(&write, &Method::POST, "/api/workout/records"));
+        assert!(!scope_allows(&write, &Method::POST, "/api/pet/feed"));
+        assert!(!scope_allows(&write, &Method::GET, "/api/workout/records"));
+    }
+
+    #[test]
+    fn restricted_paths_are_never_allowed() {
+        let all = scopes(API_KEY_SCOPES);
+        assert!(!scope_allows(&all, &Method::GET, "/api/user/api-keys"));
+        assert!(!scope_allows(&all, &Method::GET, "/api/v1/admin/users"));
+        assert!(!scope_allows(&all, &Method::POST, "/api/auth/2fa/setup"));
+        assert!(!scope_allows(&all, &Method::GET, "/user/info"));
+        // A shared prefix alone does not make a path restricted
+        assert!(scope_allows(&all, &Method::GET, "/api/user/emailx"));
+    }
+}
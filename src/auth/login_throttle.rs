@@ -0,0 +1,133 @@
+//! Password login throttling
+//!
+//! Limits password login attempts per client IP and locks a login ID for a while after repeated
+//! failures. Counters and locks live in the shared store so every instance enforces the same
+//! limits. Locked requests get a 429 with `Retry-After` and a `retryAfter` field in seconds.
+
+use std::time::Duration;
+
+use actix_web::{HttpRequest, HttpResponse};
+use chrono::Utc;
+
+use crate::shared_store::SharedStore;
+
+/// Window for counting attempts and failures
+const ATTEMPT_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// Login attempts allowed per IP within the window
+const MAX_ATTEMPTS_PER_IP: u64 = 30;
+/// Consecutive failures allowed per login ID within the window
+const MAX_FAILURES_PER_LOGIN_ID: u64 = 5;
+/// How long an IP or login ID stays locked once a limit is exceeded
+const LOCKOUT_DURATION: Duration = Duration::from_secs(15 * 60);
+
+/// Rejected because the IP or login ID is locked
+pub struct LoginThrottled {
+    /// Seconds until the lock expires
+    pub retry_after: i64,
+}
+
+impl LoginThrottled {
+    /// Structured 429 response
+    pub fn to_response(&self) -> HttpResponse {
+        HttpResponse::TooManyRequests()
+            .append_header(("Retry-After", self.retry_after.to_string()))
+            .json(serde_json::json!({
+                "error": "RATE_LIMITED",
+                "message": format!(
+                    "ログインの試行回数が多すぎます。{}分ほど待ってから再度お試しください",
+                    (self.retry_after + 59) / 60
+                ),
+                "retryAfter": self.retry_after,
+            }))
+    }
+}
+
+/// Client IP as reported by the reverse proxy (falls back to the peer address)
+pub fn client_ip(req: &HttpRequest) -> String {
+    req.connection_info()
+        .realip_remote_addr()
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn ip_lock_key(ip: &str) -> String {
+    format!("login_lock:ip:{}", ip)
+}
+
+fn login_id_lock_key(login_id: &str) -> String {
+    format!("login_lock:id:{}", login_id.trim().to_lowercase())
+}
+
+fn failure_key(login_id: &str) -> String {
+    format!("login_fail:{}", login_id.trim().to_lowercase())
+}
+
+/// Seconds left on a lock, if it is still active
+async fn remaining_lock(store: &SharedStore, key: &str) -> Option<i64> {
+    let locked_until: i64 = store.get_cached(key).await?;
+    let remaining = locked_until - Utc::now().timestamp();
+    (remaining > 0).then_some(remaining)
+}
+
+async fn lock(store: &SharedStore, key: &str) -> LoginThrottled {
+    let locked_until = Utc::now().timestamp() + LOCKOUT_DURATION.as_secs() as i64;
+    store.set_cached(key, &locked_until, LOCKOUT_DURATION).await;
+    LoginThrottled {
+        retry_after: LOCKOUT_DURATION.as_secs() as i64,
+    }
+}
+
+/// Count an attempt and reject it if the IP or login ID is locked.
+/// Call before verifying the password.
+pub async fn check_login_attempt(
+    store: &SharedStore,
+    ip: &str,
+    login_id: &str,
+) -> Result<(), LoginThrottled> {
+    for key in [ip_lock_key(ip), login_id_lock_key(login_id)] {
+        if let Some(retry_after) = remaining_lock(store, &key).await {
+            return Err(LoginThrottled { retry_after });
+        }
+    }
+
+    match store
+        .increment(&format!("login_ip:{}", ip), ATTEMPT_WINDOW)
+        .await
+    {
+        Ok(count) if count > MAX_ATTEMPTS_PER_IP => {
+            tracing::warn!("[LOGIN] Too many attempts from {}, locking", ip);
+            Err(lock(store, &ip_lock_key(ip)).await)
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            tracing::warn!("Login attempt count failed for {}: {}", ip, e);
+            Ok(())
+        }
+    }
+}
+
+/// Record a failed password check; locks the login ID once the limit is reached
+pub async fn record_login_failure(store: &SharedStore, ip: &str, login_id: &str) {
+    match store
+        .increment(&failure_key(login_id), ATTEMPT_WINDOW)
+        .await
+    {
+        Ok(count) if count >= MAX_FAILURES_PER_LOGIN_ID => {
+            tracing::warn!(
+                "[LOGIN] {} failures for login_id={} (last from {}), locking",
+                count,
+                login_id,
+                ip
+            );
+            lock(store, &login_id_lock_key(login_id)).await;
+            store.reset_count(&failure_key(login_id)).await;
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Login failure count failed for {}: {}", login_id, e),
+    }
+}
+
+/// Clear the failure count after a successful login
+pub async fn record_login_success(store: &SharedStore, login_id: &str) {
+    store.reset_count(&failure_key(login_id)).await;
+}
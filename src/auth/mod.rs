@@ -1,5 +1,13 @@
+pub mod api_key;
+pub mod login_throttle;
+pub mod oauth;
+pub mod oauth_apple;
 pub mod oauth_github;
 pub mod oauth_google;
+pub mod oauth_line;
 pub mod oauth_microsoft;
+pub mod password_policy;
 pub mod session;
-
+pub mod session_store;
+pub mod token;
+pub mod totp;
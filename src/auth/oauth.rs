@@ -0,0 +1,250 @@
+//! OAuth2 provider registry
+//!
+//! Each provider is described by an `OAuthProviderConfig` (endpoints, scopes and a
+//! user-info mapper). The generic `/oauth2/authorization/{provider}` and
+//! `/login/oauth2/code/{provider}` routes look providers up here, so adding a new
+//! login method only needs a new `oauth_*` module that builds its config.
+
+use std::sync::Arc;
+
+use jsonwebtoken::{DecodingKey, Validation};
+use oauth2::{
+    basic::{
+        BasicErrorResponse, BasicRevocationErrorResponse, BasicTokenIntrospectionResponse,
+        BasicTokenType,
+    },
+    reqwest::async_http_client,
+    AuthType, AuthUrl, AuthorizationCode, Client, ClientId, ClientSecret, CsrfToken,
+    ExtraTokenFields, RedirectUrl, Scope, StandardRevocableToken, StandardTokenResponse,
+    TokenResponse, TokenUrl,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+
+/// Provider-independent user info returned by a mapper
+#[derive(Debug, Clone)]
+pub struct OAuthUserInfo {
+    pub oauth_id: String,
+    pub email: Option<String>,
+    pub name: Option<String>,
+    pub profile_image_url: Option<String>,
+}
+
+/// Raw profile data collected during the callback
+#[derive(Debug, Default)]
+pub struct OAuthProfile {
+    /// Response of the provider's user-info endpoint
+    pub userinfo: Option<serde_json::Value>,
+    /// Claims of the ID token (only for providers with `id_token_issuer`)
+    pub id_token_claims: Option<serde_json::Value>,
+    /// `user` parameter sent with a form_post callback (Apple sends the name only here)
+    pub callback_user: Option<serde_json::Value>,
+}
+
+/// Maps the provider's raw profile into `OAuthUserInfo`
+pub type UserInfoMapper = fn(OAuthProfile) -> Result<OAuthUserInfo, String>;
+
+/// Client secret: a fixed value or one generated per token request (e.g. Apple's signed JWT)
+#[derive(Clone)]
+pub enum OAuthClientSecret {
+    Static(String),
+    Generated(Arc<dyn Fn() -> Result<String, String> + Send + Sync>),
+}
+
+impl OAuthClientSecret {
+    fn resolve(&self) -> Result<String, String> {
+        match self {
+            OAuthClientSecret::Static(secret) => Ok(secret.clone()),
+            OAuthClientSecret::Generated(generate) => generate(),
+        }
+    }
+}
+
+/// Token response fields beyond the standard ones (OpenID Connect `id_token`)
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct IdTokenFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+impl ExtraTokenFields for IdTokenFields {}
+
+type OAuthClient = Client<
+    BasicErrorResponse,
+    StandardTokenResponse<IdTokenFields, BasicTokenType>,
+    BasicTokenType,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+>;
+
+/// Static description of an OAuth2 provider
+pub struct OAuthProviderConfig {
+    /// URL segment used in the generic routes (e.g. "google")
+    pub name: &'static str,
+    /// Value stored in users.oauth_provider (e.g. "GOOGLE")
+    pub provider_code: &'static str,
+    pub client_id: String,
+    pub client_secret: OAuthClientSecret,
+    pub redirect_uri: String,
+    pub auth_url: &'static str,
+    pub token_url: &'static str,
+    /// User-info endpoint (None when everything comes from the ID token)
+    pub userinfo_url: Option<&'static str>,
+    /// Expected `iss` of the ID token. When set, the ID token is decoded for the mapper
+    pub id_token_issuer: Option<&'static str>,
+    pub scopes: &'static [&'static str],
+    /// Extra authorization parameters (e.g. Apple's `response_mode=form_post`)
+    pub extra_auth_params: &'static [(&'static str, &'static str)],
+    /// How client credentials are sent to the token endpoint
+    pub token_auth_type: AuthType,
+    pub map_user_info: UserInfoMapper,
+}
+
+impl OAuthProviderConfig {
+    /// Whether client credentials are configured for this provider
+    pub fn is_enabled(&self) -> bool {
+        !self.client_id.is_empty()
+    }
+
+    fn client(&self, client_secret: Option<String>) -> Result<OAuthClient, String> {
+        let auth_url = AuthUrl::new(self.auth_url.to_string())
+            .map_err(|e| format!("Invalid auth URL: {}", e))?;
+        let token_url = TokenUrl::new(self.token_url.to_string())
+            .map_err(|e| format!("Invalid token URL: {}", e))?;
+        let redirect_url = RedirectUrl::new(self.redirect_uri.clone())
+            .map_err(|e| format!("Invalid redirect URL: {}", e))?;
+
+        Ok(OAuthClient::new(
+            ClientId::new(self.client_id.clone()),
+            client_secret.map(ClientSecret::new),
+            auth_url,
+            Some(token_url),
+        )
+        .set_auth_type(self.token_auth_type.clone())
+        .set_redirect_uri(redirect_url))
+    }
+
+    /// Generate authorization URL
+    pub fn authorize_url(&self) -> Result<(String, CsrfToken), String> {
+        // 認可URLの生成にクライアントシークレットは不要
+        let client = self.client(None)?;
+        let mut request = client.authorize_url(CsrfToken::new_random);
+        for scope in self.scopes {
+            request = request.add_scope(Scope::new(scope.to_string()));
+        }
+        for (name, value) in self.extra_auth_params {
+            request = request.add_extra_param(*name, *value);
+        }
+        let (auth_url, csrf_token) = request.url();
+        Ok((auth_url.to_string(), csrf_token))
+    }
+
+    /// Exchange authorization code for access token and fetch user info
+    pub async fn exchange_code_for_user_info(
+        &self,
+        code: String,
+        callback_user: Option<serde_json::Value>,
+    ) -> Result<OAuthUserInfo, String> {
+        let client = self.client(Some(self.client_secret.resolve()?))?;
+
+        // Exchange code for token
+        let token_result = client
+            .exchange_code(AuthorizationCode::new(code))
+            .request_async(async_http_client)
+            .await
+            .map_err(|e| {
+                tracing::error!("{} OAuth token exchange failed: {:?}", self.provider_code, e);
+                format!("Token exchange failed: {}", e)
+            })?;
+
+        let mut profile = OAuthProfile {
+            callback_user,
+            ..Default::default()
+        };
+
+        if let Some(issuer) = self.id_token_issuer {
+            let id_token = token_result
+                .extra_fields()
+                .id_token
+                .as_deref()
+                .ok_or_else(|| "ID token was not returned".to_string())?;
+            profile.id_token_claims = Some(self.decode_id_token(id_token, issuer)?);
+        }
+
+        if let Some(userinfo_url) = self.userinfo_url {
+            let access_token = token_result.access_token().secret();
+
+            // Fetch user info (GitHub requires a User-Agent header)
+            let http_client = reqwest::Client::new();
+            let user_info: serde_json::Value = http_client
+                .get(userinfo_url)
+                .header("User-Agent", "FithubFast")
+                .bearer_auth(access_token)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to fetch user info: {}", e))?
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse user info: {}", e))?;
+            profile.userinfo = Some(user_info);
+        }
+
+        (self.map_user_info)(profile)
+    }
+
+    /// Decode the ID token claims
+    ///
+    /// The token comes straight from the token endpoint over TLS, so (per OpenID Connect
+    /// Core 3.1.3.7) the signature check is skipped; issuer, audience and expiry are validated.
+    fn decode_id_token(&self, id_token: &str, issuer: &str) -> Result<serde_json::Value, String> {
+        let header = jsonwebtoken::decode_header(id_token)
+            .map_err(|e| format!("Invalid ID token: {}", e))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.insecure_disable_signature_validation();
+        validation.set_issuer(&[issuer]);
+        validation.set_audience(&[&self.client_id]);
+
+        jsonwebtoken::decode::<serde_json::Value>(
+            id_token,
+            &DecodingKey::from_secret(&[]),
+            &validation,
+        )
+        .map(|data| data.claims)
+        .map_err(|e| format!("Invalid ID token: {}", e))
+    }
+}
+
+/// Deserialize one section of the raw profile into the provider's user-info struct
+pub fn parse_profile_section<T: serde::de::DeserializeOwned>(
+    value: Option<serde_json::Value>,
+) -> Result<T, String> {
+    let value = value.ok_or_else(|| "Missing user info".to_string())?;
+    serde_json::from_value(value).map_err(|e| format!("Failed to parse user info: {}", e))
+}
+
+/// Registry of all OAuth2 providers
+pub struct OAuthRegistry {
+    providers: Vec<OAuthProviderConfig>,
+}
+
+impl OAuthRegistry {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            providers: vec![
+                crate::auth::oauth_google::provider(config),
+                crate::auth::oauth_github::provider(config),
+                crate::auth::oauth_microsoft::provider(config),
+                crate::auth::oauth_apple::provider(config),
+                crate::auth::oauth_line::provider(config),
+            ],
+        }
+    }
+
+    /// Look up a provider by its route name
+    pub fn get(&self, name: &str) -> Option<&OAuthProviderConfig> {
+        self.providers.iter().find(|p| p.name == name)
+    }
+}
@@ -0,0 +1,141 @@
+//! Sign in with Apple provider
+//!
+//! Apple differs from the other providers in three ways:
+//! - the client secret is an ES256-signed JWT generated from the .p8 key
+//! - name/email scopes require `response_mode=form_post`, so the callback is a POST
+//! - user info comes from the ID token; the name is only sent once, in the `user` form field
+
+use std::sync::Arc;
+
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use oauth2::AuthType;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::oauth::{
+    parse_profile_section, OAuthClientSecret, OAuthProfile, OAuthProviderConfig, OAuthUserInfo,
+};
+use crate::config::AppConfig;
+
+const APPLE_ISSUER: &str = "https://appleid.apple.com";
+
+/// Domain of Apple's private email relay ("Hide My Email")
+const PRIVATE_RELAY_DOMAIN: &str = "privaterelay.appleid.com";
+
+/// Client secret JWT lifetime in seconds (generated per token request, so keep it short)
+const CLIENT_SECRET_TTL_SECS: i64 = 300;
+
+/// ID token claims
+#[derive(Debug, Deserialize)]
+pub struct AppleIdTokenClaims {
+    pub sub: String,
+    /// 「メールを非公開」を選んだ場合はプライベートリレーアドレス
+    pub email: Option<String>,
+}
+
+/// `user` form field sent on the first authorization only
+#[derive(Debug, Deserialize)]
+pub struct AppleCallbackUser {
+    pub name: Option<AppleUserName>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppleUserName {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ClientSecretClaims<'a> {
+    iss: &'a str,
+    iat: i64,
+    exp: i64,
+    aud: &'a str,
+    sub: &'a str,
+}
+
+pub fn provider(config: &AppConfig) -> OAuthProviderConfig {
+    let team_id = config.apple_team_id.clone();
+    let key_id = config.apple_key_id.clone();
+    let private_key = config.apple_private_key.clone();
+    let client_id = config.apple_client_id.clone();
+
+    OAuthProviderConfig {
+        name: "apple",
+        provider_code: "APPLE",
+        client_id: config.apple_client_id.clone(),
+        client_secret: OAuthClientSecret::Generated(Arc::new(move || {
+            generate_client_secret(&team_id, &key_id, &private_key, &client_id)
+        })),
+        redirect_uri: config.apple_redirect_uri.clone(),
+        auth_url: "https://appleid.apple.com/auth/authorize",
+        token_url: "https://appleid.apple.com/auth/token",
+        userinfo_url: None,
+        id_token_issuer: Some(APPLE_ISSUER),
+        scopes: &["name", "email"],
+        extra_auth_params: &[("response_mode", "form_post")],
+        token_auth_type: AuthType::RequestBody,
+        map_user_info,
+    }
+}
+
+/// Generate the client secret JWT signed with the Apple private key
+fn generate_client_secret(
+    team_id: &str,
+    key_id: &str,
+    private_key: &str,
+    client_id: &str,
+) -> Result<String, String> {
+    let key = EncodingKey::from_ec_pem(private_key.as_bytes())
+        .map_err(|e| format!("Invalid Apple private key: {}", e))?;
+
+    let mut header = Header::new(Algorithm::ES256);
+    header.kid = Some(key_id.to_string());
+
+    let now = chrono::Utc::now().timestamp();
+    let claims = ClientSecretClaims {
+        iss: team_id,
+        iat: now,
+        exp: now + CLIENT_SECRET_TTL_SECS,
+        aud: APPLE_ISSUER,
+        sub: client_id,
+    };
+
+    jsonwebtoken::encode(&header, &claims, &key)
+        .map_err(|e| format!("Failed to sign Apple client secret: {}", e))
+}
+
+/// Whether the address is an Apple private relay address
+pub fn is_private_relay_email(email: &str) -> bool {
+    email
+        .rsplit_once('@')
+        .map(|(_, domain)| domain.eq_ignore_ascii_case(PRIVATE_RELAY_DOMAIN))
+        .unwrap_or(false)
+}
+
+fn map_user_info(profile: OAuthProfile) -> Result<OAuthUserInfo, String> {
+    let claims: AppleIdTokenClaims = parse_profile_section(profile.id_token_claims)?;
+
+    // 名前は初回認可時のみフォームのuserフィールドで送られる
+    let name = profile
+        .callback_user
+        .and_then(|v| serde_json::from_value::<AppleCallbackUser>(v).ok())
+        .and_then(|user| user.name)
+        .and_then(|name| {
+            let full_name = [name.first_name, name.last_name]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let full_name = full_name.trim().to_string();
+            (!full_name.is_empty()).then_some(full_name)
+        });
+
+    Ok(OAuthUserInfo {
+        oauth_id: claims.sub,
+        email: claims.email,
+        name,
+        // Appleはプロフィール画像を提供しない
+        profile_image_url: None,
+    })
+}
@@ -0,0 +1,60 @@
+//! LINE Login (v2.1) provider
+
+use oauth2::AuthType;
+use serde::Deserialize;
+
+use crate::auth::oauth::{
+    parse_profile_section, OAuthClientSecret, OAuthProfile, OAuthProviderConfig, OAuthUserInfo,
+};
+use crate::config::AppConfig;
+
+/// Response of the profile endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineProfile {
+    pub user_id: String,
+    pub display_name: Option<String>,
+    pub picture_url: Option<String>,
+}
+
+/// ID token claims (the email address is only available here)
+#[derive(Debug, Deserialize)]
+pub struct LineIdTokenClaims {
+    pub email: Option<String>,
+}
+
+pub fn provider(config: &AppConfig) -> OAuthProviderConfig {
+    OAuthProviderConfig {
+        name: "line",
+        provider_code: "LINE",
+        client_id: config.line_client_id.clone(),
+        client_secret: OAuthClientSecret::Static(config.line_client_secret.clone()),
+        redirect_uri: config.line_redirect_uri.clone(),
+        auth_url: "https://access.line.me/oauth2/v2.1/authorize",
+        token_url: "https://api.line.me/oauth2/v2.1/token",
+        userinfo_url: Some("https://api.line.me/v2/profile"),
+        id_token_issuer: Some("https://access.line.me"),
+        scopes: &["profile", "openid", "email"],
+        extra_auth_params: &[],
+        token_auth_type: AuthType::RequestBody,
+        map_user_info,
+    }
+}
+
+fn map_user_info(profile: OAuthProfile) -> Result<OAuthUserInfo, String> {
+    let line_profile: LineProfile = parse_profile_section(profile.userinfo)?;
+
+    // メールアドレスの提供を許可していないユーザーはemailクレームがない
+    let email = profile
+        .id_token_claims
+        .and_then(|v| serde_json::from_value::<LineIdTokenClaims>(v).ok())
+        .and_then(|claims| claims.email)
+        .filter(|email| !email.is_empty());
+
+    Ok(OAuthUserInfo {
+        oauth_id: line_profile.user_id,
+        email,
+        name: line_profile.display_name,
+        profile_image_url: line_profile.picture_url,
+    })
+}
@@ -0,0 +1,282 @@
+//! Password strength policy
+//!
+//! Checks new passwords (registration and password change) against configurable rules: length,
+//! number of character classes, a built-in list of common passwords and, optionally, the
+//! Have I Been Pwned breach corpus. The breach check uses the k-anonymity range API, so only the
+//! first 5 hex characters of the SHA-1 hash leave the server. Every failed rule is reported so the
+//! client can show all problems at once.
+
+use std::time::Duration;
+
+use actix_web::HttpResponse;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+use crate::config::AppConfig;
+
+/// Upper bound so hashing cost stays predictable
+const MAX_PASSWORD_LENGTH: usize = 128;
+/// Have I Been Pwned range API (takes the first 5 hex characters of the SHA-1 hash)
+const PWNED_RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+const PWNED_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Frequently used passwords, compared case-insensitively
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "1234567",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "12345678910",
+    "111111",
+    "11111111",
+    "000000",
+    "00000000",
+    "112233",
+    "121212",
+    "123123",
+    "123321",
+    "654321",
+    "666666",
+    "696969",
+    "777777",
+    "888888",
+    "987654321",
+    "password",
+    "password1",
+    "password12",
+    "password123",
+    "passw0rd",
+    "p@ssw0rd",
+    "p@ssword",
+    "qwerty",
+    "qwerty123",
+    "qwertyuiop",
+    "asdfgh",
+    "asdfghjkl",
+    "zxcvbnm",
+    "1q2w3e4r",
+    "1q2w3e4r5t",
+    "1qaz2wsx",
+    "qazwsx",
+    "abc123",
+    "abcd1234",
+    "abcdefg",
+    "abcdefgh",
+    "aa123456",
+    "a123456",
+    "iloveyou",
+    "letmein",
+    "welcome",
+    "welcome1",
+    "admin",
+    "admin123",
+    "administrator",
+    "root",
+    "login",
+    "master",
+    "monkey",
+    "dragon",
+    "sunshine",
+    "princess",
+    "football",
+    "baseball",
+    "soccer",
+    "superman",
+    "batman",
+    "shadow",
+    "trustno1",
+    "starwars",
+    "whatever",
+    "freedom",
+    "secret",
+    "changeme",
+    "default",
+    "guest",
+    "test",
+    "test123",
+    "testtest",
+    "hello",
+    "hello123",
+    "loveme",
+    "pokemon",
+    "naruto",
+    "doraemon",
+    "sakura",
+    "tokyo",
+    "japan",
+    "nippon",
+    "fithub",
+    "fithub123",
+    "fitness",
+    "workout",
+    "training",
+    "muscle",
+    "muscle123",
+    "protein",
+    "bodybuilding",
+    "gymrat",
+];
+
+/// Rules applied to new passwords
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    /// How many of lowercase / uppercase / digits / symbols must appear
+    pub required_classes: usize,
+    /// Query Have I Been Pwned for known-breached passwords
+    pub breach_check: bool,
+}
+
+/// A rule the password failed
+#[derive(Debug, Clone, Serialize)]
+pub struct PasswordViolation {
+    /// Machine-readable rule name (min_length, max_length, character_classes, common_password,
+    /// same_as_login_id, breached)
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl PasswordViolation {
+    fn new(rule: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            message: message.into(),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            min_length: config.password_min_length,
+            required_classes: config.password_required_classes,
+            breach_check: config.password_breach_check,
+        }
+    }
+
+    /// All rules the password fails (empty when it is acceptable).
+    /// The breach check only runs when the local rules pass.
+    pub async fn validate(&self, password: &str, login_id: &str) -> Vec<PasswordViolation> {
+        let violations = self.check_local(password, login_id);
+        if violations.is_empty() && self.breach_check {
+            if let Some(count) = pwned_count(password).await.filter(|count| *count > 0) {
+                tracing::info!("[PASSWORD] Rejected password found in {} breaches", count);
+                return vec![PasswordViolation::new(
+                    "breached",
+                    "このパスワードは過去のデータ漏えいで流出しています。別のパスワードを設定してください",
+                )];
+            }
+        }
+        violations
+    }
+
+    /// Rules that need no network access
+    fn check_local(&self, password: &str, login_id: &str) -> Vec<PasswordViolation> {
+        let mut violations = Vec::new();
+        let length = password.chars().count();
+
+        if length < self.min_length {
+            violations.push(PasswordViolation::new(
+                "min_length",
+                format!("パスワードは{}文字以上で入力してください", self.min_length),
+            ));
+        }
+        if length > MAX_PASSWORD_LENGTH {
+            violations.push(PasswordViolation::new(
+                "max_length",
+                format!(
+                    "パスワードは{}文字以内で入力してください",
+                    MAX_PASSWORD_LENGTH
+                ),
+            ));
+        }
+        if character_classes(password) < self.required_classes {
+            violations.push(PasswordViolation::new(
+                "character_classes",
+                format!(
+                    "英小文字・英大文字・数字・記号のうち{}種類以上を組み合わせてください",
+                    self.required_classes
+                ),
+            ));
+        }
+
+        let lowered = password.to_lowercase();
+        if COMMON_PASSWORDS.contains(&lowered.as_str()) {
+            violations.push(PasswordViolation::new(
+                "common_password",
+                "よく使われているパスワードは使用できません",
+            ));
+        }
+        if !login_id.is_empty() && lowered == login_id.trim().to_lowercase() {
+            violations.push(PasswordViolation::new(
+                "same_as_login_id",
+                "ユーザーIDと同じパスワードは使用できません",
+            ));
+        }
+        violations
+    }
+}
+
+/// 400 response listing every failed rule
+pub fn violations_response(violations: &[PasswordViolation]) -> HttpResponse {
+    let message = violations
+        .iter()
+        .map(|v| v.message.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": message,
+        "code": "WEAK_PASSWORD",
+        "violations": violations,
+    }))
+}
+
+/// Number of character classes (lowercase, uppercase, digits, symbols) in the password
+fn character_classes(password: &str) -> usize {
+    let has_lower = password.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = password.chars().any(|c| c.is_ascii_uppercase());
+    let has_digit = password.chars().any(|c| c.is_ascii_digit());
+    let has_symbol = password.chars().any(|c| !c.is_ascii_alphanumeric());
+    [has_lower, has_upper, has_digit, has_symbol]
+        .into_iter()
+        .filter(|present| *present)
+        .count()
+}
+
+/// How many times the password appears in the breach corpus.
+/// Returns None when the API cannot be reached, so an outage never blocks sign-ups.
+async fn pwned_count(password: &str) -> Option<u64> {
+    let hash: String = Sha1::digest(password.as_bytes())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect();
+    let (prefix, suffix) = hash.split_at(5);
+
+    let client = reqwest::Client::builder()
+        .timeout(PWNED_TIMEOUT)
+        .build()
+        .ok()?;
+    let body = match client
+        .get(format!("{}{}", PWNED_RANGE_URL, prefix))
+        // Padding hides the real number of matches from anyone watching the response size
+        .header("Add-Padding", "true")
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+    {
+        Ok(res) => res.text().await.ok()?,
+        Err(e) => {
+            tracing::warn!("[PASSWORD] Breach check unavailable: {}", e);
+            return None;
+        }
+    };
+
+    let count = body
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0);
+    Some(count)
+}
@@ -1,14 +1,36 @@
 //! Session management
+//!
+//! Handlers take [`Session`], which wraps the cookie session and also carries the user that
+//! a bearer token or API key authenticated for the current request only. Such users live in the
+//! request extensions and are never written to the cookie.
 
-use actix_session::Session;
+use actix_session::{Session as CookieSession, SessionExt};
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+use futures::future::{ready, Ready};
 use serde::{Deserialize, Serialize};
+use std::ops::Deref;
 
 use crate::db::models::User;
 
 const USER_SESSION_KEY: &str = "user";
 const PENDING_REGISTRATION_KEY: &str = "pending_registration";
+const PENDING_OAUTH_REGISTRATION_KEY: &str = "pending_oauth_registration";
+const SESSION_STARTED_AT_KEY: &str = "session_started_at";
+const LAST_ACTIVITY_AT_KEY: &str = "last_activity_at";
+const USER_CHECKED_AT_KEY: &str = "user_checked_at";
+const SESSION_ID_KEY: &str = "session_id";
+const OAUTH_STATE_KEY: &str = "oauth_state";
+const PENDING_REACTIVATION_KEY: &str = "pending_reactivation";
+const PENDING_TWO_FACTOR_KEY: &str = "pending_two_factor";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How long a sign-up may stay pending before it has to be started over
+pub const PENDING_REGISTRATION_TTL_SECS: i64 = 30 * 60;
+/// How long a deactivated account that just signed in may be reactivated without signing in again
+const PENDING_REACTIVATION_TTL_SECS: i64 = 10 * 60;
+/// How long a social login may wait for its second factor before it has to be started over
+const PENDING_TWO_FACTOR_TTL_SECS: i64 = 5 * 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SessionUser {
     pub id: i64,
     pub login_id: String,
@@ -37,47 +59,236 @@ impl From<User> for SessionUser {
 pub struct PendingRegistration {
     pub login_id: String,
     pub password_hash: String,
+    /// Unix seconds (registrations stored before expiry tracking count as expired)
+    #[serde(default)]
+    pub expires_at: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[allow(dead_code)]
 pub struct PendingOAuthRegistration {
     pub provider: String,
     pub oauth_id: String,
     pub email: Option<String>,
     pub name: Option<String>,
     pub profile_image_url: Option<String>,
+    /// Unix seconds (registrations stored before expiry tracking count as expired)
+    #[serde(default)]
+    pub expires_at: i64,
+}
+
+/// Deadline for a registration that is put on hold now
+pub fn pending_registration_deadline() -> i64 {
+    chrono::Utc::now().timestamp() + PENDING_REGISTRATION_TTL_SECS
+}
+
+/// Deactivated account that passed authentication and may be reactivated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingReactivation {
+    pub user_id: i64,
+    /// Unix seconds
+    pub expires_at: i64,
+}
+
+/// Social login of a two-factor account that still has to enter its code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTwoFactor {
+    pub user_id: i64,
+    pub provider: String,
+    /// Unix seconds
+    pub expires_at: i64,
+}
+
+/// OAuth `state` issued when the authorization flow started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthState {
+    pub state: String,
+    pub provider: String,
+    /// Unix seconds
+    pub issued_at: i64,
+}
+
+/// User authenticated by a bearer token or API key, kept in the request extensions
+#[derive(Debug, Clone)]
+pub struct RequestUser {
+    pub user: SessionUser,
+    /// Unix seconds of the last check against the database
+    pub checked_at: i64,
+}
+
+/// Put the user authenticated for this request only into the request extensions
+pub fn set_request_user(req: &impl HttpMessage, user: SessionUser, checked_at: i64) {
+    req.extensions_mut()
+        .insert(RequestUser { user, checked_at });
+}
+
+/// Get the user authenticated for this request only
+pub fn get_request_user(req: &impl HttpMessage) -> Option<RequestUser> {
+    req.extensions().get::<RequestUser>().cloned()
+}
+
+/// Drop the user authenticated for this request only (the handler sees an anonymous request)
+pub fn clear_request_user(req: &impl HttpMessage) {
+    req.extensions_mut().remove::<RequestUser>();
+}
+
+/// Cookie session together with the user authenticated for this request only
+#[derive(Clone)]
+pub struct Session {
+    cookie: CookieSession,
+    request_user: Option<SessionUser>,
 }
 
-/// Get current user from session
+impl Deref for Session {
+    type Target = CookieSession;
+
+    fn deref(&self) -> &CookieSession {
+        &self.cookie
+    }
+}
+
+impl FromRequest for Session {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(Ok(Self {
+            cookie: req.get_session(),
+            request_user: get_request_user(req).map(|r| r.user),
+        }))
+    }
+}
+
+/// Get current user (the request user first, then the cookie session)
 pub fn get_current_user(session: &Session) -> Result<SessionUser, crate::error::AppError> {
-    session
-        .get::<SessionUser>(USER_SESSION_KEY)
-        .ok()
-        .flatten()
+    get_current_user_opt(session)
         .ok_or_else(|| crate::error::AppError::Unauthorized("Not logged in".to_string()))
 }
 
-/// Get current user from session (optional version)
+/// Get current user (optional version)
 pub fn get_current_user_opt(session: &Session) -> Option<SessionUser> {
+    session
+        .request_user
+        .clone()
+        .or_else(|| get_session_user(session))
+}
+
+/// Get the user signed in to the cookie session (ignores the request user)
+pub fn get_session_user(session: &CookieSession) -> Option<SessionUser> {
     session.get::<SessionUser>(USER_SESSION_KEY).ok().flatten()
 }
 
-/// Set current user in session
+/// Set current user in session (also starts the session lifetime clock).
+/// The session key is rotated so a key issued before login cannot be used afterwards
+/// (session fixation). The session is registered in the session list again on the next request.
 pub fn set_current_user(
-    session: &Session,
+    session: &CookieSession,
+    user: SessionUser,
+) -> Result<(), actix_session::SessionInsertError> {
+    let now = chrono::Utc::now().timestamp();
+    session.renew();
+    session.remove(SESSION_ID_KEY);
+    session.insert(USER_SESSION_KEY, user)?;
+    session.insert(SESSION_STARTED_AT_KEY, now)?;
+    session.insert(USER_CHECKED_AT_KEY, now)?;
+    session.insert(LAST_ACTIVITY_AT_KEY, now)
+}
+
+/// Replace the session user with fresh data (keeps the session lifetime clock).
+/// Does nothing unless the cookie session is signed in as the same user.
+pub fn replace_current_user(
+    session: &CookieSession,
     user: SessionUser,
 ) -> Result<(), actix_session::SessionInsertError> {
-    session.insert(USER_SESSION_KEY, user)
+    // Requests authenticated by a bearer token or API key must not sign the cookie in
+    if get_session_user(session).is_some_and(|current| current.id == user.id) {
+        session.insert(USER_SESSION_KEY, user)?;
+    }
+    Ok(())
+}
+
+/// When the session user was last revalidated against the database (unix seconds)
+pub fn get_user_checked_at(session: &CookieSession) -> Option<i64> {
+    session.get::<i64>(USER_CHECKED_AT_KEY).ok().flatten()
+}
+
+/// Record that the session user has been revalidated
+pub fn mark_user_checked(
+    session: &CookieSession,
+    now: i64,
+) -> Result<(), actix_session::SessionInsertError> {
+    session.insert(USER_CHECKED_AT_KEY, now)
+}
+
+/// ID of this session in the server-side session list (`user_sessions.session_id`)
+pub fn get_session_id(session: &CookieSession) -> Option<String> {
+    session.get::<String>(SESSION_ID_KEY).ok().flatten()
+}
+
+/// Record the server-side session ID
+pub fn set_session_id(
+    session: &CookieSession,
+    session_id: &str,
+) -> Result<(), actix_session::SessionInsertError> {
+    session.insert(SESSION_ID_KEY, session_id)
+}
+
+/// Session activity timestamps (unix seconds)
+#[derive(Debug, Clone, Copy)]
+pub struct SessionActivity {
+    pub started_at: i64,
+    pub last_activity_at: i64,
+}
+
+/// Get session activity timestamps
+pub fn get_session_activity(session: &CookieSession) -> Option<SessionActivity> {
+    let started_at = session.get::<i64>(SESSION_STARTED_AT_KEY).ok().flatten()?;
+    let last_activity_at = session
+        .get::<i64>(LAST_ACTIVITY_AT_KEY)
+        .ok()
+        .flatten()
+        .unwrap_or(started_at);
+    Some(SessionActivity {
+        started_at,
+        last_activity_at,
+    })
+}
+
+/// Record user activity (starts the lifetime clock for sessions created before tracking existed)
+pub fn touch_session_activity(
+    session: &CookieSession,
+    now: i64,
+) -> Result<(), actix_session::SessionInsertError> {
+    if session
+        .get::<i64>(SESSION_STARTED_AT_KEY)
+        .ok()
+        .flatten()
+        .is_none()
+    {
+        session.insert(SESSION_STARTED_AT_KEY, now)?;
+    }
+    session.insert(LAST_ACTIVITY_AT_KEY, now)
 }
 
 /// Clear current user from session (logout)
-pub fn clear_current_user(session: &Session) {
+pub fn clear_current_user(session: &CookieSession) {
     session.remove(USER_SESSION_KEY);
 }
 
+/// Store the OAuth state for the callback (replaces any flow started earlier)
+pub fn set_oauth_state(
+    session: &CookieSession,
+    state: OAuthState,
+) -> Result<(), actix_session::SessionInsertError> {
+    session.insert(OAUTH_STATE_KEY, state)
+}
+
+/// Take the OAuth state out of the session (each state can be used only once)
+pub fn take_oauth_state(session: &CookieSession) -> Option<OAuthState> {
+    session.remove_as::<OAuthState>(OAUTH_STATE_KEY)?.ok()
+}
+
 /// Get pending registration from session
-pub fn get_pending_registration(session: &Session) -> Option<PendingRegistration> {
+pub fn get_pending_registration(session: &CookieSession) -> Option<PendingRegistration> {
     session
         .get::<PendingRegistration>(PENDING_REGISTRATION_KEY)
         .ok()
@@ -86,13 +297,102 @@ pub fn get_pending_registration(session: &Session) -> Option<PendingRegistration
 
 /// Set pending registration in session
 pub fn set_pending_registration(
-    session: &Session,
+    session: &CookieSession,
     pending: PendingRegistration,
 ) -> Result<(), actix_session::SessionInsertError> {
     session.insert(PENDING_REGISTRATION_KEY, pending)
 }
 
 /// Clear pending registration from session
-pub fn clear_pending_registration(session: &Session) {
+pub fn clear_pending_registration(session: &CookieSession) {
     session.remove(PENDING_REGISTRATION_KEY);
 }
+
+/// Drop pending registrations whose deadline has passed.
+/// Returns true if anything was discarded, so callers can tell "expired" from "never started".
+pub fn discard_expired_pending_registrations(session: &CookieSession) -> bool {
+    let now = chrono::Utc::now().timestamp();
+    let mut discarded = false;
+    if get_pending_registration(session).is_some_and(|p| p.expires_at <= now) {
+        clear_pending_registration(session);
+        discarded = true;
+    }
+    if get_pending_oauth_registration(session).is_some_and(|p| p.expires_at <= now) {
+        clear_pending_oauth_registration(session);
+        discarded = true;
+    }
+    discarded
+}
+
+/// Get pending OAuth registration from session
+pub fn get_pending_oauth_registration(session: &CookieSession) -> Option<PendingOAuthRegistration> {
+    session
+        .get::<PendingOAuthRegistration>(PENDING_OAUTH_REGISTRATION_KEY)
+        .ok()
+        .flatten()
+}
+
+/// Set pending OAuth registration in session
+pub fn set_pending_oauth_registration(
+    session: &CookieSession,
+    pending: PendingOAuthRegistration,
+) -> Result<(), actix_session::SessionInsertError> {
+    session.insert(PENDING_OAUTH_REGISTRATION_KEY, pending)
+}
+
+/// Clear pending OAuth registration from session
+pub fn clear_pending_oauth_registration(session: &CookieSession) {
+    session.remove(PENDING_OAUTH_REGISTRATION_KEY);
+}
+
+/// Remember a deactivated account that passed authentication (replaces any earlier one)
+pub fn set_pending_reactivation(
+    session: &CookieSession,
+    user_id: i64,
+) -> Result<(), actix_session::SessionInsertError> {
+    session.insert(
+        PENDING_REACTIVATION_KEY,
+        PendingReactivation {
+            user_id,
+            expires_at: chrono::Utc::now().timestamp() + PENDING_REACTIVATION_TTL_SECS,
+        },
+    )
+}
+
+/// Remember a social login that is waiting for its second factor (replaces any earlier one)
+pub fn set_pending_two_factor(
+    session: &CookieSession,
+    user_id: i64,
+    provider: &str,
+) -> Result<(), actix_session::SessionInsertError> {
+    session.insert(
+        PENDING_TWO_FACTOR_KEY,
+        PendingTwoFactor {
+            user_id,
+            provider: provider.to_string(),
+            expires_at: chrono::Utc::now().timestamp() + PENDING_TWO_FACTOR_TTL_SECS,
+        },
+    )
+}
+
+/// Get the social login waiting for its second factor (None once expired)
+pub fn get_pending_two_factor(session: &CookieSession) -> Option<PendingTwoFactor> {
+    session
+        .get::<PendingTwoFactor>(PENDING_TWO_FACTOR_KEY)
+        .ok()
+        .flatten()
+        .filter(|p| p.expires_at > chrono::Utc::now().timestamp())
+}
+
+/// Clear the social login waiting for its second factor
+pub fn clear_pending_two_factor(session: &CookieSession) {
+    session.remove(PENDING_TWO_FACTOR_KEY);
+}
+
+/// Take the account waiting for reactivation out of the session (None once expired)
+pub fn take_pending_reactivation(session: &CookieSession) -> Option<i64> {
+    let pending = session
+        .remove_as::<PendingReactivation>(PENDING_REACTIVATION_KEY)?
+        .ok()?;
+    (pending.expires_at > chrono::Utc::now().timestamp()).then_some(pending.user_id)
+}
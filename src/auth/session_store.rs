@@ -0,0 +1,120 @@
+//! Session storage backend
+//!
+//! By default the whole session state is kept in the signed cookie. With `SESSION_STORE=redis`
+//! the state is stored in Redis and the cookie only carries a random session key, so large
+//! payloads stay server-side. If Redis is not reachable at startup, cookies are used instead
+//! (local development without Redis keeps working).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use actix_session::storage::{
+    CookieSessionStore, LoadError, RedisSessionStore, SaveError, SessionKey, SessionStore,
+    UpdateError,
+};
+use actix_web::cookie::time::Duration as CookieDuration;
+
+use crate::config::AppConfig;
+
+/// Redis key prefix for session state (shares the namespace of the shared store)
+const SESSION_KEY_PREFIX: &str = "fithub:session:";
+/// Startup connection timeout
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Session state as passed around by actix-session
+type SessionState = HashMap<String, String>;
+
+/// Cookie or Redis session store, chosen at startup
+#[derive(Clone)]
+pub enum AppSessionStore {
+    Cookie,
+    Redis(Box<RedisSessionStore>),
+}
+
+impl AppSessionStore {
+    /// Build the store selected by the config, falling back to cookies
+    pub async fn from_config(config: &AppConfig) -> Self {
+        if config.session_store != "redis" {
+            return Self::Cookie;
+        }
+        if config.redis_url.is_empty() {
+            tracing::warn!("SESSION_STORE=redis but REDIS_URL is empty, using cookie sessions");
+            return Self::Cookie;
+        }
+
+        let builder = RedisSessionStore::builder(config.redis_url.clone())
+            .cache_keygen(|key| format!("{}{}", SESSION_KEY_PREFIX, key));
+        match tokio::time::timeout(CONNECT_TIMEOUT, builder.build()).await {
+            Ok(Ok(store)) => {
+                tracing::info!("Session store: Redis");
+                Self::Redis(Box::new(store))
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Failed to connect to Redis, using cookie sessions: {}", e);
+                Self::Cookie
+            }
+            Err(_) => {
+                tracing::warn!("Timed out connecting to Redis, using cookie sessions");
+                Self::Cookie
+            }
+        }
+    }
+}
+
+impl SessionStore for AppSessionStore {
+    async fn load(&self, session_key: &SessionKey) -> Result<Option<SessionState>, LoadError> {
+        match self {
+            Self::Cookie => CookieSessionStore::default().load(session_key).await,
+            Self::Redis(store) => store.load(session_key).await,
+        }
+    }
+
+    async fn save(
+        &self,
+        session_state: SessionState,
+        ttl: &CookieDuration,
+    ) -> Result<SessionKey, SaveError> {
+        match self {
+            Self::Cookie => CookieSessionStore::default().save(session_state, ttl).await,
+            Self::Redis(store) => store.save(session_state, ttl).await,
+        }
+    }
+
+    async fn update(
+        &self,
+        session_key: SessionKey,
+        session_state: SessionState,
+        ttl: &CookieDuration,
+    ) -> Result<SessionKey, UpdateError> {
+        match self {
+            Self::Cookie => {
+                CookieSessionStore::default()
+                    .update(session_key, session_state, ttl)
+                    .await
+            }
+            Self::Redis(store) => store.update(session_key, session_state, ttl).await,
+        }
+    }
+
+    async fn update_ttl(
+        &self,
+        session_key: &SessionKey,
+        ttl: &CookieDuration,
+    ) -> Result<(), anyhow::Error> {
+        match self {
+            Self::Cookie => {
+                CookieSessionStore::default()
+                    .update_ttl(session_key, ttl)
+                    .await
+            }
+            Self::Redis(store) => store.update_ttl(session_key, ttl).await,
+        }
+    }
+
+    async fn delete(&self, session_key: &SessionKey) -> Result<(), anyhow::Error> {
+        match self {
+            Self::Cookie => CookieSessionStore::default().delete(session_key).await,
+            Self::Redis(store) => store.delete(session_key).await,
+        }
+    }
+}
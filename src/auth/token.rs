@@ -0,0 +1,107 @@
+//! Bearer token (JWT) management
+//!
+//! Mobile clients that cannot keep cookie sessions authenticate with a short-lived access token
+//! and exchange a long-lived refresh token for a new pair when it expires.
+//! Refresh tokens are single-use: each exchange rotates them (see `api::auth_token`).
+
+use chrono::Utc;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::auth::session::SessionUser;
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+pub const TOKEN_TYPE_ACCESS: &str = "access";
+pub const TOKEN_TYPE_REFRESH: &str = "refresh";
+
+/// JWT claims
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenClaims {
+    /// User ID
+    pub sub: i64,
+    /// "access" or "refresh"
+    pub typ: String,
+    pub iat: i64,
+    pub exp: i64,
+    /// Unique token ID
+    pub jti: String,
+    /// User snapshot at issue time (access tokens only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<SessionUser>,
+}
+
+/// Issued access/refresh token pair
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: &'static str,
+    /// Access token lifetime in seconds
+    pub expires_in: i64,
+    /// Refresh token ID, recorded server-side for rotation
+    #[serde(skip)]
+    pub refresh_jti: String,
+}
+
+fn encode(config: &AppConfig, claims: &TokenClaims) -> Result<String, AppError> {
+    jsonwebtoken::encode(
+        &Header::new(Algorithm::HS256),
+        claims,
+        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    )
+    .map_err(|e| AppError::InternalError(format!("Failed to sign token: {}", e)))
+}
+
+/// Issue an access token and a refresh token for the user
+pub fn issue_token_pair(config: &AppConfig, user: &SessionUser) -> Result<TokenPair, AppError> {
+    let now = Utc::now().timestamp();
+    let access_ttl = config.jwt_access_ttl_minutes * 60;
+    let refresh_ttl = config.jwt_refresh_ttl_days * 24 * 3600;
+
+    let access = TokenClaims {
+        sub: user.id,
+        typ: TOKEN_TYPE_ACCESS.to_string(),
+        iat: now,
+        exp: now + access_ttl,
+        jti: uuid::Uuid::new_v4().to_string(),
+        user: Some(user.clone()),
+    };
+    let refresh = TokenClaims {
+        sub: user.id,
+        typ: TOKEN_TYPE_REFRESH.to_string(),
+        iat: now,
+        exp: now + refresh_ttl,
+        jti: uuid::Uuid::new_v4().to_string(),
+        user: None,
+    };
+
+    Ok(TokenPair {
+        access_token: encode(config, &access)?,
+        refresh_token: encode(config, &refresh)?,
+        token_type: "Bearer",
+        expires_in: access_ttl,
+        refresh_jti: refresh.jti,
+    })
+}
+
+/// Verify the signature, expiry and type of a token
+pub fn decode_token(
+    config: &AppConfig,
+    token: &str,
+    expected_type: &str,
+) -> Result<TokenClaims, AppError> {
+    let claims = jsonwebtoken::decode::<TokenClaims>(
+        token,
+        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+        &Validation::new(Algorithm::HS256),
+    )
+    .map_err(|_| AppError::Unauthorized("Invalid or expired token".to_string()))?
+    .claims;
+
+    if claims.typ != expected_type {
+        return Err(AppError::Unauthorized("Invalid token type".to_string()));
+    }
+    Ok(claims)
+}
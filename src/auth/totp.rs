@@ -0,0 +1,188 @@
+//! TOTP two-factor authentication (RFC 6238)
+//!
+//! Secrets are 160-bit random keys shared with the authenticator app as base32 via an
+//! `otpauth://` URI. Codes are 6-digit HMAC-SHA1 over 30-second steps.
+//! Backup codes are single-use and only their SHA-256 hashes are stored.
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Seconds per TOTP step
+const TOTP_STEP_SECONDS: i64 = 30;
+/// Number of digits in a TOTP code
+const TOTP_DIGITS: u32 = 6;
+/// Accepted clock drift in steps (before and after the current step)
+const TOTP_ALLOWED_DRIFT: i64 = 1;
+/// Secret length in bytes
+const SECRET_BYTES: usize = 20;
+
+/// Number of backup codes issued at a time
+pub const BACKUP_CODE_COUNT: usize = 10;
+/// Characters used in backup codes (ambiguous characters such as 0/o and 1/l are excluded)
+const BACKUP_CODE_CHARSET: &[u8] = b"23456789abcdefghjkmnpqrstuvwxyz";
+
+/// Generate a new random secret, base32-encoded without padding
+pub fn generate_secret() -> String {
+    let bytes: [u8; SECRET_BYTES] = rand::thread_rng().gen();
+    base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &bytes)
+}
+
+/// `otpauth://` URI to register the secret in an authenticator app (usually shown as a QR code)
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let label = format!("{}:{}", issuer, account);
+    format!(
+        "otpauth://totp/{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(&label),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_STEP_SECONDS
+    )
+}
+
+/// Verify a code against the secret at the current time.
+/// Returns the matched step so callers can reject a code that was already used.
+pub fn verify_code(secret: &str, code: &str) -> Option<i64> {
+    verify_code_at(secret, code, Utc::now().timestamp())
+}
+
+/// Verify a code against the secret at the given Unix time
+fn verify_code_at(secret: &str, code: &str, now: i64) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32::decode(base32::Alphabet::Rfc4648 { padding: false }, secret)?;
+    let current_step = now / TOTP_STEP_SECONDS;
+
+    (current_step - TOTP_ALLOWED_DRIFT..=current_step + TOTP_ALLOWED_DRIFT)
+        .find(|&step| generate_code(&key, step as u64) == code)
+}
+
+/// HOTP value for a step, zero-padded to the code length
+fn generate_code(key: &[u8], step: u64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    // Dynamic truncation (RFC 4226 section 5.3)
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    )
+}
+
+/// Generate a set of backup codes formatted as `xxxxx-xxxxx`
+pub fn generate_backup_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..BACKUP_CODE_COUNT)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| BACKUP_CODE_CHARSET[rng.gen_range(0..BACKUP_CODE_CHARSET.len())] as char)
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// Hash a backup code for storage (case and separators are ignored)
+pub fn hash_backup_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+/// Percent-encode a URI component (unreserved characters are kept as-is)
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RFC 6238 Appendix B SHA-1 key ("12345678901234567890")
+    const RFC_KEY: &[u8] = b"12345678901234567890";
+    /// `RFC_KEY` in base32, as stored in `user_two_factor.secret`
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    /// RFC 6238 Appendix B SHA-1 vectors: (Unix time, last 6 digits of the 8-digit TOTP)
+    const RFC_VECTORS: &[(i64, &str)] = &[
+        (59, "287082"),
+        (1111111109, "081804"),
+        (1111111111, "050471"),
+        (1234567890, "005924"),
+        (2000000000, "279037"),
+        (20000000000, "353130"),
+    ];
+
+    #[test]
+    fn generate_code_matches_rfc6238_vectors() {
+        for &(time, expected) in RFC_VECTORS {
+            let step = (time / TOTP_STEP_SECONDS) as u64;
+            assert_eq!(generate_code(RFC_KEY, step), expected, "time {}", time);
+        }
+    }
+
+    #[test]
+    fn verify_code_accepts_rfc6238_vectors() {
+        for &(time, code) in RFC_VECTORS {
+            assert_eq!(
+                verify_code_at(RFC_SECRET, code, time),
+                Some(time / TOTP_STEP_SECONDS),
+                "time {}",
+                time
+            );
+        }
+    }
+
+    #[test]
+    fn verify_code_accepts_one_step_of_drift() {
+        // 081804 belongs to step 37037036 (1111111080..=1111111109)
+        let step = 37037036;
+        assert_eq!(verify_code_at(RFC_SECRET, "081804", 1111111080), Some(step));
+        assert_eq!(verify_code_at(RFC_SECRET, "081804", 1111111109), Some(step));
+        // First and last second of the previous and next steps
+        assert_eq!(verify_code_at(RFC_SECRET, "081804", 1111111050), Some(step));
+        assert_eq!(verify_code_at(RFC_SECRET, "081804", 1111111110), Some(step));
+        assert_eq!(verify_code_at(RFC_SECRET, "081804", 1111111139), Some(step));
+    }
+
+    #[test]
+    fn verify_code_rejects_codes_outside_the_window() {
+        // Last second two steps before and first second two steps after step 37037036
+        assert_eq!(verify_code_at(RFC_SECRET, "081804", 1111111049), None);
+        assert_eq!(verify_code_at(RFC_SECRET, "081804", 1111111140), None);
+    }
+
+    #[test]
+    fn verify_code_rejects_malformed_codes() {
+        assert_eq!(verify_code_at(RFC_SECRET, " 081804 ", 1111111109), Some(37037036));
+        assert_eq!(verify_code_at(RFC_SECRET, "81804", 1111111109), None);
+        assert_eq!(verify_code_at(RFC_SECRET, "94287082", 59), None);
+        assert_eq!(verify_code_at(RFC_SECRET, "08180a", 1111111109), None);
+        assert_eq!(verify_code_at("not base32!", "081804", 1111111109), None);
+    }
+}
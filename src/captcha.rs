@@ -0,0 +1,106 @@
+//! CAPTCHA検証
+//!
+//! 登録・お問い合わせでボットによる送信を防ぐ。方式はCAPTCHA_PROVIDERで切り替える。
+//! - recaptcha: Google reCAPTCHA（v2 / v3。v3はスコアがCAPTCHA_MIN_SCORE未満なら拒否）
+//! - hcaptcha: hCaptcha
+//!
+//! CAPTCHA_SECRETが未設定の場合は検証しない（開発環境など）。
+
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::AppConfig;
+
+const RECAPTCHA_VERIFY_URL: &str = "https://www.google.com/recaptcha/api/siteverify";
+const HCAPTCHA_VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// siteverifyの応答（reCAPTCHA・hCaptcha共通）
+#[derive(Deserialize)]
+struct VerifyResponse {
+    success: bool,
+    /// reCAPTCHA v3のみ
+    score: Option<f64>,
+    #[serde(rename = "error-codes", default)]
+    error_codes: Vec<String>,
+}
+
+/// CAPTCHA検証クライアント
+pub enum Captcha {
+    Disabled,
+    Enabled {
+        client: reqwest::Client,
+        verify_url: &'static str,
+        secret: String,
+        min_score: f64,
+    },
+}
+
+impl Captcha {
+    pub fn from_config(config: &AppConfig) -> Self {
+        if config.captcha_secret.is_empty() {
+            return Captcha::Disabled;
+        }
+        let verify_url = match config.captcha_provider.as_str() {
+            "hcaptcha" => HCAPTCHA_VERIFY_URL,
+            "recaptcha" => RECAPTCHA_VERIFY_URL,
+            other => {
+                tracing::warn!("Unknown CAPTCHA_PROVIDER={}; CAPTCHA is disabled", other);
+                return Captcha::Disabled;
+            }
+        };
+        Captcha::Enabled {
+            client: reqwest::Client::builder()
+                .timeout(VERIFY_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            verify_url,
+            secret: config.captcha_secret.clone(),
+            min_score: config.captcha_min_score,
+        }
+    }
+
+    /// トークンを検証する（無効時は常にtrue）
+    /// 検証サーバーに接続できない場合はボットを通さないようfalseとする
+    pub async fn verify(&self, token: Option<&str>, remote_ip: Option<&str>) -> bool {
+        let Captcha::Enabled {
+            client,
+            verify_url,
+            secret,
+            min_score,
+        } = self
+        else {
+            return true;
+        };
+        let Some(token) = token.map(str::trim).filter(|t| !t.is_empty()) else {
+            return false;
+        };
+
+        let mut form = vec![("secret", secret.as_str()), ("response", token)];
+        if let Some(ip) = remote_ip {
+            form.push(("remoteip", ip));
+        }
+        let response = match client.post(*verify_url).form(&form).send().await {
+            Ok(res) => res.json::<VerifyResponse>().await,
+            Err(e) => Err(e),
+        };
+        match response {
+            Ok(res) if !res.success => {
+                tracing::info!("[CAPTCHA] Rejected: {:?}", res.error_codes);
+                false
+            }
+            Ok(res) => match res.score {
+                Some(score) if score < *min_score => {
+                    tracing::info!("[CAPTCHA] Rejected low score {}", score);
+                    false
+                }
+                _ => true,
+            },
+            Err(e) => {
+                tracing::warn!("[CAPTCHA] Verification failed: {}", e);
+                false
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
 //! Application configuration
 
+use serde::{Deserialize, Serialize};
 use std::env;
 
 /// EXP system configuration
-/// Change these values to adjust the EXP system behavior
-#[derive(Debug, Clone)]
+/// The values are stored in the exp_config table and can be changed by admins (see exp_config.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ExpConfig {
     /// Daily EXP limit for current/recent records
     pub daily_limit: i32,
@@ -22,18 +24,22 @@ pub struct ExpConfig {
 
 impl Default for ExpConfig {
     fn default() -> Self {
-        Self {
-            daily_limit: 50000, // 1日上限 50,000 EXP
-            past_days_threshold: 2,
-            past_exp_multiplier: 0.25,
-            past_limit_multiplier: 0.5,
-            max_exp_per_set: 2000, // 1セット上限 2,000 EXP
-            exp_coefficient: 1.0,  // 係数 0.01 → 1.0
-        }
+        Self::DEFAULT
     }
 }
 
 impl ExpConfig {
+    /// Initial values (used until exp_config is loaded from the database)
+    pub const DEFAULT: ExpConfig = ExpConfig {
+        daily_limit: 50000, // 1日上限 50,000 EXP
+        past_days_threshold: 2,
+        past_exp_multiplier: 0.25,
+        past_limit_multiplier: 0.5,
+        max_exp_per_set: 2000, // 1セット上限 2,000 EXP
+        exp_coefficient: 1.0,  // 係数 0.01 → 1.0
+    };
+
+
     /// Get the daily limit based on whether the record is a past record
     pub fn get_daily_limit(&self, is_past_record: bool) -> i32 {
         if is_past_record {
@@ -53,6 +59,60 @@ impl ExpConfig {
     }
 }
 
+/// Pet EXP curve configuration
+/// Pets grow faster than the user at low levels and slower at high levels
+/// Required total EXP for level L: base_exp × (L - 1) ^ exponent
+/// The values are stored in the pet_exp_config table and can be changed by admins (see pet_exp_config.rs)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PetExpConfig {
+    /// EXP scale of the curve
+    pub base_exp: f64,
+    /// Curve steepness (higher = faster early, slower later)
+    pub exponent: f64,
+}
+
+impl Default for PetExpConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl PetExpConfig {
+    /// Initial values (used until pet_exp_config is loaded from the database)
+    /// Lv2: 16, Lv10: 2,505, Lv31: 39,950, Lv51: 129,360
+    pub const DEFAULT: PetExpConfig = PetExpConfig {
+        base_exp: 16.0,
+        exponent: 2.3,
+    };
+
+    /// Total EXP required to reach the level
+    pub fn required_exp(&self, level: i32) -> i64 {
+        if level <= 1 {
+            return 0;
+        }
+        (self.base_exp * ((level - 1) as f64).powf(self.exponent)).round() as i64
+    }
+
+    /// Level for the total EXP (binary search, the curve is monotonically increasing)
+    pub fn level_for_exp(&self, total_exp: i64) -> i32 {
+        if total_exp <= 0 {
+            return 1;
+        }
+        let mut low = 1;
+        let mut high = crate::level_curve::MAX_LEVEL;
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            if self.required_exp(mid) <= total_exp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct AppConfig {
@@ -70,12 +130,76 @@ pub struct AppConfig {
     pub microsoft_client_id: String,
     pub microsoft_client_secret: String,
     pub microsoft_redirect_uri: String,
+    /// Sign in with AppleのServices ID
+    pub apple_client_id: String,
+    pub apple_team_id: String,
+    pub apple_key_id: String,
+    /// Appleから発行された秘密鍵（.p8のPEM文字列）
+    pub apple_private_key: String,
+    pub apple_redirect_uri: String,
+    /// LINEログインのチャネルID
+    pub line_client_id: String,
+    pub line_client_secret: String,
+    pub line_redirect_uri: String,
     pub frontend_url: String,
     pub discord_webhook_url: String,
+    /// メール送信方式（"log": ログ出力のみ / "http": HTTPメールAPI）
+    pub mail_provider: String,
+    /// HTTPメールAPIのエンドポイント（JSONで from/to/subject/text をPOSTする）
+    pub mail_api_url: String,
+    pub mail_api_key: String,
+    pub mail_from: String,
+    /// メール本文などに載せる絶対URLのベース
+    pub app_base_url: String,
+    /// 記録日からこの日数を過ぎたトレーニング記録は変更不可（0で無効）
+    pub record_lock_days: i64,
+    /// 複数インスタンスで共有するRedis（空の場合はプロセス内ストア）
+    pub redis_url: String,
+    /// セッションの保存先（"cookie" / "redis"）。redisの場合はREDIS_URLに保存し、接続できなければcookie
+    pub session_store: String,
+    /// ログ出力形式（"text" / "json"）
+    pub log_format: String,
+    /// 無操作でセッションが切れるまでの時間（分）。操作のたびに延長される
+    pub session_idle_timeout_minutes: i64,
+    /// ログインからの最大セッション有効期間（時間）。操作があっても延長されない
+    pub session_max_lifetime_hours: i64,
+    /// セッションのユーザー情報（ロール・表示名など）をDBと照合する間隔（分）
+    pub session_revalidate_minutes: i64,
+    /// Bearerトークン（JWT）の署名鍵（未設定の場合はSESSION_SECRET）
+    pub jwt_secret: String,
+    /// アクセストークンの有効期間（分）
+    pub jwt_access_ttl_minutes: i64,
+    /// リフレッシュトークンの有効期間（日）
+    pub jwt_refresh_ttl_days: i64,
+    /// パスワードの最小文字数
+    pub password_min_length: usize,
+    /// パスワードに含める文字種（英小文字・英大文字・数字・記号）の最小数
+    pub password_required_classes: usize,
+    /// Have I Been Pwnedで流出済みのパスワードを拒否する
+    pub password_breach_check: bool,
+    /// CAPTCHAの種類（"recaptcha" / "hcaptcha"）
+    pub captcha_provider: String,
+    /// フロントエンドのウィジェットに渡すサイトキー
+    pub captcha_site_key: String,
+    /// 検証用のシークレット（空の場合はCAPTCHAを使わない）
+    pub captcha_secret: String,
+    /// reCAPTCHA v3でこのスコア未満を拒否する（0.0〜1.0）
+    pub captcha_min_score: f64,
+    /// アップロードファイルの保存先（"local" / "s3"）
+    pub storage_backend: String,
+    /// ローカル保存時の保存ディレクトリ
+    pub storage_local_dir: String,
+    /// 公開URLの基準（CDN等。空の場合は保存先から決める）
+    pub storage_public_url: String,
+    /// S3保存時のバケット名
+    pub s3_bucket: String,
 }
 
 impl AppConfig {
     pub fn from_env() -> Self {
+        let session_secret = env::var("SESSION_SECRET").unwrap_or_else(|_| {
+            "default-secret-key-change-in-production-64-chars-minimum".to_string()
+        });
         Self {
             host: env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
             port: env::var("PORT")
@@ -83,9 +207,6 @@ impl AppConfig {
                 .parse()
                 .unwrap_or(5000),
             database_url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
-            session_secret: env::var("SESSION_SECRET").unwrap_or_else(|_| {
-                "default-secret-key-change-in-production-64-chars-minimum".to_string()
-            }),
             google_maps_api_key: env::var("GOOGLE_MAPS_API_KEY")
                 .or_else(|_| env::var("VITE_GOOGLE_MAPS_API_KEY"))
                 .unwrap_or_default(),
@@ -101,8 +222,101 @@ impl AppConfig {
             microsoft_client_secret: env::var("MICROSOFT_CLIENT_SECRET").unwrap_or_default(),
             microsoft_redirect_uri: env::var("MICROSOFT_REDIRECT_URI")
                 .unwrap_or_else(|_| "https://fithub.jp/login/oauth2/code/microsoft".to_string()),
+            apple_client_id: env::var("APPLE_CLIENT_ID").unwrap_or_default(),
+            apple_team_id: env::var("APPLE_TEAM_ID").unwrap_or_default(),
+            apple_key_id: env::var("APPLE_KEY_ID").unwrap_or_default(),
+            // 環境変数では改行を\nで渡せるようにする
+            apple_private_key: env::var("APPLE_PRIVATE_KEY")
+                .map(|v| v.replace("\\n", "\n"))
+                .unwrap_or_default(),
+            apple_redirect_uri: env::var("APPLE_REDIRECT_URI")
+                .unwrap_or_else(|_| "https://fithub.jp/login/oauth2/code/apple".to_string()),
+            line_client_id: env::var("LINE_CLIENT_ID").unwrap_or_default(),
+            line_client_secret: env::var("LINE_CLIENT_SECRET").unwrap_or_default(),
+            line_redirect_uri: env::var("LINE_REDIRECT_URI")
+                .unwrap_or_else(|_| "https://fithub.jp/login/oauth2/code/line".to_string()),
             frontend_url: env::var("FRONTEND_URL").unwrap_or_default(),
             discord_webhook_url: env::var("DISCORD_WEBHOOK_URL").unwrap_or_default(),
+            mail_provider: env::var("MAIL_PROVIDER").unwrap_or_else(|_| "log".to_string()),
+            mail_api_url: env::var("MAIL_API_URL").unwrap_or_default(),
+            mail_api_key: env::var("MAIL_API_KEY").unwrap_or_default(),
+            mail_from: env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "Fithub <noreply@fithub.jp>".to_string()),
+            app_base_url: env::var("APP_BASE_URL")
+                .map(|v| v.trim_end_matches('/').to_string())
+                .unwrap_or_else(|_| "https://fithub.jp".to_string()),
+            record_lock_days: env::var("RECORD_LOCK_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v >= 0)
+                .unwrap_or(30),
+            redis_url: env::var("REDIS_URL").unwrap_or_default(),
+            session_store: env::var("SESSION_STORE")
+                .map(|v| v.to_lowercase())
+                .unwrap_or_else(|_| "cookie".to_string()),
+            log_format: env::var("LOG_FORMAT")
+                .map(|v| v.to_lowercase())
+                .unwrap_or_else(|_| "text".to_string()),
+            session_idle_timeout_minutes: env::var("SESSION_IDLE_TIMEOUT_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(24 * 60),
+            session_max_lifetime_hours: env::var("SESSION_MAX_LIFETIME_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(24 * 7),
+            session_revalidate_minutes: env::var("SESSION_REVALIDATE_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(5),
+            jwt_secret: env::var("JWT_SECRET")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| session_secret.clone()),
+            jwt_access_ttl_minutes: env::var("JWT_ACCESS_TTL_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(15),
+            jwt_refresh_ttl_days: env::var("JWT_REFRESH_TTL_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(30),
+            password_min_length: env::var("PASSWORD_MIN_LENGTH")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v > 0)
+                .unwrap_or(8),
+            password_required_classes: env::var("PASSWORD_REQUIRED_CLASSES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| *v <= 4)
+                .unwrap_or(2),
+            password_breach_check: env::var("PASSWORD_BREACH_CHECK")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            captcha_provider: env::var("CAPTCHA_PROVIDER")
+                .map(|v| v.to_lowercase())
+                .unwrap_or_else(|_| "recaptcha".to_string()),
+            captcha_site_key: env::var("CAPTCHA_SITE_KEY").unwrap_or_default(),
+            captcha_secret: env::var("CAPTCHA_SECRET").unwrap_or_default(),
+            captcha_min_score: env::var("CAPTCHA_MIN_SCORE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|v| (0.0..=1.0).contains(v))
+                .unwrap_or(0.5),
+            storage_backend: env::var("STORAGE_BACKEND")
+                .map(|v| v.to_lowercase())
+                .unwrap_or_else(|_| "local".to_string()),
+            storage_local_dir: env::var("STORAGE_LOCAL_DIR")
+                .unwrap_or_else(|_| "./uploads".to_string()),
+            storage_public_url: env::var("STORAGE_PUBLIC_URL").unwrap_or_default(),
+            s3_bucket: env::var("S3_BUCKET").unwrap_or_default(),
+            session_secret,
         }
     }
 }
@@ -7,6 +7,9 @@ use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::level_curve;
+use crate::pet_exp_config;
+
 // ============================================
 // ユーザーと統計
 // ============================================
@@ -38,41 +41,21 @@ pub struct UserStats {
 
 impl UserStats {
     /// 指定レベルに必要な累計EXPを計算
-    /// 新計算式（2週間スピードラン用）: 40 × Level² + 100 × Level - 140
-    /// Lv1: 0, Lv2: 220, Lv5: 1360, Lv10: 4860, Lv50: 104860, Lv100: 409860
+    /// 計算式は有効なレベル曲線による（初期値: 40 × Level² + 100 × Level - 140）
     pub fn get_required_exp_for_level(level: i32) -> i64 {
-        if level <= 1 {
-            return 0;
-        }
-        let l = level as i64;
-        40 * l * l + 100 * l - 140
+        level_curve::current().required_exp(level)
     }
 
     /// 現在レベルから次レベルに必要なEXP
-    /// 微分: 80 × Level + 140
     pub fn get_exp_to_next_level(level: i32) -> i32 {
         let next = Self::get_required_exp_for_level(level + 1);
         let current = Self::get_required_exp_for_level(level);
         (next - current) as i32
     }
 
-    /// Calculate level from total EXP using binary search for efficiency
+    /// 累計EXPからレベルを計算（有効なレベル曲線で二分探索）
     pub fn calculate_level(total_exp: i64) -> i32 {
-        if total_exp <= 0 {
-            return 1;
-        }
-        // Binary search for level (1 to 1000)
-        let mut low = 1;
-        let mut high = 1000;
-        while low < high {
-            let mid = (low + high + 1) / 2;
-            if Self::get_required_exp_for_level(mid) <= total_exp {
-                low = mid;
-            } else {
-                high = mid - 1;
-            }
-        }
-        low
+        level_curve::current().level_for_exp(total_exp)
     }
 
     /// 現在レベル内の進行度（0.0〜1.0）
@@ -104,6 +87,8 @@ pub struct Exercise {
     pub target_muscles: Option<String>,
     pub video_path: Option<String>,
     pub display_order: Option<i32>,
+    /// 自重種目（EXPは体重 + 追加重量で計算）
+    pub is_bodyweight: bool,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
@@ -163,6 +148,9 @@ pub struct UserCustomExercise {
     pub user_id: i64,
     pub name: String,
     pub muscle: String,
+    pub is_bodyweight: bool,
+    /// EXP計算の難易度係数（NULLはデフォルト）
+    pub difficulty_coef: Option<i32>,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
 }
@@ -221,6 +209,7 @@ pub struct Tag {
     pub id: i64,
     pub name: Option<String>,
     pub display_order: Option<i32>,
+    pub tag_group: Option<String>,
 }
 
 /// gym_tagsの中間テーブル（多対多）
@@ -338,6 +327,8 @@ pub struct UserStreak {
     pub best_streak: i32,
     pub last_active_date: Option<NaiveDate>,
     pub grace_days_used: i32, // 中休み使用日数
+    pub shields_used: i32,    // ストリークシールドの消費数
+    pub last_shield_used_date: Option<NaiveDate>,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
 }
@@ -357,6 +348,9 @@ pub struct UserSettings {
     pub id: i64,
     pub user_id: i64,
     pub grace_days_allowed: i32, // 中休み許容日数 (default: 1)
+    pub heatmap_mode: String,    // ヒートマップしきい値 (ADAPTIVE / FIXED)
+    pub weight_unit: String,     // 重量の単位 (kg / lb)
+    pub timezone: String,        // 日付の境界を判定するタイムゾーン (IANA名)
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
 }
@@ -379,12 +373,29 @@ pub struct PetType {
     pub display_order: Option<i32>,
     pub is_active: Option<bool>,
     // 解放条件
-    pub unlock_type: Option<String>, // 'default', 'user_level', 'pet_growth'
+    pub unlock_type: Option<String>, // 'default', 'user_level', 'pet_growth', 'event'
     pub unlock_level: Option<i32>,   // user_level時の必要レベル
     pub unlock_pet_code: Option<String>, // pet_growth時の対象ペットcode
     pub is_starter: Option<bool>,    // 初期3種類
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
+    /// 覚醒後の進化先（pet_evolution_branchesから別途読み込む）
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub branches: Vec<PetEvolutionBranch>,
+}
+
+/// ペット種類ごとの進化先（最も鍛えた筋肉グループで分岐）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PetEvolutionBranch {
+    pub id: i32,
+    pub pet_type_id: i32,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub dominant_muscle: Option<String>, // NULLは偏りがない場合の進化先
+    pub image_path: Option<String>,
+    pub display_order: i32,
 }
 
 /// ユーザーのペット
@@ -399,6 +410,7 @@ pub struct Pet {
     pub total_exp: i64,  // ペット専用累計経験値
     pub level: i32,      // ペット専用レベル
     pub is_active: bool, // アクティブペットフラグ
+    pub branch_id: Option<i32>, // 進化先（未進化はNone）
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
 }
@@ -412,19 +424,83 @@ pub struct UserPetUnlock {
     pub unlocked_at: Option<NaiveDateTime>,
 }
 
+/// 小屋の背景シーン（ジムチェックインで解放）
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct BarnBackground {
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub image_path: Option<String>,
+    pub unlock_type: String,
+    pub unlock_value: i32,
+    pub display_order: i32,
+}
+
+/// パートナーの着せ替えアクセサリー
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct PetAccessory {
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub slot: String, // back / neck / face / head
+    pub image_path: Option<String>,
+    pub unlock_type: String,
+    pub unlock_value: i32,
+    pub display_order: i32,
+}
+
 impl Pet {
     /// ペットレベルからステージを計算（新閾値）
     pub fn calculate_stage(level: i32) -> i32 {
         match level {
-            1..=10 => 1,  // Egg
+            ..=10 => 1,   // Egg
             11..=30 => 2, // Child
-            _ => 3,       // Adult
+            31..=50 => 3, // Adult
+            _ => 4,       // Evolution（進化先を選ぶまでは覚醒の姿）
+        }
+    }
+
+    /// 現在のステージ（進化先を選んだペットはレベルに関わらず進化の姿のまま）
+    pub fn calculate_stage_with_branch(level: i32, branch_id: Option<i32>) -> i32 {
+        if branch_id.is_some() {
+            Self::EVOLUTION_STAGE
+        } else {
+            Self::calculate_stage(level)
         }
     }
 
-    /// 累計EXPからペットレベルを計算（ユーザーと同じ計算式）
+    /// ステージに到達するレベル（calculate_stageの境界）
+    pub fn get_stage_start_level(stage: i32) -> i32 {
+        match stage {
+            2 => 11,
+            3 => 31,
+            4 => 51,
+            _ => 1,
+        }
+    }
+
+    /// 最終ステージ
+    pub const MAX_STAGE: i32 = 4;
+
+    /// 進化先を選べるステージ
+    pub const EVOLUTION_STAGE: i32 = 4;
+
+    /// 累計EXPからペットレベルを計算（ペット用の成長曲線）
     pub fn calculate_level(total_exp: i64) -> i32 {
-        UserStats::calculate_level(total_exp)
+        pet_exp_config::current().level_for_exp(total_exp)
+    }
+
+    /// 指定レベルに必要な累計EXP（ペット用の成長曲線）
+    pub fn get_required_exp_for_level(level: i32) -> i64 {
+        pet_exp_config::current().required_exp(level)
+    }
+
+    /// 現在レベルから次レベルに必要なEXP
+    pub fn get_exp_to_next_level(level: i32) -> i32 {
+        (Self::get_required_exp_for_level(level + 1) - Self::get_required_exp_for_level(level))
+            as i32
     }
 
     /// ステージ名を取得
@@ -433,17 +509,17 @@ impl Pet {
             1 => "卵",
             2 => "成長期",
             3 => "覚醒",
+            4 => "進化",
             _ => "Unknown",
         }
     }
 
-    /// 最終トレーニング日からムードスコアを計算
-    pub fn calculate_mood(last_active_date: Option<NaiveDate>) -> i32 {
+    /// 最終トレーニング日からムードスコアを計算（todayはユーザーのタイムゾーンでの今日）
+    pub fn calculate_mood(last_active_date: Option<NaiveDate>, today: NaiveDate) -> i32 {
         let Some(last) = last_active_date else {
             return 50; // トレーニング記録なし
         };
 
-        let today = chrono::Local::now().date_naive();
         let days_elapsed = (today - last).num_days();
 
         match days_elapsed {
@@ -455,15 +531,82 @@ impl Pet {
         }
     }
 
+    /// 今日のお世話の分を加えたムードスコア（上限100）
+    pub fn calculate_mood_with_care(
+        last_active_date: Option<NaiveDate>,
+        care_boost: i32,
+        today: NaiveDate,
+    ) -> i32 {
+        (Self::calculate_mood(last_active_date, today) + care_boost).min(100)
+    }
+
     /// ムードラベルを取得
+    /// お世話でムードが上がるため範囲で判定する（50はトレーニング記録なしの場合のみ）
     pub fn get_mood_label(mood_score: i32) -> &'static str {
         match mood_score {
-            100 => "絶好調",
-            80 => "元気",
-            60 => "普通",
-            40 => "寂しい",
+            100.. => "絶好調",
+            80..=99 => "元気",
+            60..=79 => "普通",
             50 => "眠そう",
+            40..=59 => "寂しい",
             _ => "弱っている",
         }
     }
 }
+
+// ============================================
+// ショップ（アイテム）
+// ============================================
+
+/// ショップのアイテム
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Item {
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub category: String, // 'food', 'toy', 'streak_protection'
+    pub price: i32,
+    pub mood_boost: Option<i32>,
+    pub image_path: Option<String>,
+    pub display_order: i32,
+    pub is_active: bool,
+}
+
+/// 実績（バッジ）のマスタ
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Achievement {
+    pub id: i32,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub condition_type: String, // 'TOTAL_WORKOUTS', 'SESSION_VOLUME', 'TRAINING_STREAK', 'LOGIN_STREAK', 'PET_LEVEL'
+    pub threshold: i64,
+    pub icon_path: Option<String>,
+    pub display_order: i32,
+    pub is_active: bool,
+}
+
+/// 期間限定イベント
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct SeasonalEvent {
+    pub id: i64,
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub banner_image_path: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub exp_multiplier: f64,
+    pub special_pet_type_id: Option<i32>,
+}
+
+/// デイリーリワードの日ごとの報酬
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DailyRewardConfig {
+    pub day: i32,
+    pub reward_type: String, // 'exp', 'coins', 'item'
+    pub amount: i32,
+    pub item_id: Option<i32>,
+    pub is_big_reward: bool,
+}
@@ -20,3 +20,8 @@ pub async fn create_pool() -> Result<MySqlPool, sqlx::Error> {
         .connect(&database_url)
         .await
 }
+
+/// migrations/ 配下のスキーママイグレーションを適用
+pub async fn run_migrations(pool: &MySqlPool) -> Result<(), sqlx::migrate::MigrateError> {
+    sqlx::migrate!("./migrations").run(pool).await
+}
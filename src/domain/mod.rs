@@ -0,0 +1,5 @@
+//! ドメインロジック（DB・HTTPに依存しない計算）
+
+pub mod one_rm;
+pub mod timezone;
+pub mod weight_unit;
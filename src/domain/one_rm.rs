@@ -0,0 +1,66 @@
+//! 推定1RM（1回だけ挙げられる最大重量）の計算
+//!
+//! 自己ベスト・種目の履歴・1RM推移で同じ計算式を使う。
+
+/// 推定1RMの計算式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OneRmFormula {
+    /// weight × (1 + reps / 30)
+    #[default]
+    Epley,
+    /// weight × 36 / (37 - reps)（37回以上は計算不可）
+    Brzycki,
+}
+
+impl OneRmFormula {
+    /// クエリパラメータの値から変換（epley / brzycki）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "epley" => Some(Self::Epley),
+            "brzycki" => Some(Self::Brzycki),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Epley => "epley",
+            Self::Brzycki => "brzycki",
+        }
+    }
+
+    /// 推定1RM（小数第1位に丸める。1回はそのままの重量、重量・回数が0以下はNone）
+    pub fn estimate(self, weight: f64, reps: i32) -> Option<f64> {
+        if weight <= 0.0 || reps <= 0 {
+            return None;
+        }
+        let one_rm = if reps == 1 {
+            weight
+        } else {
+            match self {
+                Self::Epley => weight * (1.0 + reps as f64 / 30.0),
+                Self::Brzycki if reps < 37 => weight * 36.0 / (37.0 - reps as f64),
+                Self::Brzycki => return None,
+            }
+        };
+        Some((one_rm * 10.0).round() / 10.0)
+    }
+
+    /// SQLでの計算式（training_setsの別名はts）
+    pub fn sql_expr(self) -> &'static str {
+        match self {
+            Self::Epley => {
+                "CASE WHEN ts.reps = 1 THEN ts.weight ELSE ts.weight * (1 + ts.reps / 30) END"
+            }
+            Self::Brzycki => {
+                "CASE WHEN ts.reps = 1 THEN ts.weight \
+                 WHEN ts.reps < 37 THEN ts.weight * 36 / (37 - ts.reps) ELSE 0 END"
+            }
+        }
+    }
+}
+
+/// 推定1RM（Epley式）
+pub fn estimate_one_rep_max(weight: f64, reps: i32) -> Option<f64> {
+    OneRmFormula::Epley.estimate(weight, reps)
+}
@@ -0,0 +1,55 @@
+//! ユーザーのタイムゾーン
+//!
+//! 「今日」の境界（記録日・ストリーク・デイリーリワード・ダッシュボード）はユーザーのタイムゾーンで判定する。
+//! 未設定・不正な値のユーザーは日本時間として扱う。
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// 既定のタイムゾーン
+pub const DEFAULT_TIMEZONE: Tz = chrono_tz::Asia::Tokyo;
+
+/// IANAのタイムゾーン名（例: "Asia/Tokyo"）から変換
+pub fn parse(name: &str) -> Option<Tz> {
+    name.trim().parse().ok()
+}
+
+/// 設定値から変換（不正な値は既定のタイムゾーン）
+pub fn from_setting(name: &str) -> Tz {
+    parse(name).unwrap_or(DEFAULT_TIMEZONE)
+}
+
+/// タイムゾーンでの今日の日付
+pub fn today_in(tz: Tz) -> NaiveDate {
+    Utc::now().with_timezone(&tz).date_naive()
+}
+
+/// タイムゾーンでの日付の開始時刻をUTCで表したもの
+/// DBの日時（created_atなど）はUTCで保存されるため、「今日の分」の絞り込みに使う
+pub fn day_start_utc(tz: Tz, date: NaiveDate) -> NaiveDateTime {
+    let midnight = date.and_time(NaiveTime::MIN);
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .map_or(midnight, |start| start.naive_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_start_utc_shifts_midnight_by_the_offset() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 15).unwrap();
+        assert_eq!(
+            day_start_utc(chrono_tz::Asia::Tokyo, date),
+            NaiveDate::from_ymd_opt(2026, 1, 14)
+                .unwrap()
+                .and_hms_opt(15, 0, 0)
+                .unwrap()
+        );
+        assert_eq!(
+            day_start_utc(chrono_tz::America::New_York, date),
+            date.and_hms_opt(5, 0, 0).unwrap()
+        );
+    }
+}
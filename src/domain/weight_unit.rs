@@ -0,0 +1,48 @@
+//! 重量の単位（kg / lb）
+//!
+//! 重量は常にkgで保存し、入力と表示のときだけユーザーの単位と相互に変換する。
+
+/// 1ポンドあたりのkg
+const KG_PER_LB: f64 = 0.45359237;
+
+/// 重量の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeightUnit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    /// 設定・リクエストの値から変換（kg / lb）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "kg" => Some(Self::Kg),
+            "lb" | "lbs" => Some(Self::Lb),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Kg => "kg",
+            Self::Lb => "lb",
+        }
+    }
+
+    /// この単位の重量をkgに変換（lbは保存用に小数第3位に丸める）
+    pub fn to_kg(self, weight: f64) -> f64 {
+        match self {
+            Self::Kg => weight,
+            Self::Lb => (weight * KG_PER_LB * 1000.0).round() / 1000.0,
+        }
+    }
+
+    /// kgの重量をこの単位に変換（lbは表示用に小数第2位に丸める）
+    pub fn convert_from_kg(self, weight_kg: f64) -> f64 {
+        match self {
+            Self::Kg => weight_kg,
+            Self::Lb => (weight_kg / KG_PER_LB * 100.0).round() / 100.0,
+        }
+    }
+}
@@ -11,6 +11,10 @@ pub enum AppError {
     BadRequest(String),
     Unauthorized(String),
     Forbidden(String),
+    /// ロック期間を過ぎたトレーニング記録の変更
+    RecordLocked(String),
+    /// レート制限の超過
+    TooManyRequests(String),
     InternalError(String),
     DatabaseError(String),
 }
@@ -28,6 +32,8 @@ impl fmt::Display for AppError {
             AppError::BadRequest(msg) => write!(f, "Bad Request: {}", msg),
             AppError::Unauthorized(msg) => write!(f, "Unauthorized: {}", msg),
             AppError::Forbidden(msg) => write!(f, "Forbidden: {}", msg),
+            AppError::RecordLocked(msg) => write!(f, "Record Locked: {}", msg),
+            AppError::TooManyRequests(msg) => write!(f, "Too Many Requests: {}", msg),
             AppError::InternalError(msg) => write!(f, "Internal Error: {}", msg),
             AppError::DatabaseError(msg) => write!(f, "Database Error: {}", msg),
         }
@@ -41,6 +47,8 @@ impl ResponseError for AppError {
             AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
             AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::RecordLocked(_) => StatusCode::LOCKED,
+            AppError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
             AppError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
@@ -52,6 +60,8 @@ impl ResponseError for AppError {
             AppError::BadRequest(_) => "BAD_REQUEST",
             AppError::Unauthorized(_) => "UNAUTHORIZED",
             AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::RecordLocked(_) => "RECORD_LOCKED",
+            AppError::TooManyRequests(_) => "RATE_LIMITED",
             AppError::InternalError(_) => "INTERNAL_ERROR",
             AppError::DatabaseError(_) => "DATABASE_ERROR",
         };
@@ -61,6 +71,8 @@ impl ResponseError for AppError {
             | AppError::BadRequest(msg)
             | AppError::Unauthorized(msg)
             | AppError::Forbidden(msg)
+            | AppError::RecordLocked(msg)
+            | AppError::TooManyRequests(msg)
             | AppError::InternalError(msg)
             | AppError::DatabaseError(msg) => msg.clone(),
         };
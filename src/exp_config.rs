@@ -0,0 +1,170 @@
+//! EXP設定
+//!
+//! 1日の上限・過去の記録の倍率・1セットの上限・係数をexp_configテーブルで管理する。
+//! 起動時に読み込み、管理者が変更した場合は通知バスで全インスタンスに反映する。
+
+use std::sync::RwLock;
+
+use sqlx::MySqlPool;
+
+use crate::config::ExpConfig;
+use crate::error::AppError;
+use crate::shared_store::{BusMessage, SharedStore};
+
+/// 設定変更の通知トピック
+pub const TOPIC_EXP_CONFIG_UPDATED: &str = "expConfig.updated";
+
+static CURRENT: RwLock<ExpConfig> = RwLock::new(ExpConfig::DEFAULT);
+
+/// 現在有効なEXP設定
+pub fn current() -> ExpConfig {
+    CURRENT
+        .read()
+        .map(|c| c.clone())
+        .unwrap_or(ExpConfig::DEFAULT)
+}
+
+fn set_current(config: ExpConfig) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = config;
+    }
+}
+
+/// 値の範囲を確認
+pub fn validate(config: &ExpConfig) -> Result<(), AppError> {
+    if config.daily_limit <= 0 {
+        return Err(AppError::BadRequest(
+            "1日の上限は1以上を指定してください".to_string(),
+        ));
+    }
+    if config.past_days_threshold < 0 {
+        return Err(AppError::BadRequest(
+            "過去の記録とみなす日数は0以上を指定してください".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&config.past_exp_multiplier)
+        || !(0.0..=1.0).contains(&config.past_limit_multiplier)
+    {
+        return Err(AppError::BadRequest(
+            "過去の記録の倍率は0〜1の範囲で指定してください".to_string(),
+        ));
+    }
+    if config.max_exp_per_set <= 0 {
+        return Err(AppError::BadRequest(
+            "1セットの上限は1以上を指定してください".to_string(),
+        ));
+    }
+    if !(config.exp_coefficient > 0.0 && config.exp_coefficient <= 100.0) {
+        return Err(AppError::BadRequest(
+            "係数は0より大きく100以下で指定してください".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct ExpConfigRow {
+    daily_limit: i32,
+    past_days_threshold: i32,
+    past_exp_multiplier: f64,
+    past_limit_multiplier: f64,
+    max_exp_per_set: i32,
+    exp_coefficient: f64,
+}
+
+impl From<ExpConfigRow> for ExpConfig {
+    fn from(row: ExpConfigRow) -> Self {
+        Self {
+            daily_limit: row.daily_limit,
+            past_days_threshold: row.past_days_threshold as i64,
+            past_exp_multiplier: row.past_exp_multiplier,
+            past_limit_multiplier: row.past_limit_multiplier,
+            max_exp_per_set: row.max_exp_per_set,
+            exp_coefficient: row.exp_coefficient,
+        }
+    }
+}
+
+/// DBからEXP設定を読み込む（未登録の場合は初期値）
+pub async fn load(pool: &MySqlPool) -> Result<ExpConfig, AppError> {
+    let row: Option<ExpConfigRow> = sqlx::query_as(
+        r#"SELECT daily_limit, past_days_threshold, past_exp_multiplier, past_limit_multiplier,
+                  max_exp_per_set, exp_coefficient
+           FROM exp_config WHERE id = 1"#,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let config = row.map(ExpConfig::from).unwrap_or(ExpConfig::DEFAULT);
+    set_current(config.clone());
+    Ok(config)
+}
+
+/// EXP設定を更新して全インスタンスに反映する
+pub async fn update(
+    pool: &MySqlPool,
+    store: &SharedStore,
+    config: ExpConfig,
+    updated_by: i64,
+) -> Result<ExpConfig, AppError> {
+    validate(&config)?;
+
+    sqlx::query(
+        r#"INSERT INTO exp_config
+               (id, daily_limit, past_days_threshold, past_exp_multiplier, past_limit_multiplier,
+                max_exp_per_set, exp_coefficient, updated_by, updated_at)
+           VALUES (1, ?, ?, ?, ?, ?, ?, ?, NOW())
+           ON DUPLICATE KEY UPDATE
+               daily_limit = VALUES(daily_limit),
+               past_days_threshold = VALUES(past_days_threshold),
+               past_exp_multiplier = VALUES(past_exp_multiplier),
+               past_limit_multiplier = VALUES(past_limit_multiplier),
+               max_exp_per_set = VALUES(max_exp_per_set),
+               exp_coefficient = VALUES(exp_coefficient),
+               updated_by = VALUES(updated_by),
+               updated_at = NOW()"#,
+    )
+    .bind(config.daily_limit)
+    .bind(config.past_days_threshold)
+    .bind(config.past_exp_multiplier)
+    .bind(config.past_limit_multiplier)
+    .bind(config.max_exp_per_set)
+    .bind(config.exp_coefficient)
+    .bind(updated_by)
+    .execute(pool)
+    .await?;
+
+    set_current(config.clone());
+    store
+        .publish(BusMessage {
+            topic: TOPIC_EXP_CONFIG_UPDATED.to_string(),
+            user_id: None,
+            payload: serde_json::json!({}),
+        })
+        .await;
+
+    tracing::info!("EXP config updated by user {}: {:?}", updated_by, config);
+    Ok(config)
+}
+
+/// 他インスタンスでの設定変更を受け取り、設定を読み直す
+pub fn spawn_reloader(pool: MySqlPool, store: &SharedStore) {
+    let mut receiver = store.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(message) if message.topic == TOPIC_EXP_CONFIG_UPDATED => {
+                    if let Err(e) = load(&pool).await {
+                        tracing::warn!("Failed to reload EXP config: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                    // 取りこぼした可能性があるため読み直す
+                    let _ = load(&pool).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,65 @@
+//! 退会処理ジョブ
+//! 猶予期間が終了した削除予約を1時間ごとに処理する
+
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+use crate::api::account::{purge_user_data, STATUS_COMPLETED, STATUS_SCHEDULED};
+use crate::api::profile_image::{delete_profile_image_files, find_profile_image_key};
+use crate::error::AppError;
+use crate::storage::Storage;
+
+/// 実行間隔
+const INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// ジョブを開始
+pub fn spawn(pool: MySqlPool, storage: Storage) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+
+            match purge_due_accounts(&pool, &storage).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Deleted {} accounts after grace period", count),
+                Err(e) => tracing::error!("Account deletion job failed: {}", e),
+            }
+        }
+    });
+}
+
+/// 猶予期間が終了したアカウントを削除し、削除した件数を返す
+async fn purge_due_accounts(pool: &MySqlPool, storage: &Storage) -> Result<usize, AppError> {
+    let due: Vec<(i64, i64)> = sqlx::query_as(
+        r#"SELECT id, user_id FROM account_deletion_requests
+           WHERE status = ? AND scheduled_deletion_at <= NOW()"#,
+    )
+    .bind(STATUS_SCHEDULED)
+    .fetch_all(pool)
+    .await?;
+
+    let mut deleted = 0;
+    for (request_id, user_id) in due {
+        let profile_image_key = find_profile_image_key(pool, user_id).await?;
+        if let Err(e) = purge_user_data(pool, user_id).await {
+            tracing::error!("Failed to delete account {}: {}", user_id, e);
+            continue;
+        }
+        // アップロードしたプロフィール画像（DBの削除後に行う）
+        if let Some(key) = profile_image_key {
+            delete_profile_image_files(storage, &key).await;
+        }
+
+        sqlx::query(
+            "UPDATE account_deletion_requests SET status = ?, completed_at = NOW() WHERE id = ?",
+        )
+        .bind(STATUS_COMPLETED)
+        .bind(request_id)
+        .execute(pool)
+        .await?;
+
+        deleted += 1;
+    }
+
+    Ok(deleted)
+}
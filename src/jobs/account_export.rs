@@ -0,0 +1,31 @@
+//! アカウントデータのエクスポートジョブ
+//! 10分ごとに中断されたエクスポートを処理し、有効期限を過ぎたZIPを削除する
+
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+use crate::api::account_export::{purge_expired_exports, resume_stale_exports};
+
+/// 実行間隔
+const INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// ジョブを開始
+pub fn spawn(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+
+            match resume_stale_exports(&pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Processed {} pending account exports", count),
+                Err(e) => tracing::error!("Account export job failed: {}", e),
+            }
+            match purge_expired_exports(&pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Deleted {} expired account exports", count),
+                Err(e) => tracing::error!("Account export cleanup failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,147 @@
+//! EXP異常検知ジョブ
+//!
+//! 毎晩、直近24時間に更新されたトレーニング記録を調べ、不自然なEXP獲得を
+//! exp_anomaliesテーブルに登録する。判定は管理者のレビューに委ね、自動でEXPは変更しない。
+
+use chrono::{FixedOffset, NaiveDate, NaiveTime, Utc};
+use sqlx::MySqlPool;
+
+use crate::error::AppError;
+use crate::exp_config;
+
+/// 実行時刻（JST）。4:00の日付切り替え後に前日分を検査する
+const RUN_AT_JST: (u32, u32) = (4, 30);
+
+/// 1日上限を超えたEXP獲得（過去日付の記録を大量に登録した場合など）
+pub const ANOMALY_DAILY_CAP_EXCEEDED: &str = "DAILY_CAP_EXCEEDED";
+/// 同一内容（種目・重量・回数）のセットの大量登録
+pub const ANOMALY_IDENTICAL_SETS: &str = "IDENTICAL_SETS";
+
+/// 同一セットとみなす件数のしきい値
+const IDENTICAL_SETS_THRESHOLD: i64 = 1000;
+
+/// ジョブを開始
+pub fn spawn(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let run_at = NaiveTime::from_hms_opt(RUN_AT_JST.0, RUN_AT_JST.1, 0).unwrap();
+        loop {
+            tokio::time::sleep(super::duration_until_jst(run_at)).await;
+
+            match detect_anomalies(&pool).await {
+                Ok(count) => tracing::info!("EXP anomaly scan finished: {} new anomalies", count),
+                Err(e) => tracing::error!("EXP anomaly scan failed: {}", e),
+            }
+        }
+    });
+}
+
+/// 直近24時間の記録を検査し、新たに登録した異常の件数を返す
+pub async fn detect_anomalies(pool: &MySqlPool) -> Result<u64, AppError> {
+    let detected_for = today_jst();
+    let daily_limit = exp_config::current().daily_limit as i64;
+
+    let mut inserted = 0;
+
+    // 1日上限の超過: 記録日ごとに上限がかかるため、過去日付を複数登録すると1日で上限以上を獲得できる
+    let cap_rows = sqlx::query_as::<_, (i64, i64, i64, i64)>(
+        r#"SELECT user_id,
+                  CAST(SUM(exp_earned) AS SIGNED) AS total_exp,
+                  COUNT(*) AS record_count,
+                  COUNT(DISTINCT record_date) AS date_count
+           FROM training_records
+           WHERE updated_at >= NOW() - INTERVAL 1 DAY
+           GROUP BY user_id
+           HAVING total_exp > ?"#,
+    )
+    .bind(daily_limit)
+    .fetch_all(pool)
+    .await?;
+
+    for (user_id, total_exp, record_count, date_count) in cap_rows {
+        let detail = serde_json::json!({
+            "dailyLimit": daily_limit,
+            "recordCount": record_count,
+            "recordDateCount": date_count,
+        });
+        inserted += insert_anomaly(
+            pool,
+            user_id,
+            ANOMALY_DAILY_CAP_EXCEEDED,
+            detected_for,
+            total_exp,
+            &detail,
+        )
+        .await?;
+    }
+
+    // 同一セットの大量登録（ユーザーごとに最も多い組み合わせを1件として登録）
+    let set_rows = sqlx::query_as::<_, (i64, Option<i64>, Option<i64>, f64, i32, i64)>(
+        r#"SELECT tr.user_id, tre.exercise_id, tre.custom_exercise_id,
+                  ts.weight, ts.reps, COUNT(*) AS set_count
+           FROM training_sets ts
+           JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+           JOIN training_records tr ON tre.record_id = tr.id
+           WHERE tr.updated_at >= NOW() - INTERVAL 1 DAY AND ts.set_type <> 'warmup'
+           GROUP BY tr.user_id, tre.exercise_id, tre.custom_exercise_id, ts.weight, ts.reps
+           HAVING set_count >= ?
+           ORDER BY tr.user_id, set_count DESC"#,
+    )
+    .bind(IDENTICAL_SETS_THRESHOLD)
+    .fetch_all(pool)
+    .await?;
+
+    let mut last_user_id = None;
+    for (user_id, exercise_id, custom_exercise_id, weight, reps, set_count) in set_rows {
+        if last_user_id == Some(user_id) {
+            continue;
+        }
+        last_user_id = Some(user_id);
+
+        let detail = serde_json::json!({
+            "exerciseId": exercise_id,
+            "customExerciseId": custom_exercise_id,
+            "weight": weight,
+            "reps": reps,
+        });
+        inserted += insert_anomaly(
+            pool,
+            user_id,
+            ANOMALY_IDENTICAL_SETS,
+            detected_for,
+            set_count,
+            &detail,
+        )
+        .await?;
+    }
+
+    Ok(inserted)
+}
+
+/// 異常を登録（同日・同種別の再実行では重複登録しない）
+async fn insert_anomaly(
+    pool: &MySqlPool,
+    user_id: i64,
+    anomaly_type: &str,
+    detected_for: NaiveDate,
+    metric_value: i64,
+    detail: &serde_json::Value,
+) -> Result<u64, AppError> {
+    let result = sqlx::query(
+        r#"INSERT IGNORE INTO exp_anomalies (user_id, anomaly_type, detected_for, metric_value, detail)
+           VALUES (?, ?, ?, ?, ?)"#,
+    )
+    .bind(user_id)
+    .bind(anomaly_type)
+    .bind(detected_for)
+    .bind(metric_value)
+    .bind(detail.to_string())
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+fn today_jst() -> NaiveDate {
+    let jst = FixedOffset::east_opt(9 * 3600).unwrap();
+    Utc::now().with_timezone(&jst).date_naive()
+}
@@ -0,0 +1,109 @@
+//! ランキング集計ジョブ
+//!
+//! ランキングの取得で毎回training_setsなどを集計しないよう、
+//! ランキングに参加しているユーザーのスコアと順位を定期的にleaderboard_rankingsへ書き出す。
+
+use chrono::NaiveDate;
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+use crate::api::challenge::week_start_of;
+use crate::api::leaderboard::{
+    LEADERBOARD_LONGEST_STREAK, LEADERBOARD_TYPES, LEADERBOARD_WEEKLY_EXP,
+    LEADERBOARD_WEEKLY_VOLUME,
+};
+use crate::domain::timezone::{self, DEFAULT_TIMEZONE};
+use crate::error::AppError;
+
+/// 実行間隔
+const INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// ジョブを開始
+pub fn spawn(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = refresh_leaderboards(&pool).await {
+                tracing::error!("Leaderboard job failed: {}", e);
+            }
+        }
+    });
+}
+
+/// ユーザーごとのスコアを求めるSQLと、バインドする週の月曜日の数
+fn scores_sql(board_type: &str) -> Option<(&'static str, usize)> {
+    let scores = match board_type {
+        // 管理者によるレベル変更などは除き、記録・報酬による増減のみ数える
+        LEADERBOARD_WEEKLY_EXP => (
+            r#"SELECT user_id, CAST(SUM(amount) AS SIGNED) AS score FROM exp_transactions
+               WHERE created_at >= ? AND source NOT IN ('admin', 'level_curve', 'streak_recovery')
+               GROUP BY user_id"#,
+            1,
+        ),
+        LEADERBOARD_WEEKLY_VOLUME => (
+            r#"SELECT tr.user_id, CAST(SUM(ts.weight * ts.reps) AS SIGNED) AS score
+               FROM training_sets ts
+               INNER JOIN training_record_exercises tre ON ts.record_exercise_id = tre.id
+               INNER JOIN training_records tr ON tre.record_id = tr.id
+               WHERE tr.record_date >= ? AND ts.set_type <> 'warmup'
+               GROUP BY tr.user_id"#,
+            1,
+        ),
+        LEADERBOARD_LONGEST_STREAK => (
+            r#"SELECT user_id, best_streak AS score FROM user_streaks
+               WHERE streak_type = 'training'"#,
+            0,
+        ),
+        _ => return None,
+    };
+    Some(scores)
+}
+
+/// 1つのランキングを集計し直し、載ったユーザー数を返す
+async fn refresh_board(
+    pool: &MySqlPool,
+    board_type: &str,
+    week_start: NaiveDate,
+) -> Result<u64, AppError> {
+    let Some((scores, week_binds)) = scores_sql(board_type) else {
+        return Ok(0);
+    };
+    let period_start = (board_type != LEADERBOARD_LONGEST_STREAK).then_some(week_start);
+
+    let sql = format!(
+        r#"INSERT INTO leaderboard_rankings
+               (board_type, user_id, period_start, score, rank_position, refreshed_at)
+           SELECT ?, scores.user_id, ?, scores.score,
+                  RANK() OVER (ORDER BY scores.score DESC), NOW()
+           FROM ({}) scores
+           INNER JOIN user_settings s ON s.user_id = scores.user_id AND s.show_on_leaderboard = TRUE
+           INNER JOIN users u ON u.id = scores.user_id AND u.deactivated_at IS NULL
+           WHERE scores.score > 0"#,
+        scores
+    );
+    let mut query = sqlx::query(&sql).bind(board_type).bind(period_start);
+    for _ in 0..week_binds {
+        query = query.bind(week_start);
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM leaderboard_rankings WHERE board_type = ?")
+        .bind(board_type)
+        .execute(&mut *tx)
+        .await?;
+    let inserted = query.execute(&mut *tx).await?.rows_affected();
+    tx.commit().await?;
+    Ok(inserted)
+}
+
+/// すべてのランキングを集計し直す（週は日本時間の月曜日から）
+pub async fn refresh_leaderboards(pool: &MySqlPool) -> Result<(), AppError> {
+    let week_start = week_start_of(timezone::today_in(DEFAULT_TIMEZONE));
+    for board_type in LEADERBOARD_TYPES {
+        let count = refresh_board(pool, board_type, week_start).await?;
+        tracing::debug!("Refreshed leaderboard {} ({} users)", board_type, count);
+    }
+    Ok(())
+}
@@ -0,0 +1,26 @@
+//! ログイン履歴の削除ジョブ
+//! 保存期間を過ぎたログイン履歴を1日1回削除する
+
+use chrono::NaiveTime;
+use sqlx::MySqlPool;
+
+use crate::api::login_audit::purge_old_login_audit;
+
+/// 実行時刻（JST）。アクセスの少ない時間帯に行う
+const RUN_AT_JST: (u32, u32) = (3, 30);
+
+/// ジョブを開始
+pub fn spawn(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let run_at = NaiveTime::from_hms_opt(RUN_AT_JST.0, RUN_AT_JST.1, 0).unwrap();
+        loop {
+            tokio::time::sleep(super::duration_until_jst(run_at)).await;
+
+            match purge_old_login_audit(&pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Deleted {} expired login audit entries", count),
+                Err(e) => tracing::error!("Login audit cleanup failed: {}", e),
+            }
+        }
+    });
+}
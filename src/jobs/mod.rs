@@ -0,0 +1,40 @@
+//! バックグラウンドジョブ
+//! サーバー起動時に開始され、プロセスが終了するまで定期実行される
+
+pub mod account_deletion;
+pub mod account_export;
+pub mod exp_anomaly;
+pub mod leaderboard;
+pub mod login_audit_cleanup;
+pub mod pet_mood;
+
+use chrono::{FixedOffset, NaiveTime, Utc};
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+use crate::storage::Storage;
+
+/// すべてのバックグラウンドジョブを開始
+pub fn start(pool: MySqlPool, storage: Storage) {
+    exp_anomaly::spawn(pool.clone());
+    pet_mood::spawn(pool.clone());
+    leaderboard::spawn(pool.clone());
+    login_audit_cleanup::spawn(pool.clone());
+    account_export::spawn(pool.clone());
+    account_deletion::spawn(pool, storage);
+}
+
+/// 次のJST指定時刻までの待ち時間を計算
+pub(crate) fn duration_until_jst(time: NaiveTime) -> Duration {
+    let jst = FixedOffset::east_opt(9 * 3600).unwrap();
+    let now_jst = Utc::now().with_timezone(&jst);
+
+    let mut next = now_jst.date_naive().and_time(time);
+    if next <= now_jst.naive_local() {
+        next += chrono::Duration::days(1);
+    }
+
+    (next - now_jst.naive_local())
+        .to_std()
+        .unwrap_or(Duration::from_secs(60))
+}
@@ -0,0 +1,71 @@
+//! パートナーのムード更新ジョブ
+//!
+//! ムードは最終トレーニング日からの経過日数で下がるため、ペットのAPIが呼ばれなくても
+//! 定期的に全ペットのmood_scoreを再計算し、変化があればムードの推移として記録する。
+
+use chrono::NaiveDate;
+use sqlx::MySqlPool;
+use std::time::Duration;
+
+use crate::api::pet::record_mood_change;
+use crate::db::models::Pet;
+use crate::domain::timezone::{self, DEFAULT_TIMEZONE};
+use crate::error::AppError;
+
+/// 実行間隔
+const INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+/// ジョブを開始
+pub fn spawn(pool: MySqlPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(INTERVAL);
+        loop {
+            interval.tick().await;
+
+            match refresh_pet_moods(&pool).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!("Updated mood of {} pets", count),
+                Err(e) => tracing::error!("Pet mood job failed: {}", e),
+            }
+        }
+    });
+}
+
+/// 所持中の全ペットのムードを再計算し、更新した件数を返す
+/// 全ユーザーをまとめて処理するため、「今日」は日本時間で判定する
+/// （表示時はPetResponseContextがユーザーのタイムゾーンで再計算する）
+pub async fn refresh_pet_moods(pool: &MySqlPool) -> Result<usize, AppError> {
+    let today = timezone::today_in(DEFAULT_TIMEZONE);
+    let day_start = timezone::day_start_utc(DEFAULT_TIMEZONE, today);
+
+    let pets: Vec<(i64, i32, Option<NaiveDate>, i64)> = sqlx::query_as(
+        r#"SELECT p.id, p.mood_score, us.last_active_date,
+                  CAST(COALESCE(SUM(pcl.mood_boost), 0) AS SIGNED) AS care_boost
+           FROM pets p
+           LEFT JOIN user_streaks us ON us.user_id = p.user_id AND us.streak_type = 'training'
+           LEFT JOIN pet_care_log pcl ON pcl.pet_id = p.id AND pcl.created_at >= ?
+           WHERE p.released_at IS NULL
+           GROUP BY p.id, p.mood_score, us.last_active_date"#,
+    )
+    .bind(day_start)
+    .fetch_all(pool)
+    .await?;
+
+    let mut updated = 0;
+    for (pet_id, mood_score, last_active_date, care_boost) in pets {
+        let new_mood = Pet::calculate_mood_with_care(last_active_date, care_boost as i32, today);
+        if new_mood == mood_score {
+            continue;
+        }
+
+        sqlx::query("UPDATE pets SET mood_score = ?, updated_at = NOW() WHERE id = ?")
+            .bind(new_mood)
+            .bind(pet_id)
+            .execute(pool)
+            .await?;
+        record_mood_change(pool, pet_id, new_mood).await?;
+        updated += 1;
+    }
+
+    Ok(updated)
+}
@@ -0,0 +1,258 @@
+//! レベル曲線
+//!
+//! レベルに必要な累計EXPの計算式（quadratic × L² + linear × L + constant）をDBでバージョン管理する。
+//! 起動時に有効なバージョンを読み込み、管理者が変更した場合は通知バスで全インスタンスに反映する。
+
+use std::sync::RwLock;
+
+use serde::Serialize;
+use sqlx::MySqlPool;
+
+use crate::error::AppError;
+use crate::shared_store::{BusMessage, SharedStore};
+
+/// 最大レベル（レベル計算の探索範囲）
+pub const MAX_LEVEL: i32 = 1000;
+
+/// 曲線変更の通知トピック
+pub const TOPIC_LEVEL_CURVE_UPDATED: &str = "levelCurve.updated";
+
+/// レベル曲線のパラメータ
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelCurve {
+    pub version: i32,
+    pub quadratic: i64,
+    pub linear: i64,
+    pub constant: i64,
+}
+
+impl LevelCurve {
+    /// 初期曲線（2週間スピードラン用）: 40 × L² + 100 × L - 140
+    /// Lv1: 0, Lv2: 220, Lv5: 1360, Lv10: 4860, Lv50: 104860, Lv100: 409860
+    pub const DEFAULT: LevelCurve = LevelCurve {
+        version: 1,
+        quadratic: 40,
+        linear: 100,
+        constant: -140,
+    };
+
+    /// 指定レベルに必要な累計EXP
+    pub fn required_exp(&self, level: i32) -> i64 {
+        if level <= 1 {
+            return 0;
+        }
+        let l = level as i64;
+        self.quadratic * l * l + self.linear * l + self.constant
+    }
+
+    /// 累計EXPからレベルを計算（必要EXPが単調増加であれば任意の曲線で使える二分探索）
+    pub fn level_for_exp(&self, total_exp: i64) -> i32 {
+        if total_exp <= 0 {
+            return 1;
+        }
+        let mut low = 1;
+        let mut high = MAX_LEVEL;
+        while low < high {
+            let mid = (low + high + 1) / 2;
+            if self.required_exp(mid) <= total_exp {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        low
+    }
+
+    /// 現在レベル内の進行度（0.0〜1.0）
+    pub fn progress(&self, total_exp: i64) -> f64 {
+        let level = self.level_for_exp(total_exp);
+        let current = self.required_exp(level);
+        let next = self.required_exp(level + 1);
+        if next <= current {
+            return 1.0;
+        }
+        ((total_exp - current) as f64 / (next - current) as f64).clamp(0.0, 1.0)
+    }
+
+    /// 必要EXPがLv2以降で正かつ単調増加であることを確認
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut previous = 0;
+        for level in 2..=MAX_LEVEL + 1 {
+            let required = self.required_exp(level);
+            if required <= previous {
+                return Err(AppError::BadRequest(format!(
+                    "必要EXPがLv{}で増加していません",
+                    level
+                )));
+            }
+            previous = required;
+        }
+        Ok(())
+    }
+}
+
+static CURRENT: RwLock<LevelCurve> = RwLock::new(LevelCurve::DEFAULT);
+
+/// 現在有効なレベル曲線
+pub fn current() -> LevelCurve {
+    CURRENT.read().map(|c| *c).unwrap_or(LevelCurve::DEFAULT)
+}
+
+fn set_current(curve: LevelCurve) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = curve;
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct LevelCurveRow {
+    version: i32,
+    quadratic: i64,
+    linear_coef: i64,
+    constant_term: i64,
+}
+
+impl From<LevelCurveRow> for LevelCurve {
+    fn from(row: LevelCurveRow) -> Self {
+        Self {
+            version: row.version,
+            quadratic: row.quadratic,
+            linear: row.linear_coef,
+            constant: row.constant_term,
+        }
+    }
+}
+
+/// 全バージョンを取得（新しい順）
+pub async fn fetch_all(pool: &MySqlPool) -> Result<Vec<(LevelCurve, bool)>, AppError> {
+    #[derive(sqlx::FromRow)]
+    struct Row {
+        #[sqlx(flatten)]
+        curve: LevelCurveRow,
+        is_active: bool,
+    }
+
+    let rows: Vec<Row> = sqlx::query_as(
+        r#"SELECT version, quadratic, linear_coef, constant_term, is_active
+           FROM level_curves ORDER BY version DESC"#,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|r| (LevelCurve::from(r.curve), r.is_active))
+        .collect())
+}
+
+/// DBから有効なレベル曲線を読み込む（未登録の場合は初期曲線）
+pub async fn load_active(pool: &MySqlPool) -> Result<LevelCurve, AppError> {
+    let row: Option<LevelCurveRow> = sqlx::query_as(
+        r#"SELECT version, quadratic, linear_coef, constant_term
+           FROM level_curves WHERE is_active = TRUE
+           ORDER BY version DESC LIMIT 1"#,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let curve = row.map(LevelCurve::from).unwrap_or(LevelCurve::DEFAULT);
+    set_current(curve);
+    Ok(curve)
+}
+
+/// 新しいバージョンを作成して有効化する
+/// 既存ユーザーには変更前のバージョンを記録し、管理者の移行処理で新しい曲線へ移す
+pub async fn activate_new_version(
+    pool: &MySqlPool,
+    store: &SharedStore,
+    quadratic: i64,
+    linear: i64,
+    constant: i64,
+) -> Result<LevelCurve, AppError> {
+    let mut tx = pool.begin().await?;
+
+    let previous: Option<(i32,)> = sqlx::query_as(
+        r#"SELECT version FROM level_curves WHERE is_active = TRUE
+           ORDER BY version DESC LIMIT 1 FOR UPDATE"#,
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    let previous_version = previous
+        .map(|(v,)| v)
+        .unwrap_or(LevelCurve::DEFAULT.version);
+
+    let (max_version,): (Option<i32>,) = sqlx::query_as("SELECT MAX(version) FROM level_curves")
+        .fetch_one(&mut *tx)
+        .await?;
+    let curve = LevelCurve {
+        version: max_version.unwrap_or(0) + 1,
+        quadratic,
+        linear,
+        constant,
+    };
+    curve.validate()?;
+
+    sqlx::query("UPDATE user_stats SET level_curve_version = ? WHERE level_curve_version IS NULL")
+        .bind(previous_version)
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query("UPDATE level_curves SET is_active = FALSE WHERE is_active = TRUE")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query(
+        r#"INSERT INTO level_curves
+           (version, quadratic, linear_coef, constant_term, is_active, created_at)
+           VALUES (?, ?, ?, ?, TRUE, NOW())"#,
+    )
+    .bind(curve.version)
+    .bind(curve.quadratic)
+    .bind(curve.linear)
+    .bind(curve.constant)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    set_current(curve);
+    store
+        .publish(BusMessage {
+            topic: TOPIC_LEVEL_CURVE_UPDATED.to_string(),
+            user_id: None,
+            payload: serde_json::json!({ "version": curve.version }),
+        })
+        .await;
+
+    tracing::info!(
+        "Level curve v{} activated: {} × L² + {} × L + {}",
+        curve.version,
+        curve.quadratic,
+        curve.linear,
+        curve.constant
+    );
+    Ok(curve)
+}
+
+/// 他インスタンスでの曲線変更を受け取り、有効な曲線を読み直す
+pub fn spawn_reloader(pool: MySqlPool, store: &SharedStore) {
+    let mut receiver = store.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(message) if message.topic == TOPIC_LEVEL_CURVE_UPDATED => {
+                    if let Err(e) = load_active(&pool).await {
+                        tracing::warn!("Failed to reload level curve: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                    // 取りこぼした可能性があるため読み直す
+                    let _ = load_active(&pool).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -1,6 +1,15 @@
 pub mod api;
 pub mod auth;
+pub mod captcha;
 pub mod config;
 pub mod db;
+pub mod domain;
 pub mod error;
+pub mod exp_config;
+pub mod jobs;
+pub mod level_curve;
+pub mod mailer;
 pub mod middleware;
+pub mod pet_exp_config;
+pub mod shared_store;
+pub mod storage;
@@ -0,0 +1,98 @@
+//! メール送信
+//!
+//! 送信方式はMAIL_PROVIDERで切り替える。
+//! - log: 送信せずに内容をログ出力する（開発環境・未設定時）
+//! - http: MAIL_API_URLへJSON（from/to/subject/text）をPOSTする（Resend等のHTTPメールAPI）
+
+use serde::Serialize;
+
+use crate::config::AppConfig;
+
+/// 送信するメール
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Serialize)]
+struct HttpMailPayload<'a> {
+    from: &'a str,
+    to: [&'a str; 1],
+    subject: &'a str,
+    text: &'a str,
+}
+
+/// メール送信クライアント
+pub enum Mailer {
+    Log,
+    Http {
+        client: reqwest::Client,
+        api_url: String,
+        api_key: String,
+        from: String,
+    },
+}
+
+impl Mailer {
+    pub fn from_config(config: &AppConfig) -> Self {
+        match config.mail_provider.as_str() {
+            "http" if !config.mail_api_url.is_empty() => Mailer::Http {
+                client: reqwest::Client::new(),
+                api_url: config.mail_api_url.clone(),
+                api_key: config.mail_api_key.clone(),
+                from: config.mail_from.clone(),
+            },
+            "http" => {
+                tracing::warn!("MAIL_PROVIDER=http but MAIL_API_URL is not set; mails will only be logged");
+                Mailer::Log
+            }
+            _ => Mailer::Log,
+        }
+    }
+
+    /// メールを送信
+    pub async fn send(&self, message: &MailMessage) -> Result<(), String> {
+        match self {
+            Mailer::Log => {
+                tracing::info!(
+                    "[mail] to={} subject={}\n{}",
+                    message.to,
+                    message.subject,
+                    message.body
+                );
+                Ok(())
+            }
+            Mailer::Http {
+                client,
+                api_url,
+                api_key,
+                from,
+            } => {
+                let payload = HttpMailPayload {
+                    from,
+                    to: [&message.to],
+                    subject: &message.subject,
+                    text: &message.body,
+                };
+
+                let response = client
+                    .post(api_url)
+                    .bearer_auth(api_key)
+                    .json(&payload)
+                    .send()
+                    .await
+                    .map_err(|e| format!("Failed to send mail: {}", e))?;
+
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    return Err(format!("Mail API returned {}: {}", status, body));
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -4,10 +4,10 @@
 
 use actix_cors::Cors;
 use actix_files::Files;
-use actix_session::{config::PersistentSession, storage::CookieSessionStore, SessionMiddleware};
+use actix_session::{config::PersistentSession, SessionMiddleware};
 use actix_web::{
     cookie::Key,
-    middleware::{Compress, Logger},
+    middleware::{Compress, Condition, Logger},
     web, App, HttpResponse, HttpServer,
 };
 use tracing::info;
@@ -15,31 +15,59 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 mod api;
 mod auth;
+mod captcha;
 mod config;
 mod db;
+mod domain;
 mod error;
+mod exp_config;
+mod jobs;
+mod level_curve;
+mod mailer;
 mod middleware;
+mod pet_exp_config;
+mod shared_store;
+mod storage;
 
+use auth::oauth::OAuthRegistry;
+use auth::session_store::AppSessionStore;
+use captcha::Captcha;
 use config::AppConfig;
-use db::pool::create_pool;
+use db::pool::{create_pool, run_migrations};
+use mailer::Mailer;
+use middleware::api_deprecation::ApiDeprecation;
+use middleware::api_key_auth::ApiKeyAuth;
 use middleware::basic_auth::BasicAuth;
+use middleware::bearer_auth::BearerAuth;
+use middleware::request_log::RequestLog;
+use middleware::session_refresh::SessionRefresh;
+use middleware::session_timeout::SessionTimeout;
+use shared_store::SharedStore;
+use storage::{Storage, LOCAL_PUBLIC_PATH};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     // .envファイルを読み込み
     dotenvy::dotenv().ok();
 
-    // ロギングを初期化
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "info,fithub_fast=debug,sqlx=warn,actix_web=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
     // 設定を読み込み
     let config = AppConfig::from_env();
+
+    // ロギングを初期化（LOG_FORMAT=jsonでCloudWatch等で検索しやすいJSON出力）
+    let json_logs = config.log_format == "json";
+    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "info,fithub_fast=debug,sqlx=warn,actix_web=info".into());
+    if json_logs {
+        tracing_subscriber::registry()
+            .with(env_filter)
+            .with(tracing_subscriber::fmt::layer().json().flatten_event(true))
+            .init();
+    } else {
+        tracing_subscriber::registry()
+            .with(env_filter)
+            .with(tracing_subscriber::fmt::layer())
+            .init();
+    }
     info!(
         "Starting FithubFast server on {}:{}",
         config.host, config.port
@@ -63,12 +91,64 @@ async fn main() -> std::io::Result<()> {
         }
     }
 
+    // スキーママイグレーションを適用
+    if let Err(e) = run_migrations(&pool).await {
+        tracing::error!("Database migration failed: {}", e);
+        return Err(std::io::Error::other("Failed to run database migrations"));
+    }
+    info!("Database migrations applied");
+
+    // 有効なレベル曲線を読み込む
+    match level_curve::load_active(&pool).await {
+        Ok(curve) => info!("Level curve v{} loaded", curve.version),
+        Err(e) => tracing::warn!("Failed to load level curve, using default: {}", e),
+    }
+
+    // EXP設定を読み込む
+    if let Err(e) = exp_config::load(&pool).await {
+        tracing::warn!("Failed to load EXP config, using default: {}", e);
+    }
+
+    // ペットの成長曲線を読み込む
+    if let Err(e) = pet_exp_config::load(&pool).await {
+        tracing::warn!("Failed to load pet EXP config, using default: {}", e);
+    }
+
+    // アップロードファイルの保存先（STORAGE_BACKEND=s3でS3、それ以外はローカル）
+    let storage = web::Data::new(Storage::from_config(&config).await);
+
+    // バックグラウンドジョブを開始
+    jobs::start(pool.clone(), storage.get_ref().clone());
+
     // セッションキー（64バイト以上が必要）
     let session_key = Key::from(config.session_secret.as_bytes());
 
+    // OAuthプロバイダーレジストリ
+    let oauth_registry = web::Data::new(OAuthRegistry::from_config(&config));
+
+    // メール送信クライアント
+    let mailer = web::Data::new(Mailer::from_config(&config));
+
+    // CAPTCHA検証クライアント（CAPTCHA_SECRET未設定時は検証しない）
+    let captcha = web::Data::new(Captcha::from_config(&config));
+
+    // インスタンス間で共有するストア（レート制限・キャッシュ・通知）
+    let shared_store = web::Data::new(SharedStore::from_config(&config).await);
+
+    // セッションの保存先（SESSION_STORE=redisでRedis、それ以外・接続できない場合はCookie）
+    let session_store = AppSessionStore::from_config(&config).await;
+    level_curve::spawn_reloader(pool.clone(), &shared_store);
+    exp_config::spawn_reloader(pool.clone(), &shared_store);
+    pet_exp_config::spawn_reloader(pool.clone(), &shared_store);
+
     let host = config.host.clone();
     let port = config.port;
 
+    // セッション有効期限（無操作タイムアウトで延長、最大有効期間で打ち切り）
+    let session_idle_minutes = config.session_idle_timeout_minutes;
+    let session_max_hours = config.session_max_lifetime_hours;
+    let session_revalidate_minutes = config.session_revalidate_minutes;
+
     // HTTPサーバーを開始
     HttpServer::new(move || {
         // CORS設定
@@ -83,23 +163,48 @@ async fn main() -> std::io::Result<()> {
             // ミドルウェア（順序重要: 最初に追加 = 最外層）
             .wrap(BasicAuth::new())
             .wrap(Compress::default())
-            .wrap(Logger::default())
+            // JSONログ時はRequestLogがアクセスログを出力する
+            .wrap(Condition::new(!json_logs, Logger::default()))
             .wrap(cors)
+            // 削除予定エンドポイントへの非推奨ヘッダー付与
+            .wrap(ApiDeprecation)
+            // セッションのユーザー情報をDBと照合（有効期限チェックの後に実行される）
+            .wrap(SessionRefresh::new(session_revalidate_minutes * 60))
+            // セッション有効期限チェック（SessionMiddlewareの内側で実行される）
+            .wrap(SessionTimeout::new(
+                session_idle_minutes * 60,
+                session_max_hours * 3600,
+            ))
+            // リクエストログ（ユーザーIDを取得するためSessionMiddlewareの内側）
+            .wrap(Condition::new(json_logs, RequestLog))
+            // Bearerトークン認証（トークンのユーザーをセッションに載せる）
+            .wrap(BearerAuth)
+            // APIキー認証（キーの持ち主をセッションに載せ、スコープ外のリクエストを拒否する）
+            .wrap(ApiKeyAuth)
             .wrap(
-                SessionMiddleware::builder(CookieSessionStore::default(), session_key.clone())
+                SessionMiddleware::builder(session_store.clone(), session_key.clone())
                     .cookie_secure(false) // 本番環境ではHTTPSでtrueに設定
                     .cookie_http_only(true)
                     .session_lifecycle(
                         PersistentSession::default()
-                            .session_ttl(actix_web::cookie::time::Duration::hours(24)),
+                            .session_ttl(actix_web::cookie::time::Duration::minutes(
+                                session_idle_minutes,
+                            )),
                     )
                     .build(),
             )
             // 共有ステート
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::new(config.clone()))
+            .app_data(oauth_registry.clone())
+            .app_data(mailer.clone())
+            .app_data(captcha.clone())
+            .app_data(shared_store.clone())
+            .app_data(storage.clone())
             // ルートレベル認証ルート（ログイン、ログアウト、登録、OAuth）
             .configure(api::auth::configure_root)
+            // パートナーのイベント通知（WebSocket）
+            .configure(api::pet_events::configure_root)
             // APIルート
             .configure(api::configure)
             // ヘルスチェック
@@ -110,6 +215,12 @@ async fn main() -> std::io::Result<()> {
             .service(Files::new("/.well-known", "./static/.well-known"))
             .service(Files::new("/assets", "./static/assets"))
             .service(Files::new("/images", "./static/images"))
+            // ローカルに保存したアップロードファイル
+            .configure(|cfg| {
+                if let Some(dir) = storage.local_dir() {
+                    cfg.service(Files::new(LOCAL_PUBLIC_PATH, dir));
+                }
+            })
             .route("/vite.svg", web::get().to(serve_vite_svg))
             // クライアントサイドルーティング用SPAフォールバック（React Router）
             .default_service(web::route().to(spa_fallback))
@@ -0,0 +1,155 @@
+//! APIの非推奨ヘッダーミドルウェア
+//!
+//! 削除予定のエンドポイントのレスポンスにDeprecation（RFC 9745）・Sunset（RFC 8594）ヘッダーと
+//! 後継エンドポイントへのLinkヘッダーを付与し、連携先が削除前に移行できるようにする。
+//! /api/v1/...とバージョンなしの/api/...のどちらで呼ばれても同じ扱いとする。
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::{
+        header::{HeaderName, HeaderValue, LINK},
+        Method,
+    },
+    Error,
+};
+use chrono::{NaiveDate, NaiveDateTime};
+use futures::future::{ok, Ready};
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use crate::api::API_V1_PREFIX;
+
+/// 削除予定のエンドポイント
+struct DeprecatedEndpoint {
+    method: Method,
+    /// バージョンを除いたルートパターン（例: "/pet"）
+    pattern: &'static str,
+    /// 非推奨になった日
+    deprecated_on: (i32, u32, u32),
+    /// 削除予定日
+    sunset_on: (i32, u32, u32),
+    /// 後継エンドポイント（バージョンを除いたパス）
+    successor: &'static str,
+}
+
+/// 削除予定のエンドポイント一覧（削除時はルートと合わせてここからも外す）
+fn deprecated_endpoints() -> [DeprecatedEndpoint; 2] {
+    [
+        // アクティブペット単体の取得（旧API互換）→ 小屋情報に統合
+        DeprecatedEndpoint {
+            method: Method::GET,
+            pattern: "/pet",
+            deprecated_on: (2026, 11, 1),
+            sunset_on: (2027, 5, 1),
+            successor: "/pet/barn",
+        },
+        // アクティブペットの名前変更（旧API互換）→ ペットID指定の更新
+        DeprecatedEndpoint {
+            method: Method::PUT,
+            pattern: "/pet",
+            deprecated_on: (2026, 11, 1),
+            sunset_on: (2027, 5, 1),
+            successor: "/pet/{id}",
+        },
+    ]
+}
+
+fn midnight_utc((y, m, d): (i32, u32, u32)) -> Option<NaiveDateTime> {
+    NaiveDate::from_ymd_opt(y, m, d)?.and_hms_opt(0, 0, 0)
+}
+
+/// 非推奨エンドポイントに付与するヘッダー
+fn deprecation_headers(method: &Method, route: &str) -> Vec<(HeaderName, String)> {
+    let unversioned = route
+        .strip_prefix(API_V1_PREFIX)
+        .or_else(|| route.strip_prefix("/api"))
+        .unwrap_or(route);
+
+    let Some(endpoint) = deprecated_endpoints()
+        .into_iter()
+        .find(|e| e.method == method && e.pattern == unversioned)
+    else {
+        return Vec::new();
+    };
+
+    let mut headers = Vec::new();
+    if let Some(deprecated) = midnight_utc(endpoint.deprecated_on) {
+        let ts = deprecated.and_utc().timestamp();
+        headers.push((HeaderName::from_static("deprecation"), format!("@{}", ts)));
+    }
+    if let Some(sunset) = midnight_utc(endpoint.sunset_on) {
+        headers.push((
+            HeaderName::from_static("sunset"),
+            sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+        ));
+    }
+    headers.push((
+        LINK,
+        format!(
+            "<{}{}>; rel=\"successor-version\"",
+            API_V1_PREFIX, endpoint.successor
+        ),
+    ));
+    headers
+}
+
+/// 非推奨ヘッダーミドルウェアファクトリ
+pub struct ApiDeprecation;
+
+impl<S, B> Transform<S, ServiceRequest> for ApiDeprecation
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = ApiDeprecationMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(ApiDeprecationMiddleware {
+            service: Rc::new(service),
+        })
+    }
+}
+
+pub struct ApiDeprecationMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for ApiDeprecationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let headers = req
+            .match_pattern()
+            .map(|route| deprecation_headers(req.method(), &route))
+            .unwrap_or_default();
+
+        Box::pin(async move {
+            let mut res = service.call(req).await?;
+            for (name, value) in headers {
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    res.headers_mut().insert(name, value);
+                }
+            }
+            Ok(res)
+        })
+    }
+}
@@ -0,0 +1,131 @@
+//! APIキー認証ミドルウェア
+//!
+//! X-Api-KeyヘッダーのAPIキーを検証し、キーの持ち主をリクエストの拡張データに載せてハンドラに渡す。
+//! キーの権限（スコープ）で許可されていないリクエストは403で拒否する。
+//! Bearerトークンと同じく認証状態はCookieセッションに書き込まない。
+//! SessionMiddlewareの内側、SessionTimeout・SessionRefreshの外側に配置すること。
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error,
+};
+use futures::future::{ok, Ready};
+use sqlx::MySqlPool;
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use crate::auth::api_key::{hash_api_key, parse_scopes, scope_allows, API_KEY_HEADER};
+use crate::auth::session::set_request_user;
+use crate::error::AppError;
+use crate::middleware::session_refresh::load_user;
+
+/// APIキー認証ミドルウェアファクトリ
+pub struct ApiKeyAuth;
+
+impl<S, B> Transform<S, ServiceRequest> for ApiKeyAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = ApiKeyAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(ApiKeyAuthMiddleware {
+            service: Rc::new(service),
+        })
+    }
+}
+
+pub struct ApiKeyAuthMiddleware<S> {
+    service: Rc<S>,
+}
+
+/// X-Api-KeyヘッダーからAPIキーを取り出す
+pub(crate) fn api_key(req: &ServiceRequest) -> Option<String> {
+    let value = req.headers().get(API_KEY_HEADER)?.to_str().ok()?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+impl<S, B> Service<ServiceRequest> for ApiKeyAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            // APIリクエストのみ対象
+            let key = api_key(&req).filter(|_| req.path().starts_with("/api/"));
+            let Some(key) = key else {
+                return service.call(req).await;
+            };
+
+            let pool = req.app_data::<web::Data<MySqlPool>>().ok_or_else(|| {
+                AppError::InternalError("MySqlPool is not registered".to_string())
+            })?;
+            let row: Option<(i64, i64, String)> = sqlx::query_as(
+                r#"SELECT id, user_id, scopes FROM user_api_keys
+                   WHERE key_hash = ? AND revoked_at IS NULL"#,
+            )
+            .bind(hash_api_key(&key))
+            .fetch_optional(pool.get_ref())
+            .await
+            .map_err(AppError::from)?;
+            let (key_id, user_id, scopes) =
+                row.ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()))?;
+
+            if !scope_allows(&parse_scopes(&scopes), req.method(), req.path()) {
+                tracing::info!(
+                    "[API KEY] key id={} is not allowed to {} {}",
+                    key_id,
+                    req.method(),
+                    req.path()
+                );
+                return Err(AppError::Forbidden(
+                    "This API key does not have permission for this request".to_string(),
+                )
+                .into());
+            }
+
+            let user = load_user(pool.get_ref(), user_id)
+                .await
+                .map_err(AppError::from)?
+                .ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()))?;
+
+            // 最終利用日時は1分に1回だけ更新する
+            if let Err(e) = sqlx::query(
+                r#"UPDATE user_api_keys SET last_used_at = NOW()
+                   WHERE id = ?
+                     AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL 1 MINUTE)"#,
+            )
+            .bind(key_id)
+            .execute(pool.get_ref())
+            .await
+            {
+                tracing::warn!("Failed to update last use of API key {}: {}", key_id, e);
+            }
+
+            // DBから読み込んだばかりなのでSessionRefreshでの再検証は不要
+            set_request_user(&req, user, chrono::Utc::now().timestamp());
+
+            service.call(req).await
+        })
+    }
+}
@@ -1,8 +1,6 @@
 //! 認証ガードミドルウェア
 
-use actix_session::Session;
-
-use crate::auth::session::{get_current_user_opt, SessionUser};
+use crate::auth::session::{get_current_user_opt, Session, SessionUser};
 
 /// リクエストから認証済みユーザーを抽出
 /// 未認証の場合はNoneを返す
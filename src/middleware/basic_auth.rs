@@ -24,8 +24,8 @@ const EXCLUDED_PATHS: &[&str] = &[
     "/health",
     "/api/auth/github",
     "/api/auth/google",
-    "/login/oauth2/code/github",
-    "/login/oauth2/code/google",
+    // 全OAuthプロバイダーのコールバック（/login/oauth2/code/{provider}）
+    "/login/oauth2/code/",
 ];
 
 /// Basic認証の資格情報（簡略化のためハードコード）
@@ -114,8 +114,7 @@ where
             // Basic認証ヘッダーを検証
             if let Some(auth_header) = req.headers().get(header::AUTHORIZATION) {
                 if let Ok(auth_str) = auth_header.to_str() {
-                    if auth_str.starts_with("Basic ") {
-                        let encoded = &auth_str[6..];
+                    if let Some(encoded) = auth_str.strip_prefix("Basic ") {
                         if let Ok(decoded) = STANDARD.decode(encoded) {
                             if let Ok(credentials) = String::from_utf8(decoded) {
                                 if let Some((user, pass)) = credentials.split_once(':') {
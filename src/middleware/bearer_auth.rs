@@ -0,0 +1,95 @@
+//! Bearerトークン認証ミドルウェア
+//!
+//! Authorization: Bearer のアクセストークンを検証し、トークンのユーザーをリクエストの拡張データに
+//! 載せてハンドラに渡す。ハンドラはCookieセッションと同じくget_current_userでユーザーを取得できる。
+//! トークンでの認証状態はCookieセッションに書き込まない。
+//! SessionMiddlewareの内側、SessionTimeout・SessionRefreshの外側に配置すること。
+
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    web, Error,
+};
+use futures::future::{ok, Ready};
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use crate::auth::session::set_request_user;
+use crate::auth::token::{decode_token, TOKEN_TYPE_ACCESS};
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+/// Bearerトークン認証ミドルウェアファクトリ
+pub struct BearerAuth;
+
+impl<S, B> Transform<S, ServiceRequest> for BearerAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = BearerAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(BearerAuthMiddleware {
+            service: Rc::new(service),
+        })
+    }
+}
+
+pub struct BearerAuthMiddleware<S> {
+    service: Rc<S>,
+}
+
+/// Authorizationヘッダーからトークンを取り出す
+pub(crate) fn bearer_token(req: &ServiceRequest) -> Option<String> {
+    let value = req.headers().get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("Bearer")
+        .then(|| token.trim().to_string())
+}
+
+impl<S, B> Service<ServiceRequest> for BearerAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let Some(token) = bearer_token(&req) else {
+                return service.call(req).await;
+            };
+
+            let config = req.app_data::<web::Data<AppConfig>>().ok_or_else(|| {
+                AppError::InternalError("AppConfig is not registered".to_string())
+            })?;
+            let claims = decode_token(config, &token, TOKEN_TYPE_ACCESS)?;
+            let user = claims
+                .user
+                .ok_or_else(|| AppError::Unauthorized("Invalid token".to_string()))?;
+
+            // 発行後にロール変更などがあればSessionRefreshで再検証される
+            set_request_user(&req, user, claims.iat);
+
+            service.call(req).await
+        })
+    }
+}
@@ -1,2 +1,8 @@
+pub mod api_deprecation;
+pub mod api_key_auth;
 pub mod auth_guard;
 pub mod basic_auth;
+pub mod bearer_auth;
+pub mod request_log;
+pub mod session_refresh;
+pub mod session_timeout;
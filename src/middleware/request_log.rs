@@ -0,0 +1,145 @@
+//! リクエストログミドルウェア
+//!
+//! リクエストごとにrequest_id・user_idを持つspanを作成し、ハンドラ内のログにも付与する。
+//! 完了時にメソッド・ルート・ステータス・処理時間を1行で出力する（LOG_FORMAT=jsonでの集計用）。
+//! ユーザーIDを取得するため、SessionMiddlewareの内側に配置すること。
+
+use actix_session::SessionExt;
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error, HttpMessage,
+};
+use futures::future::{ok, Ready};
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tracing::Instrument;
+
+use crate::auth::session::{get_request_user, get_session_user};
+
+/// リクエストIDのヘッダー（ロードバランサーが付与した値があれば引き継ぐ）
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// リクエストのユーザーID（Bearerトークン・APIキーのユーザーを優先し、なければCookieセッション）
+fn current_user_id(req: &(impl HttpMessage + SessionExt)) -> Option<i64> {
+    get_request_user(req)
+        .map(|r| r.user)
+        .or_else(|| get_session_user(&req.get_session()))
+        .map(|u| u.id)
+}
+
+/// リクエストログミドルウェアファクトリ
+pub struct RequestLog;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestLog
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestLogMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RequestLogMiddleware {
+            service: Rc::new(service),
+        })
+    }
+}
+
+pub struct RequestLogMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestLogMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty() && v.len() <= 128)
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        // 未マッチのパス（SPAフォールバック等）はパスをそのまま使う
+        let route = req
+            .match_pattern()
+            .unwrap_or_else(|| req.path().to_string());
+        let method = req.method().to_string();
+        let user_id = current_user_id(&req);
+
+        let span = tracing::info_span!(
+            "request",
+            request_id = %request_id,
+            user_id = tracing::field::Empty,
+        );
+        if let Some(id) = user_id {
+            span.record("user_id", id);
+        }
+
+        Box::pin(
+            async move {
+                let started = Instant::now();
+                let result = service.call(req).await;
+                let latency_ms = started.elapsed().as_millis() as u64;
+
+                match result {
+                    Ok(mut res) => {
+                        // ログイン・ログアウト時はハンドラ実行後のセッションを参照する
+                        let user_id = current_user_id(res.request()).or(user_id);
+                        let status = res.status().as_u16();
+
+                        tracing::info!(
+                            user_id,
+                            method = %method,
+                            route = %route,
+                            status,
+                            latency_ms,
+                            "request completed"
+                        );
+
+                        if let Ok(value) = HeaderValue::from_str(&request_id) {
+                            res.headers_mut()
+                                .insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+                        }
+                        Ok(res)
+                    }
+                    Err(e) => {
+                        let status = e.as_response_error().status_code().as_u16();
+                        tracing::warn!(
+                            user_id,
+                            method = %method,
+                            route = %route,
+                            status,
+                            latency_ms,
+                            error = %e,
+                            "request failed"
+                        );
+                        Err(e)
+                    }
+                }
+            }
+            .instrument(span),
+        )
+    }
+}
@@ -0,0 +1,253 @@
+//! セッションユーザー再検証ミドルウェア
+//!
+//! SessionUserはログイン時点のロール・表示名を保持しているため、一定間隔でDBと照合し、
+//! 変更があればセッションを更新する（ユーザーが削除されていればセッションを破棄）。
+//! ロール変更など即時に反映したい場合はbump_session_epochで次のリクエストから再検証させる。
+//! Cookieセッションはセッション一覧（user_sessions）に登録し、再検証のたびに無効にされていないか照合する。
+//! Bearerトークン・APIキーのユーザーはリクエストの拡張データ上で照合し、Cookieには書き込まない。
+//! SessionMiddlewareの内側、SessionTimeoutの内側に配置すること。
+
+use actix_session::{Session, SessionExt};
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header,
+    web, Error,
+};
+use futures::future::{ok, Ready};
+use sqlx::MySqlPool;
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::api::user_session::{register_session, touch_session};
+use crate::auth::login_throttle::client_ip;
+use crate::auth::session::{
+    clear_request_user, get_request_user, get_session_id, get_session_user, get_user_checked_at,
+    mark_user_checked, replace_current_user, set_request_user, set_session_id, SessionUser,
+};
+use crate::config::AppConfig;
+use crate::db::models::User;
+use crate::shared_store::SharedStore;
+
+fn session_epoch_key(user_id: i64) -> String {
+    format!("session-epoch:{}", user_id)
+}
+
+/// ユーザーの全セッションを次のリクエストで再検証させる
+/// 再検証間隔を過ぎれば全セッションが照合済みになるため、エポックはその間だけ保持する
+pub async fn bump_session_epoch(store: &SharedStore, config: &AppConfig, user_id: i64) {
+    let now = chrono::Utc::now().timestamp();
+    let ttl = Duration::from_secs(config.session_revalidate_minutes as u64 * 60);
+    store
+        .set_cached(&session_epoch_key(user_id), &now, ttl)
+        .await;
+}
+
+/// セッションユーザー再検証ミドルウェアファクトリ
+pub struct SessionRefresh {
+    interval_secs: i64,
+}
+
+impl SessionRefresh {
+    pub fn new(interval_secs: i64) -> Self {
+        SessionRefresh { interval_secs }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SessionRefresh
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = SessionRefreshMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(SessionRefreshMiddleware {
+            service: Rc::new(service),
+            interval_secs: self.interval_secs,
+        })
+    }
+}
+
+pub struct SessionRefreshMiddleware<S> {
+    service: Rc<S>,
+    interval_secs: i64,
+}
+
+impl<S, B> Service<ServiceRequest> for SessionRefreshMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let interval_secs = self.interval_secs;
+
+        Box::pin(async move {
+            let is_api = req.path().starts_with("/api/");
+            let now = chrono::Utc::now().timestamp();
+
+            // Bearerトークン・APIキーのユーザーはリクエストの拡張データだけを更新する
+            if let Some(request_user) = get_request_user(&req).filter(|_| is_api) {
+                let user_id = request_user.user.id;
+                if is_due(&req, user_id, request_user.checked_at, now, interval_secs).await {
+                    revalidate_request_user(&req, user_id, now).await;
+                }
+            }
+
+            let session = req.get_session();
+            let current = is_api.then(|| get_session_user(&session)).flatten();
+
+            if let Some(current) = current {
+                // 再検証導入前のセッションは即時に照合する
+                let checked_at = get_user_checked_at(&session).unwrap_or(0);
+                let due = is_due(&req, current.id, checked_at, now, interval_secs).await;
+
+                let ip = client_ip(req.request());
+                let session_id = get_session_id(&session);
+                if session_id.is_none() {
+                    register(&req, &session, current.id, &ip).await;
+                }
+
+                if due {
+                    if let Some(pool) = req.app_data::<web::Data<MySqlPool>>() {
+                        let revoked = match session_id {
+                            Some(session_id) => {
+                                match touch_session(pool.get_ref(), &session_id, Some(&ip)).await {
+                                    Ok(active) => !active,
+                                    Err(e) => {
+                                        tracing::warn!("Failed to check session: {}", e);
+                                        false
+                                    }
+                                }
+                            }
+                            None => false,
+                        };
+
+                        if revoked {
+                            // 他の端末からログアウトされたセッション: ハンドラには未ログインとして渡る
+                            tracing::info!("Session of user {} was revoked", current.id);
+                            session.purge();
+                            return service.call(req).await;
+                        }
+
+                        match load_user(pool.get_ref(), current.id).await {
+                            Ok(Some(fresh)) => {
+                                if fresh != current {
+                                    tracing::info!("Refreshed session user {}", current.id);
+                                    let _ = replace_current_user(&session, fresh);
+                                }
+                                let _ = mark_user_checked(&session, now);
+                            }
+                            Ok(None) => {
+                                // 削除・無効化されたユーザー: ハンドラには未ログインとして渡る
+                                tracing::info!(
+                                    "Session user {} is deleted or deactivated",
+                                    current.id
+                                );
+                                session.purge();
+                            }
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Failed to revalidate session user {}: {}",
+                                    current.id,
+                                    e
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
+
+/// 最後の照合から再検証間隔が過ぎたか、照合後にエポックが更新されていれば再検証する
+async fn is_due(
+    req: &ServiceRequest,
+    user_id: i64,
+    checked_at: i64,
+    now: i64,
+    interval_secs: i64,
+) -> bool {
+    if now - checked_at >= interval_secs {
+        return true;
+    }
+    let Some(store) = req.app_data::<web::Data<SharedStore>>() else {
+        return false;
+    };
+    let epoch: Option<i64> = store.get_cached(&session_epoch_key(user_id)).await;
+    epoch.is_some_and(|epoch| epoch >= checked_at)
+}
+
+/// Bearerトークン・APIキーのユーザーをDBと照合する（Cookieセッションには書き込まない）
+async fn revalidate_request_user(req: &ServiceRequest, user_id: i64, now: i64) {
+    let Some(pool) = req.app_data::<web::Data<MySqlPool>>() else {
+        return;
+    };
+    match load_user(pool.get_ref(), user_id).await {
+        Ok(Some(fresh)) => set_request_user(req, fresh, now),
+        Ok(None) => {
+            // 削除・無効化されたユーザー: ハンドラには未ログインとして渡る
+            tracing::info!("Token user {} is deleted or deactivated", user_id);
+            clear_request_user(req);
+        }
+        Err(e) => tracing::warn!("Failed to revalidate token user {}: {}", user_id, e),
+    }
+}
+
+/// セッションを一覧に登録する（失敗してもリクエストは続行し、次のリクエストで再試行する）
+async fn register(req: &ServiceRequest, session: &Session, user_id: i64, ip: &str) {
+    let (Some(pool), Some(config)) = (
+        req.app_data::<web::Data<MySqlPool>>(),
+        req.app_data::<web::Data<AppConfig>>(),
+    ) else {
+        return;
+    };
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok());
+
+    match register_session(pool.get_ref(), config, user_id, user_agent, Some(ip)).await {
+        Ok(session_id) => {
+            let _ = set_session_id(session, &session_id);
+        }
+        Err(e) => tracing::warn!("Failed to register session of user {}: {}", user_id, e),
+    }
+}
+
+/// セッションに載せるユーザー（削除・無効化されたアカウントはNone）
+pub(crate) async fn load_user(
+    pool: &MySqlPool,
+    user_id: i64,
+) -> Result<Option<SessionUser>, sqlx::Error> {
+    let user: Option<User> = sqlx::query_as(
+        r#"SELECT id, login_id, password, email, display_name, gender, birthday,
+           profile_image_url, oauth_provider, oauth_id, role, created_at, updated_at
+           FROM users WHERE id = ? AND deactivated_at IS NULL"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(user.map(SessionUser::from))
+}
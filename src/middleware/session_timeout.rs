@@ -0,0 +1,121 @@
+//! セッションタイムアウトミドルウェア
+//!
+//! 操作のたびに最終アクティビティ時刻を更新し（スライディング有効期限）、
+//! 無操作時間またはログインからの最大有効期間を超えたセッションを破棄する。
+//! SessionMiddlewareの内側に配置すること。
+
+use actix_session::SessionExt;
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    Error,
+};
+use futures::future::{ok, Ready};
+use std::{
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use crate::auth::session::{get_session_activity, get_session_user, touch_session_activity};
+
+/// アクティビティとして扱わないパス（有効期限の確認のみでセッションを延長しない）
+const PASSIVE_PATHS: &[&str] = &["/api/auth/session", "/api/v1/auth/session"];
+
+/// 最終アクティビティ時刻を書き込む最小間隔（秒）。毎リクエストのCookie再発行を避ける
+const TOUCH_INTERVAL_SECS: i64 = 60;
+
+/// セッションタイムアウトミドルウェアファクトリ
+pub struct SessionTimeout {
+    idle_timeout_secs: i64,
+    max_lifetime_secs: i64,
+}
+
+impl SessionTimeout {
+    pub fn new(idle_timeout_secs: i64, max_lifetime_secs: i64) -> Self {
+        SessionTimeout {
+            idle_timeout_secs,
+            max_lifetime_secs,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SessionTimeout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = SessionTimeoutMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(SessionTimeoutMiddleware {
+            service: Rc::new(service),
+            idle_timeout_secs: self.idle_timeout_secs,
+            max_lifetime_secs: self.max_lifetime_secs,
+        })
+    }
+}
+
+pub struct SessionTimeoutMiddleware<S> {
+    service: Rc<S>,
+    idle_timeout_secs: i64,
+    max_lifetime_secs: i64,
+}
+
+impl<S, B> Service<ServiceRequest> for SessionTimeoutMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, ctx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(ctx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let idle_timeout_secs = self.idle_timeout_secs;
+        let max_lifetime_secs = self.max_lifetime_secs;
+
+        Box::pin(async move {
+            // APIリクエストのみ対象（静的アセットはアクティビティとみなさない）
+            let path = req.path();
+            let is_api = path.starts_with("/api/");
+            let is_passive = PASSIVE_PATHS.contains(&path);
+
+            let session = req.get_session();
+            if is_api && get_session_user(&session).is_some() {
+                let now = chrono::Utc::now().timestamp();
+
+                match get_session_activity(&session) {
+                    Some(activity)
+                        if now - activity.started_at >= max_lifetime_secs
+                            || now - activity.last_activity_at >= idle_timeout_secs =>
+                    {
+                        // 期限切れ: ハンドラには未ログインとして渡る
+                        tracing::debug!("Session expired for path {}", path);
+                        session.purge();
+                    }
+                    Some(activity) => {
+                        if !is_passive && now - activity.last_activity_at >= TOUCH_INTERVAL_SECS {
+                            let _ = touch_session_activity(&session, now);
+                        }
+                    }
+                    None => {
+                        // タイムスタンプ導入前のセッション: ここから計測を開始
+                        let _ = touch_session_activity(&session, now);
+                    }
+                }
+            }
+
+            service.call(req).await
+        })
+    }
+}
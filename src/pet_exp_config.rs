@@ -0,0 +1,193 @@
+//! ペットの成長曲線
+//!
+//! ペットのレベルに必要な累計EXP（base_exp × (L - 1) ^ exponent）をpet_exp_configテーブルで管理する。
+//! 起動時に読み込み、管理者が変更した場合は保存済みのレベル・ステージを再計算し、
+//! 通知バスで全インスタンスに反映する。
+
+use std::sync::RwLock;
+
+use sqlx::{MySql, MySqlConnection, MySqlPool, QueryBuilder};
+
+use crate::config::PetExpConfig;
+use crate::db::models::Pet;
+use crate::error::AppError;
+use crate::shared_store::{BusMessage, SharedStore};
+
+/// 設定変更の通知トピック
+pub const TOPIC_PET_EXP_CONFIG_UPDATED: &str = "petExpConfig.updated";
+
+static CURRENT: RwLock<PetExpConfig> = RwLock::new(PetExpConfig::DEFAULT);
+
+/// 現在有効なペットの成長曲線
+pub fn current() -> PetExpConfig {
+    CURRENT
+        .read()
+        .map(|c| c.clone())
+        .unwrap_or(PetExpConfig::DEFAULT)
+}
+
+fn set_current(config: PetExpConfig) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = config;
+    }
+}
+
+/// 値の範囲を確認（レベルごとの必要EXPが1以上ずつ増えるようにする）
+pub fn validate(config: &PetExpConfig) -> Result<(), AppError> {
+    if !(1.0..=100_000.0).contains(&config.base_exp) {
+        return Err(AppError::BadRequest(
+            "基準EXPは1〜100,000の範囲で指定してください".to_string(),
+        ));
+    }
+    if !(1.0..=4.0).contains(&config.exponent) {
+        return Err(AppError::BadRequest(
+            "指数は1〜4の範囲で指定してください".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct PetExpConfigRow {
+    base_exp: f64,
+    exponent: f64,
+}
+
+impl From<PetExpConfigRow> for PetExpConfig {
+    fn from(row: PetExpConfigRow) -> Self {
+        Self {
+            base_exp: row.base_exp,
+            exponent: row.exponent,
+        }
+    }
+}
+
+/// DBからペットの成長曲線を読み込む（未登録の場合は初期値）
+pub async fn load(pool: &MySqlPool) -> Result<PetExpConfig, AppError> {
+    let row: Option<PetExpConfigRow> =
+        sqlx::query_as("SELECT base_exp, exponent FROM pet_exp_config WHERE id = 1")
+            .fetch_optional(pool)
+            .await?;
+
+    let config = row.map(PetExpConfig::from).unwrap_or(PetExpConfig::DEFAULT);
+    set_current(config.clone());
+    Ok(config)
+}
+
+/// ペットの成長曲線を更新し、保存済みのレベル・ステージを同じトランザクションで再計算する
+/// 再計算したペットの数を返す
+pub async fn update(
+    pool: &MySqlPool,
+    store: &SharedStore,
+    config: PetExpConfig,
+    updated_by: i64,
+) -> Result<(PetExpConfig, u64), AppError> {
+    validate(&config)?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        r#"INSERT INTO pet_exp_config (id, base_exp, exponent, updated_by, updated_at)
+           VALUES (1, ?, ?, ?, NOW())
+           ON DUPLICATE KEY UPDATE
+               base_exp = VALUES(base_exp),
+               exponent = VALUES(exponent),
+               updated_by = VALUES(updated_by),
+               updated_at = NOW()"#,
+    )
+    .bind(config.base_exp)
+    .bind(config.exponent)
+    .bind(updated_by)
+    .execute(&mut *tx)
+    .await?;
+    let recomputed = recompute_pet_levels(&mut tx, &config).await?;
+    tx.commit().await?;
+
+    set_current(config.clone());
+    store
+        .publish(BusMessage {
+            topic: TOPIC_PET_EXP_CONFIG_UPDATED.to_string(),
+            user_id: None,
+            payload: serde_json::json!({}),
+        })
+        .await;
+
+    tracing::info!(
+        "Pet EXP config updated by user {}: {:?} ({} pets recomputed)",
+        updated_by,
+        config,
+        recomputed
+    );
+    Ok((config, recomputed))
+}
+
+/// 保存済みのペットのレベル・ステージを成長曲線で再計算する（1回のUPDATE）
+/// 進化先を選んだペットはレベルに関わらず進化の姿のままにする。更新したペットの数を返す
+async fn recompute_pet_levels(
+    conn: &mut MySqlConnection,
+    config: &PetExpConfig,
+) -> Result<u64, AppError> {
+    let (max_exp,): (Option<i64>,) = sqlx::query_as("SELECT MAX(total_exp) FROM pets")
+        .fetch_one(&mut *conn)
+        .await?;
+    let top_level = config.level_for_exp(max_exp.unwrap_or(0));
+
+    let mut builder = QueryBuilder::<MySql>::new(
+        "UPDATE pets p JOIN (SELECT l.id, l.level, CASE WHEN l.branch_id IS NOT NULL THEN ",
+    );
+    builder.push_bind(Pet::EVOLUTION_STAGE);
+    for stage in (2..=Pet::MAX_STAGE).rev() {
+        builder
+            .push(" WHEN l.level >= ")
+            .push_bind(Pet::get_stage_start_level(stage))
+            .push(" THEN ")
+            .push_bind(stage);
+    }
+    // レベルごとの必要EXP（最も育ったペットのレベルまで）を満たした数 + 1 がレベル
+    builder.push(
+        r#" ELSE 1 END AS stage
+           FROM (
+               SELECT p2.id, p2.branch_id, 1 + COUNT(t.level) AS level
+               FROM pets p2
+               LEFT JOIN (SELECT 0 AS level, 0 AS required_exp FROM DUAL WHERE FALSE"#,
+    );
+    for level in 2..=top_level {
+        builder
+            .push(" UNION ALL SELECT ")
+            .push_bind(level)
+            .push(", ")
+            .push_bind(config.required_exp(level));
+    }
+    builder.push(
+        r#") t ON t.required_exp <= p2.total_exp
+               GROUP BY p2.id, p2.branch_id
+           ) l
+         ) c ON c.id = p.id
+         SET p.level = c.level, p.stage = c.stage, p.updated_at = NOW()
+         WHERE p.level <> c.level OR p.stage <> c.stage"#,
+    );
+
+    let result = builder.build().execute(&mut *conn).await?;
+    Ok(result.rows_affected())
+}
+
+/// 他インスタンスでの設定変更を受け取り、設定を読み直す
+pub fn spawn_reloader(pool: MySqlPool, store: &SharedStore) {
+    let mut receiver = store.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(message) if message.topic == TOPIC_PET_EXP_CONFIG_UPDATED => {
+                    if let Err(e) = load(&pool).await {
+                        tracing::warn!("Failed to reload pet EXP config: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {
+                    // 取りこぼした可能性があるため読み直す
+                    let _ = load(&pool).await;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,339 @@
+//! インスタンス間で共有するストア
+//!
+//! REDIS_URLが設定されている場合はRedisを使い、複数インスタンスでレート制限のカウンタ・
+//! マスタデータのキャッシュ・通知を共有する。
+//! 未設定または起動時に接続できない場合はプロセス内のストアにフォールバックする（単一インスタンス・ローカル開発向け）。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use redis::aio::ConnectionManager;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+/// Redisキーの接頭辞
+const KEY_PREFIX: &str = "fithub:";
+/// 通知バスのRedisチャンネル
+const BUS_CHANNEL: &str = "fithub:bus";
+/// 起動時のRedis接続タイムアウト
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// 通知バスのバッファサイズ（購読側が遅れた場合は古い通知から破棄される）
+const BUS_CAPACITY: usize = 256;
+/// プロセス内ストアの期限切れエントリを掃除するしきい値
+const IN_PROCESS_PRUNE_THRESHOLD: usize = 10_000;
+
+/// 通知バスで配信するメッセージ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BusMessage {
+    /// 通知の種類（例: "pet.levelUp"）
+    pub topic: String,
+    /// 対象ユーザー（全体通知の場合はNone）
+    pub user_id: Option<i64>,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Default)]
+struct InProcessState {
+    /// レート制限カウンタ: キー -> (回数, ウィンドウ終了時刻)
+    counters: HashMap<String, (u64, Instant)>,
+    /// キャッシュ: キー -> (JSON, 有効期限)
+    cache: HashMap<String, (String, Instant)>,
+}
+
+enum Backend {
+    InProcess(Mutex<InProcessState>),
+    Redis(ConnectionManager),
+}
+
+/// 共有ストア
+pub struct SharedStore {
+    backend: Backend,
+    bus: broadcast::Sender<BusMessage>,
+}
+
+impl SharedStore {
+    /// プロセス内ストアを作成
+    pub fn in_process() -> Self {
+        let (bus, _) = broadcast::channel(BUS_CAPACITY);
+        Self {
+            backend: Backend::InProcess(Mutex::new(InProcessState::default())),
+            bus,
+        }
+    }
+
+    /// 設定からストアを作成（Redisに接続できない場合はプロセス内ストア）
+    pub async fn from_config(config: &AppConfig) -> Self {
+        if config.redis_url.is_empty() {
+            return Self::in_process();
+        }
+
+        let client = match redis::Client::open(config.redis_url.as_str()) {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::warn!("Invalid REDIS_URL, falling back to in-process store: {}", e);
+                return Self::in_process();
+            }
+        };
+
+        let manager =
+            match tokio::time::timeout(CONNECT_TIMEOUT, ConnectionManager::new(client.clone()))
+                .await
+            {
+                Ok(Ok(manager)) => manager,
+                Ok(Err(e)) => {
+                    tracing::warn!(
+                        "Failed to connect to Redis, falling back to in-process store: {}",
+                        e
+                    );
+                    return Self::in_process();
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        "Timed out connecting to Redis, falling back to in-process store"
+                    );
+                    return Self::in_process();
+                }
+            };
+
+        let (bus, _) = broadcast::channel(BUS_CAPACITY);
+        spawn_bus_subscriber(client, bus.clone());
+
+        tracing::info!("Shared store: Redis");
+        Self {
+            backend: Backend::Redis(manager),
+            bus,
+        }
+    }
+
+    /// 固定ウィンドウでカウントを加算し、ウィンドウ内の累計回数を返す
+    pub async fn increment(&self, key: &str, window: Duration) -> Result<u64, String> {
+        match &self.backend {
+            Backend::InProcess(state) => {
+                let mut state = state.lock().map_err(|e| e.to_string())?;
+                let now = Instant::now();
+                if state.counters.len() > IN_PROCESS_PRUNE_THRESHOLD {
+                    state.counters.retain(|_, (_, reset_at)| *reset_at > now);
+                }
+
+                let entry = state
+                    .counters
+                    .entry(key.to_string())
+                    .or_insert((0, now + window));
+                if entry.1 <= now {
+                    *entry = (0, now + window);
+                }
+                entry.0 += 1;
+                Ok(entry.0)
+            }
+            Backend::Redis(manager) => {
+                let key = format!("{}rate:{}", KEY_PREFIX, key);
+                // ウィンドウ開始時のみ有効期限付きで作成し、加算する
+                let (count,): (u64,) = redis::pipe()
+                    .atomic()
+                    .cmd("SET")
+                    .arg(&key)
+                    .arg(0)
+                    .arg("EX")
+                    .arg(window.as_secs().max(1))
+                    .arg("NX")
+                    .ignore()
+                    .cmd("INCR")
+                    .arg(&key)
+                    .query_async(&mut manager.clone())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(count)
+            }
+        }
+    }
+
+    /// カウントをリセット（障害時はログのみ）
+    pub async fn reset_count(&self, key: &str) {
+        match &self.backend {
+            Backend::InProcess(state) => {
+                if let Ok(mut state) = state.lock() {
+                    state.counters.remove(key);
+                }
+            }
+            Backend::Redis(manager) => {
+                let result: Result<(), _> = redis::cmd("DEL")
+                    .arg(format!("{}rate:{}", KEY_PREFIX, key))
+                    .query_async(&mut manager.clone())
+                    .await;
+                if let Err(e) = result {
+                    tracing::warn!("Failed to reset count {}: {}", key, e);
+                }
+            }
+        }
+    }
+
+    /// ウィンドウ内の回数が上限を超えた場合はTooManyRequestsを返す
+    /// ストアの障害時はリクエストを止めないよう許可する
+    pub async fn check_rate_limit(
+        &self,
+        key: &str,
+        limit: u64,
+        window: Duration,
+    ) -> Result<(), AppError> {
+        match self.increment(key, window).await {
+            Ok(count) if count > limit => Err(AppError::TooManyRequests(
+                "リクエストが多すぎます。しばらく時間をおいてから再度お試しください".to_string(),
+            )),
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::warn!("Rate limit check failed for {}: {}", key, e);
+                Ok(())
+            }
+        }
+    }
+
+    /// キャッシュを取得（未登録・期限切れ・障害時はNone）
+    pub async fn get_cached<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let json = match &self.backend {
+            Backend::InProcess(state) => {
+                let state = state.lock().ok()?;
+                state
+                    .cache
+                    .get(key)
+                    .filter(|(_, expires_at)| *expires_at > Instant::now())
+                    .map(|(json, _)| json.clone())?
+            }
+            Backend::Redis(manager) => {
+                let result: Result<Option<String>, _> = redis::cmd("GET")
+                    .arg(format!("{}cache:{}", KEY_PREFIX, key))
+                    .query_async(&mut manager.clone())
+                    .await;
+                match result {
+                    Ok(json) => json?,
+                    Err(e) => {
+                        tracing::warn!("Failed to read cache {}: {}", key, e);
+                        return None;
+                    }
+                }
+            }
+        };
+
+        serde_json::from_str(&json).ok()
+    }
+
+    /// キャッシュを保存（障害時はログのみ）
+    pub async fn set_cached<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) {
+        let Ok(json) = serde_json::to_string(value) else {
+            return;
+        };
+
+        match &self.backend {
+            Backend::InProcess(state) => {
+                if let Ok(mut state) = state.lock() {
+                    let now = Instant::now();
+                    if state.cache.len() > IN_PROCESS_PRUNE_THRESHOLD {
+                        state.cache.retain(|_, (_, expires_at)| *expires_at > now);
+                    }
+                    state.cache.insert(key.to_string(), (json, now + ttl));
+                }
+            }
+            Backend::Redis(manager) => {
+                let result: Result<(), _> = redis::cmd("SET")
+                    .arg(format!("{}cache:{}", KEY_PREFIX, key))
+                    .arg(json)
+                    .arg("EX")
+                    .arg(ttl.as_secs().max(1))
+                    .query_async(&mut manager.clone())
+                    .await;
+                if let Err(e) = result {
+                    tracing::warn!("Failed to write cache {}: {}", key, e);
+                }
+            }
+        }
+    }
+
+    /// キャッシュを削除
+    pub async fn invalidate(&self, keys: &[&str]) {
+        match &self.backend {
+            Backend::InProcess(state) => {
+                if let Ok(mut state) = state.lock() {
+                    for key in keys {
+                        state.cache.remove(*key);
+                    }
+                }
+            }
+            Backend::Redis(manager) => {
+                let redis_keys: Vec<String> = keys
+                    .iter()
+                    .map(|key| format!("{}cache:{}", KEY_PREFIX, key))
+                    .collect();
+                let result: Result<(), _> = redis::cmd("DEL")
+                    .arg(redis_keys)
+                    .query_async(&mut manager.clone())
+                    .await;
+                if let Err(e) = result {
+                    tracing::warn!("Failed to invalidate cache {:?}: {}", keys, e);
+                }
+            }
+        }
+    }
+
+    /// 全インスタンスへ通知を配信
+    pub async fn publish(&self, message: BusMessage) {
+        match &self.backend {
+            Backend::InProcess(_) => {
+                // 購読者がいない場合のエラーは無視
+                let _ = self.bus.send(message);
+            }
+            Backend::Redis(manager) => {
+                // 自インスタンスへもRedis経由で届くため、ここでは直接送信しない
+                let Ok(json) = serde_json::to_string(&message) else {
+                    return;
+                };
+                let result: Result<(), _> = redis::cmd("PUBLISH")
+                    .arg(BUS_CHANNEL)
+                    .arg(json)
+                    .query_async(&mut manager.clone())
+                    .await;
+                if let Err(e) = result {
+                    tracing::warn!("Failed to publish {}: {}", message.topic, e);
+                }
+            }
+        }
+    }
+
+    /// 通知を購読
+    pub fn subscribe(&self) -> broadcast::Receiver<BusMessage> {
+        self.bus.subscribe()
+    }
+}
+
+/// Redisの通知チャンネルを購読し、プロセス内のバスへ転送する（切断時は再接続）
+fn spawn_bus_subscriber(client: redis::Client, bus: broadcast::Sender<BusMessage>) {
+    tokio::spawn(async move {
+        loop {
+            match client.get_async_pubsub().await {
+                Ok(mut pubsub) => {
+                    if let Err(e) = pubsub.subscribe(BUS_CHANNEL).await {
+                        tracing::warn!("Failed to subscribe to {}: {}", BUS_CHANNEL, e);
+                    } else {
+                        let mut messages = pubsub.on_message();
+                        while let Some(msg) = messages.next().await {
+                            let Ok(json) = msg.get_payload::<String>() else {
+                                continue;
+                            };
+                            if let Ok(message) = serde_json::from_str::<BusMessage>(&json) {
+                                let _ = bus.send(message);
+                            }
+                        }
+                        tracing::warn!("Redis pub/sub connection closed; reconnecting");
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to connect Redis pub/sub: {}", e),
+            }
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        }
+    });
+}
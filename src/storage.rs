@@ -0,0 +1,139 @@
+//! アップロードファイルの保存先
+//!
+//! 保存先はSTORAGE_BACKENDで切り替える。
+//! - local: STORAGE_LOCAL_DIRに保存し、/uploadsで配信する（開発環境・未設定時）
+//! - s3: S3_BUCKETに保存する。認証情報とリージョンはAWS SDKの標準の環境変数から読み込む
+//!
+//! STORAGE_PUBLIC_URLを設定した場合（CDN等）は、そのURLを基準に公開URLを作る。
+
+use std::path::{Path, PathBuf};
+
+use aws_sdk_s3::primitives::ByteStream;
+
+use crate::config::AppConfig;
+
+/// ローカル保存時の配信パス
+pub const LOCAL_PUBLIC_PATH: &str = "/uploads";
+/// 保存するファイルのキャッシュ期間（キーは毎回変わるため長くてよい）
+const CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// ファイルの保存先
+#[derive(Clone)]
+pub enum Storage {
+    Local {
+        dir: PathBuf,
+        public_url: String,
+    },
+    S3 {
+        client: aws_sdk_s3::Client,
+        bucket: String,
+        public_url: String,
+    },
+}
+
+impl Storage {
+    pub async fn from_config(config: &AppConfig) -> Self {
+        let local = || Storage::Local {
+            dir: PathBuf::from(&config.storage_local_dir),
+            public_url: non_empty(&config.storage_public_url)
+                .unwrap_or(LOCAL_PUBLIC_PATH)
+                .to_string(),
+        };
+        match config.storage_backend.as_str() {
+            "s3" if !config.s3_bucket.is_empty() => {
+                let sdk_config =
+                    aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+                let public_url = match non_empty(&config.storage_public_url) {
+                    Some(url) => url.to_string(),
+                    None => {
+                        let region = sdk_config
+                            .region()
+                            .map(|r| r.to_string())
+                            .unwrap_or_else(|| "us-east-1".to_string());
+                        format!("https://{}.s3.{}.amazonaws.com", config.s3_bucket, region)
+                    }
+                };
+                Storage::S3 {
+                    client: aws_sdk_s3::Client::new(&sdk_config),
+                    bucket: config.s3_bucket.clone(),
+                    public_url,
+                }
+            }
+            "s3" => {
+                tracing::warn!(
+                    "STORAGE_BACKEND=s3 but S3_BUCKET is not set; files will be stored locally"
+                );
+                local()
+            }
+            _ => local(),
+        }
+    }
+
+    /// ローカル保存時の保存ディレクトリ（/uploadsの配信用）
+    pub fn local_dir(&self) -> Option<&Path> {
+        match self {
+            Storage::Local { dir, .. } => Some(dir),
+            Storage::S3 { .. } => None,
+        }
+    }
+
+    /// キーに対応する公開URL
+    pub fn public_url(&self, key: &str) -> String {
+        let base = match self {
+            Storage::Local { public_url, .. } | Storage::S3 { public_url, .. } => public_url,
+        };
+        format!("{}/{}", base.trim_end_matches('/'), key)
+    }
+
+    /// ファイルを保存する（同じキーのファイルは上書きする）
+    pub async fn put(&self, key: &str, data: Vec<u8>, content_type: &str) -> Result<(), String> {
+        match self {
+            Storage::Local { dir, .. } => {
+                let path = dir.join(key);
+                if let Some(parent) = path.parent() {
+                    tokio::fs::create_dir_all(parent)
+                        .await
+                        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+                }
+                tokio::fs::write(&path, data)
+                    .await
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+            }
+            Storage::S3 { client, bucket, .. } => client
+                .put_object()
+                .bucket(bucket)
+                .key(key)
+                .content_type(content_type)
+                .cache_control(CACHE_CONTROL)
+                .body(ByteStream::from(data))
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to upload {} to S3: {}", key, e)),
+        }
+    }
+
+    /// ファイルを削除する（存在しない場合も成功とする）
+    pub async fn delete(&self, key: &str) -> Result<(), String> {
+        match self {
+            Storage::Local { dir, .. } => match tokio::fs::remove_file(dir.join(key)).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    Err(format!("Failed to delete {}: {}", key, e))
+                }
+                _ => Ok(()),
+            },
+            Storage::S3 { client, bucket, .. } => client
+                .delete_object()
+                .bucket(bucket)
+                .key(key)
+                .send()
+                .await
+                .map(|_| ())
+                .map_err(|e| format!("Failed to delete {} from S3: {}", key, e)),
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    Some(value.trim()).filter(|v| !v.is_empty())
+}
@@ -52,6 +52,20 @@ async fn test_get_muscle_groups_no_auth() {
     assert!(body.is_array(), "Expected array of muscle groups");
 }
 
+#[tokio::test]
+async fn test_versioned_route_alias() {
+    let client = create_client();
+    let res = client
+        .get(format!("{}/api/v1/workout/muscle-groups", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = res.json().await.expect("Failed to parse JSON");
+    assert!(body.is_array(), "Expected array of muscle groups");
+}
+
 #[tokio::test]
 async fn test_get_default_tags_no_auth() {
     let client = create_client();
@@ -79,6 +93,22 @@ async fn test_registration_status_no_auth() {
     assert_eq!(res.status(), StatusCode::OK);
 }
 
+#[tokio::test]
+async fn test_registration_status_without_pending_has_no_deadline() {
+    let client = create_client();
+    let res = client
+        .get(format!("{}/api/auth/registration-status", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: serde_json::Value = res.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["hasPendingRegistration"], false);
+    assert_eq!(body["expired"], false);
+    assert!(body.get("expiresInSeconds").is_none());
+}
+
 // =============================================================================
 // 認証必要エンドポイント (未認証でのアクセス確認)
 // =============================================================================
@@ -188,6 +218,310 @@ async fn test_login_with_invalid_credentials() {
     assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
 }
 
+#[tokio::test]
+async fn test_token_with_invalid_credentials() {
+    let client = create_client();
+    let res = client
+        .post(format!("{}/api/auth/token", BASE_URL))
+        .json(&serde_json::json!({
+            "loginId": "nonexistent@test.com",
+            "password": "wrongpassword",
+        }))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_refresh_with_invalid_token() {
+    let client = create_client();
+    let res = client
+        .post(format!("{}/api/auth/refresh", BASE_URL))
+        .json(&serde_json::json!({ "refreshToken": "invalid-token" }))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+/// ログインが必要なエンドポイントは、未ログインでは401を返す
+#[tokio::test]
+async fn test_routes_require_login() {
+    let client = create_client();
+    let url = |path: &str| format!("{}{}", BASE_URL, path);
+    let requests = [
+        client
+            .put(url("/api/user/email"))
+            .json(&serde_json::json!({ "email": "test@example.com" })),
+        client.post(url("/api/auth/2fa/setup")),
+        client.get(url("/api/user/sessions")),
+        client.get(url("/api/user/api-keys")),
+        client
+            .post(url("/api/user/profile-image"))
+            .header("Content-Type", "multipart/form-data; boundary=fithub")
+            .body("--fithub--\r\n"),
+        client.get(url("/api/user/exp-history")),
+        client.get(url("/api/coins/transactions")),
+        client.get(url("/api/events/current")),
+        client.get(url("/api/admin/daily-rewards")),
+        client.get(url("/api/reports/monthly?month=2026-01")),
+        client.get(url("/api/dashboard/progression?exerciseId=1")),
+        client.get(url("/api/dashboard/volume-by-muscle?range=30d")),
+        client.get(url("/api/leaderboards/weekly_exp")),
+        client.get(url("/api/challenges")),
+        client.get(url("/api/achievements")),
+        client
+            .post(url("/api/streak/recover"))
+            .json(&serde_json::json!({ "streakType": "training", "payWith": "coins" })),
+        client
+            .post(url("/api/settings"))
+            .json(&serde_json::json!({ "graceDaysAllowed": 1, "weightUnit": "lb" })),
+        client
+            .post(url("/api/settings"))
+            .json(&serde_json::json!({ "graceDaysAllowed": 1, "timezone": "America/New_York" })),
+        client.get(url("/api/goals")),
+        client
+            .post(url("/api/user/account/deactivate"))
+            .json(&serde_json::json!({ "password": "password" })),
+        client.get(url("/api/user/export")),
+        client
+            .put(url("/api/user/login-id"))
+            .json(&serde_json::json!({ "loginId": "new_login_id" })),
+        client.get(url("/api/user/preferences")),
+        client.get(url("/api/settings/privacy")),
+        client.get(url("/api/user/security/activity")),
+    ];
+
+    for request in requests {
+        let request = request.build().expect("Failed to build request");
+        let route = format!("{} {}", request.method(), request.url().path());
+        let res = client
+            .execute(request)
+            .await
+            .expect("Failed to send request");
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED, "{}", route);
+    }
+}
+
+#[tokio::test]
+async fn test_invalid_api_key_rejected() {
+    let client = create_client();
+    let res = client
+        .get(format!("{}/api/workout/records", BASE_URL))
+        .header("X-Api-Key", "fh_invalidinvalidinvalidinvalidinvalidinva")
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_public_profile_of_unknown_user_is_not_found() {
+    let client = create_client();
+    let res = client
+        .get(format!("{}/api/users/no-such-user-0000/profile", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_invalid_bearer_token_rejected() {
+    let client = create_client();
+    let res = client
+        .get(format!("{}/api/user/info", BASE_URL))
+        .bearer_auth("invalid-token")
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_register_requires_captcha_when_enabled() {
+    let client = create_client();
+    let config: serde_json::Value = client
+        .get(format!("{}/api/public-config", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    if config.get("captchaSiteKey").is_none() {
+        // CAPTCHAが無効な環境
+        return;
+    }
+    assert!(config["captchaProvider"].is_string());
+
+    let res = client
+        .post(format!("{}/register", BASE_URL))
+        .form(&[
+            ("loginId", "captchatest"),
+            ("password", "Str0ng-Passphrase!"),
+            ("confirmPassword", "Str0ng-Passphrase!"),
+        ])
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = res.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["code"], "CAPTCHA_FAILED");
+}
+
+#[tokio::test]
+async fn test_register_rejects_weak_password_with_details() {
+    let client = create_client();
+    let res = client
+        .post(format!("{}/register", BASE_URL))
+        .form(&[
+            ("loginId", "weakpwtest"),
+            ("password", "password"),
+            ("confirmPassword", "password"),
+        ])
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = res.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["code"], "WEAK_PASSWORD");
+    let rules: Vec<&str> = body["violations"]
+        .as_array()
+        .expect("violations should be an array")
+        .iter()
+        .filter_map(|v| v["rule"].as_str())
+        .collect();
+    assert!(rules.contains(&"common_password"), "rules: {:?}", rules);
+    assert!(rules.contains(&"character_classes"), "rules: {:?}", rules);
+}
+
+// =============================================================================
+// OAuthのstate検証（ログインCSRF）
+// =============================================================================
+
+/// リダイレクトを追わないHTTPクライアント（Cookie保持）
+fn create_no_redirect_client() -> Client {
+    Client::builder()
+        .cookie_store(true)
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+fn location(res: &reqwest::Response) -> String {
+    res.headers()
+        .get(reqwest::header::LOCATION)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// コールバックがstate不正としてログイン画面に戻されたか
+fn assert_state_rejected(res: &reqwest::Response) {
+    assert_eq!(res.status(), StatusCode::FOUND);
+    assert!(
+        location(res).contains("/login?error=oauth_state"),
+        "unexpected redirect: {}",
+        location(res)
+    );
+}
+
+#[tokio::test]
+async fn test_oauth_callback_without_started_flow_rejected() {
+    // 攻撃者が自分の認可コードとstateを埋め込んだリンクを踏ませる
+    let client = create_no_redirect_client();
+    let res = client
+        .get(format!(
+            "{}/login/oauth2/code/google?code=attacker-code&state=attacker-state",
+            BASE_URL
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    if res.status() == StatusCode::NOT_FOUND {
+        // Googleログインが無効な環境
+        return;
+    }
+    assert_state_rejected(&res);
+}
+
+#[tokio::test]
+async fn test_oauth_callback_with_mismatched_state_rejected() {
+    let client = create_no_redirect_client();
+    let start = client
+        .get(format!("{}/oauth2/authorization/google", BASE_URL))
+        .send()
+        .await
+        .expect("Failed to send request");
+    if start.status() == StatusCode::NOT_FOUND {
+        return;
+    }
+    assert_eq!(start.status(), StatusCode::FOUND);
+    let auth_url = location(&start);
+    let state = auth_url
+        .split(['?', '&'])
+        .find_map(|param| param.strip_prefix("state="))
+        .expect("authorization URL should carry a state")
+        .to_string();
+
+    // 別のstateを持つコールバックは拒否される
+    let res = client
+        .get(format!(
+            "{}/login/oauth2/code/google?code=attacker-code&state=forged-{}",
+            BASE_URL, state
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_state_rejected(&res);
+
+    // 照合に失敗したstateは使えなくなる（正しいstateでも再利用できない）
+    let res = client
+        .get(format!(
+            "{}/login/oauth2/code/google?code=attacker-code&state={}",
+            BASE_URL, state
+        ))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_state_rejected(&res);
+}
+
+#[tokio::test]
+async fn test_oauth_form_post_callback_is_forwarded_for_state_check() {
+    let client = create_no_redirect_client();
+    let res = client
+        .post(format!("{}/login/oauth2/code/apple", BASE_URL))
+        .form(&[("code", "attacker-code"), ("state", "attacker-state")])
+        .send()
+        .await
+        .expect("Failed to send request");
+
+    if res.status() == StatusCode::NOT_FOUND {
+        // Appleログインが無効な環境
+        return;
+    }
+    assert_eq!(res.status(), StatusCode::SEE_OTHER);
+    assert!(location(&res).starts_with("/login/oauth2/code/apple?"));
+
+    let res = client
+        .get(format!("{}{}", BASE_URL, location(&res)))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_state_rejected(&res);
+}
+
 // =============================================================================
 // 静的ファイル配信
 // =============================================================================